@@ -1,13 +1,24 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 
 use dpc::{
+	common::{mc::version::MinecraftVersion, IRType},
 	ir::IR,
+	lir::LIR,
+	lower::{
+		bitwise::legalize_bitwise, ir_to_mir, mir_to_lir::lower_mir as lower_mir_impl,
+		rand::legalize_rands,
+	},
+	mir::MIR,
 	output::datapack::{Datapack, Function},
 	parse::Parser,
-	project::ProjectSettings,
+	passes,
+	project::{OptimizationLevel, ProjectSettings, ProjectSettingsBuilder},
 	CodegenIRSettings,
 };
-use pyo3::{exceptions::PyRuntimeError, prelude::*};
+use pyo3::{
+	exceptions::{PyRuntimeError, PyValueError},
+	prelude::*,
+};
 
 /// Formats the sum of two numbers as string.
 #[pyfunction]
@@ -21,7 +32,16 @@ fn datapack_compiler(_py: Python, m: &PyModule) -> PyResult<()> {
 	m.add_function(wrap_pyfunction!(sum_as_string, m)?)?;
 	m.add_function(wrap_pyfunction!(codegen_ir, m)?)?;
 	m.add_function(wrap_pyfunction!(parse_ir, m)?)?;
+	m.add_function(wrap_pyfunction!(run_ir_passes, m)?)?;
+	m.add_function(wrap_pyfunction!(lower_ir, m)?)?;
+	m.add_function(wrap_pyfunction!(run_mir_passes, m)?)?;
+	m.add_function(wrap_pyfunction!(lower_mir, m)?)?;
+	m.add_function(wrap_pyfunction!(run_lir_passes, m)?)?;
+	m.add_function(wrap_pyfunction!(link, m)?)?;
 	m.add_class::<PyIR>()?;
+	m.add_class::<PyMIR>()?;
+	m.add_class::<PyLIR>()?;
+	m.add_class::<PyFunctionInfo>()?;
 	m.add_class::<PyCodegenIRSettings>()?;
 	m.add_class::<PyProjectSettings>()?;
 	m.add_class::<PyDatapack>()?;
@@ -52,12 +72,146 @@ fn parse_ir(text: &str) -> PyResult<PyIR> {
 	Ok(PyIR { inner: ir })
 }
 
+/// Run the IR passes on IR in place
+#[pyfunction]
+fn run_ir_passes(ir: &mut PyIR, project: PyProjectSettings) -> PyResult<()> {
+	passes::run_ir_passes(&mut ir.inner, &project.inner, false).map_err(to_py_err)
+}
+
+/// Lower IR to MIR, along with the legalization that happens before the MIR passes
+#[pyfunction]
+fn lower_ir(ir: PyIR, project: PyProjectSettings) -> PyResult<PyMIR> {
+	let mut mir = ir_to_mir::lower_ir(ir.inner).map_err(to_py_err)?;
+	legalize_rands(&mut mir, &project.inner).map_err(to_py_err)?;
+	legalize_bitwise(&mut mir, &project.inner).map_err(to_py_err)?;
+
+	Ok(PyMIR { inner: mir })
+}
+
+/// Run the MIR optimization passes on MIR in place
+#[pyfunction]
+fn run_mir_passes(mir: &mut PyMIR, project: PyProjectSettings) -> PyResult<()> {
+	passes::run_mir_passes(&mut mir.inner, &project.inner, false).map_err(to_py_err)
+}
+
+/// Lower MIR to LIR
+#[pyfunction]
+fn lower_mir(mir: PyMIR) -> PyResult<PyLIR> {
+	let lir = lower_mir_impl(mir.inner).map_err(to_py_err)?;
+	Ok(PyLIR { inner: lir })
+}
+
+/// Run the LIR optimization passes on LIR in place
+#[pyfunction]
+fn run_lir_passes(lir: &mut PyLIR, project: PyProjectSettings) -> PyResult<()> {
+	passes::run_lir_passes(&mut lir.inner, &project.inner, false).map_err(to_py_err)
+}
+
+/// Generate the commands for LIR and link them into a datapack
+#[pyfunction]
+fn link(lir: PyLIR, project: PyProjectSettings) -> PyResult<PyDatapack> {
+	let pack = dpc::output::link(lir.inner, &project.inner).map_err(to_py_err)?;
+	Ok(PyDatapack { inner: pack })
+}
+
+fn to_py_err(e: impl Debug) -> PyErr {
+	PyRuntimeError::new_err(format!("{e:?}"))
+}
+
 #[pyclass(name = "IR")]
 #[derive(Clone)]
 struct PyIR {
 	inner: IR,
 }
 
+#[pymethods]
+impl PyIR {
+	/// Get the functions, sorted by their IDs
+	fn functions(&self) -> Vec<PyFunctionInfo> {
+		let funcs = self.inner.functions.iter();
+		sort_infos(funcs.map(|(id, func)| PyFunctionInfo::new(id, &func.block.contents)))
+	}
+}
+
+#[pyclass(name = "MIR")]
+#[derive(Clone)]
+struct PyMIR {
+	inner: MIR,
+}
+
+#[pymethods]
+impl PyMIR {
+	/// Get the functions, sorted by their IDs
+	fn functions(&self) -> Vec<PyFunctionInfo> {
+		let funcs = self.inner.functions.iter();
+		sort_infos(funcs.map(|(id, func)| PyFunctionInfo::new(id, &func.block.contents)))
+	}
+
+	fn instr_count(&self) -> usize {
+		self.inner.instr_count()
+	}
+}
+
+#[pyclass(name = "LIR")]
+#[derive(Clone)]
+struct PyLIR {
+	inner: LIR,
+}
+
+#[pymethods]
+impl PyLIR {
+	/// Get the functions, sorted by their IDs
+	fn functions(&self) -> Vec<PyFunctionInfo> {
+		let funcs = self.inner.functions.iter();
+		sort_infos(funcs.map(|(id, func)| PyFunctionInfo::new(id, &func.block.contents)))
+	}
+
+	fn instr_count(&self) -> usize {
+		self.inner.instr_count()
+	}
+}
+
+/// A read-only view of a function from one of the stages of compilation,
+/// with its instructions formatted as text
+#[pyclass(name = "FunctionInfo")]
+#[derive(Clone)]
+struct PyFunctionInfo {
+	#[pyo3(get)]
+	id: String,
+	#[pyo3(get)]
+	instructions: Vec<String>,
+}
+
+impl PyFunctionInfo {
+	fn new(id: impl ToString, instrs: &[impl Debug]) -> Self {
+		Self {
+			id: id.to_string(),
+			instructions: instrs.iter().map(|x| format!("{x:?}")).collect(),
+		}
+	}
+}
+
+fn sort_infos(infos: impl Iterator<Item = PyFunctionInfo>) -> Vec<PyFunctionInfo> {
+	let mut out: Vec<_> = infos.collect();
+	out.sort_by(|l, r| l.id.cmp(&r.id));
+	out
+}
+
+#[pymethods]
+impl PyFunctionInfo {
+	fn __len__(&self) -> usize {
+		self.instructions.len()
+	}
+
+	fn __repr__(&self) -> String {
+		format!(
+			"FunctionInfo({}, {} instructions)",
+			self.id,
+			self.instructions.len()
+		)
+	}
+}
+
 #[pyclass(name = "CodegenIRSettings")]
 #[derive(Clone)]
 struct PyCodegenIRSettings {
@@ -92,6 +246,14 @@ impl PyCodegenIRSettings {
 	fn lir_passes(&mut self, val: bool) {
 		self.inner.lir_passes = val;
 	}
+
+	fn tick_budget(&mut self, val: bool) {
+		self.inner.tick_budget = val;
+	}
+
+	fn explain(&mut self, path: Option<String>) {
+		self.inner.explain = path.map(PathBuf::from);
+	}
 }
 
 #[pyclass(name = "ProjectSettings")]
@@ -108,6 +270,35 @@ impl PyProjectSettings {
 			inner: ProjectSettings::new(name),
 		}
 	}
+
+	/// Set the optimization level to one of none, basic, more, or full
+	fn op_level(&mut self, level: &str) -> PyResult<()> {
+		let level = match level {
+			"none" => OptimizationLevel::None,
+			"basic" => OptimizationLevel::Basic,
+			"more" => OptimizationLevel::More,
+			"full" => OptimizationLevel::Full,
+			other => {
+				return Err(PyValueError::new_err(format!(
+					"Unknown optimization level {other}"
+				)))
+			}
+		};
+		self.inner = ProjectSettingsBuilder::from_settings(self.inner.clone())
+			.op_level(level)
+			.build();
+		Ok(())
+	}
+
+	/// Set the Minecraft version that the pack targets, like 1.20.4
+	fn target_version(&mut self, version: &str) -> PyResult<()> {
+		let version = MinecraftVersion::parse(version)
+			.ok_or_else(|| PyValueError::new_err(format!("Invalid version {version}")))?;
+		self.inner = ProjectSettingsBuilder::from_settings(self.inner.clone())
+			.target_version(version)
+			.build();
+		Ok(())
+	}
 }
 
 #[pyclass(name = "Datapack")]
@@ -134,6 +325,25 @@ impl PyDatapack {
 			.map_err(|x| PyRuntimeError::new_err(format!("{x:?}")))?;
 		Ok(())
 	}
+
+	fn to_json(&self) -> PyResult<String> {
+		self.inner
+			.to_json()
+			.map_err(|x| PyRuntimeError::new_err(format!("{x:?}")))
+	}
+
+	fn to_bundle(&self) -> PyResult<String> {
+		self.inner
+			.to_bundle()
+			.map_err(|x| PyRuntimeError::new_err(format!("{x:?}")))
+	}
+
+	#[staticmethod]
+	fn from_json(json: &str) -> PyResult<Self> {
+		let inner =
+			Datapack::from_json(json).map_err(|x| PyRuntimeError::new_err(format!("{x:?}")))?;
+		Ok(Self { inner })
+	}
 }
 
 #[pyclass(name = "Datapack")]
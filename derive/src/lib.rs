@@ -0,0 +1,80 @@
+//! Derive macros used by dpc. These generate paths into the dpc crate itself,
+//! so they are only meant to be used from inside it
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives Codegen for a type by writing it out with one of its existing string
+/// representations. The representation is picked with an attribute:
+/// - `#[codegen(debug)]` uses the Debug impl. This is the default
+/// - `#[codegen(display)]` uses the Display impl
+/// - `#[codegen(str)]` uses a `codegen_str(&self)` method
+#[proc_macro_derive(Codegen, attributes(codegen))]
+pub fn derive_codegen(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match derive_codegen_impl(input) {
+		Ok(out) => out.into(),
+		Err(e) => e.to_compile_error().into(),
+	}
+}
+
+fn derive_codegen_impl(input: DeriveInput) -> syn::Result<TokenStream2> {
+	let mode = get_mode(&input)?;
+	let write = match mode {
+		Mode::Debug => quote!(write!(f, "{self:?}")?;),
+		Mode::Display => quote!(write!(f, "{self}")?;),
+		Mode::Str => quote!(write!(f, "{}", self.codegen_str())?;),
+	};
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics crate::output::codegen::Codegen for #name #ty_generics #where_clause {
+			fn gen_writer<F>(
+				&self,
+				f: &mut F,
+				_cbcx: &mut crate::output::codegen::CodegenBlockCx,
+			) -> anyhow::Result<()>
+			where
+				F: std::fmt::Write,
+			{
+				#write
+				Ok(())
+			}
+		}
+	})
+}
+
+enum Mode {
+	Debug,
+	Display,
+	Str,
+}
+
+fn get_mode(input: &DeriveInput) -> syn::Result<Mode> {
+	let mut mode = None;
+	for attr in &input.attrs {
+		if !attr.path().is_ident("codegen") {
+			continue;
+		}
+		attr.parse_nested_meta(|meta| {
+			if mode.is_some() {
+				return Err(meta.error("Codegen mode was already specified"));
+			}
+			mode = Some(if meta.path.is_ident("debug") {
+				Mode::Debug
+			} else if meta.path.is_ident("display") {
+				Mode::Display
+			} else if meta.path.is_ident("str") {
+				Mode::Str
+			} else {
+				return Err(meta.error("Unknown codegen mode"));
+			});
+			Ok(())
+		})?;
+	}
+
+	Ok(mode.unwrap_or(Mode::Debug))
+}
@@ -1,5 +1,7 @@
+use super::mc::item::ItemModifyLocation;
 use super::mc::pos::IntCoordinates;
-use super::mc::EntityTarget;
+use super::mc::{Difficulty, EntityTarget, Gamemode};
+use super::range::IntRange;
 use super::reg::GetUsedRegs;
 use super::val::{MutableValue, Value};
 use super::{Identifier, ResourceLocation, ResourceLocationTag};
@@ -27,6 +29,12 @@ pub enum Condition {
 	Loaded(IntCoordinates),
 	Dimension(ResourceLocation),
 	Function(ResourceLocationTag),
+	Gamemode(EntityTarget, Gamemode),
+	Difficulty(Difficulty),
+	/// The number of values that an NBT path matches is in a range
+	DataCount(Value, IntRange),
+	/// An item in the slots of a block or entity matches an item predicate
+	Items(ItemModifyLocation, String, String),
 }
 
 impl Condition {
@@ -41,7 +49,7 @@ impl Condition {
 					.into_iter()
 					.chain(r.get_used_regs_mut()),
 			),
-			Self::Exists(val) | Self::Bool(val) | Self::NotBool(val) => {
+			Self::Exists(val) | Self::Bool(val) | Self::NotBool(val) | Self::DataCount(val, ..) => {
 				Box::new(val.get_used_regs_mut().into_iter())
 			}
 			Self::Not(condition) => condition.iter_used_regs_mut(),
@@ -53,7 +61,10 @@ impl Condition {
 			| Self::Biome(..)
 			| Self::Loaded(..)
 			| Self::Dimension(..)
-			| Self::Function(..) => Box::new(iter::empty()),
+			| Self::Function(..)
+			| Self::Gamemode(..)
+			| Self::Difficulty(..)
+			| Self::Items(..) => Box::new(iter::empty()),
 		}
 	}
 
@@ -64,7 +75,7 @@ impl Condition {
 			| Self::GreaterThanOrEqual(l, r)
 			| Self::LessThan(l, r)
 			| Self::LessThanOrEqual(l, r) => Box::new(l.iter_mut_val().into_iter().chain(r.iter_mut_val())),
-			Self::Exists(val) | Self::Bool(val) | Self::NotBool(val) => {
+			Self::Exists(val) | Self::Bool(val) | Self::NotBool(val) | Self::DataCount(val, ..) => {
 				Box::new(val.iter_mut_val().into_iter())
 			}
 			Self::Not(condition) => condition.iter_mut_vals(),
@@ -76,7 +87,10 @@ impl Condition {
 			| Self::Biome(..)
 			| Self::Loaded(..)
 			| Self::Dimension(..)
-			| Self::Function(..) => Box::new(iter::empty()),
+			| Self::Function(..)
+			| Self::Gamemode(..)
+			| Self::Difficulty(..)
+			| Self::Items(..) => Box::new(iter::empty()),
 		}
 	}
 
@@ -99,7 +113,7 @@ impl GetUsedRegs for Condition {
 				l.append_used_regs(regs);
 				r.append_used_regs(regs);
 			}
-			Self::Exists(val) | Self::Bool(val) | Self::NotBool(val) => {
+			Self::Exists(val) | Self::Bool(val) | Self::NotBool(val) | Self::DataCount(val, ..) => {
 				val.append_used_regs(regs);
 			}
 			Self::Not(condition) => {
@@ -114,7 +128,10 @@ impl GetUsedRegs for Condition {
 			| Self::Biome(..)
 			| Self::Loaded(..)
 			| Self::Dimension(..)
-			| Self::Function(..) => {}
+			| Self::Function(..)
+			| Self::Gamemode(..)
+			| Self::Difficulty(..)
+			| Self::Items(..) => {}
 		}
 	}
 }
@@ -140,6 +157,10 @@ impl Debug for Condition {
 			Self::Loaded(loc) => write!(f, "load {loc:?}"),
 			Self::Dimension(dim) => write!(f, "dim {dim}"),
 			Self::Function(func) => write!(f, "fn {func}"),
+			Self::Gamemode(tgt, gamemode) => write!(f, "gm {tgt:?} {gamemode}"),
+			Self::Difficulty(difficulty) => write!(f, "diff {difficulty}"),
+			Self::DataCount(val, range) => write!(f, "dcnt {val:?} {range}"),
+			Self::Items(loc, slot, item) => write!(f, "itm {loc:?} {slot} {item}"),
 		}
 	}
 }
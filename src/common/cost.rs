@@ -7,6 +7,11 @@ use super::{
 	},
 	val::{MutableValue, Value},
 };
+use crate::mir::MIRBlock;
+
+/// How much more an instruction costs for every body that it is nested in,
+/// since each level of nesting puts more modifiers in front of it
+const NESTING_COST: f32 = 0.5;
 
 /// Used to get the cost of some operation as a number for comparison with other costs
 pub trait GetCost {
@@ -32,21 +37,52 @@ impl GetCost for Condition {
 		match self {
 			Self::And(l, r) | Self::Or(l, r) | Self::Xor(l, r) => l.get_cost() + r.get_cost(),
 			Self::Not(cond) => cond.get_cost(),
-			Self::Entity(..) => 40.0,
+			Self::Entity(..) | Self::Gamemode(..) => 40.0,
 			Self::Function(..) => 20.0,
 			Self::Biome(..) | Self::Loaded(..) | Self::Dimension(..) => 18.0,
+			// The difficulty has to be queried into a score first
+			Self::Difficulty(..) => 30.0,
 			Self::Predicate(..) => 12.0,
 			Self::GreaterThan(l, r)
 			| Self::GreaterThanOrEqual(l, r)
 			| Self::LessThan(l, r)
 			| Self::LessThanOrEqual(l, r) => (l.get_cost() + r.get_cost()) * 1.8,
 			Self::Exists(val) => val.get_cost() * 1.8,
+			// The count has to be stored into a score first
+			Self::DataCount(val, ..) => val.get_cost() * 1.8 + 10.0,
+			Self::Items(..) => 30.0,
 			Self::Equal(l, r) => (l.get_cost() + r.get_cost()) * 1.2,
 			Self::Bool(val) | Self::NotBool(val) => val.get_cost() * 1.1,
 		}
 	}
 }
 
+/// The cost of a block is how much code it is made of
+impl GetCost for MIRBlock {
+	fn get_cost(&self) -> f32 {
+		get_block_cost(self, 0)
+	}
+}
+
+fn get_block_cost(block: &MIRBlock, depth: u32) -> f32 {
+	block
+		.contents
+		.iter()
+		.map(|instr| {
+			// Instructions with bodies become modifiers on the commands in them
+			let bodies = instr.kind.get_bodies();
+			if bodies.is_empty() {
+				1.0 + depth as f32 * NESTING_COST
+			} else {
+				bodies
+					.into_iter()
+					.map(|body| get_block_cost(body, depth + 1))
+					.sum()
+			}
+		})
+		.sum()
+}
+
 impl GetCost for Value {
 	fn get_cost(&self) -> f32 {
 		match self {
@@ -111,7 +147,7 @@ impl GetCost for IfModCondition {
 				cost
 			}
 			Self::Entity(..) => 50.0,
-			Self::DataEquals(..) | Self::DataExists(..) => 40.0,
+			Self::DataEquals(..) | Self::DataExists(..) | Self::Items(..) => 40.0,
 			Self::Biome(..) | Self::Loaded(..) | Self::Dimension(..) => 32.0,
 			Self::Function(..) => 20.0,
 			Self::Predicate(..) => 15.0,
@@ -158,8 +194,12 @@ impl GetCost for SelectorParameter {
 			| Self::Tag { .. }
 			| Self::Type { .. }
 			| Self::Predicate { .. }
+			| Self::Scores { .. }
+			| Self::Advancements { .. }
+			| Self::Level { .. }
+			| Self::Team { .. }
 			| Self::NoTags => 7.0,
-			Self::Sort(..) | Self::Limit(..) => 1.0,
+			Self::Sort(..) | Self::Limit(..) | Self::Origin { .. } | Self::Volume { .. } => 1.0,
 		}
 	}
 }
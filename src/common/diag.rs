@@ -0,0 +1,190 @@
+use std::fmt::{Display, Write};
+
+use super::lint::Lint;
+use super::span::Span;
+
+/// How severe a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	/// Compilation failed
+	Error,
+	/// Compilation succeeded, but something in the input is likely a mistake
+	Warning,
+}
+
+/// A compiler error or warning with the location in the source that caused it
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	/// The code of the lint that caused the diagnostic, if there is one
+	pub code: Option<&'static str>,
+	/// The most specific message of the diagnostic
+	pub message: String,
+	/// The contexts that the error happened in, from innermost to outermost
+	pub notes: Vec<String>,
+	pub span: Option<Span>,
+}
+
+impl Diagnostic {
+	/// Creates a diagnostic from an error, using the innermost span attached to it
+	pub fn from_error(error: &anyhow::Error) -> Self {
+		let span = find_span(error);
+		let span_message = span.map(|x| SpanContext(x).to_string());
+		let mut messages: Vec<_> = error
+			.chain()
+			.map(|x| x.to_string())
+			.filter(|x| Some(x) != span_message.as_ref())
+			.collect();
+		let message = messages.pop().unwrap_or_default();
+		messages.reverse();
+
+		Self {
+			severity: Severity::Error,
+			code: None,
+			message,
+			notes: messages,
+			span,
+		}
+	}
+
+	/// Creates a warning from a lint
+	pub fn from_lint(lint: Lint, message: impl Display, span: Option<Span>) -> Self {
+		Self {
+			severity: Severity::Warning,
+			code: Some(lint.code()),
+			message: message.to_string(),
+			notes: Vec::new(),
+			span,
+		}
+	}
+
+	/// Renders the diagnostic with a snippet of the source file it came from
+	pub fn render(&self, file_name: &str, source: &str) -> String {
+		let mut out = format!("{}: {}\n", self.get_title(), self.message);
+		let line = self
+			.span
+			.and_then(|span| Some((span, source.lines().nth(span.line.checked_sub(1)?)?)));
+		if let Some((span, line)) = line {
+			let line_num = span.line.to_string();
+			let gutter = " ".repeat(line_num.len());
+			let _ = writeln!(
+				out,
+				"{gutter}--> {file_name}:{}:{}",
+				span.line,
+				span.col + 1
+			);
+			let _ = writeln!(out, "{gutter} |");
+			let _ = writeln!(out, "{line_num} | {line}");
+			// Keep tabs in the padding so that the marker lines up with the source line
+			let padding: String = line
+				.chars()
+				.take(span.col)
+				.map(|c| if c == '\t' { '\t' } else { ' ' })
+				.collect();
+			let line_len = line.chars().count();
+			let marker_len = span.len().min(line_len.saturating_sub(span.col)).max(1);
+			let _ = writeln!(out, "{gutter} | {padding}{}", "^".repeat(marker_len));
+		} else {
+			let _ = writeln!(out, " --> {file_name}");
+		}
+		for note in &self.notes {
+			let _ = writeln!(out, "  = note: {note}");
+		}
+
+		out
+	}
+
+	/// Gets the severity and code at the start of the diagnostic, like `warning[lossy_time]`
+	fn get_title(&self) -> String {
+		let severity = match self.severity {
+			Severity::Error => "error",
+			Severity::Warning => "warning",
+		};
+		match self.code {
+			Some(code) => format!("{severity}[{code}]"),
+			None => severity.into(),
+		}
+	}
+}
+
+impl Display for Diagnostic {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.get_title(), self.message)?;
+		if let Some(span) = self.span {
+			write!(f, " at line {}, column {}", span.line, span.col + 1)?;
+		}
+		Ok(())
+	}
+}
+
+/// Finds the innermost span that was attached to an error
+pub fn find_span(error: &anyhow::Error) -> Option<Span> {
+	error.downcast_ref::<SpanContext>().map(|x| x.0)
+}
+
+/// Error context with the location that caused an error
+#[derive(Debug, Clone, Copy)]
+struct SpanContext(Span);
+
+impl Display for SpanContext {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "At line {}, column {}", self.0.line, self.0.col + 1)
+	}
+}
+
+/// Trait for attaching source locations to errors
+pub trait WithSpan<T> {
+	/// Attaches a span to the error, unless it already has a more specific one
+	fn with_span(self, span: Option<Span>) -> anyhow::Result<T>;
+}
+
+impl<T> WithSpan<T> for anyhow::Result<T> {
+	fn with_span(self, span: Option<Span>) -> anyhow::Result<T> {
+		self.map_err(|e| match span {
+			Some(span) if find_span(&e).is_none() => e.context(SpanContext(span)),
+			_ => e,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use anyhow::{anyhow, Context};
+
+	use super::*;
+
+	#[test]
+	fn test_render() {
+		let source = "\"test:main\" {\n\tlet x: score = foo;\n}";
+		let error: anyhow::Result<()> = Err(anyhow!("Unexpected token"));
+		let error = error
+			.with_span(Some(Span::new(2, 16, 30, 33)))
+			.with_span(Some(Span::new(2, 1, 15, 34)))
+			.context("Failed to parse instruction 0")
+			.unwrap_err();
+		let diag = Diagnostic::from_error(&error);
+		assert_eq!(diag.span, Some(Span::new(2, 16, 30, 33)));
+		assert_eq!(
+			diag.render("main.dpc", source),
+			"error: Unexpected token\n --> main.dpc:2:17\n  |\n2 | \tlet x: score = foo;\n  | \t               ^^^\n  = note: Failed to parse instruction 0\n"
+		);
+	}
+
+	#[test]
+	fn test_render_warning() {
+		let source = "\"test:main\" {\n\tkill @e[];\n}";
+		let diag = Diagnostic::from_lint(
+			Lint::BroadSelector,
+			"Kill affects every entity",
+			Some(Span::new(2, 1, 15, 25)),
+		);
+		assert_eq!(
+			diag.render("main.dpc", source),
+			"warning[broad_selector]: Kill affects every entity\n --> main.dpc:2:2\n  |\n2 | \tkill @e[];\n  | \t^^^^^^^^^^\n"
+		);
+		assert_eq!(
+			diag.to_string(),
+			"warning[broad_selector]: Kill affects every entity at line 2, column 2"
+		);
+	}
+}
@@ -1,3 +1,4 @@
+use super::lint::Lint;
 use super::reg::GetUsedRegs;
 use super::val::MutableValue;
 use super::{ty::DataType, ResourceLocation};
@@ -185,6 +186,30 @@ pub struct FunctionAnnotations {
 	pub no_inline: bool,
 	pub no_strip: bool,
 	pub unused_result: bool,
+	/// Whether this function is an in-game test that gets a generated runner
+	pub test: bool,
+	/// Whether this function is part of the exported surface of the pack,
+	/// meaning it can be called from other packs and keeps its name
+	pub public: bool,
+	/// Whether this function is added to the minecraft:tick tag
+	pub tick: bool,
+	/// Whether this function is added to the minecraft:load tag
+	pub load: bool,
+	/// Where this function is called relative to the other functions in the
+	/// same generated tag, with lower orders being called first
+	pub order: Option<i32>,
+	/// How often this function is expected to run
+	pub freq: Option<InvocationFrequency>,
+	/// A manual estimate of how many commands this function runs per invocation
+	pub cost: Option<u32>,
+	/// Lints that are suppressed for the whole function
+	pub allow: Vec<Lint>,
+	/// A stable name that the function is output with, even when stripping, so
+	/// that other packs can call it
+	pub export: Option<ResourceLocation>,
+	/// Whether the compiler created this function for part of another one, like
+	/// the body of an if or a loop
+	pub generated: bool,
 }
 
 impl FunctionAnnotations {
@@ -194,12 +219,61 @@ impl FunctionAnnotations {
 			no_inline: false,
 			no_strip: false,
 			unused_result: false,
+			test: false,
+			public: false,
+			tick: false,
+			load: false,
+			order: None,
+			freq: None,
+			cost: None,
+			allow: Vec::new(),
+			export: None,
+			generated: false,
 		}
 	}
 }
 
+impl FunctionAnnotations {
+	/// Whether the function can be run without being called by another
+	/// function in the pack, meaning it can't be removed
+	pub fn is_root(&self) -> bool {
+		self.preserve || self.public || self.test || self.tick || self.load || self.export.is_some()
+	}
+}
+
 impl Default for FunctionAnnotations {
 	fn default() -> Self {
 		Self::new()
 	}
 }
+
+/// How often a function is expected to be invoked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvocationFrequency {
+	/// Runs every tick
+	Tick,
+	/// Runs every once in a while, such as in response to an event
+	Rare,
+	/// Runs once every given number of ticks
+	Interval(u32),
+}
+
+impl InvocationFrequency {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"tick" => Some(Self::Tick),
+			"rare" => Some(Self::Rare),
+			_ => None,
+		}
+	}
+
+	/// Gets the estimated number of times the function is run each tick
+	pub fn get_invocations_per_tick(&self) -> f32 {
+		match self {
+			Self::Tick => 1.0,
+			// About once every minute
+			Self::Rare => 1.0 / 1200.0,
+			Self::Interval(ticks) => 1.0 / (*ticks).max(1) as f32,
+		}
+	}
+}
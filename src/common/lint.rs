@@ -0,0 +1,103 @@
+use std::fmt::Display;
+
+use anyhow::anyhow;
+
+use super::diag::{Diagnostic, WithSpan};
+use super::span::Span;
+
+/// A kind of warning that the compiler can emit, which can be
+/// allowed on a function or instruction to suppress it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+	/// A condition or selector that can never be true
+	ImpossibleCondition,
+	/// A write to a register bound to a criteria objective
+	CriteriaWrite,
+	/// A destructive instruction like a kill or teleport on every entity,
+	/// using a selector without any type, tag, or distance constraints
+	BroadSelector,
+	/// A time that is not a whole number of ticks, which has to be rounded
+	LossyTime,
+}
+
+impl Lint {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"impossible_condition" => Some(Self::ImpossibleCondition),
+			"criteria_write" => Some(Self::CriteriaWrite),
+			"broad_selector" => Some(Self::BroadSelector),
+			"lossy_time" => Some(Self::LossyTime),
+			_ => None,
+		}
+	}
+
+	/// Gets the code used to refer to this lint
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::ImpossibleCondition => "impossible_condition",
+			Self::CriteriaWrite => "criteria_write",
+			Self::BroadSelector => "broad_selector",
+			Self::LossyTime => "lossy_time",
+		}
+	}
+}
+
+/// How severe a lint is when it is triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LintLevel {
+	/// The lint is ignored
+	Allow,
+	/// The lint prints a warning
+	#[default]
+	Warn,
+	/// The lint is a compile error
+	Deny,
+}
+
+impl LintLevel {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"allow" => Some(Self::Allow),
+			"warn" => Some(Self::Warn),
+			"deny" => Some(Self::Deny),
+			_ => None,
+		}
+	}
+}
+
+/// Adds a warning to the diagnostics unless its lint is in the list of allowed lints
+pub fn warn<'a>(
+	diagnostics: &mut Vec<Diagnostic>,
+	lint: Lint,
+	allowed: impl IntoIterator<Item = &'a Lint>,
+	message: impl Display,
+	span: Option<Span>,
+) {
+	if allowed.into_iter().any(|x| *x == lint) {
+		return;
+	}
+	diagnostics.push(Diagnostic::from_lint(lint, message, span));
+}
+
+/// Reports a lint at the given level unless it is in the list of allowed lints,
+/// erroring if the lint is denied
+pub fn report<'a>(
+	diagnostics: &mut Vec<Diagnostic>,
+	lint: Lint,
+	level: LintLevel,
+	allowed: impl IntoIterator<Item = &'a Lint>,
+	message: impl Display,
+	span: Option<Span>,
+) -> anyhow::Result<()> {
+	match level {
+		LintLevel::Allow => {}
+		LintLevel::Warn => warn(diagnostics, lint, allowed, message, span),
+		LintLevel::Deny => {
+			if !allowed.into_iter().any(|x| *x == lint) {
+				return Err(anyhow!("Error[{}]: {message}", lint.code())).with_span(span);
+			}
+		}
+	}
+
+	Ok(())
+}
@@ -2,6 +2,7 @@ use std::{collections::HashMap, fmt::Debug};
 
 use crate::common::{ty::NBTCompoundTypeContents, Identifier};
 use crate::common::{ResourceLocation, ResourceLocationTag};
+use crate::output::codegen::Codegen;
 
 use super::pos::IntCoordinates;
 
@@ -144,7 +145,7 @@ impl Debug for BlockProperties {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum SetBlockMode {
 	Destroy,
 	Keep,
@@ -172,7 +173,7 @@ impl SetBlockMode {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum FillMode {
 	Destroy,
 	Hollow,
@@ -199,6 +200,20 @@ impl Debug for FillMode {
 	}
 }
 
+impl FillMode {
+	/// Parses a fill mode without a filter
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"destroy" => Some(Self::Destroy),
+			"hollow" => Some(Self::Hollow),
+			"keep" => Some(Self::Keep),
+			"outline" => Some(Self::Outline),
+			"replace" => Some(Self::Replace(None)),
+			_ => None,
+		}
+	}
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum CloneMaskMode {
 	Replace,
@@ -216,7 +231,18 @@ impl Debug for CloneMaskMode {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+impl CloneMaskMode {
+	/// Parses a mask mode without a filter
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"replace" => Some(Self::Replace),
+			"masked" => Some(Self::Masked),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum CloneMode {
 	Force,
 	Move,
@@ -233,6 +259,17 @@ impl Debug for CloneMode {
 	}
 }
 
+impl CloneMode {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"force" => Some(Self::Force),
+			"move" => Some(Self::Move),
+			"normal" => Some(Self::Normal),
+			_ => None,
+		}
+	}
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct BlockStates(HashMap<String, BlockStateValue>);
 
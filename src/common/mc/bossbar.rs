@@ -0,0 +1,59 @@
+use std::fmt::Debug;
+
+use crate::output::codegen::Codegen;
+
+use super::text::TextComponent;
+use super::EntityTarget;
+
+/// A property of a bossbar that can be set
+#[derive(Clone, PartialEq)]
+pub enum BossbarOption {
+	Value(i32),
+	Max(i32),
+	Name(TextComponent),
+	/// Sets the players that can see the bossbar, or removes all of them when the target is None
+	Players(Option<EntityTarget>),
+}
+
+impl Debug for BossbarOption {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Value(value) => write!(f, "value {value}"),
+			Self::Max(max) => write!(f, "max {max}"),
+			Self::Name(name) => write!(f, "name {name:?}"),
+			Self::Players(players) => write!(f, "players {players:?}"),
+		}
+	}
+}
+
+/// A property of a bossbar that can be read
+#[derive(Clone, PartialEq, Eq, Codegen)]
+pub enum BossbarQuery {
+	Value,
+	Max,
+	Players,
+	Visible,
+}
+
+impl BossbarQuery {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"value" => Some(Self::Value),
+			"max" => Some(Self::Max),
+			"players" => Some(Self::Players),
+			"visible" => Some(Self::Visible),
+			_ => None,
+		}
+	}
+}
+
+impl Debug for BossbarQuery {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Value => write!(f, "value"),
+			Self::Max => write!(f, "max"),
+			Self::Players => write!(f, "players"),
+			Self::Visible => write!(f, "visible"),
+		}
+	}
+}
@@ -1,8 +1,17 @@
 use std::fmt::{Debug, Display};
+use std::ops::Add;
 
-use crate::common::range::FloatRange;
+use anyhow::bail;
 
-use super::{super::ty::NBTCompoundTypeContents, Gamemode};
+use crate::common::range::{FloatRange, IntRange};
+use crate::output::codegen::Codegen;
+
+use super::pos::Axis;
+use super::time::{Time, TimeUnit};
+use super::version::MinecraftVersion;
+
+use super::super::ty::{create_nint_array, NBTArrayTypeContents, NBTTypeContents};
+use super::{super::ty::NBTCompoundTypeContents, EntityTarget, Gamemode};
 
 #[derive(Clone, PartialEq)]
 pub struct TargetSelector {
@@ -23,8 +32,18 @@ impl TargetSelector {
 		matches!(self.selector, SelectorType::This) && self.params.is_empty()
 	}
 
+	/// Checks if two selectors select the same entities. The order of parameters
+	/// doesn't matter to the game, so it is ignored
 	pub fn is_value_eq(&self, other: &Self) -> bool {
-		self.selector == other.selector && self.params == other.params
+		let contains_all = |left: &Self, right: &Self| {
+			left.params
+				.iter()
+				.all(|x| right.params.iter().any(|y| x.is_value_eq(y)))
+		};
+		self.selector == other.selector
+			&& self.params.len() == other.params.len()
+			&& contains_all(self, other)
+			&& contains_all(other, self)
 	}
 
 	/// Checks if this selector is in single type
@@ -39,17 +58,71 @@ impl TargetSelector {
 			|| matches!(self.selector, SelectorType::AllEntities if self.params.contains(&SelectorParameter::Type { ty: "player".into(), invert: false }))
 	}
 
+	/// Checks if the entities this selector finds depend on the position it is run at.
+	/// Specifying all of the coordinates of the origin makes it independent of the position
 	pub fn relies_on_position(&self) -> bool {
+		let has_origin = [Axis::X, Axis::Y, Axis::Z].iter().all(|axis| {
+			self.params.iter().any(
+				|x| matches!(x, SelectorParameter::Origin { axis: other, .. } if other == axis),
+			)
+		});
+		if has_origin {
+			return false;
+		}
+
 		matches!(self.selector, SelectorType::NearestPlayer)
-			| self
-				.params
-				.iter()
-				.any(|x| matches!(x, SelectorParameter::Distance { .. }))
+			| self.params.iter().any(|x| {
+				matches!(
+					x,
+					SelectorParameter::Distance { .. }
+						| SelectorParameter::Volume { .. }
+						| SelectorParameter::Sort(SelectorSort::Nearest | SelectorSort::Furthest)
+				)
+			})
+	}
+
+	/// Sets the order that entities are selected in, replacing any existing sort
+	pub fn set_sort(&mut self, sort: SelectorSort) {
+		self.params
+			.retain(|x| !matches!(x, SelectorParameter::Sort(..)));
+		self.params.push(SelectorParameter::Sort(sort));
 	}
 
 	pub fn relies_on_executor(&self) -> bool {
 		matches!(self.selector, SelectorType::This)
 	}
+
+	/// Checks if this selector can never select any entities
+	pub fn is_impossible(&self) -> bool {
+		let has_no_tags = self.params.contains(&SelectorParameter::NoTags);
+		self.params.iter().any(|param| match param {
+			SelectorParameter::Limit(0) => true,
+			SelectorParameter::Tag { tag, invert } => {
+				(has_no_tags && !invert)
+					|| self.params.contains(&SelectorParameter::Tag {
+						tag: tag.clone(),
+						invert: !invert,
+					})
+			}
+			SelectorParameter::Type { ty, invert } => self.params.contains(&SelectorParameter::Type {
+				ty: ty.clone(),
+				invert: !invert,
+			}),
+			SelectorParameter::Team { team, invert } => {
+				self.params.contains(&SelectorParameter::Team {
+					team: team.clone(),
+					invert: !invert,
+				})
+			}
+			SelectorParameter::Gamemode { gamemode, invert } => {
+				self.params.contains(&SelectorParameter::Gamemode {
+					gamemode: gamemode.clone(),
+					invert: !invert,
+				})
+			}
+			_ => false,
+		})
+	}
 }
 
 impl Debug for TargetSelector {
@@ -69,7 +142,227 @@ impl Debug for TargetSelector {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Builder for a target selector, which checks that the parameters
+/// are valid for the selector type when it is built
+#[derive(Debug, Clone)]
+pub struct Selector {
+	selector: SelectorType,
+	params: Vec<SelectorParameter>,
+}
+
+impl Selector {
+	pub fn new(selector: SelectorType) -> Self {
+		Self {
+			selector,
+			params: Vec::new(),
+		}
+	}
+
+	/// `@s`
+	pub fn this() -> Self {
+		Self::new(SelectorType::This)
+	}
+
+	/// `@p`
+	pub fn nearest_player() -> Self {
+		Self::new(SelectorType::NearestPlayer)
+	}
+
+	/// `@r`
+	pub fn random_player() -> Self {
+		Self::new(SelectorType::RandomPlayer)
+	}
+
+	/// `@a`
+	pub fn all_players() -> Self {
+		Self::new(SelectorType::AllPlayers)
+	}
+
+	/// `@e`
+	pub fn all_entities() -> Self {
+		Self::new(SelectorType::AllEntities)
+	}
+
+	pub fn param(mut self, param: SelectorParameter) -> Self {
+		self.params.push(param);
+		self
+	}
+
+	pub fn ty(self, ty: &str) -> Self {
+		self.param(SelectorParameter::Type {
+			ty: ty.into(),
+			invert: false,
+		})
+	}
+
+	pub fn not_ty(self, ty: &str) -> Self {
+		self.param(SelectorParameter::Type {
+			ty: ty.into(),
+			invert: true,
+		})
+	}
+
+	pub fn tag(self, tag: &str) -> Self {
+		self.param(SelectorParameter::Tag {
+			tag: tag.into(),
+			invert: false,
+		})
+	}
+
+	pub fn not_tag(self, tag: &str) -> Self {
+		self.param(SelectorParameter::Tag {
+			tag: tag.into(),
+			invert: true,
+		})
+	}
+
+	pub fn no_tags(self) -> Self {
+		self.param(SelectorParameter::NoTags)
+	}
+
+	pub fn predicate(self, predicate: &str) -> Self {
+		self.param(SelectorParameter::Predicate {
+			predicate: predicate.into(),
+			invert: false,
+		})
+	}
+
+	pub fn name(self, name: &str) -> Self {
+		self.param(SelectorParameter::Name {
+			name: name.into(),
+			invert: false,
+		})
+	}
+
+	pub fn gamemode(self, gamemode: Gamemode) -> Self {
+		self.param(SelectorParameter::Gamemode {
+			gamemode,
+			invert: false,
+		})
+	}
+
+	pub fn nbt(self, nbt: NBTCompoundTypeContents) -> Self {
+		self.param(SelectorParameter::NBT { nbt, invert: false })
+	}
+
+	pub fn distance(self, range: FloatRange) -> Self {
+		self.param(SelectorParameter::Distance { range })
+	}
+
+	pub fn limit(self, limit: u32) -> Self {
+		self.param(SelectorParameter::Limit(limit))
+	}
+
+	pub fn level(self, range: IntRange) -> Self {
+		self.param(SelectorParameter::Level { range })
+	}
+
+	pub fn team(self, team: &str) -> Self {
+		self.param(SelectorParameter::Team {
+			team: team.into(),
+			invert: false,
+		})
+	}
+
+	pub fn not_team(self, team: &str) -> Self {
+		self.param(SelectorParameter::Team {
+			team: team.into(),
+			invert: true,
+		})
+	}
+
+	/// Sets the position that the selector searches from
+	pub fn origin(self, x: f64, y: f64, z: f64) -> Self {
+		self.param(SelectorParameter::Origin {
+			axis: Axis::X,
+			value: x,
+		})
+		.param(SelectorParameter::Origin {
+			axis: Axis::Y,
+			value: y,
+		})
+		.param(SelectorParameter::Origin {
+			axis: Axis::Z,
+			value: z,
+		})
+	}
+
+	/// Sets the size of the box that the selector searches in
+	pub fn volume(self, dx: f64, dy: f64, dz: f64) -> Self {
+		self.param(SelectorParameter::Volume {
+			axis: Axis::X,
+			value: dx,
+		})
+		.param(SelectorParameter::Volume {
+			axis: Axis::Y,
+			value: dy,
+		})
+		.param(SelectorParameter::Volume {
+			axis: Axis::Z,
+			value: dz,
+		})
+	}
+
+	pub fn sort(self, sort: SelectorSort) -> Self {
+		self.param(SelectorParameter::Sort(sort))
+	}
+
+	/// Checks the parameters and creates the selector
+	pub fn build(self) -> anyhow::Result<TargetSelector> {
+		let mut seen = Vec::new();
+		for param in &self.params {
+			let key = param.get_key();
+			// Inverted parameters can be repeated, but the others can only be used once
+			let repeatable = match param {
+				SelectorParameter::Type { invert, .. }
+				| SelectorParameter::Name { invert, .. }
+				| SelectorParameter::Gamemode { invert, .. }
+				| SelectorParameter::Team { invert, .. } => *invert,
+				SelectorParameter::Tag { .. }
+				| SelectorParameter::Predicate { .. }
+				| SelectorParameter::NBT { .. }
+				| SelectorParameter::NoTags => true,
+				SelectorParameter::Distance { .. }
+				| SelectorParameter::Scores { .. }
+				| SelectorParameter::Advancements { .. }
+				| SelectorParameter::Level { .. }
+				| SelectorParameter::Origin { .. }
+				| SelectorParameter::Volume { .. }
+				| SelectorParameter::Limit(..)
+				| SelectorParameter::Sort(..) => false,
+			};
+			if !repeatable {
+				if seen.contains(&key) {
+					bail!("Selector parameter {key} can only be used once");
+				}
+				seen.push(key);
+			}
+
+			let applicable = match param {
+				SelectorParameter::Type { .. } => !self.selector.is_player_type(),
+				SelectorParameter::Limit(..) => self.selector != SelectorType::This,
+				SelectorParameter::Sort(..) => !self.selector.is_single_type(),
+				_ => true,
+			};
+			if !applicable {
+				bail!(
+					"Selector parameter {key} cannot be used with {}",
+					self.selector.codegen_str()
+				);
+			}
+		}
+
+		Ok(TargetSelector::with_params(self.selector, self.params))
+	}
+
+	/// Checks the parameters and creates an entity target from the selector
+	pub fn build_target(self) -> anyhow::Result<EntityTarget> {
+		self.build().map(EntityTarget::Selector)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Codegen)]
+#[codegen(str)]
 pub enum SelectorType {
 	This,
 	NearestPlayer,
@@ -133,11 +426,83 @@ pub enum SelectorParameter {
 		nbt: NBTCompoundTypeContents,
 		invert: bool,
 	},
+	/// Ranges that the scores of the entity on objectives must be in
+	Scores {
+		scores: Vec<(String, IntRange)>,
+	},
+	/// Whether the player has completed advancements
+	Advancements {
+		advancements: Vec<(String, bool)>,
+	},
+	Level {
+		range: IntRange,
+	},
+	/// One of the coordinates of the position that the selector searches from
+	Origin {
+		axis: Axis,
+		value: f64,
+	},
+	/// One of the sizes of the box that the selector searches in
+	Volume {
+		axis: Axis,
+		value: f64,
+	},
+	/// The team of the entity. An empty team matches entities that are not on a team
+	Team {
+		team: String,
+		invert: bool,
+	},
 	Limit(u32),
 	Sort(SelectorSort),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl SelectorParameter {
+	/// Gets the name of this parameter in a selector
+	pub fn get_key(&self) -> &'static str {
+		match self {
+			Self::Distance { .. } => "distance",
+			Self::Type { .. } => "type",
+			Self::Tag { .. } => "tag",
+			Self::NoTags => "no_tags",
+			Self::Predicate { .. } => "predicate",
+			Self::Gamemode { .. } => "gamemode",
+			Self::Name { .. } => "name",
+			Self::NBT { .. } => "nbt",
+			Self::Scores { .. } => "scores",
+			Self::Advancements { .. } => "advancements",
+			Self::Level { .. } => "level",
+			Self::Origin { axis, .. } => axis.codegen_str(),
+			Self::Volume { axis, .. } => match axis {
+				Axis::X => "dx",
+				Axis::Y => "dy",
+				Axis::Z => "dz",
+			},
+			Self::Team { .. } => "team",
+			Self::Limit(..) => "limit",
+			Self::Sort(..) => "sort",
+		}
+	}
+
+	/// Checks if two parameters filter entities in the same way
+	pub fn is_value_eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			// The order of the entries doesn't matter
+			(Self::Scores { scores: left }, Self::Scores { scores: right }) => {
+				left.len() == right.len() && left.iter().all(|x| right.contains(x))
+			}
+			(
+				Self::Advancements { advancements: left },
+				Self::Advancements {
+					advancements: right,
+				},
+			) => left.len() == right.len() && left.iter().all(|x| right.contains(x)),
+			_ => self == other,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Codegen)]
+#[codegen(display)]
 pub enum SelectorSort {
 	Nearest,
 	Furthest,
@@ -145,6 +510,18 @@ pub enum SelectorSort {
 	Arbitrary,
 }
 
+impl SelectorSort {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"nearest" => Some(Self::Nearest),
+			"furthest" => Some(Self::Furthest),
+			"random" => Some(Self::Random),
+			"arbitrary" => Some(Self::Arbitrary),
+			_ => None,
+		}
+	}
+}
+
 impl Display for SelectorSort {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(
@@ -160,10 +537,85 @@ impl Display for SelectorSort {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct UUID(String);
+/// A 128-bit entity UUID
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Codegen)]
+pub struct UUID(u128);
 
-#[derive(Clone, PartialEq, Eq)]
+impl UUID {
+	pub fn new(val: u128) -> Self {
+		Self(val)
+	}
+
+	/// Parses a UUID from its hyphenated hex form. Like the game, this allows
+	/// each group to be shortened
+	pub fn parse(string: &str) -> Option<Self> {
+		let groups: Vec<_> = string.split('-').collect();
+		let [a, b, c, d, e] = groups[..] else {
+			return None;
+		};
+		let mut out = 0u128;
+		for (group, bits) in [(a, 32), (b, 16), (c, 16), (d, 16), (e, 48)] {
+			if group.is_empty() || group.len() * 4 > bits {
+				return None;
+			}
+			let val = u64::from_str_radix(group, 16).ok()?;
+			out = (out << bits) | val as u128;
+		}
+		Some(Self(out))
+	}
+
+	/// Creates a UUID from the four ints that it is stored as in NBT
+	pub fn from_ints(ints: [i32; 4]) -> Self {
+		let out = ints
+			.iter()
+			.fold(0u128, |acc, x| (acc << 32) | *x as u32 as u128);
+		Self(out)
+	}
+
+	/// Gets the four ints that this UUID is stored as in NBT, from most to least significant
+	pub fn to_ints(&self) -> [i32; 4] {
+		[
+			(self.0 >> 96) as u32 as i32,
+			(self.0 >> 64) as u32 as i32,
+			(self.0 >> 32) as u32 as i32,
+			self.0 as u32 as i32,
+		]
+	}
+
+	/// Converts this UUID to an NBT int array
+	pub fn to_nbt(&self) -> NBTTypeContents {
+		NBTTypeContents::Arr(create_nint_array(self.to_ints().to_vec()))
+	}
+
+	/// Converts an NBT int array to a UUID
+	pub fn from_nbt(nbt: &NBTTypeContents) -> Option<Self> {
+		let NBTTypeContents::Arr(NBTArrayTypeContents::Int(ints, ..)) = nbt else {
+			return None;
+		};
+		Some(Self::from_ints(ints.as_slice().try_into().ok()?))
+	}
+
+	pub fn get(&self) -> u128 {
+		self.0
+	}
+}
+
+impl Debug for UUID {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let hex = format!("{:032x}", self.0);
+		write!(
+			f,
+			"{}-{}-{}-{}-{}",
+			&hex[0..8],
+			&hex[8..12],
+			&hex[12..16],
+			&hex[16..20],
+			&hex[20..32]
+		)
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum AttributeType {
 	Add,
 	Multiply,
@@ -204,7 +656,208 @@ impl Debug for EffectDuration {
 }
 
 impl EffectDuration {
+	/// The longest finite duration in seconds that the game allows
+	pub const MAX_SECONDS: i32 = 1_000_000;
+	/// The first version of the game with infinite effect durations
+	pub const INFINITE_SINCE: MinecraftVersion = MinecraftVersion::new(19, 4);
+
 	pub fn is_default(&self) -> bool {
 		matches!(self, Self::Seconds(amt) if *amt == 30)
 	}
+
+	/// Gets a duration from a time, which has to be a whole number of seconds
+	pub fn from_time(time: &Time) -> anyhow::Result<Self> {
+		let seconds = time.clone().convert(TimeUnit::Seconds).amount;
+		if seconds.fract() != 0.0 {
+			bail!("Effect duration {time:?} is not a whole number of seconds");
+		}
+		if seconds > i32::MAX as f32 || seconds < i32::MIN as f32 {
+			bail!("Effect duration {time:?} is out of range");
+		}
+
+		Ok(Self::Seconds(seconds as i32))
+	}
+
+	/// Checks that the game will accept this duration
+	pub fn validate(&self) -> anyhow::Result<()> {
+		if let Self::Seconds(seconds) = self {
+			if !(1..=Self::MAX_SECONDS).contains(seconds) {
+				bail!(
+					"Effect duration {seconds} must be between 1 and {} seconds",
+					Self::MAX_SECONDS
+				);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Gets the duration to use on a version of the game. Infinite durations
+	/// become the longest finite one on versions that don't have them
+	pub fn for_version(&self, version: MinecraftVersion) -> Self {
+		match self {
+			Self::Infinite if version < Self::INFINITE_SINCE => Self::Seconds(Self::MAX_SECONDS),
+			other => other.clone(),
+		}
+	}
+}
+
+impl Add for EffectDuration {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		match (self, rhs) {
+			(Self::Seconds(l), Self::Seconds(r)) => Self::Seconds(l.saturating_add(r)),
+			_ => Self::Infinite,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_effect_duration() {
+		let duration = EffectDuration::from_time(&Time::new(1200.0, TimeUnit::Ticks))
+			.expect("Time is whole seconds");
+		assert_eq!(duration, EffectDuration::Seconds(60));
+		assert!(EffectDuration::from_time(&Time::new(5.0, TimeUnit::Ticks)).is_err());
+
+		let sum = duration + EffectDuration::Seconds(30);
+		assert_eq!(sum, EffectDuration::Seconds(90));
+		assert_eq!(sum + EffectDuration::Infinite, EffectDuration::Infinite);
+
+		assert!(EffectDuration::Seconds(0).validate().is_err());
+		assert!(EffectDuration::Seconds(EffectDuration::MAX_SECONDS + 1)
+			.validate()
+			.is_err());
+		assert_eq!(
+			EffectDuration::Infinite.for_version(MinecraftVersion::new(19, 2)),
+			EffectDuration::Seconds(EffectDuration::MAX_SECONDS)
+		);
+		assert_eq!(
+			EffectDuration::Infinite.for_version(MinecraftVersion::new(20, 0)),
+			EffectDuration::Infinite
+		);
+	}
+
+	#[test]
+	fn test_uuid_parse() {
+		let uuid = UUID::parse("f81d4fae-7dec-11d0-a765-00a0c91e6bf6").expect("Failed to parse");
+		assert_eq!(uuid.get(), 0xf81d4fae7dec11d0a76500a0c91e6bf6);
+		assert_eq!(format!("{uuid:?}"), "f81d4fae-7dec-11d0-a765-00a0c91e6bf6");
+
+		let short = UUID::parse("0-0-0-0-1").expect("Failed to parse");
+		assert_eq!(short.get(), 1);
+		assert!(UUID::parse("0-0-0-1").is_none());
+		assert!(UUID::parse("0-0-0-0-g").is_none());
+		assert!(UUID::parse("0-00000-0-0-1").is_none());
+	}
+
+	#[test]
+	fn test_impossible_selector() {
+		let tag = |tag: &str, invert| SelectorParameter::Tag {
+			tag: tag.into(),
+			invert,
+		};
+		let sel = |params| TargetSelector::with_params(SelectorType::AllEntities, params);
+		assert!(sel(vec![SelectorParameter::Limit(0)]).is_impossible());
+		assert!(sel(vec![tag("foo", false), tag("foo", true)]).is_impossible());
+		assert!(sel(vec![SelectorParameter::NoTags, tag("foo", false)]).is_impossible());
+		assert!(!sel(vec![SelectorParameter::NoTags, tag("foo", true)]).is_impossible());
+		assert!(!sel(vec![tag("foo", false), tag("bar", true)]).is_impossible());
+	}
+
+	#[test]
+	fn test_selector_builder() {
+		let sel = Selector::all_players()
+			.tag("x")
+			.limit(1)
+			.sort(SelectorSort::Nearest)
+			.build()
+			.expect("Selector should be valid");
+		assert_eq!(sel.selector, SelectorType::AllPlayers);
+		assert_eq!(sel.params[1], SelectorParameter::Limit(1));
+
+		assert!(Selector::all_entities().limit(1).limit(2).build().is_err());
+		assert!(Selector::all_entities()
+			.not_ty("a")
+			.not_ty("b")
+			.build()
+			.is_ok());
+		assert!(Selector::all_players().ty("zombie").build().is_err());
+		assert!(Selector::this().limit(1).build().is_err());
+		assert!(Selector::nearest_player()
+			.sort(SelectorSort::Random)
+			.build()
+			.is_err());
+	}
+
+	#[test]
+	fn test_selector_dependencies() {
+		let sel = Selector::all_entities()
+			.distance(FloatRange::parse("..5").expect("Range is valid"))
+			.build()
+			.expect("Selector should be valid");
+		assert!(sel.relies_on_position());
+		assert!(!sel.relies_on_executor());
+
+		let sel = Selector::all_entities()
+			.volume(1.0, 2.0, 3.0)
+			.build()
+			.expect("Selector should be valid");
+		assert!(sel.relies_on_position());
+		let sel = Selector::nearest_player()
+			.origin(0.0, 64.0, 0.0)
+			.build()
+			.expect("Selector should be valid");
+		assert!(!sel.relies_on_position());
+
+		let sel = Selector::all_players()
+			.level(IntRange::parse("5..").expect("Range is valid"))
+			.team("red")
+			.build()
+			.expect("Selector should be valid");
+		assert!(!sel.relies_on_position());
+		assert!(Selector::all_players()
+			.team("red")
+			.not_team("red")
+			.build()
+			.expect("Selector should be valid")
+			.is_impossible());
+	}
+
+	#[test]
+	fn test_selector_value_eq() {
+		let scores = |scores: &[(&str, i32)]| SelectorParameter::Scores {
+			scores: scores
+				.iter()
+				.map(|(obj, val)| (obj.to_string(), IntRange::parse(&val.to_string()).unwrap()))
+				.collect(),
+		};
+		let left = TargetSelector::with_params(
+			SelectorType::AllEntities,
+			vec![scores(&[("a", 1), ("b", 2)]), SelectorParameter::Limit(1)],
+		);
+		let right = TargetSelector::with_params(
+			SelectorType::AllEntities,
+			vec![SelectorParameter::Limit(1), scores(&[("b", 2), ("a", 1)])],
+		);
+		assert!(left.is_value_eq(&right));
+		let other = TargetSelector::with_params(
+			SelectorType::AllEntities,
+			vec![SelectorParameter::Limit(1), scores(&[("b", 2), ("a", 3)])],
+		);
+		assert!(!left.is_value_eq(&other));
+	}
+
+	#[test]
+	fn test_uuid_ints() {
+		let uuid = UUID::parse("f81d4fae-7dec-11d0-a765-00a0c91e6bf6").expect("Failed to parse");
+		let ints = uuid.to_ints();
+		assert_eq!(ints, [-132296786, 2112623056, -1486552928, -920753162]);
+		assert_eq!(UUID::from_ints(ints), uuid);
+		assert_eq!(UUID::from_nbt(&uuid.to_nbt()), Some(uuid));
+	}
 }
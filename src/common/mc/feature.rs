@@ -0,0 +1,117 @@
+use super::instr::MinecraftInstr;
+use super::version::MinecraftVersion;
+
+/// Content that is only available when a pack enables a feature flag,
+/// up until the version where it became part of the base game
+struct GatedContent {
+	feature: &'static str,
+	/// The first version where the content no longer needs the flag
+	until: MinecraftVersion,
+	entities: &'static [&'static str],
+	items: &'static [&'static str],
+}
+
+const GATED_CONTENT: &[GatedContent] = &[
+	GatedContent {
+		feature: "update_1_20",
+		until: MinecraftVersion::new(20, 0),
+		entities: &["camel", "sniffer"],
+		items: &[
+			"camel_spawn_egg",
+			"sniffer_spawn_egg",
+			"brush",
+			"cherry_sapling",
+			"chiseled_bookshelf",
+			"bamboo_raft",
+		],
+	},
+	GatedContent {
+		feature: "update_1_21",
+		until: MinecraftVersion::new(21, 0),
+		entities: &["breeze", "bogged", "wind_charge"],
+		items: &[
+			"crafter",
+			"trial_spawner",
+			"trial_key",
+			"breeze_spawn_egg",
+			"bogged_spawn_egg",
+			"wind_charge",
+			"mace",
+			"copper_bulb",
+		],
+	},
+	GatedContent {
+		feature: "bundle",
+		until: MinecraftVersion::new(21, 2),
+		entities: &[],
+		items: &["bundle"],
+	},
+];
+
+/// Gets the feature flag that an instruction needs to be enabled on a version,
+/// along with the content that needs it
+pub fn get_required_feature(
+	instr: &MinecraftInstr,
+	version: MinecraftVersion,
+) -> Option<(&'static str, &str)> {
+	let (id, is_entity) = match instr {
+		MinecraftInstr::SummonEntity { entity, .. } => (entity, true),
+		MinecraftInstr::GiveItem { item, .. } | MinecraftInstr::ItemReplaceWith { item, .. } => {
+			(&item.item, false)
+		}
+		_ => return None,
+	};
+	let name = id.strip_prefix("minecraft:").unwrap_or(id);
+
+	GATED_CONTENT
+		.iter()
+		.filter(|x| version < x.until)
+		.find(|x| {
+			let ids = if is_entity { x.entities } else { x.items };
+			ids.contains(&name)
+		})
+		.map(|x| (x.feature, id.as_ref()))
+}
+
+/// Checks if a list of enabled feature flags contains a feature,
+/// which may be written with or without the minecraft namespace
+pub fn is_feature_enabled(enabled: &[String], feature: &str) -> bool {
+	enabled
+		.iter()
+		.any(|x| x.strip_prefix("minecraft:").unwrap_or(x) == feature)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::common::mc::pos::Coordinates;
+	use crate::common::ty::NBTCompoundTypeContents;
+
+	use super::*;
+
+	#[test]
+	fn test_required_feature() {
+		let summon = |entity: &str| MinecraftInstr::SummonEntity {
+			entity: entity.into(),
+			pos: Coordinates::origin(),
+			nbt: NBTCompoundTypeContents::new(),
+		};
+
+		let instr = summon("minecraft:breeze");
+		assert_eq!(
+			get_required_feature(&instr, MinecraftVersion::new(20, 4)),
+			Some(("update_1_21", "minecraft:breeze"))
+		);
+		assert_eq!(
+			get_required_feature(&instr, MinecraftVersion::new(21, 0)),
+			None
+		);
+		assert_eq!(
+			get_required_feature(&summon("zombie"), MinecraftVersion::new(20, 4)),
+			None
+		);
+		assert!(is_feature_enabled(
+			&["minecraft:update_1_21".into()],
+			"update_1_21"
+		));
+	}
+}
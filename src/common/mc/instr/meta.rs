@@ -0,0 +1,294 @@
+use crate::common::mc::bossbar::BossbarOption;
+use crate::common::mc::version::MinecraftVersion;
+use crate::passes::opt::modifiers::ModifierContext;
+
+use super::MinecraftInstr;
+
+/// Facts about what an instruction does. Passes and codegen should use these
+/// instead of matching on instructions themselves, so that they all agree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstrMeta {
+	/// The name of the command that the instruction generates
+	pub command: &'static str,
+	/// The first version that has the command, if it was added after the
+	/// oldest version that packs can target
+	pub since: Option<MinecraftVersion>,
+	/// What the instruction can change
+	pub writes: InstrWrites,
+	/// Whether running the instruction multiple times in a row does
+	/// the same thing as running it once
+	pub idempotent: bool,
+	/// The parts of the execution context that the instruction reads itself,
+	/// apart from the ones that its arguments depend on
+	pub reads: &'static [ModifierContext],
+	/// What the instruction does with the entities that it targets
+	pub targets: TargetUse,
+}
+
+impl InstrMeta {
+	const fn new(command: &'static str, writes: InstrWrites) -> Self {
+		Self {
+			command,
+			since: None,
+			writes,
+			// Reading something again gives the same result
+			idempotent: matches!(writes, InstrWrites::Nothing),
+			reads: &[],
+			targets: TargetUse::Loaded,
+		}
+	}
+
+	const fn since(mut self, minor: u8, patch: u8) -> Self {
+		self.since = Some(MinecraftVersion::new(minor, patch));
+		self
+	}
+
+	const fn idempotent(mut self) -> Self {
+		self.idempotent = true;
+		self
+	}
+
+	const fn reads(mut self, reads: &'static [ModifierContext]) -> Self {
+		self.reads = reads;
+		self
+	}
+
+	const fn targets(mut self, targets: TargetUse) -> Self {
+		self.targets = targets;
+		self
+	}
+
+	/// Checks if the instruction only reads the game, so it can be removed
+	/// if nothing uses its result
+	pub fn is_pure(&self) -> bool {
+		self.writes == InstrWrites::Nothing
+	}
+}
+
+/// The parts of the game that an instruction can change, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InstrWrites {
+	/// Only reads the game, like a query
+	Nothing,
+	/// Sends messages or sounds to players
+	Output,
+	/// Changes scores, tags, settings, or other state that doesn't
+	/// affect where entities and blocks are
+	State,
+	/// Can create, remove, or move entities, or change blocks
+	World,
+}
+
+/// What an instruction does with the entities that it targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetUse {
+	/// Only affects loaded entities, so a selector for a single player
+	/// is the same as their name
+	Loaded,
+	/// Kills or moves loaded entities
+	Moved,
+	/// Takes names that don't have to be loaded, like players that are
+	/// offline or fake score holders
+	Names,
+}
+
+impl TargetUse {
+	pub fn is_loaded(&self) -> bool {
+		matches!(self, Self::Loaded | Self::Moved)
+	}
+}
+
+impl MinecraftInstr {
+	/// Gets the metadata for this instruction
+	pub fn get_meta(&self) -> InstrMeta {
+		use InstrWrites::{Nothing, Output, State, World};
+		use ModifierContext::{Dimension, Executor, Position};
+		use TargetUse::{Moved, Names};
+
+		match self {
+			Self::SetGameruleBool { .. } | Self::SetGameruleInt { .. } => {
+				InstrMeta::new("gamerule", State).idempotent()
+			}
+			Self::GetGamerule { .. } => InstrMeta::new("gamerule", Nothing),
+			// Listings are run for the feedback they give, unlike queries that
+			// are run to store their result
+			Self::Locate { .. } => InstrMeta::new("locate", Output).reads(&[Position, Dimension]),
+			// Messages are sent with the name of the executor
+			Self::Say { .. } => InstrMeta::new("say", Output).reads(&[Executor]),
+			Self::Tell { .. } => InstrMeta::new("tell", Output).reads(&[Executor]),
+			Self::Me { .. } => InstrMeta::new("me", Output).reads(&[Executor]),
+			Self::TellRaw { .. } => InstrMeta::new("tellraw", Output),
+			Self::Title { .. } | Self::Subtitle { .. } | Self::ActionBar { .. } => {
+				InstrMeta::new("title", Output)
+			}
+			Self::TeamMessage { .. } => InstrMeta::new("teammsg", Output)
+				.since(14, 0)
+				.reads(&[Executor]),
+			Self::ListPlayers | Self::ListPlayerUUIDs => InstrMeta::new("list", Output),
+			Self::StopServer => InstrMeta::new("stop", State),
+			Self::BanPlayers { .. } => InstrMeta::new("ban", World).targets(Names),
+			Self::BanIP { .. } => InstrMeta::new("ban-ip", World),
+			Self::PardonPlayers { .. } => InstrMeta::new("pardon", State).targets(Names),
+			Self::PardonIP { .. } => InstrMeta::new("pardon-ip", State),
+			Self::Banlist => InstrMeta::new("banlist", Output),
+			Self::Op { .. } => InstrMeta::new("op", State).targets(Names),
+			Self::Deop { .. } => InstrMeta::new("deop", State).targets(Names),
+			Self::WhitelistAdd { .. } | Self::WhitelistRemove { .. } => {
+				InstrMeta::new("whitelist", State).targets(Names)
+			}
+			Self::WhitelistReload => InstrMeta::new("whitelist", State),
+			Self::WhitelistOn | Self::WhitelistOff => {
+				InstrMeta::new("whitelist", State).idempotent()
+			}
+			Self::WhitelistList => InstrMeta::new("whitelist", Output),
+			Self::Kick { .. } => InstrMeta::new("kick", World),
+			Self::Kill { .. } => InstrMeta::new("kill", World).targets(Moved),
+			Self::SetXP { .. } | Self::AddXP { .. } => InstrMeta::new("xp", State),
+			Self::GetXP { .. } => InstrMeta::new("xp", Nothing),
+			Self::Enchant { .. } => InstrMeta::new("enchant", State),
+			Self::Seed => InstrMeta::new("seed", Output),
+			Self::GetDifficulty => InstrMeta::new("difficulty", Nothing),
+			Self::SetDifficulty { .. } => InstrMeta::new("difficulty", State).idempotent(),
+			Self::Reload => InstrMeta::new("reload", World),
+			Self::StopSound => InstrMeta::new("stopsound", Output),
+			Self::SetBlock { .. } => InstrMeta::new("setblock", World),
+			Self::Fill { .. } => InstrMeta::new("fill", World),
+			Self::Clone { .. } => InstrMeta::new("clone", World).reads(&[Position]),
+			Self::SetWeather { .. } => InstrMeta::new("weather", State).reads(&[Dimension]),
+			Self::AddTime { .. } => InstrMeta::new("time", State),
+			Self::SetTime { .. } | Self::SetTimePreset { .. } => {
+				InstrMeta::new("time", State).idempotent()
+			}
+			Self::GetTime { .. } => InstrMeta::new("time", Nothing),
+			Self::ScheduleFunction { .. } => InstrMeta::new("schedule", State),
+			Self::ScheduleClear { .. } => InstrMeta::new("schedule", State).since(15, 0),
+			Self::AddTag { .. } | Self::RemoveTag { .. } => InstrMeta::new("tag", State),
+			Self::ListTags { .. } => InstrMeta::new("tag", Output),
+			Self::RideMount { .. } | Self::RideDismount { .. } => {
+				InstrMeta::new("ride", World).since(19, 4)
+			}
+			Self::FillBiome { .. } => InstrMeta::new("fillbiome", World).since(19, 3),
+			Self::Spectate { .. } => InstrMeta::new("spectate", World).since(15, 0),
+			Self::SpectateStop => InstrMeta::new("spectate", State)
+				.since(15, 0)
+				.reads(&[Executor]),
+			Self::SetGamemode { .. } => InstrMeta::new("gamemode", State),
+			Self::DefaultGamemode { .. } => InstrMeta::new("defaultgamemode", State).idempotent(),
+			Self::TeleportToEntity { .. }
+			| Self::TeleportToLocation { .. }
+			| Self::TeleportWithRotation { .. }
+			| Self::TeleportFacingLocation { .. }
+			| Self::TeleportFacingEntity { .. } => InstrMeta::new("tp", World).targets(Moved),
+			Self::GiveItem { .. } => InstrMeta::new("give", World),
+			Self::AddScoreboardObjective { .. } | Self::RemoveScoreboardObjective { .. } => {
+				InstrMeta::new("scoreboard", State)
+			}
+			Self::ListScoreboardObjectives => InstrMeta::new("scoreboard", Output),
+			Self::SetObjectiveDisplayName { .. } => {
+				InstrMeta::new("scoreboard", State).idempotent()
+			}
+			Self::SetObjectiveNumberFormat { .. } => InstrMeta::new("scoreboard", State)
+				.since(20, 3)
+				.idempotent(),
+			Self::AddTeam { .. } | Self::RemoveTeam { .. } => InstrMeta::new("team", State),
+			// Teams can have score holders that aren't entities
+			Self::JoinTeam { .. } | Self::LeaveTeam { .. } => {
+				InstrMeta::new("team", State).targets(Names)
+			}
+			Self::EmptyTeam { .. } | Self::ModifyTeam { .. } => {
+				InstrMeta::new("team", State).idempotent()
+			}
+			Self::ListTeams { .. } => InstrMeta::new("team", Output),
+			Self::AddBossbar { .. } | Self::RemoveBossbar { .. } => {
+				InstrMeta::new("bossbar", State)
+			}
+			Self::SetBossbar {
+				option: BossbarOption::Players(Some(..)),
+				..
+			} => InstrMeta::new("bossbar", State),
+			Self::SetBossbar { .. } => InstrMeta::new("bossbar", State).idempotent(),
+			Self::GetBossbar { .. } => InstrMeta::new("bossbar", Nothing),
+			Self::TriggerAdd { .. } | Self::TriggerSet { .. } => {
+				InstrMeta::new("trigger", State).reads(&[Executor])
+			}
+			Self::GetAttribute { .. }
+			| Self::GetAttributeBase { .. }
+			| Self::GetAttributeModifier { .. } => InstrMeta::new("attribute", Nothing).since(16, 0),
+			// Attributes like the scale of an entity change where it is
+			Self::SetAttributeBase { .. }
+			| Self::AddAttributeModifier { .. }
+			| Self::RemoveAttributeModifier { .. } => InstrMeta::new("attribute", World).since(16, 0),
+			// Changing the enabled packs reloads the game
+			Self::DisableDatapack { .. } | Self::EnableDatapack { .. } => {
+				InstrMeta::new("datapack", World).idempotent()
+			}
+			Self::SetDatapackPriority { .. } | Self::SetDatapackOrder { .. } => {
+				InstrMeta::new("datapack", World)
+			}
+			Self::ListDatapacks { .. } => InstrMeta::new("datapack", Output),
+			Self::SummonEntity { .. } => InstrMeta::new("summon", World),
+			Self::SetWorldSpawn { .. } => InstrMeta::new("setworldspawn", State),
+			Self::ClearItems { .. } => InstrMeta::new("clear", State),
+			Self::SetSpawnpoint { .. } => InstrMeta::new("spawnpoint", State),
+			Self::SpreadPlayers { .. } => InstrMeta::new("spreadplayers", World).targets(Moved),
+			Self::ClearEffect { .. } | Self::GiveEffect { .. } => InstrMeta::new("effect", State),
+			Self::LootGive { .. }
+			| Self::LootInsert { .. }
+			| Self::LootSpawn { .. }
+			| Self::LootReplaceBlock { .. }
+			| Self::LootReplaceEntity { .. } => InstrMeta::new("loot", World).since(14, 0),
+			Self::ItemModify { .. }
+			| Self::ItemReplaceWith { .. }
+			| Self::ItemReplaceFrom { .. } => InstrMeta::new("item", World).since(17, 0),
+			Self::PlaceFeature { .. } | Self::PlaceJigsaw { .. } | Self::PlaceStructure { .. } => {
+				InstrMeta::new("place", World).since(19, 0)
+			}
+			Self::WorldBorderAdd { .. }
+			| Self::WorldBorderSet { .. }
+			| Self::WorldBorderCenter { .. } => InstrMeta::new("worldborder", State),
+			Self::WorldBorderDamage { .. }
+			| Self::WorldBorderBuffer { .. }
+			| Self::WorldBorderWarningDistance { .. }
+			| Self::WorldBorderWarningTime { .. } => InstrMeta::new("worldborder", State).idempotent(),
+			Self::WorldBorderGet => InstrMeta::new("worldborder", Nothing),
+			// Random numbers from a sequence move it forward
+			Self::RandomValue { .. } => InstrMeta::new("random", State).since(20, 2),
+			// Rolls are announced with the name of the executor
+			Self::RandomRoll { .. } => InstrMeta::new("random", State)
+				.since(20, 2)
+				.reads(&[Executor]),
+			Self::PlaySound { .. } => InstrMeta::new("playsound", Output),
+			Self::Particle { .. } => InstrMeta::new("particle", Output),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::mc::EntityTarget;
+
+	#[test]
+	fn test_instr_meta() {
+		let target = EntityTarget::Player("Player".into());
+		let meta = MinecraftInstr::RideDismount { target }.get_meta();
+		assert_eq!(meta.command, "ride");
+		assert_eq!(meta.since, Some(MinecraftVersion::new(19, 4)));
+		assert_eq!(meta.writes, InstrWrites::World);
+
+		let meta = MinecraftInstr::GetDifficulty.get_meta();
+		assert!(meta.is_pure());
+		assert!(meta.idempotent);
+		assert_eq!(meta.since, None);
+		assert!(!MinecraftInstr::Seed.get_meta().is_pure());
+
+		let meta = MinecraftInstr::Say {
+			message: "hello".into(),
+		}
+		.get_meta();
+		assert_eq!(meta.writes, InstrWrites::Output);
+		assert!(!meta.idempotent);
+		assert_eq!(meta.reads, [ModifierContext::Executor]);
+	}
+}
@@ -1,15 +1,22 @@
+pub mod meta;
+
 use std::fmt::Debug;
 
+use crate::common::range::IntRange;
+use crate::common::reg::{GetUsedLocals, GetUsedRegs, Local};
 use crate::common::ty::Float;
 use crate::common::{ty::NBTCompoundTypeContents, Identifier, ResourceLocation};
 
 use super::block::{CloneData, FillBiomeData, FillData, SetBlockData};
+use super::bossbar::{BossbarOption, BossbarQuery};
 use super::entity::{AttributeType, EffectDuration, UUID};
 use super::item::LootSource;
 use super::item::{ItemData, ItemModifyLocation};
+use super::particle::{ParticleData, ParticleMode};
 use super::pos::{Angle, DoubleCoordinates, DoubleCoordinates2D, IntCoordinates};
-use super::scoreboard_and_teams::Criterion;
-use super::time::{Time, TimePreset, TimeQuery};
+use super::scoreboard_and_teams::{Criterion, NumberFormat, TeamOption};
+use super::text::TextComponent;
+use super::time::{ScheduleMode, Time, TimePreset, TimeQuery};
 use super::{
 	DatapackListMode, DatapackOrder, DatapackPriority, Difficulty, EntityTarget, Gamemode,
 	Location, SoundSource, Weather, XPValue,
@@ -42,6 +49,22 @@ pub enum MinecraftInstr {
 	Me {
 		message: String,
 	},
+	TellRaw {
+		target: EntityTarget,
+		message: TextComponent,
+	},
+	Title {
+		target: EntityTarget,
+		title: TextComponent,
+	},
+	Subtitle {
+		target: EntityTarget,
+		subtitle: TextComponent,
+	},
+	ActionBar {
+		target: EntityTarget,
+		text: TextComponent,
+	},
 	TeamMessage {
 		message: String,
 	},
@@ -136,6 +159,14 @@ pub enum MinecraftInstr {
 	GetTime {
 		query: TimeQuery,
 	},
+	ScheduleFunction {
+		function: ResourceLocation,
+		time: Time,
+		mode: ScheduleMode,
+	},
+	ScheduleClear {
+		function: ResourceLocation,
+	},
 	AddTag {
 		target: EntityTarget,
 		tag: Identifier,
@@ -206,6 +237,56 @@ pub enum MinecraftInstr {
 		objective: String,
 	},
 	ListScoreboardObjectives,
+	SetObjectiveDisplayName {
+		objective: String,
+		name: TextComponent,
+	},
+	/// Sets the number format of an objective, or resets it when the format is None
+	SetObjectiveNumberFormat {
+		objective: String,
+		format: Option<NumberFormat>,
+	},
+	AddTeam {
+		team: String,
+		display_name: Option<TextComponent>,
+	},
+	RemoveTeam {
+		team: String,
+	},
+	/// Removes all of the members of a team
+	EmptyTeam {
+		team: String,
+	},
+	JoinTeam {
+		team: String,
+		target: EntityTarget,
+	},
+	LeaveTeam {
+		target: EntityTarget,
+	},
+	/// Lists all of the teams, or the members of one team
+	ListTeams {
+		team: Option<String>,
+	},
+	ModifyTeam {
+		team: String,
+		option: TeamOption,
+	},
+	AddBossbar {
+		id: ResourceLocation,
+		name: TextComponent,
+	},
+	RemoveBossbar {
+		id: ResourceLocation,
+	},
+	SetBossbar {
+		id: ResourceLocation,
+		option: BossbarOption,
+	},
+	GetBossbar {
+		id: ResourceLocation,
+		query: BossbarQuery,
+	},
 	TriggerAdd {
 		objective: String,
 		amount: i32,
@@ -384,6 +465,17 @@ pub enum MinecraftInstr {
 	WorldBorderWarningTime {
 		time: i32,
 	},
+	/// Gets a random number in a range. The number is the result of the command,
+	/// so it can be stored
+	RandomValue {
+		range: IntRange,
+		sequence: Option<ResourceLocation>,
+	},
+	/// Gets a random number in a range and shows it to all players
+	RandomRoll {
+		range: IntRange,
+		sequence: Option<ResourceLocation>,
+	},
 	PlaySound {
 		sound: ResourceLocation,
 		source: SoundSource,
@@ -393,6 +485,309 @@ pub enum MinecraftInstr {
 		pitch: Float,
 		min_volume: Float,
 	},
+	/// Spawns particles, showing them to all players if there are no viewers
+	Particle {
+		particle: ParticleData,
+		pos: DoubleCoordinates,
+		delta: [f64; 3],
+		speed: f64,
+		count: i32,
+		mode: ParticleMode,
+		viewers: Option<EntityTarget>,
+	},
+}
+
+impl MinecraftInstr {
+	/// Gets the text components that this instruction writes
+	pub fn get_text_components(&self) -> Vec<&TextComponent> {
+		match self {
+			Self::TellRaw { message: text, .. }
+			| Self::Title { title: text, .. }
+			| Self::Subtitle { subtitle: text, .. }
+			| Self::ActionBar { text, .. }
+			| Self::SetObjectiveDisplayName { name: text, .. }
+			| Self::AddTeam {
+				display_name: Some(text),
+				..
+			}
+			| Self::ModifyTeam {
+				option:
+					TeamOption::DisplayName(text) | TeamOption::Prefix(text) | TeamOption::Suffix(text),
+				..
+			}
+			| Self::SetObjectiveNumberFormat {
+				format: Some(NumberFormat::Fixed(text)),
+				..
+			}
+			| Self::AddBossbar { name: text, .. }
+			| Self::SetBossbar {
+				option: BossbarOption::Name(text),
+				..
+			} => vec![text],
+			_ => Vec::new(),
+		}
+	}
+
+	/// Gets the text components that this instruction writes mutably
+	pub fn get_text_components_mut(&mut self) -> Vec<&mut TextComponent> {
+		match self {
+			Self::TellRaw { message: text, .. }
+			| Self::Title { title: text, .. }
+			| Self::Subtitle { subtitle: text, .. }
+			| Self::ActionBar { text, .. }
+			| Self::SetObjectiveDisplayName { name: text, .. }
+			| Self::AddTeam {
+				display_name: Some(text),
+				..
+			}
+			| Self::ModifyTeam {
+				option:
+					TeamOption::DisplayName(text) | TeamOption::Prefix(text) | TeamOption::Suffix(text),
+				..
+			}
+			| Self::SetObjectiveNumberFormat {
+				format: Some(NumberFormat::Fixed(text)),
+				..
+			}
+			| Self::AddBossbar { name: text, .. }
+			| Self::SetBossbar {
+				option: BossbarOption::Name(text),
+				..
+			} => vec![text],
+			_ => Vec::new(),
+		}
+	}
+
+	/// Gets the entities that this instruction acts on
+	pub fn get_targets(&self) -> Vec<&EntityTarget> {
+		match self {
+			Self::Tell { target, .. }
+			| Self::TellRaw { target, .. }
+			| Self::Title { target, .. }
+			| Self::Subtitle { target, .. }
+			| Self::ActionBar { target, .. }
+			| Self::Kill { target }
+			| Self::SetXP { target, .. }
+			| Self::AddXP { target, .. }
+			| Self::GetXP { target, .. }
+			| Self::Enchant { target, .. }
+			| Self::AddTag { target, .. }
+			| Self::RemoveTag { target, .. }
+			| Self::ListTags { target }
+			| Self::RideMount { target, .. }
+			| Self::RideDismount { target }
+			| Self::Spectate {
+				spectator: target, ..
+			}
+			| Self::SetGamemode { target, .. }
+			| Self::TeleportToEntity { source: target, .. }
+			| Self::TeleportToLocation { source: target, .. }
+			| Self::TeleportWithRotation { source: target, .. }
+			| Self::TeleportFacingLocation { source: target, .. }
+			| Self::TeleportFacingEntity { source: target, .. }
+			| Self::GiveItem { target, .. }
+			| Self::JoinTeam { target, .. }
+			| Self::LeaveTeam { target }
+			| Self::SetBossbar {
+				option: BossbarOption::Players(Some(target)),
+				..
+			}
+			| Self::GetAttribute { target, .. }
+			| Self::GetAttributeBase { target, .. }
+			| Self::SetAttributeBase { target, .. }
+			| Self::AddAttributeModifier { target, .. }
+			| Self::RemoveAttributeModifier { target, .. }
+			| Self::GetAttributeModifier { target, .. }
+			| Self::SpreadPlayers { target, .. }
+			| Self::ClearEffect { target, .. }
+			| Self::GiveEffect { target, .. }
+			| Self::LootGive { player: target, .. }
+			| Self::LootReplaceEntity { target, .. }
+			| Self::PlaySound { target, .. }
+			| Self::Particle {
+				viewers: Some(target),
+				..
+			} => vec![target],
+			Self::BanPlayers { targets, .. }
+			| Self::PardonPlayers { targets }
+			| Self::Op { targets }
+			| Self::Deop { targets }
+			| Self::WhitelistAdd { targets }
+			| Self::WhitelistRemove { targets }
+			| Self::Kick { targets, .. }
+			| Self::ClearItems { targets, .. }
+			| Self::SetSpawnpoint { targets, .. } => targets.iter().collect(),
+			_ => Vec::new(),
+		}
+	}
+
+	/// Gets the entities that this instruction acts on mutably
+	pub fn get_targets_mut(&mut self) -> Vec<&mut EntityTarget> {
+		match self {
+			Self::Tell { target, .. }
+			| Self::TellRaw { target, .. }
+			| Self::Title { target, .. }
+			| Self::Subtitle { target, .. }
+			| Self::ActionBar { target, .. }
+			| Self::Kill { target }
+			| Self::SetXP { target, .. }
+			| Self::AddXP { target, .. }
+			| Self::GetXP { target, .. }
+			| Self::Enchant { target, .. }
+			| Self::AddTag { target, .. }
+			| Self::RemoveTag { target, .. }
+			| Self::ListTags { target }
+			| Self::RideMount { target, .. }
+			| Self::RideDismount { target }
+			| Self::Spectate {
+				spectator: target, ..
+			}
+			| Self::SetGamemode { target, .. }
+			| Self::TeleportToEntity { source: target, .. }
+			| Self::TeleportToLocation { source: target, .. }
+			| Self::TeleportWithRotation { source: target, .. }
+			| Self::TeleportFacingLocation { source: target, .. }
+			| Self::TeleportFacingEntity { source: target, .. }
+			| Self::GiveItem { target, .. }
+			| Self::JoinTeam { target, .. }
+			| Self::LeaveTeam { target }
+			| Self::SetBossbar {
+				option: BossbarOption::Players(Some(target)),
+				..
+			}
+			| Self::GetAttribute { target, .. }
+			| Self::GetAttributeBase { target, .. }
+			| Self::SetAttributeBase { target, .. }
+			| Self::AddAttributeModifier { target, .. }
+			| Self::RemoveAttributeModifier { target, .. }
+			| Self::GetAttributeModifier { target, .. }
+			| Self::SpreadPlayers { target, .. }
+			| Self::ClearEffect { target, .. }
+			| Self::GiveEffect { target, .. }
+			| Self::LootGive { player: target, .. }
+			| Self::LootReplaceEntity { target, .. }
+			| Self::PlaySound { target, .. }
+			| Self::Particle {
+				viewers: Some(target),
+				..
+			} => vec![target],
+			Self::BanPlayers { targets, .. }
+			| Self::PardonPlayers { targets }
+			| Self::Op { targets }
+			| Self::Deop { targets }
+			| Self::WhitelistAdd { targets }
+			| Self::WhitelistRemove { targets }
+			| Self::Kick { targets, .. }
+			| Self::ClearItems { targets, .. }
+			| Self::SetSpawnpoint { targets, .. } => targets.iter_mut().collect(),
+			_ => Vec::new(),
+		}
+	}
+
+	/// Gets the arguments of this instruction, other than its targets and text
+	/// components, that passes need to resolve or check
+	pub fn get_operands(&self) -> Vec<InstrOperand<'_>> {
+		match self {
+			Self::SetBlock { data } => vec![InstrOperand::BlockPos(&data.pos)],
+			Self::Fill { data } => vec![
+				InstrOperand::BlockPos(&data.start),
+				InstrOperand::BlockPos(&data.end),
+			],
+			Self::FillBiome { data } => vec![
+				InstrOperand::BlockPos(&data.start),
+				InstrOperand::BlockPos(&data.end),
+			],
+			Self::Clone { data } => vec![
+				InstrOperand::BlockPos(&data.start),
+				InstrOperand::BlockPos(&data.end),
+				InstrOperand::BlockPos(&data.destination),
+			],
+			Self::SetWeather {
+				duration: Some(time),
+				..
+			}
+			| Self::AddTime { time }
+			| Self::SetTime { time }
+			| Self::ScheduleFunction { time, .. } => vec![InstrOperand::Time(time)],
+			Self::RideMount {
+				vehicle: entity, ..
+			}
+			| Self::Spectate { target: entity, .. }
+			| Self::TeleportToEntity { dest: entity, .. } => vec![InstrOperand::Entity(entity)],
+			Self::TeleportToLocation { dest, .. } => vec![InstrOperand::Pos(dest)],
+			Self::TeleportWithRotation { dest, rotation, .. } => {
+				vec![InstrOperand::Pos(dest), InstrOperand::Pos2D(rotation)]
+			}
+			Self::TeleportFacingLocation { dest, facing, .. } => {
+				vec![InstrOperand::Pos(dest), InstrOperand::Pos(facing)]
+			}
+			Self::TeleportFacingEntity { dest, facing, .. } => {
+				vec![InstrOperand::Pos(dest), InstrOperand::Entity(facing)]
+			}
+			Self::SummonEntity { pos, .. }
+			| Self::PlaySound { pos, .. }
+			| Self::Particle { pos, .. } => vec![InstrOperand::Pos(pos)],
+			Self::SetWorldSpawn { pos, .. }
+			| Self::SetSpawnpoint { pos, .. }
+			| Self::WorldBorderCenter { pos }
+			| Self::PlaceFeature { pos, .. }
+			| Self::PlaceJigsaw { pos, .. }
+			| Self::PlaceStructure { pos, .. } => vec![InstrOperand::BlockPos(pos)],
+			Self::SpreadPlayers { center, .. } => vec![InstrOperand::Pos2D(center)],
+			Self::LootGive { source, .. } | Self::LootReplaceEntity { source, .. } => {
+				vec![InstrOperand::Loot(source)]
+			}
+			Self::LootInsert { pos, source } | Self::LootReplaceBlock { pos, source, .. } => {
+				vec![InstrOperand::BlockPos(pos), InstrOperand::Loot(source)]
+			}
+			Self::LootSpawn { pos, source } => {
+				vec![InstrOperand::Pos(pos), InstrOperand::Loot(source)]
+			}
+			Self::ItemModify { location, .. } | Self::ItemReplaceWith { location, .. } => {
+				vec![InstrOperand::Item(location)]
+			}
+			Self::ItemReplaceFrom { dest, source, .. } => {
+				vec![InstrOperand::Item(dest), InstrOperand::Item(source)]
+			}
+			_ => Vec::new(),
+		}
+	}
+
+	pub fn get_used_regs_mut(&mut self) -> Vec<&mut Identifier> {
+		self.get_text_components_mut()
+			.into_iter()
+			.flat_map(|x| x.get_used_regs_mut())
+			.collect()
+	}
+}
+
+/// An argument of an instruction
+#[derive(Debug, Clone, Copy)]
+pub enum InstrOperand<'a> {
+	Entity(&'a EntityTarget),
+	Pos(&'a DoubleCoordinates),
+	BlockPos(&'a IntCoordinates),
+	Pos2D(&'a DoubleCoordinates2D),
+	Item(&'a ItemModifyLocation),
+	Loot(&'a LootSource),
+	Time(&'a Time),
+}
+
+/// Instructions can only use registers through the scores and NBT in their text components
+impl GetUsedRegs for MinecraftInstr {
+	fn append_used_regs<'a>(&'a self, regs: &mut Vec<&'a Identifier>) {
+		for text in self.get_text_components() {
+			text.append_used_regs(regs);
+		}
+	}
+}
+
+impl GetUsedLocals for MinecraftInstr {
+	fn append_used_locals<'a>(&'a self, locals: &mut Vec<&'a Local>) {
+		for text in self.get_text_components() {
+			text.append_used_locals(locals);
+		}
+	}
 }
 
 impl Debug for MinecraftInstr {
@@ -401,6 +796,10 @@ impl Debug for MinecraftInstr {
 			Self::Say { message } => format!("say {message}"),
 			Self::Tell { target, message } => format!("tell {target:?} {message}"),
 			Self::Me { message } => format!("me {message}"),
+			Self::TellRaw { target, message } => format!("tellraw {target:?} {message:?}"),
+			Self::Title { target, title } => format!("title {target:?} {title:?}"),
+			Self::Subtitle { target, subtitle } => format!("subtitle {target:?} {subtitle:?}"),
+			Self::ActionBar { target, text } => format!("actionbar {target:?} {text:?}"),
 			Self::TeamMessage { message } => format!("tm {message}"),
 			Self::Banlist => "banl".into(),
 			Self::BanPlayers { targets, reason } => format!("ban {targets:?} {reason:?}"),
@@ -442,6 +841,12 @@ impl Debug for MinecraftInstr {
 			Self::SetTime { time } => format!("tims {time:?}"),
 			Self::SetTimePreset { time } => format!("timp {time:?}"),
 			Self::GetTime { query } => format!("timg {query:?}"),
+			Self::ScheduleFunction {
+				function,
+				time,
+				mode,
+			} => format!("sch {function} {time:?} {mode:?}"),
+			Self::ScheduleClear { function } => format!("schc {function}"),
 			Self::AddTag { target, tag } => format!("taga {target:?} {tag}"),
 			Self::RemoveTag { target, tag } => format!("tagr {target:?} {tag}"),
 			Self::ListTags { target } => format!("tagl {target:?}"),
@@ -487,6 +892,23 @@ impl Debug for MinecraftInstr {
 			}
 			Self::RemoveScoreboardObjective { objective } => format!("sbor {objective}"),
 			Self::ListScoreboardObjectives => "sbol".into(),
+			Self::SetObjectiveDisplayName { objective, name } => {
+				format!("sbodn {objective} {name:?}")
+			}
+			Self::SetObjectiveNumberFormat { objective, format } => {
+				format!("sbonf {objective} {format:?}")
+			}
+			Self::AddTeam { team, display_name } => format!("teama {team} {display_name:?}"),
+			Self::RemoveTeam { team } => format!("teamr {team}"),
+			Self::EmptyTeam { team } => format!("teame {team}"),
+			Self::JoinTeam { team, target } => format!("teamj {team} {target:?}"),
+			Self::LeaveTeam { target } => format!("teaml {target:?}"),
+			Self::ListTeams { team } => format!("teamls {team:?}"),
+			Self::ModifyTeam { team, option } => format!("teamm {team} {option:?}"),
+			Self::AddBossbar { id, name } => format!("bba {id} {name:?}"),
+			Self::RemoveBossbar { id } => format!("bbr {id}"),
+			Self::SetBossbar { id, option } => format!("bbs {id} {option:?}"),
+			Self::GetBossbar { id, query } => format!("bbg {id} {query:?}"),
 			Self::TriggerAdd { objective, amount } => format!("trga {objective}, {amount}"),
 			Self::TriggerSet { objective, amount } => format!("trgs {objective}, {amount}"),
 			Self::GetAttribute {
@@ -606,6 +1028,8 @@ impl Debug for MinecraftInstr {
 			Self::WorldBorderBuffer { buffer } => format!("wbb {buffer}"),
 			Self::WorldBorderWarningDistance { dist } => format!("wbwd {dist}"),
 			Self::WorldBorderWarningTime { time } => format!("wbwt {time}"),
+			Self::RandomValue { range, sequence } => format!("rndv {range} {sequence:?}"),
+			Self::RandomRoll { range, sequence } => format!("rndr {range} {sequence:?}"),
 			Self::PlaySound {
 				sound,
 				source,
@@ -617,7 +1041,17 @@ impl Debug for MinecraftInstr {
 			 } => {
 				format!("ply {sound} {source:?} {target:?} {pos:?} {volume} {pitch} {min_volume}")
 			}
+			Self::Particle {
+				particle,
+				pos,
+				delta,
+				speed,
+				count,
+				mode,
+				viewers,
+			} => format!("ptc {particle:?} {pos:?} {delta:?} {speed} {count} {mode:?} {viewers:?}"),
 		};
 		write!(f, "{text}")
 	}
 }
+
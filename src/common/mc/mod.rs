@@ -1,15 +1,22 @@
 pub mod block;
+pub mod bossbar;
 pub mod entity;
+pub mod feature;
 pub mod instr;
 pub mod item;
 pub mod modifier;
+pub mod particle;
 pub mod pos;
+pub mod region;
 pub mod scoreboard_and_teams;
+pub mod text;
 pub mod time;
+pub mod version;
 
 use std::fmt::{Debug, Display};
 
 use crate::common::Identifier;
+use crate::output::codegen::Codegen;
 
 use self::pos::IntCoordinates;
 
@@ -19,7 +26,7 @@ use super::ResourceLocation;
 
 #[derive(Clone, PartialEq)]
 pub enum EntityTarget {
-	Player(String),
+	Player(Identifier),
 	Selector(TargetSelector),
 }
 
@@ -28,6 +35,11 @@ impl EntityTarget {
 		matches!(self, EntityTarget::Selector(sel) if sel.is_blank_this())
 	}
 
+	/// Checks if this target can never select any entities
+	pub fn is_impossible(&self) -> bool {
+		matches!(self, Self::Selector(sel) if sel.is_impossible())
+	}
+
 	pub fn is_value_eq(&self, other: &Self) -> bool {
 		matches!((self, other), (Self::Player(l), Self::Player(r)) if l == r)
 			|| matches!((self, other), (Self::Selector(l), Self::Selector(r)) if l.is_value_eq(r))
@@ -73,7 +85,7 @@ impl Debug for Score {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq, Default)]
+#[derive(Clone, PartialEq, Eq, Default, Codegen)]
 pub enum DataPath {
 	#[default]
 	This,
@@ -120,7 +132,8 @@ impl FullDataLocation {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Codegen)]
+#[codegen(display)]
 pub enum XPValue {
 	Points,
 	Levels,
@@ -149,7 +162,8 @@ impl Display for XPValue {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Codegen)]
+#[codegen(display)]
 pub enum Difficulty {
 	Peaceful,
 	Easy,
@@ -182,9 +196,20 @@ impl Difficulty {
 			_ => None,
 		}
 	}
+
+	/// Gets the number that the difficulty query command returns for this difficulty
+	pub fn get_id(&self) -> i32 {
+		match self {
+			Self::Peaceful => 0,
+			Self::Easy => 1,
+			Self::Normal => 2,
+			Self::Hard => 3,
+		}
+	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Codegen)]
+#[codegen(display)]
 pub enum Gamemode {
 	Survival,
 	Creative,
@@ -192,6 +217,18 @@ pub enum Gamemode {
 	Spectator,
 }
 
+impl Gamemode {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"survival" => Some(Self::Survival),
+			"creative" => Some(Self::Creative),
+			"adventure" => Some(Self::Adventure),
+			"spectator" => Some(Self::Spectator),
+			_ => None,
+		}
+	}
+}
+
 impl Display for Gamemode {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(
@@ -207,7 +244,7 @@ impl Display for Gamemode {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum Heightmap {
 	WorldSurface,
 	MotionBlocking,
@@ -226,7 +263,8 @@ impl Debug for Heightmap {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Codegen)]
+#[codegen(display)]
 pub enum Weather {
 	Clear,
 	Rain,
@@ -247,7 +285,7 @@ impl Display for Weather {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum DatapackPriority {
 	First,
 	Last,
@@ -262,7 +300,7 @@ impl Debug for DatapackPriority {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum DatapackOrder {
 	Before,
 	After,
@@ -277,7 +315,7 @@ impl Debug for DatapackOrder {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum DatapackListMode {
 	All,
 	Available,
@@ -326,7 +364,7 @@ impl Debug for Location {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum SoundSource {
 	Master,
 	Music,
@@ -7,8 +7,10 @@ use crate::common::reg::{GetUsedLocals, GetUsedRegs, Local};
 use crate::common::ty::{DataType, Double, NBTType, NBTTypeContents};
 use crate::common::val::{MutableNBTValue, MutableScoreValue, MutableValue, ScoreValue};
 use crate::common::{RegisterList, ResourceLocationTag};
+use crate::output::codegen::Codegen;
 
 use super::block::BlockFilter;
+use super::item::ItemModifyLocation;
 use super::pos::{DoubleCoordinates, DoubleCoordinates2D};
 use super::{EntityTarget, FullDataLocation, Heightmap, IntCoordinates, Score};
 
@@ -175,7 +177,9 @@ impl Debug for MIRModifier {
 
 #[derive(Clone, PartialEq)]
 pub enum StoreModLocation {
-	Local(Local, Double),
+	/// A register or other local. The type is usually inferred from the
+	/// local, but can be written out, in which case it has to match
+	Local(Local, Option<StoreDataType>, Double),
 	Score(Score),
 	Data(FullDataLocation, StoreDataType, Double),
 	Bossbar(ResourceLocation, StoreBossbarMode),
@@ -198,7 +202,7 @@ impl StoreModLocation {
 	}
 	pub fn from_mut_score_val(val: &MutableScoreValue) -> anyhow::Result<Self> {
 		match val {
-			MutableScoreValue::Local(loc) => Ok(Self::Local(loc.clone(), 1.0)),
+			MutableScoreValue::Local(loc) => Ok(Self::Local(loc.clone(), None, 1.0)),
 			MutableScoreValue::Score(score) => Ok(Self::Score(score.clone())),
 		}
 	}
@@ -210,7 +214,7 @@ impl StoreModLocation {
 		scale: Double,
 	) -> anyhow::Result<Self> {
 		match val {
-			MutableNBTValue::Local(loc) => Ok(Self::Local(loc.clone(), scale)),
+			MutableNBTValue::Local(loc) => Ok(Self::Local(loc.clone(), None, scale)),
 			MutableNBTValue::Data(data) => Ok(Self::Data(data.clone(), ty, scale)),
 			_ => bail!("Unsupported storage location"),
 		}
@@ -249,7 +253,8 @@ impl GetUsedLocals for StoreModLocation {
 impl Debug for StoreModLocation {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
-			Self::Local(loc, scale) => write!(f, "{loc:?} {scale}"),
+			Self::Local(loc, Some(ty), scale) => write!(f, "{loc:?} {ty:?} {scale}"),
+			Self::Local(loc, None, scale) => write!(f, "{loc:?} {scale}"),
 			Self::Score(score) => write!(f, "{score:?}"),
 			Self::Data(data, ty, scale) => write!(f, "{data:?} {ty:?} {scale}"),
 			Self::Bossbar(bar, mode) => write!(f, "bb {bar} {mode:?}"),
@@ -257,7 +262,7 @@ impl Debug for StoreModLocation {
 	}
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Codegen)]
 pub enum StoreDataType {
 	Byte,
 	Short,
@@ -309,7 +314,7 @@ impl Debug for StoreBossbarMode {
 	}
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Codegen)]
 pub enum AnchorLocation {
 	Eyes,
 	Feet,
@@ -336,7 +341,7 @@ pub enum EntityRelation {
 	Vehicle,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Codegen)]
 pub struct AlignAxes {
 	pub x: bool,
 	pub y: bool,
@@ -370,6 +375,8 @@ pub enum IfModCondition {
 	DataExists(MutableNBTValue),
 	DataEquals(MutableNBTValue, NBTTypeContents),
 	Block(IntCoordinates, BlockFilter),
+	/// Items in the slots of a block or entity that match an item predicate
+	Items(ItemModifyLocation, String, String),
 	Const(bool),
 }
 
@@ -399,6 +406,7 @@ impl GetUsedRegs for IfModCondition {
 			| Self::Dimension(..)
 			| Self::Loaded(..)
 			| Self::Block(..)
+			| Self::Items(..)
 			| Self::Const(..) => {}
 		}
 	}
@@ -426,6 +434,7 @@ impl GetUsedLocals for IfModCondition {
 			| Self::Dimension(..)
 			| Self::Loaded(..)
 			| Self::Block(..)
+			| Self::Items(..)
 			| Self::Const(..) => {}
 		}
 	}
@@ -444,6 +453,7 @@ impl Debug for IfModCondition {
 			Self::DataExists(loc) => write!(f, "data {loc:?}"),
 			Self::DataEquals(l, r) => write!(f, "deq {l:?} {r:?}"),
 			Self::Block(loc, block) => write!(f, "blo {loc:?} {block:?}"),
+			Self::Items(loc, slot, item) => write!(f, "itm {loc:?} {slot} {item}"),
 			Self::Const(val) => write!(f, "const {val}"),
 		}
 	}
@@ -0,0 +1,133 @@
+use std::fmt::Debug;
+
+use crate::common::ResourceLocation;
+use crate::output::codegen::t::macros::cgwrite;
+use crate::output::codegen::util::FloatCG;
+use crate::output::codegen::{Codegen, CodegenBlockCx};
+
+use super::block::BlockData;
+use super::item::ItemData;
+
+/// A particle type along with the options that it takes
+#[derive(Clone, PartialEq)]
+pub enum ParticleData {
+	/// A particle without any options
+	Simple(ResourceLocation),
+	Dust {
+		color: [f64; 3],
+		size: f64,
+	},
+	DustColorTransition {
+		from: [f64; 3],
+		to: [f64; 3],
+		size: f64,
+	},
+	Block(BlockData),
+	BlockMarker(BlockData),
+	FallingDust(BlockData),
+	Item(ItemData),
+}
+
+impl Debug for ParticleData {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Simple(particle) => write!(f, "{particle}"),
+			Self::Dust { color, size } => write!(f, "dust {color:?} {size}"),
+			Self::DustColorTransition { from, to, size } => {
+				write!(f, "dust_color_transition {from:?} {to:?} {size}")
+			}
+			Self::Block(block) => write!(f, "block {block:?}"),
+			Self::BlockMarker(block) => write!(f, "block_marker {block:?}"),
+			Self::FallingDust(block) => write!(f, "falling_dust {block:?}"),
+			Self::Item(item) => write!(f, "item {item:?}"),
+		}
+	}
+}
+
+impl Codegen for ParticleData {
+	fn gen_writer<F>(&self, f: &mut F, cbcx: &mut CodegenBlockCx) -> anyhow::Result<()>
+	where
+		F: std::fmt::Write,
+	{
+		match self {
+			Self::Simple(particle) => cgwrite!(f, cbcx, particle)?,
+			Self::Dust { color, size } => {
+				cgwrite!(f, cbcx, "dust ", ColorCG(color), " ", float_cg(*size))?
+			}
+			Self::DustColorTransition { from, to, size } => cgwrite!(
+				f,
+				cbcx,
+				"dust_color_transition ",
+				ColorCG(from),
+				" ",
+				float_cg(*size),
+				" ",
+				ColorCG(to)
+			)?,
+			Self::Block(block) => cgwrite!(f, cbcx, "block ", block)?,
+			Self::BlockMarker(block) => cgwrite!(f, cbcx, "block_marker ", block)?,
+			Self::FallingDust(block) => cgwrite!(f, cbcx, "falling_dust ", block)?,
+			Self::Item(item) => cgwrite!(f, cbcx, "item ", item)?,
+		}
+
+		Ok(())
+	}
+}
+
+/// Whether a particle is shown to players with their particle setting turned down
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ParticleMode {
+	Normal,
+	Force,
+}
+
+impl ParticleMode {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"normal" => Some(Self::Normal),
+			"force" => Some(Self::Force),
+			_ => None,
+		}
+	}
+}
+
+impl Debug for ParticleMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Self::Normal => "normal",
+				Self::Force => "force",
+			}
+		)
+	}
+}
+
+impl Codegen for ParticleMode {
+	fn gen_writer<F>(&self, f: &mut F, cbcx: &mut CodegenBlockCx) -> anyhow::Result<()>
+	where
+		F: std::fmt::Write,
+	{
+		let _ = cbcx;
+		write!(f, "{self:?}")?;
+		Ok(())
+	}
+}
+
+/// Codegen for the three space-separated components of a color
+struct ColorCG<'a>(&'a [f64; 3]);
+
+impl Codegen for ColorCG<'_> {
+	fn gen_writer<F>(&self, f: &mut F, cbcx: &mut CodegenBlockCx) -> anyhow::Result<()>
+	where
+		F: std::fmt::Write,
+	{
+		let [r, g, b] = *self.0;
+		cgwrite!(f, cbcx, float_cg(r), " ", float_cg(g), " ", float_cg(b))
+	}
+}
+
+fn float_cg(num: f64) -> FloatCG {
+	FloatCG(num, false, true, true)
+}
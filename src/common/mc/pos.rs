@@ -154,7 +154,7 @@ impl<T: Debug + Num> Debug for AbsOrRelCoord<T> {
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Axis {
 	X,
 	Y,
@@ -0,0 +1,146 @@
+use super::pos::{AbsOrRelCoord, Coordinates, IntCoordinates};
+
+/// The most blocks that a single fill or clone command can affect
+pub const MAX_REGION_VOLUME: u64 = 32768;
+
+/// A box of blocks with corners that are known at compile time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+	min: [i64; 3],
+	max: [i64; 3],
+	/// Which axes are relative to the execution position
+	rel: [bool; 3],
+}
+
+impl Region {
+	/// Gets the region between two corners, which can only be known
+	/// if both corners use the same kind of coordinate on each axis
+	pub fn from_corners(start: &IntCoordinates, end: &IntCoordinates) -> Option<Self> {
+		let (Coordinates::XYZ(x1, y1, z1), Coordinates::XYZ(x2, y2, z2)) = (start, end) else {
+			return None;
+		};
+		let mut out = Self {
+			min: [0; 3],
+			max: [0; 3],
+			rel: [false; 3],
+		};
+		for (i, (a, b)) in [(x1, x2), (y1, y2), (z1, z2)].into_iter().enumerate() {
+			let (a, b, rel) = match (a, b) {
+				(AbsOrRelCoord::Abs(a), AbsOrRelCoord::Abs(b)) => (*a, *b, false),
+				(AbsOrRelCoord::Rel(a), AbsOrRelCoord::Rel(b)) => (*a, *b, true),
+				_ => return None,
+			};
+			out.min[i] = a.min(b);
+			out.max[i] = a.max(b);
+			out.rel[i] = rel;
+		}
+
+		Some(out)
+	}
+
+	/// Gets the size of the region on each axis
+	fn get_size(&self) -> [u64; 3] {
+		[0, 1, 2].map(|i| self.max[i].abs_diff(self.min[i]) + 1)
+	}
+
+	/// Gets the number of blocks in the region
+	pub fn volume(&self) -> u64 {
+		self.get_size().iter().product()
+	}
+
+	/// Splits the region into boxes that each have at most the given volume.
+	/// Boxes are whole horizontal layers where possible
+	pub fn split(&self, max_volume: u64) -> Vec<Self> {
+		let [sx, sy, sz] = self.get_size();
+		let cx = sx.min(max_volume);
+		let cz = sz.min(max_volume / cx);
+		let cy = sy.min(max_volume / (cx * cz));
+
+		let mut out = Vec::new();
+		for y in (0..sy).step_by(cy as usize) {
+			for z in (0..sz).step_by(cz as usize) {
+				for x in (0..sx).step_by(cx as usize) {
+					let start = [x, y, z];
+					let len = [cx.min(sx - x), cy.min(sy - y), cz.min(sz - z)];
+					let mut chunk = *self;
+					for i in 0..3 {
+						chunk.min[i] = self.min[i] + start[i] as i64;
+						chunk.max[i] = chunk.min[i] + len[i] as i64 - 1;
+					}
+					out.push(chunk);
+				}
+			}
+		}
+
+		out
+	}
+
+	/// Gets the corner of the region with the lowest coordinates
+	pub fn start(&self) -> IntCoordinates {
+		self.make_coords(self.min)
+	}
+
+	/// Gets the corner of the region with the highest coordinates
+	pub fn end(&self) -> IntCoordinates {
+		self.make_coords(self.max)
+	}
+
+	/// Gets how far the lowest corner of this region is from that of another
+	pub fn offset_from(&self, other: &Self) -> [i64; 3] {
+		[0, 1, 2].map(|i| self.min[i] - other.min[i])
+	}
+
+	fn make_coords(&self, coords: [i64; 3]) -> IntCoordinates {
+		let [x, y, z] = [0, 1, 2].map(|i| {
+			if self.rel[i] {
+				AbsOrRelCoord::Rel(coords[i])
+			} else {
+				AbsOrRelCoord::Abs(coords[i])
+			}
+		});
+		Coordinates::XYZ(x, y, z)
+	}
+}
+
+/// Moves coordinates by an offset, if they aren't local ones
+pub fn offset_coords(coords: &IntCoordinates, offset: [i64; 3]) -> Option<IntCoordinates> {
+	let Coordinates::XYZ(x, y, z) = coords else {
+		return None;
+	};
+	let mut axes = [x.clone(), y.clone(), z.clone()];
+	for (axis, offset) in axes.iter_mut().zip(offset) {
+		match axis {
+			AbsOrRelCoord::Abs(val) | AbsOrRelCoord::Rel(val) => *val += offset,
+		}
+	}
+	let [x, y, z] = axes;
+
+	Some(Coordinates::XYZ(x, y, z))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_region_split() {
+		let region = Region::from_corners(
+			&IntCoordinates::absolute(0, 0, 0),
+			&IntCoordinates::absolute(99, 9, 99),
+		)
+		.expect("Region should be known");
+		assert_eq!(region.volume(), 100_000);
+
+		let chunks = region.split(MAX_REGION_VOLUME);
+		assert!(chunks.iter().all(|x| x.volume() <= MAX_REGION_VOLUME));
+		assert_eq!(chunks.iter().map(Region::volume).sum::<u64>(), 100_000);
+		assert_eq!(chunks[0].start(), IntCoordinates::absolute(0, 0, 0));
+		assert_eq!(chunks[0].end(), IntCoordinates::absolute(99, 2, 99));
+
+		let mixed = Region::from_corners(
+			&IntCoordinates::absolute(0, 0, 0),
+			&IntCoordinates::relative(5, 5, 5),
+		);
+		assert_eq!(mixed, None);
+	}
+}
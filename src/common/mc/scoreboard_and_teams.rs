@@ -1,5 +1,9 @@
 use std::fmt::Debug;
 
+use crate::output::codegen::Codegen;
+
+use super::text::{TextComponent, TextStyle};
+
 /// Criterion for a scoreboard objective
 #[derive(Clone, PartialEq)]
 pub enum Criterion {
@@ -16,7 +20,25 @@ impl Debug for Criterion {
 	}
 }
 
-#[derive(Clone, PartialEq)]
+impl Criterion {
+	/// Gets the name of the objective that the compiler generates for this criterion
+	pub fn get_objective_name(&self) -> String {
+		// Characters that objectives can't contain are escaped with a dash so that
+		// different criteria never end up with the same objective
+		let mut name = String::from("_c_");
+		for x in format!("{self:?}").chars() {
+			match x {
+				'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.' | '+' => name.push(x),
+				'-' => name.push_str("--"),
+				':' => name.push_str("-."),
+				_ => name.push_str(&format!("-{:x}+", x as u32)),
+			}
+		}
+		name
+	}
+}
+
+#[derive(Clone, PartialEq, Codegen)]
 pub enum SingleCriterion {
 	Dummy,
 	Trigger,
@@ -71,3 +93,160 @@ impl SingleCriterion {
 		}
 	}
 }
+
+/// How the scores of an objective are displayed
+#[derive(Clone, PartialEq)]
+pub enum NumberFormat {
+	Blank,
+	Fixed(TextComponent),
+	Styled(TextStyle),
+}
+
+impl Debug for NumberFormat {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Blank => write!(f, "blank"),
+			Self::Fixed(component) => write!(f, "fixed {component:?}"),
+			Self::Styled(style) => write!(f, "styled {style:?}"),
+		}
+	}
+}
+
+/// A setting of a team that can be changed with `team modify`
+#[derive(Clone, PartialEq)]
+pub enum TeamOption {
+	DisplayName(TextComponent),
+	Color(String),
+	FriendlyFire(bool),
+	SeeFriendlyInvisibles(bool),
+	NametagVisibility(TeamVisibility),
+	DeathMessageVisibility(TeamVisibility),
+	CollisionRule(CollisionRule),
+	Prefix(TextComponent),
+	Suffix(TextComponent),
+}
+
+impl Debug for TeamOption {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::DisplayName(name) => write!(f, "display_name {name:?}"),
+			Self::Color(color) => write!(f, "color {color}"),
+			Self::FriendlyFire(value) => write!(f, "friendly_fire {value}"),
+			Self::SeeFriendlyInvisibles(value) => write!(f, "see_friendly_invisibles {value}"),
+			Self::NametagVisibility(value) => write!(f, "nametag_visibility {value:?}"),
+			Self::DeathMessageVisibility(value) => write!(f, "death_message_visibility {value:?}"),
+			Self::CollisionRule(value) => write!(f, "collision_rule {value:?}"),
+			Self::Prefix(prefix) => write!(f, "prefix {prefix:?}"),
+			Self::Suffix(suffix) => write!(f, "suffix {suffix:?}"),
+		}
+	}
+}
+
+/// The colors that a team can have
+pub const TEAM_COLORS: [&str; 17] = [
+	"black",
+	"dark_blue",
+	"dark_green",
+	"dark_aqua",
+	"dark_red",
+	"dark_purple",
+	"gold",
+	"gray",
+	"dark_gray",
+	"blue",
+	"green",
+	"aqua",
+	"red",
+	"light_purple",
+	"yellow",
+	"white",
+	"reset",
+];
+
+/// Who can see something about the members of a team
+#[derive(Clone, PartialEq, Eq, Codegen)]
+pub enum TeamVisibility {
+	Always,
+	Never,
+	HideForOtherTeams,
+	HideForOwnTeam,
+}
+
+impl TeamVisibility {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"always" => Some(Self::Always),
+			"never" => Some(Self::Never),
+			"hide_for_other_teams" => Some(Self::HideForOtherTeams),
+			"hide_for_own_team" => Some(Self::HideForOwnTeam),
+			_ => None,
+		}
+	}
+}
+
+impl Debug for TeamVisibility {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Always => write!(f, "always"),
+			Self::Never => write!(f, "never"),
+			Self::HideForOtherTeams => write!(f, "hideForOtherTeams"),
+			Self::HideForOwnTeam => write!(f, "hideForOwnTeam"),
+		}
+	}
+}
+
+/// Which entities the members of a team can push
+#[derive(Clone, PartialEq, Eq, Codegen)]
+pub enum CollisionRule {
+	Always,
+	Never,
+	PushOtherTeams,
+	PushOwnTeam,
+}
+
+impl CollisionRule {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"always" => Some(Self::Always),
+			"never" => Some(Self::Never),
+			"push_other_teams" => Some(Self::PushOtherTeams),
+			"push_own_team" => Some(Self::PushOwnTeam),
+			_ => None,
+		}
+	}
+}
+
+impl Debug for CollisionRule {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Always => write!(f, "always"),
+			Self::Never => write!(f, "never"),
+			Self::PushOtherTeams => write!(f, "pushOtherTeams"),
+			Self::PushOwnTeam => write!(f, "pushOwnTeam"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_criterion_objective_names() {
+		let stick = Criterion::Compound("minecraft.used:minecraft.stick".into());
+		let stick2 = Criterion::Compound("minecraft.used:minecraft:stick".into());
+		let stick3 = Criterion::Compound("minecraft.used.minecraft:stick".into());
+		assert_eq!(
+			stick.get_objective_name(),
+			"_c_minecraft.used-.minecraft.stick"
+		);
+		assert_ne!(stick.get_objective_name(), stick2.get_objective_name());
+		assert_ne!(stick.get_objective_name(), stick3.get_objective_name());
+
+		let dash = Criterion::Compound("a-.b".into());
+		let colon = Criterion::Compound("a:b".into());
+		assert_ne!(dash.get_objective_name(), colon.get_objective_name());
+		let health = Criterion::Single(SingleCriterion::Health);
+		assert_eq!(health.get_objective_name(), "_c_health");
+	}
+}
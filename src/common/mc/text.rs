@@ -0,0 +1,332 @@
+use std::fmt::Debug;
+
+use anyhow::bail;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::common::reg::{GetUsedLocals, GetUsedRegs, Local};
+use crate::common::val::{MutableNBTValue, MutableScoreValue};
+use crate::common::Identifier;
+
+/// A JSON text component
+#[derive(Clone, PartialEq)]
+pub struct TextComponent {
+	pub contents: TextComponentContents,
+	pub style: TextStyle,
+	/// Components that are shown after this one and inherit its style
+	pub extra: Vec<TextComponent>,
+}
+
+impl TextComponent {
+	pub fn new(contents: TextComponentContents) -> Self {
+		Self::with_style(contents, TextStyle::new())
+	}
+
+	pub fn with_style(contents: TextComponentContents, style: TextStyle) -> Self {
+		Self {
+			contents,
+			style,
+			extra: Vec::new(),
+		}
+	}
+
+	/// Creates an unstyled plain text component
+	pub fn text(text: &str) -> Self {
+		Self::new(TextComponentContents::Text(text.into()))
+	}
+
+	/// Converts the component to JSON. Scores and NBT need to be resolved by the
+	/// given function, which returns the keys to put in the component for them
+	pub fn to_json_with<F>(&self, resolve: &mut F) -> anyhow::Result<JsonValue>
+	where
+		F: FnMut(&TextComponentContents) -> anyhow::Result<Map<String, JsonValue>>,
+	{
+		let mut out = self.style.to_json_map_with(resolve)?;
+		match &self.contents {
+			TextComponentContents::Text(text) => {
+				// Plain text can just be written as a string
+				if out.is_empty() && self.extra.is_empty() {
+					return Ok(JsonValue::String(text.clone()));
+				}
+				out.insert("text".into(), JsonValue::String(text.clone()));
+			}
+			other => out.extend(resolve(other)?),
+		}
+		if !self.extra.is_empty() {
+			let extra = self
+				.extra
+				.iter()
+				.map(|x| x.to_json_with(resolve))
+				.collect::<anyhow::Result<_>>()?;
+			out.insert("extra".into(), JsonValue::Array(extra));
+		}
+		Ok(JsonValue::Object(out))
+	}
+
+	/// Converts a component without any scores or NBT to JSON
+	pub fn to_json(&self) -> anyhow::Result<JsonValue> {
+		self.to_json_with(&mut |contents| {
+			bail!("Text component contents {contents:?} can only be written during codegen")
+		})
+	}
+
+	pub fn get_json_str(&self) -> anyhow::Result<String> {
+		Ok(self.to_json()?.to_string())
+	}
+
+	/// Gets the contents of this component and all of the components inside it
+	pub fn get_all_contents(&self) -> Vec<&TextComponentContents> {
+		let mut out = vec![&self.contents];
+		if let Some(HoverEvent::ShowText(text)) = &self.style.hover_event {
+			out.extend(text.get_all_contents());
+		}
+		for extra in &self.extra {
+			out.extend(extra.get_all_contents());
+		}
+		out
+	}
+
+	/// Gets the contents of this component and all of the components inside it mutably
+	pub fn get_all_contents_mut(&mut self) -> Vec<&mut TextComponentContents> {
+		let mut out = vec![&mut self.contents];
+		if let Some(HoverEvent::ShowText(text)) = &mut self.style.hover_event {
+			out.extend(text.get_all_contents_mut());
+		}
+		for extra in &mut self.extra {
+			out.extend(extra.get_all_contents_mut());
+		}
+		out
+	}
+
+	pub fn get_used_regs_mut(&mut self) -> Vec<&mut Identifier> {
+		self.get_all_contents_mut()
+			.into_iter()
+			.flat_map(|x| match x {
+				TextComponentContents::Score(val) => val.get_used_regs_mut(),
+				TextComponentContents::Nbt(val) => val.get_used_regs_mut(),
+				TextComponentContents::Text(..) => Vec::new(),
+			})
+			.collect()
+	}
+}
+
+impl GetUsedRegs for TextComponent {
+	fn append_used_regs<'a>(&'a self, regs: &mut Vec<&'a Identifier>) {
+		for contents in self.get_all_contents() {
+			match contents {
+				TextComponentContents::Score(val) => val.append_used_regs(regs),
+				TextComponentContents::Nbt(val) => val.append_used_regs(regs),
+				TextComponentContents::Text(..) => {}
+			}
+		}
+	}
+}
+
+impl GetUsedLocals for TextComponent {
+	fn append_used_locals<'a>(&'a self, locals: &mut Vec<&'a Local>) {
+		for contents in self.get_all_contents() {
+			match contents {
+				TextComponentContents::Score(val) => val.append_used_locals(locals),
+				TextComponentContents::Nbt(val) => val.append_used_locals(locals),
+				TextComponentContents::Text(..) => {}
+			}
+		}
+	}
+}
+
+impl Debug for TextComponent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if !self.extra.is_empty() {
+			write!(f, "[")?;
+		}
+		if !self.style.is_empty() {
+			write!(f, "sty {:?} ", self.style)?;
+		}
+		write!(f, "{:?}", self.contents)?;
+		if !self.extra.is_empty() {
+			for extra in &self.extra {
+				write!(f, ", {extra:?}")?;
+			}
+			write!(f, "]")?;
+		}
+		Ok(())
+	}
+}
+
+/// The content of a text component
+#[derive(Clone)]
+pub enum TextComponentContents {
+	Text(String),
+	/// The value of a score. Score registers are shown with their value
+	Score(MutableScoreValue),
+	/// The value of some NBT. NBT registers are shown with their value
+	Nbt(MutableNBTValue),
+}
+
+impl PartialEq for TextComponentContents {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(Self::Text(l), Self::Text(r)) => l == r,
+			(Self::Score(l), Self::Score(r)) => l.is_value_eq(r),
+			(Self::Nbt(l), Self::Nbt(r)) => l.is_value_eq(r),
+			_ => false,
+		}
+	}
+}
+
+impl Debug for TextComponentContents {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Text(text) => write!(f, "{text:?}"),
+			Self::Score(val) => write!(f, "sco {val:?}"),
+			Self::Nbt(val) => write!(f, "nbt {val:?}"),
+		}
+	}
+}
+
+/// Formatting for a text component. Unset fields are inherited
+#[derive(Clone, PartialEq, Default)]
+pub struct TextStyle {
+	pub color: Option<String>,
+	pub font: Option<String>,
+	pub bold: Option<bool>,
+	pub italic: Option<bool>,
+	pub underlined: Option<bool>,
+	pub strikethrough: Option<bool>,
+	pub obfuscated: Option<bool>,
+	pub click_event: Option<ClickEvent>,
+	pub hover_event: Option<HoverEvent>,
+}
+
+impl TextStyle {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self == &Self::default()
+	}
+
+	fn flags(&self) -> [(&'static str, Option<bool>); 5] {
+		[
+			("bold", self.bold),
+			("italic", self.italic),
+			("underlined", self.underlined),
+			("strikethrough", self.strikethrough),
+			("obfuscated", self.obfuscated),
+		]
+	}
+
+	/// Converts the style to the keys of a JSON object, using a function to
+	/// resolve the scores and NBT in the hover text
+	pub fn to_json_map_with<F>(&self, resolve: &mut F) -> anyhow::Result<Map<String, JsonValue>>
+	where
+		F: FnMut(&TextComponentContents) -> anyhow::Result<Map<String, JsonValue>>,
+	{
+		let mut out = Map::new();
+		if let Some(color) = &self.color {
+			out.insert("color".into(), JsonValue::String(color.clone()));
+		}
+		if let Some(font) = &self.font {
+			out.insert("font".into(), JsonValue::String(font.clone()));
+		}
+		for (key, flag) in self.flags() {
+			if let Some(flag) = flag {
+				out.insert(key.into(), JsonValue::Bool(flag));
+			}
+		}
+		if let Some(click) = &self.click_event {
+			let mut event = Map::new();
+			event.insert(
+				"action".into(),
+				JsonValue::String(format!("{:?}", click.action)),
+			);
+			event.insert("value".into(), JsonValue::String(click.value.clone()));
+			out.insert("clickEvent".into(), JsonValue::Object(event));
+		}
+		if let Some(HoverEvent::ShowText(text)) = &self.hover_event {
+			let mut event = Map::new();
+			event.insert("action".into(), JsonValue::String("show_text".into()));
+			event.insert("contents".into(), text.to_json_with(resolve)?);
+			out.insert("hoverEvent".into(), JsonValue::Object(event));
+		}
+		Ok(out)
+	}
+
+	pub fn get_json_str(&self) -> anyhow::Result<String> {
+		let map = self.to_json_map_with(&mut |contents| {
+			bail!("Text component contents {contents:?} can only be written during codegen")
+		})?;
+		Ok(JsonValue::Object(map).to_string())
+	}
+}
+
+impl Debug for TextStyle {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut params = Vec::new();
+		if let Some(color) = &self.color {
+			params.push(format!("color={color:?}"));
+		}
+		if let Some(font) = &self.font {
+			params.push(format!("font={font:?}"));
+		}
+		for (key, flag) in self.flags() {
+			if let Some(flag) = flag {
+				params.push(format!("{key}={flag}"));
+			}
+		}
+		if let Some(click) = &self.click_event {
+			params.push(format!("click={:?} {:?}", click.action, click.value));
+		}
+		if let Some(HoverEvent::ShowText(text)) = &self.hover_event {
+			params.push(format!("hover={text:?}"));
+		}
+		write!(f, "[{}]", params.join(","))
+	}
+}
+
+/// An action that is run when a text component is clicked
+#[derive(Clone, PartialEq)]
+pub struct ClickEvent {
+	pub action: ClickAction,
+	pub value: String,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum ClickAction {
+	OpenUrl,
+	RunCommand,
+	SuggestCommand,
+	ChangePage,
+	CopyToClipboard,
+}
+
+impl ClickAction {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"open_url" => Some(Self::OpenUrl),
+			"run_command" => Some(Self::RunCommand),
+			"suggest_command" => Some(Self::SuggestCommand),
+			"change_page" => Some(Self::ChangePage),
+			"copy_to_clipboard" => Some(Self::CopyToClipboard),
+			_ => None,
+		}
+	}
+}
+
+impl Debug for ClickAction {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::OpenUrl => write!(f, "open_url"),
+			Self::RunCommand => write!(f, "run_command"),
+			Self::SuggestCommand => write!(f, "suggest_command"),
+			Self::ChangePage => write!(f, "change_page"),
+			Self::CopyToClipboard => write!(f, "copy_to_clipboard"),
+		}
+	}
+}
+
+/// Something that is shown when a text component is hovered over
+#[derive(Clone, PartialEq)]
+pub enum HoverEvent {
+	ShowText(Box<TextComponent>),
+}
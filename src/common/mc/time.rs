@@ -1,8 +1,13 @@
 use std::fmt::Debug;
+use std::ops::{Add, Sub};
+
+use anyhow::bail;
 
 use crate::output::codegen::{util::cg_float, Codegen};
 
-#[derive(Clone, PartialEq)]
+/// An amount of game time. Times are equal if they are the same
+/// number of ticks, even if they use different units
+#[derive(Clone)]
 pub struct Time {
 	pub amount: f32,
 	pub unit: TimeUnit,
@@ -30,6 +35,53 @@ impl Time {
 		self
 	}
 
+	/// Gets the number of ticks in this time, which may be fractional
+	pub fn to_ticks(&self) -> f32 {
+		self.amount * self.unit.get_tick_conversion_factor()
+	}
+
+	/// Checks if this time is a whole number of ticks, which the game can represent exactly
+	pub fn is_whole_ticks(&self) -> bool {
+		self.to_ticks().fract() == 0.0
+	}
+
+	/// Rounds this time to the nearest whole tick
+	pub fn round_to_ticks(&self) -> Self {
+		Self::new_ticks(self.to_ticks().round()).normalize()
+	}
+
+	/// Gets the number of ticks in this time, checking that it is a whole,
+	/// non-negative number that the game can use
+	pub fn get_whole_ticks(&self) -> anyhow::Result<u32> {
+		let ticks = self.to_ticks();
+		if ticks < 0.0 {
+			bail!("Time {self:?} cannot be negative");
+		}
+		if ticks.fract() != 0.0 {
+			bail!("Time {self:?} is {ticks} ticks, which is not a whole number of ticks");
+		}
+		if ticks > u32::MAX as f32 {
+			bail!("Time {self:?} is too long");
+		}
+
+		Ok(ticks as u32)
+	}
+
+	/// Converts this time to the largest unit that it is a whole number of
+	pub fn normalize(self) -> Self {
+		let ticks = self.to_ticks();
+		if ticks.fract() != 0.0 || ticks == 0.0 {
+			return self.convert(TimeUnit::Ticks);
+		}
+		for unit in [TimeUnit::Days, TimeUnit::Seconds] {
+			if ticks % unit.get_tick_conversion_factor() == 0.0 {
+				return self.convert(unit);
+			}
+		}
+
+		self.convert(TimeUnit::Ticks)
+	}
+
 	fn codegen_str(self) -> anyhow::Result<String> {
 		let mut amount = String::new();
 		cg_float(&mut amount, self.amount.into(), false, true, true);
@@ -44,6 +96,28 @@ impl Time {
 	}
 }
 
+impl PartialEq for Time {
+	fn eq(&self, other: &Self) -> bool {
+		self.to_ticks() == other.to_ticks()
+	}
+}
+
+impl Add for Time {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self::new_ticks(self.to_ticks() + rhs.to_ticks()).normalize()
+	}
+}
+
+impl Sub for Time {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self::new_ticks(self.to_ticks() - rhs.to_ticks()).normalize()
+	}
+}
+
 impl Debug for Time {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(f, "{}", self.amount)?;
@@ -61,10 +135,14 @@ impl Codegen for Time {
 		F: std::fmt::Write,
 	{
 		let _ = cbcx;
+		// The game drops fractional ticks, so we round them ourselves. The
+		// value ranges pass warns about times that lose precision like this
+		let time = self.round_to_ticks();
+		time.get_whole_ticks()?;
 		// Try different formats and pick the shortest one
-		let ticks = self.clone().convert(TimeUnit::Ticks).codegen_str()?;
-		let seconds = self.clone().convert(TimeUnit::Seconds).codegen_str()?;
-		let days = self.clone().convert(TimeUnit::Days).codegen_str()?;
+		let ticks = time.clone().convert(TimeUnit::Ticks).codegen_str()?;
+		let seconds = time.clone().convert(TimeUnit::Seconds).codegen_str()?;
+		let days = time.clone().convert(TimeUnit::Days).codegen_str()?;
 
 		let shortest = [ticks, seconds, days]
 			.iter()
@@ -95,6 +173,16 @@ impl Debug for TimeUnit {
 }
 
 impl TimeUnit {
+	/// Parses a unit from its suffix
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"t" => Some(Self::Ticks),
+			"s" => Some(Self::Seconds),
+			"d" => Some(Self::Days),
+			_ => None,
+		}
+	}
+
 	pub fn get_tick_conversion_factor(&self) -> f32 {
 		match self {
 			Self::Ticks => 1.0,
@@ -142,7 +230,7 @@ impl Codegen for TimePreset {
 	}
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Codegen)]
 pub enum TimeQuery {
 	Daytime,
 	Gametime,
@@ -159,17 +247,62 @@ impl Debug for TimeQuery {
 	}
 }
 
-impl Codegen for TimeQuery {
-	fn gen_writer<F>(
-		&self,
-		f: &mut F,
-		cbcx: &mut crate::output::codegen::CodegenBlockCx,
-	) -> anyhow::Result<()>
-	where
-		F: std::fmt::Write,
-	{
-		let _ = cbcx;
-		write!(f, "{self:?}")?;
-		Ok(())
+/// What to do when scheduling a function that is already scheduled
+#[derive(Clone, PartialEq, Eq, Codegen)]
+pub enum ScheduleMode {
+	/// Schedule it again in addition to the existing times
+	Append,
+	/// Replace the existing time. This is what the game does by default
+	Replace,
+}
+
+impl ScheduleMode {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"append" => Some(Self::Append),
+			"replace" => Some(Self::Replace),
+			_ => None,
+		}
+	}
+}
+
+impl Debug for ScheduleMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Append => write!(f, "append"),
+			Self::Replace => write!(f, "replace"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_time_arithmetic() {
+		assert_eq!(
+			Time::new(200.0, TimeUnit::Ticks),
+			Time::new(10.0, TimeUnit::Seconds)
+		);
+
+		let sum = Time::new(0.5, TimeUnit::Days) + Time::new(60.0, TimeUnit::Seconds);
+		assert!(sum.unit == TimeUnit::Seconds);
+		assert_eq!(sum.amount, 660.0);
+
+		let normal = Time::new_ticks(48000.0).normalize();
+		assert!(normal.unit == TimeUnit::Days);
+		assert_eq!(normal.amount, 2.0);
+
+		assert_eq!(
+			Time::new(1.5, TimeUnit::Seconds).get_whole_ticks().ok(),
+			Some(30)
+		);
+		assert!(Time::new_ticks(0.5).get_whole_ticks().is_err());
+		assert!(!Time::new(0.01, TimeUnit::Seconds).is_whole_ticks());
+		assert_eq!(Time::new_ticks(2.6).round_to_ticks(), Time::new_ticks(3.0));
+		assert!((Time::new_ticks(5.0) - Time::new_ticks(10.0))
+			.get_whole_ticks()
+			.is_err());
 	}
 }
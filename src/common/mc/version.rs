@@ -0,0 +1,100 @@
+use std::fmt::{Debug, Display};
+
+/// A release of Minecraft: Java Edition that a pack can target
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MinecraftVersion {
+	pub minor: u8,
+	pub patch: u8,
+}
+
+impl MinecraftVersion {
+	/// The newest version that DPC knows about
+	pub const LATEST: Self = Self::new(20, 4);
+
+	pub const fn new(minor: u8, patch: u8) -> Self {
+		Self { minor, patch }
+	}
+
+	/// Parses a version string like 1.20 or 1.20.4
+	pub fn parse(string: &str) -> Option<Self> {
+		let mut parts = string.split('.');
+		if parts.next()? != "1" {
+			return None;
+		}
+		let minor = parts.next()?.parse().ok()?;
+		let patch = match parts.next() {
+			Some(patch) => patch.parse().ok()?,
+			None => 0,
+		};
+		if parts.next().is_some() {
+			return None;
+		}
+		Some(Self::new(minor, patch))
+	}
+
+	/// Gets the data pack format number that this version of the game expects in the pack.mcmeta
+	pub fn get_pack_format(&self) -> u32 {
+		match (self.minor, self.patch) {
+			(..=14, _) => 4,
+			(15, _) | (16, 0..=1) => 5,
+			(16, _) => 6,
+			(17, _) => 7,
+			(18, 0..=1) => 8,
+			(18, _) => 9,
+			(19, 0..=3) => 10,
+			(19, _) => 12,
+			(20, 0..=1) => 15,
+			(20, 2) => 18,
+			(20, 3..=4) => 26,
+			(20, _) => 41,
+			(21, 0..=1) => 48,
+			(21, 2..=3) => 57,
+			_ => 61,
+		}
+	}
+}
+
+impl Default for MinecraftVersion {
+	fn default() -> Self {
+		Self::LATEST
+	}
+}
+
+impl Display for MinecraftVersion {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.patch == 0 {
+			write!(f, "1.{}", self.minor)
+		} else {
+			write!(f, "1.{}.{}", self.minor, self.patch)
+		}
+	}
+}
+
+impl Debug for MinecraftVersion {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{self}")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_version_parse() {
+		assert_eq!(
+			MinecraftVersion::parse("1.20.4"),
+			Some(MinecraftVersion::new(20, 4))
+		);
+		assert_eq!(
+			MinecraftVersion::parse("1.19"),
+			Some(MinecraftVersion::new(19, 0))
+		);
+		assert_eq!(MinecraftVersion::parse("2.0"), None);
+		assert_eq!(MinecraftVersion::parse("1.20.1.1"), None);
+		assert!(MinecraftVersion::new(20, 3) > MinecraftVersion::new(19, 4));
+		assert_eq!(MinecraftVersion::new(19, 0).to_string(), "1.19");
+		assert_eq!(MinecraftVersion::new(20, 4).get_pack_format(), 26);
+		assert_eq!(MinecraftVersion::new(16, 1).get_pack_format(), 5);
+	}
+}
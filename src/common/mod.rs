@@ -1,11 +1,16 @@
 pub mod block;
 pub mod condition;
 pub mod cost;
+pub mod diag;
 pub mod function;
+pub mod lint;
 pub mod mc;
 pub mod op;
 pub mod range;
 pub mod reg;
+pub mod snippet;
+pub mod span;
+pub mod storage;
 pub mod ty;
 pub mod val;
 
@@ -17,14 +22,15 @@ use self::block::Block;
 use self::condition::Condition;
 use self::function::FunctionSignature;
 use self::reg::GetUsedRegs;
-use self::ty::DataType;
+use self::ty::{DataType, Double};
 use self::val::{MutableValue, Value};
 
 #[derive(Clone, PartialEq)]
 pub enum DeclareBinding {
 	Null,
 	Value(Value),
-	Cast(DataType, MutableValue),
+	/// Cast of a value to a type, multiplying it by a scale
+	Cast(DataType, Value, Double),
 	Condition(Condition),
 }
 
@@ -57,7 +63,7 @@ impl GetUsedRegs for DeclareBinding {
 		match self {
 			Self::Null => {}
 			Self::Value(val) => val.append_used_regs(regs),
-			Self::Cast(_, val) => val.append_used_regs(regs),
+			Self::Cast(_, val, _) => val.append_used_regs(regs),
 			Self::Condition(cond) => cond.append_used_regs(regs),
 		}
 	}
@@ -68,7 +74,13 @@ impl Debug for DeclareBinding {
 		let text = match self {
 			Self::Null => "null".to_string(),
 			Self::Value(val) => format!("{val:?}"),
-			Self::Cast(ty, val) => format!("cast {ty:?} {val:?}"),
+			Self::Cast(ty, val, scale) => {
+				if *scale == 1.0 {
+					format!("cast {ty:?} {val:?}")
+				} else {
+					format!("cast {ty:?} {val:?}, {scale}")
+				}
+			}
 			Self::Condition(cond) => format!("cond {cond:?}"),
 		};
 		write!(f, "{text}")
@@ -1,4 +1,7 @@
 use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
 
 use num_traits::{Bounded, NumOps, One, PrimInt, Signed};
 
@@ -28,6 +31,40 @@ pub struct Range<N: Rangeable> {
 }
 
 impl<N: Rangeable> Range<N> {
+	pub fn new(left: RangeEnd<N>, right: RangeEnd<N>) -> Self {
+		Self { left, right }
+	}
+
+	/// Parses a range in the game's format, like `1..5`, `..5`, `1..`, or `3`
+	pub fn parse(string: &str) -> anyhow::Result<Self>
+	where
+		N: FromStr,
+	{
+		let parse_end = |end: &str| -> anyhow::Result<RangeEnd<N>> {
+			if end.is_empty() {
+				Ok(RangeEnd::Infinite)
+			} else {
+				let val = end
+					.parse()
+					.map_err(|_| anyhow!("Invalid range value {end}"))?;
+				Ok(RangeEnd::Value(val))
+			}
+		};
+
+		if let Some((left, right)) = string.split_once("..") {
+			if left.is_empty() && right.is_empty() {
+				bail!("Range must have at least one end");
+			}
+			Ok(Self::new(parse_end(left)?, parse_end(right)?))
+		} else {
+			let val = parse_end(string)?;
+			if let RangeEnd::Infinite = val {
+				bail!("Range cannot be empty");
+			}
+			Ok(Self::new(val.clone(), val))
+		}
+	}
+
 	/// Canonicalize this range so that the smaller number is on the left
 	#[must_use]
 	pub fn canonicalize(self) -> Self {
@@ -73,6 +110,18 @@ impl<N: Rangeable> Range<N> {
 	pub fn is_infinite(&self) -> bool {
 		self.left.is_infinite() && self.right.is_infinite()
 	}
+
+	/// Gets the smallest and largest values in this range, where infinite ends
+	/// are the limits of the number type
+	pub fn get_bounds(&self) -> (N, N) {
+		let left = self.left.get_val(RangeSide::Left);
+		let right = self.right.get_val(RangeSide::Right);
+		if left > right {
+			(right, left)
+		} else {
+			(left, right)
+		}
+	}
 }
 
 impl<N: Rangeable + PrimInt + One> Range<N> {
@@ -166,15 +215,26 @@ impl<N: Rangeable> Codegen for Range<N> {
 	where
 		F: std::fmt::Write,
 	{
+		let _ = cbcx;
+		write!(f, "{self}")?;
+
+		Ok(())
+	}
+}
+
+impl<N: Rangeable> Display for Range<N> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let range = self.clone().canonicalize().make_single_infinite();
 		if let Some(single) = range.get_single() {
-			write!(f, "{single}")?;
+			write!(f, "{single}")
 		} else {
-			self.left.gen_writer(f, cbcx)?;
+			let write_end = |f: &mut std::fmt::Formatter<'_>, end: &RangeEnd<N>| match end {
+				RangeEnd::Value(val) => write!(f, "{val}"),
+				RangeEnd::Infinite => Ok(()),
+			};
+			write_end(f, &self.left)?;
 			write!(f, "..")?;
-			self.right.gen_writer(f, cbcx)?;
+			write_end(f, &self.right)
 		}
-
-		Ok(())
 	}
 }
@@ -0,0 +1,174 @@
+use anyhow::{bail, Context};
+
+use crate::common::storage::get_reg_storage;
+use crate::common::ResourceLocation;
+use crate::ir::IR;
+use crate::output::text::{REG_OBJECTIVE, REG_STORAGE_LOCATION};
+use crate::project::ProjectSettings;
+
+/// A handwritten function that is copied into the output pack as-is. Snippets can be
+/// called from the pack with extern calls, and declare what they touch so that the
+/// rest of the pack can still be optimized around them
+#[derive(Debug, Clone)]
+pub struct Snippet {
+	pub id: ResourceLocation,
+	/// The commands in the function
+	pub contents: Vec<String>,
+	pub effects: SnippetEffects,
+	/// Whether the snippet is added to the tick tag
+	pub tick: bool,
+	/// Whether the snippet is added to the load tag
+	pub load: bool,
+}
+
+impl Snippet {
+	/// Creates a snippet from its commands, finding the functions that it calls
+	pub fn new(id: ResourceLocation, contents: Vec<String>) -> Self {
+		let calls = contents.iter().flat_map(|x| get_line_calls(x)).collect();
+		Self {
+			id,
+			contents,
+			effects: SnippetEffects {
+				calls,
+				..Default::default()
+			},
+			tick: false,
+			load: false,
+		}
+	}
+
+	/// Parses a snippet from an mcfunction file. Comments at the start of a line like
+	/// `# @reads storage foo:bar` declare the effects of the snippet, and `# @tick` and
+	/// `# @load` add it to those tags
+	pub fn parse(id: ResourceLocation, text: &str) -> anyhow::Result<Self> {
+		let mut out = Self::new(id, text.lines().map(String::from).collect());
+		for line in text.lines() {
+			let Some(directive) = line.trim().strip_prefix("# @") else {
+				continue;
+			};
+			let words: Vec<_> = directive.split_whitespace().collect();
+			match words.as_slice() {
+				["tick"] => out.tick = true,
+				["load"] => out.load = true,
+				["calls", func] => out.effects.calls.push((*func).into()),
+				["reads", kind, name] => out
+					.effects
+					.reads
+					.push(SnippetResource::parse(kind, name).context("Invalid read")?),
+				["writes", kind, name] => out
+					.effects
+					.writes
+					.push(SnippetResource::parse(kind, name).context("Invalid write")?),
+				_ => bail!("Unknown snippet directive '{directive}'"),
+			}
+		}
+
+		Ok(out)
+	}
+}
+
+/// What a snippet does to the rest of the pack
+#[derive(Debug, Clone, Default)]
+pub struct SnippetEffects {
+	/// Functions in the pack that the snippet calls
+	pub calls: Vec<ResourceLocation>,
+	pub reads: Vec<SnippetResource>,
+	pub writes: Vec<SnippetResource>,
+}
+
+/// Something that a snippet reads or writes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnippetResource {
+	Objective(String),
+	Storage(ResourceLocation),
+}
+
+impl SnippetResource {
+	pub fn parse(kind: &str, name: &str) -> anyhow::Result<Self> {
+		match kind {
+			"objective" => Ok(Self::Objective(name.into())),
+			"storage" => Ok(Self::Storage(name.into())),
+			other => bail!("Unknown snippet resource kind '{other}'"),
+		}
+	}
+}
+
+/// Gets the functions that a line of a snippet calls directly
+fn get_line_calls(line: &str) -> Vec<ResourceLocation> {
+	let line = line.trim();
+	if line.starts_with('#') {
+		return Vec::new();
+	}
+	let words: Vec<_> = line.split_whitespace().collect();
+	words
+		.windows(2)
+		.enumerate()
+		.filter(|(i, pair)| pair[0] == "function" && (*i == 0 || words[i - 1] == "run"))
+		// Tags can't be checked
+		.filter(|(_, pair)| !pair[1].starts_with('#'))
+		.map(|(_, pair)| pair[1].into())
+		.collect()
+}
+
+/// Checks the snippets of a project against the IR, and marks the functions that
+/// they call so that they are kept and not renamed
+pub fn prepare_snippets(ir: &mut IR, project: &ProjectSettings) -> anyhow::Result<()> {
+	let reg_storage = get_reg_storage(&ir.storages)
+		.cloned()
+		.unwrap_or_else(|| REG_STORAGE_LOCATION.into());
+	for snippet in &project.snippets {
+		let id = &snippet.id;
+		if ir.functions.contains_key(id) {
+			bail!("Snippet {id} conflicts with an existing function");
+		}
+
+		for func_id in &snippet.effects.calls {
+			let Some(func) = ir.functions.get_mut(func_id) else {
+				continue;
+			};
+			if !func.interface.sig.params.is_empty() {
+				bail!("Snippet {id} calls function {func_id}, which has parameters");
+			}
+			func.interface.annotations.preserve = true;
+		}
+
+		// The compiler assumes that nothing else touches its registers and literals
+		for resource in snippet.effects.reads.iter().chain(&snippet.effects.writes) {
+			let internal = match resource {
+				SnippetResource::Objective(objective) => {
+					objective == REG_OBJECTIVE || objective == project.get_literal_objective()
+				}
+				SnippetResource::Storage(storage) => storage == &reg_storage,
+			};
+			if internal {
+				bail!("Snippet {id} uses {resource:?}, which is internal to the compiler");
+			}
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_snippet_parse() {
+		let text = "# @tick\n# @writes storage foo:data\nexecute as @a run function foo:bar\nfunction #foo:tag\nsay hi";
+		let snippet = Snippet::parse("foo:snip".into(), text).expect("Failed to parse");
+		assert!(snippet.tick);
+		assert!(!snippet.load);
+		assert_eq!(
+			snippet.effects.calls,
+			vec![ResourceLocation::from("foo:bar")]
+		);
+		assert_eq!(
+			snippet.effects.writes,
+			vec![SnippetResource::Storage("foo:data".into())]
+		);
+		assert_eq!(snippet.contents.len(), 5);
+
+		assert!(Snippet::parse("foo:snip".into(), "# @reads block foo").is_err());
+	}
+}
@@ -0,0 +1,58 @@
+use std::fmt::{Debug, Display};
+
+/// A range of characters in source text
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+	/// The line that the span starts on, starting at 1
+	pub line: usize,
+	/// The column that the span starts at, starting at 0
+	pub col: usize,
+	/// The absolute index of the first character in the span
+	pub start: usize,
+	/// The absolute index of the character after the end of the span
+	pub end: usize,
+}
+
+impl Span {
+	pub fn new(line: usize, col: usize, start: usize, end: usize) -> Self {
+		Self {
+			line,
+			col,
+			start,
+			end,
+		}
+	}
+
+	/// Creates a span that covers this span and another one that comes after it
+	pub fn to(&self, other: &Span) -> Self {
+		Self {
+			end: self.end.max(other.end),
+			..*self
+		}
+	}
+
+	/// Gets the number of characters in the span
+	pub fn len(&self) -> usize {
+		self.end.saturating_sub(self.start)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl Debug for Span {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"({}:{}:{}..{})",
+			self.line, self.col, self.start, self.end
+		)
+	}
+}
+
+impl Display for Span {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "({}:{})", self.line, self.col)
+	}
+}
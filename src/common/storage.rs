@@ -0,0 +1,73 @@
+use rustc_hash::FxHashMap;
+
+use super::mc::DataPath;
+use super::ResourceLocation;
+
+/// Storages that a pack declares, with how long their data lives
+pub type StorageDeclarations = FxHashMap<ResourceLocation, StorageLifetime>;
+
+/// How long the data in a declared storage lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageLifetime {
+	/// Data is kept between loads of the pack
+	#[default]
+	Persistent,
+	/// Data is cleared by the init function every time the pack loads,
+	/// which also makes the storage a good place to spill registers to
+	Transient,
+}
+
+impl StorageLifetime {
+	pub fn parse(string: &str) -> Option<Self> {
+		match string {
+			"persistent" => Some(Self::Persistent),
+			"transient" => Some(Self::Transient),
+			_ => None,
+		}
+	}
+}
+
+/// Gets the transient storage that registers should be spilled to, if one was declared
+pub fn get_reg_storage(decls: &StorageDeclarations) -> Option<&ResourceLocation> {
+	decls
+		.iter()
+		.filter(|(_, lifetime)| **lifetime == StorageLifetime::Transient)
+		.map(|(storage, _)| storage)
+		.min()
+}
+
+/// Gets the top-level key of a storage that a path reads or writes
+pub fn get_root_key(path: &DataPath) -> Option<&str> {
+	match path {
+		DataPath::This => None,
+		DataPath::String(string) => {
+			let end = string.find(['.', '[', '{']).unwrap_or(string.len());
+			Some(&string[..end]).filter(|x| !x.is_empty())
+		}
+		DataPath::Access(path, ..) | DataPath::Index(path, ..) => get_root_key(path),
+	}
+}
+
+/// Gets the rest of a path after a parent path, if the path is the same as or inside
+/// of the parent. Paths are compared by their text, so paths that are written
+/// differently won't be found, but a wrong one never is. Only paths that select
+/// a single value are compared, since filters and wildcards can select many
+pub fn strip_parent_path(path: &DataPath, parent: &DataPath) -> Option<String> {
+	if matches!(path, DataPath::This) || matches!(parent, DataPath::This) {
+		return None;
+	}
+	let path = format!("{path:?}");
+	if path.contains("[]") || path.contains('{') {
+		return None;
+	}
+	let rest = path.strip_prefix(&format!("{parent:?}"))?;
+	(rest.is_empty() || rest.starts_with(['.', '['])).then(|| rest.to_string())
+}
+
+/// Checks if two paths could refer to the same value or to values inside of each other
+pub fn can_paths_overlap(left: &DataPath, right: &DataPath) -> bool {
+	match (get_root_key(left), get_root_key(right)) {
+		(Some(left), Some(right)) => left == right,
+		_ => true,
+	}
+}
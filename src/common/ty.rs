@@ -45,30 +45,44 @@ impl Debug for DataType {
 	}
 }
 
+/// The most bits that flags can have, which keeps them from reaching the sign bit of a score
+pub const MAX_FLAG_BITS: u8 = 31;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ScoreType {
 	Score,
 	Bool,
+	/// A set of bit flags packed into a score, with the number of bits that it uses
+	Flags(u8),
+	/// One of the variants of an enum, numbered from zero, with the number of variants
+	Enum(u32),
 }
 
 impl ScoreType {
 	pub fn is_trivially_castable(&self, other: &ScoreType) -> bool {
 		match other {
 			Self::Score => {
-				matches!(self, Self::Score | Self::Bool)
+				matches!(
+					self,
+					Self::Score | Self::Bool | Self::Flags(..) | Self::Enum(..)
+				)
 			}
 			Self::Bool => matches!(self, Self::Score | Self::Bool),
+			Self::Flags(..) => matches!(self, Self::Score | Self::Flags(..)),
+			// Other values could be out of the range of the enum
+			Self::Enum(..) => self == other,
 		}
 	}
 }
 
 impl Debug for ScoreType {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		let text = match self {
-			Self::Score => "score",
-			Self::Bool => "bool",
-		};
-		write!(f, "{text}")
+		match self {
+			Self::Score => write!(f, "score"),
+			Self::Bool => write!(f, "bool"),
+			Self::Flags(bits) => write!(f, "flags<{bits}>"),
+			Self::Enum(count) => write!(f, "enum<{count}>"),
+		}
 	}
 }
 
@@ -148,6 +162,8 @@ impl NBTType {
 			match other {
 				ScoreType::Score => true,
 				ScoreType::Bool => true,
+				ScoreType::Flags(..) => true,
+				ScoreType::Enum(..) => false,
 			}
 		} else {
 			false
@@ -501,6 +517,17 @@ impl NBTCompoundTypeContents {
 	pub fn is_empty(&self) -> bool {
 		self.0.is_empty()
 	}
+
+	/// Get the compound type of these contents
+	pub fn get_ty(&self) -> NBTCompoundType {
+		let mut out = FxHashMap::default();
+		for (key, val) in self.0.iter() {
+			if let DataType::NBT(ty) = val.get_ty() {
+				out.insert(key.clone(), ty);
+			}
+		}
+		Arc::new(out)
+	}
 }
 
 impl Default for NBTCompoundTypeContents {
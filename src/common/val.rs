@@ -392,6 +392,14 @@ impl MutableNBTValue {
 			|| matches!((self, other), (Self::Local(l), Self::Local(r)) if l == r)
 	}
 
+	pub fn get_used_regs_mut(&mut self) -> Vec<&mut Identifier> {
+		match self {
+			Self::Property(val, ..) | Self::Index(val, ..) => val.get_used_regs_mut(),
+			Self::Data(..) => Vec::new(),
+			Self::Local(loc) => loc.get_used_regs_mut(),
+		}
+	}
+
 	pub fn is_root(&self) -> bool {
 		matches!(
 			self,
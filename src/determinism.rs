@@ -0,0 +1,171 @@
+use std::fmt::Display;
+
+use anyhow::Context;
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::ir::IR;
+use crate::output::datapack::Datapack;
+use crate::project::ProjectSettings;
+use crate::{codegen_ir, CodegenIRSettings};
+
+/// The number of extra times the input is compiled with a different map order
+const PERTURBED_RUNS: usize = 3;
+
+/// A place where two compilations of the same input produced different output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nondeterminism {
+	/// A function or tag only exists in the output of one of the runs
+	Missing { location: String, run: usize },
+	/// A function or tag has different contents in two of the runs
+	Changed {
+		location: String,
+		run: usize,
+		line: usize,
+		expected: String,
+		found: String,
+	},
+}
+
+impl Display for Nondeterminism {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Missing { location, run } => {
+				write!(
+					f,
+					"{location} only exists in one of the outputs of run {run}"
+				)
+			}
+			Self::Changed {
+				location,
+				run,
+				line,
+				expected,
+				found,
+			} => write!(
+				f,
+				"{location} differs in run {run} at line {line}: expected `{expected}`, found `{found}`"
+			),
+		}
+	}
+}
+
+/// Compiles the input multiple times, changing the iteration order of the maps
+/// in the IR each time, and finds all of the differences between the outputs.
+/// The hash maps in the compiler aren't seeded, so rebuilding the input maps with
+/// different capacities and insertion orders is used to shuffle iteration instead
+pub fn check_determinism(
+	ir: IR,
+	project: &ProjectSettings,
+	settings: CodegenIRSettings,
+) -> anyhow::Result<Vec<Nondeterminism>> {
+	let baseline =
+		codegen_ir(ir.clone(), project, settings.clone()).context("Failed to compile input")?;
+
+	let mut out = Vec::new();
+	for run in 1..=PERTURBED_RUNS {
+		let perturbed = perturb_ir(&ir, run);
+		let pack = codegen_ir(perturbed, project, settings.clone())
+			.with_context(|| format!("Failed to compile input in run {run}"))?;
+		diff_packs(&baseline, &pack, run, &mut out);
+	}
+
+	Ok(out)
+}
+
+/// Rebuilds the maps in the IR so that they iterate in a different order
+fn perturb_ir(ir: &IR, run: usize) -> IR {
+	let mut out = ir.clone();
+	out.functions = perturb_map(&ir.functions, run);
+	out
+}
+
+fn perturb_map<K: Clone + Ord + std::hash::Hash, V: Clone>(
+	map: &FxHashMap<K, V>,
+	run: usize,
+) -> FxHashMap<K, V> {
+	let mut out = FxHashMap::with_capacity_and_hasher((map.len() + 1) << run, Default::default());
+	let mut entries: Vec<_> = map.iter().sorted_by(|l, r| l.0.cmp(r.0)).collect();
+	if run % 2 == 1 {
+		entries.reverse();
+	}
+	out.extend(entries.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+	out
+}
+
+/// Finds the differences between the output of the first run and another one
+fn diff_packs(first: &Datapack, other: &Datapack, run: usize, out: &mut Vec<Nondeterminism>) {
+	let first_fns = first
+		.functions
+		.iter()
+		.map(|(id, func)| (format!("Function {id}"), func.contents.clone()));
+	let first_tags = first
+		.function_tags
+		.iter()
+		.map(|(id, tag)| (format!("Function tag {id}"), tag.inner.values.clone()));
+	let other_fns = other
+		.functions
+		.iter()
+		.map(|(id, func)| (format!("Function {id}"), func.contents.clone()));
+	let other_tags = other
+		.function_tags
+		.iter()
+		.map(|(id, tag)| (format!("Function tag {id}"), tag.inner.values.clone()));
+
+	let first: FxHashMap<_, _> = first_fns.chain(first_tags).collect();
+	let other: FxHashMap<_, _> = other_fns.chain(other_tags).collect();
+
+	for location in first.keys().chain(other.keys()).unique().sorted() {
+		let (Some(expected), Some(found)) = (first.get(location), other.get(location)) else {
+			out.push(Nondeterminism::Missing {
+				location: location.clone(),
+				run,
+			});
+			continue;
+		};
+		let len = expected.len().max(found.len());
+		for line in 0..len {
+			let expected = expected.get(line).cloned().unwrap_or_default();
+			let found = found.get(line).cloned().unwrap_or_default();
+			if expected != found {
+				out.push(Nondeterminism::Changed {
+					location: location.clone(),
+					run,
+					line: line + 1,
+					expected,
+					found,
+				});
+				break;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::common::ResourceLocation;
+	use crate::output::datapack::Function;
+
+	use super::*;
+
+	#[test]
+	fn test_diff_packs() {
+		let mut first = Datapack::new();
+		let mut func = Function::new();
+		func.contents = vec!["say a".into(), "say b".into()];
+		first.functions.insert("test:main".into(), func);
+
+		let mut other = first.clone();
+		let mut out = Vec::new();
+		diff_packs(&first, &other, 1, &mut out);
+		assert!(out.is_empty());
+
+		let id = ResourceLocation::from("test:main");
+		other.functions.get_mut(&id).unwrap().contents[1] = "say c".into();
+		other.functions.insert("test:other".into(), Function::new());
+		diff_packs(&first, &other, 1, &mut out);
+		assert_eq!(out.len(), 2);
+		assert!(matches!(&out[0], Nondeterminism::Changed { line: 2, .. }));
+		assert!(matches!(&out[1], Nondeterminism::Missing { .. }));
+	}
+}
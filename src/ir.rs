@@ -1,13 +1,20 @@
 use std::fmt::Debug;
 
+use derivative::Derivative;
 use rustc_hash::FxHashMap;
 
 use crate::common::block::Block as BlockTrait;
 use crate::common::condition::Condition;
+use crate::common::diag::Diagnostic;
 use crate::common::function::{CallInterface, FunctionInterface};
+use crate::common::lint::Lint;
 use crate::common::mc::instr::MinecraftInstr;
+use crate::common::mc::scoreboard_and_teams::Criterion;
+use crate::common::mc::{DataLocation, DataPath, EntityTarget};
 use crate::common::mc::modifier::MIRModifier;
-use crate::common::ty::{DataType, Double};
+use crate::common::span::Span;
+use crate::common::storage::StorageDeclarations;
+use crate::common::ty::{DataType, Double, NBTCompoundTypeContents};
 use crate::common::val::ArgRetIndex;
 use crate::common::{val::MutableValue, val::Value, DeclareBinding, Identifier, ResourceLocation};
 use crate::common::{FunctionTrait, IRType};
@@ -15,12 +22,18 @@ use crate::common::{FunctionTrait, IRType};
 #[derive(Debug, Clone)]
 pub struct IR {
 	pub functions: FxHashMap<ResourceLocation, IRFunction>,
+	/// Storages that the pack declares
+	pub storages: StorageDeclarations,
+	/// Warnings that passes have found in the IR
+	pub diagnostics: Vec<Diagnostic>,
 }
 
 impl IR {
 	pub fn new() -> Self {
 		Self {
 			functions: FxHashMap::default(),
+			storages: StorageDeclarations::default(),
+			diagnostics: Vec::new(),
 		}
 	}
 }
@@ -80,6 +93,54 @@ impl Block {
 	pub fn from_single(instr: InstrKind) -> Self {
 		Self::with_contents(vec![Instruction::new(instr)])
 	}
+
+	/// Gets the functions that the instructions in this block and the blocks inside
+	/// of it call, schedule, or check in conditions
+	pub fn get_callees(&self) -> Vec<&ResourceLocation> {
+		let mut out = Vec::new();
+		self.append_callees(&mut out);
+		out
+	}
+
+	fn append_callees<'a>(&'a self, out: &mut Vec<&'a ResourceLocation>) {
+		for instr in &self.contents {
+			match &instr.kind {
+				InstrKind::Call { call } => out.push(&call.function),
+				InstrKind::CallExtern { func } | InstrKind::CallWith { func, .. } => out.push(func),
+				InstrKind::MC(MinecraftInstr::ScheduleFunction { function, .. }) => {
+					out.push(function)
+				}
+				InstrKind::SpreadOverTicks {
+					on_complete: Some(func),
+					..
+				} => out.push(func),
+				InstrKind::If { condition, .. }
+				| InstrKind::IfElse { condition, .. }
+				| InstrKind::Loop { condition, .. }
+				| InstrKind::Assert { condition, .. }
+				| InstrKind::Declare {
+					right: DeclareBinding::Condition(condition),
+					..
+				} => append_condition_callees(condition, out),
+				_ => {}
+			}
+			for body in instr.kind.get_bodies() {
+				body.append_callees(out);
+			}
+		}
+	}
+}
+
+fn append_condition_callees<'a>(condition: &'a Condition, out: &mut Vec<&'a ResourceLocation>) {
+	match condition {
+		Condition::Function(func) => out.push(func),
+		Condition::Not(condition) => append_condition_callees(condition, out),
+		Condition::And(l, r) | Condition::Or(l, r) | Condition::Xor(l, r) => {
+			append_condition_callees(l, out);
+			append_condition_callees(r, out);
+		}
+		_ => {}
+	}
 }
 
 impl Default for Block {
@@ -107,14 +168,24 @@ impl Debug for Block {
 	}
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Derivative)]
+#[derivative(PartialEq)]
 pub struct Instruction {
 	pub kind: InstrKind,
+	/// Lints that are suppressed for this instruction
+	pub allowed_lints: Vec<Lint>,
+	/// Where the instruction came from in the source
+	#[derivative(PartialEq = "ignore")]
+	pub span: Option<Span>,
 }
 
 impl Instruction {
 	pub fn new(kind: InstrKind) -> Self {
-		Self { kind }
+		Self {
+			kind,
+			allowed_lints: Vec::new(),
+			span: None,
+		}
 	}
 }
 
@@ -131,10 +202,26 @@ pub enum InstrKind {
 		ty: DataType,
 		right: DeclareBinding,
 	},
+	/// Declares a score register that is bound to an objective with a criterion,
+	/// so that it reads the value that the game tracks for the target
+	DeclareCriteria {
+		left: Identifier,
+		criterion: Criterion,
+		target: EntityTarget,
+	},
 	Assign {
 		left: MutableValue,
 		right: Value,
 	},
+	/// Casts a value to another type, multiplying it by a scale, and stores it.
+	/// The type decides the width of the stored number, which makes fixed-point
+	/// math possible without writing out the store modifier
+	Cast {
+		left: MutableValue,
+		ty: DataType,
+		right: Value,
+		scale: Double,
+	},
 	Add {
 		left: MutableValue,
 		right: Value,
@@ -163,6 +250,48 @@ pub enum InstrKind {
 		left: MutableValue,
 		right: Value,
 	},
+	/// Bitwise and of two scores
+	BitAnd {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Bitwise or of two scores
+	BitOr {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Bitwise xor of two scores
+	BitXor {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Shifts the bits of a score left. Only the low five bits of the shift amount are used
+	Shl {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Shifts the bits of a score right, keeping the sign
+	Shr {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Sets a value to whether a bit of a flags score is set
+	GetFlag {
+		left: MutableValue,
+		flags: MutableValue,
+		bit: u8,
+	},
+	/// Sets a bit of a flags score to a bool
+	SetFlag {
+		flags: MutableValue,
+		bit: u8,
+		right: Value,
+	},
+	/// Flips a bit of a flags score
+	ToggleFlag {
+		flags: MutableValue,
+		bit: u8,
+	},
 	Swap {
 		left: MutableValue,
 		right: MutableValue,
@@ -170,6 +299,12 @@ pub enum InstrKind {
 	Remove {
 		val: MutableValue,
 	},
+	/// Copies the values at paths in one data location to the same paths in another
+	CopyData {
+		from: DataLocation,
+		to: DataLocation,
+		paths: Vec<DataPath>,
+	},
 	Abs {
 		val: MutableValue,
 	},
@@ -177,6 +312,22 @@ pub enum InstrKind {
 		base: MutableValue,
 		exp: u8,
 	},
+	/// Sets a value to a random number between min and max, inclusive
+	Rand {
+		left: MutableValue,
+		min: i32,
+		max: i32,
+	},
+	/// Sets a string to the hyphenated form of a UUID int array
+	UUIDToString {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Sets a UUID int array to the UUID in a hyphenated string
+	StringToUUID {
+		left: MutableValue,
+		right: Value,
+	},
 	Get {
 		value: MutableValue,
 		scale: Double,
@@ -232,6 +383,11 @@ pub enum InstrKind {
 	CallExtern {
 		func: ResourceLocation,
 	},
+	/// Calls a function with a compound as its macro arguments
+	CallWith {
+		func: ResourceLocation,
+		args: Value,
+	},
 	MC(MinecraftInstr),
 	ReturnValue {
 		index: ArgRetIndex,
@@ -246,6 +402,17 @@ pub enum InstrKind {
 	Command {
 		command: String,
 	},
+	/// A command that has registers put into it where it says `$(reg)`. Plain commands
+	/// are left as they are, so only these are run as macro lines
+	InterpolatedCommand {
+		command: String,
+	},
+	/// A command that is run as a macro line, so it can use the macro arguments
+	/// of the function. Bodies that are lowered to their own functions don't receive
+	/// the arguments, so this only works at the top level or in a single-instruction body
+	MacroCommand {
+		command: String,
+	},
 	Comment {
 		comment: String,
 	},
@@ -253,12 +420,94 @@ pub enum InstrKind {
 		modifier: MIRModifier,
 		body: Box<Block>,
 	},
+	/// Checks a condition at runtime, printing the message and
+	/// failing the function if it is false
+	Assert {
+		condition: Condition,
+		message: String,
+	},
+	/// Runs a body that is guaranteed to complete within the same function
+	/// invocation, without being split across ticks
+	Atomic {
+		body: Box<Block>,
+	},
+	/// Summons an entity and runs the body as it, optionally merging NBT
+	/// into it and capturing its UUID first
+	SummonAs {
+		entity: ResourceLocation,
+		nbt: NBTCompoundTypeContents,
+		uuid: Option<MutableValue>,
+		body: Box<Block>,
+	},
+	/// A point at the top level of a function that can be jumped to
+	Label {
+		name: String,
+	},
+	/// Jumps to a label in the same function, never coming back
+	Goto {
+		label: String,
+	},
+	/// Runs a body over and over while a condition is true. The body runs in its
+	/// own recursive function, with the registers it uses passed in and out
+	Loop {
+		condition: Condition,
+		body: Box<Block>,
+		check: LoopCheck,
+	},
+	/// Runs a body for each item of a list over multiple ticks, processing a
+	/// fixed number of items every tick and optionally calling a function once
+	/// all of them are done. The body runs in its own function, so only the item
+	/// register is available inside of it
+	SpreadOverTicks {
+		per_tick: u32,
+		item: Identifier,
+		ty: DataType,
+		list: Value,
+		on_complete: Option<ResourceLocation>,
+		body: Box<Block>,
+	},
+}
+
+impl InstrKind {
+	/// Gets the blocks that are nested inside of this instruction
+	pub fn get_bodies(&self) -> Vec<&Block> {
+		match self {
+			Self::If { body, .. }
+			| Self::Modify { body, .. }
+			| Self::ReturnRun { body }
+			| Self::Atomic { body }
+			| Self::Loop { body, .. }
+			| Self::SummonAs { body, .. }
+			| Self::SpreadOverTicks { body, .. } => vec![body],
+			Self::IfElse { first, second, .. } => vec![first, second],
+			_ => Vec::new(),
+		}
+	}
+
+	pub fn get_bodies_mut(&mut self) -> Vec<&mut Block> {
+		match self {
+			Self::If { body, .. }
+			| Self::Modify { body, .. }
+			| Self::ReturnRun { body }
+			| Self::Atomic { body }
+			| Self::Loop { body, .. }
+			| Self::SummonAs { body, .. }
+			| Self::SpreadOverTicks { body, .. } => vec![body],
+			Self::IfElse { first, second, .. } => vec![first, second],
+			_ => Vec::new(),
+		}
+	}
 }
 
 impl Debug for InstrKind {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let text = match self {
 			Self::Declare { left, ty, right } => format!("let {left}: {ty:?} = {right:?}"),
+			Self::DeclareCriteria {
+				left,
+				criterion,
+				target,
+			} => format!("let {left}: score = criteria {criterion:?} of {target:?}"),
 			Self::Assign { left, right } => format!("{left:?} = {right:?}"),
 			Self::Add { left, right } => format!("add {left:?}, {right:?}"),
 			Self::Sub { left, right } => format!("sub {left:?}, {right:?}"),
@@ -267,9 +516,20 @@ impl Debug for InstrKind {
 			Self::Mod { left, right } => format!("mod {left:?}, {right:?}"),
 			Self::Min { left, right } => format!("min {left:?}, {right:?}"),
 			Self::Max { left, right } => format!("max {left:?}, {right:?}"),
+			Self::BitAnd { left, right } => format!("band {left:?}, {right:?}"),
+			Self::BitOr { left, right } => format!("bor {left:?}, {right:?}"),
+			Self::BitXor { left, right } => format!("bxor {left:?}, {right:?}"),
+			Self::Shl { left, right } => format!("shl {left:?}, {right:?}"),
+			Self::Shr { left, right } => format!("shr {left:?}, {right:?}"),
+			Self::GetFlag { left, flags, bit } => format!("fget {left:?}, {flags:?}, {bit}"),
+			Self::SetFlag { flags, bit, right } => format!("fset {flags:?}, {bit}, {right:?}"),
+			Self::ToggleFlag { flags, bit } => format!("ftgl {flags:?}, {bit}"),
 			Self::Swap { left, right } => format!("swp {left:?}, {right:?}"),
 			Self::Abs { val } => format!("abs {val:?}"),
 			Self::Pow { base, exp } => format!("pow {base:?}, {exp}"),
+			Self::Rand { left, min, max } => format!("rand {left:?}, {min}, {max}"),
+			Self::UUIDToString { left, right } => format!("uuidstr {left:?}, {right:?}"),
+			Self::StringToUUID { left, right } => format!("struuid {left:?}, {right:?}"),
 			Self::Get { value, scale } => format!("get {value:?} {scale}"),
 			Self::Merge { left, right } => format!("merge {left:?}, {right:?}"),
 			Self::Push { left, right } => format!("push {left:?}, {right:?}"),
@@ -282,6 +542,7 @@ impl Debug for InstrKind {
 			Self::Use { val } => format!("use {val:?}"),
 			Self::Call { call } => format!("call {call:?}"),
 			Self::CallExtern { func } => format!("callx {func}"),
+			Self::CallWith { func, args } => format!("callw {func}, {args:?}"),
 			Self::If { condition, body } => format!("if {condition:?}: {body:?}"),
 			Self::IfElse {
 				condition,
@@ -289,14 +550,64 @@ impl Debug for InstrKind {
 				second,
 			} => format!("if {condition:?}: {first:?} else {second:?}"),
 			Self::Remove { val } => format!("rm {val:?}"),
+			Self::Cast {
+				left,
+				ty,
+				right,
+				scale,
+			} => format!("cast {left:?}, {ty:?} {right:?}, {scale}"),
+			Self::CopyData { from, to, paths } => format!("copy {to:?}, {from:?}, {paths:?}"),
 			Self::ReturnValue { index, value } => format!("retv {index} {value:?}"),
 			Self::Return { value } => format!("ret {value:?}"),
 			Self::ReturnRun { body } => format!("retr {body:?}"),
+			Self::Label { name } => format!("label {name}"),
+			Self::Goto { label } => format!("goto {label}"),
+			Self::SpreadOverTicks {
+				per_tick,
+				item,
+				ty,
+				list,
+				on_complete,
+				body,
+			} => match on_complete {
+				Some(func) => format!("spread {per_tick}, {item}: {ty:?}, {list:?}, {func} {body:?}"),
+				None => format!("spread {per_tick}, {item}: {ty:?}, {list:?} {body:?}"),
+			},
 			Self::Command { command } => format!("cmd {command}"),
+			Self::InterpolatedCommand { command } => format!("icmd {command}"),
+			Self::MacroCommand { command } => format!("mcmd {command}"),
 			Self::Comment { comment } => format!("cmt {comment}"),
 			Self::Modify { modifier, body } => format!("mdf {modifier:?}: {body:?}"),
+			Self::Assert { condition, message } => format!("assert {condition:?}, {message}"),
+			Self::Atomic { body } => format!("atomic {body:?}"),
+			Self::Loop {
+				condition,
+				body,
+				check,
+			} => match check {
+				LoopCheck::Before => format!("while {condition:?}: {body:?}"),
+				LoopCheck::After => format!("dow {condition:?}: {body:?}"),
+			},
+			Self::SummonAs {
+				entity,
+				nbt,
+				uuid,
+				body,
+			} => match uuid {
+				Some(uuid) => format!("smna {entity} {nbt:?} {uuid:?}: {body:?}"),
+				None => format!("smna {entity} {nbt:?}: {body:?}"),
+			},
 			Self::MC(instr) => format!("{instr:?}"),
 		};
 		write!(f, "{text}")
 	}
 }
+
+/// When the condition of a loop is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopCheck {
+	/// Before every iteration, so the body might never run
+	Before,
+	/// After every iteration, so the body always runs at least once
+	After,
+}
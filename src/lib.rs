@@ -1,40 +1,177 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
 use anyhow::Context;
-use common::IRType;
-use ir::IR;
+use common::diag::Diagnostic;
+use common::function::FunctionInterface;
+use common::snippet::prepare_snippets;
+use common::{IRType, ResourceLocation};
+use ir::{Block, IRFunction, InstrKind, Instruction, IR};
+use lir::LIR;
+use output::datapack::stream::{PackWriter, StreamReport};
 use output::datapack::Datapack;
+use output::hook::{apply_function_hooks, FunctionHook};
+use parse::Parser;
 use project::ProjectSettings;
+use rustc_hash::FxHashSet;
 
+use crate::lower::bitwise::legalize_bitwise;
+use crate::lower::rand::legalize_rands;
+use crate::lower::uuid::legalize_uuids;
 use crate::lower::{ir_to_mir::lower_ir, mir_to_lir::lower_mir};
-use crate::output::link;
-use crate::passes::{run_ir_passes, run_lir_passes, run_mir_passes};
+use crate::output::{link, link_streaming};
+use crate::passes::analysis::budget::{estimate_tick_budget, TickBudgetReport};
+use crate::passes::explain::OptimizationLog;
+use crate::passes::{run_ir_passes, run_lir_passes_with_log, run_mir_passes_with_log};
 
 pub mod common;
+pub mod determinism;
 pub mod ir;
 pub mod lir;
 pub mod lower;
 pub mod macros;
 pub mod mir;
 pub mod output;
+pub mod package;
 pub mod parse;
 pub mod passes;
 pub mod project;
+pub mod refactor;
+pub mod repl;
 mod util;
+pub mod workspace;
 
 /// Runs the full routine for lowering IR and producing a datapack
 pub fn codegen_ir(
+	ir: IR,
+	project: &ProjectSettings,
+	settings: CodegenIRSettings,
+) -> anyhow::Result<Datapack> {
+	let (lir, diagnostics, tick_budget) = lower_and_optimize(ir, project, &settings)?;
+
+	if settings.debug {
+		println!("Doing codegen...");
+	}
+	let mut datapack = link(lir, project).context("Failed to link datapack")?;
+	datapack.diagnostics = diagnostics;
+	datapack.tick_budget = tick_budget;
+	if settings.debug {
+		dbg!(&datapack);
+	}
+
+	Ok(datapack)
+}
+
+/// Runs the same routine as codegen_ir, but only compiles the given functions and the
+/// functions that they call, producing a partial datapack. Useful for previewing the
+/// output of a few functions without compiling the whole project
+pub fn codegen_ir_subset(
 	mut ir: IR,
+	functions: &[ResourceLocation],
+	project: &ProjectSettings,
+	settings: CodegenIRSettings,
+) -> anyhow::Result<Datapack> {
+	let mut included = FxHashSet::default();
+	let mut stack = functions.to_vec();
+	while let Some(func_id) = stack.pop() {
+		if included.contains(&func_id) {
+			continue;
+		}
+		let func = ir
+			.functions
+			.get(&func_id)
+			.with_context(|| format!("Function {func_id} does not exist"))?;
+		// Functions from other packs won't be in the IR
+		stack.extend(
+			func.block
+				.get_callees()
+				.into_iter()
+				.filter(|x| ir.functions.contains_key(*x))
+				.cloned(),
+		);
+		included.insert(func_id);
+	}
+	ir.functions.retain(|func_id, _| included.contains(func_id));
+
+	codegen_ir(ir, project, settings)
+}
+
+/// Parses several source files and compiles them together as one project
+pub fn compile_project(
+	files: &[PathBuf],
 	project: &ProjectSettings,
 	settings: CodegenIRSettings,
 ) -> anyhow::Result<Datapack> {
+	let ir = parse_files(files)?;
+	codegen_ir(ir, project, settings)
+}
+
+/// Parses several source files into one IR. The files are parsed in order, so types
+/// declared in a file can be used by the files after it. Functions can only be defined once
+pub fn parse_files(files: &[PathBuf]) -> anyhow::Result<IR> {
+	let mut parser = Parser::new();
+	for file in files {
+		let text = std::fs::read_to_string(file)
+			.with_context(|| format!("Failed to read source file {file:?}"))?;
+		parser
+			.parse(&text)
+			.with_context(|| format!("Failed to parse source file {file:?}"))?;
+	}
+
+	Ok(parser.finish())
+}
+
+/// Runs the same routine as codegen_ir, but writes the functions of the datapack to
+/// a directory in batches of the given size as they are generated instead of keeping
+/// them all in memory. Passes that look at the whole program still run first
+pub fn codegen_ir_streaming(
+	ir: IR,
+	project: &ProjectSettings,
+	settings: CodegenIRSettings,
+	path: &Path,
+	batch_size: usize,
+) -> anyhow::Result<StreamReport> {
+	let (lir, diagnostics, tick_budget) = lower_and_optimize(ir, project, &settings)?;
+
+	if settings.debug {
+		println!("Doing codegen...");
+	}
+	let mut writer = PackWriter::new(
+		path,
+		project.get_directory_layout(),
+		project.text_format,
+		batch_size,
+	)
+	.context("Failed to create pack writer")?;
+	let rest = link_streaming(lir, project, &mut writer).context("Failed to link datapack")?;
+	let mut report = writer.finish(rest).context("Failed to output datapack")?;
+	report.diagnostics = diagnostics;
+	report.tick_budget = tick_budget;
+	Ok(report)
+}
+
+/// Lowers IR all the way to LIR, running the passes that are enabled.
+/// Also returns the warnings that were found along the way, and the tick budget
+/// report if it was enabled
+fn lower_and_optimize(
+	mut ir: IR,
+	project: &ProjectSettings,
+	settings: &CodegenIRSettings,
+) -> anyhow::Result<(LIR, Vec<Diagnostic>, Option<TickBudgetReport>)> {
 	if settings.debug {
 		println!("IR:");
 		dbg!(&ir.functions);
 	}
+	prepare_snippets(&mut ir, project).context("Invalid snippets")?;
 	if settings.ir_passes {
 		run_ir_passes(&mut ir, project, settings.debug).context("IR passes failed")?;
 	}
 
+	let mut log = settings.explain.is_some().then(OptimizationLog::new);
 	let mut mir = lower_ir(ir).context("Failed to lower IR")?;
+	legalize_rands(&mut mir, project).context("Failed to lower random numbers")?;
+	legalize_bitwise(&mut mir, project).context("Failed to lower bitwise operations")?;
+	legalize_uuids(&mut mir, project).context("Failed to lower UUID conversions")?;
 	let init_count = mir.instr_count();
 	if settings.debug {
 		println!("MIR:");
@@ -42,7 +179,8 @@ pub fn codegen_ir(
 	}
 
 	if settings.mir_passes {
-		run_mir_passes(&mut mir, project, settings.debug).context("MIR passes failed")?;
+		run_mir_passes_with_log(&mut mir, project, settings.debug, log.as_mut())
+			.context("MIR passes failed")?;
 		if settings.debug {
 			println!("Optimized MIR:");
 			dbg!(&mir.functions);
@@ -58,6 +196,9 @@ pub fn codegen_ir(
 		println!("Removed percent: {pct}%");
 	}
 
+	// Sort the warnings so that they don't depend on the order that functions were visited in
+	let mut diagnostics = std::mem::take(&mut mir.diagnostics);
+	diagnostics.sort_by_key(|x| (x.span.map(|span| span.start), x.message.clone()));
 	let mut lir = lower_mir(mir).context("Failed to lower MIR")?;
 	let init_count = lir.instr_count();
 	if settings.debug {
@@ -65,7 +206,8 @@ pub fn codegen_ir(
 		dbg!(&lir.functions);
 	}
 	if settings.lir_passes {
-		run_lir_passes(&mut lir, project, settings.debug).context("LIR passes failed")?;
+		run_lir_passes_with_log(&mut lir, project, settings.debug, log.as_mut())
+			.context("LIR passes failed")?;
 		if settings.debug {
 			println!("Optimized LIR:");
 			dbg!(&lir.functions);
@@ -82,15 +224,58 @@ pub fn codegen_ir(
 		println!("Removed percent: {pct}%");
 	}
 
-	if settings.debug {
-		println!("Doing codegen...");
-	}
-	let datapack = link(lir, project).context("Failed to link datapack")?;
-	if settings.debug {
-		dbg!(&datapack);
+	apply_function_hooks(&mut lir, &settings.function_hooks);
+
+	let tick_budget = settings.tick_budget.then(|| estimate_tick_budget(&lir));
+	if let (Some(path), Some(log)) = (&settings.explain, log) {
+		if let Some(parent) = path.parent() {
+			let _ = std::fs::create_dir_all(parent);
+		}
+		std::fs::write(path, log.report()).context("Failed to write optimization report")?;
 	}
 
-	Ok(datapack)
+	Ok((lir, diagnostics, tick_budget))
+}
+
+/// Compiles a single instruction to the commands that it produces, without
+/// running any passes. Useful for tools that want to preview the output of an instruction
+pub fn compile_single_instruction(
+	instr: InstrKind,
+	project: &ProjectSettings,
+) -> anyhow::Result<Vec<String>> {
+	compile_instructions(vec![instr], project)
+}
+
+/// Compiles a list of instructions as if they were the body of a function,
+/// without running any passes
+pub fn compile_instructions(
+	instrs: Vec<InstrKind>,
+	project: &ProjectSettings,
+) -> anyhow::Result<Vec<String>> {
+	let func_id = ResourceLocation::from("dpc:single");
+	let mut interface = FunctionInterface::new(func_id.clone());
+	interface.annotations.preserve = true;
+	let mut block = Block::new();
+	block
+		.contents
+		.extend(instrs.into_iter().map(Instruction::new));
+
+	let mut ir = IR::new();
+	ir.functions
+		.insert(func_id.clone(), IRFunction { interface, block });
+
+	let mut mir = lower_ir(ir).context("Failed to lower IR")?;
+	legalize_rands(&mut mir, project).context("Failed to lower random numbers")?;
+	legalize_bitwise(&mut mir, project).context("Failed to lower bitwise operations")?;
+	legalize_uuids(&mut mir, project).context("Failed to lower UUID conversions")?;
+	let lir = lower_mir(mir).context("Failed to lower MIR")?;
+	let mut datapack = link(lir, project).context("Failed to link datapack")?;
+	let func = datapack
+		.functions
+		.remove(&func_id)
+		.context("Function is missing after codegen")?;
+
+	Ok(func.contents)
 }
 
 /// Settings for the codegen_ir utility function
@@ -101,6 +286,13 @@ pub struct CodegenIRSettings {
 	pub ir_passes: bool,
 	pub mir_passes: bool,
 	pub lir_passes: bool,
+	/// Whether to estimate the commands run per tick by functions
+	/// annotated with a frequency, which is returned with the pack
+	pub tick_budget: bool,
+	/// A file to write a report of the optimizations that changed each function to
+	pub explain: Option<PathBuf>,
+	/// Hooks that add instructions to the start and end of every function before codegen
+	pub function_hooks: Vec<Arc<dyn FunctionHook>>,
 }
 
 impl CodegenIRSettings {
@@ -112,6 +304,9 @@ impl CodegenIRSettings {
 			ir_passes: true,
 			mir_passes: false,
 			lir_passes: false,
+			tick_budget: false,
+			explain: None,
+			function_hooks: Vec::new(),
 		}
 	}
 }
@@ -121,3 +316,108 @@ impl Default for CodegenIRSettings {
 		Self::new()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::mc::instr::MinecraftInstr;
+
+	#[test]
+	fn test_compile_single_instruction() {
+		let instr = InstrKind::MC(MinecraftInstr::Say {
+			message: "Hello".into(),
+		});
+		let cmds = compile_single_instruction(instr, &ProjectSettings::new("dpc".into()))
+			.expect("Failed to compile instruction");
+		assert_eq!(cmds, vec![String::from("say Hello")]);
+	}
+
+	#[test]
+	fn test_codegen_ir_subset() {
+		let mut parser = Parser::new();
+		parser
+			.parse(
+				r#"
+				"test:a" { call "test:b"; }
+				"test:b" { sch "test:c", 5t, replace; callx "other:d"; }
+				"test:c" { say "c"; }
+				"test:unused" { say "unused"; }
+				"#,
+			)
+			.expect("Failed to parse");
+		let ir = parser.finish();
+		let project = ProjectSettings::new("test".into());
+
+		let pack = codegen_ir_subset(
+			ir.clone(),
+			&["test:b".into()],
+			&project,
+			CodegenIRSettings::new(),
+		)
+		.expect("Failed to compile subset");
+		let mut funcs: Vec<_> = pack.functions.keys().map(|x| x.to_string()).collect();
+		funcs.sort();
+		assert_eq!(funcs, ["test:b", "test:c"]);
+
+		assert!(
+			codegen_ir_subset(ir, &["test:x".into()], &project, CodegenIRSettings::new()).is_err()
+		);
+	}
+
+	#[test]
+	fn test_parse_files() {
+		let dir = std::env::temp_dir().join("dpc_test_parse_files");
+		std::fs::create_dir_all(&dir).expect("Failed to create directory");
+		let write = |name: &str, text: &str| {
+			let path = dir.join(name);
+			std::fs::write(&path, text).expect("Failed to write file");
+			path
+		};
+		let a = write("a.dpc", r#"enum Mode { On, Off } "test:a" { say "a"; }"#);
+		let b = write("b.dpc", r#""test:b" { let x: enum<Mode> = val Mode::Off; }"#);
+		let dup = write("dup.dpc", r#""test:a" { say "again"; }"#);
+
+		let ir = parse_files(&[a.clone(), b]).expect("Failed to parse files");
+		assert_eq!(ir.functions.len(), 2);
+		assert!(parse_files(&[a, dup]).is_err());
+	}
+
+	#[test]
+	fn test_lint_diagnostics() {
+		let mut parser = Parser::new();
+		parser
+			.parse(
+				r#"
+				"test:main" {
+					let x: score = val 0s;
+					if eq %x, 1s: say "never";
+					let hp: score = criteria "health" of @s[];
+					set %hp, 20s;
+					kill @e[];
+					tims 0.01s;
+					@allow broad_selector kill @e[];
+				}
+				"#,
+			)
+			.expect("Failed to parse");
+		let project = ProjectSettings::new("test".into());
+		let pack = codegen_ir(parser.finish(), &project, CodegenIRSettings::new())
+			.expect("Failed to compile");
+
+		let found: Vec<_> = pack
+			.diagnostics
+			.iter()
+			.map(|x| (x.code, x.span.map(|span| span.line)))
+			.collect();
+		assert_eq!(
+			found,
+			[
+				(Some("impossible_condition"), Some(4)),
+				(Some("criteria_write"), Some(6)),
+				(Some("broad_selector"), Some(7)),
+				(Some("lossy_time"), Some(8)),
+			]
+		);
+	}
+}
@@ -6,9 +6,13 @@ use rustc_hash::FxHashMap;
 use crate::common::block::Block;
 use crate::common::function::FunctionInterface;
 use crate::common::mc::instr::MinecraftInstr;
+use crate::common::mc::scoreboard_and_teams::Criterion;
 use crate::common::mc::modifier::{IfModCondition, IfScoreCondition, IfScoreRangeEnd, Modifier};
+use crate::common::mc::text::TextComponentContents;
 use crate::common::reg::{GetUsedLocals, GetUsedRegs, Local};
-use crate::common::ty::Double;
+use crate::common::span::Span;
+use crate::common::storage::StorageDeclarations;
+use crate::common::ty::{Double, NBTTypeContents};
 use crate::common::val::{ArgRetIndex, MutableNBTValue, MutableScoreValue, NBTValue, ScoreValue};
 use crate::common::{FunctionTrait, IRType, Identifier, RegisterList, ResourceLocation};
 use crate::passes::util::AnalysisResult;
@@ -16,12 +20,21 @@ use crate::passes::util::AnalysisResult;
 #[derive(Debug, Clone)]
 pub struct LIR {
 	pub functions: FxHashMap<ResourceLocation, LIRFunction>,
+	/// Scoreboard objectives with criteria that the pack needs
+	pub objectives: FxHashMap<String, Criterion>,
+	/// Constants that are put in register storage when the pack loads
+	pub storage_consts: FxHashMap<String, NBTTypeContents>,
+	/// Storages that the pack declares
+	pub storages: StorageDeclarations,
 }
 
 impl LIR {
 	pub fn new() -> Self {
 		Self {
 			functions: FxHashMap::default(),
+			objectives: FxHashMap::default(),
+			storage_consts: FxHashMap::default(),
+			storages: StorageDeclarations::default(),
 		}
 	}
 
@@ -31,6 +44,9 @@ impl LIR {
 				function_capacity,
 				BuildHasherDefault::default(),
 			),
+			objectives: FxHashMap::default(),
+			storage_consts: FxHashMap::default(),
+			storages: StorageDeclarations::default(),
 		}
 	}
 }
@@ -112,6 +128,8 @@ impl Debug for LIRBlock {
 pub struct LIRInstruction {
 	pub kind: LIRInstrKind,
 	pub modifiers: Vec<Modifier>,
+	/// Where the instruction came from in the source
+	pub span: Option<Span>,
 }
 
 impl LIRInstruction {
@@ -120,7 +138,11 @@ impl LIRInstruction {
 	}
 
 	pub fn with_modifiers(kind: LIRInstrKind, modifiers: Vec<Modifier>) -> Self {
-		Self { kind, modifiers }
+		Self {
+			kind,
+			modifiers,
+			span: None,
+		}
 	}
 }
 
@@ -182,10 +204,14 @@ pub enum LIRInstrKind {
 	Use(Local),
 	NoOp,
 	Call(ResourceLocation, Vec<Local>),
+	/// Calls a function with a compound as its macro arguments
+	CallWith(ResourceLocation, NBTValue),
 	ReturnValue(i32),
 	ReturnFail,
 	ReturnRun(Box<LIRInstruction>),
 	Command(String),
+	/// A command that is run as a macro line
+	MacroCommand(String),
 	Comment(String),
 	MC(MinecraftInstr),
 }
@@ -215,10 +241,12 @@ impl Debug for LIRInstrKind {
 			Self::Use(val) => format!("use {val:?}"),
 			Self::NoOp => "no".into(),
 			Self::Call(fun, ..) => format!("call {fun}"),
+			Self::CallWith(fun, args) => format!("callw {fun} {args:?}"),
 			Self::ReturnValue(val) => format!("retv {val}"),
 			Self::ReturnFail => "retf".into(),
 			Self::ReturnRun(cmd) => format!("retr {cmd:?}"),
 			Self::Command(cmd) => format!("cmd {cmd}"),
+			Self::MacroCommand(cmd) => format!("mcmd {cmd}"),
 			Self::Comment(cmt) => format!("cmt {cmt}"),
 			Self::MC(instr) => format!("{instr:?}"),
 		};
@@ -264,13 +292,15 @@ impl GetUsedRegs for LIRInstrKind {
 					loc.append_used_regs(regs);
 				}
 			}
+			LIRInstrKind::CallWith(_, args) => args.append_used_regs(regs),
 			LIRInstrKind::NoOp
 			| LIRInstrKind::GetConst(..)
 			| LIRInstrKind::ReturnValue(..)
 			| LIRInstrKind::ReturnFail
 			| LIRInstrKind::Command(..)
-			| LIRInstrKind::Comment(..)
-			| LIRInstrKind::MC(..) => {}
+			| LIRInstrKind::MacroCommand(..)
+			| LIRInstrKind::Comment(..) => {}
+			LIRInstrKind::MC(instr) => instr.append_used_regs(regs),
 			LIRInstrKind::ReturnRun(body) => body.append_used_regs(regs),
 		}
 	}
@@ -312,13 +342,15 @@ impl GetUsedLocals for LIRInstrKind {
 			LIRInstrKind::Call(_, locs) => {
 				locals.extend(locs);
 			}
+			LIRInstrKind::CallWith(_, args) => args.append_used_locals(locals),
 			LIRInstrKind::NoOp
 			| LIRInstrKind::GetConst(..)
 			| LIRInstrKind::ReturnValue(..)
 			| LIRInstrKind::ReturnFail
 			| LIRInstrKind::Command(..)
-			| LIRInstrKind::Comment(..)
-			| LIRInstrKind::MC(..) => {}
+			| LIRInstrKind::MacroCommand(..)
+			| LIRInstrKind::Comment(..) => {}
+			LIRInstrKind::MC(instr) => instr.append_used_locals(locals),
 			LIRInstrKind::ReturnRun(body) => body.append_used_locals(locals),
 		}
 	}
@@ -378,6 +410,15 @@ impl LIRInstrKind {
 			LIRInstrKind::ResetScore(val) => {
 				f(val);
 			}
+			LIRInstrKind::MC(instr) => {
+				for text in instr.get_text_components_mut() {
+					for contents in text.get_all_contents_mut() {
+						if let TextComponentContents::Score(val) = contents {
+							f(val);
+						}
+					}
+				}
+			}
 			_ => {}
 		}
 	}
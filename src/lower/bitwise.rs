@@ -0,0 +1,340 @@
+use anyhow::bail;
+use rustc_hash::FxHashMap;
+
+use crate::common::condition::Condition;
+use crate::common::function::{CallInterface, FunctionInterface, FunctionSignature, ReturnType};
+use crate::common::ty::{DataType, DataTypeContents, ScoreType, ScoreTypeContents};
+use crate::common::val::{MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier, ResourceLocation};
+use crate::mir::{MIRBlock, MIRFunction, MIRInstrKind, MIRInstruction, MIR};
+use crate::output::get_generated_fn_id;
+use crate::project::ProjectSettings;
+
+/// The kinds of bitwise operations that can need a helper function
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum BitOp {
+	And,
+	Or,
+	Xor,
+	Shl,
+	Shr,
+}
+
+impl BitOp {
+	fn get_name(&self) -> &'static str {
+		match self {
+			Self::And => "and",
+			Self::Or => "or",
+			Self::Xor => "xor",
+			Self::Shl => "shl",
+			Self::Shr => "shr",
+		}
+	}
+}
+
+/// Replaces bitwise instructions with score operations. Operations with a constant
+/// that can be done with arithmetic are done in place, and the rest call generated
+/// helper functions that go over every bit of the operands
+pub fn legalize_bitwise(mir: &mut MIR, project: &ProjectSettings) -> anyhow::Result<()> {
+	let mut helpers = FxHashMap::default();
+	for func in mir.functions.values_mut() {
+		legalize_block(&mut func.block, project, &mut helpers)?;
+	}
+
+	for (op, id) in helpers {
+		if mir.functions.contains_key(&id) {
+			bail!("Function {id} generated for bitwise operations conflicts with an existing function");
+		}
+		let sig = FunctionSignature::with_all(
+			vec![score_ty(), score_ty()],
+			ReturnType::Standard(vec![score_ty()]),
+		);
+		let func = MIRFunction {
+			interface: FunctionInterface::with_signature(id.clone(), sig),
+			block: MIRBlock::with_contents(
+				gen_helper(op)
+					.into_iter()
+					.map(MIRInstruction::new)
+					.collect(),
+			),
+		};
+		mir.functions.insert(id, func);
+	}
+
+	Ok(())
+}
+
+fn legalize_block(
+	block: &mut MIRBlock,
+	project: &ProjectSettings,
+	helpers: &mut FxHashMap<BitOp, ResourceLocation>,
+) -> anyhow::Result<()> {
+	let mut contents = Vec::with_capacity(block.contents.len());
+	for mut instr in std::mem::take(&mut block.contents) {
+		if let MIRInstrKind::ReturnRun { body } = &mut instr.kind {
+			legalize_block(body, project, helpers)?;
+		}
+		for body in instr.kind.get_bodies_mut() {
+			legalize_block(body, project, helpers)?;
+		}
+
+		let (op, left, right) = match instr.kind {
+			MIRInstrKind::BitAnd { left, right } => (BitOp::And, left, right),
+			MIRInstrKind::BitOr { left, right } => (BitOp::Or, left, right),
+			MIRInstrKind::BitXor { left, right } => (BitOp::Xor, left, right),
+			MIRInstrKind::Shl { left, right } => (BitOp::Shl, left, right),
+			MIRInstrKind::Shr { left, right } => (BitOp::Shr, left, right),
+			_ => {
+				contents.push(instr);
+				continue;
+			}
+		};
+
+		let kinds = match &right {
+			Value::Constant(DataTypeContents::Score(val)) => {
+				lower_constant_op(op, &left, val.get_i32())
+			}
+			_ => None,
+		};
+		let kinds = match kinds {
+			Some(kinds) => kinds,
+			None => {
+				let function = helpers
+					.entry(op)
+					.or_insert_with(|| {
+						get_generated_fn_id(project, &format!("bit/{}", op.get_name())).into()
+					})
+					.clone();
+				vec![MIRInstrKind::Call {
+					call: CallInterface {
+						function,
+						args: vec![Value::Mutable(left.clone()), right],
+						ret: vec![left],
+					},
+				}]
+			}
+		};
+		contents.extend(kinds.into_iter().map(|kind| MIRInstruction {
+			kind,
+			allowed_lints: instr.allowed_lints.clone(),
+			span: instr.span,
+		}));
+	}
+	block.contents = contents;
+
+	Ok(())
+}
+
+/// Lowers an operation with a constant right side to arithmetic, if it can be
+fn lower_constant_op(op: BitOp, left: &MutableValue, right: i32) -> Option<Vec<MIRInstrKind>> {
+	let out = match (op, right) {
+		(BitOp::And, -1) | (BitOp::Or | BitOp::Xor, 0) => Vec::new(),
+		(BitOp::And, 0) => vec![assign(left, 0)],
+		(BitOp::Or, -1) => vec![assign(left, -1)],
+		// Flipping every bit is the same as negating and subtracting one
+		(BitOp::Xor, -1) => vec![
+			MIRInstrKind::Mul {
+				left: left.clone(),
+				right: constant(-1),
+			},
+			MIRInstrKind::Sub {
+				left: left.clone(),
+				right: constant(1),
+			},
+		],
+		// A mask of the low bits is a modulo, since scoreboard modulo is never negative
+		(BitOp::And, mask) if mask > 0 && mask < i32::MAX && (mask + 1).count_ones() == 1 => {
+			vec![MIRInstrKind::Mod {
+				left: left.clone(),
+				right: constant(mask + 1),
+			}]
+		}
+		(BitOp::Shl, amount) => match amount & 31 {
+			0 => Vec::new(),
+			amount => vec![MIRInstrKind::Mul {
+				left: left.clone(),
+				right: constant(pow2(amount)),
+			}],
+		},
+		// Scoreboard division rounds down, which is an arithmetic shift
+		(BitOp::Shr, amount) => match amount & 31 {
+			0 => Vec::new(),
+			31 => vec![
+				MIRInstrKind::Div {
+					left: left.clone(),
+					right: constant(pow2(30)),
+				},
+				MIRInstrKind::Div {
+					left: left.clone(),
+					right: constant(2),
+				},
+			],
+			amount => vec![MIRInstrKind::Div {
+				left: left.clone(),
+				right: constant(pow2(amount)),
+			}],
+		},
+		_ => return None,
+	};
+
+	Some(out)
+}
+
+/// Generates the body of the helper function for an operation
+fn gen_helper(op: BitOp) -> Vec<MIRInstrKind> {
+	let mut out = Vec::new();
+	let reg = |name: &str| MutableValue::Reg(Identifier::from(name));
+	let declare = |out: &mut Vec<MIRInstrKind>, name: &str, val: Value| {
+		out.push(MIRInstrKind::Declare {
+			left: name.into(),
+			ty: score_ty(),
+		});
+		out.push(MIRInstrKind::Assign {
+			left: reg(name),
+			right: DeclareBinding::Value(val),
+		});
+	};
+
+	match op {
+		BitOp::And | BitOp::Or | BitOp::Xor => {
+			declare(&mut out, "left", Value::Mutable(MutableValue::Arg(0)));
+			declare(&mut out, "right", Value::Mutable(MutableValue::Arg(1)));
+			declare(&mut out, "out", constant(0));
+			declare(&mut out, "left_bit", constant(0));
+			declare(&mut out, "right_bit", constant(0));
+
+			// Go over each bit from the lowest, taking it off of the operands and adding it
+			// to the output with its place value from the table of powers of two
+			for bit in 0..32 {
+				for (val, bit_val) in [("left", "left_bit"), ("right", "right_bit")] {
+					out.push(MIRInstrKind::Assign {
+						left: reg(bit_val),
+						right: DeclareBinding::Value(Value::Mutable(reg(val))),
+					});
+					out.push(MIRInstrKind::Mod {
+						left: reg(bit_val),
+						right: constant(2),
+					});
+				}
+				let is_set = |name: &str| Condition::Equal(Value::Mutable(reg(name)), constant(1));
+				let condition = match op {
+					BitOp::And => {
+						Condition::And(Box::new(is_set("left_bit")), Box::new(is_set("right_bit")))
+					}
+					BitOp::Or => {
+						Condition::Or(Box::new(is_set("left_bit")), Box::new(is_set("right_bit")))
+					}
+					_ => Condition::Not(Box::new(Condition::Equal(
+						Value::Mutable(reg("left_bit")),
+						Value::Mutable(reg("right_bit")),
+					))),
+				};
+				out.push(MIRInstrKind::If {
+					condition,
+					body: Box::new(MIRBlock::with_contents(vec![MIRInstruction::new(
+						MIRInstrKind::Add {
+							left: reg("out"),
+							right: constant(pow2(bit)),
+						},
+					)])),
+				});
+				if bit != 31 {
+					for val in ["left", "right"] {
+						out.push(MIRInstrKind::Div {
+							left: reg(val),
+							right: constant(2),
+						});
+					}
+				}
+			}
+		}
+		BitOp::Shl | BitOp::Shr => {
+			declare(&mut out, "out", Value::Mutable(MutableValue::Arg(0)));
+			declare(&mut out, "amount", Value::Mutable(MutableValue::Arg(1)));
+			out.push(MIRInstrKind::Mod {
+				left: reg("amount"),
+				right: constant(32),
+			});
+			declare(&mut out, "factor", constant(1));
+
+			// Look up the power of two for the shift amount. Two to the 31st doesn't fit
+			// in a score, so it wraps to the lowest score when shifting left and is
+			// split into two divisions when shifting right
+			let max = if op == BitOp::Shl { 31 } else { 30 };
+			for amount in 1..=max {
+				out.push(MIRInstrKind::If {
+					condition: Condition::Equal(Value::Mutable(reg("amount")), constant(amount)),
+					body: Box::new(MIRBlock::with_contents(vec![MIRInstruction::new(assign(
+						&reg("factor"),
+						pow2(amount),
+					))])),
+				});
+			}
+			if op == BitOp::Shl {
+				out.push(MIRInstrKind::Mul {
+					left: reg("out"),
+					right: Value::Mutable(reg("factor")),
+				});
+			} else {
+				out.push(MIRInstrKind::Div {
+					left: reg("out"),
+					right: Value::Mutable(reg("factor")),
+				});
+				let body = lower_constant_op(BitOp::Shr, &reg("out"), 31)
+					.expect("Constant shift should lower")
+					.into_iter()
+					.map(MIRInstruction::new)
+					.collect();
+				out.push(MIRInstrKind::If {
+					condition: Condition::Equal(Value::Mutable(reg("amount")), constant(31)),
+					body: Box::new(MIRBlock::with_contents(body)),
+				});
+			}
+		}
+	}
+
+	out.push(MIRInstrKind::ReturnValue {
+		index: 0,
+		value: Value::Mutable(reg("out")),
+	});
+
+	out
+}
+
+/// Gets two to a power, with the 31st power wrapping around to the lowest score
+fn pow2(exp: i32) -> i32 {
+	(1u32 << exp) as i32
+}
+
+fn assign(left: &MutableValue, val: i32) -> MIRInstrKind {
+	MIRInstrKind::Assign {
+		left: left.clone(),
+		right: DeclareBinding::Value(constant(val)),
+	}
+}
+
+fn constant(val: i32) -> Value {
+	Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(val)))
+}
+
+fn score_ty() -> DataType {
+	DataType::Score(ScoreType::Score)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_constant_masks() {
+		let left = MutableValue::Reg("x".into());
+		assert!(matches!(
+			lower_constant_op(BitOp::And, &left, 15).as_deref(),
+			Some([MIRInstrKind::Mod { .. }])
+		));
+		assert!(lower_constant_op(BitOp::And, &left, 6).is_none());
+		assert!(lower_constant_op(BitOp::And, &left, i32::MIN).is_none());
+		assert_eq!(lower_constant_op(BitOp::Shl, &left, 32), Some(Vec::new()));
+		assert_eq!(pow2(31), i32::MIN);
+	}
+}
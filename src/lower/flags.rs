@@ -0,0 +1,114 @@
+use crate::common::ty::{DataType, DataTypeContents, ScoreType, ScoreTypeContents};
+use crate::common::val::{MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier};
+use crate::ir::{Block, InstrKind, Instruction, IR};
+
+/// Replaces flag instructions with arithmetic on the score that holds the flags.
+/// The bit is taken out with a division and modulo, and then moved into place
+/// by adding the difference between the old and new bit times its place value
+pub fn legalize_flags(ir: &mut IR) -> anyhow::Result<()> {
+	for func in ir.functions.values_mut() {
+		let mut count = 0;
+		legalize_block(&mut func.block, &mut count);
+	}
+
+	Ok(())
+}
+
+fn legalize_block(block: &mut Block, count: &mut u32) {
+	let mut contents = Vec::with_capacity(block.contents.len());
+	for mut instr in std::mem::take(&mut block.contents) {
+		for body in instr.kind.get_bodies_mut() {
+			legalize_block(body, count);
+		}
+
+		let kinds = match instr.kind {
+			InstrKind::GetFlag { left, flags, bit } => {
+				let (temp, mut out) = get_bit(flags, bit, count);
+				out.push(InstrKind::Assign {
+					left,
+					right: Value::Mutable(temp),
+				});
+				out
+			}
+			InstrKind::SetFlag { flags, bit, right } => {
+				// flags -= (old - new) * 2^bit
+				let (temp, mut out) = get_bit(flags.clone(), bit, count);
+				if right != constant(0) {
+					out.push(InstrKind::Sub {
+						left: temp.clone(),
+						right,
+					});
+				}
+				if bit != 0 {
+					out.push(InstrKind::Mul {
+						left: temp.clone(),
+						right: constant(1 << bit),
+					});
+				}
+				out.push(InstrKind::Sub {
+					left: flags,
+					right: Value::Mutable(temp),
+				});
+				out
+			}
+			InstrKind::ToggleFlag { flags, bit } => {
+				// flags += (1 - 2 * old) * 2^bit
+				let (temp, mut out) = get_bit(flags.clone(), bit, count);
+				out.push(InstrKind::Mul {
+					left: temp.clone(),
+					right: constant(-2 << bit),
+				});
+				out.push(InstrKind::Add {
+					left: temp.clone(),
+					right: constant(1 << bit),
+				});
+				out.push(InstrKind::Add {
+					left: flags,
+					right: Value::Mutable(temp),
+				});
+				out
+			}
+			other => {
+				instr.kind = other;
+				contents.push(instr);
+				continue;
+			}
+		};
+
+		contents.extend(kinds.into_iter().map(|kind| Instruction {
+			kind,
+			allowed_lints: instr.allowed_lints.clone(),
+			span: instr.span,
+		}));
+	}
+	block.contents = contents;
+}
+
+/// Declares a new temporary register holding a bit of some flags
+fn get_bit(flags: MutableValue, bit: u8, count: &mut u32) -> (MutableValue, Vec<InstrKind>) {
+	let reg = Identifier::from(format!("__flag_{count}"));
+	*count += 1;
+	let temp = MutableValue::Reg(reg.clone());
+	let mut out = vec![InstrKind::Declare {
+		left: reg,
+		ty: DataType::Score(ScoreType::Score),
+		right: DeclareBinding::Value(Value::Mutable(flags)),
+	}];
+	if bit != 0 {
+		out.push(InstrKind::Div {
+			left: temp.clone(),
+			right: constant(1 << bit),
+		});
+	}
+	out.push(InstrKind::Mod {
+		left: temp.clone(),
+		right: constant(2),
+	});
+
+	(temp, out)
+}
+
+fn constant(val: i32) -> Value {
+	Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(val)))
+}
@@ -1,16 +1,113 @@
-use crate::common::{val::MutableValue, DeclareBinding};
-use crate::ir::{Block, InstrKind, IR};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use crate::common::condition::Condition;
+use crate::common::diag::{Diagnostic, WithSpan};
+use crate::common::function::{
+	CallInterface, FunctionAnnotations, FunctionInterface, FunctionSignature, ReturnType,
+};
+use crate::common::lint::{warn, Lint};
+use crate::common::mc::entity::{SelectorType, TargetSelector};
+use crate::common::mc::modifier::MIRModifier;
+use crate::common::mc::text::{TextComponent, TextComponentContents, TextStyle};
+use crate::common::mc::scoreboard_and_teams::Criterion;
+use crate::common::mc::{DataLocation, DataPath, EntityTarget, FullDataLocation, Score};
+use crate::common::reg::GetUsedRegs;
+use crate::common::ty::{
+	DataType, DataTypeContents, NBTCompoundTypeContents, NBTType, NBTTypeContents, ScoreType,
+	ScoreTypeContents,
+};
+use crate::common::val::{ArgRetIndex, Value};
+use crate::common::{val::MutableValue, DeclareBinding, Identifier, ResourceLocation};
+use crate::ir::{Block, InstrKind, LoopCheck, IR};
 use crate::mir::{MIRBlock, MIRFunction, MIRInstrKind, MIRInstruction, MIR};
+use crate::lower::flags::legalize_flags;
+use crate::lower::labels::legalize_labels;
+use crate::lower::spread::legalize_spreads;
+use crate::passes::analysis::atomic::check_atomic_scopes;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 /// Lower IR to MIR
-pub fn lower_ir(ir: IR) -> anyhow::Result<MIR> {
+pub fn lower_ir(mut ir: IR) -> anyhow::Result<MIR> {
+	check_atomic_scopes(&ir).context("Failed to check atomic scopes")?;
+	legalize_spreads(&mut ir).context("Failed to legalize spreads")?;
+	legalize_labels(&mut ir).context("Failed to legalize labels")?;
+	legalize_flags(&mut ir).context("Failed to legalize flags")?;
 	let mut mir = MIR::with_capacity(ir.functions.len());
+	mir.diagnostics = ir.diagnostics;
+	let existing_fns: FxHashSet<_> = ir.functions.keys().cloned().collect();
 
 	for (func_id, func) in ir.functions {
-		let mir_block = lower_block(func.block)?;
+		let mut criteria = FxHashMap::default();
+		get_criteria_regs(&func.block, &mut criteria);
+		let mut reg_types = FxHashMap::default();
+		get_declared_reg_types(&func.block, &mut reg_types);
+		let mut cx = LowerCx {
+			func_id: &func_id,
+			interface: &func.interface,
+			reg_types,
+			loop_fns: Vec::new(),
+			command_fns: Vec::new(),
+			body_depth: 0,
+			loop_depth: 0,
+			uses_assert_flag: false,
+		};
+		let mut mir_block = lower_block(func.block, &mut cx)?;
+		if cx.uses_assert_flag {
+			let declare = declare_assert_flag().into_iter().map(MIRInstruction::new);
+			mir_block.contents.splice(0..0, declare);
+		}
+		let mut loop_fns = cx.loop_fns;
+		let command_fns = cx.command_fns;
+		if !criteria.is_empty() {
+			bind_criteria_regs(
+				&mut mir_block,
+				&criteria,
+				&func_id,
+				&func.interface.annotations.allow,
+				&mut mir.diagnostics,
+			);
+			let replace_criteria = |val: &mut MutableValue| {
+				if let MutableValue::Reg(reg) = val {
+					if let Some((score, ..)) = criteria.get(reg) {
+						*val = MutableValue::Score(score.clone());
+					}
+				}
+			};
+			for instr in &mut mir_block.contents {
+				instr.kind.replace_mut_vals(&replace_criteria);
+			}
+			for loop_fn in &mut loop_fns {
+				loop_fn.block.replace_mut_vals(&replace_criteria);
+			}
+			for (score, criterion) in criteria.into_values() {
+				mir.objectives
+					.insert(score.objective.to_string(), criterion);
+			}
+		}
 
+		for loop_fn in loop_fns {
+			if existing_fns.contains(&loop_fn.interface.id) {
+				bail!(
+					"Function {} for a loop conflicts with an existing function",
+					loop_fn.interface.id
+				);
+			}
+			mir.functions.insert(loop_fn.interface.id.clone(), loop_fn);
+		}
+		for command_fn in command_fns {
+			if existing_fns.contains(&command_fn.interface.id) {
+				bail!(
+					"Function {} for an interpolated command conflicts with an existing function",
+					command_fn.interface.id
+				);
+			}
+			mir.functions
+				.insert(command_fn.interface.id.clone(), command_fn);
+		}
 		mir.functions.insert(
 			func_id,
 			MIRFunction {
@@ -19,15 +116,167 @@ pub fn lower_ir(ir: IR) -> anyhow::Result<MIR> {
 			},
 		);
 	}
+	mir.storages = ir.storages;
 
 	Ok(mir)
 }
 
-fn lower_block(block: Block) -> anyhow::Result<MIRBlock> {
+/// Finds registers that are bound to criteria objectives
+fn get_criteria_regs(block: &Block, out: &mut FxHashMap<Identifier, (Score, Criterion)>) {
+	for instr in &block.contents {
+		if let InstrKind::DeclareCriteria {
+			left,
+			criterion,
+			target,
+		} = &instr.kind
+		{
+			let score = Score::new(target.clone(), criterion.get_objective_name().into());
+			out.insert(left.clone(), (score, criterion.clone()));
+		}
+		for body in instr.kind.get_bodies() {
+			get_criteria_regs(body, out);
+		}
+	}
+}
+
+/// Warns about writes to criteria registers, since only the game should modify them,
+/// and binds the registers that receive call return values
+fn bind_criteria_regs(
+	block: &mut MIRBlock,
+	criteria: &FxHashMap<Identifier, (Score, Criterion)>,
+	func_id: &ResourceLocation,
+	func_allowed: &[Lint],
+	diagnostics: &mut Vec<Diagnostic>,
+) {
+	let is_criteria = |val: &MutableValue| matches!(val, MutableValue::Reg(reg) if criteria.contains_key(reg));
+	for instr in &mut block.contents {
+		let written = match &instr.kind {
+			MIRInstrKind::Swap { left, right } => is_criteria(left) || is_criteria(right),
+			MIRInstrKind::Call { call } => call.ret.iter().any(is_criteria),
+			other => other.get_op_lhs().is_some_and(is_criteria),
+		};
+		if written {
+			warn(
+				diagnostics,
+				Lint::CriteriaWrite,
+				instr.allowed_lints.iter().chain(func_allowed),
+				format!("Criteria register is written to in function {func_id}. The game may overwrite it"),
+				instr.span,
+			);
+		}
+
+		match &mut instr.kind {
+			MIRInstrKind::Call { call } => {
+				for ret in &mut call.ret {
+					if let MutableValue::Reg(reg) = ret {
+						if let Some((score, ..)) = criteria.get(reg) {
+							*ret = MutableValue::Score(score.clone());
+						}
+					}
+				}
+			}
+			MIRInstrKind::ReturnRun { body } => {
+				bind_criteria_regs(body, criteria, func_id, func_allowed, diagnostics)
+			}
+			other => {
+				for body in other.get_bodies_mut() {
+					bind_criteria_regs(body, criteria, func_id, func_allowed, diagnostics);
+				}
+			}
+		}
+	}
+}
+
+/// State for lowering a single function
+struct LowerCx<'a> {
+	func_id: &'a ResourceLocation,
+	interface: &'a FunctionInterface,
+	/// The types of all of the registers declared in the function
+	reg_types: FxHashMap<Identifier, DataType>,
+	/// Functions that were created for the loops in the function
+	loop_fns: Vec<MIRFunction>,
+	/// Functions that were created to run interpolated commands as macro lines
+	command_fns: Vec<MIRFunction>,
+	/// How many bodies deep the instructions being lowered are
+	body_depth: usize,
+	/// How many loops deep the instructions being lowered are
+	loop_depth: usize,
+	/// Whether failed asserts in bodies have to be passed out through the assert flag
+	uses_assert_flag: bool,
+}
+
+/// The register that asserts in bodies set when they fail. Bodies can end up in
+/// their own functions, where returning does not exit the function they are in,
+/// so the flag is checked again after the body
+const ASSERT_FLAG_REG: &str = "__assert_failed";
+
+fn declare_assert_flag() -> [MIRInstrKind; 2] {
+	let declare = MIRInstrKind::Declare {
+		left: ASSERT_FLAG_REG.into(),
+		ty: DataType::Score(ScoreType::Bool),
+	};
+	[declare, set_assert_flag(false)]
+}
+
+/// Returns failure from the function if a condition is true. In loop functions
+/// the assert flag is passed out first, since returning skips the return values
+/// at the end of the function
+fn fail_if(condition: Condition, cx: &LowerCx) -> Vec<MIRInstruction> {
+	let mut out = Vec::new();
+	if cx.loop_depth > 0 {
+		let ret = MIRInstruction::new(MIRInstrKind::ReturnValue {
+			index: 0,
+			value: Value::Mutable(MutableValue::Reg(ASSERT_FLAG_REG.into())),
+		});
+		out.push(MIRInstruction::new(MIRInstrKind::If {
+			condition: condition.clone(),
+			body: Box::new(MIRBlock::with_contents(vec![ret])),
+		}));
+	}
+	let fail = MIRInstruction::new(MIRInstrKind::ReturnFail);
+	out.push(MIRInstruction::new(MIRInstrKind::If {
+		condition,
+		body: Box::new(MIRBlock::with_contents(vec![fail])),
+	}));
+	out
+}
+
+fn set_assert_flag(value: bool) -> MIRInstrKind {
+	MIRInstrKind::Assign {
+		left: MutableValue::Reg(ASSERT_FLAG_REG.into()),
+		right: DeclareBinding::Value(Value::Constant(DataTypeContents::Score(
+			ScoreTypeContents::Bool(value),
+		))),
+	}
+}
+
+/// Lowers a block that is nested inside of another instruction
+fn lower_body(block: Block, cx: &mut LowerCx) -> anyhow::Result<MIRBlock> {
+	cx.body_depth += 1;
+	let out = lower_block(block, cx);
+	cx.body_depth -= 1;
+	out
+}
+
+fn contains_assert(block: &Block) -> bool {
+	block.contents.iter().any(|instr| {
+		matches!(instr.kind, InstrKind::Assert { .. })
+			|| instr.kind.get_bodies().into_iter().any(contains_assert)
+	})
+}
+
+fn lower_block(block: Block, cx: &mut LowerCx) -> anyhow::Result<MIRBlock> {
 	let mut mir_block = MIRBlock::with_capacity(block.contents.len());
 
 	for ir_instr in block.contents {
-		let instrs = lower_kind(ir_instr.kind).context("Failed to lower instruction")?;
+		let mut instrs = lower_kind(ir_instr.kind, cx)
+			.with_span(ir_instr.span)
+			.context("Failed to lower instruction")?;
+		// Suppressions and locations carry over to every instruction that this one lowers to
+		for instr in &mut instrs {
+			instr.allowed_lints.extend(ir_instr.allowed_lints.iter().copied());
+			instr.span = instr.span.or(ir_instr.span);
+		}
 		mir_block.contents.extend(instrs);
 	}
 
@@ -48,8 +297,16 @@ macro_rules! lower {
 	}
 }
 
-fn lower_kind(kind: InstrKind) -> anyhow::Result<Vec<MIRInstruction>> {
+fn lower_kind(kind: InstrKind, cx: &mut LowerCx) -> anyhow::Result<Vec<MIRInstruction>> {
 	let mut out = Vec::new();
+	let check_assert_flag = matches!(
+		kind,
+		InstrKind::If { .. }
+			| InstrKind::IfElse { .. }
+			| InstrKind::Modify { .. }
+			| InstrKind::SummonAs { .. }
+			| InstrKind::Loop { .. }
+	) && kind.get_bodies().into_iter().any(contains_assert);
 	let kind = match kind {
 		InstrKind::Declare { left, ty, right } => {
 			let left_clone = left.clone();
@@ -59,12 +316,23 @@ fn lower_kind(kind: InstrKind) -> anyhow::Result<Vec<MIRInstruction>> {
 				right,
 			})
 		}
+		// Uses of the register are replaced with the score after lowering
+		InstrKind::DeclareCriteria { .. } => MIRInstrKind::NoOp,
 		InstrKind::Assign { left, right } => {
 			lower!(MIRInstrKind::Assign {
 				left,
 				right: DeclareBinding::Value(right),
 			})
 		}
+		InstrKind::Cast {
+			left,
+			ty,
+			right,
+			scale,
+		} => lower!(MIRInstrKind::Assign {
+			left,
+			right: DeclareBinding::Cast(ty, right, scale),
+		}),
 		InstrKind::Add { left, right } => lower!(Add, left, right),
 		InstrKind::Sub { left, right } => lower!(Sub, left, right),
 		InstrKind::Mul { left, right } => lower!(Mul, left, right),
@@ -72,10 +340,19 @@ fn lower_kind(kind: InstrKind) -> anyhow::Result<Vec<MIRInstruction>> {
 		InstrKind::Mod { left, right } => lower!(Mod, left, right),
 		InstrKind::Min { left, right } => lower!(Min, left, right),
 		InstrKind::Max { left, right } => lower!(Max, left, right),
+		InstrKind::BitAnd { left, right } => lower!(BitAnd, left, right),
+		InstrKind::BitOr { left, right } => lower!(BitOr, left, right),
+		InstrKind::BitXor { left, right } => lower!(BitXor, left, right),
+		InstrKind::Shl { left, right } => lower!(Shl, left, right),
+		InstrKind::Shr { left, right } => lower!(Shr, left, right),
+		InstrKind::UUIDToString { left, right } => lower!(UUIDToString, left, right),
+		InstrKind::StringToUUID { left, right } => lower!(StringToUUID, left, right),
 		InstrKind::Swap { left, right } => lower!(Swap, left, right),
 		InstrKind::Remove { val } => lower!(Remove, val),
+		InstrKind::CopyData { from, to, paths } => lower!(CopyData, from, to, paths),
 		InstrKind::Abs { val } => lower!(Abs, val),
 		InstrKind::Pow { base, exp } => lower!(Pow, base, exp),
+		InstrKind::Rand { left, min, max } => lower!(Rand, left, min, max),
 		InstrKind::Get { value, scale } => lower!(Get, value, scale),
 		InstrKind::Merge { left, right } => lower!(Merge, left, right),
 		InstrKind::Push { left, right } => lower!(Push, left, right),
@@ -90,8 +367,9 @@ fn lower_kind(kind: InstrKind) -> anyhow::Result<Vec<MIRInstruction>> {
 		InstrKind::Use { val } => lower!(Use, val),
 		InstrKind::Call { call } => lower!(Call, call),
 		InstrKind::CallExtern { func } => lower!(CallExtern, func),
+		InstrKind::CallWith { func, args } => lower!(CallWith, func, args),
 		InstrKind::If { condition, body } => {
-			let instrs = lower_block(*body).context("Failed to lower if body")?;
+			let instrs = lower_body(*body, cx).context("Failed to lower if body")?;
 			MIRInstrKind::If {
 				condition,
 				body: Box::new(instrs),
@@ -102,8 +380,8 @@ fn lower_kind(kind: InstrKind) -> anyhow::Result<Vec<MIRInstruction>> {
 			first,
 			second,
 		} => {
-			let first = lower_block(*first).context("Failed to lower if else first body")?;
-			let second = lower_block(*second).context("Failed to lower if else second body")?;
+			let first = lower_body(*first, cx).context("Failed to lower if else first body")?;
+			let second = lower_body(*second, cx).context("Failed to lower if else second body")?;
 			MIRInstrKind::IfElse {
 				condition,
 				first: Box::new(first),
@@ -111,25 +389,407 @@ fn lower_kind(kind: InstrKind) -> anyhow::Result<Vec<MIRInstruction>> {
 			}
 		}
 		InstrKind::Modify { modifier, body } => {
-			let instrs = lower_block(*body).context("Failed to lower mdf body")?;
+			let instrs = lower_body(*body, cx).context("Failed to lower mdf body")?;
 			MIRInstrKind::Modify {
 				modifier,
 				body: Box::new(instrs),
 			}
 		}
+		InstrKind::SummonAs {
+			entity,
+			nbt,
+			uuid,
+			body,
+		} => {
+			let instrs = lower_body(*body, cx).context("Failed to lower smna body")?;
+			let mut new_body = MIRBlock::with_capacity(instrs.contents.len() + 2);
+			// The summoned entity is the executor inside of the body
+			let this = DataLocation::Entity(EntityTarget::Selector(TargetSelector::new(
+				SelectorType::This,
+			)));
+			if !nbt.is_empty() {
+				new_body.contents.push(MIRInstruction::new(MIRInstrKind::Merge {
+					left: MutableValue::Data(FullDataLocation {
+						loc: this.clone(),
+						path: DataPath::This,
+					}),
+					right: Value::Constant(DataTypeContents::NBT(NBTTypeContents::Compound(
+						nbt.get_ty(),
+						nbt,
+					))),
+				}));
+			}
+			if let Some(uuid) = uuid {
+				new_body.contents.push(MIRInstruction::new(MIRInstrKind::Assign {
+					left: uuid,
+					right: DeclareBinding::Value(Value::Mutable(MutableValue::Data(
+						FullDataLocation {
+							loc: this,
+							path: DataPath::String("UUID".into()),
+						},
+					))),
+				}));
+			}
+			new_body.contents.extend(instrs.contents);
+			MIRInstrKind::Modify {
+				modifier: MIRModifier::Summon(entity),
+				body: Box::new(new_body),
+			}
+		}
+		InstrKind::Assert { condition, message } => {
+			// The message and the return are checked separately so that the return
+			// is not put in a separate function, where it would not exit this one
+			let condition = Condition::Not(Box::new(condition));
+			let style = TextStyle {
+				color: Some("red".into()),
+				..Default::default()
+			};
+			let message = TextComponent::with_style(TextComponentContents::Text(message), style);
+			let print = MIRInstruction::new(MIRInstrKind::Command {
+				command: format!("tellraw @a {}", message.get_json_str()?),
+			});
+			out.push(MIRInstruction::new(MIRInstrKind::If {
+				condition: condition.clone(),
+				body: Box::new(MIRBlock::with_contents(vec![print])),
+			}));
+			if cx.body_depth > 0 {
+				let set = MIRInstruction::new(set_assert_flag(true));
+				out.push(MIRInstruction::new(MIRInstrKind::If {
+					condition: condition.clone(),
+					body: Box::new(MIRBlock::with_contents(vec![set])),
+				}));
+			}
+
+			out.extend(fail_if(condition, cx));
+			return Ok(out);
+		}
 		InstrKind::ReturnRun { body } => {
-			let instrs = lower_block(*body).context("Failed to lower retr body")?;
+			let instrs = lower_block(*body, cx).context("Failed to lower retr body")?;
 			MIRInstrKind::ReturnRun {
 				body: Box::new(instrs),
 			}
 		}
+		// The body is put directly in the parent block so that it runs in the same function
+		InstrKind::Atomic { body } => {
+			let instrs = lower_block(*body, cx).context("Failed to lower atomic body")?;
+			out.extend(instrs.contents);
+			return Ok(out);
+		}
+		InstrKind::Loop {
+			condition,
+			body,
+			check,
+		} => {
+			check_loop_body(&body)?;
+			if contains_assert(&body) {
+				// The flag is passed into and out of the loop function like other registers
+				cx.uses_assert_flag = true;
+				let ty = DataType::Score(ScoreType::Bool);
+				cx.reg_types.insert(ASSERT_FLAG_REG.into(), ty);
+			}
+			cx.loop_depth += 1;
+			let body = lower_body(*body, cx).context("Failed to lower loop body");
+			cx.loop_depth -= 1;
+			let call = lower_loop(condition, body?, check, cx).context("Failed to lower loop")?;
+			MIRInstrKind::Call { call }
+		}
 		InstrKind::ReturnValue { index, value } => lower!(ReturnValue, index, value),
 		InstrKind::Return { value } => lower!(Return, value),
 		InstrKind::Command { command } => lower!(Command, command),
+		InstrKind::InterpolatedCommand { command } => {
+			if get_interpolated_regs(&command).is_empty() {
+				lower!(Command, command)
+			} else {
+				lower_interpolated_command(command, &mut out, cx)
+					.context("Failed to lower interpolated command")?
+			}
+		}
+		InstrKind::MacroCommand { command } => lower!(MacroCommand, command),
 		InstrKind::Comment { comment } => lower!(Comment, comment),
 		InstrKind::MC(instr) => MIRInstrKind::MC(instr),
+		InstrKind::Label { name } => bail!("Label {name} was not legalized"),
+		InstrKind::Goto { label } => bail!("Goto {label} was not legalized"),
+		InstrKind::SpreadOverTicks { .. } => bail!("Spread was not legalized"),
+		InstrKind::GetFlag { .. } | InstrKind::SetFlag { .. } | InstrKind::ToggleFlag { .. } => {
+			bail!("Flag instruction was not legalized")
+		}
 	};
 	out.push(MIRInstruction::new(kind));
+	if check_assert_flag {
+		cx.uses_assert_flag = true;
+		let flag = Value::Mutable(MutableValue::Reg(ASSERT_FLAG_REG.into()));
+		out.extend(fail_if(Condition::Bool(flag), cx));
+	}
 
 	Ok(out)
 }
+
+/// Moves a loop into a function that runs the body and then calls itself again
+/// while the condition is true, returning the call to that function. Registers
+/// and arguments from outside of the loop are passed into the function and
+/// returned back out of it, since the function can't access them directly
+fn lower_loop(
+	mut condition: Condition,
+	mut body: MIRBlock,
+	check: LoopCheck,
+	cx: &mut LowerCx,
+) -> anyhow::Result<CallInterface> {
+	let func_id: ResourceLocation = format!("{}_loop_{}", cx.func_id, cx.loop_fns.len()).into();
+
+	// Arguments are replaced with registers inside of the loop
+	let used_args = RefCell::new(FxHashSet::default());
+	let replace_args = |val: &mut MutableValue| {
+		if let MutableValue::Arg(idx) = val {
+			used_args.borrow_mut().insert(*idx);
+			*val = MutableValue::Reg(get_loop_arg_reg(*idx));
+		}
+	};
+	body.replace_mut_vals(&replace_args);
+	for val in condition.iter_mut_vals() {
+		replace_args(val);
+	}
+
+	let mut declared_inside = FxHashMap::default();
+	get_declared_mir_regs(&body, &mut declared_inside);
+	let mut used_regs = condition.get_used_regs();
+	body.append_used_regs(&mut used_regs);
+	let mut captured: Vec<_> = used_regs
+		.into_iter()
+		.filter(|x| !declared_inside.contains_key(*x))
+		.filter_map(|x| {
+			let ty = cx.reg_types.get(x)?;
+			Some((MutableValue::Reg(x.clone()), x.clone(), ty.clone()))
+		})
+		.collect();
+	captured.sort_by(|a, b| a.1.cmp(&b.1));
+	captured.dedup_by(|a, b| a.1 == b.1);
+	// Failed asserts pass the flag out as the first return value
+	if let Some(pos) = captured.iter().position(|x| x.1.as_ref() == ASSERT_FLAG_REG) {
+		let flag = captured.remove(pos);
+		captured.insert(0, flag);
+	}
+	let mut used_args: Vec<_> = used_args.into_inner().into_iter().collect();
+	used_args.sort();
+	for idx in used_args {
+		let ty = cx
+			.interface
+			.sig
+			.params
+			.get(idx)
+			.with_context(|| format!("Argument {idx} does not exist"))?;
+		captured.push((MutableValue::Arg(idx), get_loop_arg_reg(idx), ty.clone()));
+	}
+
+	let inner_vals: Vec<_> = captured
+		.iter()
+		.map(|(_, reg, _)| MutableValue::Reg(reg.clone()))
+		.collect();
+	let recurse = MIRInstruction::new(MIRInstrKind::Call {
+		call: CallInterface {
+			function: func_id.clone(),
+			args: inner_vals.iter().cloned().map(Value::Mutable).collect(),
+			ret: inner_vals.clone(),
+		},
+	});
+
+	let mut block = MIRBlock::new();
+	for (i, (_, reg, ty)) in captured.iter().enumerate() {
+		block.contents.push(MIRInstruction::new(MIRInstrKind::Declare {
+			left: reg.clone(),
+			ty: ty.clone(),
+		}));
+		block.contents.push(MIRInstruction::new(MIRInstrKind::Assign {
+			left: MutableValue::Reg(reg.clone()),
+			right: DeclareBinding::Value(Value::Mutable(MutableValue::Arg(i as ArgRetIndex))),
+		}));
+	}
+	match check {
+		LoopCheck::Before => {
+			body.contents.push(recurse);
+			block.contents.push(MIRInstruction::new(MIRInstrKind::If {
+				condition,
+				body: Box::new(body),
+			}));
+		}
+		LoopCheck::After => {
+			block.contents.extend(body.contents);
+			block.contents.push(MIRInstruction::new(MIRInstrKind::If {
+				condition,
+				body: Box::new(MIRBlock::with_contents(vec![recurse])),
+			}));
+		}
+	}
+	for (i, val) in inner_vals.iter().enumerate() {
+		block
+			.contents
+			.push(MIRInstruction::new(MIRInstrKind::ReturnValue {
+				index: i as ArgRetIndex,
+				value: Value::Mutable(val.clone()),
+			}));
+	}
+
+	let types: Vec<_> = captured.iter().map(|(_, _, ty)| ty.clone()).collect();
+	let ret = if types.is_empty() {
+		ReturnType::Void
+	} else {
+		ReturnType::Standard(types.clone())
+	};
+	// Inlining the function into itself would never end
+	let annotations = FunctionAnnotations {
+		no_inline: true,
+		allow: cx.interface.annotations.allow.clone(),
+		generated: true,
+		..Default::default()
+	};
+	cx.loop_fns.push(MIRFunction {
+		interface: FunctionInterface::with_all(
+			func_id.clone(),
+			FunctionSignature::with_all(types, ret),
+			annotations,
+		),
+		block,
+	});
+
+	let outer_vals: Vec<_> = captured.into_iter().map(|(val, ..)| val).collect();
+	Ok(CallInterface {
+		function: func_id,
+		args: outer_vals.iter().cloned().map(Value::Mutable).collect(),
+		ret: outer_vals,
+	})
+}
+
+/// Checks that a loop body doesn't return, since it runs in a different function
+/// and returning would only leave the loop
+fn check_loop_body(block: &Block) -> anyhow::Result<()> {
+	for instr in &block.contents {
+		match &instr.kind {
+			InstrKind::Return { .. } | InstrKind::ReturnValue { .. } | InstrKind::ReturnRun { .. } => {
+				bail!("Loop bodies cannot return from the function")
+			}
+			other => {
+				for body in other.get_bodies() {
+					check_loop_body(body)?;
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn get_loop_arg_reg(idx: ArgRetIndex) -> Identifier {
+	format!("__loop_arg_{idx}").into()
+}
+
+/// Gets the registers that a command interpolates with `$(reg)`
+fn get_interpolated_regs(command: &str) -> Vec<&str> {
+	let mut out = Vec::new();
+	let mut rest = command;
+	while let Some(start) = rest.find("$(") {
+		rest = &rest[start + 2..];
+		let Some(end) = rest.find(')') else {
+			break;
+		};
+		out.push(&rest[..end]);
+		rest = &rest[end + 1..];
+	}
+	out
+}
+
+/// Lowers a command that interpolates registers. The registers are put into a compound
+/// that is passed as the macro arguments of a generated function, which runs the
+/// command as a macro line
+fn lower_interpolated_command(
+	command: String,
+	out: &mut Vec<MIRInstruction>,
+	cx: &mut LowerCx,
+) -> anyhow::Result<MIRInstrKind> {
+	let index = cx.command_fns.len();
+	let func_id: ResourceLocation = format!("{}_cmd_{index}", cx.func_id).into();
+	let args_reg = Identifier::from(format!("__cmd_args_{index}"));
+
+	out.push(MIRInstruction::new(MIRInstrKind::Declare {
+		left: args_reg.clone(),
+		ty: DataType::NBT(NBTType::Compound(Arc::default())),
+	}));
+	out.push(MIRInstruction::new(MIRInstrKind::Assign {
+		left: MutableValue::Reg(args_reg.clone()),
+		right: DeclareBinding::Value(Value::Constant(DataTypeContents::NBT(
+			NBTTypeContents::Compound(Arc::default(), NBTCompoundTypeContents::new()),
+		))),
+	}));
+	let regs: FxHashSet<_> = get_interpolated_regs(&command).into_iter().collect();
+	for reg in regs.into_iter().sorted() {
+		if reg.is_empty() || !reg.chars().all(|x| x.is_ascii_alphanumeric() || x == '_') {
+			bail!("Interpolated register name {reg:?} is not a valid macro argument name");
+		}
+		let mut val = Value::Mutable(MutableValue::Reg(reg.into()));
+		match cx.reg_types.get(reg) {
+			// Scores can't be stored straight into the compound, so they are cast first
+			Some(DataType::Score(..)) => {
+				let cast_reg = Identifier::from(format!("__cmd_args_{index}_{reg}"));
+				out.push(MIRInstruction::new(MIRInstrKind::Declare {
+					left: cast_reg.clone(),
+					ty: DataType::NBT(NBTType::Int),
+				}));
+				out.push(MIRInstruction::new(MIRInstrKind::Assign {
+					left: MutableValue::Reg(cast_reg.clone()),
+					right: DeclareBinding::Cast(DataType::NBT(NBTType::Int), val, 1.0),
+				}));
+				val = Value::Mutable(MutableValue::Reg(cast_reg));
+			}
+			Some(DataType::NBT(..)) => {}
+			Some(other) => bail!("Register {reg} of type {other:?} cannot be interpolated"),
+			None => bail!("Interpolated register {reg} does not exist"),
+		}
+		out.push(MIRInstruction::new(MIRInstrKind::Assign {
+			left: MutableValue::Property(Box::new(MutableValue::Reg(args_reg.clone())), reg.into()),
+			right: DeclareBinding::Value(val),
+		}));
+	}
+
+	let annotations = FunctionAnnotations {
+		allow: cx.interface.annotations.allow.clone(),
+		generated: true,
+		..Default::default()
+	};
+	cx.command_fns.push(MIRFunction {
+		interface: FunctionInterface::with_all(
+			func_id.clone(),
+			FunctionSignature::new(),
+			annotations,
+		),
+		block: MIRBlock::with_contents(vec![MIRInstruction::new(MIRInstrKind::MacroCommand {
+			command,
+		})]),
+	});
+
+	Ok(MIRInstrKind::CallWith {
+		func: func_id,
+		args: Value::Mutable(MutableValue::Reg(args_reg)),
+	})
+}
+
+/// Gets the types of all of the registers declared in a block
+fn get_declared_reg_types(block: &Block, out: &mut FxHashMap<Identifier, DataType>) {
+	for instr in &block.contents {
+		if let InstrKind::Declare { left, ty, .. } = &instr.kind {
+			out.insert(left.clone(), ty.clone());
+		}
+		for body in instr.kind.get_bodies() {
+			get_declared_reg_types(body, out);
+		}
+	}
+}
+
+/// Gets the registers declared in a block after it has been lowered
+fn get_declared_mir_regs(block: &MIRBlock, out: &mut FxHashMap<Identifier, DataType>) {
+	for instr in &block.contents {
+		if let MIRInstrKind::Declare { left, ty } = &instr.kind {
+			out.insert(left.clone(), ty.clone());
+		}
+		for body in instr.kind.get_bodies() {
+			get_declared_mir_regs(body, out);
+		}
+	}
+}
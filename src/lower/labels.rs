@@ -0,0 +1,294 @@
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::function::{
+	CallInterface, FunctionAnnotations, FunctionInterface, FunctionSignature, ReturnType,
+};
+use crate::common::ty::DataType;
+use crate::common::val::{ArgRetIndex, MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier, ResourceLocation};
+use crate::ir::{Block, IRFunction, InstrKind, Instruction, IR};
+
+/// Splits functions with labels into a continuation function for each label,
+/// so that every goto becomes a tail call. Registers are carried over to the
+/// continuations as extra arguments after the function's own ones
+pub fn legalize_labels(ir: &mut IR) -> anyhow::Result<()> {
+	let func_ids: Vec<_> = ir.functions.keys().sorted().cloned().collect();
+	for func_id in func_ids {
+		let func = ir
+			.functions
+			.get_mut(&func_id)
+			.expect("Function should exist");
+		let labels = get_labels(&func.block)
+			.with_context(|| format!("Invalid labels in function {func_id}"))?;
+		if labels.is_empty() {
+			check_no_gotos(&func.block)
+				.with_context(|| format!("Invalid goto in function {func_id}"))?;
+			continue;
+		}
+		if !matches!(func.interface.sig.ret, ReturnType::Void) {
+			bail!("Function {func_id} uses labels, so it cannot return values");
+		}
+
+		let new_funcs = split_function(func, &labels)
+			.with_context(|| format!("Failed to split labels in function {func_id}"))?;
+		for new_func in new_funcs {
+			if ir.functions.contains_key(&new_func.interface.id) {
+				bail!(
+					"Continuation {} for a label conflicts with an existing function",
+					new_func.interface.id
+				);
+			}
+			ir.functions.insert(new_func.interface.id.clone(), new_func);
+		}
+	}
+
+	Ok(())
+}
+
+/// Gets the names of the labels in a function, in order
+fn get_labels(block: &Block) -> anyhow::Result<Vec<String>> {
+	let mut out = Vec::new();
+	for instr in &block.contents {
+		match &instr.kind {
+			InstrKind::Label { name } => {
+				if out.contains(name) {
+					bail!("Label {name} is defined more than once");
+				}
+				out.push(name.clone());
+			}
+			other => {
+				if other.get_bodies().into_iter().any(has_label) {
+					bail!("Labels can only be at the top level of a function");
+				}
+			}
+		}
+	}
+
+	Ok(out)
+}
+
+fn has_label(block: &Block) -> bool {
+	block.contents.iter().any(|instr| {
+		matches!(instr.kind, InstrKind::Label { .. })
+			|| instr.kind.get_bodies().into_iter().any(has_label)
+	})
+}
+
+fn check_no_gotos(block: &Block) -> anyhow::Result<()> {
+	for instr in &block.contents {
+		if let InstrKind::Goto { label } = &instr.kind {
+			bail!("Label {label} does not exist");
+		}
+		for body in instr.kind.get_bodies() {
+			check_no_gotos(body)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn split_function(func: &mut IRFunction, labels: &[String]) -> anyhow::Result<Vec<IRFunction>> {
+	let func_id = func.interface.id.clone();
+	let label_funcs: FxHashMap<_, _> = labels
+		.iter()
+		.map(|x| (x.clone(), get_label_func_id(&func_id, x)))
+		.collect();
+
+	// Every register is passed along since any of them could be used after a jump
+	let mut regs = FxHashMap::default();
+	get_declared_regs(&func.block, &mut regs)?;
+	let regs: Vec<_> = regs.into_iter().sorted_by(|a, b| a.0.cmp(&b.0)).collect();
+
+	let param_count = func.interface.sig.params.len();
+	let mut jump_args: Vec<_> = (0..param_count)
+		.map(|x| Value::Mutable(MutableValue::Arg(x as ArgRetIndex)))
+		.collect();
+	jump_args.extend(
+		regs.iter()
+			.map(|(reg, _)| Value::Mutable(MutableValue::Reg(reg.clone()))),
+	);
+	let jcx = JumpCx {
+		label_funcs: &label_funcs,
+		args: &jump_args,
+	};
+
+	// Split the top level at each label, dropping anything after a goto
+	// that can't be reached
+	let mut segments = vec![Vec::new()];
+	let mut unreachable = false;
+	for instr in std::mem::take(&mut func.block.contents) {
+		match instr.kind {
+			InstrKind::Label { .. } => {
+				segments.push(Vec::new());
+				unreachable = false;
+			}
+			_ if unreachable => {}
+			_ => {
+				unreachable = matches!(instr.kind, InstrKind::Goto { .. });
+				segments
+					.last_mut()
+					.expect("There is always a segment")
+					.push(instr);
+			}
+		}
+	}
+
+	let mut sig = func.interface.sig.clone();
+	sig.params.extend(regs.iter().map(|(_, ty)| ty.clone()));
+	let annotations = FunctionAnnotations {
+		no_inline: true,
+		allow: func.interface.annotations.allow.clone(),
+		generated: true,
+		..Default::default()
+	};
+	// Inlining the function would make the tail calls return from the caller
+	func.interface.annotations.no_inline = true;
+
+	let mut out = Vec::new();
+	let segment_count = segments.len();
+	for (i, segment) in segments.into_iter().enumerate() {
+		let mut contents = Vec::new();
+		for (j, (reg, ty)) in regs.iter().enumerate() {
+			// The entry has nothing to pass along yet
+			let right = if i == 0 {
+				DeclareBinding::Null
+			} else {
+				DeclareBinding::Value(Value::Mutable(MutableValue::Arg(
+					(param_count + j) as ArgRetIndex,
+				)))
+			};
+			contents.push(Instruction::new(InstrKind::Declare {
+				left: reg.clone(),
+				ty: ty.clone(),
+				right,
+			}));
+		}
+
+		let ends_in_goto = matches!(
+			segment.last(),
+			Some(Instruction {
+				kind: InstrKind::Goto { .. },
+				..
+			})
+		);
+		for mut instr in segment {
+			jcx.replace_gotos(&mut instr, true)?;
+			contents.push(instr);
+		}
+		// Fall through to the next label
+		if i + 1 < segment_count && !ends_in_goto {
+			contents.push(jcx.jump(&labels[i]));
+		}
+
+		if i == 0 {
+			func.block.contents = contents;
+		} else {
+			let label = &labels[i - 1];
+			let mut block = Block::new();
+			block.contents = contents;
+			out.push(IRFunction {
+				interface: FunctionInterface::with_all(
+					label_funcs[label].clone(),
+					FunctionSignature::with_all(sig.params.clone(), ReturnType::Void),
+					annotations.clone(),
+				),
+				block,
+			});
+		}
+	}
+
+	Ok(out)
+}
+
+struct JumpCx<'a> {
+	label_funcs: &'a FxHashMap<String, ResourceLocation>,
+	args: &'a [Value],
+}
+
+impl<'a> JumpCx<'a> {
+	/// Creates the tail call that jumps to a label
+	fn jump(&self, label: &str) -> Instruction {
+		let call = CallInterface {
+			function: self.label_funcs[label].clone(),
+			args: self.args.to_vec(),
+			ret: Vec::new(),
+		};
+		let mut body = Block::new();
+		body.contents
+			.push(Instruction::new(InstrKind::Call { call }));
+
+		Instruction::new(InstrKind::ReturnRun {
+			body: Box::new(body),
+		})
+	}
+
+	/// Replaces gotos with jumps. Gotos can only be used where returning
+	/// will leave the whole function, which is at the top level or as the only
+	/// instruction in an if
+	fn replace_gotos(&self, instr: &mut Instruction, allowed: bool) -> anyhow::Result<()> {
+		match &mut instr.kind {
+			InstrKind::Goto { label } => {
+				if !allowed {
+					bail!("Goto {label} can only be used at the top level of a function or as the only instruction in an if");
+				}
+				if !self.label_funcs.contains_key(label) {
+					bail!("Label {label} does not exist");
+				}
+				let allowed_lints = std::mem::take(&mut instr.allowed_lints);
+				let span = instr.span;
+				*instr = self.jump(label);
+				instr.allowed_lints = allowed_lints;
+				instr.span = span;
+			}
+			InstrKind::If { body, .. } => {
+				let allowed = allowed && body.contents.len() == 1;
+				for instr in &mut body.contents {
+					self.replace_gotos(instr, allowed)?;
+				}
+			}
+			other => {
+				for body in other.get_bodies_mut() {
+					for instr in &mut body.contents {
+						self.replace_gotos(instr, false)?;
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Gets the types of all of the registers declared in a block
+fn get_declared_regs(
+	block: &Block,
+	regs: &mut FxHashMap<Identifier, DataType>,
+) -> anyhow::Result<()> {
+	for instr in &block.contents {
+		match &instr.kind {
+			InstrKind::Declare { left, ty, .. } => {
+				if let Some(existing) = regs.insert(left.clone(), ty.clone()) {
+					if existing != *ty {
+						bail!("Register {left} is declared with different types across labels");
+					}
+				}
+			}
+			InstrKind::DeclareCriteria { left, .. } => {
+				bail!("Criteria register {left} cannot be used in a function with labels");
+			}
+			other => {
+				for body in other.get_bodies() {
+					get_declared_regs(body, regs)?;
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn get_label_func_id(func_id: &ResourceLocation, label: &str) -> ResourceLocation {
+	format!("{func_id}_label_{label}").into()
+}
@@ -3,12 +3,17 @@ use anyhow::{bail, Context};
 use super::{lower_add, lower_subblock_impl, LowerBlockCx};
 use crate::common::condition::Condition;
 use crate::common::cost::GetCost;
+use crate::common::mc::entity::{SelectorParameter, SelectorType, TargetSelector};
+use crate::common::mc::instr::MinecraftInstr;
 use crate::common::mc::modifier::{
 	IfModCondition, IfScoreCondition, IfScoreRangeEnd, Modifier, StoreModLocation,
 };
+use crate::common::mc::{EntityTarget, Gamemode};
 use crate::common::reg::Local;
-use crate::common::ty::{DataType, DataTypeContents, ScoreType, ScoreTypeContents};
-use crate::common::val::{MutableScoreValue, MutableValue, NBTValue, ScoreValue, Value};
+use crate::common::ty::{DataType, DataTypeContents, NBTType, ScoreType, ScoreTypeContents};
+use crate::common::val::{
+	MutableNBTValue, MutableScoreValue, MutableValue, NBTValue, ScoreValue, Value,
+};
 use crate::common::Register;
 use crate::lir::{LIRInstrKind, LIRInstruction};
 use crate::util::Only;
@@ -34,6 +39,7 @@ pub(super) fn lower_condition(
 						right: r.to_score_value()?,
 					})
 				}
+				(DataType::NBT(..), DataType::NBT(..)) => lower_nbt_eq(l, r, &mut prelude, lbcx)?,
 				_ => bail!("Condition does not allow these types"),
 			};
 			out.push(LoweringCondition::new(cond));
@@ -177,11 +183,183 @@ pub(super) fn lower_condition(
 				Vec::new(),
 			)));
 		}
+		Condition::Gamemode(target, gamemode) => {
+			out.push(LoweringCondition::new(lower_gamemode_cond(
+				target, gamemode,
+			)));
+		}
+		Condition::Difficulty(difficulty) => {
+			// The difficulty query returns the ID of the current difficulty
+			let reg = lbcx.new_additional_reg();
+			lbcx.registers.insert(
+				reg.clone(),
+				Register {
+					id: reg.clone(),
+					ty: DataType::Score(ScoreType::Score),
+				},
+			);
+			let mut instr = LIRInstruction::new(LIRInstrKind::MC(MinecraftInstr::GetDifficulty));
+			instr
+				.modifiers
+				.push(Modifier::StoreResult(StoreModLocation::Local(
+					Local::Reg(reg.clone()),
+					None,
+					1.0,
+				)));
+			prelude.push(instr);
+			out.push(LoweringCondition::new(IfModCondition::Score(
+				IfScoreCondition::Single {
+					left: ScoreValue::Mutable(MutableScoreValue::Local(Local::Reg(reg))),
+					right: ScoreValue::Constant(ScoreTypeContents::Score(difficulty.get_id())),
+				},
+			)));
+		}
+		Condition::DataCount(val, range) => {
+			let NBTValue::Mutable(val) = val.to_nbt_value()? else {
+				bail!("Data count condition must be on a mutable value");
+			};
+			// The if data subcommand returns the number of values that it matched
+			let reg = lbcx.new_additional_reg();
+			lbcx.registers.insert(
+				reg.clone(),
+				Register {
+					id: reg.clone(),
+					ty: DataType::Score(ScoreType::Score),
+				},
+			);
+			let mut instr = LIRInstruction::new(LIRInstrKind::NoOp);
+			instr.modifiers.extend([
+				Modifier::StoreResult(StoreModLocation::Local(Local::Reg(reg.clone()), None, 1.0)),
+				Modifier::If {
+					condition: Box::new(IfModCondition::DataExists(val)),
+					negate: false,
+				},
+			]);
+			prelude.push(instr);
+			let score = ScoreValue::Mutable(MutableScoreValue::Local(Local::Reg(reg)));
+			let cond = if let Some(count) = range.get_single() {
+				IfScoreCondition::Single {
+					left: score,
+					right: ScoreValue::Constant(ScoreTypeContents::Score(count)),
+				}
+			} else {
+				// Infinite ends of the range are the largest values
+				let get_end = |val: i32| {
+					if val <= -i32::MAX || val == i32::MAX {
+						IfScoreRangeEnd::Infinite
+					} else {
+						IfScoreRangeEnd::Fixed {
+							value: ScoreValue::Constant(ScoreTypeContents::Score(val)),
+							inclusive: true,
+						}
+					}
+				};
+				let (min, max) = range.get_bounds();
+				IfScoreCondition::Range {
+					score,
+					left: get_end(min),
+					right: get_end(max),
+				}
+			};
+			out.push(LoweringCondition::new(IfModCondition::Score(cond)));
+		}
+		Condition::Items(loc, slot, item) => {
+			out.push(LoweringCondition::new(IfModCondition::Items(
+				loc, slot, item,
+			)));
+		}
 	};
 
 	Ok((prelude, out))
 }
 
+/// Lower a gamemode check to an entity condition with a gamemode selector parameter
+fn lower_gamemode_cond(target: EntityTarget, gamemode: Gamemode) -> IfModCondition {
+	let mut sel = match target {
+		EntityTarget::Selector(sel) => sel,
+		EntityTarget::Player(name) => TargetSelector::with_params(
+			SelectorType::AllPlayers,
+			vec![SelectorParameter::Name {
+				name: name.to_string(),
+				invert: false,
+			}],
+		),
+	};
+	// Selectors can only have one gamemode that is not inverted, so check
+	// against the one that is already there
+	for param in &sel.params {
+		if let SelectorParameter::Gamemode {
+			gamemode: other,
+			invert,
+		} = param
+		{
+			if (*other == gamemode) == *invert {
+				return IfModCondition::Const(false);
+			}
+			if !invert {
+				return IfModCondition::Entity(EntityTarget::Selector(sel));
+			}
+		}
+	}
+	// Any inverted gamemodes are implied by the new one
+	sel.params
+		.retain(|x| !matches!(x, SelectorParameter::Gamemode { .. }));
+	sel.params.push(SelectorParameter::Gamemode {
+		gamemode,
+		invert: false,
+	});
+
+	IfModCondition::Entity(EntityTarget::Selector(sel))
+}
+
+/// Lower an equality check between two NBT values. The left side is copied
+/// and then overwritten with the right side, which will only succeed if they differ
+fn lower_nbt_eq(
+	l: Value,
+	r: Value,
+	prelude: &mut Vec<LIRInstruction>,
+	lbcx: &mut LowerBlockCx,
+) -> anyhow::Result<IfModCondition> {
+	let copy_reg = lbcx.new_additional_reg();
+	lbcx.registers.insert(
+		copy_reg.clone(),
+		Register {
+			id: copy_reg.clone(),
+			ty: DataType::NBT(NBTType::Any),
+		},
+	);
+	let success_reg = lbcx.new_additional_reg();
+	lbcx.registers.insert(
+		success_reg.clone(),
+		Register {
+			id: success_reg.clone(),
+			ty: DataType::Score(ScoreType::Bool),
+		},
+	);
+
+	prelude.push(LIRInstruction::new(LIRInstrKind::SetData(
+		MutableNBTValue::Local(Local::Reg(copy_reg.clone())),
+		l.to_nbt_value()?,
+	)));
+	let mut instr = LIRInstruction::new(LIRInstrKind::SetData(
+		MutableNBTValue::Local(Local::Reg(copy_reg)),
+		r.to_nbt_value()?,
+	));
+	instr
+		.modifiers
+		.push(Modifier::StoreSuccess(StoreModLocation::Local(
+			Local::Reg(success_reg.clone()),
+			None,
+			1.0,
+		)));
+	prelude.push(instr);
+
+	Ok(IfModCondition::Score(IfScoreCondition::Single {
+		left: ScoreValue::Mutable(MutableScoreValue::Local(Local::Reg(success_reg))),
+		right: ScoreValue::Constant(ScoreTypeContents::Bool(false)),
+	}))
+}
+
 pub(super) fn lower_bool_cond(
 	val: Value,
 	check: bool,
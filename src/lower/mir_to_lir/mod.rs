@@ -1,19 +1,30 @@
 use anyhow::{bail, Context};
 
-use crate::common::function::{FunctionInterface, FunctionSignature};
+use crate::common::diag::WithSpan;
+use crate::common::function::{FunctionInterface, FunctionSignature, ReturnType};
+use crate::common::mc::instr::MinecraftInstr;
 use crate::common::mc::modifier::{
 	IfModCondition, IfScoreCondition, IfScoreRangeEnd, MIRModifier, Modifier, StoreDataType,
 	StoreModLocation,
 };
+use crate::common::mc::text::TextComponentContents;
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation};
+use crate::common::range::{IntRange, RangeEnd};
 use crate::common::reg::{GetUsedLocals, Local};
-use crate::common::ty::{get_op_tys, DataType, Double, ScoreType, ScoreTypeContents};
+use crate::common::storage::strip_parent_path;
+use crate::common::ty::{
+	get_op_tys, DataType, DataTypeContents, Double, NBTType, NBTTypeContents, ScoreType,
+	ScoreTypeContents,
+};
 use crate::common::ResourceLocation;
 use crate::common::{
 	val::MutableNBTValue, val::MutableScoreValue, val::MutableValue, val::NBTValue,
 	val::ScoreValue, val::Value, DeclareBinding, Identifier, Register, RegisterList,
 };
 use crate::lir::{LIRBlock, LIRFunction, LIRInstrKind, LIRInstruction, LIR};
-use crate::mir::{MIRBlock, MIRInstrKind, MIR};
+use crate::mir::{MIRBlock, MIRInstrKind, MIRInstruction, MIR};
+
+use rustc_hash::FxHashMap;
 
 use cond::lower_let_cond;
 
@@ -43,10 +54,17 @@ struct LowerBlockCx<'lir> {
 	body_count: u32,
 	func_id: ResourceLocation,
 	sig: FunctionSignature,
+	/// Signatures of all of the functions in the MIR, used to get the types of return slots
+	sigs: &'lir FxHashMap<ResourceLocation, FunctionSignature>,
 }
 
 impl<'lir> LowerBlockCx<'lir> {
-	fn new(lir: &'lir mut LIR, func_id: ResourceLocation, sig: FunctionSignature) -> Self {
+	fn new(
+		lir: &'lir mut LIR,
+		func_id: ResourceLocation,
+		sig: FunctionSignature,
+		sigs: &'lir FxHashMap<ResourceLocation, FunctionSignature>,
+	) -> Self {
 		Self {
 			lir,
 			registers: RegisterList::default(),
@@ -54,6 +72,7 @@ impl<'lir> LowerBlockCx<'lir> {
 			body_count: 0,
 			func_id,
 			sig,
+			sigs,
 		}
 	}
 
@@ -66,13 +85,21 @@ impl<'lir> LowerBlockCx<'lir> {
 	fn new_body_fn(&mut self) -> FunctionInterface {
 		let old_val = self.body_count;
 		self.body_count += 1;
-		FunctionInterface::new(format!("{}_body_{old_val}", self.func_id).into())
+		let id = format!("{}_body_{old_val}", self.func_id);
+		let mut interface = FunctionInterface::new(id.into());
+		interface.annotations.generated = true;
+		interface
 	}
 }
 
 /// Lower MIR to LIR
 pub fn lower_mir(mir: MIR) -> anyhow::Result<LIR> {
 	let mut lir = LIR::with_capacity(mir.functions.len());
+	let sigs: FxHashMap<_, _> = mir
+		.functions
+		.iter()
+		.map(|(id, func)| (id.clone(), func.interface.sig.clone()))
+		.collect();
 	for (func_id, func) in mir.functions {
 		let block = func.block;
 		let mut lir_instrs = Vec::with_capacity(block.contents.len());
@@ -81,10 +108,11 @@ pub fn lower_mir(mir: MIR) -> anyhow::Result<LIR> {
 			&mut lir,
 			func.interface.id.clone(),
 			func.interface.sig.clone(),
+			&sigs,
 		);
 
 		for mir_instr in block.contents {
-			lower_kind(mir_instr.kind, &mut lir_instrs, &mut lbcx)?;
+			lower_instr(mir_instr, &mut lir_instrs, &mut lbcx)?;
 		}
 
 		let mut lir_block = LIRBlock::new(lbcx.registers);
@@ -99,10 +127,28 @@ pub fn lower_mir(mir: MIR) -> anyhow::Result<LIR> {
 			},
 		);
 	}
+	lir.objectives = mir.objectives;
+	lir.storage_consts = mir.storage_consts;
+	lir.storages = mir.storages;
 
 	Ok(lir)
 }
 
+/// Lowers an instruction, carrying its span over to everything that it lowers to
+fn lower_instr(
+	instr: MIRInstruction,
+	lir_instrs: &mut Vec<LIRInstruction>,
+	lbcx: &mut LowerBlockCx,
+) -> anyhow::Result<()> {
+	let start = lir_instrs.len();
+	lower_kind(instr.kind, lir_instrs, lbcx).with_span(instr.span)?;
+	for lir_instr in &mut lir_instrs[start..] {
+		lir_instr.span = lir_instr.span.or(instr.span);
+	}
+
+	Ok(())
+}
+
 fn lower_kind(
 	kind: MIRInstrKind,
 	lir_instrs: &mut Vec<LIRInstruction>,
@@ -215,26 +261,68 @@ fn lower_kind(
 				lir_instrs.extend(instrs);
 			}
 			lower!(lir_instrs, Call, call.function.clone(), Vec::new());
-			// Set the return values
+			// Set the return values. Each slot is read with the type that the callee
+			// declared for it, since that decides whether it is a score or in storage
+			let ret_tys = match lbcx.sigs.get(&call.function).map(|sig| &sig.ret) {
+				Some(ReturnType::Standard(tys)) => Some(tys.clone()),
+				_ => None,
+			};
 			for (i, ret) in call.ret.iter().enumerate() {
+				let ty = match ret_tys.as_ref().and_then(|tys| tys.get(i)) {
+					Some(ty) => ty.clone(),
+					None => ret.get_ty(&lbcx.registers, &lbcx.sig)?,
+				};
 				let instrs = lower_assign(
 					ret.clone(),
 					DeclareBinding::Value(Value::Mutable(MutableValue::CallReturnValue(
 						i.try_into().expect("This should fit"),
 						call.function.clone(),
-						ret.get_ty(&lbcx.registers, &lbcx.sig)?,
+						ty,
 					))),
 					lbcx,
 				)
-				.context("Failed to lower return value assignment")?;
+				.with_context(|| {
+					format!(
+						"Failed to lower return value {i} of call to {}",
+						call.function
+					)
+				})?;
 				lir_instrs.extend(instrs);
 			}
 		}
 		MIRInstrKind::CallExtern { func } => lower!(lir_instrs, Call, func, Vec::new()),
+		MIRInstrKind::CallWith { func, args } => {
+			let args = args.to_nbt_value().context("Macro arguments must be NBT")?;
+			lower!(lir_instrs, CallWith, func, args)
+		}
 		MIRInstrKind::Remove { val } => lir_instrs.push(LIRInstruction::new(lower_rm(val, lbcx)?)),
+		MIRInstrKind::CopyData { from, to, paths } => {
+			lir_instrs.extend(lower_copy_data(from, to, paths)?);
+		}
 		MIRInstrKind::Pow { base, exp } => {
 			lower_pow(base, exp, lir_instrs, lbcx)?;
 		}
+		MIRInstrKind::BitAnd { .. }
+		| MIRInstrKind::BitOr { .. }
+		| MIRInstrKind::BitXor { .. }
+		| MIRInstrKind::Shl { .. }
+		| MIRInstrKind::Shr { .. } => {
+			bail!("Bitwise instructions should have been legalized before lowering")
+		}
+		MIRInstrKind::UUIDToString { .. } | MIRInstrKind::StringToUUID { .. } => {
+			bail!("UUID conversions should have been legalized before lowering")
+		}
+		MIRInstrKind::Rand { left, min, max } => {
+			let range = IntRange::new(RangeEnd::Value(min), RangeEnd::Value(max));
+			let store_loc = StoreModLocation::from_mut_val(left, &lbcx.registers, &lbcx.sig)?;
+			lir_instrs.push(LIRInstruction::with_modifiers(
+				LIRInstrKind::MC(MinecraftInstr::RandomValue {
+					range,
+					sequence: None,
+				}),
+				vec![Modifier::StoreResult(store_loc)],
+			));
+		}
 		MIRInstrKind::If { condition, body } => {
 			let (prepend, conditions) =
 				lower_condition(condition, lbcx).context("Failed to lower condition")?;
@@ -330,10 +418,12 @@ fn lower_kind(
 				lower!(lir_instrs, ReturnRun, Box::new(subinstr))
 			}
 		},
+		MIRInstrKind::ReturnFail => lower!(lir_instrs, ReturnFail),
 		MIRInstrKind::NoOp => {}
 		MIRInstrKind::Command { command } => lower!(lir_instrs, Command, command),
+		MIRInstrKind::MacroCommand { command } => lower!(lir_instrs, MacroCommand, command),
 		MIRInstrKind::Comment { comment } => lower!(lir_instrs, Comment, comment),
-		MIRInstrKind::MC(instr) => lower!(lir_instrs, MC, instr),
+		MIRInstrKind::MC(instr) => lower!(lir_instrs, MC, lower_mc(instr, lbcx)?),
 	}
 	Ok(())
 }
@@ -350,17 +440,36 @@ fn lower_assign(
 	let right_val = match &right {
 		DeclareBinding::Null => None,
 		DeclareBinding::Value(val) => Some(val.clone()),
-		DeclareBinding::Cast(ty, val) => {
+		DeclareBinding::Cast(ty, Value::Constant(val), scale) => {
+			Some(Value::Constant(cast_constant(val, ty, *scale)?))
+		}
+		DeclareBinding::Cast(ty, Value::Mutable(val), scale) => {
 			let val_ty = val.get_ty(&lbcx.registers, &lbcx.sig)?;
 			// If the cast is not trivial, or they are not both
 			// score types, we have to declare a new register,
 			// initialize it with the cast, and then assign the result to our declaration
-
-			if val_ty.is_trivially_castable(ty)
-				|| matches!((&val_ty, &ty), (DataType::Score(..), DataType::Score(..)))
-			{
+			let both_scores = matches!((&val_ty, &ty), (DataType::Score(..), DataType::Score(..)));
+			if *scale == 1.0 && (val_ty.is_trivially_castable(ty) || both_scores) {
 				Some(Value::Mutable(val.clone()))
 			} else {
+				if both_scores {
+					bail!("Casts between scores cannot be scaled");
+				}
+				// The scale is applied when getting the value if the result is an integer, so
+				// that fractional values are scaled before they are truncated
+				let scale_on_get = match (&val_ty, ty) {
+					(DataType::NBT(..), DataType::Score(..)) => true,
+					(DataType::NBT(..), DataType::NBT(ty)) => {
+						!matches!(ty, NBTType::Float | NBTType::Double)
+					}
+					_ => false,
+				};
+				let (get_scale, store_scale) = if scale_on_get {
+					(*scale, 1.0)
+				} else {
+					(1.0, *scale)
+				};
+
 				// Run the cast
 				let store_loc = match ty {
 					DataType::Score(..) => StoreModLocation::from_mut_score_val(
@@ -375,7 +484,7 @@ fn lower_assign(
 						StoreModLocation::from_mut_nbt_val(
 							&left.clone().to_mutable_nbt_value()?,
 							ty,
-							1.0,
+							store_scale,
 						)?
 					}
 					_ => bail!("Type not supported"),
@@ -386,7 +495,7 @@ fn lower_assign(
 						LIRInstrKind::GetScore(val.clone().to_mutable_score_value()?)
 					}
 					DataType::NBT(..) => {
-						LIRInstrKind::GetData(val.clone().to_mutable_nbt_value()?, 1.0)
+						LIRInstrKind::GetData(val.clone().to_mutable_nbt_value()?, get_scale)
 					}
 					_ => bail!("Type not supported"),
 				};
@@ -422,6 +531,54 @@ fn lower_assign(
 	Ok(out)
 }
 
+/// Casts a constant value to another type at compile time, matching the
+/// rounding that the game would do if the cast was done with commands
+fn cast_constant(
+	val: &DataTypeContents,
+	ty: &DataType,
+	scale: Double,
+) -> anyhow::Result<DataTypeContents> {
+	if scale == 1.0 && val.get_ty().is_trivially_castable(ty) {
+		return Ok(val.clone());
+	}
+
+	let num = match val {
+		DataTypeContents::Score(val) => val.get_i32() as f64,
+		DataTypeContents::NBT(NBTTypeContents::Byte(val)) => *val as f64,
+		DataTypeContents::NBT(NBTTypeContents::Bool(val)) => *val as i32 as f64,
+		DataTypeContents::NBT(NBTTypeContents::Short(val)) => *val as f64,
+		DataTypeContents::NBT(NBTTypeContents::Int(val)) => *val as f64,
+		DataTypeContents::NBT(NBTTypeContents::Long(val)) => *val as f64,
+		DataTypeContents::NBT(NBTTypeContents::Float(val)) => *val as f64,
+		DataTypeContents::NBT(NBTTypeContents::Double(val)) => *val,
+		_ => bail!("Value {val:?} cannot be cast to a number"),
+	};
+	let num = num * scale;
+	let int = num.floor();
+
+	let out = match ty {
+		DataType::Score(ScoreType::Score | ScoreType::Flags(..)) => {
+			DataTypeContents::Score(ScoreTypeContents::Score(int as i32))
+		}
+		DataType::Score(ScoreType::Bool) => {
+			DataTypeContents::Score(ScoreTypeContents::Bool(int != 0.0))
+		}
+		DataType::NBT(ty) => DataTypeContents::NBT(match ty {
+			NBTType::Byte => NBTTypeContents::Byte(int as i8),
+			NBTType::Bool => NBTTypeContents::Bool(int != 0.0),
+			NBTType::Short => NBTTypeContents::Short(int as i16),
+			NBTType::Int => NBTTypeContents::Int(int as i32),
+			NBTType::Long => NBTTypeContents::Long(int as i64),
+			NBTType::Float => NBTTypeContents::Float(num as f32),
+			NBTType::Double => NBTTypeContents::Double(num),
+			_ => bail!("Cannot cast a number to type {ty:?}"),
+		}),
+		_ => bail!("Cannot cast a number to type {ty:?}"),
+	};
+
+	Ok(out)
+}
+
 fn lower_add(
 	left: MutableValue,
 	right: Value,
@@ -582,6 +739,26 @@ fn lower_swap(
 	Ok(out)
 }
 
+/// Lowers a Minecraft instruction by making text components show NBT registers as NBT
+fn lower_mc(mut instr: MinecraftInstr, lbcx: &LowerBlockCx) -> anyhow::Result<MinecraftInstr> {
+	for text in instr.get_text_components_mut() {
+		for contents in text.get_all_contents_mut() {
+			let TextComponentContents::Score(MutableScoreValue::Local(loc)) = contents else {
+				continue;
+			};
+			match loc.get_ty(&lbcx.registers, &lbcx.sig)? {
+				DataType::Score(..) => {}
+				DataType::NBT(..) => {
+					*contents = TextComponentContents::Nbt(MutableNBTValue::Local(loc.clone()))
+				}
+				_ => bail!("Type not supported in a text component"),
+			}
+		}
+	}
+
+	Ok(instr)
+}
+
 fn lower_abs(val: MutableValue, lbcx: &LowerBlockCx) -> anyhow::Result<LIRInstruction> {
 	let kind = match val.get_ty(&lbcx.registers, &lbcx.sig)? {
 		DataType::Score(..) => LIRInstrKind::MulScore(
@@ -687,6 +864,40 @@ fn lower_insert(
 	Ok(kind)
 }
 
+/// Lowers a copy between data locations. Paths that are repeated or inside of another
+/// copied path are already copied with it, so they are left out
+fn lower_copy_data(
+	from: DataLocation,
+	to: DataLocation,
+	paths: Vec<DataPath>,
+) -> anyhow::Result<Vec<LIRInstruction>> {
+	if paths.contains(&DataPath::This) {
+		bail!("Cannot copy the root of a data location");
+	}
+
+	let mut out = Vec::new();
+	for (i, path) in paths.iter().enumerate() {
+		let covered = paths.iter().enumerate().any(|(j, other)| {
+			j != i && strip_parent_path(path, other).is_some_and(|rest| !rest.is_empty() || j < i)
+		});
+		if covered {
+			continue;
+		}
+		out.push(LIRInstruction::new(LIRInstrKind::SetData(
+			MutableNBTValue::Data(FullDataLocation {
+				loc: to.clone(),
+				path: path.clone(),
+			}),
+			NBTValue::Mutable(MutableNBTValue::Data(FullDataLocation {
+				loc: from.clone(),
+				path: path.clone(),
+			})),
+		)));
+	}
+
+	Ok(out)
+}
+
 fn lower_rm(val: MutableValue, lbcx: &LowerBlockCx) -> anyhow::Result<LIRInstrKind> {
 	let kind = match val.get_ty(&lbcx.registers, &lbcx.sig)? {
 		DataType::Score(..) => LIRInstrKind::ResetScore(val.to_mutable_score_value()?),
@@ -788,7 +999,7 @@ fn highest_power_of_2_factor(num: u8) -> u8 {
 fn lower_subblock(block: MIRBlock, lbcx: &mut LowerBlockCx) -> anyhow::Result<LIRInstruction> {
 	let mut new_lir_instrs = Vec::new();
 	for instr in block.contents {
-		lower_kind(instr.kind, &mut new_lir_instrs, lbcx)
+		lower_instr(instr, &mut new_lir_instrs, lbcx)
 			.context("Failed to lower subinstruction body")?;
 	}
 
@@ -1,7 +1,13 @@
 use crate::common::{val::ArgRetIndex, Identifier};
 
+pub mod bitwise;
+pub mod flags;
 pub mod ir_to_mir;
+pub mod labels;
 pub mod mir_to_lir;
+pub mod rand;
+pub mod spread;
+pub mod uuid;
 
 pub fn fmt_lowered_arg(func_id: &str, arg_num: ArgRetIndex) -> Identifier {
 	let reg = format!("in_arg_{func_id}_{arg_num}");
@@ -0,0 +1,131 @@
+use anyhow::bail;
+
+use crate::common::diag::WithSpan;
+use crate::common::mc::scoreboard_and_teams::{Criterion, SingleCriterion};
+use crate::common::mc::version::MinecraftVersion;
+use crate::common::mc::{EntityTarget, Score};
+use crate::common::ty::{DataTypeContents, ScoreTypeContents};
+use crate::common::val::{MutableValue, Value};
+use crate::common::DeclareBinding;
+use crate::mir::{MIRBlock, MIRInstrKind, MIRInstruction, MIR};
+use crate::output::text::get_fake_player_sigil;
+use crate::passes::analysis::ranges::check_random_range;
+use crate::project::ProjectSettings;
+
+/// The objective that the state of the fallback random number generator is kept in
+const RNG_OBJECTIVE: &str = "dpc_rng";
+/// Constants for the linear congruential generator, the same as the ones used by glibc
+const LCG_MULTIPLIER: i32 = 1_103_515_245;
+const LCG_INCREMENT: i32 = 12345;
+/// The low bits of the generator repeat quickly, so only the high 16 bits are used
+const LCG_SHIFT: i32 = 65536;
+
+/// Replaces random instructions with a linear congruential generator on scores when the
+/// project targets a version from before the random command was added. The generator
+/// starts from zero, so it gives the same sequence of numbers every time the pack loads
+pub fn legalize_rands(mir: &mut MIR, project: &ProjectSettings) -> anyhow::Result<()> {
+	if project.target_version >= MinecraftVersion::new(20, 2) {
+		return Ok(());
+	}
+
+	let sigil = get_fake_player_sigil(project.hidden_fake_players);
+	let state = rng_score(&format!("{sigil}rng"));
+	let out = rng_score(&format!("{sigil}rng_out"));
+	let mut used = false;
+	for func in mir.functions.values_mut() {
+		legalize_block(&mut func.block, &state, &out, &mut used)?;
+	}
+	if used {
+		mir.objectives.insert(
+			RNG_OBJECTIVE.into(),
+			Criterion::Single(SingleCriterion::Dummy),
+		);
+	}
+
+	Ok(())
+}
+
+fn legalize_block(
+	block: &mut MIRBlock,
+	state: &MutableValue,
+	out: &MutableValue,
+	used: &mut bool,
+) -> anyhow::Result<()> {
+	let mut contents = Vec::with_capacity(block.contents.len());
+	for mut instr in std::mem::take(&mut block.contents) {
+		if let MIRInstrKind::ReturnRun { body } = &mut instr.kind {
+			legalize_block(body, state, out, used)?;
+		}
+		for body in instr.kind.get_bodies_mut() {
+			legalize_block(body, state, out, used)?;
+		}
+
+		let MIRInstrKind::Rand { left, min, max } = instr.kind else {
+			contents.push(instr);
+			continue;
+		};
+		let span = get_fallback_span(min, max).with_span(instr.span)?;
+		*used = true;
+
+		let constant =
+			|val: i32| Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(val)));
+		let mut kinds = vec![
+			MIRInstrKind::Mul {
+				left: state.clone(),
+				right: constant(LCG_MULTIPLIER),
+			},
+			MIRInstrKind::Add {
+				left: state.clone(),
+				right: constant(LCG_INCREMENT),
+			},
+			MIRInstrKind::Assign {
+				left: out.clone(),
+				right: DeclareBinding::Value(Value::Mutable(state.clone())),
+			},
+			MIRInstrKind::Div {
+				left: out.clone(),
+				right: constant(LCG_SHIFT),
+			},
+			MIRInstrKind::Mod {
+				left: out.clone(),
+				right: constant(span),
+			},
+		];
+		if min != 0 {
+			kinds.push(MIRInstrKind::Add {
+				left: out.clone(),
+				right: constant(min),
+			});
+		}
+		kinds.push(MIRInstrKind::Assign {
+			left,
+			right: DeclareBinding::Value(Value::Mutable(out.clone())),
+		});
+		contents.extend(kinds.into_iter().map(|kind| MIRInstruction {
+			kind,
+			allowed_lints: instr.allowed_lints.clone(),
+			span: instr.span,
+		}));
+	}
+	block.contents = contents;
+
+	Ok(())
+}
+
+/// Gets how many numbers are in a random range, checking that the fallback can generate them
+fn get_fallback_span(min: i32, max: i32) -> anyhow::Result<i32> {
+	check_random_range(min as i64, max as i64)?;
+	let span = max as i64 - min as i64 + 1;
+	if span > LCG_SHIFT as i64 {
+		bail!("Random range {min}..{max} is too large to be generated on versions before 1.20.2");
+	}
+
+	Ok(span as i32)
+}
+
+fn rng_score(holder: &str) -> MutableValue {
+	MutableValue::Score(Score::new(
+		EntityTarget::Player(holder.into()),
+		RNG_OBJECTIVE.into(),
+	))
+}
@@ -0,0 +1,214 @@
+use anyhow::bail;
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::condition::Condition;
+use crate::common::function::{
+	CallInterface, FunctionAnnotations, FunctionInterface, FunctionSignature, InvocationFrequency,
+	ReturnType,
+};
+use crate::common::lint::Lint;
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation};
+use crate::common::val::{MutableValue, Value};
+use crate::common::{DeclareBinding, ResourceLocation};
+use crate::ir::{Block, IRFunction, InstrKind, Instruction, IR};
+
+use super::cleanup_fn_id;
+
+/// The storage that the queues of items are kept in
+const QUEUE_STORAGE: &str = "dpc:spread";
+
+/// Moves the bodies of spread instructions into step functions that are run
+/// on the items of a queue by a generated tick function. The instruction itself
+/// is replaced with filling the queue
+pub fn legalize_spreads(ir: &mut IR) -> anyhow::Result<()> {
+	let param_counts: FxHashMap<_, _> = ir
+		.functions
+		.iter()
+		.map(|(id, func)| (id.clone(), func.interface.sig.params.len()))
+		.collect();
+
+	let func_ids: Vec<_> = ir.functions.keys().sorted().cloned().collect();
+	let mut new_funcs = Vec::new();
+	for func_id in func_ids {
+		let func = ir
+			.functions
+			.get_mut(&func_id)
+			.expect("Function should exist");
+		let mut scx = SpreadCx {
+			func_id: &func_id,
+			allow: &func.interface.annotations.allow,
+			order: func.interface.annotations.order,
+			param_counts: &param_counts,
+			count: 0,
+			out: &mut new_funcs,
+		};
+		scx.legalize_block(&mut func.block)?;
+	}
+
+	for new_func in new_funcs {
+		if ir.functions.contains_key(&new_func.interface.id) {
+			bail!(
+				"Function {} generated for a spread conflicts with an existing function",
+				new_func.interface.id
+			);
+		}
+		ir.functions.insert(new_func.interface.id.clone(), new_func);
+	}
+
+	Ok(())
+}
+
+struct SpreadCx<'a> {
+	func_id: &'a ResourceLocation,
+	allow: &'a [Lint],
+	order: Option<i32>,
+	param_counts: &'a FxHashMap<ResourceLocation, usize>,
+	count: usize,
+	out: &'a mut Vec<IRFunction>,
+}
+
+impl<'a> SpreadCx<'a> {
+	fn legalize_block(&mut self, block: &mut Block) -> anyhow::Result<()> {
+		let mut out = Vec::with_capacity(block.contents.len());
+		for mut instr in std::mem::take(&mut block.contents) {
+			let InstrKind::SpreadOverTicks {
+				per_tick,
+				item,
+				ty,
+				list,
+				on_complete,
+				mut body,
+			} = instr.kind
+			else {
+				for body in instr.kind.get_bodies_mut() {
+					self.legalize_block(body)?;
+				}
+				out.push(instr);
+				continue;
+			};
+			// Spreads inside of the body end up in the step function
+			self.legalize_block(&mut body)?;
+
+			let callback = on_complete
+				.map(|func| self.get_callback(func))
+				.transpose()?;
+
+			let base_id = format!("{}_spread_{}", self.func_id, self.count);
+			let queue = MutableValue::Data(FullDataLocation {
+				loc: DataLocation::Storage(QUEUE_STORAGE.into()),
+				path: DataPath::String(format!("{}_{}", cleanup_fn_id(self.func_id), self.count)),
+			});
+			let first = MutableValue::Index(Box::new(queue.clone()), 0);
+			self.count += 1;
+
+			// Fill the queue, finishing right away if there is nothing in it
+			out.push(Instruction::new(InstrKind::Assign {
+				left: queue,
+				right: list,
+			}));
+			if let Some(callback) = &callback {
+				out.push(if_block(
+					Condition::Not(Box::new(Condition::Exists(Value::Mutable(first.clone())))),
+					vec![callback.clone()],
+				));
+			}
+
+			// The step function takes the first item off of the queue and runs the body on it
+			let step_id: ResourceLocation = format!("{base_id}_step").into();
+			let mut step = Block::new();
+			step.contents.push(Instruction::new(InstrKind::Declare {
+				left: item,
+				ty,
+				right: DeclareBinding::Value(Value::Mutable(first.clone())),
+			}));
+			step.contents
+				.push(Instruction::new(InstrKind::Remove { val: first.clone() }));
+			step.contents.extend(body.contents);
+			self.add_func(step_id.clone(), step, FunctionAnnotations::new());
+
+			// The tick function runs the step until it has done enough items for this
+			// tick or the queue runs out
+			let call_step = Instruction::new(InstrKind::Call {
+				call: CallInterface {
+					function: step_id,
+					args: Vec::new(),
+					ret: Vec::new(),
+				},
+			});
+			let mut batch = vec![call_step.clone()];
+			for _ in 1..per_tick {
+				batch.push(if_block(
+					Condition::Exists(Value::Mutable(first.clone())),
+					vec![call_step.clone()],
+				));
+			}
+			if let Some(callback) = callback {
+				batch.push(if_block(
+					Condition::Not(Box::new(Condition::Exists(Value::Mutable(first.clone())))),
+					vec![callback],
+				));
+			}
+			let mut tick = Block::new();
+			tick.contents
+				.push(if_block(Condition::Exists(Value::Mutable(first)), batch));
+			let annotations = FunctionAnnotations {
+				preserve: true,
+				tick: true,
+				// Tick functions run in the same order as the functions that start them
+				order: self.order,
+				freq: Some(InvocationFrequency::Tick),
+				..Default::default()
+			};
+			self.add_func(base_id.into(), tick, annotations);
+		}
+		block.contents = out;
+
+		Ok(())
+	}
+
+	/// Creates the call to a function that runs once all of the items are done
+	fn get_callback(&self, func: ResourceLocation) -> anyhow::Result<Instruction> {
+		let kind = match self.param_counts.get(&func) {
+			Some(0) => InstrKind::Call {
+				call: CallInterface {
+					function: func,
+					args: Vec::new(),
+					ret: Vec::new(),
+				},
+			},
+			Some(..) => bail!("Spread completion function {func} cannot have parameters"),
+			// Functions from outside of the project can only be called by name
+			None => InstrKind::CallExtern { func },
+		};
+
+		Ok(Instruction::new(kind))
+	}
+
+	fn add_func(
+		&mut self,
+		id: ResourceLocation,
+		block: Block,
+		mut annotations: FunctionAnnotations,
+	) {
+		annotations.allow = self.allow.to_vec();
+		annotations.generated = true;
+		self.out.push(IRFunction {
+			interface: FunctionInterface::with_all(
+				id,
+				FunctionSignature::with_all(Vec::new(), ReturnType::Void),
+				annotations,
+			),
+			block,
+		});
+	}
+}
+
+fn if_block(condition: Condition, contents: Vec<Instruction>) -> Instruction {
+	let mut body = Block::new();
+	body.contents = contents;
+	Instruction::new(InstrKind::If {
+		condition,
+		body: Box::new(body),
+	})
+}
@@ -0,0 +1,424 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use itertools::Itertools;
+
+use crate::common::condition::Condition;
+use crate::common::function::{CallInterface, FunctionInterface, FunctionSignature, ReturnType};
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation};
+use crate::common::storage::get_reg_storage;
+use crate::common::ty::{
+	DataType, DataTypeContents, NBTArrayType, NBTArrayTypeContents, NBTCompoundTypeContents,
+	NBTType, NBTTypeContents, ScoreType, ScoreTypeContents,
+};
+use crate::common::val::{MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier, ResourceLocation};
+use crate::mir::{MIRBlock, MIRFunction, MIRInstrKind, MIRInstruction, MIR};
+use crate::output::get_generated_fn_id;
+use crate::output::text::REG_STORAGE_LOCATION;
+use crate::project::ProjectSettings;
+
+/// The positions of the hyphens in the hyphenated form of a UUID
+const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+/// Replaces UUID conversions with calls to generated helper functions. The helpers
+/// use macros to map each hex digit, so only the canonical 36 character form of a
+/// UUID string can be converted to an int array
+pub fn legalize_uuids(mir: &mut MIR, project: &ProjectSettings) -> anyhow::Result<()> {
+	let ids = UUIDHelpers {
+		to_string: get_generated_fn_id(project, "uuid/to_string").into(),
+		from_string: get_generated_fn_id(project, "uuid/from_string").into(),
+	};
+	let mut used = false;
+	for func in mir.functions.values_mut() {
+		legalize_block(&mut func.block, &ids, &mut used);
+	}
+	if !used {
+		return Ok(());
+	}
+
+	let storage = get_reg_storage(&mir.storages)
+		.cloned()
+		.unwrap_or_else(|| REG_STORAGE_LOCATION.into());
+	let hex_fn: ResourceLocation = get_generated_fn_id(project, "uuid/hex").into();
+	let join_fn: ResourceLocation = get_generated_fn_id(project, "uuid/join").into();
+	let lookup_fn: ResourceLocation = get_generated_fn_id(project, "uuid/lookup").into();
+
+	let funcs = [
+		(
+			ids.to_string.clone(),
+			FunctionSignature::with_all(vec![uuid_ty()], ReturnType::Standard(vec![string_ty()])),
+			gen_to_string(&storage, &hex_fn, &join_fn),
+		),
+		(
+			ids.from_string.clone(),
+			FunctionSignature::with_all(vec![string_ty()], ReturnType::Standard(vec![uuid_ty()])),
+			gen_from_string(&storage, &lookup_fn),
+		),
+		(hex_fn, FunctionSignature::new(), gen_hex_macro(&storage)),
+		(join_fn, FunctionSignature::new(), gen_join_macro(&storage)),
+		(
+			lookup_fn,
+			FunctionSignature::new(),
+			gen_lookup_macro(&storage),
+		),
+	];
+	for (id, sig, contents) in funcs {
+		if mir.functions.contains_key(&id) {
+			bail!(
+				"Function {id} generated for UUID conversions conflicts with an existing function"
+			);
+		}
+		let func = MIRFunction {
+			interface: FunctionInterface::with_signature(id.clone(), sig),
+			block: MIRBlock::with_contents(contents.into_iter().map(MIRInstruction::new).collect()),
+		};
+		mir.functions.insert(id, func);
+	}
+
+	Ok(())
+}
+
+struct UUIDHelpers {
+	to_string: ResourceLocation,
+	from_string: ResourceLocation,
+}
+
+fn legalize_block(block: &mut MIRBlock, ids: &UUIDHelpers, used: &mut bool) {
+	for instr in &mut block.contents {
+		if let MIRInstrKind::ReturnRun { body } = &mut instr.kind {
+			legalize_block(body, ids, used);
+		}
+		for body in instr.kind.get_bodies_mut() {
+			legalize_block(body, ids, used);
+		}
+
+		let (function, left, right) = match &instr.kind {
+			MIRInstrKind::UUIDToString { left, right } => (&ids.to_string, left, right),
+			MIRInstrKind::StringToUUID { left, right } => (&ids.from_string, left, right),
+			_ => continue,
+		};
+		*used = true;
+		instr.kind = MIRInstrKind::Call {
+			call: CallInterface {
+				function: function.clone(),
+				args: vec![right.clone()],
+				ret: vec![left.clone()],
+			},
+		};
+	}
+}
+
+/// Generates the helper that splits each int of a UUID into hex digits and joins them
+fn gen_to_string(
+	storage: &ResourceLocation,
+	hex_fn: &ResourceLocation,
+	join_fn: &ResourceLocation,
+) -> Vec<MIRInstrKind> {
+	let mut out = Vec::new();
+	declare(
+		&mut out,
+		"uuid",
+		uuid_ty(),
+		Value::Mutable(MutableValue::Arg(0)),
+	);
+	let empty = NBTTypeContents::Compound(Arc::default(), NBTCompoundTypeContents::new());
+	declare(
+		&mut out,
+		"args",
+		DataType::NBT(NBTType::Compound(Arc::default())),
+		Value::Constant(DataTypeContents::NBT(empty)),
+	);
+	declare(&mut out, "part", score_ty(), constant(0));
+	declare(&mut out, "top", score_ty(), constant(0));
+	declare(&mut out, "digit", score_ty(), constant(0));
+
+	declare(
+		&mut out,
+		"int",
+		int_ty(),
+		Value::Constant(DataTypeContents::NBT(NBTTypeContents::Int(0))),
+	);
+
+	let set_digit = |out: &mut Vec<MIRInstrKind>, digit: usize, val: &str| {
+		out.push(cast_int(val));
+		out.push(assign(
+			&MutableValue::Property(Box::new(reg("args")), format!("h{digit}")),
+			Value::Mutable(reg("int")),
+		));
+	};
+
+	for i in 0..4 {
+		out.push(MIRInstrKind::Assign {
+			left: reg("part"),
+			right: DeclareBinding::Cast(
+				score_ty(),
+				Value::Mutable(MutableValue::Index(Box::new(reg("uuid")), i)),
+				1.0,
+			),
+		});
+		out.push(assign(&reg("top"), constant(0)));
+		// Negative ints are moved up into the positive range so that the digits can be
+		// taken off with division, with the top bit added back to the highest digit
+		out.push(MIRInstrKind::If {
+			condition: Condition::LessThan(Value::Mutable(reg("part")), constant(0)),
+			body: Box::new(MIRBlock::with_contents(
+				[
+					MIRInstrKind::Add {
+						left: reg("part"),
+						right: constant(i32::MAX),
+					},
+					MIRInstrKind::Add {
+						left: reg("part"),
+						right: constant(1),
+					},
+					assign(&reg("top"), constant(8)),
+				]
+				.into_iter()
+				.map(MIRInstruction::new)
+				.collect(),
+			)),
+		});
+		for digit in (1..8).rev() {
+			out.push(assign(&reg("digit"), Value::Mutable(reg("part"))));
+			out.push(MIRInstrKind::Mod {
+				left: reg("digit"),
+				right: constant(16),
+			});
+			out.push(MIRInstrKind::Div {
+				left: reg("part"),
+				right: constant(16),
+			});
+			set_digit(&mut out, i * 8 + digit, "digit");
+		}
+		out.push(MIRInstrKind::Add {
+			left: reg("part"),
+			right: Value::Mutable(reg("top")),
+		});
+		set_digit(&mut out, i * 8, "part");
+	}
+
+	let digits = (0..16).map(|x| format!("\"{x:x}\"")).join(",");
+	out.push(MIRInstrKind::Command {
+		command: format!("data modify storage {storage} __uuid_digits set value [{digits}]"),
+	});
+	out.push(MIRInstrKind::CallWith {
+		func: hex_fn.clone(),
+		args: Value::Mutable(reg("args")),
+	});
+	out.push(MIRInstrKind::CallWith {
+		func: join_fn.clone(),
+		args: Value::Mutable(storage_val(storage, "__uuid_hex")),
+	});
+	out.push(MIRInstrKind::ReturnValue {
+		index: 0,
+		value: Value::Mutable(storage_val(storage, "__uuid_out")),
+	});
+
+	out
+}
+
+/// Generates the helper that takes each hex digit of a string and adds them up into
+/// the ints of a UUID
+fn gen_from_string(storage: &ResourceLocation, lookup_fn: &ResourceLocation) -> Vec<MIRInstrKind> {
+	let mut out = Vec::new();
+	out.push(MIRInstrKind::Assign {
+		left: storage_val(storage, "__uuid_in"),
+		right: DeclareBinding::Value(Value::Mutable(MutableValue::Arg(0))),
+	});
+	let values = (0..16)
+		.map(|x| format!("\"{x:x}\":{x}"))
+		.chain((10..16).map(|x| format!("\"{x:X}\":{x}")))
+		.join(",");
+	out.push(MIRInstrKind::Command {
+		command: format!("data modify storage {storage} __uuid_values set value {{{values}}}"),
+	});
+	out.push(MIRInstrKind::Command {
+		command: format!("data modify storage {storage} __uuid_nibbles set value {{}}"),
+	});
+	for digit in 0..32 {
+		let pos = get_digit_pos(digit);
+		out.push(MIRInstrKind::Command {
+			command: format!(
+				"data modify storage {storage} __uuid_chars.c{digit} set string storage {storage} __uuid_in {pos} {}",
+				pos + 1
+			),
+		});
+	}
+	out.push(MIRInstrKind::CallWith {
+		func: lookup_fn.clone(),
+		args: Value::Mutable(storage_val(storage, "__uuid_chars")),
+	});
+
+	let zero = NBTArrayTypeContents::Int(vec![0; 4], 4);
+	declare(
+		&mut out,
+		"out",
+		uuid_ty(),
+		Value::Constant(DataTypeContents::NBT(NBTTypeContents::Arr(zero))),
+	);
+	declare(&mut out, "acc", score_ty(), constant(0));
+	declare(&mut out, "digit", score_ty(), constant(0));
+	declare(
+		&mut out,
+		"int",
+		int_ty(),
+		Value::Constant(DataTypeContents::NBT(NBTTypeContents::Int(0))),
+	);
+	for i in 0..4 {
+		out.push(assign(&reg("acc"), constant(0)));
+		// Overflow wraps around the same way as the int would in two's complement
+		for digit in i * 8..i * 8 + 8 {
+			let path = DataPath::Access(
+				Box::new(DataPath::String("__uuid_nibbles".into())),
+				format!("c{digit}"),
+			);
+			let val = MutableValue::Data(FullDataLocation {
+				loc: DataLocation::Storage(storage.clone()),
+				path,
+			});
+			out.push(MIRInstrKind::Assign {
+				left: reg("digit"),
+				right: DeclareBinding::Cast(score_ty(), Value::Mutable(val), 1.0),
+			});
+			out.push(MIRInstrKind::Mul {
+				left: reg("acc"),
+				right: constant(16),
+			});
+			out.push(MIRInstrKind::Add {
+				left: reg("acc"),
+				right: Value::Mutable(reg("digit")),
+			});
+		}
+		out.push(cast_int("acc"));
+		out.push(assign(
+			&MutableValue::Index(Box::new(reg("out")), i),
+			Value::Mutable(reg("int")),
+		));
+	}
+	out.push(MIRInstrKind::ReturnValue {
+		index: 0,
+		value: Value::Mutable(reg("out")),
+	});
+
+	out
+}
+
+/// Generates the macro function that turns each digit value into its hex character
+fn gen_hex_macro(storage: &ResourceLocation) -> Vec<MIRInstrKind> {
+	(0..32)
+		.map(|digit| MIRInstrKind::MacroCommand {
+			command: format!(
+				"data modify storage {storage} __uuid_hex.h{digit} set from storage {storage} __uuid_digits[$(h{digit})]"
+			),
+		})
+		.collect()
+}
+
+/// Generates the macro function that joins the hex characters into the hyphenated form
+fn gen_join_macro(storage: &ResourceLocation) -> Vec<MIRInstrKind> {
+	let mut string = String::new();
+	for digit in 0..32 {
+		if digit > 0 && get_digit_pos(digit) != get_digit_pos(digit - 1) + 1 {
+			string.push('-');
+		}
+		string.push_str(&format!("$(h{digit})"));
+	}
+
+	vec![MIRInstrKind::MacroCommand {
+		command: format!("data modify storage {storage} __uuid_out set value \"{string}\""),
+	}]
+}
+
+/// Generates the macro function that turns each hex character into its digit value
+fn gen_lookup_macro(storage: &ResourceLocation) -> Vec<MIRInstrKind> {
+	(0..32)
+		.map(|digit| MIRInstrKind::MacroCommand {
+			command: format!(
+				"data modify storage {storage} __uuid_nibbles.c{digit} set from storage {storage} __uuid_values.\"$(c{digit})\""
+			),
+		})
+		.collect()
+}
+
+/// Gets the position of a hex digit in the hyphenated form of a UUID
+fn get_digit_pos(digit: usize) -> usize {
+	let mut pos = digit;
+	for hyphen in HYPHENS {
+		if pos >= hyphen {
+			pos += 1;
+		}
+	}
+	pos
+}
+
+fn declare(out: &mut Vec<MIRInstrKind>, name: &str, ty: DataType, val: Value) {
+	out.push(MIRInstrKind::Declare {
+		left: name.into(),
+		ty,
+	});
+	out.push(assign(&reg(name), val));
+}
+
+fn assign(left: &MutableValue, val: Value) -> MIRInstrKind {
+	MIRInstrKind::Assign {
+		left: left.clone(),
+		right: DeclareBinding::Value(val),
+	}
+}
+
+/// Casts a score register into the int register, since casts can't store into
+/// properties and indices
+fn cast_int(val: &str) -> MIRInstrKind {
+	MIRInstrKind::Assign {
+		left: reg("int"),
+		right: DeclareBinding::Cast(int_ty(), Value::Mutable(reg(val)), 1.0),
+	}
+}
+
+fn reg(name: &str) -> MutableValue {
+	MutableValue::Reg(Identifier::from(name))
+}
+
+fn storage_val(storage: &ResourceLocation, path: &str) -> MutableValue {
+	MutableValue::Data(FullDataLocation {
+		loc: DataLocation::Storage(storage.clone()),
+		path: DataPath::String(path.into()),
+	})
+}
+
+fn constant(val: i32) -> Value {
+	Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(val)))
+}
+
+fn score_ty() -> DataType {
+	DataType::Score(ScoreType::Score)
+}
+
+fn uuid_ty() -> DataType {
+	DataType::NBT(NBTType::Arr(NBTArrayType::Int(4)))
+}
+
+fn int_ty() -> DataType {
+	DataType::NBT(NBTType::Int)
+}
+
+fn string_ty() -> DataType {
+	DataType::NBT(NBTType::String)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_digit_positions() {
+		let positions: Vec<_> = (0..32).map(get_digit_pos).collect();
+		assert_eq!(positions[7], 7);
+		assert_eq!(positions[8], 9);
+		assert_eq!(positions[12], 14);
+		assert_eq!(positions[20], 24);
+		assert_eq!(positions[31], 35);
+		assert!(positions.iter().all(|x| !HYPHENS.contains(x)));
+	}
+}
@@ -1,16 +1,34 @@
-use std::io::{stdin, Read};
+use std::io::{stdin, stdout, Read, Write};
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use anyhow::Context;
-use clap::Parser;
-use dpc::{codegen_ir, project::ProjectSettings, CodegenIRSettings};
+use anyhow::{bail, Context};
+use clap::{Parser, Subcommand, ValueEnum};
+use dpc::common::diag::Diagnostic;
+use dpc::common::snippet::Snippet;
+use dpc::determinism::check_determinism;
+use dpc::ir::IR;
+use dpc::output::datapack::{LineEnding, TextFormat};
+use dpc::lower::ir_to_mir::lower_ir;
+use dpc::passes::analysis::graph::export_dot;
+use dpc::parse::version::migrate_text;
+use dpc::passes::run_mir_passes;
+use dpc::project::{OptimizationLevel, ProjectSettingsBuilder};
+use dpc::repl::Repl;
+use dpc::workspace::Workspace;
+use dpc::{
+	codegen_ir, codegen_ir_streaming, codegen_ir_subset, parse_files, project::ProjectSettings,
+	CodegenIRSettings,
+};
 
 fn main() -> ExitCode {
 	let cli = Cli::parse();
 	let res = run(cli);
 	if let Err(e) = res {
-		eprintln!("{e:#?}");
+		// Diagnostics are printed when they are created
+		if !e.is::<Reported>() {
+			eprintln!("{e:#?}");
+		}
 		return ExitCode::FAILURE;
 	}
 
@@ -18,60 +36,481 @@ fn main() -> ExitCode {
 }
 
 fn run(cli: Cli) -> anyhow::Result<()> {
-	let contents = if cli.stdin {
+	match cli.command {
+		Some(Command::Repl) => return run_repl(cli.name),
+		Some(Command::Workspace {
+			config,
+			out,
+			update_lock,
+		}) => return run_workspace(&config, &out, update_lock),
+		Some(Command::Inspect {
+			file,
+			graph,
+			optimize,
+		}) => return run_inspect(&file, graph, optimize),
+		Some(Command::Migrate { file, write }) => return run_migrate(&file, write),
+		None => {}
+	}
+
+	// Diagnostics can only point into the input when there is a single file
+	let (ir, source) = if cli.stdin {
 		let mut stdin = stdin();
 		let mut text = String::new();
 		stdin
 			.read_to_string(&mut text)
 			.context("Failed to read from stdin")?;
-		text
+		let ir = parse_single("<stdin>", &text)?;
+		(ir, Some(("<stdin>".to_string(), text)))
 	} else {
-		let file = cli.file.context("No file specified")?;
-		let path = PathBuf::from(file);
-
-		std::fs::read_to_string(path).context("Failed to read input file")?
+		let files = get_source_files(&cli.files)?;
+		if let [file] = files.as_slice() {
+			let text = std::fs::read_to_string(file).context("Failed to read input file")?;
+			let file_name = file.to_string_lossy().to_string();
+			let ir = parse_single(&file_name, &text)?;
+			(ir, Some((file_name, text)))
+		} else {
+			(parse_files(&files)?, None)
+		}
 	};
-
-	// Parse the input
-	let mut parse = dpc::parse::Parser::new();
-	parse.parse(&contents).expect("Failed to parse input");
-	let ir = parse.finish();
-
-	let settings = CodegenIRSettings {
-		debug: false,
-		debug_functions: false,
-		ir_passes: false,
-		mir_passes: false,
-		lir_passes: false,
+	let report = |e| match &source {
+		Some((file_name, contents)) => report(e, file_name, contents),
+		None => e,
 	};
+
 	let name = if let Some(name) = cli.name {
 		name.clone()
 	} else {
 		"dpc".into()
 	};
+	let mut project = ProjectSettingsBuilder::new(&name).op_level(cli.opt_level.into());
+	if let Some(objective) = &cli.literal_objective {
+		project = project.literal_objective(objective);
+	}
+	if let Some(namespace) = &cli.internal_namespace {
+		project = project.internal_namespace(namespace);
+	}
+	project = project
+		.create_objectives(cli.create_objectives)
+		.load_init(!cli.no_load_init);
+	for snippet in &cli.snippet {
+		let (id, path) = snippet
+			.split_once('=')
+			.context("Snippets must be given as ID=PATH")?;
+		let text = std::fs::read_to_string(path)
+			.with_context(|| format!("Failed to read snippet {path}"))?;
+		let snippet = Snippet::parse(id.into(), &text)
+			.with_context(|| format!("Failed to parse snippet {id}"))?;
+		project = project.snippet(snippet);
+	}
+	if let Some(icon) = &cli.pack_icon {
+		project = project.pack_icon(icon);
+	}
+	if let Some(license) = &cli.license {
+		project = project.license(license);
+	}
+	if let Some(readme) = &cli.readme {
+		project = project.readme(readme);
+	}
+	project = project.text_format(TextFormat {
+		line_ending: if cli.crlf {
+			LineEnding::Crlf
+		} else {
+			LineEnding::Lf
+		},
+		trailing_newline: cli.trailing_newline,
+		bom: cli.bom,
+	});
+	let project = project.build();
+
+	// Higher optimization levels turn on the MIR and LIR passes unless they are disabled
+	let implied = matches!(cli.opt_level, OptLevel::More | OptLevel::Full);
+	let passes = CodegenIRSettings {
+		ir_passes: cli.ir_passes || !cli.no_ir_passes,
+		mir_passes: cli.mir_passes || (implied && !cli.no_mir_passes),
+		lir_passes: cli.lir_passes || (implied && !cli.no_lir_passes),
+		..CodegenIRSettings::new()
+	};
+
+	if cli.determinism_check {
+		return run_determinism_check(ir, &project, passes);
+	}
+
+	let out = cli.out.context("No output directory specified")?;
+	let settings = CodegenIRSettings {
+		debug: cli.debug,
+		tick_budget: cli.tick_budget,
+		explain: cli
+			.explain
+			.then(|| PathBuf::from(&out).join("optimizations.txt")),
+		..passes
+	};
 
 	// Run the codegen
-	let datapack =
-		codegen_ir(ir, &ProjectSettings::new(name), settings).expect("Failed to codegen input");
+	if let Some(batch_size) = cli.stream {
+		let stream_report =
+			codegen_ir_streaming(ir, &project, settings, &PathBuf::from(out), batch_size)
+				.map_err(report)?;
+		print_warnings(&stream_report.diagnostics, source.as_ref());
+		if let Some(tick_budget) = &stream_report.tick_budget {
+			print!("{tick_budget}");
+		}
+		println!("{stream_report}");
+		return Ok(());
+	}
+	let datapack = if cli.only.is_empty() {
+		codegen_ir(ir, &project, settings).map_err(report)?
+	} else {
+		let functions: Vec<_> = cli.only.iter().map(|x| x.as_str().into()).collect();
+		codegen_ir_subset(ir, &functions, &project, settings).map_err(report)?
+	};
+	print_warnings(&datapack.diagnostics, source.as_ref());
+	if let Some(tick_budget) = &datapack.tick_budget {
+		print!("{tick_budget}");
+	}
+	if cli.requirements {
+		print!("{}", datapack.requirements());
+	}
+	if cli.bundle {
+		return datapack.output_bundle(&PathBuf::from(out));
+	}
 	datapack
-		.output(&PathBuf::from(cli.out))
+		.output(&PathBuf::from(out))
 		.context("Failed to output datapack")?;
 
 	Ok(())
 }
 
+/// Parses a single file, printing a diagnostic if it fails
+fn parse_single(file_name: &str, contents: &str) -> anyhow::Result<IR> {
+	let mut parse = dpc::parse::Parser::new();
+	parse
+		.parse(contents)
+		.map_err(|e| report(e, file_name, contents))?;
+	Ok(parse.finish())
+}
+
+/// Gets the source files to compile from the paths given on the command line.
+/// Directories are searched for .dpc files, which are compiled in order of their names
+fn get_source_files(paths: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+	if paths.is_empty() {
+		bail!("No file specified");
+	}
+	let mut out = Vec::new();
+	for path in paths {
+		let path = PathBuf::from(path);
+		if !path.is_dir() {
+			out.push(path);
+			continue;
+		}
+		let entries = std::fs::read_dir(&path)
+			.with_context(|| format!("Failed to read directory {path:?}"))?;
+		let mut files = Vec::new();
+		for entry in entries {
+			let entry = entry.context("Failed to read directory entry")?;
+			let file = entry.path();
+			if file.is_file() && file.extension().is_some_and(|x| x == "dpc") {
+				files.push(file);
+			}
+		}
+		files.sort();
+		out.extend(files);
+	}
+
+	Ok(out)
+}
+
+/// Prints an error as a diagnostic pointing to where it happened in the input file
+fn report(e: anyhow::Error, file_name: &str, contents: &str) -> anyhow::Error {
+	eprint!("{}", Diagnostic::from_error(&e).render(file_name, contents));
+	Reported.into()
+}
+
+/// Prints the warnings from compiling the input, pointing into the input file if there is only one
+fn print_warnings(diagnostics: &[Diagnostic], source: Option<&(String, String)>) {
+	for diagnostic in diagnostics {
+		match source {
+			Some((file_name, contents)) => eprint!("{}", diagnostic.render(file_name, contents)),
+			None => eprintln!("{diagnostic}"),
+		}
+	}
+}
+
+/// Error for when a diagnostic has already been printed
+#[derive(Debug)]
+struct Reported;
+
+impl std::fmt::Display for Reported {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Compilation failed")
+	}
+}
+
+impl std::error::Error for Reported {}
+
+fn run_determinism_check(
+	ir: IR,
+	project: &ProjectSettings,
+	settings: CodegenIRSettings,
+) -> anyhow::Result<()> {
+	let found = check_determinism(ir, project, settings)?;
+	if found.is_empty() {
+		println!("Output is deterministic");
+		return Ok(());
+	}
+	for nondeterminism in &found {
+		println!("{nondeterminism}");
+	}
+	bail!("Found {} nondeterministic locations in the output", found.len());
+}
+
+fn run_workspace(config: &str, out: &str, update_lock: bool) -> anyhow::Result<()> {
+	let workspace = Workspace::load_with_lock(&PathBuf::from(config), update_lock)?;
+	let packs = workspace.build(CodegenIRSettings::new())?;
+	for (name, pack) in packs {
+		print_warnings(&pack.diagnostics, None);
+		pack.output(&PathBuf::from(out).join(&name))
+			.with_context(|| format!("Failed to output datapack {name}"))?;
+	}
+
+	Ok(())
+}
+
+fn run_inspect(file: &str, graph: GraphFormat, optimize: bool) -> anyhow::Result<()> {
+	let contents = std::fs::read_to_string(file).context("Failed to read input file")?;
+	let mut parse = dpc::parse::Parser::new();
+	parse
+		.parse(&contents)
+		.map_err(|e| report(e, file, &contents))?;
+	let mut mir = lower_ir(parse.finish()).map_err(|e| report(e, file, &contents))?;
+	if optimize {
+		run_mir_passes(&mut mir, &ProjectSettings::new("dpc".into()), false)
+			.context("MIR passes failed")?;
+	}
+
+	match graph {
+		GraphFormat::Dot => print!("{}", export_dot(&mir)),
+	}
+
+	Ok(())
+}
+
+fn run_migrate(file: &str, write: bool) -> anyhow::Result<()> {
+	let contents = std::fs::read_to_string(file).context("Failed to read input file")?;
+	let migrated = migrate_text(&contents).context("Failed to migrate file")?;
+	if write {
+		std::fs::write(file, migrated).context("Failed to write migrated file")?;
+	} else {
+		print!("{migrated}");
+	}
+
+	Ok(())
+}
+
+fn run_repl(name: Option<String>) -> anyhow::Result<()> {
+	let name = name.unwrap_or_else(|| "dpc".into());
+	let mut repl = Repl::new(ProjectSettings::new(name));
+
+	let stdin = stdin();
+	let mut line = String::new();
+	loop {
+		print!("> ");
+		stdout().flush().context("Failed to flush stdout")?;
+		line.clear();
+		let read = stdin
+			.read_line(&mut line)
+			.context("Failed to read from stdin")?;
+		let trimmed = line.trim();
+		if read == 0 || trimmed == "exit" {
+			break;
+		}
+		if trimmed.is_empty() {
+			continue;
+		}
+
+		match repl.eval(trimmed) {
+			Ok(cmds) => {
+				for cmd in cmds {
+					println!("{cmd}");
+				}
+			}
+			Err(e) => eprintln!("{e:?}"),
+		}
+	}
+
+	Ok(())
+}
+
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Cli {
+	#[command(subcommand)]
+	command: Option<Command>,
 	/// Whether to take input from stdin
 	#[arg(short, long)]
 	stdin: bool,
 	/// The output directory
 	#[arg(short, long)]
-	out: String,
+	out: Option<String>,
 	/// The project name, which is used for namespacing things.
 	/// Defaults to 'dpc'
 	#[arg(short, long)]
 	name: Option<String>,
-	/// The file to read from
-	file: Option<String>,
+	/// How aggressively to optimize the pack
+	#[arg(short = 'O', long, value_enum, default_value_t = OptLevel::Basic)]
+	opt_level: OptLevel,
+	/// The objective to put score literals in. Defaults to '_l'
+	#[arg(long)]
+	literal_objective: Option<String>,
+	/// A namespace to put the functions that the compiler generates in
+	#[arg(long)]
+	internal_namespace: Option<String>,
+	/// Create the objectives that scores in the pack use when it loads
+	#[arg(long)]
+	create_objectives: bool,
+	/// Don't run the init function from the load tag, so that it can be called manually
+	#[arg(long)]
+	no_load_init: bool,
+	/// A handwritten mcfunction file to copy into the pack, given as ID=PATH.
+	/// Comments like '# @writes storage foo:bar' declare what the file does
+	#[arg(long, value_name = "ID=PATH")]
+	snippet: Vec<String>,
+	/// An image to copy into the pack as its pack.png
+	#[arg(long)]
+	pack_icon: Option<PathBuf>,
+	/// A license file to copy into the pack
+	#[arg(long)]
+	license: Option<PathBuf>,
+	/// A readme file to copy into the pack
+	#[arg(long)]
+	readme: Option<PathBuf>,
+	/// Use CRLF line endings in the text files of the pack instead of LF
+	#[arg(long)]
+	crlf: bool,
+	/// End the text files of the pack with a line ending
+	#[arg(long)]
+	trailing_newline: bool,
+	/// Start the text files of the pack with a UTF-8 byte order mark
+	#[arg(long)]
+	bom: bool,
+	/// Run the IR passes, which validate the input and do some early simplification.
+	/// These are enabled by default
+	#[arg(long, overrides_with = "no_ir_passes")]
+	ir_passes: bool,
+	/// Skip the IR passes
+	#[arg(long, overrides_with = "ir_passes")]
+	no_ir_passes: bool,
+	/// Run the MIR optimization passes. These are enabled by default at
+	/// optimization levels of more and above
+	#[arg(long, overrides_with = "no_mir_passes")]
+	mir_passes: bool,
+	/// Skip the MIR optimization passes
+	#[arg(long, overrides_with = "mir_passes")]
+	no_mir_passes: bool,
+	/// Run the LIR optimization passes. These are enabled by default at
+	/// optimization levels of more and above
+	#[arg(long, overrides_with = "no_lir_passes")]
+	lir_passes: bool,
+	/// Skip the LIR optimization passes
+	#[arg(long, overrides_with = "lir_passes")]
+	no_lir_passes: bool,
+	/// Print the IR, MIR, and LIR as they go through the compiler
+	#[arg(long)]
+	debug: bool,
+	/// Whether to print an estimate of the commands run every tick
+	#[arg(long)]
+	tick_budget: bool,
+	/// Whether to write a report of the optimizations that changed each
+	/// function to optimizations.txt in the output directory
+	#[arg(long)]
+	explain: bool,
+	/// Whether to print the commands, gamerules, and minimum game version
+	/// that the generated pack needs
+	#[arg(long)]
+	requirements: bool,
+	/// Write all of the functions to one file at the output path for sharing,
+	/// instead of writing a pack directory
+	#[arg(long, conflicts_with = "stream")]
+	bundle: bool,
+	/// Only compile this function and the functions that it calls. Can be given
+	/// more than once
+	#[arg(long, value_name = "FUNCTION", conflicts_with = "stream")]
+	only: Vec<String>,
+	/// Compile the input multiple times with different map iteration orders
+	/// and report any differences between the outputs, instead of writing a pack
+	#[arg(long)]
+	determinism_check: bool,
+	/// Write functions to the output in batches of this size as they are generated,
+	/// instead of holding the whole pack in memory, and print the peak memory usage
+	#[arg(long, value_name = "BATCH_SIZE")]
+	stream: Option<usize>,
+	/// The files to read from. Directories are searched for .dpc files, and
+	/// all of the files are compiled together as one project
+	files: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Start an interactive session that prints the commands
+	/// generated by each instruction
+	Repl,
+	/// Build every project in a workspace into its own datapack
+	Workspace {
+		/// The workspace config file
+		config: String,
+		/// The output directory, which will contain a folder for each pack
+		#[arg(short, long)]
+		out: String,
+		/// Rewrite the lockfile with the current versions of packages
+		#[arg(long)]
+		update_lock: bool,
+	},
+	/// Print the call graph and register def-use chains of a file
+	Inspect {
+		/// The file to read from
+		file: String,
+		/// The format of the graph
+		#[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+		graph: GraphFormat,
+		/// Run the MIR passes before exporting the graph, to see what
+		/// the optimizer removed or kept
+		#[arg(long)]
+		optimize: bool,
+	},
+	/// Rewrite a file from an older version of the text format to the current one
+	Migrate {
+		/// The file to migrate
+		file: String,
+		/// Overwrite the file instead of printing the result
+		#[arg(short, long)]
+		write: bool,
+	},
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GraphFormat {
+	/// Graphviz DOT
+	Dot,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OptLevel {
+	/// Only run transformations that are needed for correctness
+	None,
+	/// Basic optimizations
+	Basic,
+	/// More intensive optimizations that can make the output harder to debug
+	More,
+	/// All optimizations. Can increase compile time by a lot
+	Full,
+}
+
+impl From<OptLevel> for OptimizationLevel {
+	fn from(value: OptLevel) -> Self {
+		match value {
+			OptLevel::None => Self::None,
+			OptLevel::Basic => Self::Basic,
+			OptLevel::More => Self::More,
+			OptLevel::Full => Self::Full,
+		}
+	}
 }
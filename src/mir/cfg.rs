@@ -0,0 +1,488 @@
+//! Control flow graph view of MIR functions. Functions are still stored as nested
+//! blocks, since the other passes and the lowering to LIR all work on that shape,
+//! and LIR has no control flow besides calls to functions. Passes that work on basic
+//! blocks build a graph from a function and convert it back with `into_block`, which
+//! can repeat blocks that are shared between paths. Storing the graph as the form of
+//! MIR and LIR functions themselves is not supported yet
+
+use anyhow::bail;
+use rustc_hash::FxHashSet;
+
+use crate::common::condition::Condition;
+use crate::common::function::CallInterface;
+use crate::common::lint::Lint;
+use crate::common::span::Span;
+
+use super::{MIRBlock, MIRInstrKind, MIRInstruction};
+
+/// The index of a block in a control flow graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockId(pub usize);
+
+/// A function as a graph of basic blocks instead of nested blocks. Control flow
+/// in MIR never loops inside a function, so the graph is always acyclic
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+	pub blocks: Vec<BasicBlock>,
+	pub entry: BlockId,
+}
+
+/// A list of instructions without any control flow, followed by a terminator
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+	pub contents: Vec<MIRInstruction>,
+	pub terminator: Terminator,
+}
+
+/// Where control goes after the end of a basic block
+#[derive(Debug, Clone)]
+pub enum Terminator {
+	/// Continues to another block
+	Jump(BlockId),
+	/// Continues to one of two blocks depending on a condition
+	Branch {
+		condition: Condition,
+		then: BlockId,
+		otherwise: BlockId,
+		allowed_lints: Vec<Lint>,
+		span: Option<Span>,
+	},
+	/// Calls a function and then continues to another block
+	Call {
+		call: CallInterface,
+		next: BlockId,
+		allowed_lints: Vec<Lint>,
+		span: Option<Span>,
+	},
+	/// Reaches the end of the function
+	Return,
+}
+
+impl Terminator {
+	/// Gets the blocks that control can go to from this terminator
+	pub fn successors(&self) -> Vec<BlockId> {
+		match self {
+			Self::Jump(target) | Self::Call { next: target, .. } => vec![*target],
+			Self::Branch {
+				then, otherwise, ..
+			} => vec![*then, *otherwise],
+			Self::Return => Vec::new(),
+		}
+	}
+
+	fn successors_mut(&mut self) -> Vec<&mut BlockId> {
+		match self {
+			Self::Jump(target) | Self::Call { next: target, .. } => vec![target],
+			Self::Branch {
+				then, otherwise, ..
+			} => vec![then, otherwise],
+			Self::Return => Vec::new(),
+		}
+	}
+}
+
+impl ControlFlowGraph {
+	/// Creates a graph from the nested blocks of a function
+	pub fn from_block(block: MIRBlock) -> Self {
+		let mut out = Self {
+			blocks: Vec::new(),
+			entry: BlockId(0),
+		};
+		let entry = out.add_block();
+		out.add_contents(block, entry);
+		out
+	}
+
+	/// Adds a new empty block that ends the function
+	pub fn add_block(&mut self) -> BlockId {
+		self.blocks.push(BasicBlock {
+			contents: Vec::new(),
+			terminator: Terminator::Return,
+		});
+		BlockId(self.blocks.len() - 1)
+	}
+
+	pub fn get(&self, id: BlockId) -> &BasicBlock {
+		&self.blocks[id.0]
+	}
+
+	pub fn get_mut(&mut self, id: BlockId) -> &mut BasicBlock {
+		&mut self.blocks[id.0]
+	}
+
+	/// Adds the instructions of a nested block starting at a basic block,
+	/// returning the basic block that control is in at the end
+	fn add_contents(&mut self, block: MIRBlock, mut current: BlockId) -> BlockId {
+		for instr in block.contents {
+			let allowed_lints = instr.allowed_lints;
+			let span = instr.span;
+			match instr.kind {
+				MIRInstrKind::If { condition, body } => {
+					let then = self.add_block();
+					let next = self.add_block();
+					self.get_mut(current).terminator = Terminator::Branch {
+						condition,
+						then,
+						otherwise: next,
+						allowed_lints,
+						span,
+					};
+					let end = self.add_contents(*body, then);
+					self.get_mut(end).terminator = Terminator::Jump(next);
+					current = next;
+				}
+				MIRInstrKind::IfElse {
+					condition,
+					first,
+					second,
+				} => {
+					let then = self.add_block();
+					let otherwise = self.add_block();
+					let next = self.add_block();
+					self.get_mut(current).terminator = Terminator::Branch {
+						condition,
+						then,
+						otherwise,
+						allowed_lints,
+						span,
+					};
+					let end = self.add_contents(*first, then);
+					self.get_mut(end).terminator = Terminator::Jump(next);
+					let end = self.add_contents(*second, otherwise);
+					self.get_mut(end).terminator = Terminator::Jump(next);
+					current = next;
+				}
+				MIRInstrKind::Call { call } => {
+					let next = self.add_block();
+					self.get_mut(current).terminator = Terminator::Call {
+						call,
+						next,
+						allowed_lints,
+						span,
+					};
+					current = next;
+				}
+				kind => self.get_mut(current).contents.push(MIRInstruction {
+					kind,
+					allowed_lints,
+					span,
+				}),
+			}
+		}
+
+		current
+	}
+
+	/// Gets the blocks that can be reached from the entry, in the order they are found
+	pub fn reachable(&self) -> Vec<BlockId> {
+		let mut out = Vec::new();
+		let mut visited = FxHashSet::default();
+		let mut stack = vec![self.entry];
+		while let Some(id) = stack.pop() {
+			if !visited.insert(id) {
+				continue;
+			}
+			out.push(id);
+			stack.extend(self.get(id).terminator.successors().into_iter().rev());
+		}
+
+		out
+	}
+
+	/// Gets the reachable predecessors of every block
+	pub fn predecessors(&self) -> Vec<Vec<BlockId>> {
+		let mut out = vec![Vec::new(); self.blocks.len()];
+		for id in self.reachable() {
+			for succ in self.get(id).terminator.successors() {
+				out[succ.0].push(id);
+			}
+		}
+
+		out
+	}
+
+	/// Merges blocks into the block that jumps to them when they have no other
+	/// predecessors, and skips over empty blocks that only jump somewhere else.
+	/// Returns whether anything changed
+	pub fn merge_blocks(&mut self) -> bool {
+		let mut changed = false;
+
+		// Skip over empty jump blocks
+		let forwards: Vec<_> = (0..self.blocks.len())
+			.map(|i| self.get_forwarded(BlockId(i)))
+			.collect();
+		for block in &mut self.blocks {
+			for target in block.terminator.successors_mut() {
+				if forwards[target.0] != *target {
+					*target = forwards[target.0];
+					changed = true;
+				}
+			}
+		}
+		if forwards[self.entry.0] != self.entry {
+			self.entry = forwards[self.entry.0];
+			changed = true;
+		}
+
+		// Branches to the same place only need a jump
+		for block in &mut self.blocks {
+			if let Terminator::Branch {
+				condition,
+				then,
+				otherwise,
+				..
+			} = &block.terminator
+			{
+				if then == otherwise && !condition.has_side_effects() {
+					block.terminator = Terminator::Jump(*then);
+					changed = true;
+				}
+			}
+		}
+
+		// Merge jumps into blocks with only one predecessor
+		loop {
+			let preds = self.predecessors();
+			let merge = self.reachable().into_iter().find_map(|id| {
+				let Terminator::Jump(target) = self.get(id).terminator else {
+					return None;
+				};
+				(target != id && target != self.entry && preds[target.0] == [id])
+					.then_some((id, target))
+			});
+			let Some((id, target)) = merge else {
+				break;
+			};
+			let target = std::mem::replace(
+				self.get_mut(target),
+				BasicBlock {
+					contents: Vec::new(),
+					terminator: Terminator::Return,
+				},
+			);
+			let block = self.get_mut(id);
+			block.contents.extend(target.contents);
+			block.terminator = target.terminator;
+			changed = true;
+		}
+
+		changed
+	}
+
+	/// Follows a chain of empty blocks that only jump to find where it ends up
+	fn get_forwarded(&self, mut id: BlockId) -> BlockId {
+		let mut visited = FxHashSet::default();
+		while let BasicBlock {
+			contents,
+			terminator: Terminator::Jump(target),
+		} = self.get(id)
+		{
+			if !contents.is_empty() || !visited.insert(id) {
+				break;
+			}
+			id = *target;
+		}
+
+		id
+	}
+
+	/// Finds the closest block that every path from each block goes through
+	/// on the way to the end of the function
+	fn immediate_post_dominators(&self) -> anyhow::Result<Vec<Option<BlockId>>> {
+		let order = self.topological_order()?;
+		let mut post_doms: Vec<FxHashSet<BlockId>> = vec![FxHashSet::default(); self.blocks.len()];
+		for id in order.iter().rev() {
+			let mut succs = self.get(*id).terminator.successors().into_iter();
+			let mut set = match succs.next() {
+				Some(first) => post_doms[first.0].clone(),
+				None => FxHashSet::default(),
+			};
+			for succ in succs {
+				set.retain(|x| post_doms[succ.0].contains(x));
+			}
+			set.insert(*id);
+			post_doms[id.0] = set;
+		}
+
+		// The immediate post dominator is the only strict one that is
+		// post dominated by all of the others
+		let out = (0..self.blocks.len())
+			.map(|i| {
+				let strict = post_doms[i].len().checked_sub(1)?;
+				post_doms[i]
+					.iter()
+					.find(|x| x.0 != i && post_doms[x.0].len() == strict)
+					.copied()
+			})
+			.collect();
+
+		Ok(out)
+	}
+
+	/// Orders the reachable blocks so that every block comes before its successors
+	fn topological_order(&self) -> anyhow::Result<Vec<BlockId>> {
+		let mut in_degree = vec![0; self.blocks.len()];
+		let reachable = self.reachable();
+		for id in &reachable {
+			for succ in self.get(*id).terminator.successors() {
+				in_degree[succ.0] += 1;
+			}
+		}
+
+		let mut out = Vec::with_capacity(reachable.len());
+		let mut ready = vec![self.entry];
+		while let Some(id) = ready.pop() {
+			out.push(id);
+			for succ in self.get(id).terminator.successors() {
+				in_degree[succ.0] -= 1;
+				if in_degree[succ.0] == 0 {
+					ready.push(succ);
+				}
+			}
+		}
+		if out.len() != reachable.len() {
+			bail!("Control flow graph has a cycle");
+		}
+
+		Ok(out)
+	}
+
+	/// Converts the graph back into nested blocks
+	pub fn into_block(self) -> anyhow::Result<MIRBlock> {
+		let post_doms = self.immediate_post_dominators()?;
+		let mut out = MIRBlock::new();
+		self.write_blocks(self.entry, None, &post_doms, &mut out);
+		Ok(out)
+	}
+
+	/// Writes the blocks from a start block up to a stopping block. Since the graph is
+	/// acyclic, blocks that are shared between paths can be safely written more than once
+	fn write_blocks(
+		&self,
+		start: BlockId,
+		stop: Option<BlockId>,
+		post_doms: &[Option<BlockId>],
+		out: &mut MIRBlock,
+	) {
+		let mut current = Some(start);
+		while let Some(id) = current {
+			if current == stop {
+				break;
+			}
+			let block = self.get(id);
+			out.contents.extend(block.contents.iter().cloned());
+			current = match &block.terminator {
+				Terminator::Jump(target) => Some(*target),
+				Terminator::Call {
+					call,
+					next,
+					allowed_lints,
+					span,
+				} => {
+					out.contents.push(MIRInstruction {
+						kind: MIRInstrKind::Call { call: call.clone() },
+						allowed_lints: allowed_lints.clone(),
+						span: *span,
+					});
+					Some(*next)
+				}
+				Terminator::Branch {
+					condition,
+					then,
+					otherwise,
+					allowed_lints,
+					span,
+				} => {
+					let join = post_doms[id.0];
+					let mut first = MIRBlock::new();
+					self.write_blocks(*then, join, post_doms, &mut first);
+					let mut second = MIRBlock::new();
+					self.write_blocks(*otherwise, join, post_doms, &mut second);
+					let kind = if second.contents.is_empty() {
+						MIRInstrKind::If {
+							condition: condition.clone(),
+							body: Box::new(first),
+						}
+					} else if first.contents.is_empty() {
+						MIRInstrKind::If {
+							condition: Condition::Not(Box::new(condition.clone())),
+							body: Box::new(second),
+						}
+					} else {
+						MIRInstrKind::IfElse {
+							condition: condition.clone(),
+							first: Box::new(first),
+							second: Box::new(second),
+						}
+					};
+					out.contents.push(MIRInstruction {
+						kind,
+						allowed_lints: allowed_lints.clone(),
+						span: *span,
+					});
+					join
+				}
+				Terminator::Return => None,
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::common::mc::instr::MinecraftInstr;
+	use crate::common::ty::{DataTypeContents, ScoreTypeContents};
+	use crate::common::val::Value;
+
+	use super::*;
+
+	fn say(message: &str) -> MIRInstruction {
+		MIRInstruction::new(MIRInstrKind::MC(MinecraftInstr::Say {
+			message: message.into(),
+		}))
+	}
+
+	#[test]
+	fn test_cfg_round_trip() {
+		let condition = Condition::Bool(Value::Constant(DataTypeContents::Score(
+			ScoreTypeContents::Bool(true),
+		)));
+		let block = MIRBlock::with_contents(vec![
+			say("start"),
+			MIRInstruction::new(MIRInstrKind::IfElse {
+				condition: condition.clone(),
+				first: Box::new(MIRBlock::with_contents(vec![say("first")])),
+				second: Box::new(MIRBlock::with_contents(vec![say("second")])),
+			}),
+			MIRInstruction::new(MIRInstrKind::If {
+				condition,
+				body: Box::new(MIRBlock::with_contents(vec![say("if")])),
+			}),
+			say("end"),
+		]);
+
+		let cfg = ControlFlowGraph::from_block(block.clone());
+		assert_eq!(cfg.blocks.len(), 6);
+		assert_eq!(cfg.into_block().expect("Graph is acyclic"), block);
+	}
+
+	#[test]
+	fn test_cfg_merge_blocks() {
+		let mut cfg = ControlFlowGraph::from_block(MIRBlock::with_contents(vec![
+			say("start"),
+			MIRInstruction::new(MIRInstrKind::If {
+				condition: Condition::Bool(Value::Constant(DataTypeContents::Score(
+					ScoreTypeContents::Bool(true),
+				))),
+				body: Box::new(MIRBlock::new()),
+			}),
+			say("end"),
+		]));
+		assert!(cfg.merge_blocks());
+		assert_eq!(cfg.reachable(), vec![cfg.entry]);
+		assert_eq!(
+			cfg.into_block().expect("Graph is acyclic"),
+			MIRBlock::with_contents(vec![say("start"), say("end")])
+		);
+	}
+}
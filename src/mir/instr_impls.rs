@@ -17,7 +17,7 @@ impl MIRInstrKind {
 				let right_regs: Box<dyn Iterator<Item = _>> = match right {
 					DeclareBinding::Null => Box::new(std::iter::empty()),
 					DeclareBinding::Value(val) => Box::new(val.get_used_regs_mut().into_iter()),
-					DeclareBinding::Cast(_, val) => Box::new(val.get_used_regs_mut().into_iter()),
+					DeclareBinding::Cast(_, val, _) => Box::new(val.get_used_regs_mut().into_iter()),
 
 					DeclareBinding::Condition(cond) => cond.iter_used_regs_mut(),
 				};
@@ -38,7 +38,14 @@ impl MIRInstrKind {
 			| Self::Insert { left, right, .. }
 			| Self::And { left, right }
 			| Self::Or { left, right }
-			| Self::Xor { left, right } => {
+			| Self::Xor { left, right }
+			| Self::BitAnd { left, right }
+			| Self::BitOr { left, right }
+			| Self::BitXor { left, right }
+			| Self::Shl { left, right }
+			| Self::Shr { left, right }
+			| Self::UUIDToString { left, right }
+			| Self::StringToUUID { left, right } => {
 				for reg in left
 					.get_used_regs_mut()
 					.into_iter()
@@ -58,6 +65,7 @@ impl MIRInstrKind {
 			}
 			Self::Abs { val }
 			| Self::Pow { base: val, .. }
+			| Self::Rand { left: val, .. }
 			| Self::Get { value: val, .. }
 			| Self::Use { val }
 			| Self::Not { value: val }
@@ -95,17 +103,26 @@ impl MIRInstrKind {
 			Self::ReturnRun { body } => {
 				body.replace_regs(f);
 			}
-			Self::ReturnValue { value, .. } | Self::Return { value } => {
+			Self::ReturnValue { value, .. }
+			| Self::Return { value }
+			| Self::CallWith { args: value, .. } => {
 				for reg in value.get_used_regs_mut() {
 					f(reg);
 				}
 			}
 			Self::NoOp
+			| Self::ReturnFail
 			| Self::GetConst { .. }
 			| Self::Command { .. }
+			| Self::MacroCommand { .. }
 			| Self::Comment { .. }
-			| Self::CallExtern { .. }
-			| Self::MC(..) => {}
+			| Self::CopyData { .. }
+			| Self::CallExtern { .. } => {}
+			Self::MC(instr) => {
+				for reg in instr.get_used_regs_mut() {
+					f(reg);
+				}
+			}
 		}
 	}
 
@@ -115,7 +132,7 @@ impl MIRInstrKind {
 				let right_regs: Box<dyn Iterator<Item = _>> = match right {
 					DeclareBinding::Null => Box::new(std::iter::empty()),
 					DeclareBinding::Value(val) => Box::new(val.iter_mut_val().into_iter()),
-					DeclareBinding::Cast(_, val) => Box::new(iter::once(val)),
+					DeclareBinding::Cast(_, val, _) => Box::new(val.iter_mut_val().into_iter()),
 					DeclareBinding::Condition(cond) => cond.iter_mut_vals(),
 				};
 				for reg in iter::once(left).chain(right_regs) {
@@ -135,7 +152,14 @@ impl MIRInstrKind {
 			| Self::Insert { left, right, .. }
 			| Self::And { left, right }
 			| Self::Or { left, right }
-			| Self::Xor { left, right } => {
+			| Self::Xor { left, right }
+			| Self::BitAnd { left, right }
+			| Self::BitOr { left, right }
+			| Self::BitXor { left, right }
+			| Self::Shl { left, right }
+			| Self::Shr { left, right }
+			| Self::UUIDToString { left, right }
+			| Self::StringToUUID { left, right } => {
 				for reg in iter::once(left).chain(right.iter_mut_val()) {
 					f(reg);
 				}
@@ -146,6 +170,7 @@ impl MIRInstrKind {
 			}
 			Self::Abs { val }
 			| Self::Pow { base: val, .. }
+			| Self::Rand { left: val, .. }
 			| Self::Get { value: val, .. }
 			| Self::Use { val }
 			| Self::Not { value: val }
@@ -156,6 +181,10 @@ impl MIRInstrKind {
 			| Self::Remove { val }
 			| Self::Return {
 				value: Value::Mutable(val),
+			}
+			| Self::CallWith {
+				args: Value::Mutable(val),
+				..
 			} => {
 				f(val);
 			}
@@ -187,10 +216,13 @@ impl MIRInstrKind {
 				body.replace_mut_vals(f);
 			}
 			Self::NoOp
+			| Self::ReturnFail
 			| Self::Declare { .. }
 			| Self::GetConst { .. }
 			| Self::Command { .. }
+			| Self::MacroCommand { .. }
 			| Self::Comment { .. }
+			| Self::CopyData { .. }
 			| Self::CallExtern { .. }
 			| Self::Return {
 				value: Value::Constant(..),
@@ -199,6 +231,10 @@ impl MIRInstrKind {
 				value: Value::Constant(..),
 				..
 			}
+			| Self::CallWith {
+				args: Value::Constant(..),
+				..
+			}
 			| Self::MC(..) => {}
 		}
 	}
@@ -236,8 +272,16 @@ impl MIRInstrKind {
 			| Self::Remove { val: left, .. }
 			| Self::And { left, .. }
 			| Self::Or { left, .. }
+			| Self::BitAnd { left, .. }
+			| Self::BitOr { left, .. }
+			| Self::BitXor { left, .. }
+			| Self::Shl { left, .. }
+			| Self::Shr { left, .. }
+			| Self::UUIDToString { left, .. }
+			| Self::StringToUUID { left, .. }
 			| Self::Not { value: left, .. }
-			| Self::Abs { val: left, .. } => Some(left),
+			| Self::Abs { val: left, .. }
+			| Self::Rand { left, .. } => Some(left),
 			_ => None,
 		}
 	}
@@ -275,7 +319,14 @@ impl GetUsedRegs for MIRInstrKind {
 			| Self::Insert { left, right, .. }
 			| Self::And { left, right }
 			| Self::Or { left, right }
-			| Self::Xor { left, right } => {
+			| Self::Xor { left, right }
+			| Self::BitAnd { left, right }
+			| Self::BitOr { left, right }
+			| Self::BitXor { left, right }
+			| Self::Shl { left, right }
+			| Self::Shr { left, right }
+			| Self::UUIDToString { left, right }
+			| Self::StringToUUID { left, right } => {
 				left.append_used_regs(regs);
 				right.append_used_regs(regs);
 			}
@@ -286,13 +337,14 @@ impl GetUsedRegs for MIRInstrKind {
 			Self::Abs { val } => val.append_used_regs(regs),
 			Self::Not { value } => value.append_used_regs(regs),
 			Self::Pow { base, .. } => base.append_used_regs(regs),
+			Self::Rand { left, .. } => left.append_used_regs(regs),
 			Self::Get { value, .. } => value.append_used_regs(regs),
 			Self::Use { val } => val.append_used_regs(regs),
 			Self::Call { call } => call.append_used_regs(regs),
 			Self::Remove { val } => val.append_used_regs(regs),
-			Self::ReturnValue { value, .. } | Self::Return { value } => {
-				value.append_used_regs(regs)
-			}
+			Self::ReturnValue { value, .. }
+			| Self::Return { value }
+			| Self::CallWith { args: value, .. } => value.append_used_regs(regs),
 			Self::If { condition, body } => {
 				condition.append_used_regs(regs);
 				body.append_used_regs(regs);
@@ -313,11 +365,14 @@ impl GetUsedRegs for MIRInstrKind {
 			Self::ReturnRun { body } => body.append_used_regs(regs),
 			Self::Declare { .. }
 			| Self::NoOp
+			| Self::ReturnFail
 			| Self::GetConst { .. }
 			| Self::Command { .. }
+			| Self::MacroCommand { .. }
 			| Self::Comment { .. }
-			| Self::CallExtern { .. }
-			| Self::MC(..) => {}
+			| Self::CopyData { .. }
+			| Self::CallExtern { .. } => {}
+			Self::MC(instr) => instr.append_used_regs(regs),
 		}
 	}
 }
@@ -1,17 +1,25 @@
+pub mod cfg;
 pub mod instr_impls;
 
 use std::fmt::Debug;
 use std::hash::BuildHasherDefault;
 
+use derivative::Derivative;
 use rustc_hash::FxHashMap;
 
 use crate::common::block::Block;
 use crate::common::condition::Condition;
+use crate::common::diag::Diagnostic;
 use crate::common::function::{CallInterface, FunctionInterface};
+use crate::common::lint::Lint;
 use crate::common::mc::instr::MinecraftInstr;
+use crate::common::mc::scoreboard_and_teams::Criterion;
 use crate::common::mc::modifier::MIRModifier;
+use crate::common::mc::{DataLocation, DataPath};
 use crate::common::reg::GetUsedRegs;
-use crate::common::ty::{DataType, Double};
+use crate::common::span::Span;
+use crate::common::storage::StorageDeclarations;
+use crate::common::ty::{DataType, Double, NBTTypeContents};
 use crate::common::val::ArgRetIndex;
 use crate::common::{val::MutableValue, val::Value, DeclareBinding, Identifier, ResourceLocation};
 use crate::common::{FunctionTrait, IRType};
@@ -19,12 +27,24 @@ use crate::common::{FunctionTrait, IRType};
 #[derive(Debug, Clone)]
 pub struct MIR {
 	pub functions: FxHashMap<ResourceLocation, MIRFunction>,
+	/// Scoreboard objectives with criteria that the pack needs
+	pub objectives: FxHashMap<String, Criterion>,
+	/// Constants that are put in register storage when the pack loads
+	pub storage_consts: FxHashMap<String, NBTTypeContents>,
+	/// Storages that the pack declares
+	pub storages: StorageDeclarations,
+	/// Warnings that have been found while compiling, carried over from the IR
+	pub diagnostics: Vec<Diagnostic>,
 }
 
 impl MIR {
 	pub fn new() -> Self {
 		Self {
 			functions: FxHashMap::default(),
+			objectives: FxHashMap::default(),
+			storage_consts: FxHashMap::default(),
+			storages: StorageDeclarations::default(),
+			diagnostics: Vec::new(),
 		}
 	}
 
@@ -34,6 +54,10 @@ impl MIR {
 				function_capacity,
 				BuildHasherDefault::default(),
 			),
+			objectives: FxHashMap::default(),
+			storage_consts: FxHashMap::default(),
+			storages: StorageDeclarations::default(),
+			diagnostics: Vec::new(),
 		}
 	}
 }
@@ -134,14 +158,24 @@ impl Debug for MIRBlock {
 	}
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Derivative)]
+#[derivative(PartialEq)]
 pub struct MIRInstruction {
 	pub kind: MIRInstrKind,
+	/// Lints that are suppressed for this instruction
+	pub allowed_lints: Vec<Lint>,
+	/// Where the instruction came from in the source
+	#[derivative(PartialEq = "ignore")]
+	pub span: Option<Span>,
 }
 
 impl MIRInstruction {
 	pub fn new(kind: MIRInstrKind) -> Self {
-		Self { kind }
+		Self {
+			kind,
+			allowed_lints: Vec::new(),
+			span: None,
+		}
 	}
 }
 
@@ -195,6 +229,31 @@ pub enum MIRInstrKind {
 		left: MutableValue,
 		right: Value,
 	},
+	/// Bitwise and of two scores
+	BitAnd {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Bitwise or of two scores
+	BitOr {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Bitwise xor of two scores
+	BitXor {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Shifts the bits of a score left. Only the low five bits of the shift amount are used
+	Shl {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Shifts the bits of a score right, keeping the sign
+	Shr {
+		left: MutableValue,
+		right: Value,
+	},
 	Swap {
 		left: MutableValue,
 		right: MutableValue,
@@ -202,6 +261,12 @@ pub enum MIRInstrKind {
 	Remove {
 		val: MutableValue,
 	},
+	/// Copies the values at paths in one data location to the same paths in another
+	CopyData {
+		from: DataLocation,
+		to: DataLocation,
+		paths: Vec<DataPath>,
+	},
 	Abs {
 		val: MutableValue,
 	},
@@ -209,6 +274,24 @@ pub enum MIRInstrKind {
 		base: MutableValue,
 		exp: u8,
 	},
+	/// Sets a value to a random number between min and max, inclusive
+	Rand {
+		left: MutableValue,
+		min: i32,
+		max: i32,
+	},
+	/// Sets a string to the hyphenated form of a UUID int array. Replaced with a call
+	/// to a generated helper function before lowering
+	UUIDToString {
+		left: MutableValue,
+		right: Value,
+	},
+	/// Sets a UUID int array to the UUID in a hyphenated string. Replaced with a call
+	/// to a generated helper function before lowering
+	StringToUUID {
+		left: MutableValue,
+		right: Value,
+	},
 	Get {
 		value: MutableValue,
 		scale: Double,
@@ -258,6 +341,11 @@ pub enum MIRInstrKind {
 	CallExtern {
 		func: ResourceLocation,
 	},
+	/// Calls a function with a compound as its macro arguments
+	CallWith {
+		func: ResourceLocation,
+		args: Value,
+	},
 	If {
 		condition: Condition,
 		body: Box<MIRBlock>,
@@ -279,10 +367,17 @@ pub enum MIRInstrKind {
 	ReturnRun {
 		body: Box<MIRBlock>,
 	},
+	ReturnFail,
 	NoOp,
 	Command {
 		command: String,
 	},
+	/// A command that is run as a macro line, so it can use the macro arguments
+	/// of the function. Bodies that are lowered to their own functions don't receive
+	/// the arguments, so this only works at the top level or in a single-instruction body
+	MacroCommand {
+		command: String,
+	},
 	Comment {
 		comment: String,
 	},
@@ -304,9 +399,17 @@ impl Debug for MIRInstrKind {
 			Self::Mod { left, right } => format!("mod {left:?}, {right:?}"),
 			Self::Min { left, right } => format!("min {left:?}, {right:?}"),
 			Self::Max { left, right } => format!("max {left:?}, {right:?}"),
+			Self::BitAnd { left, right } => format!("band {left:?}, {right:?}"),
+			Self::BitOr { left, right } => format!("bor {left:?}, {right:?}"),
+			Self::BitXor { left, right } => format!("bxor {left:?}, {right:?}"),
+			Self::Shl { left, right } => format!("shl {left:?}, {right:?}"),
+			Self::Shr { left, right } => format!("shr {left:?}, {right:?}"),
 			Self::Swap { left, right } => format!("swp {left:?}, {right:?}"),
 			Self::Abs { val } => format!("abs {val:?}"),
 			Self::Pow { base, exp } => format!("pow {base:?}, {exp}"),
+			Self::Rand { left, min, max } => format!("rand {left:?}, {min}, {max}"),
+			Self::UUIDToString { left, right } => format!("uuidstr {left:?}, {right:?}"),
+			Self::StringToUUID { left, right } => format!("struuid {left:?}, {right:?}"),
 			Self::Get { value, scale } => format!("get {value:?} {scale}"),
 			Self::GetConst { value } => format!("getc {value:?}"),
 			Self::Merge { left, right } => format!("merge {left:?}, {right:?}"),
@@ -320,6 +423,7 @@ impl Debug for MIRInstrKind {
 			Self::Use { val } => format!("use {val:?}"),
 			Self::Call { call } => format!("call {call:?}"),
 			Self::CallExtern { func } => format!("callx {func}"),
+			Self::CallWith { func, args } => format!("callw {func}, {args:?}"),
 			Self::If { condition, body } => format!("if {condition:?} then {body:?}"),
 			Self::IfElse {
 				condition,
@@ -327,11 +431,14 @@ impl Debug for MIRInstrKind {
 				second,
 			} => format!("if {condition:?}: {first:?} else {second:?}"),
 			Self::Remove { val } => format!("rm {val:?}"),
+			Self::CopyData { from, to, paths } => format!("copy {to:?}, {from:?}, {paths:?}"),
 			Self::ReturnValue { index, value } => format!("retv {index} {value:?}"),
 			Self::Return { value } => format!("ret {value:?}"),
 			Self::ReturnRun { body } => format!("retr {body:?}"),
+			Self::ReturnFail => "retf".into(),
 			Self::NoOp => "noop".into(),
 			Self::Command { command } => format!("cmd {command}"),
+			Self::MacroCommand { command } => format!("mcmd {command}"),
 			Self::Comment { comment } => format!("cmt {comment}"),
 			Self::Modify { modifier, body } => format!("mdf {modifier:?}: {body:?}"),
 			Self::MC(instr) => format!("{instr:?}"),
@@ -2,6 +2,7 @@ use crate::common::mc::entity::{SelectorParameter, TargetSelector};
 use crate::common::mc::EntityTarget;
 
 use super::super::t::macros::cgwrite;
+use super::super::util::cg_float;
 use super::{Codegen, CodegenBlockCx};
 
 impl Codegen for EntityTarget {
@@ -53,12 +54,44 @@ impl Codegen for TargetSelector {
 					}
 					SelectorParameter::Name { name, invert } => {
 						let invert = gen_invert_char(*invert);
-						write!(f, "gamemode={invert}\"{name}\"")?;
+						write!(f, "name={invert}\"{name}\"")?;
 					}
 					SelectorParameter::NBT { nbt, invert } => {
 						let invert = gen_invert_char(*invert);
 						cgwrite!(f, cbcx, "nbt=", invert, nbt.get_literal_str())?;
 					}
+					SelectorParameter::Scores { scores } => {
+						write!(f, "scores={{")?;
+						for (i, (objective, range)) in scores.iter().enumerate() {
+							cgwrite!(f, cbcx, objective, "=", range)?;
+							if i != scores.len() - 1 {
+								write!(f, ",")?;
+							}
+						}
+						write!(f, "}}")?;
+					}
+					SelectorParameter::Advancements { advancements } => {
+						write!(f, "advancements={{")?;
+						for (i, (advancement, value)) in advancements.iter().enumerate() {
+							write!(f, "{advancement}={value}")?;
+							if i != advancements.len() - 1 {
+								write!(f, ",")?;
+							}
+						}
+						write!(f, "}}")?;
+					}
+					SelectorParameter::Level { range } => {
+						cgwrite!(f, cbcx, "level=", range)?;
+					}
+					SelectorParameter::Origin { value, .. }
+					| SelectorParameter::Volume { value, .. } => {
+						write!(f, "{}=", param.get_key())?;
+						cg_float(f, *value, false, true, true);
+					}
+					SelectorParameter::Team { team, invert } => {
+						let invert = gen_invert_char(*invert);
+						write!(f, "team={invert}{team}")?;
+					}
 					SelectorParameter::Limit(limit) => {
 						write!(f, "limit={limit}")?;
 					}
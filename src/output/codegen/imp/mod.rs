@@ -2,25 +2,25 @@ pub mod entity_target;
 
 use std::sync::Arc;
 
+use serde_json::{Map, Value as JsonValue};
+
 use crate::common::mc::block::{
 	BlockData, BlockFilter, BlockProperties, BlockStateValue, BlockStates, CloneMaskMode,
-	CloneMode, FillMode, SetBlockMode,
 };
-use crate::common::mc::entity::{AttributeType, EffectDuration, SelectorSort, SelectorType, UUID};
+use crate::common::mc::bossbar::BossbarOption;
+use crate::common::mc::entity::EffectDuration;
 use crate::common::mc::item::ItemData;
-use crate::common::mc::modifier::{AlignAxes, AnchorLocation, StoreDataType};
 use crate::common::mc::pos::{
 	AbsOrRelCoord, DoubleCoordinates, DoubleCoordinates2D, IntCoordinates, IntCoordinates2D,
 };
-use crate::common::mc::scoreboard_and_teams::SingleCriterion;
-use crate::common::mc::{
-	DataLocation, DataPath, DatapackListMode, DatapackOrder, DatapackPriority, Difficulty,
-	FullDataLocation, Gamemode, Heightmap, SoundSource, Weather, XPValue,
-};
+use crate::common::mc::scoreboard_and_teams::{NumberFormat, TeamOption};
+use crate::common::mc::text::{TextComponent, TextComponentContents, TextStyle};
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation};
 use crate::common::val::{MutableNBTValue, MutableScoreValue, NBTValue, ScoreValue};
 
+use super::t::macros::{cgformat, cgwrite};
 use super::util::{cg_float, get_mut_nbt_val_loc, get_mut_score_val_score, get_score_val_score};
-use super::{t::macros::cgwrite, Codegen, CodegenBlockCx};
+use super::{Codegen, CodegenBlockCx};
 
 macro_rules! cg_impl {
 	($name:ty, $self:ident, $f:ident, $b:tt) => {
@@ -46,6 +46,8 @@ macro_rules! cg_impl {
 	};
 }
 
+// Types defined in this crate should derive Codegen instead. These are only for
+// foreign types and specific instantiations of generic types
 macro_rules! impl_disp {
 	($name:ty) => {
 		cg_impl!(
@@ -78,22 +80,6 @@ macro_rules! impl_dbg {
 	};
 }
 
-macro_rules! impl_cg_str {
-	($name:ty) => {
-		cg_impl!(
-			$name,
-			self,
-			f,
-			cbcx,
-			(|| {
-				let _ = cbcx;
-				write!(f, "{}", self.codegen_str())?;
-				Ok(())
-			})
-		);
-	};
-}
-
 // Common types
 impl_disp!(str);
 impl_disp!(String);
@@ -173,6 +159,9 @@ cg_impl!(
 	f,
 	cbcx,
 	(|| {
+		if let DataLocation::Storage(storage) = &self.loc {
+			cbcx.ccx.use_storage_path(storage, &self.path);
+		}
 		cgwrite!(f, cbcx, self.loc)?;
 		if let DataPath::This = self.path {
 		} else {
@@ -181,25 +170,137 @@ cg_impl!(
 		Ok(())
 	})
 );
-impl_dbg!(DataPath);
-impl_disp!(XPValue);
-impl_disp!(Difficulty);
-impl_disp!(Gamemode);
-impl_dbg!(Heightmap);
-impl_disp!(Weather);
-impl_dbg!(SingleCriterion);
-impl_dbg!(UUID);
-impl_dbg!(AttributeType);
-impl_dbg!(DatapackPriority);
-impl_dbg!(DatapackOrder);
-impl_dbg!(DatapackListMode);
-impl_dbg!(EffectDuration);
-impl_dbg!(StoreDataType);
-impl_dbg!(SoundSource);
-
-// Selectors
-impl_disp!(SelectorSort);
-impl_cg_str!(SelectorType);
+
+cg_impl!(
+	TextComponent,
+	self,
+	f,
+	cbcx,
+	(|| {
+		let json = self.to_json_with(&mut |contents| cg_text_contents(contents, cbcx))?;
+		write!(f, "{json}")?;
+		Ok(())
+	})
+);
+
+/// Gets the JSON keys for the score or NBT that a text component shows
+fn cg_text_contents(
+	contents: &TextComponentContents,
+	cbcx: &mut CodegenBlockCx,
+) -> anyhow::Result<Map<String, JsonValue>> {
+	let mut out = Map::new();
+	match contents {
+		TextComponentContents::Text(text) => {
+			out.insert("text".into(), JsonValue::String(text.clone()));
+		}
+		TextComponentContents::Score(val) => {
+			let score = get_mut_score_val_score(val, cbcx)?;
+			let mut score_obj = Map::new();
+			let holder = cgformat!(cbcx, score.holder)?;
+			score_obj.insert("name".into(), JsonValue::String(holder));
+			score_obj.insert(
+				"objective".into(),
+				JsonValue::String(score.objective.to_string()),
+			);
+			out.insert("score".into(), JsonValue::Object(score_obj));
+		}
+		TextComponentContents::Nbt(val) => {
+			let loc = get_mut_nbt_val_loc(val, cbcx)?;
+			let (key, source) = match &loc.loc {
+				DataLocation::Block(pos) => ("block", cgformat!(cbcx, pos)?),
+				DataLocation::Entity(target) => ("entity", cgformat!(cbcx, target)?),
+				DataLocation::Storage(storage) => {
+					cbcx.ccx.use_storage_path(storage, &loc.path);
+					("storage", storage.to_string())
+				}
+			};
+			let path = match &loc.path {
+				DataPath::This => "{}".to_string(),
+				path => cgformat!(cbcx, path)?,
+			};
+			out.insert("nbt".into(), JsonValue::String(path));
+			out.insert(key.into(), JsonValue::String(source));
+		}
+	}
+
+	Ok(out)
+}
+
+cg_impl!(
+	TextStyle,
+	self,
+	f,
+	(|| {
+		write!(f, "{}", self.get_json_str()?)?;
+		Ok(())
+	})
+);
+cg_impl!(
+	NumberFormat,
+	self,
+	f,
+	cbcx,
+	(|| {
+		match self {
+			Self::Blank => write!(f, "blank")?,
+			Self::Fixed(component) => cgwrite!(f, cbcx, "fixed ", component)?,
+			Self::Styled(style) => write!(f, "styled {}", style.get_json_str()?)?,
+		}
+		Ok(())
+	})
+);
+cg_impl!(
+	TeamOption,
+	self,
+	f,
+	cbcx,
+	(|| {
+		match self {
+			Self::DisplayName(name) => cgwrite!(f, cbcx, "displayName ", name)?,
+			Self::Color(color) => write!(f, "color {color}")?,
+			Self::FriendlyFire(value) => write!(f, "friendlyFire {value}")?,
+			Self::SeeFriendlyInvisibles(value) => write!(f, "seeFriendlyInvisibles {value}")?,
+			Self::NametagVisibility(value) => cgwrite!(f, cbcx, "nametagVisibility ", value)?,
+			Self::DeathMessageVisibility(value) => {
+				cgwrite!(f, cbcx, "deathMessageVisibility ", value)?
+			}
+			Self::CollisionRule(value) => cgwrite!(f, cbcx, "collisionRule ", value)?,
+			Self::Prefix(prefix) => cgwrite!(f, cbcx, "prefix ", prefix)?,
+			Self::Suffix(suffix) => cgwrite!(f, cbcx, "suffix ", suffix)?,
+		}
+		Ok(())
+	})
+);
+
+cg_impl!(
+	BossbarOption,
+	self,
+	f,
+	cbcx,
+	(|| {
+		match self {
+			Self::Value(value) => write!(f, "value {value}")?,
+			Self::Max(max) => write!(f, "max {max}")?,
+			Self::Name(name) => cgwrite!(f, cbcx, "name ", name)?,
+			Self::Players(None) => write!(f, "players")?,
+			Self::Players(Some(players)) => cgwrite!(f, cbcx, "players ", players)?,
+		}
+		Ok(())
+	})
+);
+
+cg_impl!(
+	EffectDuration,
+	self,
+	f,
+	cbcx,
+	(|| {
+		self.validate()?;
+		let duration = self.for_version(cbcx.ccx.project.target_version);
+		write!(f, "{duration:?}")?;
+		Ok(())
+	})
+);
 
 // Blocks
 cg_impl!(
@@ -241,8 +342,6 @@ cg_impl!(
 		Ok(())
 	})
 );
-impl_dbg!(SetBlockMode);
-impl_dbg!(FillMode);
 cg_impl!(
 	CloneMaskMode,
 	self,
@@ -257,7 +356,6 @@ cg_impl!(
 		Ok(())
 	})
 );
-impl_dbg!(CloneMode);
 cg_impl!(
 	BlockStates,
 	self,
@@ -358,10 +456,6 @@ cg_impl!(
 		self.0.gen_writer(f, cbcx)?;
 		write!(f, " ")?;
 		self.1.gen_writer(f, cbcx)?;
-		write!(f, " ")?;
 		Ok(())
 	})
 );
-
-impl_dbg!(AnchorLocation);
-impl_dbg!(AlignAxes);
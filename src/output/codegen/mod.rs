@@ -1,33 +1,47 @@
 mod imp;
 mod modifier;
+pub mod spec;
 pub mod t;
 pub mod util;
 
 use std::collections::HashSet;
 
 use anyhow::{bail, Context};
+use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::common::diag::WithSpan;
 use crate::common::function::FunctionSignature;
 use crate::common::mc::block::{CloneMaskMode, CloneMode, FillMode, SetBlockMode};
+use crate::common::mc::feature::{get_required_feature, is_feature_enabled};
 use crate::common::mc::instr::MinecraftInstr;
 use crate::common::mc::modifier::Modifier;
+use crate::common::mc::particle::ParticleMode;
+use crate::common::mc::region::{offset_coords, Region, MAX_REGION_VOLUME};
 use crate::common::mc::scoreboard_and_teams::Criterion;
-use crate::common::mc::{DatapackListMode, Score};
+use crate::common::mc::time::ScheduleMode;
+use crate::common::mc::version::MinecraftVersion;
+use crate::common::mc::{DataPath, DatapackListMode, EntityTarget, Location, Score};
+use crate::common::storage::{get_reg_storage, get_root_key, StorageDeclarations, StorageLifetime};
 use crate::common::val::MutableScoreValue;
-use crate::common::{val::NBTValue, val::ScoreValue, RegisterList};
+use crate::common::ty::NBTTypeContents;
+use crate::common::{val::NBTValue, val::ScoreValue, Identifier, RegisterList, ResourceLocation};
 use crate::lir::{LIRBlock, LIRFunction, LIRInstrKind, LIRInstruction};
 use crate::output::codegen::util::cg_data_modify_rhs;
-use crate::project::ProjectSettings;
+use crate::output::text::{REG_OBJECTIVE, REG_STORAGE_LOCATION};
+use crate::project::{CommandPrefix, ProjectSettings};
 
 use self::modifier::codegen_modifier;
 use self::t::macros::cgwrite;
-use self::util::{create_lit_score, get_mut_score_val_score, FloatCG, SpaceSepListCG};
+use self::util::{get_mut_score_val_score, FloatCG, SpaceSepListCG};
 
+use super::intern::Interner;
 use super::ra::{GlobalRegAllocResult, RegAllocCx, RegAllocResult};
 use super::strip::FunctionMapping;
 
 use t::macros::cgformat;
 pub use t::Codegen;
+/// Derives Codegen for simple types using their Debug or Display impls
+pub use dpc_derive::Codegen;
 
 pub struct CodegenCx<'proj> {
 	pub project: &'proj ProjectSettings,
@@ -36,6 +50,21 @@ pub struct CodegenCx<'proj> {
 	pub ra: GlobalRegAllocResult,
 	pub score_literals: HashSet<i32>,
 	pub requirements: HashSet<CodegenRequirement>,
+	/// Objectives with criteria that need to be created in the init function
+	pub objectives: FxHashMap<String, Criterion>,
+	/// Objectives that scores in the pack are in
+	pub used_objectives: FxHashSet<Identifier>,
+	/// Objectives that the pack creates itself with commands
+	pub added_objectives: FxHashSet<Identifier>,
+	/// Constants that need to be put in register storage in the init function
+	pub storage_consts: FxHashMap<String, NBTTypeContents>,
+	/// Storages that the pack declares
+	pub storages: StorageDeclarations,
+	/// The top-level keys of transient storages that the pack uses, which
+	/// are cleared in the init function
+	pub transient_keys: FxHashMap<ResourceLocation, FxHashSet<String>>,
+	/// Shared copies of the holder and objective names in the output
+	pub interner: Interner,
 }
 
 impl<'proj> CodegenCx<'proj> {
@@ -43,6 +72,7 @@ impl<'proj> CodegenCx<'proj> {
 		project: &'proj ProjectSettings,
 		func_mapping: Option<FunctionMapping>,
 		ra: GlobalRegAllocResult,
+		interner: Interner,
 	) -> Self {
 		Self {
 			project,
@@ -51,12 +81,71 @@ impl<'proj> CodegenCx<'proj> {
 			ra,
 			score_literals: HashSet::new(),
 			requirements: HashSet::new(),
+			objectives: FxHashMap::default(),
+			used_objectives: FxHashSet::default(),
+			added_objectives: FxHashSet::default(),
+			storage_consts: FxHashMap::default(),
+			storages: StorageDeclarations::default(),
+			transient_keys: FxHashMap::default(),
+			interner,
 		}
 	}
 
+	/// Gets the storage that registers are put in
+	pub fn get_reg_storage(&self) -> ResourceLocation {
+		get_reg_storage(&self.storages)
+			.cloned()
+			.unwrap_or_else(|| REG_STORAGE_LOCATION.into())
+	}
+
+	/// Records a use of a path in a storage so that it can be cleared
+	/// if the storage is transient
+	pub fn use_storage_path(&mut self, storage: &ResourceLocation, path: &DataPath) {
+		if self.storages.get(storage) != Some(&StorageLifetime::Transient) {
+			return;
+		}
+		if let Some(key) = get_root_key(path) {
+			self.transient_keys
+				.entry(storage.clone())
+				.or_default()
+				.insert(key.to_string());
+		}
+	}
+
+	/// Gets the score for a constant in the literal objective
+	pub fn get_lit_score(&mut self, num: i32) -> Score {
+		let holder = self
+			.interner
+			.lit_fake_player(num, self.project.hidden_fake_players);
+		Score::new(
+			EntityTarget::Player(holder),
+			self.interner.intern(self.project.get_literal_objective()),
+		)
+	}
+
+	/// Gets the score for a register fake player in the register objective
+	pub fn get_reg_score(&mut self, holder: Identifier) -> Score {
+		self.add_requirement(CodegenRequirement::UseRegObjective);
+		Score::new(
+			EntityTarget::Player(holder),
+			self.interner.intern(REG_OBJECTIVE),
+		)
+	}
+
 	pub fn add_requirement(&mut self, req: CodegenRequirement) {
 		self.requirements.insert(req);
 	}
+
+	/// Errors if the project targets a version of the game older than the one
+	/// that added something
+	pub fn require_version(&self, version: MinecraftVersion, what: &str) -> anyhow::Result<()> {
+		let target = self.project.target_version;
+		if target < version {
+			bail!("{what} requires Minecraft {version} or later, but the project targets {target}");
+		}
+
+		Ok(())
+	}
 }
 
 /// Different requirements that can be imposed on the output so that it generates
@@ -105,16 +194,97 @@ pub fn codegen_block(
 
 	let mut out = Vec::new();
 	for (i, instr) in block.contents.iter().enumerate() {
-		let mut command =
-			codegen_instr(instr, &mut cbcx).with_context(|| format!("At instruction {i}"))?;
-		command = command.map(|x| if cbcx.macro_line { format!("${x}") } else { x });
-		cbcx.macro_line = false;
-		out.extend(command);
+		let chunks = chunk_large_region(instr, cbcx.ccx.project)
+			.with_span(instr.span)
+			.with_context(|| format!("At instruction {i}"))?;
+		for instr in chunks.as_deref().unwrap_or(std::slice::from_ref(instr)) {
+			let mut command = codegen_instr(instr, &mut cbcx)
+				.with_span(instr.span)
+				.with_context(|| format!("At instruction {i}"))?;
+			command = command.map(|x| if cbcx.macro_line { format!("${x}") } else { x });
+			cbcx.macro_line = false;
+			out.extend(command);
+		}
 	}
 
 	Ok(out)
 }
 
+/// Checks that a fill or clone with a constant region is within the size limit,
+/// splitting it into multiple commands over smaller regions if the project allows it
+fn chunk_large_region(
+	instr: &LIRInstruction,
+	project: &ProjectSettings,
+) -> anyhow::Result<Option<Vec<LIRInstruction>>> {
+	let LIRInstrKind::MC(mc_instr) = &instr.kind else {
+		return Ok(None);
+	};
+	let (start, end) = match mc_instr {
+		MinecraftInstr::Fill { data } => (&data.start, &data.end),
+		MinecraftInstr::Clone { data } => (&data.start, &data.end),
+		_ => return Ok(None),
+	};
+	let Some(region) = Region::from_corners(start, end) else {
+		return Ok(None);
+	};
+	let volume = region.volume();
+	if volume <= MAX_REGION_VOLUME {
+		return Ok(None);
+	}
+	if !project.chunk_large_regions {
+		bail!("Region of {volume} blocks is larger than the limit of {MAX_REGION_VOLUME}");
+	}
+
+	let mut out = Vec::new();
+	for chunk in region.split(MAX_REGION_VOLUME) {
+		let kind = match mc_instr {
+			MinecraftInstr::Fill { data } => {
+				// Hollow and outline fills would put walls between the chunks
+				if matches!(data.mode, FillMode::Hollow | FillMode::Outline) {
+					bail!(
+						"Large fill with mode {:?} cannot be split into chunks",
+						data.mode
+					);
+				}
+				let mut data = data.clone();
+				data.start = chunk.start();
+				data.end = chunk.end();
+				MinecraftInstr::Fill { data }
+			}
+			MinecraftInstr::Clone { data } => {
+				let mut data = data.clone();
+				data.start = chunk.start();
+				data.end = chunk.end();
+				data.destination = offset_coords(&data.destination, chunk.offset_from(&region))
+					.context("Large clone with a local destination cannot be split into chunks")?;
+				MinecraftInstr::Clone { data }
+			}
+			_ => unreachable!(),
+		};
+		out.push(LIRInstruction {
+			kind: LIRInstrKind::MC(kind),
+			modifiers: instr.modifiers.clone(),
+			span: instr.span,
+		});
+	}
+
+	Ok(Some(out))
+}
+
+/// Checks that the feature flags an instruction needs on the target version are enabled
+fn check_feature_flags(instr: &MinecraftInstr, project: &ProjectSettings) -> anyhow::Result<()> {
+	if let Some((feature, id)) = get_required_feature(instr, project.target_version) {
+		if !is_feature_enabled(&project.features, feature) {
+			bail!(
+				"{id} requires the {feature} feature flag on Minecraft {}, but the project does not enable it",
+				project.target_version
+			);
+		}
+	}
+
+	Ok(())
+}
+
 pub fn codegen_instr(
 	instr: &LIRInstruction,
 	cbcx: &mut CodegenBlockCx,
@@ -123,6 +293,15 @@ pub fn codegen_instr(
 
 	let modifiers = Vec::new();
 
+	if let LIRInstrKind::MC(instr) = &instr.kind {
+		let meta = instr.get_meta();
+		if let Some(since) = meta.since {
+			let what = format!("The {} command", meta.command);
+			cbcx.ccx.require_version(since, &what)?;
+		}
+		check_feature_flags(instr, cbcx.ccx.project)?;
+	}
+
 	let cmd = match &instr.kind {
 		LIRInstrKind::SetScore(left, right) => Some(match right {
 			ScoreValue::Constant(data) => {
@@ -137,8 +316,11 @@ pub fn codegen_instr(
 			Some(match right {
 				ScoreValue::Constant(data) => {
 					let lit = data.get_i32();
-					// Negative signs in add/remove commands are illegal
-					if lit.is_negative() {
+					// Negative signs in add/remove commands are illegal, and the lowest
+					// score is out of their range entirely so it has to use a literal
+					if lit == i32::MIN {
+						cgformat!(cbcx, "scoreboard players operation ", left, " += ", right)?
+					} else if lit.is_negative() {
 						cgformat!(cbcx, "scoreboard players remove ", left, " ", lit.abs())?
 					} else {
 						cgformat!(cbcx, "scoreboard players add ", left, " ", lit)?
@@ -153,8 +335,11 @@ pub fn codegen_instr(
 			Some(match right {
 				ScoreValue::Constant(data) => {
 					let lit = data.get_i32();
-					// Negative signs in add/remove commands are illegal
-					if lit.is_negative() {
+					// Negative signs in add/remove commands are illegal, and the lowest
+					// score is out of their range entirely so it has to use a literal
+					if lit == i32::MIN {
+						cgformat!(cbcx, "scoreboard players operation ", left, " -= ", right)?
+					} else if lit.is_negative() {
 						cgformat!(cbcx, "scoreboard players add ", left, " ", lit.abs())?
 					} else {
 						cgformat!(cbcx, "scoreboard players remove ", left, " ", lit)?
@@ -259,7 +444,7 @@ pub fn codegen_instr(
 			Some(out)
 		}
 		LIRInstrKind::GetConst(val) => {
-			let lit = create_lit_score(*val);
+			let lit = cbcx.ccx.get_lit_score(*val);
 			Some(cgformat!(cbcx, "scoreboard players get ", lit)?)
 		}
 		LIRInstrKind::PushData(left, right) => {
@@ -291,29 +476,51 @@ pub fn codegen_instr(
 			}
 			Some(format!("function {func_id}"))
 		}
-		LIRInstrKind::ReturnValue(val) => Some(cgformat!(cbcx, "return ", val)?),
-		LIRInstrKind::ReturnFail => Some("return fail".into()),
+		LIRInstrKind::CallWith(fun, args) => {
+			cbcx.ccx.require_version(
+				MinecraftVersion::new(20, 2),
+				"Calling a function with macro arguments",
+			)?;
+			let func_id = cbcx
+				.ccx
+				.func_mapping
+				.as_ref()
+				.and_then(|mapping| mapping.0.get(fun))
+				.unwrap_or(fun)
+				.clone();
+			match args {
+				NBTValue::Constant(NBTTypeContents::Compound(_, args)) => {
+					Some(format!("function {func_id} {}", args.get_literal_str()))
+				}
+				NBTValue::Constant(..) => bail!("Macro arguments must be a compound"),
+				NBTValue::Mutable(args) => {
+					Some(cgformat!(cbcx, "function ", func_id, " with ", args)?)
+				}
+			}
+		}
+		LIRInstrKind::ReturnValue(val) => {
+			cbcx.ccx
+				.require_version(MinecraftVersion::new(20, 0), "Returning a value")?;
+			Some(cgformat!(cbcx, "return ", val)?)
+		}
+		LIRInstrKind::ReturnFail => {
+			cbcx.ccx
+				.require_version(MinecraftVersion::new(20, 3), "Returning a failure")?;
+			Some("return fail".into())
+		}
 		LIRInstrKind::ReturnRun(instr) => {
+			cbcx.ccx.require_version(
+				MinecraftVersion::new(20, 2),
+				"Returning the result of a command",
+			)?;
 			let cmd = codegen_instr(instr, cbcx)
 				.context("Failed to codegen return run subinstruction")?
 				.context("Return run command is missing after codegen")?;
 			Some(cgformat!(cbcx, "return run ", cmd)?)
 		}
 		LIRInstrKind::MC(instr) => match instr {
-			MinecraftInstr::Say { message } => Some(format!("say {message}")),
-			MinecraftInstr::Me { message } => Some(format!("me {message}")),
-			MinecraftInstr::TeamMessage { message } => Some(format!("tm {message}")),
-			MinecraftInstr::Tell { target, message } => {
-				Some(cgformat!(cbcx, "w ", target, " ", message)?)
-			}
-			MinecraftInstr::Kill { target } => {
-				if target.is_blank_this() {
-					Some("kill".into())
-				} else {
-					Some(cgformat!(cbcx, "kill ", target)?)
-				}
-			}
-			MinecraftInstr::Reload => Some("reload".into()),
+			// Commands in the spec table are generated from their spec
+			spec::spec_instr_pattern!() => Some(spec::codegen_spec(instr, cbcx)?),
 			MinecraftInstr::SetXP {
 				target,
 				amount,
@@ -323,88 +530,6 @@ pub fn codegen_instr(
 				let amount = if amount < &0 { 0 } else { *amount };
 				Some(cgformat!(cbcx, "xp set ", target, " ", amount, " ", value)?)
 			}
-			MinecraftInstr::AddXP {
-				target,
-				amount,
-				value,
-			} => Some(cgformat!(cbcx, "xp add ", target, " ", amount, " ", value)?),
-			MinecraftInstr::GetXP { target, value } => {
-				Some(cgformat!(cbcx, "xp query ", target, " ", value)?)
-			}
-			MinecraftInstr::BanPlayers { targets, reason } => {
-				let list = SpaceSepListCG(targets);
-				if let Some(reason) = reason {
-					Some(cgformat!(cbcx, "ban ", list, " ", reason)?)
-				} else {
-					Some(cgformat!(cbcx, "ban ", list)?)
-				}
-			}
-			MinecraftInstr::BanIP { target, reason } => {
-				if let Some(reason) = reason {
-					Some(cgformat!(cbcx, "ban-ip ", target, " ", reason)?)
-				} else {
-					Some(cgformat!(cbcx, "ban-ip ", target)?)
-				}
-			}
-			MinecraftInstr::PardonPlayers { targets } => {
-				let list = SpaceSepListCG(targets);
-				Some(cgformat!(cbcx, "pardon ", list)?)
-			}
-			MinecraftInstr::PardonIP { target } => Some(format!("pardon-ip {target}")),
-			MinecraftInstr::Op { targets } => {
-				Some(cgformat!(cbcx, "op ", SpaceSepListCG(targets))?)
-			}
-			MinecraftInstr::Deop { targets } => {
-				Some(cgformat!(cbcx, "deop ", SpaceSepListCG(targets))?)
-			}
-			MinecraftInstr::WhitelistAdd { targets } => {
-				Some(cgformat!(cbcx, "whitelist add ", SpaceSepListCG(targets))?)
-			}
-			MinecraftInstr::WhitelistRemove { targets } => Some(cgformat!(
-				cbcx,
-				"whitelist remove ",
-				SpaceSepListCG(targets)
-			)?),
-			MinecraftInstr::WhitelistOn => Some("whitelist on".into()),
-			MinecraftInstr::WhitelistOff => Some("whitelist off".into()),
-			MinecraftInstr::WhitelistReload => Some("whitelist reload".into()),
-			MinecraftInstr::WhitelistList => Some("whitelist list".into()),
-			MinecraftInstr::Kick { targets, reason } => {
-				let list = SpaceSepListCG(targets);
-				if let Some(reason) = reason {
-					Some(cgformat!(cbcx, "kick ", list, " ", reason)?)
-				} else {
-					Some(cgformat!(cbcx, "kick ", list)?)
-				}
-			}
-			MinecraftInstr::Banlist => Some("banlist".into()),
-			MinecraftInstr::StopSound => Some("stopsound".into()),
-			MinecraftInstr::StopServer => Some("stop".into()),
-			MinecraftInstr::ListPlayers => Some("list".into()),
-			MinecraftInstr::Seed => Some("seed".into()),
-			MinecraftInstr::GetDifficulty => Some("difficulty".into()),
-			MinecraftInstr::SetDifficulty { difficulty } => {
-				Some(cgformat!(cbcx, "difficulty ", difficulty)?)
-			}
-			MinecraftInstr::Enchant {
-				target,
-				enchantment,
-				level,
-			} => {
-				if level == &1 {
-					Some(cgformat!(cbcx, "enchant ", target, " ", enchantment)?)
-				} else {
-					Some(cgformat!(
-						cbcx,
-						"enchant ",
-						target,
-						" ",
-						enchantment,
-						" ",
-						level
-					)?)
-				}
-			}
 			MinecraftInstr::SetBlock { data } => {
 				let mut out = String::new();
 				cgwrite!(&mut out, cbcx, "setblock ", data.pos, " ", data.block)?;
@@ -421,13 +546,12 @@ pub fn codegen_instr(
 				cgwrite!(&mut out, cbcx, "fill ", data.start, " ", data.end, " ", data.block)?;
 
 				// Replace mode is default and can be omitted if there is no filter
-				if let FillMode::Replace(filter) = &data.mode {
-					cgwrite!(&mut out, cbcx, "replace")?;
-					if let Some(filter) = filter {
-						cgwrite!(&mut out, cbcx, " ", filter)?;
+				match &data.mode {
+					FillMode::Replace(None) => {}
+					FillMode::Replace(Some(filter)) => {
+						cgwrite!(&mut out, cbcx, " replace ", filter)?;
 					}
-				} else {
-					cgwrite!(&mut out, cbcx, " ", data.mode)?;
+					other => cgwrite!(&mut out, cbcx, " ", other)?,
 				}
 				Some(out)
 			}
@@ -436,29 +560,24 @@ pub fn codegen_instr(
 				cgwrite!(&mut out, cbcx, "clone ")?;
 
 				if let Some(source) = &data.source_dimension {
-					cgwrite!(&mut out, cbcx, "from ", source)?;
+					cgwrite!(&mut out, cbcx, "from ", source, " ")?;
 				}
 
 				cgwrite!(&mut out, cbcx, data.start, " ", data.end)?;
 
 				if let Some(target) = &data.target_dimension {
-					cgwrite!(&mut out, cbcx, "to ", target)?;
+					cgwrite!(&mut out, cbcx, " to ", target)?;
 				}
 
-				cgwrite!(&mut out, cbcx, " ", data.destination, " ")?;
+				cgwrite!(&mut out, cbcx, " ", data.destination)?;
 
-				// Replace mode is default and can be omitted if there is no filter
-				match &data.mask_mode {
-					CloneMaskMode::Filtered(filter) => {
-						cgwrite!(&mut out, cbcx, "filter ", filter)?;
-					}
-					CloneMaskMode::Replace => {}
-					other => cgwrite!(&mut out, cbcx, " ", other)?,
+				// Replace mask and normal mode are default and can be omitted,
+				// but the mask has to be given for the mode to be
+				let write_mode = data.mode != CloneMode::Normal;
+				if write_mode || data.mask_mode != CloneMaskMode::Replace {
+					cgwrite!(&mut out, cbcx, " ", data.mask_mode)?;
 				}
-
-				// Normal mode is default and can be omitted
-				if let CloneMode::Normal = data.mode {
-				} else {
+				if write_mode {
 					cgwrite!(&mut out, cbcx, " ", data.mode)?;
 				}
 
@@ -483,52 +602,14 @@ pub fn codegen_instr(
 
 				Some(out)
 			}
-			MinecraftInstr::SetWeather { weather, duration } => {
-				if let Some(duration) = duration {
-					Some(cgformat!(cbcx, "weather ", weather, " ", duration)?)
-				} else {
-					Some(cgformat!(cbcx, "weather ", weather)?)
-				}
-			}
-			MinecraftInstr::AddTime { time } => Some(cgformat!(cbcx, "time add ", time)?),
 			MinecraftInstr::SetTime { time } => {
 				// Using the day preset is shorter than the time it represents
-				if time.amount == 1000.0 {
-					Some("tmie set day".into())
+				if time.to_ticks() == 1000.0 {
+					Some("time set day".into())
 				} else {
 					Some(cgformat!(cbcx, "time set ", time)?)
 				}
 			}
-			MinecraftInstr::SetTimePreset { time } => Some(cgformat!(cbcx, "time set ", time)?),
-			MinecraftInstr::GetTime { query } => Some(cgformat!(cbcx, "time get ", query)?),
-			MinecraftInstr::AddTag { target, tag } => {
-				Some(cgformat!(cbcx, "tag ", target, " add ", tag)?)
-			}
-			MinecraftInstr::RemoveTag { target, tag } => {
-				Some(cgformat!(cbcx, "tag ", target, " remove ", tag)?)
-			}
-			MinecraftInstr::ListTags { target } => Some(cgformat!(cbcx, "tag ", target, " list")?),
-			MinecraftInstr::RideMount { target, vehicle } => {
-				Some(cgformat!(cbcx, "ride ", target, " mount ", vehicle)?)
-			}
-			MinecraftInstr::RideDismount { target } => {
-				Some(cgformat!(cbcx, "ride ", target, " dismount")?)
-			}
-			MinecraftInstr::Spectate { target, spectator } => {
-				let mut out = String::new();
-				cgwrite!(&mut out, cbcx, "spectate ", target)?;
-				if !spectator.is_blank_this() {
-					cgwrite!(&mut out, cbcx, " ", spectator)?;
-				}
-				Some(out)
-			}
-			MinecraftInstr::SpectateStop => Some("spectate".into()),
-			MinecraftInstr::SetGamemode { target, gamemode } => {
-				Some(cgformat!(cbcx, "gamemode ", gamemode, " ", target)?)
-			}
-			MinecraftInstr::DefaultGamemode { gamemode } => {
-				Some(cgformat!(cbcx, "defaultgamemode ", gamemode)?)
-			}
 			MinecraftInstr::TeleportToEntity { source, dest } => {
 				let mut out = String::new();
 				cgwrite!(&mut out, cbcx, "tp ")?;
@@ -599,6 +680,7 @@ pub fn codegen_instr(
 				criterion,
 				display_name,
 			} => {
+				cbcx.ccx.added_objectives.insert(objective.as_str().into());
 				let mut out = String::new();
 				cgwrite!(&mut out, cbcx, "scoreboard objectives add ", objective, " ")?;
 				match criterion {
@@ -611,20 +693,6 @@ pub fn codegen_instr(
 				}
 				Some(out)
 			}
-			MinecraftInstr::RemoveScoreboardObjective { objective } => {
-				Some(cgformat!(cbcx, "scoreboard objectives remove ", objective)?)
-			}
-			MinecraftInstr::ListScoreboardObjectives => Some("scoreboard objectives list".into()),
-			MinecraftInstr::TriggerAdd { objective, amount } => {
-				if *amount == 1 {
-					Some(cgformat!(cbcx, "trigger ", objective)?)
-				} else {
-					Some(cgformat!(cbcx, "trigger ", objective, " add ", amount)?)
-				}
-			}
-			MinecraftInstr::TriggerSet { objective, amount } => {
-				Some(cgformat!(cbcx, "trigger ", objective, " set ", amount)?)
-			}
 			MinecraftInstr::GetAttribute {
 				target,
 				attribute,
@@ -740,33 +808,10 @@ pub fn codegen_instr(
 
 				Some(out)
 			}
-			MinecraftInstr::DisableDatapack { pack } => {
-				Some(cgformat!(cbcx, "datapack disable ", pack)?)
-			}
-			MinecraftInstr::EnableDatapack { pack } => {
-				Some(cgformat!(cbcx, "datapack enable ", pack)?)
-			}
-			MinecraftInstr::SetDatapackPriority { pack, priority } => {
-				Some(cgformat!(cbcx, "datapack enable ", pack, " ", priority)?)
-			}
-			MinecraftInstr::SetDatapackOrder {
-				pack,
-				order,
-				existing,
-			} => Some(cgformat!(
-				cbcx,
-				"datapack enable ",
-				pack,
-				" ",
-				order,
-				" ",
-				existing
-			)?),
 			MinecraftInstr::ListDatapacks { mode } => match mode {
 				DatapackListMode::All => Some("datapack list".into()),
 				other => Some(cgformat!(cbcx, "datapack list ", other)?),
 			},
-			MinecraftInstr::ListPlayerUUIDs => Some("list uuids".into()),
 			MinecraftInstr::SummonEntity { entity, pos, nbt } => {
 				let mut out = String::new();
 				cgwrite!(&mut out, cbcx, "summon ", entity)?;
@@ -905,158 +950,56 @@ pub fn codegen_instr(
 				Some(format!("gamerule {rule} {value}"))
 			}
 			MinecraftInstr::GetGamerule { rule } => Some(format!("gamerule {rule}")),
-			MinecraftInstr::Locate {
-				location_type,
-				location,
-			} => Some(format!("locate {location_type:?} {location}")),
-			MinecraftInstr::LootGive { player, source } => {
-				Some(cgformat!(cbcx, "loot give ", player, " ", source)?)
-			}
-			MinecraftInstr::LootInsert { pos, source } => {
-				Some(cgformat!(cbcx, "loot insert ", pos, " ", source)?)
-			}
-			MinecraftInstr::LootSpawn { pos, source } => {
-				Some(cgformat!(cbcx, "loot spawn ", pos, " ", source)?)
-			}
-			MinecraftInstr::LootReplaceBlock {
-				pos,
-				slot,
-				count,
-				source,
+			MinecraftInstr::ScheduleFunction {
+				function,
+				time,
+				mode,
 			} => {
-				if *count == 1 {
-					Some(cgformat!(
-						cbcx,
-						"loot replace block ",
-						pos,
-						" ",
-						slot,
-						" ",
-						source
-					)?)
-				} else {
-					Some(cgformat!(
-						cbcx,
-						"loot replace block ",
-						pos,
-						" ",
-						slot,
-						" ",
-						count,
-						" ",
-						source
-					)?)
+				let func_id = cbcx
+					.ccx
+					.func_mapping
+					.as_ref()
+					.and_then(|mapping| mapping.0.get(function))
+					.unwrap_or(function)
+					.clone();
+				let mut out = cgformat!(cbcx, "schedule function ", func_id, " ", time)?;
+				// Replace is the default
+				if let ScheduleMode::Append = mode {
+					out.push_str(" append");
 				}
+				Some(out)
 			}
-			MinecraftInstr::LootReplaceEntity {
-				target,
-				slot,
-				count,
-				source,
-			} => {
-				if *count == 1 {
-					Some(cgformat!(
-						cbcx,
-						"loot replace entity ",
-						target,
-						" ",
-						slot,
-						" ",
-						source
-					)?)
-				} else {
-					Some(cgformat!(
-						cbcx,
-						"loot replace entity ",
-						target,
-						" ",
-						slot,
-						" ",
-						count,
-						" ",
-						source
-					)?)
-				}
+			MinecraftInstr::ScheduleClear { function } => {
+				let func_id = cbcx
+					.ccx
+					.func_mapping
+					.as_ref()
+					.and_then(|mapping| mapping.0.get(function))
+					.unwrap_or(function);
+				Some(format!("schedule clear {func_id}"))
 			}
-			MinecraftInstr::ItemModify {
-				location,
-				slot,
-				modifier,
-			} => Some(cgformat!(
-				cbcx,
-				"item modify ",
-				location,
-				" ",
-				slot,
-				" ",
-				modifier
-			)?),
-			MinecraftInstr::ItemReplaceWith {
+			MinecraftInstr::Locate {
+				location_type,
 				location,
-				slot,
-				item,
-				count,
-			} => {
-				if *count == 1 {
-					Some(cgformat!(
-						cbcx,
-						"item replace ",
-						location,
-						" ",
-						slot,
-						" with ",
-						item
-					)?)
-				} else {
-					Some(cgformat!(
-						cbcx,
-						"item replace ",
-						location,
-						" ",
-						slot,
-						" with ",
-						item,
-						" ",
-						count
-					)?)
-				}
-			}
-			MinecraftInstr::ItemReplaceFrom {
-				dest,
-				slot,
-				source,
-				modifier,
 			} => {
-				if let Some(modifier) = modifier {
-					Some(cgformat!(
-						cbcx,
-						"item replace ",
-						dest,
-						" ",
-						slot,
-						" with ",
-						source,
-						" ",
-						modifier
-					)?)
+				// Locate was split into separate commands before 1.19
+				if cbcx.ccx.project.target_version >= MinecraftVersion::new(19, 0) {
+					Some(format!("locate {location_type:?} {location}"))
 				} else {
-					Some(cgformat!(
-						cbcx,
-						"item replace ",
-						dest,
-						" ",
-						slot,
-						" with ",
-						source
-					)?)
-				}
-			}
-			MinecraftInstr::PlaceFeature { feature, pos } => {
-				let mut out = cgformat!(cbcx, "place feature ", feature)?;
-				if !pos.are_zero() {
-					cgwrite!(&mut out, cbcx, " ", pos)?;
+					match location_type {
+						Location::Structure => Some(format!("locate {location}")),
+						Location::Biome => {
+							cbcx.ccx.require_version(
+								MinecraftVersion::new(16, 0),
+								"Locating a biome",
+							)?;
+							Some(format!("locatebiome {location}"))
+						}
+						Location::POI => {
+							bail!("Locating a point of interest requires Minecraft 1.19 or later")
+						}
+					}
 				}
-				Some(out)
 			}
 			MinecraftInstr::PlaceJigsaw {
 				pool,
@@ -1115,6 +1058,19 @@ pub fn codegen_instr(
 			MinecraftInstr::WorldBorderWarningTime { time } => {
 				Some(cgformat!(cbcx, "worldborder warning time ", time)?)
 			}
+			MinecraftInstr::RandomValue { range, sequence }
+			| MinecraftInstr::RandomRoll { range, sequence } => {
+				let mode = if matches!(instr, MinecraftInstr::RandomValue { .. }) {
+					"value"
+				} else {
+					"roll"
+				};
+				let mut out = cgformat!(cbcx, "random ", mode, " ", range)?;
+				if let Some(sequence) = sequence {
+					cgwrite!(&mut out, cbcx, " ", sequence)?;
+				}
+				Some(out)
+			}
 			MinecraftInstr::PlaySound {
 				sound,
 				source,
@@ -1139,8 +1095,54 @@ pub fn codegen_instr(
 				}
 				Some(out)
 			}
+			MinecraftInstr::Particle {
+				particle,
+				pos,
+				delta,
+				speed,
+				count,
+				mode,
+				viewers,
+			} => {
+				let mut out = cgformat!(cbcx, "particle ", particle)?;
+				let write_mode = *mode != ParticleMode::Normal || viewers.is_some();
+				let write_delta = *delta != [0.0; 3] || *speed != 0.0 || *count != 0 || write_mode;
+				if !pos.are_zero() || write_delta {
+					cgwrite!(&mut out, cbcx, " ", pos)?;
+				}
+				if write_delta {
+					let [dx, dy, dz] = *delta;
+					cgwrite!(
+						&mut out,
+						cbcx,
+						" ",
+						FloatCG(dx, false, true, true),
+						" ",
+						FloatCG(dy, false, true, true),
+						" ",
+						FloatCG(dz, false, true, true),
+						" ",
+						FloatCG(*speed, false, true, true),
+						" ",
+						count
+					)?;
+				}
+				if write_mode {
+					cgwrite!(&mut out, cbcx, " ", mode)?;
+				}
+				if let Some(viewers) = viewers {
+					cgwrite!(&mut out, cbcx, " ", viewers)?;
+				}
+				Some(out)
+			}
 		},
 		LIRInstrKind::Command(cmd) => Some(cmd.clone()),
+		LIRInstrKind::MacroCommand(cmd) => {
+			cbcx.ccx
+				.require_version(MinecraftVersion::new(20, 2), "Macro commands")?;
+			cbcx.macro_line = true;
+			Some(cmd.clone())
+		}
 		LIRInstrKind::Comment(cmt) => Some(format!("#{cmt}")),
 		LIRInstrKind::Use(..) | LIRInstrKind::NoOp => None,
 	};
@@ -1181,8 +1183,36 @@ impl CommandBuilder {
 			}
 		};
 
+		// Comments can't be prefixed
+		let is_comment = command.as_ref().is_some_and(|x| x.starts_with('#'));
+		let prefix = if is_comment {
+			None
+		} else {
+			cbcx.ccx.project.command_prefix.as_ref()
+		};
+		let has_guard = matches!(prefix, Some(CommandPrefix::ScoreGuard { .. }));
+		match prefix {
+			Some(CommandPrefix::ScoreGuard {
+				holder,
+				objective,
+				value,
+			}) => {
+				// The guard is the first modifier of the execute command
+				out.push_str(&format!(
+					"execute if score {holder} {objective} matches {value} "
+				));
+				if self.modifiers.is_empty() {
+					out.push_str("run ");
+				}
+			}
+			Some(CommandPrefix::Custom(prefix)) => out.push_str(prefix),
+			None => {}
+		}
+
 		if !self.modifiers.is_empty() {
-			out.push_str("execute ");
+			if !has_guard {
+				out.push_str("execute ");
+			}
 			let len = self.modifiers.len();
 			for (i, modifier) in self.modifiers.into_iter().enumerate() {
 				if let Some(modifier) = codegen_modifier(modifier, cbcx)? {
@@ -1214,6 +1244,7 @@ impl Codegen for Score {
 	{
 		self.holder.gen_writer(f, cbcx)?;
 		write!(f, " {}", self.objective)?;
+		cbcx.ccx.used_objectives.insert(self.objective.clone());
 		Ok(())
 	}
 }
@@ -4,13 +4,14 @@ use crate::common::mc::modifier::{
 	EntityRelation, IfModCondition, IfScoreCondition, IfScoreRangeEnd, Modifier, StoreDataType,
 	StoreModLocation,
 };
+use crate::common::mc::version::MinecraftVersion;
 use crate::common::ty::{DataType, ScoreTypeContents};
 use crate::common::val::ScoreValue;
 use crate::output::codegen::util::FloatCG;
 
 use super::t::macros::cgformat;
 use super::util::{
-	create_lit_score, get_mut_score_val_score, get_nbt_local_loc, get_score_local_score,
+	get_mut_score_val_score, get_nbt_local_loc, get_score_local_score,
 };
 use super::{Codegen, CodegenBlockCx};
 
@@ -126,6 +127,10 @@ pub fn codegen_modifier(
 					Some(cgformat!(cbcx, keyword, " predicate ", pred)?)
 				}
 				IfModCondition::Function(fun, _) => {
+					cbcx.ccx.require_version(
+						MinecraftVersion::new(20, 3),
+						"Checking the result of a function",
+					)?;
 					let mut func_id = fun;
 					if let Some(mapping) = &cbcx.ccx.func_mapping {
 						if let Some(new_id) = mapping.0.get(&func_id) {
@@ -135,12 +140,22 @@ pub fn codegen_modifier(
 					Some(cgformat!(cbcx, keyword, " function ", func_id)?)
 				}
 				IfModCondition::Biome(pos, biome) => {
+					cbcx.ccx
+						.require_version(MinecraftVersion::new(19, 4), "Checking the biome")?;
 					Some(cgformat!(cbcx, keyword, " biome ", pos, " ", biome)?)
 				}
 				IfModCondition::Dimension(dim) => {
+					cbcx.ccx
+						.require_version(MinecraftVersion::new(19, 4), "Checking the dimension")?;
 					Some(cgformat!(cbcx, keyword, " dimension ", dim)?)
 				}
-				IfModCondition::Loaded(pos) => Some(cgformat!(cbcx, keyword, " loaded ", pos)?),
+				IfModCondition::Loaded(pos) => {
+					cbcx.ccx.require_version(
+						MinecraftVersion::new(19, 4),
+						"Checking if a position is loaded",
+					)?;
+					Some(cgformat!(cbcx, keyword, " loaded ", pos)?)
+				}
 				IfModCondition::DataExists(loc) => Some(cgformat!(cbcx, keyword, " data ", loc)?),
 				IfModCondition::DataEquals(l, r) => Some(cgformat!(
 					cbcx,
@@ -153,6 +168,13 @@ pub fn codegen_modifier(
 				IfModCondition::Block(loc, block) => {
 					Some(cgformat!(cbcx, keyword, " block ", loc, " ", block)?)
 				}
+				IfModCondition::Items(loc, slot, item) => {
+					cbcx.ccx
+						.require_version(MinecraftVersion::new(20, 5), "Checking items")?;
+					Some(cgformat!(
+						cbcx, keyword, " items ", loc, " ", slot, " ", item
+					)?)
+				}
 				IfModCondition::Const(val) => {
 					let left = ScoreValue::Constant(ScoreTypeContents::Score(0));
 					let right_num = if val { 0 } else { 1 };
@@ -173,6 +195,8 @@ pub fn codegen_modifier(
 		Modifier::At(target) => Some(cgformat!(cbcx, "at ", target)?),
 		Modifier::In(dimension) => Some(format!("in {dimension}")),
 		Modifier::On(relation) => {
+			cbcx.ccx
+				.require_version(MinecraftVersion::new(19, 4), "Running on a related entity")?;
 			let string = match relation {
 				EntityRelation::Attacker => "attacker",
 				EntityRelation::Controller => "controller",
@@ -188,14 +212,22 @@ pub fn codegen_modifier(
 		}
 		Modifier::Positioned(pos) => Some(cgformat!(cbcx, "positioned ", pos)?),
 		Modifier::PositionedAs(target) => Some(cgformat!(cbcx, "positioned as ", target)?),
-		Modifier::PositionedOver(hm) => Some(cgformat!(cbcx, "positioned over ", hm)?),
+		Modifier::PositionedOver(hm) => {
+			cbcx.ccx
+				.require_version(MinecraftVersion::new(19, 4), "Positioning over a heightmap")?;
+			Some(cgformat!(cbcx, "positioned over ", hm)?)
+		}
 		Modifier::Rotated(rot) => Some(cgformat!(cbcx, "rotated ", rot)?),
 		Modifier::RotatedAs(target) => Some(cgformat!(cbcx, "rotated as ", target)?),
 		Modifier::FacingPosition(pos) => Some(cgformat!(cbcx, "facing ", pos)?),
 		Modifier::FacingEntity(target, anchor) => {
 			Some(cgformat!(cbcx, "facing entity ", target, " ", anchor)?)
 		}
-		Modifier::Summon(entity) => Some(format!("summon {entity}")),
+		Modifier::Summon(entity) => {
+			cbcx.ccx
+				.require_version(MinecraftVersion::new(19, 4), "Running as a summoned entity")?;
+			Some(format!("summon {entity}"))
+		}
 	};
 
 	Ok(out)
@@ -234,7 +266,7 @@ fn codegen_if_score_range_side(
 					cgformat!(cbcx, if_keyword, " score ", score, " matches ", check)?
 				} else {
 					cbcx.ccx.score_literals.insert(num);
-					let rhs = create_lit_score(num).gen_str(cbcx)?;
+					let rhs = cbcx.ccx.get_lit_score(num).gen_str(cbcx)?;
 					let sign = if lt { "<" } else { ">" };
 					cgformat!(cbcx, if_keyword, " score ", score, " ", sign, " ", rhs)?
 				}
@@ -281,10 +313,13 @@ fn codegen_score_lt(inclusive: bool) -> &'static str {
 impl StoreModLocation {
 	fn codegen(self, cbcx: &mut CodegenBlockCx) -> anyhow::Result<String> {
 		match self {
-			Self::Local(loc, scale) => {
+			Self::Local(loc, explicit_ty, scale) => {
 				let ty = loc.get_ty(&cbcx.regs, &cbcx.sig)?;
 				match ty {
 					DataType::Score(..) => {
+						if explicit_ty.is_some() {
+							bail!("Storage type cannot be used for storing to a local of score type");
+						}
 						let loc = get_score_local_score(&loc, cbcx)?;
 						cgformat!(cbcx, "score ", loc)
 					}
@@ -292,6 +327,12 @@ impl StoreModLocation {
 						let loc = get_nbt_local_loc(&loc, cbcx)?;
 						let ty = StoreDataType::from_nbt_ty(&ty)
 							.context("Type is not a valid storage type")?;
+						// A written out type has to agree with the one from the declaration
+						if let Some(explicit_ty) = explicit_ty {
+							if explicit_ty != ty {
+								bail!("Storage type {explicit_ty:?} does not match the inferred type {ty:?}");
+							}
+						}
 						cgformat!(cbcx, loc, " ", ty, " ", FloatCG(scale, false, true, true))
 					}
 					_ => bail!("Type not supported"),
@@ -316,6 +357,7 @@ mod tests {
 	use crate::common::mc::{EntityTarget, Score};
 	use crate::common::RegisterList;
 	use crate::common::{ty::ScoreTypeContents, val::MutableScoreValue};
+	use crate::output::intern::Interner;
 	use crate::output::ra::GlobalRegAllocResult;
 	use crate::output::{codegen::CodegenCx, ra::RegAllocResult};
 	use crate::project::ProjectSettings;
@@ -326,7 +368,7 @@ mod tests {
 		let ra = GlobalRegAllocResult {
 			results: FxHashMap::default(),
 		};
-		let mut ccx = CodegenCx::new(&proj, None, ra);
+		let mut ccx = CodegenCx::new(&proj, None, ra, Interner::new());
 		let mut cbcx = CodegenBlockCx {
 			ccx: &mut ccx,
 			ra: RegAllocResult::new(),
@@ -0,0 +1,176 @@
+//! Declarative table of command specs. Simple commands are described here as a
+//! list of arguments along with rules for when trailing arguments can be elided,
+//! and the codegen arms are generated from that description. The versions that
+//! commands were introduced in come from the instruction metadata
+
+use anyhow::bail;
+
+use crate::common::mc::instr::MinecraftInstr;
+
+use super::util::{SpaceSepListCG, WeatherDurationCG};
+use super::{Codegen, CodegenBlockCx};
+
+/// Writes the parts of a command spec. Parts are separated by commas and can be:
+/// - An expression that implements Codegen
+/// - `[if cond => parts]`, which only writes the parts when the condition is true
+/// - `[some field => parts]`, which only writes the parts when the optional field is present
+macro_rules! spec_parts {
+	($out:ident, $cbcx:ident;) => {};
+	($out:ident, $cbcx:ident; [if $cond:expr => $($inner:tt)*] $(, $($rest:tt)*)?) => {
+		if $cond {
+			spec_parts!($out, $cbcx; $($inner)*);
+		}
+		spec_parts!($out, $cbcx; $($($rest)*)?);
+	};
+	($out:ident, $cbcx:ident; [some $field:ident => $($inner:tt)*] $(, $($rest:tt)*)?) => {
+		if let Some($field) = $field {
+			spec_parts!($out, $cbcx; $($inner)*);
+		}
+		spec_parts!($out, $cbcx; $($($rest)*)?);
+	};
+	($out:ident, $cbcx:ident; $part:expr $(, $($rest:tt)*)?) => {
+		$part.gen_writer(&mut $out, $cbcx)?;
+		spec_parts!($out, $cbcx; $($($rest)*)?);
+	};
+}
+
+macro_rules! command_specs {
+	($(
+		$variant:ident $({ $($field:ident),* $(,)? })? => { $($parts:tt)* }
+	)*) => {
+		/// Pattern that matches every instruction in the spec table, so that codegen
+		/// sends exactly these instructions to `codegen_spec`
+		macro_rules! spec_instr_pattern {
+			() => {
+				$(MinecraftInstr::$variant { .. })|*
+			};
+		}
+		pub(super) use spec_instr_pattern;
+
+		/// Generates the command for an instruction from the spec table. Codegen
+		/// only calls this with the instructions matched by `spec_instr_pattern`
+		pub fn codegen_spec(
+			instr: &MinecraftInstr,
+			cbcx: &mut CodegenBlockCx,
+		) -> anyhow::Result<String> {
+			match instr {
+				$(
+					MinecraftInstr::$variant $({ $($field),* })? => {
+						let mut out = String::new();
+						spec_parts!(out, cbcx; $($parts)*);
+						Ok(out)
+					}
+				)*
+				other => bail!("Instruction {other:?} does not have a command spec"),
+			}
+		}
+	};
+}
+
+command_specs! {
+	Say { message } => { "say ", message }
+	Me { message } => { "me ", message }
+	TeamMessage { message } => { "tm ", message }
+	Tell { target, message } => { "w ", target, " ", message }
+	TellRaw { target, message } => { "tellraw ", target, " ", message }
+	Title { target, title } => { "title ", target, " title ", title }
+	Subtitle { target, subtitle } => { "title ", target, " subtitle ", subtitle }
+	ActionBar { target, text } => { "title ", target, " actionbar ", text }
+	Kill { target } => { "kill", [if !target.is_blank_this() => " ", target] }
+	Reload => { "reload" }
+	AddXP { target, amount, value } => { "xp add ", target, " ", amount, " ", value }
+	GetXP { target, value } => { "xp query ", target, " ", value }
+	BanPlayers { targets, reason } => { "ban ", SpaceSepListCG(targets), [some reason => " ", reason] }
+	BanIP { target, reason } => { "ban-ip ", target, [some reason => " ", reason] }
+	PardonPlayers { targets } => { "pardon ", SpaceSepListCG(targets) }
+	PardonIP { target } => { "pardon-ip ", target }
+	Op { targets } => { "op ", SpaceSepListCG(targets) }
+	Deop { targets } => { "deop ", SpaceSepListCG(targets) }
+	WhitelistAdd { targets } => { "whitelist add ", SpaceSepListCG(targets) }
+	WhitelistRemove { targets } => { "whitelist remove ", SpaceSepListCG(targets) }
+	WhitelistOn => { "whitelist on" }
+	WhitelistOff => { "whitelist off" }
+	WhitelistReload => { "whitelist reload" }
+	WhitelistList => { "whitelist list" }
+	Kick { targets, reason } => { "kick ", SpaceSepListCG(targets), [some reason => " ", reason] }
+	Banlist => { "banlist" }
+	StopSound => { "stopsound" }
+	StopServer => { "stop" }
+	ListPlayers => { "list" }
+	ListPlayerUUIDs => { "list uuids" }
+	Seed => { "seed" }
+	GetDifficulty => { "difficulty" }
+	SetDifficulty { difficulty } => { "difficulty ", difficulty }
+	Enchant { target, enchantment, level } => {
+		"enchant ", target, " ", enchantment, [if *level != 1 => " ", level]
+	}
+	SetWeather { weather, duration } => {
+		"weather ", weather, [some duration => " ", WeatherDurationCG(duration)]
+	}
+	AddTime { time } => { "time add ", time }
+	SetTimePreset { time } => { "time set ", time }
+	GetTime { query } => { "time get ", query }
+	AddTag { target, tag } => { "tag ", target, " add ", tag }
+	RemoveTag { target, tag } => { "tag ", target, " remove ", tag }
+	ListTags { target } => { "tag ", target, " list" }
+	RideMount { target, vehicle } => { "ride ", target, " mount ", vehicle }
+	RideDismount { target } => { "ride ", target, " dismount" }
+	Spectate { target, spectator } => {
+		"spectate ", target, [if !spectator.is_blank_this() => " ", spectator]
+	}
+	SpectateStop => { "spectate" }
+	SetGamemode { target, gamemode } => { "gamemode ", gamemode, " ", target }
+	DefaultGamemode { gamemode } => { "defaultgamemode ", gamemode }
+	RemoveScoreboardObjective { objective } => { "scoreboard objectives remove ", objective }
+	ListScoreboardObjectives => { "scoreboard objectives list" }
+	SetObjectiveDisplayName { objective, name } => {
+		"scoreboard objectives modify ", objective, " displayname ", name
+	}
+	SetObjectiveNumberFormat { objective, format } => {
+		"scoreboard objectives modify ", objective, " numberformat", [some format => " ", format]
+	}
+	AddTeam { team, display_name } => { "team add ", team, [some display_name => " ", display_name] }
+	RemoveTeam { team } => { "team remove ", team }
+	EmptyTeam { team } => { "team empty ", team }
+	JoinTeam { team, target } => {
+		"team join ", team, [if !target.is_blank_this() => " ", target]
+	}
+	LeaveTeam { target } => { "team leave ", target }
+	ListTeams { team } => { "team list", [some team => " ", team] }
+	ModifyTeam { team, option } => { "team modify ", team, " ", option }
+	AddBossbar { id, name } => { "bossbar add ", id, " ", name }
+	RemoveBossbar { id } => { "bossbar remove ", id }
+	SetBossbar { id, option } => { "bossbar set ", id, " ", option }
+	GetBossbar { id, query } => { "bossbar get ", id, " ", query }
+	TriggerAdd { objective, amount } => {
+		"trigger ", objective, [if *amount != 1 => " add ", amount]
+	}
+	TriggerSet { objective, amount } => { "trigger ", objective, " set ", amount }
+	DisableDatapack { pack } => { "datapack disable ", pack }
+	EnableDatapack { pack } => { "datapack enable ", pack }
+	SetDatapackPriority { pack, priority } => { "datapack enable ", pack, " ", priority }
+	SetDatapackOrder { pack, order, existing } => {
+		"datapack enable ", pack, " ", order, " ", existing
+	}
+	LootGive { player, source } => { "loot give ", player, " ", source }
+	LootInsert { pos, source } => { "loot insert ", pos, " ", source }
+	LootSpawn { pos, source } => { "loot spawn ", pos, " ", source }
+	LootReplaceBlock { pos, slot, count, source } => {
+		"loot replace block ", pos, " ", slot, [if *count != 1 => " ", count], " ", source
+	}
+	LootReplaceEntity { target, slot, count, source } => {
+		"loot replace entity ", target, " ", slot, [if *count != 1 => " ", count], " ", source
+	}
+	ItemModify { location, slot, modifier } => {
+		"item modify ", location, " ", slot, " ", modifier
+	}
+	ItemReplaceWith { location, slot, item, count } => {
+		"item replace ", location, " ", slot, " with ", item, [if *count != 1 => " ", count]
+	}
+	ItemReplaceFrom { dest, slot, source, modifier } => {
+		"item replace ", dest, " ", slot, " with ", source, [some modifier => " ", modifier]
+	}
+	PlaceFeature { feature, pos } => {
+		"place feature ", feature, [if !pos.are_zero() => " ", pos]
+	}
+}
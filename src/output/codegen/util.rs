@@ -1,13 +1,14 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 
-use crate::common::mc::{DataLocation, DataPath, EntityTarget, FullDataLocation, Score};
+use crate::common::mc::time::Time;
+use crate::common::mc::version::MinecraftVersion;
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation, Score};
 use crate::common::reg::Local;
 use crate::common::val::{MutableNBTValue, MutableScoreValue, NBTValue, ScoreValue};
 use crate::lower::cleanup_fn_id;
 use crate::output::text::{
-	format_arg_fake_player, format_arg_local_storage_entry, format_lit_fake_player,
-	format_ret_fake_player, format_ret_local_storage_entry, LIT_OBJECTIVE, REG_OBJECTIVE,
-	REG_STORAGE_LOCATION,
+	format_arg_fake_player, format_arg_local_storage_entry, format_ret_fake_player,
+	format_ret_local_storage_entry,
 };
 
 use super::t::macros::cgformat;
@@ -21,7 +22,7 @@ pub fn get_score_val_score(
 	let out = match val {
 		ScoreValue::Constant(score) => {
 			let num = score.get_i32();
-			(create_lit_score(num), ScoreLiteral(Some(num)))
+			(cbcx.ccx.get_lit_score(num), ScoreLiteral(Some(num)))
 		}
 		ScoreValue::Mutable(val) => (get_mut_score_val_score(val, cbcx)?, ScoreLiteral(None)),
 	};
@@ -42,47 +43,35 @@ pub fn get_mut_score_val_score(
 }
 
 pub fn get_score_local_score(val: &Local, cbcx: &mut CodegenBlockCx) -> anyhow::Result<Score> {
-	let out = match val {
-		Local::Reg(reg) => {
-			let reg = cbcx
-				.ra
-				.regs
-				.get(reg)
-				.ok_or(anyhow!("Register {reg} not allocated"))?;
-			cbcx.ccx
-				.add_requirement(CodegenRequirement::UseRegObjective);
-			Score::new(EntityTarget::Player(reg.clone()), REG_OBJECTIVE.into())
-		}
+	let hidden = cbcx.ccx.project.hidden_fake_players;
+	let holder = match val {
+		Local::Reg(reg) => cbcx
+			.ra
+			.regs
+			.get(reg)
+			.ok_or_else(|| anyhow!("Register {reg} not allocated"))?
+			.clone(),
 		Local::Arg(arg) => {
-			let arg = format_arg_fake_player(*arg, &cbcx.func_id);
-
-			cbcx.ccx
-				.add_requirement(CodegenRequirement::UseRegObjective);
-			Score::new(EntityTarget::Player(arg), REG_OBJECTIVE.into())
+			let arg = format_arg_fake_player(*arg, &cbcx.func_id, hidden);
+			cbcx.ccx.interner.intern_owned(arg)
 		}
 		Local::CallArg(arg, func, ..) => {
 			let func_id = cleanup_fn_id(func);
-			let arg = format_arg_fake_player(*arg, &func_id);
-			cbcx.ccx
-				.add_requirement(CodegenRequirement::UseRegObjective);
-			Score::new(EntityTarget::Player(arg), REG_OBJECTIVE.into())
+			let arg = format_arg_fake_player(*arg, &func_id, hidden);
+			cbcx.ccx.interner.intern_owned(arg)
 		}
 		Local::ReturnValue(ret) => {
-			let ret = format_ret_fake_player(*ret, &cbcx.func_id);
-			cbcx.ccx
-				.add_requirement(CodegenRequirement::UseRegObjective);
-			Score::new(EntityTarget::Player(ret), REG_OBJECTIVE.into())
+			let ret = format_ret_fake_player(*ret, &cbcx.func_id, hidden);
+			cbcx.ccx.interner.intern_owned(ret)
 		}
 		Local::CallReturnValue(ret, func, ..) => {
 			let func_id = cleanup_fn_id(func);
-			let ret = format_ret_fake_player(*ret, &func_id);
-			cbcx.ccx
-				.add_requirement(CodegenRequirement::UseRegObjective);
-			Score::new(EntityTarget::Player(ret), REG_OBJECTIVE.into())
+			let ret = format_ret_fake_player(*ret, &func_id, hidden);
+			cbcx.ccx.interner.intern_owned(ret)
 		}
 	};
 
-	Ok(out)
+	Ok(cbcx.ccx.get_reg_score(holder))
 }
 
 pub fn get_mut_nbt_val_loc(
@@ -117,9 +106,9 @@ pub fn get_nbt_local_loc(
 				.ra
 				.locals
 				.get(reg)
-				.ok_or(anyhow!("Local register {reg} not allocated"))?;
+				.ok_or_else(|| anyhow!("Local register {reg} not allocated"))?;
 			FullDataLocation {
-				loc: DataLocation::Storage(REG_STORAGE_LOCATION.into()),
+				loc: DataLocation::Storage(cbcx.ccx.get_reg_storage()),
 				path: DataPath::String(reg.clone()),
 			}
 		}
@@ -127,7 +116,7 @@ pub fn get_nbt_local_loc(
 			let arg = format_arg_local_storage_entry(*arg, &cbcx.func_id);
 			cbcx.ccx.add_requirement(CodegenRequirement::UseRegStorage);
 			FullDataLocation {
-				loc: DataLocation::Storage(REG_STORAGE_LOCATION.into()),
+				loc: DataLocation::Storage(cbcx.ccx.get_reg_storage()),
 				path: DataPath::String(arg),
 			}
 		}
@@ -136,7 +125,7 @@ pub fn get_nbt_local_loc(
 			let arg = format_arg_local_storage_entry(*arg, &func_id);
 			cbcx.ccx.add_requirement(CodegenRequirement::UseRegStorage);
 			FullDataLocation {
-				loc: DataLocation::Storage(REG_STORAGE_LOCATION.into()),
+				loc: DataLocation::Storage(cbcx.ccx.get_reg_storage()),
 				path: DataPath::String(arg),
 			}
 		}
@@ -144,7 +133,7 @@ pub fn get_nbt_local_loc(
 			let ret = format_ret_local_storage_entry(*ret, &cbcx.func_id);
 			cbcx.ccx.add_requirement(CodegenRequirement::UseRegStorage);
 			FullDataLocation {
-				loc: DataLocation::Storage(REG_STORAGE_LOCATION.into()),
+				loc: DataLocation::Storage(cbcx.ccx.get_reg_storage()),
 				path: DataPath::String(ret),
 			}
 		}
@@ -153,7 +142,7 @@ pub fn get_nbt_local_loc(
 			let ret = format_ret_local_storage_entry(*ret, &func_id);
 			cbcx.ccx.add_requirement(CodegenRequirement::UseRegStorage);
 			FullDataLocation {
-				loc: DataLocation::Storage(REG_STORAGE_LOCATION.into()),
+				loc: DataLocation::Storage(cbcx.ccx.get_reg_storage()),
 				path: DataPath::String(ret),
 			}
 		}
@@ -162,13 +151,6 @@ pub fn get_nbt_local_loc(
 	Ok(out)
 }
 
-pub fn create_lit_score(num: i32) -> Score {
-	Score::new(
-		EntityTarget::Player(format_lit_fake_player(num)),
-		LIT_OBJECTIVE.into(),
-	)
-}
-
 /// A score literal that must be added to the literal list
 #[must_use]
 pub struct ScoreLiteral(pub Option<i32>);
@@ -209,6 +191,30 @@ impl<'v, CG: Codegen> Codegen for SpaceSepListCG<'v, CG> {
 	}
 }
 
+/// The duration of weather, which is a number of seconds before 1.19.4 and
+/// a time with a unit after
+pub struct WeatherDurationCG<'v>(pub &'v Time);
+
+impl<'v> Codegen for WeatherDurationCG<'v> {
+	fn gen_writer<F>(&self, f: &mut F, cbcx: &mut CodegenBlockCx) -> anyhow::Result<()>
+	where
+		F: std::fmt::Write,
+	{
+		if cbcx.ccx.project.target_version >= MinecraftVersion::new(19, 4) {
+			return self.0.gen_writer(f, cbcx);
+		}
+		let ticks = self.0.round_to_ticks().get_whole_ticks()?;
+		if ticks % 20 != 0 {
+			bail!(
+				"Weather duration {:?} must be a whole number of seconds before Minecraft 1.19.4",
+				self.0
+			);
+		}
+		write!(f, "{}", ticks / 20)?;
+		Ok(())
+	}
+}
+
 pub struct FloatCG(pub f64, pub bool, pub bool, pub bool);
 
 impl Codegen for FloatCG {
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use anyhow::Context;
+use itertools::Itertools;
+
+use super::files::{encode_text, get_func_path, get_func_tag_path};
+use super::Datapack;
+
+impl Datapack {
+	/// Writes every function in the pack to one text file with a marker before each
+	/// one, so that the output can be shared or reviewed without a pack directory.
+	/// The first section is an installer function made of comments, which documents
+	/// where the functions go, the function tags, and the names of renamed functions
+	pub fn to_bundle(&self) -> anyhow::Result<String> {
+		let mut out = String::new();
+		out.push_str(&section("install"));
+		out.push_str(&format!(
+			"# {} (pack format {})\n",
+			self.meta.description, self.meta.pack_format
+		));
+		out.push_str("# Each section below is a function that goes at the path next to it\n");
+		for id in self.functions.keys().sorted() {
+			let path = get_func_path(id, self.layout)
+				.with_context(|| format!("Failed to get function path {id}"))?;
+			out.push_str(&format!("# {id}: data/{path}\n"));
+		}
+		for (id, tag) in self.function_tags.iter().sorted_by_key(|x| x.0) {
+			let path = get_func_tag_path(id, self.layout)
+				.with_context(|| format!("Failed to get function tag path {id}"))?;
+			out.push_str(&format!(
+				"# Tag {id} at data/{path}: {}\n",
+				tag.inner.values.join(", ")
+			));
+		}
+		if let Some(mapping) = &self.function_mapping {
+			for (original, new) in mapping.0.iter().sorted() {
+				out.push_str(&format!("# {original} is output as {new}\n"));
+			}
+		}
+
+		for (id, func) in self.functions.iter().sorted_by_key(|x| x.0) {
+			out.push('\n');
+			out.push_str(&section(id));
+			for line in &func.contents {
+				out.push_str(line);
+				out.push('\n');
+			}
+		}
+
+		Ok(out)
+	}
+
+	/// Writes the bundle of the pack to a file
+	pub fn output_bundle(&self, path: &Path) -> anyhow::Result<()> {
+		let bundle = self.to_bundle()?;
+		std::fs::write(path, encode_text(&bundle, self.text_format))
+			.context("Failed to write bundle")
+	}
+}
+
+/// Gets the marker line at the start of a section
+fn section(name: &str) -> String {
+	format!("# === {name} === #\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::ResourceLocation;
+	use crate::output::datapack::{DirectoryLayout, Function, Tag, TagInner};
+
+	#[test]
+	fn test_bundle() {
+		let mut pack = Datapack::new();
+		pack.layout = DirectoryLayout::Singular;
+		pack.meta.description = "Test pack".into();
+		for (id, line) in [("test:b", "say b"), ("test:a", "function test:b")] {
+			let mut func = Function::new();
+			func.contents.push(line.into());
+			pack.functions.insert(ResourceLocation::from(id), func);
+		}
+		pack.function_tags.insert(
+			"minecraft:load".into(),
+			Tag {
+				inner: TagInner {
+					values: vec!["test:a".into()],
+				},
+			},
+		);
+
+		let bundle = pack.to_bundle().expect("Failed to bundle");
+		let expected = format!(
+			"# === install === #\n# Test pack (pack format {})\n{}{}{}{}",
+			pack.meta.pack_format,
+			"# Each section below is a function that goes at the path next to it\n",
+			"# test:a: data/test/function/a.mcfunction\n# test:b: data/test/function/b.mcfunction\n",
+			"# Tag minecraft:load at data/minecraft/tags/function/load.json: test:a\n",
+			"\n# === test:a === #\nfunction test:b\n\n# === test:b === #\nsay b\n"
+		);
+		assert_eq!(bundle, expected);
+	}
+}
@@ -1,49 +1,188 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
 
-use super::Datapack;
+use super::{AuxiliaryFile, Datapack, DirectoryLayout, Function, Tag, TextFormat};
+
+/// The name of the file that the function mapping is written to
+pub(super) const FUNCTION_MAPPING_FILE: &str = "function_mapping.json";
 
 /// Outputs a datapack to a folder. Will remove all existing files in /data directory of that folder.
 pub fn output_pack(pack: Datapack, path: &Path) -> anyhow::Result<()> {
+	let data_path = clear_data_dir(path)?;
+	write_pack_meta(&pack, path).context("Failed to write pack.mcmeta")?;
+	write_function_mapping(&pack, path).context("Failed to write function mapping")?;
+	write_auxiliary_files(&pack, path)?;
+	for (id, function) in pack.functions {
+		write_function(&data_path, &id, &function, pack.layout, pack.text_format)?;
+	}
+	for (id, tag) in pack.function_tags {
+		write_function_tag(&data_path, &id, &tag, pack.layout, pack.text_format)?;
+	}
+
+	Ok(())
+}
+
+/// Removes and recreates the /data directory of a pack, returning its path
+pub(super) fn clear_data_dir(path: &Path) -> anyhow::Result<PathBuf> {
 	let data_path = path.join("data");
 	// Clear the old data
 	if data_path.exists() {
 		std::fs::remove_dir_all(&data_path).context("Failed to remove data directory")?;
 	}
 	std::fs::create_dir_all(&data_path).context("Failed to recreate data directory")?;
-	for (id, function) in pack.functions {
-		let path = data_path
-			.join(get_func_path(&id).context(format!("Failed to get function path {id}"))?);
-		if let Some(parent) = path.parent() {
-			let _ = std::fs::create_dir_all(parent);
-		}
-		std::fs::write(path, function.contents.join("\n"))
-			.context(format!("Failed to write function file {id}"))?;
+	Ok(data_path)
+}
+
+pub(super) fn write_function(
+	data_path: &Path,
+	id: &str,
+	function: &Function,
+	layout: DirectoryLayout,
+	format: TextFormat,
+) -> anyhow::Result<()> {
+	let path = data_path
+		.join(get_func_path(id, layout).context(format!("Failed to get function path {id}"))?);
+	if let Some(parent) = path.parent() {
+		let _ = std::fs::create_dir_all(parent);
 	}
-	for (id, tag) in pack.function_tags {
-		let path = data_path
-			.join(get_func_tag_path(&id).context(format!("Failed to get function tag path {id}"))?);
-		if let Some(parent) = path.parent() {
+	std::fs::write(path, encode_text(&function.contents.join("\n"), format))
+		.context(format!("Failed to write function file {id}"))
+}
+
+pub(super) fn write_function_tag(
+	data_path: &Path,
+	id: &str,
+	tag: &Tag,
+	layout: DirectoryLayout,
+	format: TextFormat,
+) -> anyhow::Result<()> {
+	let path = data_path
+		.join(get_func_tag_path(id, layout).context(format!("Failed to get function tag path {id}"))?);
+	if let Some(parent) = path.parent() {
+		let _ = std::fs::create_dir_all(parent);
+	}
+	let contents =
+		serde_json::to_string(&tag.inner).context("Failed to serialize function tag contents")?;
+	std::fs::write(path, encode_text(&contents, format))
+		.context(format!("Failed to write function tag file {id}"))
+}
+
+/// Writes the metadata, feature flags, and filter of a pack to its pack.mcmeta,
+/// keeping anything else that is already in the file
+pub(super) fn write_pack_meta(pack: &Datapack, path: &Path) -> anyhow::Result<()> {
+	let meta_path = path.join("pack.mcmeta");
+	let mut meta = if meta_path.exists() {
+		let contents = std::fs::read_to_string(&meta_path).context("Failed to read existing file")?;
+		// The file may have been written with a byte order mark
+		let contents = contents.strip_prefix('\u{feff}').unwrap_or(&contents);
+		serde_json::from_str(contents).context("Failed to parse existing file")?
+	} else {
+		serde_json::Value::Object(serde_json::Map::new())
+	};
+	let obj = meta.as_object_mut().context("Existing file is not a JSON object")?;
+
+	// Other fields in the pack section, like supported_formats, are kept
+	let pack_section = obj
+		.entry("pack")
+		.or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()))
+		.as_object_mut()
+		.context("Existing pack section is not a JSON object")?;
+	pack_section.insert("pack_format".into(), pack.meta.pack_format.into());
+	pack_section.insert("description".into(), pack.meta.description.clone().into());
+
+	if !pack.features.is_empty() {
+		let enabled: Vec<_> = pack
+			.features
+			.iter()
+			.map(|x| {
+				if x.contains(':') {
+					x.clone()
+				} else {
+					format!("minecraft:{x}")
+				}
+			})
+			.collect();
+		obj.insert("features".into(), serde_json::json!({ "enabled": enabled }));
+	}
+	if !pack.filter.is_empty() {
+		obj.insert("filter".into(), serde_json::json!({ "block": pack.filter }));
+	}
+
+	let contents = serde_json::to_string_pretty(&meta).context("Failed to serialize")?;
+	std::fs::write(meta_path, encode_text(&contents, pack.text_format))?;
+
+	Ok(())
+}
+
+/// Writes the mapping of function names next to the pack, or removes an old one
+/// if the pack doesn't rename any functions
+pub(super) fn write_function_mapping(pack: &Datapack, path: &Path) -> anyhow::Result<()> {
+	let mapping_path = path.join(FUNCTION_MAPPING_FILE);
+	if let Some(mapping) = &pack.function_mapping {
+		let contents =
+			serde_json::to_string_pretty(&mapping.to_json()).context("Failed to serialize")?;
+		std::fs::write(mapping_path, encode_text(&contents, pack.text_format))?;
+	} else if mapping_path.exists() {
+		std::fs::remove_file(mapping_path)?;
+	}
+
+	Ok(())
+}
+
+/// Copies the auxiliary files of a pack into it
+pub(super) fn write_auxiliary_files(pack: &Datapack, path: &Path) -> anyhow::Result<()> {
+	for (file_path, file) in &pack.auxiliary_files {
+		let out_path = path.join(file_path);
+		if let Some(parent) = out_path.parent() {
 			let _ = std::fs::create_dir_all(parent);
 		}
-		let contents = serde_json::to_string(&tag.inner)
-			.context("Failed to serialize function tag contents")?;
-		std::fs::write(path, contents)
-			.context(format!("Failed to write function tag file {id}"))?;
+		match file {
+			AuxiliaryFile::Path(source) => {
+				std::fs::copy(source, &out_path).with_context(|| {
+					format!("Failed to copy {} to {file_path}", source.display())
+				})?;
+			}
+			AuxiliaryFile::Contents(contents) => {
+				std::fs::write(&out_path, contents)
+					.with_context(|| format!("Failed to write {file_path}"))?;
+			}
+		}
 	}
 
 	Ok(())
 }
 
+/// Encodes text with lines separated by \n to write it to a file in a format
+pub(super) fn encode_text(text: &str, format: TextFormat) -> Vec<u8> {
+	let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+	let text = text.trim_end_matches(['\r', '\n']);
+	let mut out = String::with_capacity(text.len() + 4);
+	if format.bom {
+		out.push('\u{feff}');
+	}
+	let line_ending = format.line_ending.as_str();
+	for (i, line) in text.split('\n').enumerate() {
+		if i > 0 {
+			out.push_str(line_ending);
+		}
+		out.push_str(line.strip_suffix('\r').unwrap_or(line));
+	}
+	if format.trailing_newline && !text.is_empty() {
+		out.push_str(line_ending);
+	}
+
+	out.into_bytes()
+}
+
 /// Gets the relative path of a function
-pub fn get_func_path(loc: &str) -> anyhow::Result<String> {
-	get_resource_path(loc, "functions", "mcfunction")
+pub fn get_func_path(loc: &str, layout: DirectoryLayout) -> anyhow::Result<String> {
+	get_resource_path(loc, layout.functions_dir(), "mcfunction")
 }
 
 /// Gets the relative path of a function tag
-pub fn get_func_tag_path(loc: &str) -> anyhow::Result<String> {
-	get_resource_path(loc, "tags/functions", "json")
+pub fn get_func_tag_path(loc: &str, layout: DirectoryLayout) -> anyhow::Result<String> {
+	get_resource_path(loc, layout.function_tags_dir(), "json")
 }
 
 /// Gets the relative path of a file from a resource
@@ -57,11 +196,97 @@ pub fn get_resource_path(loc: &str, ty: &str, extension: &str) -> anyhow::Result
 mod tests {
 	use super::*;
 
+	use crate::output::datapack::LineEnding;
+
 	#[test]
 	fn test_func_path() {
 		assert_eq!(
-			get_func_path("game:gen/main").unwrap(),
+			get_func_path("game:gen/main", DirectoryLayout::Plural).unwrap(),
 			String::from("game/functions/gen/main.mcfunction")
 		);
+		assert_eq!(
+			get_func_path("game:gen/main", DirectoryLayout::Singular).unwrap(),
+			String::from("game/function/gen/main.mcfunction")
+		);
+	}
+
+	#[test]
+	fn test_func_tag_path() {
+		assert_eq!(
+			get_func_tag_path("minecraft:tick", DirectoryLayout::Singular).unwrap(),
+			String::from("minecraft/tags/function/tick.json")
+		);
+	}
+
+	#[test]
+	fn test_auxiliary_files() {
+		let dir = std::env::temp_dir().join("dpc_test_auxiliary_files");
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(&dir).expect("Failed to create directory");
+		let icon = dir.join("icon.png");
+		std::fs::write(&icon, [137, 80, 78, 71]).expect("Failed to write icon");
+
+		let mut pack = Datapack::new();
+		pack.add_file("pack.png", AuxiliaryFile::Path(icon))
+			.expect("Failed to add icon");
+		let readme = AuxiliaryFile::Contents(b"# Pack".to_vec());
+		pack.add_file("docs/README.md", readme.clone())
+			.expect("Failed to add readme");
+		assert!(pack.add_file("docs/README.md", readme.clone()).is_err());
+		assert!(pack.add_file("../README.md", readme.clone()).is_err());
+		assert!(pack.add_file("/README.md", readme.clone()).is_err());
+		assert!(pack.add_file("data/README.md", readme.clone()).is_err());
+		assert!(pack.add_file("pack.mcmeta", readme).is_err());
+
+		let out = dir.join("pack");
+		pack.output(&out).expect("Failed to output pack");
+		let read = |path: &str| std::fs::read(out.join(path)).expect("Failed to read file");
+		assert_eq!(read("pack.png"), [137, 80, 78, 71]);
+		assert_eq!(read("docs/README.md"), b"# Pack");
+	}
+
+	#[test]
+	fn test_text_format() {
+		let text = "\u{feff}say a\nsay b\n";
+		let mut format = TextFormat::default();
+		assert_eq!(encode_text(text, format), b"say a\nsay b");
+		format.trailing_newline = true;
+		assert_eq!(encode_text(text, format), b"say a\nsay b\n");
+		assert_eq!(encode_text("", format), b"");
+		format.line_ending = LineEnding::Crlf;
+		assert_eq!(encode_text(text, format), b"say a\r\nsay b\r\n");
+		assert_eq!(encode_text("say a\r\nsay b", format), b"say a\r\nsay b\r\n");
+		format.trailing_newline = false;
+		format.bom = true;
+		assert_eq!(encode_text(text, format), b"\xef\xbb\xbfsay a\r\nsay b");
+	}
+
+	#[test]
+	fn test_text_format_output() {
+		let dir = std::env::temp_dir().join("dpc_test_text_format");
+		let _ = std::fs::remove_dir_all(&dir);
+
+		let mut pack = Datapack::new();
+		pack.layout = DirectoryLayout::Singular;
+		pack.text_format = TextFormat {
+			line_ending: LineEnding::Crlf,
+			trailing_newline: true,
+			bom: true,
+		};
+		let mut func = Function::new();
+		func.contents = vec!["say a".into(), "say b".into()];
+		pack.functions.insert("test:main".into(), func);
+		// Writing twice reads back the pack.mcmeta with its byte order mark
+		pack.clone().output(&dir).expect("Failed to output pack");
+		pack.output(&dir).expect("Failed to output pack again");
+
+		let read = |path: &str| std::fs::read(dir.join(path)).expect("Failed to read file");
+		assert_eq!(
+			read("data/test/function/main.mcfunction"),
+			b"\xef\xbb\xbfsay a\r\nsay b\r\n"
+		);
+		let meta = read("pack.mcmeta");
+		assert!(meta.starts_with(b"\xef\xbb\xbf{\r\n"));
+		assert!(meta.ends_with(b"}\r\n"));
 	}
 }
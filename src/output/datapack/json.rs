@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::output::strip::FunctionMapping;
+
+use super::{
+	AuxiliaryFile, Datapack, DirectoryLayout, Function, PackFilter, PackMeta, Tag, TagInner,
+	TextFormat,
+};
+
+/// The version of the dump format, which is increased whenever it changes
+/// in a way that older readers would not understand
+const DUMP_VERSION: u32 = 1;
+
+/// A form of a datapack for tools to read. Everything is sorted so that the same
+/// pack always produces the same dump
+#[derive(Serialize, Deserialize)]
+struct DatapackDump {
+	version: u32,
+	meta: PackMeta,
+	layout: DirectoryLayout,
+	features: Vec<String>,
+	filter: Vec<PackFilter>,
+	/// The lines of every function
+	functions: BTreeMap<String, Vec<String>>,
+	/// The values of every function tag
+	function_tags: BTreeMap<String, Vec<String>>,
+	score_literals: Vec<i32>,
+	/// The names that functions were renamed to
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	function_mapping: BTreeMap<String, String>,
+	/// The files that are copied into the pack
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	auxiliary_files: BTreeMap<String, AuxiliaryFile>,
+}
+
+impl Datapack {
+	/// Writes the pack to JSON so that it can be post-processed by other tools
+	/// without outputting it to the filesystem
+	pub fn to_json(&self) -> anyhow::Result<String> {
+		let dump = DatapackDump {
+			version: DUMP_VERSION,
+			meta: self.meta.clone(),
+			layout: self.layout,
+			features: self.features.clone(),
+			filter: self.filter.clone(),
+			functions: self
+				.functions
+				.iter()
+				.map(|(id, func)| (id.to_string(), func.contents.clone()))
+				.collect(),
+			function_tags: self
+				.function_tags
+				.iter()
+				.map(|(id, tag)| (id.to_string(), tag.inner.values.clone()))
+				.collect(),
+			score_literals: self.score_literals.clone(),
+			function_mapping: self
+				.function_mapping
+				.iter()
+				.flat_map(|x| &x.0)
+				.map(|(l, r)| (l.to_string(), r.to_string()))
+				.collect(),
+			auxiliary_files: self.auxiliary_files.clone(),
+		};
+
+		serde_json::to_string_pretty(&dump).context("Failed to serialize datapack")
+	}
+
+	/// Reads a pack that was written with `to_json`
+	pub fn from_json(json: &str) -> anyhow::Result<Self> {
+		let dump: DatapackDump = serde_json::from_str(json).context("Failed to parse datapack")?;
+		if dump.version != DUMP_VERSION {
+			bail!(
+				"Datapack dump has version {}, but only version {DUMP_VERSION} is supported",
+				dump.version
+			);
+		}
+
+		Ok(Self {
+			functions: dump
+				.functions
+				.into_iter()
+				.map(|(id, contents)| (id.into(), Function { contents }))
+				.collect(),
+			function_tags: dump
+				.function_tags
+				.into_iter()
+				.map(|(id, values)| {
+					let tag = Tag {
+						inner: TagInner { values },
+					};
+					(id.into(), tag)
+				})
+				.collect(),
+			layout: dump.layout,
+			features: dump.features,
+			filter: dump.filter,
+			meta: dump.meta,
+			score_literals: dump.score_literals,
+			function_mapping: (!dump.function_mapping.is_empty()).then(|| {
+				let mapping = dump.function_mapping.into_iter();
+				FunctionMapping(mapping.map(|(l, r)| (l.into(), r.into())).collect())
+			}),
+			auxiliary_files: dump.auxiliary_files,
+			text_format: TextFormat::default(),
+			diagnostics: Vec::new(),
+			tick_budget: None,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::ResourceLocation;
+
+	#[test]
+	fn test_json_roundtrip() {
+		let mut pack = Datapack::new();
+		pack.functions.insert(
+			ResourceLocation::from("test:main"),
+			Function {
+				contents: vec!["say hi".into(), "function test:other".into()],
+			},
+		);
+		pack.function_tags.insert(
+			ResourceLocation::from("minecraft:load"),
+			Tag {
+				inner: TagInner {
+					values: vec!["test:main".into()],
+				},
+			},
+		);
+		pack.score_literals = vec![1, 5];
+
+		let json = pack.to_json().expect("Failed to write JSON");
+		let read = Datapack::from_json(&json).expect("Failed to read JSON");
+		assert_eq!(read.to_json().expect("Failed to write JSON"), json);
+		assert_eq!(
+			read.functions[&ResourceLocation::from("test:main")].contents,
+			vec!["say hi", "function test:other"]
+		);
+		assert_eq!(read.layout, pack.layout);
+
+		assert!(Datapack::from_json(&json.replace("\"version\": 1", "\"version\": 2")).is_err());
+	}
+}
@@ -1,18 +1,49 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
 
+use anyhow::bail;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
+use crate::common::diag::Diagnostic;
+use crate::common::mc::version::MinecraftVersion;
 use crate::common::ResourceLocation;
+use crate::output::strip::FunctionMapping;
+use crate::passes::analysis::budget::TickBudgetReport;
 
-use self::files::output_pack;
+use self::files::{output_pack, FUNCTION_MAPPING_FILE};
 
+mod bundle;
 mod files;
+mod json;
+pub mod requirements;
+pub mod stream;
 
 #[derive(Debug, Clone)]
 pub struct Datapack {
 	pub functions: FxHashMap<ResourceLocation, Function>,
 	pub function_tags: FxHashMap<ResourceLocation, Tag>,
+	pub layout: DirectoryLayout,
+	/// Feature flags enabled in the pack.mcmeta
+	pub features: Vec<String>,
+	/// Blocks in the filter section of the pack.mcmeta
+	pub filter: Vec<PackFilter>,
+	/// The pack section of the pack.mcmeta
+	pub meta: PackMeta,
+	/// The score literals that the functions in the pack use, in order
+	pub score_literals: Vec<i32>,
+	/// The names that functions were renamed to by stripping and exports,
+	/// which is written next to the pack for debugging
+	pub function_mapping: Option<FunctionMapping>,
+	/// Files like the pack icon and license that are copied into the root of the pack,
+	/// by their path in the pack
+	pub auxiliary_files: BTreeMap<String, AuxiliaryFile>,
+	/// How the text files of the pack are encoded when it is output
+	pub text_format: TextFormat,
+	/// Warnings that were found while compiling the pack
+	pub diagnostics: Vec<Diagnostic>,
+	/// The estimated commands per tick of the pack, if it was asked for
+	pub tick_budget: Option<TickBudgetReport>,
 }
 
 impl Datapack {
@@ -20,12 +51,43 @@ impl Datapack {
 		Self {
 			functions: FxHashMap::default(),
 			function_tags: FxHashMap::default(),
+			layout: DirectoryLayout::for_version(MinecraftVersion::LATEST),
+			features: Vec::new(),
+			filter: Vec::new(),
+			meta: PackMeta::new(MinecraftVersion::LATEST),
+			score_literals: Vec::new(),
+			function_mapping: None,
+			auxiliary_files: BTreeMap::new(),
+			text_format: TextFormat::default(),
+			diagnostics: Vec::new(),
+			tick_budget: None,
 		}
 	}
 
 	pub fn output(self, path: &Path) -> anyhow::Result<()> {
 		output_pack(self, path)
 	}
+
+	/// Adds a file that is written to a path relative to the root of the pack when
+	/// it is output. The path can't be outside of the pack, in the data directory,
+	/// or one of the files that the pack writes itself
+	pub fn add_file(&mut self, path: &str, file: AuxiliaryFile) -> anyhow::Result<()> {
+		let components: Vec<_> = Path::new(path).components().collect();
+		if components.is_empty() || !components.iter().all(|x| matches!(x, Component::Normal(..))) {
+			bail!("Auxiliary file path {path} is not a relative path inside of the pack");
+		}
+		if components[0].as_os_str() == "data"
+			|| path == "pack.mcmeta"
+			|| path == FUNCTION_MAPPING_FILE
+		{
+			bail!("Auxiliary file path {path} is reserved for generated files");
+		}
+		if self.auxiliary_files.insert(path.into(), file).is_some() {
+			bail!("Auxiliary file {path} is added more than once");
+		}
+
+		Ok(())
+	}
 }
 
 impl Default for Datapack {
@@ -34,6 +96,114 @@ impl Default for Datapack {
 	}
 }
 
+/// Naming scheme for the resource directories in a pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectoryLayout {
+	/// Plural directory names like `functions` and `tags/functions`
+	Plural,
+	/// Singular directory names like `function` and `tags/function`,
+	/// which are required starting in 1.21
+	Singular,
+}
+
+impl DirectoryLayout {
+	/// Gets the layout that a version of the game expects
+	pub fn for_version(version: MinecraftVersion) -> Self {
+		if version >= MinecraftVersion::new(21, 0) {
+			Self::Singular
+		} else {
+			Self::Plural
+		}
+	}
+
+	/// Gets the name of the functions directory
+	pub fn functions_dir(&self) -> &'static str {
+		match self {
+			Self::Plural => "functions",
+			Self::Singular => "function",
+		}
+	}
+
+	/// Gets the name of the function tags directory
+	pub fn function_tags_dir(&self) -> &'static str {
+		match self {
+			Self::Plural => "tags/functions",
+			Self::Singular => "tags/function",
+		}
+	}
+}
+
+/// How the text files in a pack, like functions and tags, are written. Some server
+/// hosts and diff tools are picky about line endings and byte order marks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextFormat {
+	/// The line ending that is put between lines
+	pub line_ending: LineEnding,
+	/// Whether files end with a line ending after their last line
+	pub trailing_newline: bool,
+	/// Whether files start with a UTF-8 byte order mark. When this is off, any
+	/// byte order mark at the start of the text is removed instead
+	pub bom: bool,
+}
+
+/// A line ending in a text file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+	/// A line feed, like on Unix
+	#[default]
+	Lf,
+	/// A carriage return and a line feed, like on Windows
+	Crlf,
+}
+
+impl LineEnding {
+	/// Gets the characters of the line ending
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Lf => "\n",
+			Self::Crlf => "\r\n",
+		}
+	}
+}
+
+/// A file that is copied into the pack along with the generated ones
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuxiliaryFile {
+	/// A file on disk, which is read when the pack is output
+	Path(PathBuf),
+	/// The contents of the file
+	Contents(Vec<u8>),
+}
+
+/// The pack section of the pack.mcmeta, which the game needs to load the pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackMeta {
+	pub pack_format: u32,
+	pub description: String,
+}
+
+impl PackMeta {
+	/// Creates metadata for a pack that targets a version of the game
+	pub fn new(version: MinecraftVersion) -> Self {
+		Self {
+			pack_format: version.get_pack_format(),
+			description: String::new(),
+		}
+	}
+}
+
+/// A pattern in the filter section of the pack.mcmeta. Each part is a
+/// regular expression, and a missing part matches everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackFilter {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub namespace: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub path: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Function {
 	pub contents: Vec<String>,
@@ -0,0 +1,191 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+
+use crate::common::mc::version::MinecraftVersion;
+
+use super::Datapack;
+
+/// The vanilla features that a pack uses, for showing what versions of
+/// the game it is compatible with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackRequirements {
+	/// The root commands that the pack runs, sorted
+	pub commands: Vec<String>,
+	/// The gamerules that the pack reads or changes, sorted
+	pub gamerules: Vec<String>,
+	/// The experimental feature flags that the pack enables
+	pub features: Vec<String>,
+	/// The oldest version of the game that has every command and
+	/// syntax used by the pack
+	pub min_version: MinecraftVersion,
+}
+
+impl Display for PackRequirements {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Minimum version: {}", self.min_version)?;
+		writeln!(f, "Commands: {}", self.commands.join(", "))?;
+		if !self.gamerules.is_empty() {
+			writeln!(f, "Gamerules: {}", self.gamerules.join(", "))?;
+		}
+		if !self.features.is_empty() {
+			writeln!(f, "Features: {}", self.features.join(", "))?;
+		}
+
+		Ok(())
+	}
+}
+
+/// The first version that functions can be loaded from datapacks in
+const MIN_DATAPACK_VERSION: MinecraftVersion = MinecraftVersion::new(13, 0);
+
+impl Datapack {
+	/// Finds the commands, gamerules, and features that the functions in the pack use
+	pub fn requirements(&self) -> PackRequirements {
+		let mut cx = RequirementsCx {
+			commands: Vec::new(),
+			gamerules: Vec::new(),
+			min_version: MIN_DATAPACK_VERSION,
+		};
+		for func in self.functions.values() {
+			for line in &func.contents {
+				let line = line.trim();
+				if line.is_empty() || line.starts_with('#') {
+					continue;
+				}
+				let line = if let Some(line) = line.strip_prefix('$') {
+					cx.require(MinecraftVersion::new(20, 2));
+					line
+				} else {
+					line
+				};
+				let words: Vec<_> = line.split_whitespace().collect();
+				cx.add_command(&words);
+			}
+		}
+
+		PackRequirements {
+			commands: cx.commands.into_iter().sorted().dedup().collect(),
+			gamerules: cx.gamerules.into_iter().sorted().dedup().collect(),
+			features: self.features.iter().cloned().sorted().dedup().collect(),
+			min_version: cx.min_version,
+		}
+	}
+}
+
+struct RequirementsCx {
+	commands: Vec<String>,
+	gamerules: Vec<String>,
+	min_version: MinecraftVersion,
+}
+
+impl RequirementsCx {
+	fn require(&mut self, version: MinecraftVersion) {
+		self.min_version = self.min_version.max(version);
+	}
+
+	fn add_command(&mut self, words: &[&str]) {
+		let Some(command) = words.first() else {
+			return;
+		};
+		self.commands.push(command.to_string());
+		if let Some(version) = get_command_version(words) {
+			self.require(version);
+		}
+		match *command {
+			"gamerule" => {
+				if let Some(rule) = words.get(1) {
+					self.gamerules.push(rule.to_string());
+				}
+			}
+			"execute" => {
+				for (i, pair) in words.windows(2).enumerate() {
+					if let Some(version) = get_subcommand_version(pair[0], pair[1]) {
+						self.require(version);
+					}
+					if pair[1] == "run" {
+						self.add_command(&words[i + 2..]);
+						break;
+					}
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Gets the version that a command or a form of it was added in, if
+/// it is newer than the first version with datapacks
+fn get_command_version(words: &[&str]) -> Option<MinecraftVersion> {
+	let arg = |i: usize| words.get(i).copied().unwrap_or_default();
+	let (minor, patch) = match *words.first()? {
+		"return" if arg(1) == "fail" => (20, 3),
+		"return" if arg(1) == "run" => (20, 2),
+		"return" => (20, 0),
+		"function" if arg(2) == "with" => (20, 2),
+		"scoreboard" if arg(1) == "objectives" && arg(3) == "numberformat" => (20, 3),
+		"scoreboard" if arg(1) == "players" && arg(2) == "display" => (20, 3),
+		"random" => (20, 2),
+		"tick" => (20, 3),
+		"transfer" => (20, 5),
+		"damage" | "ride" => (19, 4),
+		"fillbiome" => (19, 3),
+		"place" => (19, 0),
+		"item" => (17, 0),
+		"attribute" => (16, 0),
+		_ => return None,
+	};
+	Some(MinecraftVersion::new(minor, patch))
+}
+
+/// Gets the version that an execute subcommand was added in, given
+/// the word before it
+fn get_subcommand_version(prev: &str, word: &str) -> Option<MinecraftVersion> {
+	let (minor, patch) = match (prev, word) {
+		("if" | "unless", "items") => (20, 5),
+		("if" | "unless", "function") => (20, 3),
+		("if" | "unless", "loaded" | "dimension" | "biome") => (19, 4),
+		("positioned", "over") => (19, 4),
+		(_, "on" | "summon") => (19, 4),
+		_ => return None,
+	};
+	Some(MinecraftVersion::new(minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::common::ResourceLocation;
+	use crate::output::datapack::Function;
+
+	#[test]
+	fn test_requirements() {
+		let mut pack = Datapack::new();
+		pack.functions.insert(
+			ResourceLocation::from("test:main"),
+			Function {
+				contents: vec![
+					"# comment".into(),
+					"gamerule doDaylightCycle false".into(),
+					"execute as @a on vehicle run say hi".into(),
+					"say hello".into(),
+				],
+			},
+		);
+		let req = pack.requirements();
+		assert_eq!(req.commands, vec!["execute", "gamerule", "say"]);
+		assert_eq!(req.gamerules, vec!["doDaylightCycle"]);
+		assert_eq!(req.min_version, MinecraftVersion::new(19, 4));
+
+		pack.functions.insert(
+			ResourceLocation::from("test:other"),
+			Function {
+				contents: vec!["$return run function $(name)".into()],
+			},
+		);
+		assert_eq!(
+			pack.requirements().min_version,
+			MinecraftVersion::new(20, 2)
+		);
+	}
+}
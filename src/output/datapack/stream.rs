@@ -0,0 +1,170 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::common::diag::Diagnostic;
+use crate::common::ResourceLocation;
+use crate::passes::analysis::budget::TickBudgetReport;
+
+use super::files::{
+	clear_data_dir, write_auxiliary_files, write_function, write_function_mapping,
+	write_function_tag, write_pack_meta,
+};
+use super::{Datapack, DirectoryLayout, Function, TextFormat};
+
+/// Writes the functions of a pack to disk in batches as they are generated,
+/// so that the whole pack never has to be held in memory at once
+pub struct PackWriter {
+	path: PathBuf,
+	data_path: PathBuf,
+	layout: DirectoryLayout,
+	text_format: TextFormat,
+	batch_size: usize,
+	pending: Vec<(ResourceLocation, Function)>,
+	report: StreamReport,
+}
+
+impl PackWriter {
+	/// Creates a new writer for the pack at a path. Will remove all existing files in
+	/// the /data directory of that folder
+	pub fn new(
+		path: &Path,
+		layout: DirectoryLayout,
+		text_format: TextFormat,
+		batch_size: usize,
+	) -> anyhow::Result<Self> {
+		let data_path = clear_data_dir(path)?;
+		let batch_size = batch_size.max(1);
+		Ok(Self {
+			path: path.to_owned(),
+			data_path,
+			layout,
+			text_format,
+			batch_size,
+			pending: Vec::with_capacity(batch_size),
+			report: StreamReport::default(),
+		})
+	}
+
+	/// Adds a function to the current batch, writing the batch if it is full
+	pub fn add_function(&mut self, id: ResourceLocation, function: Function) -> anyhow::Result<()> {
+		self.pending.push((id, function));
+		if self.pending.len() >= self.batch_size {
+			self.flush()?;
+		}
+
+		Ok(())
+	}
+
+	/// Writes all of the functions in the current batch
+	pub fn flush(&mut self) -> anyhow::Result<()> {
+		if self.pending.is_empty() {
+			return Ok(());
+		}
+		for (id, function) in self.pending.drain(..) {
+			write_function(
+				&self.data_path,
+				&id,
+				&function,
+				self.layout,
+				self.text_format,
+			)?;
+			self.report.functions += 1;
+		}
+		self.report.batches += 1;
+		self.report.update_peak_memory();
+
+		Ok(())
+	}
+
+	/// Writes the rest of the pack, including the functions, tags, and metadata in the
+	/// given pack, and returns a report of what was written
+	pub fn finish(mut self, mut pack: Datapack) -> anyhow::Result<StreamReport> {
+		for (id, function) in std::mem::take(&mut pack.functions) {
+			self.add_function(id, function)?;
+		}
+		self.flush()?;
+		for (id, tag) in &pack.function_tags {
+			write_function_tag(&self.data_path, id, tag, self.layout, self.text_format)?;
+		}
+		write_pack_meta(&pack, &self.path).context("Failed to write pack.mcmeta")?;
+		write_function_mapping(&pack, &self.path).context("Failed to write function mapping")?;
+		write_auxiliary_files(&pack, &self.path)?;
+
+		Ok(self.report)
+	}
+}
+
+/// Statistics about a pack that was written in batches
+#[derive(Debug, Clone, Default)]
+pub struct StreamReport {
+	/// The number of functions that were written
+	pub functions: usize,
+	/// The number of batches that the functions were written in
+	pub batches: usize,
+	/// The highest resident memory of the process seen after writing a batch,
+	/// in bytes. Only available on platforms that report it
+	pub peak_memory: Option<u64>,
+	/// Warnings that were found while compiling the pack
+	pub diagnostics: Vec<Diagnostic>,
+	/// The estimated commands per tick of the pack, if it was asked for
+	pub tick_budget: Option<TickBudgetReport>,
+}
+
+impl StreamReport {
+	fn update_peak_memory(&mut self) {
+		if let Some(current) = get_peak_memory() {
+			self.peak_memory = Some(self.peak_memory.unwrap_or_default().max(current));
+		}
+	}
+}
+
+impl Display for StreamReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Wrote {} functions in {} batches",
+			self.functions, self.batches
+		)?;
+		if let Some(peak) = self.peak_memory {
+			write!(
+				f,
+				", peak memory {:.1} MiB",
+				peak as f64 / (1024.0 * 1024.0)
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Gets the peak resident memory of the process in bytes
+pub fn get_peak_memory() -> Option<u64> {
+	let status = std::fs::read_to_string("/proc/self/status").ok()?;
+	parse_peak_memory(&status)
+}
+
+fn parse_peak_memory(status: &str) -> Option<u64> {
+	let line = status.lines().find(|x| x.starts_with("VmHWM:"))?;
+	let kib: u64 = line
+		.trim_start_matches("VmHWM:")
+		.trim()
+		.trim_end_matches("kB")
+		.trim()
+		.parse()
+		.ok()?;
+	Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_peak_memory() {
+		let status = "Name:\tdpc\nVmPeak:\t  20000 kB\nVmHWM:\t    1024 kB\n";
+		assert_eq!(parse_peak_memory(status), Some(1024 * 1024));
+		assert_eq!(parse_peak_memory("Name:\tdpc\n"), None);
+	}
+}
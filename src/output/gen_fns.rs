@@ -1,14 +1,38 @@
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
 
+use crate::common::mc::text::{TextComponent, TextComponentContents, TextStyle};
 use crate::common::ResourceLocation;
+use crate::project::ProjectSettings;
 
 use super::codegen::{CodegenCx, CodegenRequirement};
 use super::datapack::{Function, Tag, TagInner};
-use super::text::{format_lit_fake_player, LIT_OBJECTIVE, REG_OBJECTIVE};
+use super::text::{
+	format_lit_fake_player, get_fake_player_sigil, REG_OBJECTIVE, REG_STORAGE_LOCATION,
+};
+
+/// A function annotated as a test
+pub struct TestFunction {
+	/// The original ID of the function
+	pub id: ResourceLocation,
+	/// The ID of the function in the output pack, which may be stripped
+	pub output_id: ResourceLocation,
+}
+
+/// A function that is registered to a tag generated by the compiler
+#[derive(Clone)]
+pub struct TagHandler {
+	/// The ID of the function in the output pack
+	pub id: ResourceLocation,
+	/// Where the function is called relative to the other handlers
+	pub order: i32,
+}
 
 pub fn gen_fns(
 	ccx: &CodegenCx,
+	tests: &[TestFunction],
+	tick_fns: &[TagHandler],
+	load_fns: &[TagHandler],
 ) -> anyhow::Result<(
 	FxHashMap<ResourceLocation, Function>,
 	FxHashMap<ResourceLocation, Tag>,
@@ -16,24 +40,164 @@ pub fn gen_fns(
 	let mut fns = FxHashMap::default();
 	let mut tags = FxHashMap::default();
 
+	// The init function has to run before any of the load handlers,
+	// since they might use the objectives that it creates
 	let init_fn = gen_init(ccx);
-	if let Some(init_fn) = init_fn {
-		let loc = if ccx.project.name == "dpc" {
-			"dpc:init".to_string()
-		} else {
-			ccx.project.name.clone() + ":dpc_init"
-		};
+	let init_loc = if let Some(init_fn) = init_fn {
+		let loc = get_generated_fn_id(ccx.project, "init");
 		fns.insert(ResourceLocation::from(loc.clone()), init_fn);
+		Some(loc)
+	} else {
+		None
+	};
+	let init_loc = init_loc.filter(|_| ccx.project.load_init);
 
-		let init_tag = Tag {
-			inner: TagInner { values: vec![loc] },
-		};
-		tags.insert(ResourceLocation::from("minecraft:load"), init_tag);
+	if init_loc.is_some() || !load_fns.is_empty() {
+		add_tag_handlers(
+			ccx,
+			"minecraft:load",
+			"load",
+			init_loc,
+			load_fns,
+			&mut fns,
+			&mut tags,
+		);
+	}
+	if !tick_fns.is_empty() {
+		add_tag_handlers(
+			ccx,
+			"minecraft:tick",
+			"tick",
+			None,
+			tick_fns,
+			&mut fns,
+			&mut tags,
+		);
+	}
+
+	if !tests.is_empty() {
+		let mut run_all = Function::new();
+		for test in tests.iter().sorted_by_key(|x| &x.id) {
+			let loc = get_generated_fn_id(ccx.project, &format!("test/{}", test.id.replace(':', "/")));
+			run_all.contents.push(format!("function {loc}"));
+			fns.insert(ResourceLocation::from(loc), gen_test_runner(ccx, test));
+		}
+		fns.insert(
+			ResourceLocation::from(get_generated_fn_id(ccx.project, "run_tests")),
+			run_all,
+		);
 	}
 
 	Ok((fns, tags))
 }
 
+/// Registers functions to a tag. Since the game doesn't guarantee the order
+/// of the entries in a tag, multiple handlers are called from a generated
+/// dispatcher function in order instead. The first function, if there
+/// is one, is always called before the rest of the handlers
+fn add_tag_handlers(
+	ccx: &CodegenCx,
+	tag: &str,
+	name: &str,
+	first: Option<String>,
+	handlers: &[TagHandler],
+	fns: &mut FxHashMap<ResourceLocation, Function>,
+	tags: &mut FxHashMap<ResourceLocation, Tag>,
+) {
+	let handlers: Vec<_> = first
+		.into_iter()
+		.chain(
+			handlers
+				.iter()
+				.sorted_by(|a, b| a.order.cmp(&b.order).then_with(|| a.id.cmp(&b.id)))
+				.map(|x| x.id.to_string()),
+		)
+		.collect();
+	let values = if handlers.len() == 1 {
+		handlers
+	} else {
+		let loc = get_generated_fn_id(ccx.project, name);
+		let mut dispatcher = Function::new();
+		dispatcher.contents = handlers.iter().map(|x| format!("function {x}")).collect();
+		fns.insert(ResourceLocation::from(loc.clone()), dispatcher);
+		vec![loc]
+	};
+
+	tags.insert(
+		ResourceLocation::from(tag),
+		Tag {
+			inner: TagInner { values },
+		},
+	);
+}
+
+/// Gets the ID for a function generated by the compiler
+pub fn get_generated_fn_id(project: &ProjectSettings, name: &str) -> String {
+	if let Some(namespace) = &project.internal_namespace {
+		format!("{namespace}:{}/{name}", project.name)
+	} else if project.name == "dpc" {
+		format!("dpc:{name}")
+	} else {
+		format!("{}:dpc_{name}", project.name)
+	}
+}
+
+/// Generates the runner for a test function, which resets the registers, calls the test,
+/// and reports whether it failed or not
+fn gen_test_runner(ccx: &CodegenCx, test: &TestFunction) -> Function {
+	let mut out = Function::new();
+	let sigil = get_fake_player_sigil(ccx.project.hidden_fake_players);
+	let result = format!("{sigil}test {REG_OBJECTIVE}");
+
+	out.contents.push(format!("scoreboard players reset * {REG_OBJECTIVE}"));
+	// Tests that don't return anything will leave the result untouched
+	// and pass, while tests that return fail will store a zero
+	out.contents.push(format!("scoreboard players set {result} 1"));
+	out.contents.push(format!(
+		"execute store success score {result} run function {}",
+		test.output_id
+	));
+
+	let report = |status: &str, color: &str| {
+		let style = TextStyle {
+			color: Some(color.into()),
+			..Default::default()
+		};
+		let text = TextComponentContents::Text(format!("[{status}] {}", test.id));
+		TextComponent::with_style(text, style)
+			.get_json_str()
+			.expect("Plain text does not need to be resolved")
+	};
+	out.contents.push(format!(
+		"execute if score {result} matches 1 run tellraw @a {}",
+		report("PASS", "green")
+	));
+	out.contents.push(format!(
+		"execute unless score {result} matches 1 run tellraw @a {}",
+		report("FAIL", "red")
+	));
+
+	out
+}
+
+/// Generates the commands that create the literal objective and set
+/// the fake players for score literals
+pub fn gen_literals(project: &ProjectSettings, literals: impl Iterator<Item = i32>) -> Vec<String> {
+	let objective = project.get_literal_objective();
+	let mut out = vec![format!("scoreboard objectives add {objective} dummy")];
+	if project.migrate_fake_players {
+		out.push(format!("scoreboard players reset * {objective}"));
+	}
+	for lit in literals.sorted().dedup() {
+		out.push(format!(
+			"scoreboard players set {} {objective} {lit}",
+			format_lit_fake_player(lit, project.hidden_fake_players),
+		));
+	}
+
+	out
+}
+
 fn gen_init(ccx: &CodegenCx) -> Option<Function> {
 	let mut out = Function::new();
 	let mut function_needed = false;
@@ -45,20 +209,50 @@ fn gen_init(ccx: &CodegenCx) -> Option<Function> {
 	{
 		let cmd = format!("scoreboard objectives add {REG_OBJECTIVE} dummy");
 		out.contents.push(cmd);
+		// Clears out the visible fake players from older versions of the pack
+		if ccx.project.migrate_fake_players {
+			out.contents
+				.push(format!("scoreboard players reset * {REG_OBJECTIVE}"));
+		}
 		function_needed = true;
 	}
-	if !ccx.score_literals.is_empty() {
-		let cmd = format!("scoreboard objectives add {LIT_OBJECTIVE} dummy");
+	for (objective, criterion) in ccx.objectives.iter().sorted_by_key(|x| x.0) {
+		let cmd = format!("scoreboard objectives add {objective} {criterion:?}");
 		out.contents.push(cmd);
 		function_needed = true;
 	}
+	if ccx.project.create_objectives {
+		for objective in get_user_objectives(ccx) {
+			let cmd = format!("scoreboard objectives add {objective} dummy");
+			out.contents.push(cmd);
+			function_needed = true;
+		}
+	}
+	if !ccx.score_literals.is_empty() {
+		// A shared pool creates the literals for every pack that uses it
+		if let Some(pool) = &ccx.project.literal_pool {
+			out.contents.push(format!("function {pool}"));
+		} else {
+			out.contents.extend(gen_literals(
+				ccx.project,
+				ccx.score_literals.iter().copied(),
+			));
+		}
+		function_needed = true;
+	}
+
+	// Transient storages are cleared before anything else is put in them
+	for (storage, keys) in ccx.transient_keys.iter().sorted_by_key(|x| x.0) {
+		for key in keys.iter().sorted() {
+			out.contents.push(format!("data remove storage {storage} {key}"));
+			function_needed = true;
+		}
+	}
 
-	for lit in ccx.score_literals.iter().sorted() {
+	for (entry, val) in ccx.storage_consts.iter().sorted_by_key(|x| x.0) {
 		let cmd = format!(
-			"scoreboard players set {} {} {}",
-			format_lit_fake_player(*lit),
-			LIT_OBJECTIVE,
-			*lit,
+			"data modify storage {REG_STORAGE_LOCATION} {entry} set value {}",
+			val.get_literal_str()
 		);
 		out.contents.push(cmd);
 		function_needed = true;
@@ -70,3 +264,21 @@ fn gen_init(ccx: &CodegenCx) -> Option<Function> {
 		None
 	}
 }
+
+/// Gets the objectives that scores in the pack use that aren't created by the
+/// compiler or by the pack itself
+fn get_user_objectives<'ccx>(ccx: &'ccx CodegenCx) -> impl Iterator<Item = &'ccx str> {
+	let literal_objective = ccx.project.get_literal_objective();
+	ccx.used_objectives
+		.iter()
+		.map(|x| x.as_ref())
+		.filter(move |x| {
+			*x != REG_OBJECTIVE
+				&& *x != literal_objective
+				&& !ccx.objectives.contains_key(*x)
+				&& !ccx.added_objectives.contains(*x)
+				// Objectives that are macro arguments aren't known until the function runs
+				&& !x.contains("$(")
+		})
+		.sorted()
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::common::mc::modifier::Modifier;
+use crate::lir::{LIRFunction, LIRInstrKind, LIRInstruction, LIR};
+
+/// Lets users of the library add their own instructions to the start and end of
+/// every function in the pack before codegen, for things like telemetry or permission
+/// checks. Functions that are generated as the bodies of other functions are not hooked
+pub trait FunctionHook: Send + Sync {
+	/// Gets the instructions to run at the start of a function
+	fn on_entry(&self, func: &LIRFunction) -> Vec<LIRInstruction> {
+		let _ = func;
+		Vec::new()
+	}
+
+	/// Gets the instructions to run at the end of a function. They are also run
+	/// before every return in the function, with the conditions of the return
+	fn on_exit(&self, func: &LIRFunction) -> Vec<LIRInstruction> {
+		let _ = func;
+		Vec::new()
+	}
+}
+
+/// Runs the hooks on every function in the LIR
+pub fn apply_function_hooks(lir: &mut LIR, hooks: &[Arc<dyn FunctionHook>]) {
+	if hooks.is_empty() {
+		return;
+	}
+
+	for func in lir.functions.values_mut() {
+		if func.parent.is_some() {
+			continue;
+		}
+		let entry: Vec<_> = hooks.iter().flat_map(|x| x.on_entry(func)).collect();
+		let exit: Vec<_> = hooks.iter().flat_map(|x| x.on_exit(func)).collect();
+
+		let mut contents = entry;
+		for instr in std::mem::take(&mut func.block.contents) {
+			if !exit.is_empty() && is_return(&instr.kind) {
+				// Conditional returns only run the exit instructions when they return
+				let modifiers: Vec<_> = instr
+					.modifiers
+					.iter()
+					.filter(|x| {
+						!matches!(x, Modifier::StoreResult(..) | Modifier::StoreSuccess(..))
+					})
+					.cloned()
+					.collect();
+				contents.extend(exit.iter().cloned().map(|mut x| {
+					let mut all = modifiers.clone();
+					all.append(&mut x.modifiers);
+					x.modifiers = all;
+					x
+				}));
+			}
+			contents.push(instr);
+		}
+		if !contents
+			.last()
+			.is_some_and(|x| is_return(&x.kind) && x.modifiers.is_empty())
+		{
+			contents.extend(exit);
+		}
+		func.block.contents = contents;
+	}
+}
+
+fn is_return(kind: &LIRInstrKind) -> bool {
+	matches!(
+		kind,
+		LIRInstrKind::ReturnValue(..) | LIRInstrKind::ReturnFail | LIRInstrKind::ReturnRun(..)
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::function::FunctionInterface;
+	use crate::common::mc::modifier::IfModCondition;
+	use crate::common::mc::EntityTarget;
+	use crate::common::{RegisterList, ResourceLocation};
+	use crate::lir::LIRBlock;
+
+	struct TestHook;
+
+	impl FunctionHook for TestHook {
+		fn on_entry(&self, _: &LIRFunction) -> Vec<LIRInstruction> {
+			vec![LIRInstruction::new(LIRInstrKind::Comment("enter".into()))]
+		}
+
+		fn on_exit(&self, _: &LIRFunction) -> Vec<LIRInstruction> {
+			vec![LIRInstruction::new(LIRInstrKind::Comment("exit".into()))]
+		}
+	}
+
+	#[test]
+	fn test_function_hooks() {
+		let mut block = LIRBlock::new(RegisterList::default());
+		let condition = Modifier::If {
+			condition: Box::new(IfModCondition::Entity(EntityTarget::Player("foo".into()))),
+			negate: false,
+		};
+		block.contents = vec![
+			LIRInstruction::with_modifiers(LIRInstrKind::ReturnValue(1), vec![condition]),
+			LIRInstruction::new(LIRInstrKind::NoOp),
+		];
+		let mut lir = LIR::new();
+		lir.functions.insert(
+			"foo:bar".into(),
+			LIRFunction {
+				interface: FunctionInterface::new("foo:bar".into()),
+				block,
+				parent: None,
+			},
+		);
+
+		apply_function_hooks(&mut lir, &[Arc::new(TestHook)]);
+		let contents = &lir.functions[&ResourceLocation::from("foo:bar")].block.contents;
+		let debug: Vec<_> = contents.iter().map(|x| format!("{:?}", x.kind)).collect();
+		assert_eq!(
+			debug,
+			vec!["cmt enter", "cmt exit", "retv 1", "no", "cmt exit"]
+		);
+		assert_eq!(contents[1].modifiers.len(), 1);
+	}
+}
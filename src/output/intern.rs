@@ -0,0 +1,79 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::common::Identifier;
+
+use super::text::format_lit_fake_player;
+
+/// Deduplicates the holder and objective names created during codegen, so that
+/// every use of the same name shares a single allocation
+#[derive(Debug, Default)]
+pub struct Interner {
+	strings: FxHashSet<Identifier>,
+	/// Fake players for score literals, which are used often enough
+	/// that formatting them every time shows up in profiles
+	lit_players: FxHashMap<i32, Identifier>,
+}
+
+impl Interner {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Gets the shared copy of a string, adding it if it doesn't exist yet
+	pub fn intern(&mut self, string: &str) -> Identifier {
+		if let Some(existing) = self.strings.get(string) {
+			return existing.clone();
+		}
+		let out: Identifier = string.into();
+		self.strings.insert(out.clone());
+		out
+	}
+
+	/// Same as intern, but avoids copying an owned string if it is new
+	pub fn intern_owned(&mut self, string: String) -> Identifier {
+		if let Some(existing) = self.strings.get(string.as_str()) {
+			return existing.clone();
+		}
+		let out: Identifier = string.into();
+		self.strings.insert(out.clone());
+		out
+	}
+
+	/// Gets the fake player for a score literal
+	pub fn lit_fake_player(&mut self, num: i32, hidden: bool) -> Identifier {
+		if let Some(existing) = self.lit_players.get(&num) {
+			return existing.clone();
+		}
+		let out = self.intern_owned(format_lit_fake_player(num, hidden));
+		self.lit_players.insert(num, out.clone());
+		out
+	}
+
+	/// Gets the number of unique strings that have been interned
+	pub fn len(&self) -> usize {
+		self.strings.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.strings.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use super::*;
+
+	#[test]
+	fn test_interning() {
+		let mut interner = Interner::new();
+		let first = interner.intern("_r");
+		let second = interner.intern_owned(String::from("_r"));
+		assert!(Arc::ptr_eq(&first, &second));
+		let lit = interner.lit_fake_player(5, true);
+		assert_eq!(lit.as_ref(), "#l5");
+		assert!(Arc::ptr_eq(&lit, &interner.lit_fake_player(5, true)));
+		assert_eq!(interner.len(), 2);
+	}
+}
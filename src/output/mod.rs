@@ -1,28 +1,79 @@
 pub mod codegen;
 pub mod datapack;
 mod gen_fns;
+pub mod hook;
+pub mod intern;
 pub mod ra;
 pub mod strip;
 pub mod text;
 
+use crate::common::ResourceLocation;
 use crate::lir::{LIRFunction, LIR};
 use crate::lower::cleanup_fn_id;
 use crate::project::ProjectSettings;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use itertools::Itertools;
 
-use self::codegen::{codegen_block, CodegenCx};
-use self::datapack::{Datapack, Function};
+use self::codegen::{codegen_block, CodegenCx, CodegenRequirement};
+use self::datapack::stream::PackWriter;
+use self::datapack::{Datapack, Function, PackMeta};
+use self::intern::Interner;
 use self::ra::alloc_registers;
 
+pub use self::gen_fns::{gen_literals, get_generated_fn_id};
+
 pub fn link(lir: LIR, project: &ProjectSettings) -> anyhow::Result<Datapack> {
+	let mut functions = Vec::new();
+	let mut out = link_with(lir, project, &mut |id, func| {
+		functions.push((id, func));
+		Ok(())
+	})?;
+	out.functions.extend(functions);
+
+	Ok(out)
+}
+
+/// Links a datapack, writing every function generated from the LIR to a writer
+/// as soon as it is finished. The returned pack contains the extra generated
+/// functions and the tags, which can only be made after everything else
+pub fn link_streaming(
+	lir: LIR,
+	project: &ProjectSettings,
+	writer: &mut PackWriter,
+) -> anyhow::Result<Datapack> {
+	link_with(lir, project, &mut |id, func| writer.add_function(id, func))
+}
+
+fn link_with(
+	lir: LIR,
+	project: &ProjectSettings,
+	sink: &mut dyn FnMut(ResourceLocation, Function) -> anyhow::Result<()>,
+) -> anyhow::Result<Datapack> {
 	let mut out = Datapack::new();
+	out.layout = project.get_directory_layout();
+	out.features = project.features.clone();
+	out.filter = project.filter.clone();
+	out.text_format = project.text_format;
+	out.meta = PackMeta::new(project.target_version);
+	out.meta.description = project.get_description().to_string();
+	for (path, file) in &project.auxiliary_files {
+		out.add_file(path, file.clone())?;
+	}
 
 	// Strip the LIR
-	let mapping = self::strip::strip(&lir, project);
+	let mapping = self::strip::strip(&lir, project).context("Failed to strip functions")?;
 
-	let ra = alloc_registers(&lir, &mapping, project)?;
-	let mut ccx = CodegenCx::new(project, mapping, ra);
+	// The interner is shared between register allocation and codegen
+	let mut interner = Interner::new();
+	let ra = alloc_registers(&lir, &mapping, project, &mut interner)?;
+	let mut ccx = CodegenCx::new(project, mapping, ra, interner);
+	ccx.objectives = lir.objectives;
+	ccx.storage_consts = lir.storage_consts;
+	ccx.storages = lir.storages;
+	let mut tests = Vec::new();
+	let mut tick_fns = Vec::new();
+	let mut load_fns = Vec::new();
 	for (func_id, func) in lir.functions {
 		let mut func_id = func_id.clone();
 		if let Some(mapping) = &ccx.func_mapping {
@@ -33,12 +84,62 @@ pub fn link(lir: LIR, project: &ProjectSettings) -> anyhow::Result<Datapack> {
 		let fun = codegen_fn(&func_id, &func, &mut ccx)
 			.with_context(|| format!("In function {:?}", func.interface))?;
 
-		out.functions.insert(func_id, fun);
+		if func.interface.annotations.test {
+			if !func.interface.sig.params.is_empty() {
+				bail!("Test function {} cannot have parameters", func.interface.id);
+			}
+			tests.push(gen_fns::TestFunction {
+				id: func.interface.id.clone(),
+				output_id: func_id.clone(),
+			});
+			// The runners reset the register objective, so make sure it exists
+			ccx.add_requirement(CodegenRequirement::UseRegObjective);
+		}
+		let annotations = &func.interface.annotations;
+		if annotations.tick || annotations.load {
+			if !func.interface.sig.params.is_empty() {
+				bail!(
+					"Function {} is run by a tag and cannot have parameters",
+					func.interface.id
+				);
+			}
+			let handler = gen_fns::TagHandler {
+				id: func_id.clone(),
+				order: annotations.order.unwrap_or_default(),
+			};
+			if annotations.load {
+				load_fns.push(handler.clone());
+			}
+			if annotations.tick {
+				tick_fns.push(handler);
+			}
+		}
+
+		sink(func_id, fun)?;
+	}
+
+	// Snippets are written to the pack as-is
+	for snippet in &project.snippets {
+		let handler = gen_fns::TagHandler {
+			id: snippet.id.clone(),
+			order: 0,
+		};
+		if snippet.load {
+			load_fns.push(handler.clone());
+		}
+		if snippet.tick {
+			tick_fns.push(handler);
+		}
+		let mut fun = Function::new();
+		fun.contents = snippet.contents.clone();
+		sink(snippet.id.clone(), fun)?;
 	}
 
-	let (extra_fns, extra_tags) = gen_fns::gen_fns(&ccx)?;
+	let (extra_fns, extra_tags) = gen_fns::gen_fns(&ccx, &tests, &tick_fns, &load_fns)?;
+	out.score_literals = ccx.score_literals.iter().copied().sorted().collect();
 	out.functions.extend(extra_fns);
 	out.function_tags.extend(extra_tags);
+	out.function_mapping = ccx.func_mapping;
 
 	Ok(out)
 }
@@ -1,4 +1,5 @@
 use anyhow::{anyhow, bail};
+use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::common::mc::modifier::{IfModCondition, Modifier};
@@ -7,13 +8,20 @@ use crate::common::{Identifier, ResourceLocation};
 use crate::lir::{LIRBlock, LIRInstrKind, LIRInstruction, LIR};
 use crate::project::{OptimizationLevel, ProjectSettings};
 
+use super::intern::Interner;
 use super::strip::FunctionMapping;
-use super::text::{format_local_storage_entry, format_reg_fake_player};
+use super::text::{
+	format_local_storage_entry, format_reg_fake_player, format_shared_local_storage_entry,
+	format_shared_reg_fake_player,
+};
 
 #[derive(Debug)]
 pub struct RegAllocCx {
 	regs: RegAllocator,
 	locals: RegAllocator,
+	/// Slots shared between the temporaries of all functions
+	shared_regs: RegAllocator,
+	shared_locals: RegAllocator,
 	has_allocated_reg: bool,
 	has_allocated_local: bool,
 }
@@ -23,6 +31,8 @@ impl RegAllocCx {
 		Self {
 			regs: RegAllocator::new(),
 			locals: RegAllocator::new(),
+			shared_regs: RegAllocator::new(),
+			shared_locals: RegAllocator::new(),
 			has_allocated_reg: false,
 			has_allocated_local: false,
 		}
@@ -41,6 +51,8 @@ impl RegAllocCx {
 	pub fn finish_using_all(&mut self) {
 		self.regs.finish_using_all();
 		self.locals.finish_using_all();
+		self.shared_regs.finish_using_all();
+		self.shared_locals.finish_using_all();
 	}
 
 	pub fn get_reg_count(&self) -> u32 {
@@ -129,7 +141,7 @@ pub struct GlobalRegAllocResult {
 /// Result from allocating registers for a block
 #[derive(Debug, Clone)]
 pub struct RegAllocResult {
-	pub regs: FxHashMap<Identifier, String>,
+	pub regs: FxHashMap<Identifier, Identifier>,
 	pub locals: FxHashMap<Identifier, String>,
 }
 
@@ -158,6 +170,7 @@ pub fn alloc_registers(
 	lir: &LIR,
 	func_mapping: &Option<FunctionMapping>,
 	project: &ProjectSettings,
+	interner: &mut Interner,
 ) -> anyhow::Result<GlobalRegAllocResult> {
 	let mut racx = RegAllocCx::new();
 	let mut out = GlobalRegAllocResult {
@@ -182,7 +195,15 @@ pub fn alloc_registers(
 	}
 
 	for (_, chunk) in chunks {
-		alloc_chunk_registers(chunk, lir, &mut racx, &mut out, func_mapping, project)?;
+		alloc_chunk_registers(
+			chunk,
+			lir,
+			&mut racx,
+			&mut out,
+			func_mapping,
+			project,
+			interner,
+		)?;
 		racx.finish_using_all();
 	}
 
@@ -195,6 +216,15 @@ struct FunctionChunk {
 	children: Vec<ResourceLocation>,
 }
 
+/// Where a register is stored
+#[derive(Debug, Clone, Copy)]
+enum RegSlot {
+	/// A slot that belongs to the function
+	Function(u32),
+	/// A slot that is shared by the temporaries of every function
+	Shared(u32),
+}
+
 /// Allocate the registers for a whole chunk
 fn alloc_chunk_registers(
 	chunk: FunctionChunk,
@@ -203,6 +233,7 @@ fn alloc_chunk_registers(
 	global: &mut GlobalRegAllocResult,
 	func_mapping: &Option<FunctionMapping>,
 	project: &ProjectSettings,
+	interner: &mut Interner,
 ) -> anyhow::Result<()> {
 	let mut parent_id = &chunk.parent;
 	if let Some(mapping) = func_mapping {
@@ -214,11 +245,12 @@ fn alloc_chunk_registers(
 	let mut out_regs = FxHashMap::default();
 	let mut out_locals = FxHashMap::default();
 
-	// Analyze the used registers of all the child functions
+	// Analyze the used registers of all the child functions, including the ones
+	// used by the children that they call
 	let mut child_used_regs = FxHashMap::default();
 	for child in &chunk.children {
-		let func = lir.functions.get(child).expect("Child should exist");
-		let used = func.block.get_used_regs();
+		let mut used = Vec::new();
+		collect_child_uses(child, &chunk, lir, &mut FxHashSet::default(), &mut used);
 		child_used_regs.insert(child.clone(), used);
 	}
 
@@ -253,11 +285,27 @@ fn alloc_chunk_registers(
 	let out = RegAllocResult {
 		regs: out_regs
 			.iter()
-			.map(|(x, y)| (x.clone(), format_reg_fake_player(*y, &func_id)))
+			.map(|(x, y)| {
+				let name = match y {
+					RegSlot::Function(num) => {
+						format_reg_fake_player(*num, &func_id, project.hidden_fake_players)
+					}
+					RegSlot::Shared(num) => {
+						format_shared_reg_fake_player(*num, project.hidden_fake_players)
+					}
+				};
+				(x.clone(), interner.intern_owned(name))
+			})
 			.collect(),
 		locals: out_locals
 			.iter()
-			.map(|(x, y)| (x.clone(), format_local_storage_entry(*y, &func_id)))
+			.map(|(x, y)| {
+				let name = match y {
+					RegSlot::Function(num) => format_local_storage_entry(*num, &func_id),
+					RegSlot::Shared(num) => format_shared_local_storage_entry(*num),
+				};
+				(x.clone(), name)
+			})
 			.collect(),
 	};
 
@@ -266,89 +314,151 @@ fn alloc_chunk_registers(
 	Ok(())
 }
 
+/// Collects the registers used by a child function and the children of the chunk that it calls
+fn collect_child_uses<'l>(
+	func_id: &ResourceLocation,
+	chunk: &FunctionChunk,
+	lir: &'l LIR,
+	visited: &mut FxHashSet<ResourceLocation>,
+	out: &mut Vec<&'l Identifier>,
+) {
+	if !visited.insert(func_id.clone()) {
+		return;
+	}
+	let func = lir.functions.get(func_id).expect("Child should exist");
+	for instr in &func.block.contents {
+		for reg in instr.get_used_regs() {
+			if !out.contains(&reg) {
+				out.push(reg);
+			}
+		}
+		for callee in get_called_functions(instr) {
+			if chunk.children.contains(callee) {
+				collect_child_uses(callee, chunk, lir, visited, out);
+			}
+		}
+	}
+}
+
 fn alloc_block_registers(
 	is_root: bool,
 	block: &LIRBlock,
 	child_uses: &FxHashMap<ResourceLocation, Vec<&Identifier>>,
-	out_regs: &mut FxHashMap<Identifier, u32>,
-	out_locals: &mut FxHashMap<Identifier, u32>,
+	out_regs: &mut FxHashMap<Identifier, RegSlot>,
+	out_locals: &mut FxHashMap<Identifier, RegSlot>,
 	project: &ProjectSettings,
 	racx: &mut RegAllocCx,
 ) -> anyhow::Result<()> {
-	let last_uses = if is_root && project.op_level >= OptimizationLevel::More {
-		analyze_last_register_uses(block, child_uses)
+	let ranges = if is_root && project.op_level >= OptimizationLevel::More {
+		analyze_live_ranges(block, child_uses)
 	} else {
 		FxHashMap::default()
 	};
+	// Registers that are never live while another function runs can share
+	// their slots with the temporaries of every other function
+	let is_shared = |reg_id: &Identifier| {
+		project.op_level >= OptimizationLevel::Full
+			&& ranges.get(reg_id).is_some_and(|x| !x.crosses_call)
+	};
 	for (i, instr) in block.contents.iter().enumerate() {
 		let used_regs = get_used_regs_ra(instr, child_uses);
-		for reg_id in used_regs {
+		for reg_id in &used_regs {
 			let reg = block
 				.regs
-				.get(reg_id)
-				.ok_or(anyhow!("Used register {reg_id} does not exist"))?;
+				.get(*reg_id)
+				.ok_or_else(|| anyhow!("Used register {reg_id} does not exist"))?;
+			let shared = is_shared(reg_id);
 			match reg.ty {
 				DataType::Score(..) => {
-					if !out_regs.contains_key(reg_id) {
-						out_regs.insert(reg_id.clone(), racx.new_reg());
+					if !out_regs.contains_key(*reg_id) {
+						let slot = if shared {
+							RegSlot::Shared(racx.shared_regs.alloc())
+						} else {
+							RegSlot::Function(racx.new_reg())
+						};
+						out_regs.insert((*reg_id).clone(), slot);
 					}
 				}
 				DataType::NBT(..) => {
-					if !out_locals.contains_key(reg_id) {
-						out_locals.insert(reg_id.clone(), racx.new_local());
+					if !out_locals.contains_key(*reg_id) {
+						let slot = if shared {
+							RegSlot::Shared(racx.shared_locals.alloc())
+						} else {
+							RegSlot::Function(racx.new_local())
+						};
+						out_locals.insert((*reg_id).clone(), slot);
 					}
 				}
 				_ => bail!("Type not supported"),
 			}
 		}
 
-		if is_root {
-			if let Some(regs) = last_uses.get(&i) {
-				for reg_id in regs.iter() {
-					let reg = block
-						.regs
-						.get(reg_id)
-						.ok_or(anyhow!("Used register {reg_id} does not exist"))?;
-					match reg.ty {
-						DataType::Score(..) => racx.regs.finish_using(
-							*out_regs
-								.get(reg_id)
-								.ok_or(anyhow!("Used register {reg_id} does not exist"))?,
-						),
-						DataType::NBT(..) => racx.locals.finish_using(
-							*out_locals
-								.get(reg_id)
-								.ok_or(anyhow!("Used register {reg_id} does not exist"))?,
-						),
-						_ => bail!("Type not supported"),
-					}
-				}
+		// Free the slots of registers that are not live anymore
+		for reg_id in used_regs.into_iter().unique() {
+			if ranges.get(reg_id).map(|x| x.end) != Some(i) {
+				continue;
+			}
+			let reg = block
+				.regs
+				.get(reg_id)
+				.ok_or_else(|| anyhow!("Used register {reg_id} does not exist"))?;
+			let slot = match reg.ty {
+				DataType::Score(..) => out_regs.get(reg_id),
+				DataType::NBT(..) => out_locals.get(reg_id),
+				_ => bail!("Type not supported"),
+			};
+			let slot = slot.ok_or_else(|| anyhow!("Used register {reg_id} does not exist"))?;
+			match (&reg.ty, slot) {
+				(DataType::Score(..), RegSlot::Function(num)) => racx.regs.finish_using(*num),
+				(DataType::Score(..), RegSlot::Shared(num)) => racx.shared_regs.finish_using(*num),
+				(_, RegSlot::Function(num)) => racx.locals.finish_using(*num),
+				(_, RegSlot::Shared(num)) => racx.shared_locals.finish_using(*num),
 			}
 		}
 	}
 	Ok(())
 }
 
-fn analyze_last_register_uses(
-	block: &LIRBlock,
-	child_uses: &FxHashMap<ResourceLocation, Vec<&Identifier>>,
-) -> FxHashMap<usize, Vec<Identifier>> {
-	let mut last_used_positions = FxHashMap::default();
-	let mut already_spent = FxHashSet::default();
-	for (i, instr) in block.contents.iter().enumerate().rev() {
-		let used_regs = get_used_regs_ra(instr, child_uses);
-		last_used_positions.insert(
-			i,
-			used_regs
-				.iter()
-				.filter(|x| !already_spent.contains(*x))
-				.map(|x| (*x).clone())
-				.collect(),
-		);
-		already_spent.extend(used_regs);
-	}
-
-	last_used_positions
+/// The instructions of a block that a register is live for
+#[derive(Debug, Clone)]
+struct LiveRange {
+	/// The index of the last instruction that uses the register
+	end: usize,
+	/// Whether other functions can run while the register is live
+	crosses_call: bool,
+}
+
+/// Finds the live range of every register used in a block. Blocks are straight-line
+/// code, so a register is live from its first use to its last use
+fn analyze_live_ranges<'r>(
+	block: &'r LIRBlock,
+	child_uses: &FxHashMap<ResourceLocation, Vec<&'r Identifier>>,
+) -> FxHashMap<&'r Identifier, LiveRange> {
+	let mut starts = FxHashMap::default();
+	let mut ranges: FxHashMap<_, LiveRange> = FxHashMap::default();
+	let mut calls = Vec::new();
+	for (i, instr) in block.contents.iter().enumerate() {
+		if may_call(instr) {
+			calls.push(i);
+		}
+		for reg in get_used_regs_ra(instr, child_uses) {
+			starts.entry(reg).or_insert(i);
+			ranges.insert(
+				reg,
+				LiveRange {
+					end: i,
+					crosses_call: false,
+				},
+			);
+		}
+	}
+
+	for (reg, range) in &mut ranges {
+		let start = starts[reg];
+		range.crosses_call = calls.iter().any(|x| (start..=range.end).contains(x));
+	}
+
+	ranges
 }
 
 /// Get used registers and registers of subfunction calls from an instruction.
@@ -359,19 +469,41 @@ fn get_used_regs_ra<'r>(
 ) -> Vec<&'r Identifier> {
 	let mut used_regs = instr.get_used_regs();
 	// Get used from the child
-	if let LIRInstrKind::Call(func, _) = &instr.kind {
+	for func in get_called_functions(instr) {
 		if let Some(child_regs) = child_uses.get(func) {
 			used_regs.extend(child_regs.clone());
 		}
 	}
+	used_regs
+}
+
+/// Gets the functions that an instruction calls directly
+fn get_called_functions(instr: &LIRInstruction) -> Vec<&ResourceLocation> {
+	let mut out = Vec::new();
 	for modi in &instr.modifiers {
 		if let Modifier::If { condition, .. } = modi {
 			if let IfModCondition::Function(func, _) = condition.as_ref() {
-				if let Some(child_regs) = child_uses.get(func) {
-					used_regs.extend(child_regs.clone());
-				}
+				out.push(func);
 			}
 		}
 	}
-	used_regs
+	match &instr.kind {
+		LIRInstrKind::Call(func, _) | LIRInstrKind::CallWith(func, _) => out.push(func),
+		LIRInstrKind::ReturnRun(body) => out.extend(get_called_functions(body)),
+		_ => {}
+	}
+	out
+}
+
+/// Checks if other functions can run during an instruction
+fn may_call(instr: &LIRInstruction) -> bool {
+	if !get_called_functions(instr).is_empty() {
+		return true;
+	}
+	match &instr.kind {
+		// Commands can call any function
+		LIRInstrKind::Command(..) | LIRInstrKind::MacroCommand(..) => true,
+		LIRInstrKind::ReturnRun(body) => may_call(body),
+		_ => false,
+	}
 }
@@ -1,5 +1,6 @@
+use anyhow::bail;
 use itertools::Itertools;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::common::ResourceLocation;
 use crate::lir::{LIRInstrKind, LIR};
@@ -17,13 +18,50 @@ pub enum StripMode {
 }
 
 /// Mapping of original function names to new, stripped ones
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FunctionMapping(pub FxHashMap<ResourceLocation, ResourceLocation>);
 
-pub fn strip(lir: &LIR, project: &ProjectSettings) -> Option<FunctionMapping> {
-	match &project.strip_mode {
-		StripMode::None => None,
-		StripMode::Unstable => Some(strip_unstable(lir, project)),
+pub fn strip(lir: &LIR, project: &ProjectSettings) -> anyhow::Result<Option<FunctionMapping>> {
+	let mut out = match &project.strip_mode {
+		StripMode::None => FunctionMapping(FxHashMap::default()),
+		StripMode::Unstable => strip_unstable(lir, project),
+	};
+
+	// Generated functions that weren't stripped are moved to the internal namespace
+	if let Some(namespace) = &project.internal_namespace {
+		for (func_id, func) in lir.functions.iter().sorted_by_key(|x| x.0) {
+			if !func.interface.annotations.generated || out.0.contains_key(func_id) {
+				continue;
+			}
+			let new_id = get_internal_id(func_id, namespace);
+			if lir.functions.contains_key(&new_id) {
+				bail!("Internal name {new_id} of function {func_id} conflicts with an existing function");
+			}
+			out.0.insert(func_id.clone(), new_id);
+		}
+	}
+
+	// Exported functions always use their stable name, even when not stripping
+	let mut exports = FxHashSet::default();
+	for (func_id, func) in lir.functions.iter().sorted_by_key(|x| x.0) {
+		let Some(export) = &func.interface.annotations.export else {
+			continue;
+		};
+		if !exports.insert(export) {
+			bail!("Exported name {export} is used by more than one function");
+		}
+		if export != func_id && lir.functions.contains_key(export) {
+			bail!(
+				"Exported name {export} of function {func_id} conflicts with an existing function"
+			);
+		}
+		out.0.insert(func_id.clone(), export.clone());
+	}
+
+	if out.0.is_empty() {
+		Ok(None)
+	} else {
+		Ok(Some(out))
 	}
 }
 
@@ -33,13 +71,22 @@ fn strip_unstable(lir: &LIR, project: &ProjectSettings) -> FunctionMapping {
 
 	for func in lir.functions.values() {
 		for instr in &func.block.contents {
-			if let LIRInstrKind::Call(func, _) = &instr.kind {
+			if let LIRInstrKind::Call(func, _) | LIRInstrKind::CallWith(func, _) = &instr.kind {
 				let entry = counts.entry(func);
 				*entry.or_insert(0) += 1;
 			}
 		}
 	}
 
+	// Stripped names can't be the same as the names of functions that keep theirs
+	let taken: FxHashSet<_> = lir
+		.functions
+		.iter()
+		.flat_map(|(id, func)| std::iter::once(id).chain(&func.interface.annotations.export))
+		.chain(&project.stable_names)
+		.map(|x| x.to_string())
+		.collect();
+
 	let mut out = FunctionMapping(FxHashMap::default());
 	let mut idx: u32 = 0;
 	// Sort by count, and then by function id, to ensure that the order between
@@ -50,19 +97,34 @@ fn strip_unstable(lir: &LIR, project: &ProjectSettings) -> FunctionMapping {
 		.rev()
 		.map(|x| x.0)
 	{
-		let func = lir.functions.values().find(|x| &x.interface.id == func_id);
+		let func = lir.functions.get(func_id);
 		// If it doesn't exist, then its probably a extern call that we can ignore
 		let Some(func) = func else {
 			continue;
 		};
-		if func.interface.annotations.preserve || func.interface.annotations.no_strip {
-			out.0.insert(func_id.clone(), func_id.clone());
+		let annotations = &func.interface.annotations;
+		// Generated functions are stripped into the internal namespace if there is one
+		let (namespace, unstripped) = match &project.internal_namespace {
+			Some(namespace) if annotations.generated => {
+				(namespace.as_str(), get_internal_id(func_id, namespace))
+			}
+			_ => (project.name.as_str(), func_id.clone()),
+		};
+		if annotations.preserve
+			|| annotations.public
+			|| annotations.no_strip
+			|| project.stable_names.contains(func_id)
+		{
+			out.0.insert(func_id.clone(), unstripped);
 		} else {
-			let mut name = get_stripped_name_unstable(idx, &RESOURCE_LOCATION_CHARSET);
-			name = format!("{}:s/{name}", project.name);
+			let mut name = get_name(idx, namespace);
+			while taken.contains(&name) {
+				idx += 1;
+				name = get_name(idx, namespace);
+			}
 			// If there is no size reduction, don't strip
-			if name.len() >= func_id.len() {
-				name = func_id.to_string();
+			if name.len() >= unstripped.len() {
+				name = unstripped.to_string();
 			} else {
 				// We are using the stripped name, so increase the index
 				idx += 1;
@@ -73,3 +135,119 @@ fn strip_unstable(lir: &LIR, project: &ProjectSettings) -> FunctionMapping {
 
 	out
 }
+
+fn get_name(idx: u32, namespace: &str) -> String {
+	let name = get_stripped_name_unstable(idx, &RESOURCE_LOCATION_CHARSET);
+	format!("{namespace}:s/{name}")
+}
+
+/// Gets the name of a generated function in the internal namespace, which keeps the
+/// namespace of the original as a directory so that packs can share the namespace
+fn get_internal_id(func_id: &ResourceLocation, namespace: &str) -> ResourceLocation {
+	format!("{namespace}:{}", func_id.replacen(':', "/", 1)).into()
+}
+
+impl FunctionMapping {
+	/// Gets the JSON for the mapping from original function names to the names they
+	/// were output with, which is written next to the pack for debugging
+	pub fn to_json(&self) -> serde_json::Value {
+		let entries = self
+			.0
+			.iter()
+			.sorted()
+			.map(|(l, r)| (l.to_string(), serde_json::Value::String(r.to_string())));
+		serde_json::Value::Object(entries.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::function::FunctionInterface;
+	use crate::common::RegisterList;
+	use crate::lir::{LIRBlock, LIRFunction, LIRInstruction};
+	use crate::project::ProjectSettingsBuilder;
+
+	fn add_func<'a>(lir: &'a mut LIR, id: &str, calls: &[&str]) -> &'a mut FunctionInterface {
+		let mut block = LIRBlock::new(RegisterList::default());
+		block.contents = calls
+			.iter()
+			.map(|x| LIRInstruction::new(LIRInstrKind::Call((*x).into(), Vec::new())))
+			.collect();
+		let func = LIRFunction {
+			interface: FunctionInterface::new(id.into()),
+			block,
+			parent: None,
+		};
+		&mut lir.functions.entry(id.into()).or_insert(func).interface
+	}
+
+	#[test]
+	fn test_stable_names() {
+		let mut lir = LIR::new();
+		add_func(
+			&mut lir,
+			"test:main",
+			&["test:internal", "test:allowed", "test:api"],
+		);
+		add_func(&mut lir, "test:internal", &[]);
+		add_func(&mut lir, "test:allowed", &[]);
+		add_func(&mut lir, "test:api", &[]).annotations.export = Some("test:v1/api".into());
+		// The first stripped name is already used
+		add_func(&mut lir, "dpc:s/", &[]);
+
+		let project = ProjectSettingsBuilder::new("dpc")
+			.strip_mode(StripMode::Unstable)
+			.stable_name("test:allowed")
+			.build();
+		let mapping = strip(&lir, &project)
+			.expect("Failed to strip")
+			.expect("Should have a mapping");
+		let get = |id: &str| mapping.0[&ResourceLocation::from(id)].to_string();
+		assert_eq!(get("test:allowed"), "test:allowed");
+		assert_eq!(get("test:api"), "test:v1/api");
+		assert!(get("test:internal").starts_with("dpc:s/"));
+		assert_ne!(get("test:internal"), "dpc:s/");
+		assert_eq!(mapping.to_json()["test:api"], "test:v1/api");
+
+		// Exports can't take the name of another function
+		add_func(&mut lir, "test:other", &[]).annotations.export = Some("test:main".into());
+		assert!(strip(&lir, &project).is_err());
+	}
+
+	#[test]
+	fn test_internal_namespace() {
+		let mut lir = LIR::new();
+		let bodies = ["test:main_body_0", "test:main_body_1"];
+		add_func(&mut lir, "test:main", &bodies);
+		for body in bodies {
+			add_func(&mut lir, body, &[]).annotations.generated = true;
+		}
+
+		let project = ProjectSettingsBuilder::new("test")
+			.internal_namespace("dpc_internal")
+			.build();
+		let mapping = strip(&lir, &project)
+			.expect("Failed to strip")
+			.expect("Should have a mapping");
+		let get = |mapping: &FunctionMapping, id: &str| {
+			let id = mapping.0.get(&ResourceLocation::from(id));
+			id.map(|x| x.to_string())
+		};
+		assert_eq!(
+			get(&mapping, "test:main_body_0").as_deref(),
+			Some("dpc_internal:test/main_body_0")
+		);
+		assert_eq!(get(&mapping, "test:main"), None);
+
+		let project = ProjectSettingsBuilder::from_settings(project)
+			.strip_mode(StripMode::Unstable)
+			.build();
+		let mapping = strip(&lir, &project)
+			.expect("Failed to strip")
+			.expect("Should have a mapping");
+		let body = get(&mapping, "test:main_body_1").expect("Body should be stripped");
+		assert!(body.starts_with("dpc_internal:s/"));
+	}
+}
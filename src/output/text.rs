@@ -7,26 +7,44 @@ pub const LIT_OBJECTIVE: &str = "_l";
 // Storage
 pub const REG_STORAGE_LOCATION: &str = "dpc:r";
 
-pub fn format_reg_fake_player(num: u32, func_id: &str) -> String {
-	format!("%r{func_id}.{num}")
+/// Gets the character that fake players for registers and literals start with.
+/// Players whose names start with a # are hidden from the sidebar
+pub fn get_fake_player_sigil(hidden: bool) -> char {
+	if hidden {
+		'#'
+	} else {
+		'%'
+	}
+}
+
+pub fn format_reg_fake_player(num: u32, func_id: &str, hidden: bool) -> String {
+	format!("{}r{func_id}.{num}", get_fake_player_sigil(hidden))
+}
+
+pub fn format_shared_reg_fake_player(num: u32, hidden: bool) -> String {
+	format!("{}r.{num}", get_fake_player_sigil(hidden))
 }
 
-pub fn format_lit_fake_player(num: i32) -> String {
-	format!("%l{num}")
+pub fn format_lit_fake_player(num: i32, hidden: bool) -> String {
+	format!("{}l{num}", get_fake_player_sigil(hidden))
 }
 
-pub fn format_arg_fake_player(num: ArgRetIndex, func_id: &str) -> String {
-	format!("%a{func_id}.{num}")
+pub fn format_arg_fake_player(num: ArgRetIndex, func_id: &str, hidden: bool) -> String {
+	format!("{}a{func_id}.{num}", get_fake_player_sigil(hidden))
 }
 
-pub fn format_ret_fake_player(num: ArgRetIndex, func_id: &str) -> String {
-	format!("%R{func_id}.{num}")
+pub fn format_ret_fake_player(num: ArgRetIndex, func_id: &str, hidden: bool) -> String {
+	format!("{}R{func_id}.{num}", get_fake_player_sigil(hidden))
 }
 
 pub fn format_local_storage_entry(num: u32, func_id: &str) -> String {
 	format!("r{func_id}_{num}")
 }
 
+pub fn format_shared_local_storage_entry(num: u32) -> String {
+	format!("r_{num}")
+}
+
 pub fn format_arg_local_storage_entry(num: ArgRetIndex, func_id: &str) -> String {
 	format!("a{func_id}_{num}")
 }
@@ -35,6 +53,10 @@ pub fn format_ret_local_storage_entry(num: ArgRetIndex, func_id: &str) -> String
 	format!("R{func_id}_{num}")
 }
 
+pub fn format_const_storage_entry(num: u32) -> String {
+	format!("c{num}")
+}
+
 pub fn format_local_storage_path(entry: &str) -> String {
 	entry.to_string()
 }
@@ -0,0 +1,401 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::ir::IR;
+use crate::parse::Parser;
+
+/// The name of the manifest file at the root of a library package
+pub const MANIFEST_NAME: &str = "dpc.json";
+/// The name of the lockfile next to a workspace config
+pub const LOCKFILE_NAME: &str = "dpc.lock";
+
+/// Manifest for a library package
+#[derive(Deserialize, Debug)]
+pub struct PackageManifest {
+	pub name: String,
+	pub version: String,
+	/// Source files, relative to the manifest
+	pub sources: Vec<PathBuf>,
+}
+
+/// A dependency on a library package
+#[derive(Deserialize, Debug)]
+pub struct PackageDependency {
+	pub name: String,
+	/// Path to the package directory or zip archive, relative to the workspace config
+	pub path: PathBuf,
+	/// Version requirement for the package
+	#[serde(default = "any_version")]
+	pub version: String,
+}
+
+fn any_version() -> String {
+	"*".into()
+}
+
+/// A package that has been loaded and checked against its dependency
+pub struct ResolvedPackage {
+	pub name: String,
+	pub version: Version,
+	pub checksum: String,
+	pub ir: IR,
+}
+
+/// Loads a package from a local directory or zip archive. The functions of the
+/// package must all be in a namespace with the same name as the package
+pub fn resolve_package(dep: &PackageDependency, root: &Path) -> anyhow::Result<ResolvedPackage> {
+	let path = root.join(&dep.path);
+	let mut files = PackageFiles::open(&path)
+		.with_context(|| format!("Failed to open package {}", dep.name))?;
+	let manifest = files
+		.read(Path::new(MANIFEST_NAME))
+		.with_context(|| format!("Failed to read manifest for package {}", dep.name))?;
+	let manifest: PackageManifest =
+		serde_json::from_str(&manifest).context("Failed to parse package manifest")?;
+	if manifest.name != dep.name {
+		bail!(
+			"Package at {path:?} is named {} instead of {}",
+			manifest.name,
+			dep.name
+		);
+	}
+
+	let version = Version::parse(&manifest.version).context("Invalid package version")?;
+	let req = VersionReq::parse(&dep.version).context("Invalid version requirement")?;
+	if !req.matches(&version) {
+		bail!(
+			"Package {} has version {version}, which does not match requirement {}",
+			dep.name,
+			dep.version
+		);
+	}
+
+	let mut parser = Parser::new();
+	let mut checksum = Checksum::new();
+	for source in &manifest.sources {
+		let text = files
+			.read(source)
+			.with_context(|| format!("Failed to read source file {source:?}"))?;
+		checksum.update(text.as_bytes());
+		parser
+			.parse(&text)
+			.with_context(|| format!("Failed to parse source file {source:?}"))?;
+	}
+	let ir = parser.finish();
+
+	let namespace = format!("{}:", dep.name);
+	for func_id in ir.functions.keys() {
+		if !func_id.starts_with(&namespace) {
+			bail!(
+				"Function {func_id} in package {} is outside of the {} namespace",
+				dep.name,
+				dep.name
+			);
+		}
+	}
+
+	Ok(ResolvedPackage {
+		name: manifest.name,
+		version,
+		checksum: checksum.finish(),
+		ir,
+	})
+}
+
+/// The files of a package, which are either in a directory or a zip archive
+enum PackageFiles {
+	Dir(PathBuf),
+	Archive(ZipArchive<File>),
+}
+
+impl PackageFiles {
+	fn open(path: &Path) -> anyhow::Result<Self> {
+		if path.is_dir() {
+			Ok(Self::Dir(path.to_owned()))
+		} else if path.is_file() {
+			let file = File::open(path).context("Failed to open archive")?;
+			let archive = ZipArchive::new(file).context("Failed to read archive")?;
+			Ok(Self::Archive(archive))
+		} else {
+			bail!("Package path {path:?} does not exist")
+		}
+	}
+
+	/// Reads a text file at a path relative to the root of the package
+	fn read(&mut self, path: &Path) -> anyhow::Result<String> {
+		match self {
+			Self::Dir(dir) => {
+				std::fs::read_to_string(dir.join(path)).context("Failed to read file")
+			}
+			Self::Archive(archive) => {
+				// Archive entries are always separated with forward slashes
+				let name = path
+					.components()
+					.map(|x| x.as_os_str().to_string_lossy())
+					.join("/");
+				let mut file = archive
+					.by_name(&name)
+					.with_context(|| format!("File {name} is missing from the archive"))?;
+				let mut out = String::new();
+				file.read_to_string(&mut out)
+					.context("Failed to read file from archive")?;
+				Ok(out)
+			}
+		}
+	}
+}
+
+/// Lockfile that pins the exact packages a workspace was built with
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Lockfile {
+	pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct LockedPackage {
+	pub name: String,
+	pub version: String,
+	pub checksum: String,
+}
+
+impl Lockfile {
+	pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+		if !path.exists() {
+			return Ok(None);
+		}
+		let contents = std::fs::read_to_string(path).context("Failed to read lockfile")?;
+		let lock = serde_json::from_str(&contents).context("Failed to parse lockfile")?;
+		Ok(Some(lock))
+	}
+
+	pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+		let contents =
+			serde_json::to_string_pretty(self).context("Failed to serialize lockfile")?;
+		std::fs::write(path, contents).context("Failed to write lockfile")
+	}
+
+	pub fn from_packages<'p>(packages: impl Iterator<Item = &'p ResolvedPackage>) -> Self {
+		let mut packages: Vec<_> = packages
+			.map(|x| LockedPackage {
+				name: x.name.clone(),
+				version: x.version.to_string(),
+				checksum: x.checksum.clone(),
+			})
+			.collect();
+		packages.sort_by(|l, r| l.name.cmp(&r.name));
+		Self { packages }
+	}
+
+	/// Checks that the resolved packages are the same as the ones in the lockfile
+	pub fn check(&self, other: &Lockfile) -> anyhow::Result<()> {
+		for package in &other.packages {
+			let Some(locked) = self.packages.iter().find(|x| x.name == package.name) else {
+				bail!("Package {} is missing from the lockfile", package.name);
+			};
+			if locked != package {
+				bail!(
+					"Package {} has changed since it was locked at version {}",
+					package.name,
+					locked.version
+				);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Simple FNV-1a hash that is stable between builds
+struct Checksum(u64);
+
+impl Checksum {
+	fn new() -> Self {
+		Self(0xcbf29ce484222325)
+	}
+
+	fn update(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 ^= *byte as u64;
+			self.0 = self.0.wrapping_mul(0x100000001b3);
+		}
+	}
+
+	fn finish(&self) -> String {
+		format!("{:016x}", self.0)
+	}
+}
+
+/// A package version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+	pub major: u32,
+	pub minor: u32,
+	pub patch: u32,
+}
+
+impl Version {
+	pub fn parse(string: &str) -> Option<Self> {
+		let mut parts = string.split('.');
+		let major = parts.next()?.parse().ok()?;
+		let minor = parts.next().map(|x| x.parse().ok()).unwrap_or(Some(0))?;
+		let patch = parts.next().map(|x| x.parse().ok()).unwrap_or(Some(0))?;
+		if parts.next().is_some() {
+			return None;
+		}
+
+		Some(Self {
+			major,
+			minor,
+			patch,
+		})
+	}
+}
+
+impl Display for Version {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+	}
+}
+
+/// A requirement on the version of a package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionReq {
+	/// Any version (*)
+	Any,
+	/// Exactly this version (=1.2.3)
+	Exact(Version),
+	/// At least this version (>=1.2.3)
+	AtLeast(Version),
+	/// Compatible with this version (^1.2.3 or 1.2.3)
+	Compatible(Version),
+}
+
+impl VersionReq {
+	pub fn parse(string: &str) -> Option<Self> {
+		let string = string.trim();
+		if string == "*" {
+			Some(Self::Any)
+		} else if let Some(version) = string.strip_prefix('=') {
+			Version::parse(version).map(Self::Exact)
+		} else if let Some(version) = string.strip_prefix(">=") {
+			Version::parse(version).map(Self::AtLeast)
+		} else {
+			let version = string.strip_prefix('^').unwrap_or(string);
+			Version::parse(version).map(Self::Compatible)
+		}
+	}
+
+	pub fn matches(&self, version: &Version) -> bool {
+		match self {
+			Self::Any => true,
+			Self::Exact(req) => version == req,
+			Self::AtLeast(req) => version >= req,
+			Self::Compatible(req) => {
+				// Before 1.0, minor versions are allowed to break compatibility
+				let same_series = if req.major == 0 {
+					version.major == 0 && version.minor == req.minor
+				} else {
+					version.major == req.major
+				};
+				same_series && version >= req
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_version_req() {
+		let version = Version::parse("1.4.2").unwrap();
+		assert!(VersionReq::parse("^1.2").unwrap().matches(&version));
+		assert!(VersionReq::parse("1.4.2").unwrap().matches(&version));
+		assert!(!VersionReq::parse("^2").unwrap().matches(&version));
+		assert!(!VersionReq::parse("=1.4.1").unwrap().matches(&version));
+		assert!(VersionReq::parse(">=0.9").unwrap().matches(&version));
+		assert!(!VersionReq::parse("^0.3")
+			.unwrap()
+			.matches(&Version::parse("0.4.0").unwrap()));
+	}
+
+	#[test]
+	fn test_resolve_package() {
+		use std::io::Write;
+		use zip::write::FileOptions;
+		use zip::ZipWriter;
+
+		let dir = std::env::temp_dir().join("dpc_test_resolve_package");
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(dir.join("math/src")).expect("Failed to create directory");
+		let files = [
+			(
+				"dpc.json",
+				r#"{"name": "math", "version": "1.2.0", "sources": ["src/lib.dpc"]}"#,
+			),
+			("src/lib.dpc", "\"math:noop\" {\n\tsay \"hi\";\n}\n"),
+		];
+		let archive = File::create(dir.join("math.zip")).expect("Failed to create archive");
+		let mut archive = ZipWriter::new(archive);
+		for (path, contents) in files {
+			std::fs::write(dir.join("math").join(path), contents).expect("Failed to write file");
+			archive
+				.start_file(path, FileOptions::default())
+				.expect("Failed to start archive file");
+			archive
+				.write_all(contents.as_bytes())
+				.expect("Failed to write archive file");
+		}
+		archive.finish().expect("Failed to finish archive");
+
+		let dep = |name: &str, path: &str, version: &str| PackageDependency {
+			name: name.into(),
+			path: path.into(),
+			version: version.into(),
+		};
+		let local = resolve_package(&dep("math", "math", "^1.1"), &dir).expect("Failed to resolve");
+		assert_eq!(local.version, Version::parse("1.2.0").unwrap());
+		assert!(local.ir.functions.keys().any(|x| x.as_ref() == "math:noop"));
+		let archived =
+			resolve_package(&dep("math", "math.zip", "^1.1"), &dir).expect("Failed to resolve");
+		assert_eq!(archived.checksum, local.checksum);
+		assert_eq!(archived.ir.functions.len(), 1);
+
+		assert!(resolve_package(&dep("math", "math.zip", "^2"), &dir).is_err());
+		assert!(resolve_package(&dep("geometry", "math", "*"), &dir).is_err());
+		assert!(resolve_package(&dep("math", "missing", "*"), &dir).is_err());
+	}
+
+	#[test]
+	fn test_lockfile_mismatch() {
+		let package = |version: &str, checksum: &str| LockedPackage {
+			name: "math".into(),
+			version: version.into(),
+			checksum: checksum.into(),
+		};
+		let lock = Lockfile {
+			packages: vec![package("1.2.0", "0123")],
+		};
+		let resolved = |packages| Lockfile { packages };
+		assert!(lock
+			.check(&resolved(vec![package("1.2.0", "0123")]))
+			.is_ok());
+		assert!(lock
+			.check(&resolved(vec![package("1.2.0", "4567")]))
+			.is_err());
+		assert!(lock
+			.check(&resolved(vec![package("1.3.0", "0123")]))
+			.is_err());
+		assert!(Lockfile::default()
+			.check(&resolved(vec![package("1.2.0", "0123")]))
+			.is_err());
+	}
+}
@@ -0,0 +1,452 @@
+use std::fmt::Display;
+
+use anyhow::{bail, Context};
+
+use crate::common::span::Span;
+use crate::common::ResourceLocation;
+use crate::ir::Instruction;
+
+use super::lex::{lex, Side, Token, TokenAndPos};
+use super::named::{resolve_names, NamedTypeKind, NamedTypes};
+use super::parse::parse_body;
+use super::version::{check_version, migrate_tokens, read_header, MIGRATIONS};
+
+/// A file of textual IR that keeps all of its tokens, including whitespace and comments,
+/// so that tools like the formatter can change it without losing the formatting of the user.
+/// Printing it gives back the exact text that it was parsed from
+#[derive(Debug, Clone)]
+pub struct Ast {
+	pub items: Vec<Item>,
+	/// The whitespace and comments after the last item
+	pub trailing: Vec<AstToken>,
+}
+
+/// A token along with the exact text that it came from
+#[derive(Debug, Clone)]
+pub struct AstToken {
+	pub tok: Token,
+	pub span: Span,
+	pub text: String,
+}
+
+impl AstToken {
+	/// Checks if this token is whitespace or a comment
+	pub fn is_trivia(&self) -> bool {
+		self.tok.is_ignored()
+	}
+}
+
+/// A definition at the top level of a file
+#[derive(Debug, Clone)]
+pub struct Item {
+	pub kind: ItemKind,
+	/// The tokens of the item before its body, including the whitespace and comments before it
+	pub head: Vec<AstToken>,
+	/// The body of the item between curly braces, if it has one
+	pub body: Option<Block>,
+}
+
+/// The different kinds of items in a file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemKind {
+	/// The version header at the start of the file
+	Header(u32),
+	/// A storage declaration
+	Storage(ResourceLocation),
+	/// A declaration of a flag set or enum
+	NamedType(NamedTypeKind, String),
+	/// A function definition
+	Function(ResourceLocation),
+}
+
+/// Statements between curly braces
+#[derive(Debug, Clone)]
+pub struct Block {
+	pub open: AstToken,
+	pub statements: Vec<Statement>,
+	/// The whitespace and comments after the last statement
+	pub trailing: Vec<AstToken>,
+	pub close: AstToken,
+}
+
+/// A statement in a block, which ends with a semicolon unless it is the last one
+#[derive(Debug, Clone)]
+pub struct Statement {
+	/// The tokens and nested blocks of the statement, starting with the whitespace
+	/// and comments before it
+	pub parts: Vec<Part>,
+}
+
+/// A part of a statement
+#[derive(Debug, Clone)]
+pub enum Part {
+	Token(AstToken),
+	Block(Block),
+}
+
+impl Ast {
+	/// Parses the text of a file
+	pub fn parse(text: &str) -> anyhow::Result<Self> {
+		let lexed = lex(text).context("Failed to lex text")?;
+		let header = read_header(&lexed)?;
+		let mut toks = get_ast_tokens(text, lexed).into_iter();
+
+		let mut items = Vec::new();
+		if let Some(header) = header {
+			check_version(header.version)?;
+			items.push(Item {
+				kind: ItemKind::Header(header.version),
+				head: toks.by_ref().take(header.len).collect(),
+				body: None,
+			});
+		}
+
+		loop {
+			let mut head: Vec<AstToken> = Vec::new();
+			let body = loop {
+				let Some(tok) = toks.next() else {
+					if let Some(tok) = head.iter().find(|x| !x.is_trivia()) {
+						bail!("Unexpected end of file after {:?} {}", tok.tok, tok.span);
+					}
+					return Ok(Self {
+						items,
+						trailing: head,
+					});
+				};
+				match &tok.tok {
+					Token::Semicolon => {
+						head.push(tok);
+						break None;
+					}
+					Token::Curly(Side::Left) => break Some(parse_block(tok, &mut toks)?),
+					Token::Curly(Side::Right) => {
+						bail!("Unexpected token {:?} {}", tok.tok, tok.span)
+					}
+					_ => head.push(tok),
+				}
+			};
+			let kind = get_item_kind(&head, body.is_some())?;
+			items.push(Item { kind, head, body });
+		}
+	}
+
+	/// Gets the functions defined in the file
+	pub fn functions(&self) -> impl Iterator<Item = (&ResourceLocation, &Block)> {
+		self.items.iter().filter_map(|x| match (&x.kind, &x.body) {
+			(ItemKind::Function(id), Some(body)) => Some((id, body)),
+			_ => None,
+		})
+	}
+
+	/// Lowers a statement in this file to an IR instruction. Statements can use
+	/// the named types that are declared anywhere in the file
+	pub fn lower_statement(&self, stmt: &Statement) -> anyhow::Result<Option<Instruction>> {
+		let types = self.get_named_types()?;
+		let mut instrs = self.lower_tokens(stmt.get_tokens(), &types)?;
+		Ok(instrs.pop())
+	}
+
+	/// Lowers the statements of a block in this file to IR instructions
+	pub fn lower_block(&self, block: &Block) -> anyhow::Result<Vec<Instruction>> {
+		let types = self.get_named_types()?;
+		let toks = block
+			.statements
+			.iter()
+			.flat_map(|x| x.get_tokens())
+			.collect();
+		self.lower_tokens(toks, &types)
+	}
+
+	fn lower_tokens(
+		&self,
+		mut toks: Vec<TokenAndPos>,
+		types: &NamedTypes,
+	) -> anyhow::Result<Vec<Instruction>> {
+		if let Some(version) = self.get_version() {
+			migrate_tokens(&mut toks, version, MIGRATIONS);
+		}
+		let toks = resolve_names(toks, types)?;
+		parse_body(toks).context("Failed to parse statements")
+	}
+
+	/// Gets the version from the header of the file, if it has one
+	fn get_version(&self) -> Option<u32> {
+		self.items.iter().find_map(|x| match x.kind {
+			ItemKind::Header(version) => Some(version),
+			_ => None,
+		})
+	}
+
+	/// Declares the flag sets and enums in the file
+	fn get_named_types(&self) -> anyhow::Result<NamedTypes> {
+		let mut types = NamedTypes::default();
+		for item in &self.items {
+			if let (ItemKind::NamedType(kind, name), Some(body)) = (&item.kind, &item.body) {
+				let body: Vec<_> = body
+					.statements
+					.iter()
+					.flat_map(|x| x.get_tokens())
+					.collect();
+				types.declare(*kind, name, &body)?;
+			}
+		}
+
+		Ok(types)
+	}
+}
+
+impl Display for Ast {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for item in &self.items {
+			write_tokens(f, &item.head)?;
+			if let Some(body) = &item.body {
+				write!(f, "{body}")?;
+			}
+		}
+		write_tokens(f, &self.trailing)
+	}
+}
+
+impl Block {
+	/// Gets the span from the opening curly brace to the closing one
+	pub fn span(&self) -> Span {
+		self.open.span.to(&self.close.span)
+	}
+}
+
+impl Display for Block {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.open.text)?;
+		for stmt in &self.statements {
+			write!(f, "{stmt}")?;
+		}
+		write_tokens(f, &self.trailing)?;
+		write!(f, "{}", self.close.text)
+	}
+}
+
+impl Statement {
+	/// Gets the comments before the statement, without their # and with surrounding
+	/// whitespace trimmed
+	pub fn comments(&self) -> impl Iterator<Item = &str> {
+		self.parts
+			.iter()
+			.map_while(|x| match x {
+				Part::Token(tok) if tok.is_trivia() => Some(&tok.tok),
+				_ => None,
+			})
+			.filter_map(|x| match x {
+				Token::Comment(comment) => Some(comment.trim()),
+				_ => None,
+			})
+	}
+
+	/// Gets the span of the statement, without the whitespace and comments before it
+	pub fn span(&self) -> Option<Span> {
+		let mut spans = self.parts.iter().filter_map(|x| match x {
+			Part::Token(tok) if tok.is_trivia() => None,
+			Part::Token(tok) => Some(tok.span),
+			Part::Block(block) => Some(block.span()),
+		});
+		let first = spans.next()?;
+		Some(first.to(&spans.next_back().unwrap_or(first)))
+	}
+
+	/// Gets the tokens of the statement that have meaning, including those in nested blocks
+	fn get_tokens(&self) -> Vec<TokenAndPos> {
+		let mut out = Vec::new();
+		for part in &self.parts {
+			match part {
+				Part::Token(tok) if tok.is_trivia() => {}
+				Part::Token(tok) => out.push((tok.tok.clone(), tok.span)),
+				Part::Block(block) => {
+					out.push((block.open.tok.clone(), block.open.span));
+					out.extend(block.statements.iter().flat_map(|x| x.get_tokens()));
+					out.push((block.close.tok.clone(), block.close.span));
+				}
+			}
+		}
+
+		out
+	}
+}
+
+impl Display for Statement {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for part in &self.parts {
+			match part {
+				Part::Token(tok) => write!(f, "{}", tok.text)?,
+				Part::Block(block) => write!(f, "{block}")?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Parses the rest of a block after its opening curly brace
+fn parse_block(open: AstToken, toks: &mut std::vec::IntoIter<AstToken>) -> anyhow::Result<Block> {
+	let mut statements = Vec::new();
+	let mut parts = Vec::new();
+	loop {
+		let Some(tok) = toks.next() else {
+			bail!("Unclosed curly brace {}", open.span);
+		};
+		match &tok.tok {
+			Token::Curly(Side::Left) => parts.push(Part::Block(parse_block(tok, toks)?)),
+			Token::Curly(Side::Right) => {
+				// The whitespace and comments after the last statement belong to the block
+				let end = parts
+					.iter()
+					.rposition(|x| !matches!(x, Part::Token(tok) if tok.is_trivia()))
+					.map(|x| x + 1)
+					.unwrap_or_default();
+				let trailing = parts
+					.split_off(end)
+					.into_iter()
+					.filter_map(|x| match x {
+						Part::Token(tok) => Some(tok),
+						Part::Block(..) => None,
+					})
+					.collect();
+				if !parts.is_empty() {
+					statements.push(Statement { parts });
+				}
+				return Ok(Block {
+					open,
+					statements,
+					trailing,
+					close: tok,
+				});
+			}
+			Token::Semicolon => {
+				parts.push(Part::Token(tok));
+				statements.push(Statement {
+					parts: std::mem::take(&mut parts),
+				});
+			}
+			_ => parts.push(Part::Token(tok)),
+		}
+	}
+}
+
+/// Figures out what kind of item the tokens before its body are for
+fn get_item_kind(head: &[AstToken], has_body: bool) -> anyhow::Result<ItemKind> {
+	let mut toks = head.iter().filter(|x| !x.is_trivia()).map(|x| &x.tok);
+	let first = toks.clone().next();
+	// Annotations can have strings as arguments, so the name of a function is the last string
+	let last_str = toks.clone().rev().find_map(|x| match x {
+		Token::Str(string) => Some(string),
+		_ => None,
+	});
+	let is_storage = toks.any(|x| matches!(x, Token::Ident(ident) if ident == "storage"));
+
+	match (first, has_body) {
+		(Some(Token::Ident(ident)), true) if ident == "flags" || ident == "enum" => {
+			let kind = if ident == "flags" {
+				NamedTypeKind::Flags
+			} else {
+				NamedTypeKind::Enum
+			};
+			let name = head.iter().filter(|x| !x.is_trivia()).nth(1);
+			match name.map(|x| &x.tok) {
+				Some(Token::Ident(name)) => Ok(ItemKind::NamedType(kind, name.clone())),
+				_ => bail!("Expected name of {ident}"),
+			}
+		}
+		(Some(..), false) if is_storage => {
+			let name = last_str.context("Expected name of storage")?;
+			Ok(ItemKind::Storage(name.clone().into()))
+		}
+		(Some(..), true) => {
+			let name = last_str.context("Expected name of function")?;
+			Ok(ItemKind::Function(name.clone().into()))
+		}
+		_ => {
+			let tok = head.iter().find(|x| !x.is_trivia()).or(head.last());
+			match tok {
+				Some(tok) => bail!("Unexpected token {:?} {}", tok.tok, tok.span),
+				None => bail!("Unexpected token"),
+			}
+		}
+	}
+}
+
+/// Attaches the text that each token came from to it. Spans are in characters, and
+/// every character of the text is in a token
+fn get_ast_tokens(text: &str, lexed: Vec<TokenAndPos>) -> Vec<AstToken> {
+	let offsets: Vec<_> = text
+		.char_indices()
+		.map(|x| x.0)
+		.chain(std::iter::once(text.len()))
+		.collect();
+	lexed
+		.into_iter()
+		.map(|(tok, span)| {
+			let text = text[offsets[span.start]..offsets[span.end]].to_string();
+			AstToken { tok, span, text }
+		})
+		.collect()
+}
+
+fn write_tokens(f: &mut std::fmt::Formatter<'_>, toks: &[AstToken]) -> std::fmt::Result {
+	for tok in toks {
+		write!(f, "{}", tok.text)?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::parse::parse_instrs;
+
+	const TEXT: &str = "dpc_version 1;\n\n# Phases of the game\nenum Phase { Idle, Done }\n\n@transient storage \"test:tmp\";\n\n@preserve @export \"test:api\"\n\"test:main\" {\n\t# Sets x\n\tlet x: score = val 5s;\n\tife eq %x, 5s {\n\t\tsay \"ünïcode\";\n\t} {\n\t\tsay \"no\";\n\t};\n\tlet y: enum<Phase> = val Phase::Done\n\t# End\n}\n";
+
+	#[test]
+	fn test_ast_lossless() {
+		let ast = Ast::parse(TEXT).expect("Failed to parse");
+		assert_eq!(ast.to_string(), TEXT);
+
+		let kinds: Vec<_> = ast.items.iter().map(|x| x.kind.clone()).collect();
+		assert_eq!(
+			kinds,
+			[
+				ItemKind::Header(1),
+				ItemKind::NamedType(NamedTypeKind::Enum, "Phase".into()),
+				ItemKind::Storage("test:tmp".into()),
+				ItemKind::Function("test:main".into()),
+			]
+		);
+
+		let (_, body) = ast.functions().next().expect("No function");
+		assert_eq!(body.statements.len(), 3);
+		let comments: Vec<_> = body.statements[0].comments().collect();
+		assert_eq!(comments, ["Sets x"]);
+		assert!(matches!(&body.trailing[1].tok, Token::Comment(x) if x == " End"));
+		let span = body.statements[1].span().expect("No span");
+		assert_eq!((span.line, span.col), (12, 1));
+
+		assert!(Ast::parse("\"test:main\" { say \"hi\";").is_err());
+		assert!(Ast::parse("\"test:main\" }").is_err());
+	}
+
+	#[test]
+	fn test_ast_lowering() {
+		let ast = Ast::parse(TEXT).expect("Failed to parse");
+		let (_, body) = ast.functions().next().expect("No function");
+		let instrs = ast.lower_block(body).expect("Failed to lower");
+		let expected = parse_instrs(
+			"let x: score = val 5s; ife eq %x, 5s { say \"ünïcode\"; } { say \"no\"; }; let y: enum<2> = val 1s",
+		)
+		.expect("Failed to parse expected");
+		assert_eq!(instrs, expected);
+
+		let instr = ast
+			.lower_statement(&body.statements[0])
+			.expect("Failed to lower");
+		assert_eq!(instr.map(|x| x.kind), Some(expected[0].kind.clone()));
+	}
+}
@@ -2,11 +2,13 @@ use std::fmt::{Debug, Display};
 
 use anyhow::bail;
 
+use crate::common::span::Span;
+
 // Yes this is all copied from mcvm
 
 /// Create a list of tokens from package text contents that we will
 /// then use for parsing
-pub fn lex(text: &str) -> anyhow::Result<Vec<(Token, TextPos)>> {
+pub fn lex(text: &str) -> anyhow::Result<Vec<TokenAndPos>> {
 	let mut tokens: Vec<(Token, TextPos)> = Vec::new();
 
 	// Positional
@@ -144,6 +146,7 @@ pub fn lex(text: &str) -> anyhow::Result<Vec<(Token, TextPos)>> {
 					} else {
 						repeat = true;
 						tokens.push((tok, tok_start_pos.clone()));
+						tok_start_pos = pos.clone();
 						tok = Token::None;
 					}
 				}
@@ -226,7 +229,24 @@ pub fn lex(text: &str) -> anyhow::Result<Vec<(Token, TextPos)>> {
 		Token::None => {}
 		_ => tokens.push((tok, tok_start_pos.clone())),
 	}
-	Ok(tokens)
+
+	Ok(get_token_spans(tokens, text.chars().count()))
+}
+
+/// Converts token start positions into spans. Whitespace and comments are tokens as well,
+/// so every token ends where the next one starts
+fn get_token_spans(tokens: Vec<(Token, TextPos)>, text_len: usize) -> Vec<TokenAndPos> {
+	let ends: Vec<_> = tokens
+		.iter()
+		.skip(1)
+		.map(|(_, pos)| pos.2)
+		.chain(std::iter::once(text_len))
+		.collect();
+	tokens
+		.into_iter()
+		.zip(ends)
+		.map(|((tok, pos), end)| (tok, Span::new(pos.0, pos.1, pos.2, end.max(pos.2))))
+		.collect()
 }
 
 /// A token that we derive from text
@@ -371,8 +391,8 @@ impl TextPos {
 	}
 }
 
-/// Token and TextPos
-pub type TokenAndPos = (Token, TextPos);
+/// Token and the span of text it came from
+pub type TokenAndPos = (Token, Span);
 
 /// What action to perform after lexing a string character
 #[derive(Debug, PartialEq)]
@@ -400,7 +420,7 @@ fn is_whitespace(c: char) -> bool {
 }
 
 /// Checks if a character is part of a valid identifier
-fn is_ident(c: char, first: bool) -> bool {
+pub(super) fn is_ident(c: char, first: bool) -> bool {
 	if first && c.is_numeric() {
 		return false;
 	}
@@ -611,9 +631,8 @@ mod tests {
 					for (i, ((_, tok_pos), (expected_row, expected_col))) in
 						toks.iter().zip($positions.iter()).enumerate()
 					{
-						// let expected_pos = TextPos(*expected_row, *expected_col);
-						assert_eq!(tok_pos.0, *expected_row as usize, "Index: {i}");
-						assert_eq!(tok_pos.1, *expected_col as usize, "Index: {i}");
+						assert_eq!(tok_pos.line, *expected_row as usize, "Index: {i}");
+						assert_eq!(tok_pos.col, *expected_col as usize, "Index: {i}");
 					}
 				}
 				Err(e) => {
@@ -1,31 +1,48 @@
+pub mod ast;
 pub mod lex;
+mod named;
 mod parse;
-
-use std::collections::HashMap;
+pub mod unparse;
+pub mod version;
 
 use anyhow::{bail, Context};
 
+use crate::common::diag::WithSpan;
 use crate::common::function::{
-	FunctionAnnotations, FunctionInterface, FunctionSignature, ReturnType,
+	FunctionAnnotations, FunctionInterface, FunctionSignature, InvocationFrequency, ReturnType,
 };
-use crate::ir::{Block, IRFunction, IR};
+use crate::common::lint::Lint;
+use crate::common::mc::time::{Time, TimeUnit};
+use crate::common::span::Span;
+use crate::common::storage::StorageLifetime;
+use crate::common::ResourceLocation;
+use crate::ir::{Block, IRFunction, Instruction, IR};
 use crate::parse::lex::{Side, Token};
 use crate::parse::parse::{parse_body, parse_simple_ty, UnparsedBody};
 
 use self::lex::{lex, reduce_tokens};
+pub use self::named::NamedTypeKind;
+
+use self::named::{resolve_names, NamedTypes};
+use self::version::{check_version, migrate_tokens, read_header, MIGRATIONS};
 
 /// Parser for IR
 pub struct Parser {
 	ir: IR,
+	/// Flag sets and enums that have been declared, which can be used by the files parsed after them
+	named_types: NamedTypes,
 }
 
 impl Parser {
 	pub fn new() -> Self {
-		Self { ir: IR::new() }
+		Self {
+			ir: IR::new(),
+			named_types: NamedTypes::default(),
+		}
 	}
 
 	pub fn parse(&mut self, text: &str) -> anyhow::Result<()> {
-		parse_definitions(&mut self.ir, text)
+		parse_definitions(&mut self.ir, &mut self.named_types, text)
 	}
 
 	pub fn finish(self) -> IR {
@@ -39,12 +56,46 @@ impl Default for Parser {
 	}
 }
 
-fn parse_definitions(ir: &mut IR, text: &str) -> anyhow::Result<()> {
+/// Parses a list of instructions that are not inside of a function
+pub fn parse_instrs(text: &str) -> anyhow::Result<Vec<Instruction>> {
+	let lexed = lex(text).context("Failed to lex text")?;
+	let body: UnparsedBody = reduce_tokens(lexed.iter()).cloned().collect();
+	parse_body(body).context("Failed to parse instructions")
+}
+
+fn parse_definitions(
+	ir: &mut IR,
+	named_types: &mut NamedTypes,
+	text: &str,
+) -> anyhow::Result<()> {
+	let mut span = None;
+	parse_definitions_impl(ir, named_types, text, &mut span).with_span(span)
+}
+
+/// Parses the definitions in a file, keeping track of the span of the current token
+fn parse_definitions_impl(
+	ir: &mut IR,
+	named_types: &mut NamedTypes,
+	text: &str,
+	current_span: &mut Option<Span>,
+) -> anyhow::Result<()> {
 	enum State {
 		Root,
 		LookingForAnnotationOrFunctionName {
 			state: AnnotationState,
 			annotations: FunctionAnnotations,
+			/// A lifetime annotation, which means that this is a storage declaration
+			lifetime: Option<StorageLifetime>,
+		},
+		Storage {
+			lifetime: StorageLifetime,
+			storage: Option<ResourceLocation>,
+		},
+		/// A declaration of a flag set or enum, with the tokens inside of its body once it starts
+		NamedType {
+			kind: NamedTypeKind,
+			name: Option<String>,
+			body: Option<UnparsedBody>,
 		},
 		LookingForOpeningCurly {
 			interface: FunctionInterface,
@@ -60,22 +111,63 @@ fn parse_definitions(ir: &mut IR, text: &str) -> anyhow::Result<()> {
 	enum AnnotationState {
 		LookingForAt,
 		LookingForName,
+		/// The argument of an annotation, and whether it is in parentheses
+		Argument(String, bool),
+		/// The amount of a time argument, waiting for its unit
+		TimeUnit(String, f32, bool),
+		ClosingParen,
 	}
 
-	let mut unparsed_defs = HashMap::new();
+	let end_argument = |parens: bool| {
+		if parens {
+			AnnotationState::ClosingParen
+		} else {
+			AnnotationState::LookingForAt
+		}
+	};
+
+	let mut unparsed_defs = Vec::new();
 
 	let mut state = State::Root;
 
-	let lexed = lex(text).context("Failed to lex text")?;
-	for (tok, pos) in reduce_tokens(lexed.iter()) {
+	let mut lexed = lex(text).context("Failed to lex text")?;
+	// Files without a version header are assumed to be on the current version
+	let body_start = if let Some(header) = read_header(&lexed)? {
+		check_version(header.version)?;
+		migrate_tokens(&mut lexed[header.len..], header.version, MIGRATIONS);
+		header.len
+	} else {
+		0
+	};
+	for (tok, pos) in reduce_tokens(lexed[body_start..].iter()) {
+		*current_span = Some(*pos);
 		match &mut state {
 			State::Root => match tok {
 				Token::At => {
 					state = State::LookingForAnnotationOrFunctionName {
 						state: AnnotationState::LookingForName,
 						annotations: FunctionAnnotations::new(),
+						lifetime: None,
 					}
 				}
+				Token::Ident(ident) if ident == "storage" => {
+					state = State::Storage {
+						lifetime: StorageLifetime::default(),
+						storage: None,
+					};
+				}
+				Token::Ident(ident) if ident == "flags" || ident == "enum" => {
+					let kind = if ident == "flags" {
+						NamedTypeKind::Flags
+					} else {
+						NamedTypeKind::Enum
+					};
+					state = State::NamedType {
+						kind,
+						name: None,
+						body: None,
+					};
+				}
 				Token::Str(name) => {
 					state = State::LookingForOpeningCurly {
 						interface: FunctionInterface::new(name.clone().into()),
@@ -87,9 +179,19 @@ fn parse_definitions(ir: &mut IR, text: &str) -> anyhow::Result<()> {
 			State::LookingForAnnotationOrFunctionName {
 				state: ann_state,
 				annotations,
+				lifetime,
 			} => match ann_state {
 				AnnotationState::LookingForAt => match tok {
 					Token::At => *ann_state = AnnotationState::LookingForName,
+					Token::Ident(ident) if ident == "storage" => {
+						state = State::Storage {
+							lifetime: lifetime.unwrap_or_default(),
+							storage: None,
+						};
+					}
+					Token::Str(..) if lifetime.is_some() => {
+						bail!("Storage lifetime annotations cannot be used on functions {pos}")
+					}
 					// Function start
 					Token::Str(name) => {
 						state = State::LookingForOpeningCurly {
@@ -105,17 +207,114 @@ fn parse_definitions(ir: &mut IR, text: &str) -> anyhow::Result<()> {
 				},
 				AnnotationState::LookingForName => match tok {
 					Token::Ident(name) => {
+						*ann_state = AnnotationState::LookingForAt;
+						if let Some(new_lifetime) = StorageLifetime::parse(name) {
+							*lifetime = Some(new_lifetime);
+							continue;
+						}
 						match name.as_str() {
 							"preserve" => annotations.preserve = true,
 							"no_inline" => annotations.no_inline = true,
 							"no_strip" => annotations.no_strip = true,
 							"unused_result" => annotations.unused_result = true,
+							"test" => annotations.test = true,
+							"public" => annotations.public = true,
+							"private" => annotations.public = false,
+							"tick" => annotations.tick = true,
+							"load" => annotations.load = true,
+							"freq" | "cost" | "order" | "allow" | "export" => {
+								*ann_state = AnnotationState::Argument(name.clone(), false)
+							}
 							other => bail!("Unknown annotation {other}"),
 						};
-						*ann_state = AnnotationState::LookingForAt;
 					}
 					_ => bail!("Unexpected token {tok:?} {pos}"),
 				},
+				AnnotationState::Argument(name, parens) => {
+					match (name.as_str(), tok) {
+						(_, Token::Paren(Side::Left)) if !*parens => {
+							*parens = true;
+							continue;
+						}
+						("freq", Token::Ident(freq)) => {
+							let freq = InvocationFrequency::parse(freq)
+								.with_context(|| format!("Unknown frequency {freq} {pos}"))?;
+							annotations.freq = Some(freq);
+						}
+						("freq", Token::Num(..) | Token::Decimal(..)) => {
+							let amount = match tok {
+								Token::Num(num) => *num as f32,
+								Token::Decimal(num) => *num as f32,
+								_ => unreachable!(),
+							};
+							*ann_state = AnnotationState::TimeUnit(name.clone(), amount, *parens);
+							continue;
+						}
+						("cost", Token::Num(cost)) => {
+							let cost = (*cost).try_into().context("Cost is not a u32")?;
+							annotations.cost = Some(cost);
+						}
+						("order", Token::Num(order)) => {
+							let order = (*order).try_into().context("Order is not an i32")?;
+							annotations.order = Some(order);
+						}
+						("allow", Token::Ident(lint)) => {
+							let lint = Lint::parse(lint)
+								.with_context(|| format!("Unknown lint {lint} {pos}"))?;
+							annotations.allow.push(lint);
+						}
+						("export", Token::Str(id)) => {
+							if !id.contains(':') {
+								bail!("Exported name {id} must have a namespace {pos}");
+							}
+							annotations.export = Some(id.clone().into());
+						}
+						_ => bail!("Unexpected token {tok:?} {pos}"),
+					}
+					*ann_state = end_argument(*parens);
+				}
+				AnnotationState::TimeUnit(name, amount, parens) => {
+					let Token::Ident(suffix) = tok else {
+						bail!("Unexpected token {tok:?} {pos}");
+					};
+					let unit = TimeUnit::parse(suffix)
+						.with_context(|| format!("Unknown time unit suffix {suffix} {pos}"))?;
+					let ticks = Time::new(*amount, unit)
+						.get_whole_ticks()
+						.with_context(|| format!("Invalid time for annotation {name} {pos}"))?;
+					if ticks == 0 {
+						bail!("Invocation interval cannot be zero {pos}");
+					}
+					annotations.freq = Some(InvocationFrequency::Interval(ticks));
+					*ann_state = end_argument(*parens);
+				}
+				AnnotationState::ClosingParen => match tok {
+					Token::Paren(Side::Right) => *ann_state = AnnotationState::LookingForAt,
+					_ => bail!("Unexpected token {tok:?} {pos}"),
+				},
+			},
+			State::Storage { lifetime, storage } => match (tok, &storage) {
+				(Token::Str(name), None) => *storage = Some(name.clone().into()),
+				(Token::Semicolon, Some(name)) => {
+					if let Some(existing) = ir.storages.get(name) {
+						if existing != lifetime {
+							bail!("Storage {name} was already declared with a different lifetime {pos}");
+						}
+					}
+					ir.storages.insert(name.clone(), *lifetime);
+					state = State::Root;
+				}
+				_ => bail!("Unexpected token {tok:?} {pos}"),
+			},
+			State::NamedType { kind, name, body } => match (tok, &name, body.as_mut()) {
+				(Token::Ident(ident), None, None) => *name = Some(ident.clone()),
+				(Token::Curly(Side::Left), Some(..), None) => *body = Some(UnparsedBody::new()),
+				(Token::Curly(Side::Right), Some(name), Some(body)) => {
+					named_types.declare(*kind, name, body)?;
+					state = State::Root;
+				}
+				(other, Some(..), Some(body)) => body.push((other.clone(), *pos)),
+				_ => bail!("Unexpected token {tok:?} {pos}"),
 			},
 			State::LookingForOpeningCurly {
 				interface,
@@ -156,24 +355,29 @@ fn parse_definitions(ir: &mut IR, text: &str) -> anyhow::Result<()> {
 			} => match tok {
 				Token::Curly(Side::Left) => {
 					*curly_count += 1;
-					body.push((tok.clone(), pos.clone()));
+					body.push((tok.clone(), *pos));
 				}
 				Token::Curly(Side::Right) => {
 					*curly_count -= 1;
 					if *curly_count == 0 {
-						unparsed_defs.insert(std::mem::take(func), std::mem::take(body));
+						unparsed_defs.push((std::mem::take(func), std::mem::take(body)));
 						state = State::Root;
 					} else {
-						body.push((tok.clone(), pos.clone()));
+						body.push((tok.clone(), *pos));
 					}
 				}
-				other => body.push((other.clone(), pos.clone())),
+				other => body.push((other.clone(), *pos)),
 			},
 		}
 	}
 
-	// Parse function bodies
+	// Parse function bodies. Instructions have their own spans
+	*current_span = None;
 	for (interface, body) in unparsed_defs {
+		if ir.functions.contains_key(&interface.id) {
+			bail!("Function {} is defined more than once", interface.id);
+		}
+		let body = resolve_names(body, named_types)?;
 		let body = parse_body(body).context("Failed to parse function body")?;
 		let mut block = Block::new();
 		block.contents = body;
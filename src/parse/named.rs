@@ -0,0 +1,242 @@
+use anyhow::{bail, Context};
+use rustc_hash::FxHashMap;
+
+use crate::common::span::Span;
+use crate::common::ty::MAX_FLAG_BITS;
+
+use super::lex::{Token, TokenAndPos};
+
+/// Types with named values, which can be used by the files parsed after them
+#[derive(Debug, Clone, Default)]
+pub struct NamedTypes {
+	flag_sets: FxHashMap<String, FlagSet>,
+	enums: FxHashMap<String, EnumDecl>,
+}
+
+/// The different kinds of declarations of named types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedTypeKind {
+	Flags,
+	Enum,
+}
+
+impl NamedTypes {
+	/// Parses and adds the declaration of a named type from the tokens in its body
+	pub fn declare(
+		&mut self,
+		kind: NamedTypeKind,
+		name: &str,
+		body: &[TokenAndPos],
+	) -> anyhow::Result<()> {
+		if self.flag_sets.contains_key(name) || self.enums.contains_key(name) {
+			bail!("Type {name} is declared more than once");
+		}
+		match kind {
+			NamedTypeKind::Flags => {
+				let set = FlagSet::parse(body)
+					.with_context(|| format!("Failed to parse flag set {name}"))?;
+				self.flag_sets.insert(name.into(), set);
+			}
+			NamedTypeKind::Enum => {
+				let decl = EnumDecl::parse(body)
+					.with_context(|| format!("Failed to parse enum {name}"))?;
+				self.enums.insert(name.into(), decl);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Gets the tokens that a value of a named type stands for. Flags become the
+	/// number of their bit and enum variants become a score literal
+	fn get_value(&self, ty: &str, value: &str, pos: Span) -> anyhow::Result<Vec<TokenAndPos>> {
+		if let Some(set) = self.flag_sets.get(ty) {
+			let bit = set.bits.get(value).context("Unknown flag")?;
+			Ok(vec![(Token::Num((*bit).into()), pos)])
+		} else if let Some(decl) = self.enums.get(ty) {
+			let variant = decl.variants.iter().position(|x| x == value);
+			let variant = variant.context("Unknown enum variant")?;
+			Ok(vec![
+				(Token::Num(variant as i128), pos),
+				(Token::Ident("s".into()), pos),
+			])
+		} else {
+			bail!("Unknown type")
+		}
+	}
+}
+
+/// Named bits of flags, declared with `flags Name { bit=0, other=1 }`
+#[derive(Debug, Clone)]
+pub struct FlagSet {
+	bits: FxHashMap<String, u8>,
+}
+
+impl FlagSet {
+	/// Parses the declarations of the bits between the curly braces of a flag set
+	pub fn parse(toks: &[TokenAndPos]) -> anyhow::Result<Self> {
+		let mut bits = FxHashMap::default();
+		for decl in toks.split(|x| x.0 == Token::Comma) {
+			let (name, bit) = match decl {
+				[] => continue,
+				[(Token::Ident(name), _), (Token::Equal, _), (Token::Num(bit), pos)] => {
+					let bit = u8::try_from(*bit)
+						.ok()
+						.filter(|x| *x < MAX_FLAG_BITS)
+						.with_context(|| format!("Flag bit {bit} is out of range {pos}"))?;
+					(name, bit)
+				}
+				[(tok, pos), ..] => bail!("Unexpected token {tok:?} {pos}"),
+			};
+			if bits.values().any(|x| *x == bit) {
+				bail!("Bit {bit} is used by more than one flag");
+			}
+			if bits.insert(name.clone(), bit).is_some() {
+				bail!("Flag {name} is declared more than once");
+			}
+		}
+		if bits.is_empty() {
+			bail!("Flag set has no flags");
+		}
+
+		Ok(Self { bits })
+	}
+
+	/// Gets the number of bits needed to store all of the flags
+	pub fn get_bit_count(&self) -> u8 {
+		self.bits.values().max().map(|x| x + 1).unwrap_or_default()
+	}
+}
+
+/// Variants of an enum, declared with `enum Name { First, Second }`. The variants
+/// are numbered in the order that they are declared in
+#[derive(Debug, Clone)]
+pub struct EnumDecl {
+	variants: Vec<String>,
+}
+
+impl EnumDecl {
+	/// Parses the variants between the curly braces of an enum
+	pub fn parse(toks: &[TokenAndPos]) -> anyhow::Result<Self> {
+		let mut variants: Vec<String> = Vec::new();
+		for decl in toks.split(|x| x.0 == Token::Comma) {
+			let name = match decl {
+				[] => continue,
+				[(Token::Ident(name), _)] => name,
+				[(tok, pos), ..] => bail!("Unexpected token {tok:?} {pos}"),
+			};
+			if variants.contains(name) {
+				bail!("Variant {name} is declared more than once");
+			}
+			variants.push(name.clone());
+		}
+		if variants.is_empty() {
+			bail!("Enum has no variants");
+		}
+
+		Ok(Self { variants })
+	}
+}
+
+/// Replaces the names of named types and their values in the tokens of a function body
+/// with the numbers they stand for. `Name::value` becomes the number of the flag bit
+/// or enum variant, and `flags<Name>` and `enum<Name>` become types with enough room
+/// for all of the values
+pub fn resolve_names(
+	toks: Vec<TokenAndPos>,
+	types: &NamedTypes,
+) -> anyhow::Result<Vec<TokenAndPos>> {
+	let mut out = Vec::with_capacity(toks.len());
+	let mut i = 0;
+	while i < toks.len() {
+		match &toks[i..] {
+			[(Token::Ident(ty), pos), (Token::Colon, _), (Token::Colon, _), (Token::Ident(value), _), ..] =>
+			{
+				let value = types
+					.get_value(ty, value, *pos)
+					.with_context(|| format!("Failed to resolve {ty}::{value} {pos}"))?;
+				out.extend(value);
+				i += 4;
+				continue;
+			}
+			[(Token::Ident(kind), _), (Token::Angle(..), _), (Token::Ident(name), pos), ..]
+				if kind == "flags" || kind == "enum" =>
+			{
+				let size = if kind == "flags" {
+					let set = types.flag_sets.get(name);
+					set.map(|x| x.get_bit_count().into())
+				} else {
+					let decl = types.enums.get(name);
+					decl.map(|x| x.variants.len())
+				};
+				let size = size.with_context(|| format!("Unknown {kind} type {name} {pos}"))?;
+				out.extend_from_slice(&toks[i..i + 2]);
+				out.push((Token::Num(size as i128), *pos));
+				i += 3;
+				continue;
+			}
+			_ => out.push(toks[i].clone()),
+		}
+		i += 1;
+	}
+
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::parse::lex::{lex, reduce_tokens};
+
+	fn toks(text: &str) -> Vec<TokenAndPos> {
+		let lexed = lex(text).expect("Failed to lex");
+		reduce_tokens(lexed.iter()).cloned().collect()
+	}
+
+	#[test]
+	fn test_flag_names() {
+		let set = FlagSet::parse(&toks("visible=0, glowing=3,")).expect("Failed to parse set");
+		assert_eq!(set.get_bit_count(), 4);
+		assert!(FlagSet::parse(&toks("a=0, b=0")).is_err());
+		assert!(FlagSet::parse(&toks("a=31")).is_err());
+
+		let mut types = NamedTypes::default();
+		types
+			.declare(NamedTypeKind::Flags, "Vis", &toks("visible=0, glowing=3"))
+			.expect("Failed to declare");
+		let body = toks("let x: flags<Vis> = val 0s; ftgl %x, Vis::glowing");
+		let body = resolve_names(body, &types).expect("Failed to resolve");
+		let body: Vec<_> = body.into_iter().map(|x| x.0).collect();
+		assert_eq!(body[5], Token::Num(4));
+		assert_eq!(body.last(), Some(&Token::Num(3)));
+
+		let body = toks("ftgl %x, Vis::hidden");
+		assert!(resolve_names(body, &types).is_err());
+	}
+
+	#[test]
+	fn test_enum_names() {
+		assert!(EnumDecl::parse(&toks("A, B, A")).is_err());
+		assert!(EnumDecl::parse(&toks("")).is_err());
+
+		let mut types = NamedTypes::default();
+		types
+			.declare(NamedTypeKind::Enum, "Phase", &toks("Idle, Active, Done,"))
+			.expect("Failed to declare");
+		assert!(types
+			.declare(NamedTypeKind::Flags, "Phase", &toks("a=0"))
+			.is_err());
+		let body = toks("let x: enum<Phase> = val Phase::Done");
+		let body = resolve_names(body, &types).expect("Failed to resolve");
+		let body: Vec<_> = body.into_iter().map(|x| x.0).collect();
+		assert_eq!(body[5], Token::Num(3));
+		assert_eq!(
+			body[body.len() - 2..],
+			[Token::Num(2), Token::Ident("s".into())]
+		);
+
+		assert!(resolve_names(toks("val Phase::Paused"), &types).is_err());
+		assert!(resolve_names(toks("let x: enum<Other> = null"), &types).is_err());
+	}
+}
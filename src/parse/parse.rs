@@ -1,36 +1,53 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::{bail, Context};
+use num_traits::NumCast;
 use rustc_hash::FxHashMap;
 
 use crate::common::condition::Condition;
+use crate::common::diag::WithSpan;
 use crate::common::function::CallInterface;
+use crate::common::lint::Lint;
 use crate::common::mc::block::{
-	BlockData, BlockProperties, BlockStateValue, BlockStates, SetBlockData, SetBlockMode,
+	BlockData, BlockProperties, BlockStateValue, BlockStates, CloneData, CloneMaskMode, CloneMode,
+	FillData, FillMode, SetBlockData, SetBlockMode,
+};
+use crate::common::mc::bossbar::{BossbarOption, BossbarQuery};
+use crate::common::mc::entity::{
+	EffectDuration, Selector, SelectorParameter, SelectorSort, SelectorType, UUID,
 };
-use crate::common::mc::entity::{EffectDuration, SelectorParameter, SelectorType, TargetSelector};
 use crate::common::mc::instr::MinecraftInstr;
-use crate::common::mc::item::ItemData;
-use crate::common::mc::modifier::{MIRModifier, StoreDataType, StoreModLocation};
+use crate::common::mc::item::{ItemData, ItemModifyLocation};
+use crate::common::mc::particle::{ParticleData, ParticleMode};
+use crate::common::mc::modifier::{MIRModifier, StoreBossbarMode, StoreDataType, StoreModLocation};
 use crate::common::mc::pos::{
-	AbsOrRelCoord, Angle, DoubleCoordinates, DoubleCoordinates2D, IntCoordinates,
+	AbsOrRelCoord, Angle, Axis, DoubleCoordinates, DoubleCoordinates2D, IntCoordinates,
+};
+use crate::common::mc::scoreboard_and_teams::{
+	CollisionRule, Criterion, NumberFormat, SingleCriterion, TeamOption, TeamVisibility,
+	TEAM_COLORS,
 };
-use crate::common::mc::scoreboard_and_teams::{Criterion, SingleCriterion};
-use crate::common::mc::time::{Time, TimeUnit};
+use crate::common::mc::text::{
+	ClickAction, ClickEvent, HoverEvent, TextComponent, TextComponentContents, TextStyle,
+};
+use crate::common::mc::time::{ScheduleMode, Time, TimeUnit};
 use crate::common::mc::{
-	DataLocation, DataPath, Difficulty, EntityTarget, FullDataLocation, Location, Score,
+	DataLocation, DataPath, Difficulty, EntityTarget, FullDataLocation, Gamemode, Location, Score,
 	SoundSource, XPValue,
 };
+use crate::common::range::{IntRange, Range, RangeEnd, Rangeable};
 use crate::common::reg::Local;
+use crate::common::span::Span;
 use crate::common::ty::{
 	ArraySize, DataType, DataTypeContents, Double, NBTArrayType, NBTArrayTypeContents,
 	NBTCompoundType, NBTCompoundTypeContents, NBTType, NBTTypeContents, ScoreType,
-	ScoreTypeContents,
+	ScoreTypeContents, MAX_FLAG_BITS,
 };
-use crate::common::val::{ArgRetIndex, MutableValue, Value};
-use crate::common::DeclareBinding;
-use crate::ir::{Block, InstrKind, Instruction};
+use crate::common::val::{ArgRetIndex, MutableNBTValue, MutableScoreValue, MutableValue, Value};
+use crate::common::{DeclareBinding, ResourceLocation};
+use crate::ir::{Block, InstrKind, Instruction, LoopCheck};
 
 use super::lex::{Side, Token, TokenAndPos};
 
@@ -57,7 +74,7 @@ pub fn parse_body(body: UnparsedBody) -> anyhow::Result<Vec<Instruction>> {
 	for (i, instr) in split.enumerate() {
 		let instr = parse_instr(&mut instr.iter())
 			.with_context(|| format!("Failed to parse instruction {i}"))?;
-		out.extend(instr.map(Instruction::new));
+		out.extend(instr);
 	}
 
 	Ok(out)
@@ -146,19 +163,72 @@ macro_rules! consume_optional_extract {
 
 fn parse_instr<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<Option<InstrKind>> {
+) -> anyhow::Result<Option<Instruction>> {
+	let mut toks = SpanTracker::new(toks);
 	let first_tok = toks.next();
-	if let Some(first_tok) = first_tok {
-		parse_instr_impl(first_tok, toks)
-	} else {
-		Ok(None)
+	let Some(first_tok) = first_tok else {
+		return Ok(None);
+	};
+	let instr = parse_instr_impl(first_tok, &mut toks).with_span(toks.last)?;
+	Ok(instr.map(|mut instr| {
+		instr.span = toks.span();
+		instr
+	}))
+}
+
+/// Iterator over tokens that remembers the spans of the first and last tokens it gave out.
+/// The inner iterator is dynamic so that nested instructions don't create new types forever
+struct SpanTracker<'i, 't> {
+	inner: &'i mut dyn Iterator<Item = &'t TokenAndPos>,
+	first: Option<Span>,
+	last: Option<Span>,
+}
+
+impl<'i, 't> SpanTracker<'i, 't> {
+	fn new(inner: &'i mut dyn Iterator<Item = &'t TokenAndPos>) -> Self {
+		Self {
+			inner,
+			first: None,
+			last: None,
+		}
+	}
+
+	/// Gets the span of all of the tokens given out so far
+	fn span(&self) -> Option<Span> {
+		self.first.map(|first| first.to(&self.last.unwrap_or(first)))
+	}
+}
+
+impl<'i, 't> Iterator for SpanTracker<'i, 't> {
+	type Item = &'t TokenAndPos;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let next = self.inner.next()?;
+		self.first.get_or_insert(next.1);
+		self.last = Some(next.1);
+		Some(next)
 	}
 }
 
 fn parse_instr_impl<'t>(
 	first_tok: &TokenAndPos,
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<Option<InstrKind>> {
+) -> anyhow::Result<Option<Instruction>> {
+	// Lints allowed on just this instruction (@allow lint_name instr)
+	if let Token::At = &first_tok.0 {
+		let directive = consume_extract!(toks, Ident, { bail!("Missing directive name") });
+		if directive != "allow" {
+			bail!("Unknown instruction directive {directive}");
+		}
+		let lint = consume_extract!(toks, Ident, { bail!("Missing lint name") });
+		let lint = Lint::parse(lint).with_context(|| format!("Unknown lint {lint}"))?;
+		let next = consume!(toks, { bail!("Missing instruction after directive") });
+		let mut instr =
+			parse_instr_impl(next, toks)?.context("Missing instruction after directive")?;
+		instr.allowed_lints.push(lint);
+		return Ok(Some(instr));
+	}
+
 	let Token::Ident(instr) = &first_tok.0 else {
 		bail!("Unexpected token for instruction");
 	};
@@ -169,6 +239,19 @@ fn parse_instr_impl<'t>(
 			let (l, r) = parse_simple_op(toks)?;
 			Ok(InstrKind::Assign { left: l, right: r })
 		}
+		"cast" => {
+			let left = parse_mut_val(toks).context("Failed to parse cast destination")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let DeclareBinding::Cast(ty, right, scale) = parse_cast(toks)? else {
+				unreachable!("Cast should parse to a cast binding");
+			};
+			Ok(InstrKind::Cast {
+				left,
+				ty,
+				right,
+				scale,
+			})
+		}
 		"add" => {
 			let (l, r) = parse_simple_op(toks)?;
 			Ok(InstrKind::Add { left: l, right: r })
@@ -197,6 +280,34 @@ fn parse_instr_impl<'t>(
 			let (l, r) = parse_simple_op(toks)?;
 			Ok(InstrKind::Max { left: l, right: r })
 		}
+		"band" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::BitAnd { left: l, right: r })
+		}
+		"bor" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::BitOr { left: l, right: r })
+		}
+		"bxor" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::BitXor { left: l, right: r })
+		}
+		"shl" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::Shl { left: l, right: r })
+		}
+		"shr" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::Shr { left: l, right: r })
+		}
+		"uuidstr" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::UUIDToString { left: l, right: r })
+		}
+		"struuid" => {
+			let (l, r) = parse_simple_op(toks)?;
+			Ok(InstrKind::StringToUUID { left: l, right: r })
+		}
 		"and" => {
 			let (l, r) = parse_simple_op(toks)?;
 			Ok(InstrKind::And { left: l, right: r })
@@ -217,6 +328,7 @@ fn parse_instr_impl<'t>(
 			let val = parse_mut_val(toks).context("Failed to parse value")?;
 			Ok(InstrKind::Remove { val })
 		}
+		"copy" => parse_copy_data(toks),
 		"abs" => {
 			let val = parse_mut_val(toks).context("Failed to parse value")?;
 			Ok(InstrKind::Abs { val })
@@ -229,6 +341,38 @@ fn parse_instr_impl<'t>(
 			let (l, r) = parse_pow(toks)?;
 			Ok(InstrKind::Pow { base: l, exp: r })
 		}
+		"rand" => {
+			let left = parse_mut_val(toks).context("Failed to parse value")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let min = consume_extract!(toks, Num, { bail!("Missing minimum") });
+			let min: i32 = (*min).try_into().context("Minimum is not an i32")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let max = consume_extract!(toks, Num, { bail!("Missing maximum") });
+			let max: i32 = (*max).try_into().context("Maximum is not an i32")?;
+			Ok(InstrKind::Rand { left, min, max })
+		}
+		"fget" => {
+			let left = parse_mut_val(toks).context("Failed to parse value")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let flags = parse_mut_val(toks).context("Failed to parse flags")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let bit = parse_flag_bit(toks)?;
+			Ok(InstrKind::GetFlag { left, flags, bit })
+		}
+		"fset" => {
+			let flags = parse_mut_val(toks).context("Failed to parse flags")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let bit = parse_flag_bit(toks)?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let right = parse_val(toks).context("Failed to parse value")?;
+			Ok(InstrKind::SetFlag { flags, bit, right })
+		}
+		"ftgl" => {
+			let flags = parse_mut_val(toks).context("Failed to parse flags")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let bit = parse_flag_bit(toks)?;
+			Ok(InstrKind::ToggleFlag { flags, bit })
+		}
 		"use" => {
 			let val = parse_mut_val(toks).context("Failed to parse value")?;
 			Ok(InstrKind::Use { val })
@@ -280,6 +424,27 @@ fn parse_instr_impl<'t>(
 				message: msg.clone(),
 			}))
 		}
+		"tellraw" | "title" | "subtitle" | "actionbar" => {
+			let target = parse_entity_target(toks).context("Failed to parse target")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let text = parse_text_component(toks).context("Failed to parse text component")?;
+			let instr = match instr.as_str() {
+				"tellraw" => MinecraftInstr::TellRaw {
+					target,
+					message: text,
+				},
+				"title" => MinecraftInstr::Title {
+					target,
+					title: text,
+				},
+				"subtitle" => MinecraftInstr::Subtitle {
+					target,
+					subtitle: text,
+				},
+				_ => MinecraftInstr::ActionBar { target, text },
+			};
+			Ok(InstrKind::MC(instr))
+		}
 		"me" => {
 			let msg = consume_extract!(toks, Str, { bail!("Missing message") });
 			Ok(InstrKind::MC(MinecraftInstr::Me {
@@ -329,6 +494,10 @@ fn parse_instr_impl<'t>(
 		}
 		"specs" => Ok(InstrKind::MC(MinecraftInstr::SpectateStop)),
 		"if" => parse_if(toks).context("Failed to parse if"),
+		"while" => parse_loop(toks, false, LoopCheck::Before).context("Failed to parse while"),
+		"until" => parse_loop(toks, true, LoopCheck::Before).context("Failed to parse until"),
+		"dow" => parse_loop(toks, false, LoopCheck::After).context("Failed to parse do while"),
+		"switch" => parse_switch(toks).context("Failed to parse switch"),
 		"ife" => {
 			let condition = parse_condition(toks).context("Failed to parse if else condition")?;
 			let first = parse_nested_block(toks).context("Failed to parse if else first body")?;
@@ -454,6 +623,125 @@ fn parse_instr_impl<'t>(
 			}))
 		}
 		"sbol" => Ok(InstrKind::MC(MinecraftInstr::ListScoreboardObjectives)),
+		"sbodn" => {
+			let obj = consume_extract!(toks, Str, { bail!("Missing objective") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let name = parse_text_component(toks).context("Failed to parse display name")?;
+			Ok(InstrKind::MC(MinecraftInstr::SetObjectiveDisplayName {
+				objective: obj.clone(),
+				name,
+			}))
+		}
+		"sbonf" => {
+			let obj = consume_extract!(toks, Str, { bail!("Missing objective") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let format = consume_extract!(toks, Ident, { bail!("Missing number format") });
+			let format = match format.as_str() {
+				"reset" => None,
+				"blank" => Some(NumberFormat::Blank),
+				"fixed" => Some(NumberFormat::Fixed(
+					parse_text_component(toks).context("Failed to parse text component")?,
+				)),
+				"styled" => Some(NumberFormat::Styled(
+					parse_text_style(toks).context("Failed to parse text style")?,
+				)),
+				other => bail!("Unknown number format {other}"),
+			};
+			Ok(InstrKind::MC(MinecraftInstr::SetObjectiveNumberFormat {
+				objective: obj.clone(),
+				format,
+			}))
+		}
+		"teama" => {
+			let team = consume_extract!(toks, Str, { bail!("Missing team") });
+			let display_name = match consume_optional!(toks) {
+				Some((Token::Comma, ..)) => {
+					Some(parse_text_component(toks).context("Failed to parse display name")?)
+				}
+				Some((other, pos)) => bail!("Unexpected token {other:?} {pos}"),
+				None => None,
+			};
+			Ok(InstrKind::MC(MinecraftInstr::AddTeam {
+				team: team.clone(),
+				display_name,
+			}))
+		}
+		"teamr" => {
+			let team = consume_extract!(toks, Str, { bail!("Missing team") });
+			Ok(InstrKind::MC(MinecraftInstr::RemoveTeam {
+				team: team.clone(),
+			}))
+		}
+		"teame" => {
+			let team = consume_extract!(toks, Str, { bail!("Missing team") });
+			Ok(InstrKind::MC(MinecraftInstr::EmptyTeam {
+				team: team.clone(),
+			}))
+		}
+		"teamj" => {
+			let team = consume_extract!(toks, Str, { bail!("Missing team") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let target = parse_entity_target(toks).context("Failed to parse target")?;
+			Ok(InstrKind::MC(MinecraftInstr::JoinTeam {
+				team: team.clone(),
+				target,
+			}))
+		}
+		"teaml" => {
+			let target = parse_entity_target(toks).context("Failed to parse target")?;
+			Ok(InstrKind::MC(MinecraftInstr::LeaveTeam { target }))
+		}
+		"teamls" => {
+			let team = match consume_optional!(toks) {
+				Some((Token::Str(team), ..)) => Some(team.clone()),
+				Some((other, pos)) => bail!("Unexpected token {other:?} {pos}"),
+				None => None,
+			};
+			Ok(InstrKind::MC(MinecraftInstr::ListTeams { team }))
+		}
+		"teamm" => {
+			let team = consume_extract!(toks, Str, { bail!("Missing team") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let option = parse_team_option(toks).context("Failed to parse team option")?;
+			Ok(InstrKind::MC(MinecraftInstr::ModifyTeam {
+				team: team.clone(),
+				option,
+			}))
+		}
+		"bba" => {
+			let id = consume_extract!(toks, Str, { bail!("Missing bossbar") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let name = parse_text_component(toks).context("Failed to parse name")?;
+			Ok(InstrKind::MC(MinecraftInstr::AddBossbar {
+				id: id.clone().into(),
+				name,
+			}))
+		}
+		"bbr" => {
+			let id = consume_extract!(toks, Str, { bail!("Missing bossbar") });
+			Ok(InstrKind::MC(MinecraftInstr::RemoveBossbar {
+				id: id.clone().into(),
+			}))
+		}
+		"bbs" => {
+			let id = consume_extract!(toks, Str, { bail!("Missing bossbar") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let option = parse_bossbar_option(toks).context("Failed to parse bossbar option")?;
+			Ok(InstrKind::MC(MinecraftInstr::SetBossbar {
+				id: id.clone().into(),
+				option,
+			}))
+		}
+		"bbg" => {
+			let id = consume_extract!(toks, Str, { bail!("Missing bossbar") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let query = consume_extract!(toks, Ident, { bail!("Missing bossbar query") });
+			let query = BossbarQuery::parse(query).context("Invalid bossbar query")?;
+			Ok(InstrKind::MC(MinecraftInstr::GetBossbar {
+				id: id.clone().into(),
+				query,
+			}))
+		}
 		"wbg" => Ok(InstrKind::MC(MinecraftInstr::WorldBorderGet)),
 		"cmd" => {
 			let cmd = consume_extract!(toks, Str, { bail!("Missing command") });
@@ -461,6 +749,18 @@ fn parse_instr_impl<'t>(
 				command: cmd.clone(),
 			})
 		}
+		"icmd" => {
+			let cmd = consume_extract!(toks, Str, { bail!("Missing command") });
+			Ok(InstrKind::InterpolatedCommand {
+				command: cmd.clone(),
+			})
+		}
+		"mcmd" => {
+			let cmd = consume_extract!(toks, Str, { bail!("Missing command") });
+			Ok(InstrKind::MacroCommand {
+				command: cmd.clone(),
+			})
+		}
 		"cmt" => {
 			let cmt = consume_extract!(toks, Str, { bail!("Missing comment") });
 			Ok(InstrKind::Comment {
@@ -501,8 +801,10 @@ fn parse_instr_impl<'t>(
 		}
 		"lspu" => Ok(InstrKind::MC(MinecraftInstr::ListPlayerUUIDs)),
 		"call" => {
-			// Return
+			// Return values, which are either followed by run or destructured with
+			// an equal sign (call %a, %b = "foo:bar" %x)
 			let mut ret = Vec::new();
+			let mut func = None;
 			loop {
 				let first_tok = consume_optional!(toks);
 				if let Some(first_tok) = first_tok {
@@ -510,6 +812,12 @@ fn parse_instr_impl<'t>(
 						Token::Comma => {
 							parse_mut_val(toks).context("Failed to parse call return value")?
 						}
+						Token::Equal => break,
+						// Calls without any return values can skip the run
+						Token::Str(name) if ret.is_empty() => {
+							func = Some(name);
+							break;
+						}
 						Token::Ident(string) => {
 							if let "run" = string.as_str() {
 								break;
@@ -527,7 +835,10 @@ fn parse_instr_impl<'t>(
 				}
 			}
 
-			let func = consume_extract!(toks, Str, { bail!("Missing function to call") });
+			let func = match func {
+				Some(func) => func,
+				None => consume_extract!(toks, Str, { bail!("Missing function to call") }),
+			};
 
 			// Args
 			let mut args = Vec::new();
@@ -559,19 +870,20 @@ fn parse_instr_impl<'t>(
 				func: func.clone().into(),
 			})
 		}
+		"callw" => {
+			let func = consume_extract!(toks, Str, { bail!("Missing function to call") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let args = parse_val(toks).context("Failed to parse macro arguments")?;
+
+			Ok(InstrKind::CallWith {
+				func: func.clone().into(),
+				args,
+			})
+		}
 		"sboa" => {
 			let obj = consume_extract!(toks, Str, { bail!("Missing objective") });
 			consume_expect!(toks, Comma, { bail!("Missing comma") });
-			let (tok, pos) = consume!(toks, { bail!("Missing criterion") });
-			let criterion = match tok {
-				Token::Str(string) => Criterion::Compound(string.clone()),
-				Token::Ident(ident) => {
-					let criterion =
-						SingleCriterion::parse(ident).context("Unknown criterion type")?;
-					Criterion::Single(criterion)
-				}
-				other => bail!("Unexpected token {other:?} {pos}"),
-			};
+			let criterion = parse_criterion(toks).context("Failed to parse criterion")?;
 			let expect_display_name = consume_optional_expect!(toks, Comma);
 			let display_name = if expect_display_name {
 				Some(consume_extract!(toks, Str, {
@@ -619,6 +931,33 @@ fn parse_instr_impl<'t>(
 				nbt,
 			}))
 		}
+		"smna" => {
+			let entity = consume_extract!(toks, Str, { bail!("Missing entity") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			consume_expect!(toks, Token::Curly(Side::Left), {
+				bail!("Missing NBT opening")
+			});
+			let (_, nbt) = parse_compound_lit(toks).context("Failed to parse NBT")?;
+			// The UUID location is optional
+			let next = consume!(toks, { bail!("Missing instruction body") });
+			let (uuid, body) = if let Token::Comma = next.0 {
+				let uuid = parse_mut_val(toks).context("Failed to parse UUID location")?;
+				let body =
+					parse_nested_block(toks).context("Failed to parse instruction body")?;
+				(Some(uuid), body)
+			} else {
+				let body = parse_nested_block_impl(next, toks)
+					.context("Failed to parse instruction body")?;
+				(None, body)
+			};
+
+			Ok(InstrKind::SummonAs {
+				entity: entity.clone().into(),
+				nbt,
+				uuid,
+				body: Box::new(body),
+			})
+		}
 		"itmg" => {
 			let target = parse_entity_target(toks).context("Failed to parse target")?;
 			consume_expect!(toks, Comma, { bail!("Missing comma") });
@@ -661,17 +1000,38 @@ fn parse_instr_impl<'t>(
 					"infinite" => EffectDuration::Infinite,
 					other => bail!("Unknown effect duration {other}"),
 				},
-				Token::Num(num) => {
-					let num = (*num)
-						.try_into()
-						.context("Effect duration is not within i32 range")?;
-					EffectDuration::Seconds(num)
+				Token::Num(..) | Token::Decimal(..) => {
+					let amount = match tok {
+						Token::Num(num) => *num as f32,
+						Token::Decimal(num) => *num as f32,
+						_ => unreachable!(),
+					};
+					// Durations are in seconds unless they have a time unit
+					let (tok, pos) = consume!(toks, { bail!("Missing comma") });
+					let unit = match tok {
+						Token::Comma => TimeUnit::Seconds,
+						Token::Ident(suffix) => {
+							let unit = TimeUnit::parse(suffix)
+								.with_context(|| format!("Unknown time unit suffix {suffix}"))?;
+							consume_expect!(toks, Comma, { bail!("Missing comma") });
+							unit
+						}
+						other => bail!("Unexpected token {other:?} {pos}"),
+					};
+					let duration = EffectDuration::from_time(&Time::new(amount, unit))
+						.context("Invalid effect duration")?;
+					duration.validate()?;
+					duration
 				}
 				other => bail!("Unexpected token {other:?} {pos}"),
 			};
-			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			if let EffectDuration::Infinite = duration {
+				consume_expect!(toks, Comma, { bail!("Missing comma") });
+			}
 			let amp = consume_extract!(toks, Num, { bail!("Missing amount") });
-			let amp: u8 = (*amp).try_into().context("Amplifier is not a u8")?;
+			let amp: u8 = (*amp)
+				.try_into()
+				.context("Effect amplifier must be between 0 and 255")?;
 			consume_expect!(toks, Comma, { bail!("Missing comma") });
 			let hide_particles =
 				parse_bool(toks).context("Failed to parse hide particles setting")?;
@@ -691,6 +1051,26 @@ fn parse_instr_impl<'t>(
 			let time = parse_time(toks).context("Failed to parse time")?;
 			Ok(InstrKind::MC(MinecraftInstr::SetTime { time }))
 		}
+		"sch" => {
+			let func = consume_extract!(toks, Str, { bail!("Missing function to schedule") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let time = parse_time(toks).context("Failed to parse time")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let mode = consume_extract!(toks, Ident, { bail!("Missing schedule mode") });
+			let mode = ScheduleMode::parse(mode).context("Invalid schedule mode")?;
+
+			Ok(InstrKind::MC(MinecraftInstr::ScheduleFunction {
+				function: func.clone().into(),
+				time,
+				mode,
+			}))
+		}
+		"schc" => {
+			let func = consume_extract!(toks, Str, { bail!("Missing function to clear") });
+			Ok(InstrKind::MC(MinecraftInstr::ScheduleClear {
+				function: func.clone().into(),
+			}))
+		}
 		"retv" => {
 			let idx = consume_extract!(toks, Num, { bail!("Missing return index") });
 			let idx: ArgRetIndex = (*idx).try_into().context("Return index is not a usize")?;
@@ -726,7 +1106,7 @@ fn parse_instr_impl<'t>(
 			consume_expect!(toks, Comma, { bail!("Missing comma") });
 			let dest = parse_double_coords(toks).context("Failed to parse dest location")?;
 			consume_expect!(toks, Comma, { bail!("Missing comma") });
-			let rot = parse_double_coords_2d(toks).context("Failed to parse rotation")?;
+			let rot = parse_rotation(toks).context("Failed to parse rotation")?;
 
 			Ok(InstrKind::MC(MinecraftInstr::TeleportWithRotation {
 				source: src,
@@ -843,6 +1223,20 @@ fn parse_instr_impl<'t>(
 				rule: rule.clone(),
 			}))
 		}
+		"rndv" => {
+			let (range, sequence) = parse_random(toks)?;
+			Ok(InstrKind::MC(MinecraftInstr::RandomValue {
+				range,
+				sequence,
+			}))
+		}
+		"rndr" => {
+			let (range, sequence) = parse_random(toks)?;
+			Ok(InstrKind::MC(MinecraftInstr::RandomRoll {
+				range,
+				sequence,
+			}))
+		}
 		"loc" => {
 			let ty = consume_extract!(toks, Ident, { bail!("Missing location type") });
 			let ty = Location::parse(ty).context("Invalid location type")?;
@@ -880,6 +1274,36 @@ fn parse_instr_impl<'t>(
 				min_volume: *min_volume as f32,
 			}))
 		}
+		"ptc" => {
+			let particle = parse_particle(toks).context("Failed to parse particle")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let pos = parse_double_coords(toks).context("Failed to parse position")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let delta = parse_decimals(toks).context("Failed to parse delta")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let speed = consume_extract!(toks, Decimal, { bail!("Missing speed") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let count = consume_extract!(toks, Num, { bail!("Missing count") });
+			let count = (*count).try_into().context("Count is too large")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let mode = consume_extract!(toks, Ident, { bail!("Missing particle mode") });
+			let mode = ParticleMode::parse(mode).context("Invalid particle mode")?;
+			let viewers = if consume_optional_expect!(toks, Comma) {
+				Some(parse_entity_target(toks).context("Failed to parse viewers")?)
+			} else {
+				None
+			};
+
+			Ok(InstrKind::MC(MinecraftInstr::Particle {
+				particle,
+				pos,
+				delta,
+				speed: *speed,
+				count,
+				mode,
+				viewers,
+			}))
+		}
 		"sb" => {
 			let pos = parse_int_coords(toks).context("Failed to parse position")?;
 			consume_expect!(toks, Comma, { bail!("Missing comma") });
@@ -892,15 +1316,95 @@ fn parse_instr_impl<'t>(
 				data: SetBlockData { pos, block, mode },
 			}))
 		}
+		"fill" => {
+			let start = parse_int_coords(toks).context("Failed to parse start position")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let end = parse_int_coords(toks).context("Failed to parse end position")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let block = parse_block_data(toks).context("Failed to parse block data")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let mode = consume_extract!(toks, Ident, { bail!("Missing mode") });
+			let mode = FillMode::parse(mode).context("Invalid mode")?;
+
+			Ok(InstrKind::MC(MinecraftInstr::Fill {
+				data: FillData {
+					start,
+					end,
+					block,
+					mode,
+				},
+			}))
+		}
+		"cln" => {
+			let start = parse_int_coords(toks).context("Failed to parse start position")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let end = parse_int_coords(toks).context("Failed to parse end position")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let destination =
+				parse_int_coords(toks).context("Failed to parse destination position")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let mask_mode = consume_extract!(toks, Ident, { bail!("Missing mask mode") });
+			let mask_mode = CloneMaskMode::parse(mask_mode).context("Invalid mask mode")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let mode = consume_extract!(toks, Ident, { bail!("Missing mode") });
+			let mode = CloneMode::parse(mode).context("Invalid mode")?;
+
+			Ok(InstrKind::MC(MinecraftInstr::Clone {
+				data: CloneData {
+					source_dimension: None,
+					start,
+					end,
+					target_dimension: None,
+					destination,
+					mask_mode,
+					mode,
+				},
+			}))
+		}
 		"mdf" => {
-			let modi = parse_modifier(toks).context("Failed to parse modifier")?;
-			let body = parse_nested_block(toks).context("Failed to parse instruction body")?;
+			let (modi, next) = parse_modifier(toks).context("Failed to parse modifier")?;
+			// The modifier might have already consumed the start of the body
+			let body = match next {
+				Some(next) => parse_nested_block_impl(next, toks),
+				None => parse_nested_block(toks),
+			}
+			.context("Failed to parse instruction body")?;
 
 			Ok(InstrKind::Modify {
 				modifier: modi,
 				body: Box::new(body),
 			})
 		}
+		"each" => {
+			// Runs the body as each entity, optionally in a certain order
+			let first_tok = consume!(toks, { bail!("Missing each target") });
+			let (sort, first_tok) = match &first_tok.0 {
+				Token::Ident(order) if order != "uuid" => {
+					let sort = match order.as_str() {
+						"nearest" => SelectorSort::Nearest,
+						"furthest" => SelectorSort::Furthest,
+						"shuffled" => SelectorSort::Random,
+						other => bail!("Unknown iteration order {other} {}", first_tok.1),
+					};
+					(Some(sort), consume!(toks, { bail!("Missing each target") }))
+				}
+				_ => (None, first_tok),
+			};
+			let mut target = parse_entity_target_impl(first_tok, toks)
+				.context("Failed to parse entity target")?;
+			if let Some(sort) = sort {
+				let EntityTarget::Selector(sel) = &mut target else {
+					bail!("Iteration order can only be used with selectors");
+				};
+				sel.set_sort(sort);
+			}
+			let body = parse_nested_block(toks).context("Failed to parse instruction body")?;
+
+			Ok(InstrKind::Modify {
+				modifier: MIRModifier::As(target),
+				body: Box::new(body),
+			})
+		}
 		"retr" => {
 			let body = parse_nested_block(toks).context("Failed to parse instruction body")?;
 
@@ -908,14 +1412,79 @@ fn parse_instr_impl<'t>(
 				body: Box::new(body),
 			})
 		}
+		"atomic" => {
+			let body = parse_nested_block(toks).context("Failed to parse instruction body")?;
+
+			Ok(InstrKind::Atomic {
+				body: Box::new(body),
+			})
+		}
 		"ret" => {
 			let value = parse_val(toks).context("Failed to parse value")?;
 			Ok(InstrKind::Return { value })
 		}
+		"label" => {
+			let name = consume_extract!(toks, Str, { bail!("Missing label name") });
+			Ok(InstrKind::Label { name: name.clone() })
+		}
+		"goto" => {
+			let label = consume_extract!(toks, Str, { bail!("Missing label name") });
+			Ok(InstrKind::Goto {
+				label: label.clone(),
+			})
+		}
+		"spread" => {
+			let per_tick = consume_extract!(toks, Num, { bail!("Missing number of items per tick") });
+			let per_tick: u32 = (*per_tick)
+				.try_into()
+				.context("Number of items per tick is out of range")?;
+			if per_tick == 0 {
+				bail!("At least one item must be processed per tick");
+			}
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let item = consume_extract!(toks, Ident, { bail!("Missing item register name") });
+			consume_expect!(toks, Colon, { bail!("Missing colon") });
+			let ty = parse_ty(toks).context("Failed to parse item type")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let list = parse_val(toks).context("Failed to parse list value")?;
+			// The completion callback is optional and comes before the body
+			let next = consume!(toks, { bail!("Missing spread body") });
+			let (on_complete, body) = match &next.0 {
+				Token::Comma => {
+					let func = consume_extract!(toks, Str, { bail!("Missing callback function") });
+					let body = parse_nested_block(toks).context("Failed to parse spread body")?;
+					(Some(func.clone().into()), body)
+				}
+				_ => {
+					let body =
+						parse_nested_block_impl(next, toks).context("Failed to parse spread body")?;
+					(None, body)
+				}
+			};
+
+			Ok(InstrKind::SpreadOverTicks {
+				per_tick,
+				item: item.clone().into(),
+				ty,
+				list,
+				on_complete,
+				body: Box::new(body),
+			})
+		}
+		"assert" => {
+			let condition = parse_condition(toks).context("Failed to parse assert condition")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let message = consume_extract!(toks, Str, { bail!("Missing message") });
+
+			Ok(InstrKind::Assert {
+				condition,
+				message: message.clone(),
+			})
+		}
 		other => bail!("Unknown instruction {other}"),
 	}
 	.context("Failed to parse instruction")?;
-	Ok(Some(instr))
+	Ok(Some(Instruction::new(instr)))
 }
 
 /// Parses a nested block with braces
@@ -923,44 +1492,35 @@ fn parse_nested_block<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<Block> {
 	let first_tok = consume!(toks, { bail!("Missing first nested block token") });
+	parse_nested_block_impl(first_tok, toks)
+}
+
+fn parse_nested_block_impl<'t>(
+	first_tok: &TokenAndPos,
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<Block> {
 	match first_tok.0 {
 		Token::Curly(Side::Left) => {
-			let mut out = Vec::new();
-			loop {
-				let first_tok = consume_optional!(toks);
-				if let Some(first_tok) = first_tok {
-					if let Token::Curly(Side::Right) = first_tok.0 {
-						break;
-					}
-
-					let instr = parse_instr_impl(first_tok, toks)?;
-					if let Some(instr) = instr {
-						out.push(Instruction::new(instr));
-					} else {
-						break;
-					}
-
-					let next = consume_optional!(toks);
-					if let Some(next) = next {
-						match &next.0 {
-							Token::Semicolon => {}
-							Token::Curly(Side::Right) => {
-								break;
-							}
-							other => bail!("Unexpected token {other:?} {}", next.1),
-						}
-					}
-				} else {
-					break;
+			// The tokens of the block are collected first so that instructions with
+			// optional trailing arguments can't consume the separators after them
+			let mut body = UnparsedBody::new();
+			let mut nested_counter = 0;
+			while let Some(tok) = consume_optional!(toks) {
+				match tok.0 {
+					Token::Curly(Side::Left) => nested_counter += 1,
+					Token::Curly(Side::Right) if nested_counter == 0 => break,
+					Token::Curly(Side::Right) => nested_counter -= 1,
+					_ => {}
 				}
+				body.push(tok.clone());
 			}
 
-			Ok(Block::with_contents(out))
+			Ok(Block::with_contents(parse_body(body)?))
 		}
 		Token::Colon => {
 			let instr = parse_instr(toks).context("Failed to parse nested block instruction")?;
 			if let Some(instr) = instr {
-				Ok(Block::from_single(instr))
+				Ok(Block::with_contents(vec![instr]))
 			} else {
 				Ok(Block::new())
 			}
@@ -974,7 +1534,26 @@ fn parse_let<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Re
 	consume_expect!(toks, Colon, { bail!("Missing comma") });
 	let ty = parse_ty(toks).context("Failed to parse register type")?;
 	consume_expect!(toks, Equal, { bail!("Missing equal sign") });
-	let binding = parse_decl_binding(toks).context("Failed to parse declare binding")?;
+	let kind = consume_extract!(toks, Ident, { bail!("Missing declare binding token") });
+	// Registers bound to a criteria objective
+	if kind == "criteria" {
+		if !matches!(ty, DataType::Score(..)) {
+			bail!("Criteria registers must be of score type");
+		}
+		let criterion = parse_criterion(toks).context("Failed to parse criterion")?;
+		let of = consume_extract!(toks, Ident, { bail!("Missing of") });
+		if of != "of" {
+			bail!("Expected of, found {of}");
+		}
+		let target = parse_entity_target(toks).context("Failed to parse entity target")?;
+		return Ok(InstrKind::DeclareCriteria {
+			left: reg.clone().into(),
+			criterion,
+			target,
+		});
+	}
+	let binding =
+		parse_decl_binding(kind, toks).context("Failed to parse declare binding")?;
 	Ok(InstrKind::Declare {
 		left: reg.clone().into(),
 		ty,
@@ -982,6 +1561,28 @@ fn parse_let<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Re
 	})
 }
 
+fn parse_criterion<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<Criterion> {
+	let (tok, pos) = consume!(toks, { bail!("Missing criterion") });
+	let criterion = match tok {
+		Token::Str(string) => Criterion::Compound(string.clone()),
+		Token::Ident(ident) => {
+			let criterion = SingleCriterion::parse(ident).context("Unknown criterion type")?;
+			Criterion::Single(criterion)
+		}
+		other => bail!("Unexpected token {other:?} {pos}"),
+	};
+	Ok(criterion)
+}
+
+/// Parses the index of a bit in flags. Named bits have already been replaced with their indices
+fn parse_flag_bit<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<u8> {
+	let bit = consume_extract!(toks, Num, { bail!("Missing flag bit") });
+	u8::try_from(*bit)
+		.ok()
+		.filter(|x| *x < MAX_FLAG_BITS)
+		.with_context(|| format!("Flag bit {bit} is out of range"))
+}
+
 fn parse_simple_op<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<(MutableValue, Value)> {
@@ -1000,6 +1601,22 @@ fn parse_swap<'t>(
 	Ok((l, r))
 }
 
+fn parse_copy_data<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<InstrKind> {
+	let to = parse_data_location(toks).context("Failed to parse copy destination")?;
+	consume_expect!(toks, Comma, { bail!("Missing comma") });
+	let from = parse_data_location(toks).context("Failed to parse copy source")?;
+	let mut paths = Vec::new();
+	while consume_optional_expect!(toks, Comma) {
+		paths.push(parse_data_path(toks).context("Failed to parse copied path")?);
+	}
+	if paths.is_empty() {
+		bail!("Copy has no paths");
+	}
+	Ok(InstrKind::CopyData { from, to, paths })
+}
+
 fn parse_pow<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<(MutableValue, u8)> {
@@ -1010,6 +1627,17 @@ fn parse_pow<'t>(
 	Ok((base, exp))
 }
 
+/// Parses the range and optional sequence of a random instruction
+fn parse_random<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<(IntRange, Option<ResourceLocation>)> {
+	let range = consume_extract!(toks, Str, { bail!("Missing range") });
+	let range = IntRange::parse(range).context("Invalid range")?;
+	consume_optional_expect!(toks, Comma);
+	let sequence = consume_optional_extract!(toks, Str);
+	Ok((range, sequence.map(|x| x.clone().into())))
+}
+
 fn parse_ty<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<DataType> {
 	let first_tok = consume!(toks, { bail!("Missing first value token") });
 	parse_ty_impl(first_tok, toks)
@@ -1022,15 +1650,45 @@ fn parse_ty_impl<'t>(
 	let (tok, pos) = first_tok;
 
 	match tok {
-		Token::Ident(ident) => parse_simple_ty(ident),
-		// Array and list types
+		Token::Ident(ident) if ident == "flags" => {
+			consume_expect!(toks, Token::Angle(Side::Left), {
+				bail!("Missing opening angle bracket")
+			});
+			let bits = consume_extract!(toks, Num, { bail!("Missing flag bit count") });
+			let bits = u8::try_from(*bits)
+				.ok()
+				.filter(|x| (1..=MAX_FLAG_BITS).contains(x))
+				.with_context(|| format!("Flags cannot have {bits} bits"))?;
+			consume_expect!(toks, Token::Angle(Side::Right), {
+				bail!("Missing closing angle bracket")
+			});
+			Ok(DataType::Score(ScoreType::Flags(bits)))
+		}
+		Token::Ident(ident) if ident == "enum" => {
+			consume_expect!(toks, Token::Angle(Side::Left), {
+				bail!("Missing opening angle bracket")
+			});
+			let count = consume_extract!(toks, Num, { bail!("Missing enum variant count") });
+			let count = u32::try_from(*count)
+				.ok()
+				.filter(|x| *x > 0)
+				.with_context(|| format!("Enums cannot have {count} variants"))?;
+			consume_expect!(toks, Token::Angle(Side::Right), {
+				bail!("Missing closing angle bracket")
+			});
+			Ok(DataType::Score(ScoreType::Enum(count)))
+		}
+		Token::Ident(ident) => parse_simple_ty(ident),
+		// Array and list types
 		Token::Square(Side::Left) => {
 			let ty = consume_extract!(toks, Ident, { bail!("Missing array/list type token") });
 			let ty = parse_simple_ty(ty).context("Failed to parse array/list internal type")?;
 			let (tok, pos) = consume!(toks, { bail!("Missing next array/list token") });
 			match tok {
 				Token::Square(Side::Right) => {
-					let DataType::NBT(ty) = ty else { bail!("Type cannot be used in a list") };
+					let DataType::NBT(ty) = ty else {
+						bail!("Type cannot be used in a list")
+					};
 					Ok(DataType::NBT(NBTType::List(Box::new(ty))))
 				}
 				Token::Comma => {
@@ -1041,7 +1699,9 @@ fn parse_ty_impl<'t>(
 					consume_expect!(toks, Token::Square(Side::Right), {
 						bail!("Missing closing bracket")
 					});
-					let DataType::NBT(ty) = ty else { bail!("Type cannot be used in an array") };
+					let DataType::NBT(ty) = ty else {
+						bail!("Type cannot be used in an array")
+					};
 					let ty = match ty {
 						NBTType::Byte => NBTArrayType::Byte(len),
 						NBTType::Int => NBTArrayType::Int(len),
@@ -1074,6 +1734,7 @@ pub fn parse_simple_ty(ty: &str) -> anyhow::Result<DataType> {
 		"nfloat" => Ok(DataType::NBT(NBTType::Float)),
 		"ndouble" => Ok(DataType::NBT(NBTType::Double)),
 		"nstr" => Ok(DataType::NBT(NBTType::String)),
+		"nuuid" => Ok(DataType::NBT(NBTType::Arr(NBTArrayType::Int(4)))),
 		other => bail!("Unknown type {other}"),
 	}
 }
@@ -1124,20 +1785,16 @@ fn parse_compound_ty<'t>(
 }
 
 fn parse_decl_binding<'t>(
+	kind: &str,
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<DeclareBinding> {
-	let kind = consume_extract!(toks, Ident, { bail!("Missing declare binding token") });
-	match kind.as_str() {
+	match kind {
 		"val" => {
 			let val = parse_val(toks).context("Failed to parse value")?;
 			Ok(DeclareBinding::Value(val))
 		}
 		"null" => Ok(DeclareBinding::Null),
-		"cast" => {
-			let ty = parse_ty(toks).context("Failed to parse cast type")?;
-			let val = parse_mut_val(toks).context("Failed to parse cast value")?;
-			Ok(DeclareBinding::Cast(ty, val))
-		}
+		"cast" => parse_cast(toks),
 		"cond" => {
 			let cond = parse_condition(toks).context("Failed to parse condition")?;
 			Ok(DeclareBinding::Condition(cond))
@@ -1146,6 +1803,19 @@ fn parse_decl_binding<'t>(
 	}
 }
 
+fn parse_cast<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<DeclareBinding> {
+	let ty = parse_ty(toks).context("Failed to parse cast type")?;
+	let val = parse_val(toks).context("Failed to parse cast value")?;
+	let scale = if consume_optional_expect!(toks, Comma) {
+		*consume_extract!(toks, Decimal, { bail!("Missing scale") })
+	} else {
+		1.0
+	};
+	Ok(DeclareBinding::Cast(ty, val, scale))
+}
+
 fn parse_val<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<Value> {
 	let first_tok = consume!(toks, { bail!("Missing first value token") });
 	parse_val_impl(first_tok, toks)
@@ -1261,7 +1931,6 @@ fn parse_data_path<'t>(
 	}
 }
 
-#[allow(dead_code)]
 fn parse_data_location<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<DataLocation> {
@@ -1386,6 +2055,11 @@ fn parse_lit_impl<'t>(
 					parse_array_lit(NBTArrayTypeContents::Long(Vec::new(), 0), toks)?,
 				)))
 			}
+			"uuid" => {
+				let uuid = consume_extract!(toks, Str, { bail!("Missing UUID string") });
+				let uuid = UUID::parse(uuid).context("Invalid UUID")?;
+				Ok(DataTypeContents::NBT(uuid.to_nbt()))
+			}
 			other => bail!("Unknown data value {other}"),
 		},
 		Token::Str(string) => Ok(DataTypeContents::NBT(NBTTypeContents::String(
@@ -1565,7 +2239,15 @@ fn parse_compound_lit<'t>(
 fn parse_entity_target<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<EntityTarget> {
-	let (tok, pos) = consume!(toks, { bail!("Missing first literal token") });
+	let first_tok = consume!(toks, { bail!("Missing first literal token") });
+	parse_entity_target_impl(first_tok, toks)
+}
+
+fn parse_entity_target_impl<'t>(
+	first_tok: &TokenAndPos,
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<EntityTarget> {
+	let (tok, pos) = first_tok;
 	match tok {
 		Token::At => {
 			let ty = consume_extract!(toks, Ident, { bail!("Missing selector type token") });
@@ -1581,21 +2263,26 @@ fn parse_entity_target<'t>(
 			consume_expect!(toks, Token::Square(Side::Left), {
 				bail!("Missing selector parameters opening bracket token");
 			});
-			let params =
-				parse_selector_parameters(toks).context("Failed to parse selector parameters")?;
-			Ok(EntityTarget::Selector(TargetSelector::with_params(
-				sel, params,
-			)))
+			let sel = parse_selector_parameters(toks, Selector::new(sel))
+				.context("Failed to parse selector parameters")?;
+			sel.build_target()
+				.with_context(|| format!("Invalid selector {pos}"))
+		}
+		Token::Str(player) => Ok(EntityTarget::Player(player.as_str().into())),
+		// UUIDs are normalized to their full hyphenated form
+		Token::Ident(ident) if ident == "uuid" => {
+			let uuid = consume_extract!(toks, Str, { bail!("Missing UUID string") });
+			let uuid = UUID::parse(uuid).context("Invalid UUID")?;
+			Ok(EntityTarget::Player(format!("{uuid:?}").into()))
 		}
-		Token::Str(player) => Ok(EntityTarget::Player(player.clone())),
 		other => bail!("Unexpected token {other:?} {pos}"),
 	}
 }
 
 fn parse_selector_parameters<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<Vec<SelectorParameter>> {
-	let mut out = Vec::new();
+	mut out: Selector,
+) -> anyhow::Result<Selector> {
 	loop {
 		let first_tok = consume_optional!(toks);
 		if let Some(first_tok) = first_tok {
@@ -1609,52 +2296,11 @@ fn parse_selector_parameters<'t>(
 			consume_expect!(toks, Token::Equal, {
 				bail!("Missing selector parameter equal");
 			});
-			let param = match key.as_str() {
-				// TODO: Inversion
-				"type" => {
-					let ty = consume_extract!(toks, Str, { bail!("Missing type token") });
-					SelectorParameter::Type {
-						ty: ty.clone(),
-						invert: false,
-					}
-				}
-				"tag" => {
-					let tag = consume_extract!(toks, Str, { bail!("Missing tag token") });
-					SelectorParameter::Tag {
-						tag: tag.clone(),
-						invert: false,
-					}
-				}
-				"no_tags" => SelectorParameter::NoTags,
-				"pred" => {
-					let pred = consume_extract!(toks, Str, { bail!("Missing predicate token") });
-					SelectorParameter::Predicate {
-						predicate: pred.clone(),
-						invert: false,
-					}
-				}
-				"name" => {
-					let name = consume_extract!(toks, Str, { bail!("Missing name token") });
-					SelectorParameter::Name {
-						name: name.clone(),
-						invert: false,
-					}
-				}
-				"nbt" => {
-					consume_expect!(toks, Token::Curly(Side::Left), {
-						bail!("Missing opening bracket")
-					});
-					let (_, nbt) = parse_compound_lit(toks).context("Failed to parse item data")?;
-					SelectorParameter::NBT { nbt, invert: false }
-				}
-				"limit" => {
-					let limit = consume_extract!(toks, Num, { bail!("Missing limit token") });
-					let limit = (*limit).try_into().context("Limit is not a u32")?;
-					SelectorParameter::Limit(limit)
-				}
-				other => bail!("Unknown selector parameter {other}"),
-			};
-			out.push(param);
+			if key == "no_tags" {
+				out = out.no_tags();
+			} else {
+				out = parse_selector_parameter(toks, key, out)?;
+			}
 
 			let next = consume_optional!(toks);
 			if let Some(next) = next {
@@ -1674,10 +2320,408 @@ fn parse_selector_parameters<'t>(
 	Ok(out)
 }
 
+/// Parses the value of a single selector parameter after the equal sign
+fn parse_selector_parameter<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+	key: &str,
+	out: Selector,
+) -> anyhow::Result<Selector> {
+	let value = consume!(toks, { bail!("Missing selector parameter value") });
+	let (invert, value) = if let Token::Bang = value.0 {
+		let value = consume!(toks, { bail!("Missing selector parameter value") });
+		(true, value)
+	} else {
+		(false, value)
+	};
+	if invert
+		&& !matches!(
+			key,
+			"type" | "tag" | "pred" | "name" | "nbt" | "gamemode" | "team"
+		) {
+		bail!("Selector parameter {key} cannot be inverted");
+	}
+
+	let out = match key {
+		"type" => {
+			let ty = extract_selector_str(value).context("Missing type token")?;
+			out.param(SelectorParameter::Type {
+				ty: ty.clone(),
+				invert,
+			})
+		}
+		"tag" => {
+			let tag = extract_selector_str(value).context("Missing tag token")?;
+			out.param(SelectorParameter::Tag {
+				tag: tag.clone(),
+				invert,
+			})
+		}
+		"pred" => {
+			let predicate = extract_selector_str(value).context("Missing predicate token")?;
+			out.param(SelectorParameter::Predicate {
+				predicate: predicate.clone(),
+				invert,
+			})
+		}
+		"name" => {
+			let name = extract_selector_str(value).context("Missing name token")?;
+			out.param(SelectorParameter::Name {
+				name: name.clone(),
+				invert,
+			})
+		}
+		"nbt" => {
+			let Token::Curly(Side::Left) = value.0 else {
+				bail!("Missing opening bracket");
+			};
+			let (_, nbt) = parse_compound_lit(toks).context("Failed to parse item data")?;
+			out.param(SelectorParameter::NBT { nbt, invert })
+		}
+		"gamemode" => {
+			let Token::Ident(gamemode) = &value.0 else {
+				bail!("Unexpected token {:?} {}", value.0, value.1);
+			};
+			let gamemode = Gamemode::parse(gamemode).context("Unknown gamemode")?;
+			out.param(SelectorParameter::Gamemode { gamemode, invert })
+		}
+		"team" => {
+			let team = extract_selector_str(value).context("Missing team token")?;
+			out.param(SelectorParameter::Team {
+				team: team.clone(),
+				invert,
+			})
+		}
+		"level" => {
+			let range = parse_selector_range(value).context("Failed to parse level range")?;
+			out.level(range)
+		}
+		"distance" => {
+			let range = parse_selector_range(value).context("Failed to parse distance range")?;
+			out.distance(range)
+		}
+		"x" | "y" | "z" | "dx" | "dy" | "dz" => {
+			let value = match &value.0 {
+				Token::Num(num) => *num as f64,
+				Token::Decimal(num) => *num,
+				other => bail!("Unexpected token {other:?} {}", value.1),
+			};
+			let axis = match &key[key.len() - 1..] {
+				"x" => Axis::X,
+				"y" => Axis::Y,
+				_ => Axis::Z,
+			};
+			if key.starts_with('d') {
+				out.param(SelectorParameter::Volume { axis, value })
+			} else {
+				out.param(SelectorParameter::Origin { axis, value })
+			}
+		}
+		"scores" => {
+			let Token::Curly(Side::Left) = value.0 else {
+				bail!("Missing opening bracket");
+			};
+			let scores = parse_selector_map(toks, |tok| {
+				parse_selector_range(tok).context("Failed to parse score range")
+			})
+			.context("Failed to parse scores")?;
+			out.param(SelectorParameter::Scores { scores })
+		}
+		"advancements" => {
+			let Token::Curly(Side::Left) = value.0 else {
+				bail!("Missing opening bracket");
+			};
+			let advancements = parse_selector_map(toks, |tok| match &tok.0 {
+				Token::Ident(val) if val == "true" => Ok(true),
+				Token::Ident(val) if val == "false" => Ok(false),
+				other => bail!("Unexpected token {other:?} {}", tok.1),
+			})
+			.context("Failed to parse advancements")?;
+			out.param(SelectorParameter::Advancements { advancements })
+		}
+		"limit" => {
+			let Token::Num(limit) = &value.0 else {
+				bail!("Unexpected token {:?} {}", value.0, value.1);
+			};
+			let limit = (*limit).try_into().context("Limit is not a u32")?;
+			out.limit(limit)
+		}
+		"sort" => {
+			let Token::Ident(sort) = &value.0 else {
+				bail!("Unexpected token {:?} {}", value.0, value.1);
+			};
+			let sort = SelectorSort::parse(sort).context("Unknown selector sort")?;
+			out.sort(sort)
+		}
+		other => bail!("Unknown selector parameter {other}"),
+	};
+
+	Ok(out)
+}
+
+fn extract_selector_str(tok: &TokenAndPos) -> anyhow::Result<&String> {
+	let Token::Str(string) = &tok.0 else {
+		bail!("Unexpected token {:?} {}", tok.0, tok.1);
+	};
+	Ok(string)
+}
+
+/// Parses a range in a selector, which is either a single number or a string like "1..5"
+fn parse_selector_range<N>(tok: &TokenAndPos) -> anyhow::Result<Range<N>>
+where
+	N: Rangeable + FromStr + NumCast,
+{
+	match &tok.0 {
+		Token::Num(num) => {
+			let num = N::from(*num).context("Range value is out of bounds")?;
+			Ok(Range::new(RangeEnd::Value(num), RangeEnd::Value(num)))
+		}
+		Token::Decimal(num) => {
+			let num = N::from(*num).context("Range value is out of bounds")?;
+			Ok(Range::new(RangeEnd::Value(num), RangeEnd::Value(num)))
+		}
+		Token::Str(range) => Range::parse(range),
+		other => bail!("Unexpected token {other:?} {}", tok.1),
+	}
+}
+
+/// Parses the entries of a map like scores={"foo"=1} after the opening bracket
+fn parse_selector_map<'t, T>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+	parse_value: impl Fn(&TokenAndPos) -> anyhow::Result<T>,
+) -> anyhow::Result<Vec<(String, T)>> {
+	let mut out = Vec::new();
+	loop {
+		let tok = consume!(toks, { bail!("Missing closing bracket") });
+		let key = match &tok.0 {
+			Token::Curly(Side::Right) => break,
+			Token::Str(key) => key,
+			other => bail!("Unexpected token {other:?} {}", tok.1),
+		};
+		consume_expect!(toks, Equal, { bail!("Missing equal sign") });
+		let value = consume!(toks, { bail!("Missing value") });
+		out.push((key.clone(), parse_value(value)?));
+
+		let tok = consume!(toks, { bail!("Missing closing bracket") });
+		match &tok.0 {
+			Token::Comma => {}
+			Token::Curly(Side::Right) => break,
+			other => bail!("Unexpected token {other:?} {}", tok.1),
+		}
+	}
+
+	Ok(out)
+}
+
+fn parse_text_component<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<TextComponent> {
+	let (tok, pos) = consume!(toks, { bail!("Missing first text component token") });
+	match tok {
+		Token::Str(text) => Ok(TextComponent::text(text)),
+		Token::Percent => {
+			let reg_name = consume_extract!(toks, Ident, { bail!("Missing register name token") });
+			let reg = MutableScoreValue::Local(Local::Reg(reg_name.clone().into()));
+			Ok(TextComponent::new(TextComponentContents::Score(reg)))
+		}
+		Token::Ident(ident) if ident == "sty" => {
+			let style = parse_text_style(toks).context("Failed to parse text style")?;
+			let mut component =
+				parse_text_component(toks).context("Failed to parse styled text component")?;
+			component.style = style;
+			Ok(component)
+		}
+		Token::Ident(ident) if ident == "sco" => {
+			let score = parse_score(toks).context("Failed to parse score")?;
+			Ok(TextComponent::new(TextComponentContents::Score(
+				MutableScoreValue::Score(score),
+			)))
+		}
+		Token::Ident(ident) if ident == "nbt" => {
+			let loc = consume_extract!(toks, Ident, { bail!("Missing data location type") });
+			let loc = impl_parse_full_data_location(loc, toks)
+				.context("Failed to parse data location")?;
+			Ok(TextComponent::new(TextComponentContents::Nbt(
+				MutableNBTValue::Data(loc),
+			)))
+		}
+		Token::Square(Side::Left) => {
+			// The first component is the parent of the rest
+			let mut out =
+				parse_text_component(toks).context("Failed to parse first text component")?;
+			loop {
+				let next = consume!(toks, { bail!("Missing text component list end") });
+				match &next.0 {
+					Token::Comma => {}
+					Token::Square(Side::Right) => break,
+					other => bail!("Unexpected token {other:?} {}", next.1),
+				}
+				out.extra
+					.push(parse_text_component(toks).context("Failed to parse text component")?);
+			}
+			Ok(out)
+		}
+		other => bail!("Unexpected token {other:?} {pos}"),
+	}
+}
+
+fn parse_team_option<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<TeamOption> {
+	let option = consume_extract!(toks, Ident, { bail!("Missing team option") });
+	let option = match option.as_str() {
+		"display_name" => TeamOption::DisplayName(
+			parse_text_component(toks).context("Failed to parse display name")?,
+		),
+		"color" => {
+			let color = consume_extract!(toks, Ident, { bail!("Missing color") });
+			if !TEAM_COLORS.contains(&color.as_str()) {
+				bail!("Invalid team color {color}");
+			}
+			TeamOption::Color(color.clone())
+		}
+		"friendly_fire" => TeamOption::FriendlyFire(parse_bool(toks)?),
+		"see_friendly_invisibles" => TeamOption::SeeFriendlyInvisibles(parse_bool(toks)?),
+		"nametag_visibility" | "death_message_visibility" => {
+			let value = consume_extract!(toks, Ident, { bail!("Missing visibility") });
+			let value = TeamVisibility::parse(value).context("Invalid visibility")?;
+			if option == "nametag_visibility" {
+				TeamOption::NametagVisibility(value)
+			} else {
+				TeamOption::DeathMessageVisibility(value)
+			}
+		}
+		"collision_rule" => {
+			let rule = consume_extract!(toks, Ident, { bail!("Missing collision rule") });
+			TeamOption::CollisionRule(CollisionRule::parse(rule).context("Invalid collision rule")?)
+		}
+		"prefix" => {
+			TeamOption::Prefix(parse_text_component(toks).context("Failed to parse prefix")?)
+		}
+		"suffix" => {
+			TeamOption::Suffix(parse_text_component(toks).context("Failed to parse suffix")?)
+		}
+		other => bail!("Unknown team option {other}"),
+	};
+
+	Ok(option)
+}
+
+fn parse_bossbar_option<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<BossbarOption> {
+	let option = consume_extract!(toks, Ident, { bail!("Missing bossbar option") });
+	let option = match option.as_str() {
+		"value" => {
+			let value = consume_extract!(toks, Num, { bail!("Missing value") });
+			let value: i32 = (*value).try_into().context("Value is not an i32")?;
+			if value < 0 {
+				bail!("Bossbar value cannot be negative");
+			}
+			BossbarOption::Value(value)
+		}
+		"max" => {
+			let max = consume_extract!(toks, Num, { bail!("Missing max") });
+			let max: i32 = (*max).try_into().context("Max is not an i32")?;
+			if max < 1 {
+				bail!("Bossbar max must be at least 1");
+			}
+			BossbarOption::Max(max)
+		}
+		"name" => BossbarOption::Name(parse_text_component(toks).context("Failed to parse name")?),
+		"players" => {
+			let players = match consume_optional!(toks) {
+				Some(first_tok) => Some(
+					parse_entity_target_impl(first_tok, toks).context("Failed to parse players")?,
+				),
+				None => None,
+			};
+			BossbarOption::Players(players)
+		}
+		other => bail!("Unknown bossbar option {other}"),
+	};
+
+	Ok(option)
+}
+
+fn parse_text_style<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<TextStyle> {
+	consume_expect!(toks, Token::Square(Side::Left), {
+		bail!("Missing style opening bracket")
+	});
+	let mut out = TextStyle::new();
+	loop {
+		let first_tok = consume_optional!(toks);
+		let Some(first_tok) = first_tok else {
+			break;
+		};
+		let key = match &first_tok.0 {
+			Token::Square(Side::Right) => break,
+			Token::Ident(key) => key,
+			other => bail!("Unexpected token {other:?} {}", first_tok.1),
+		};
+		consume_expect!(toks, Equal, { bail!("Missing equal sign") });
+		match key.as_str() {
+			"color" => {
+				let color = consume_extract!(toks, Str, { bail!("Missing color") });
+				out.color = Some(color.clone());
+			}
+			"font" => {
+				let font = consume_extract!(toks, Str, { bail!("Missing font") });
+				out.font = Some(font.clone());
+			}
+			"click" => {
+				let action = consume_extract!(toks, Ident, { bail!("Missing click action") });
+				let action = ClickAction::parse(action).context("Invalid click action")?;
+				let value = consume_extract!(toks, Str, { bail!("Missing click value") });
+				out.click_event = Some(ClickEvent {
+					action,
+					value: value.clone(),
+				});
+			}
+			"hover" => {
+				let text = parse_text_component(toks).context("Failed to parse hover text")?;
+				out.hover_event = Some(HoverEvent::ShowText(Box::new(text)));
+			}
+			flag => {
+				let val = consume_extract!(toks, Ident, { bail!("Missing flag value") });
+				let val = match val.as_str() {
+					"true" => true,
+					"false" => false,
+					other => bail!("Invalid flag value {other}"),
+				};
+				match flag {
+					"bold" => out.bold = Some(val),
+					"italic" => out.italic = Some(val),
+					"underlined" => out.underlined = Some(val),
+					"strikethrough" => out.strikethrough = Some(val),
+					"obfuscated" => out.obfuscated = Some(val),
+					other => bail!("Unknown style property {other}"),
+				}
+			}
+		}
+
+		let next = consume_optional!(toks);
+		if let Some(next) = next {
+			match &next.0 {
+				Token::Comma => {}
+				Token::Square(Side::Right) => {
+					break;
+				}
+				other => bail!("Unexpected token {other:?} {}", next.1),
+			}
+		}
+	}
+
+	Ok(out)
+}
+
+/// Parses a modifier, along with the token after it if it had to be consumed
 fn parse_modifier<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<MIRModifier> {
+) -> anyhow::Result<(MIRModifier, Option<&'t TokenAndPos>)> {
 	let kind = consume_extract!(toks, Ident, { bail!("Missing modifier token") });
+	let mut next = None;
 
 	let modif = match kind.as_str() {
 		"as" => {
@@ -1689,33 +2733,202 @@ fn parse_modifier<'t>(
 			MIRModifier::At(target)
 		}
 		"str" => {
-			let loc = parse_storage_location(toks).context("Failed to parse storage location")?;
+			let (loc, tok) =
+				parse_storage_location(toks).context("Failed to parse storage location")?;
+			next = tok;
 			MIRModifier::StoreResult(loc)
 		}
 		"sts" => {
-			let loc = parse_storage_location(toks).context("Failed to parse storage location")?;
+			let (loc, tok) =
+				parse_storage_location(toks).context("Failed to parse storage location")?;
+			next = tok;
 			MIRModifier::StoreSuccess(loc)
 		}
 		"pos" => {
 			let pos = parse_double_coords(toks).context("Failed to parse position")?;
 			MIRModifier::Positioned(pos)
 		}
+		"in" => {
+			let dim = consume_extract!(toks, Str, { bail!("Missing dimension") });
+			MIRModifier::In(dim.clone().into())
+		}
+		"rot" => {
+			let rot = parse_rotation(toks).context("Failed to parse rotation")?;
+			MIRModifier::Rotated(rot)
+		}
+		"rote" => {
+			let target = parse_entity_target(toks).context("Failed to parse entity target")?;
+			MIRModifier::RotatedAs(target)
+		}
 		other => bail!("Unknown modifier {other}"),
 	};
 
-	Ok(modif)
+	Ok((modif, next))
 }
 
 fn parse_if<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<InstrKind> {
 	let condition = parse_condition(toks).context("Failed to parse if condition")?;
 	let body = parse_nested_block(toks).context("Failed to parse instruction body")?;
 
+	// An else branch can follow, which is either a block or another if
+	if let Some(Token::Ident(ident)) = consume_optional!(toks).map(|tok| &tok.0) {
+		if ident == "else" {
+			let first_tok = consume!(toks, { bail!("Missing else body") });
+			let second = match &first_tok.0 {
+				Token::Ident(ident) if ident == "if" => {
+					let instr = parse_if(toks).context("Failed to parse else if")?;
+					Block::with_contents(vec![Instruction::new(instr)])
+				}
+				_ => {
+					parse_nested_block_impl(first_tok, toks).context("Failed to parse else body")?
+				}
+			};
+
+			return Ok(InstrKind::IfElse {
+				condition,
+				first: Box::new(body),
+				second: Box::new(second),
+			});
+		}
+	}
+
 	Ok(InstrKind::If {
 		condition,
 		body: Box::new(body),
 	})
 }
 
+/// The number of cases in a switch at which it will be split into a binary search
+/// instead of checking every case one after another
+const SWITCH_SEARCH_THRESHOLD: usize = 4;
+
+fn parse_switch<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<InstrKind> {
+	let val = parse_val(toks).context("Failed to parse switch value")?;
+	consume_expect!(toks, Token::Curly(Side::Left), { bail!("Missing switch body") });
+
+	let mut body = UnparsedBody::new();
+	let mut nested_counter = 0;
+	while let Some(tok) = consume_optional!(toks) {
+		match tok.0 {
+			Token::Curly(Side::Left) => nested_counter += 1,
+			Token::Curly(Side::Right) if nested_counter == 0 => break,
+			Token::Curly(Side::Right) => nested_counter -= 1,
+			_ => {}
+		}
+		body.push(tok.clone());
+	}
+
+	let mut nested_counter = 0;
+	let split = body.split(|x| match x.0 {
+		Token::Curly(Side::Left) => {
+			nested_counter += 1;
+			false
+		}
+		Token::Curly(Side::Right) => {
+			nested_counter -= 1;
+			false
+		}
+		Token::Semicolon => nested_counter == 0,
+		_ => false,
+	});
+
+	let mut cases = Vec::new();
+	let mut default = None;
+	for case in split {
+		let mut toks = case.iter();
+		let Some(first_tok) = consume_optional!(toks) else {
+			continue;
+		};
+		match &first_tok.0 {
+			Token::Ident(ident) if ident == "case" => {
+				let num = consume_extract!(toks, Num, { bail!("Missing case value") });
+				let num: i32 = (*num).try_into().context("Case value is not an i32")?;
+				// Case values can also be written as score literals, like enum variants are
+				if matches!(toks.clone().next(), Some((Token::Ident(suffix), _)) if suffix == "s") {
+					toks.next();
+				}
+				if cases.iter().any(|(other, _)| *other == num) {
+					bail!("Duplicate case {num} {}", first_tok.1);
+				}
+				let block = parse_nested_block(&mut toks)
+					.with_context(|| format!("Failed to parse body of case {num}"))?;
+				cases.push((num, block));
+			}
+			Token::Ident(ident) if ident == "default" => {
+				if default.is_some() {
+					bail!("Duplicate default case {}", first_tok.1);
+				}
+				let block =
+					parse_nested_block(&mut toks).context("Failed to parse default case body")?;
+				default = Some(block);
+			}
+			other => bail!("Unexpected token {other:?} {}", first_tok.1),
+		}
+	}
+
+	if cases.is_empty() {
+		bail!("Switch must have at least one case");
+	}
+	cases.sort_by_key(|(num, _)| *num);
+	let mut block = lower_switch(&val, &cases, &default.unwrap_or_default());
+	Ok(block.contents.remove(0).kind)
+}
+
+/// Creates the tree of conditions for a set of sorted switch cases
+fn lower_switch(val: &Value, cases: &[(i32, Block)], default: &Block) -> Block {
+	let constant =
+		|num: i32| Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(num)));
+
+	if cases.len() <= SWITCH_SEARCH_THRESHOLD {
+		return cases
+			.iter()
+			.rev()
+			.fold(default.clone(), |acc, (num, body)| {
+				let condition = Condition::Equal(val.clone(), constant(*num));
+				let instr = if acc.contents.is_empty() {
+					InstrKind::If {
+						condition,
+						body: Box::new(body.clone()),
+					}
+				} else {
+					InstrKind::IfElse {
+						condition,
+						first: Box::new(body.clone()),
+						second: Box::new(acc),
+					}
+				};
+				Block::with_contents(vec![Instruction::new(instr)])
+			});
+	}
+
+	let (low, high) = cases.split_at(cases.len() / 2);
+	let last_low = low.last().expect("Half of the cases should not be empty").0;
+	let instr = InstrKind::IfElse {
+		condition: Condition::LessThanOrEqual(val.clone(), constant(last_low)),
+		first: Box::new(lower_switch(val, low, default)),
+		second: Box::new(lower_switch(val, high, default)),
+	};
+	Block::with_contents(vec![Instruction::new(instr)])
+}
+
+fn parse_loop<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+	negate: bool,
+	check: LoopCheck,
+) -> anyhow::Result<InstrKind> {
+	let mut condition = parse_condition(toks).context("Failed to parse loop condition")?;
+	if negate {
+		condition = Condition::Not(Box::new(condition));
+	}
+	let body = parse_nested_block(toks).context("Failed to parse loop body")?;
+
+	Ok(InstrKind::Loop {
+		condition,
+		body: Box::new(body),
+		check,
+	})
+}
+
 fn parse_condition<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<Condition> {
@@ -1793,6 +3006,38 @@ fn parse_condition<'t>(
 			let loc = parse_int_coords(toks).context("Failed to parse location")?;
 			Ok(Condition::Loaded(loc))
 		}
+		"gm" => {
+			let target = parse_entity_target(toks).context("Failed to parse target")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let gamemode = consume_extract!(toks, Ident, { bail!("Missing gamemode") });
+			let gamemode = Gamemode::parse(gamemode).context("Unknown gamemode")?;
+			Ok(Condition::Gamemode(target, gamemode))
+		}
+		"diff" => {
+			let difficulty = consume_extract!(toks, Ident, { bail!("Missing difficulty") });
+			let difficulty = Difficulty::parse(difficulty).context("Unknown difficulty")?;
+			Ok(Condition::Difficulty(difficulty))
+		}
+		"dcnt" => {
+			let val = parse_val(toks).context("Failed to parse data value")?;
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let range = consume!(toks, { bail!("Missing range") });
+			let range = parse_selector_range(range).context("Invalid range")?;
+			Ok(Condition::DataCount(val, range))
+		}
+		"itm" => {
+			let loc = parse_data_location(toks).context("Failed to parse item location")?;
+			let loc = match loc {
+				DataLocation::Block(pos) => ItemModifyLocation::Block(pos),
+				DataLocation::Entity(target) => ItemModifyLocation::Entity(target),
+				DataLocation::Storage(..) => bail!("Items cannot be checked in a storage"),
+			};
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let slot = consume_extract!(toks, Str, { bail!("Missing slot") });
+			consume_expect!(toks, Comma, { bail!("Missing comma") });
+			let item = consume_extract!(toks, Str, { bail!("Missing item predicate") });
+			Ok(Condition::Items(loc, slot.clone(), item.clone()))
+		}
 		other => bail!("Unknown condition type {other}"),
 	}
 }
@@ -1815,14 +3060,6 @@ fn parse_double_coords<'t>(
 	Ok(DoubleCoordinates::XYZ(x, y, z))
 }
 
-fn parse_double_coords_2d<'t>(
-	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<DoubleCoordinates2D> {
-	let x = parse_coord_part_double(toks).context("Failed to parse first part of coordinate")?;
-	let y = parse_coord_part_double(toks).context("Failed to parse second part of coordinate")?;
-	Ok(DoubleCoordinates2D::new(x, y))
-}
-
 fn parse_int_coords<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<IntCoordinates> {
@@ -1832,18 +3069,55 @@ fn parse_int_coords<'t>(
 	Ok(IntCoordinates::XYZ(x, y, z))
 }
 
+/// Parses the yaw of a rotation
 fn parse_angle<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<Angle> {
+	let yaw = parse_rotation_part(toks, MAX_YAW).context("Failed to parse yaw")?;
+	let out = match yaw {
+		AbsOrRelCoord::Abs(val) => Angle::new_absolute(val as f32),
+		AbsOrRelCoord::Rel(val) => Angle::new_relative(val as f32),
+	};
+	Ok(out)
+}
+
+/// Parses a rotation of a yaw and then a pitch, like `90 ~-10.5`
+fn parse_rotation<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<DoubleCoordinates2D> {
+	let yaw = parse_rotation_part(toks, MAX_YAW).context("Failed to parse yaw")?;
+	let pitch = parse_rotation_part(toks, MAX_PITCH).context("Failed to parse pitch")?;
+	Ok(DoubleCoordinates2D::new(yaw, pitch))
+}
+
+/// The largest absolute yaw, which goes all the way around
+const MAX_YAW: Double = 180.0;
+/// The largest absolute pitch, which is looking straight up or down
+const MAX_PITCH: Double = 90.0;
+
+/// Parses one part of a rotation in degrees, which can be a whole number or a decimal.
+/// Absolute parts have to be between the negative and positive of a limit
+fn parse_rotation_part<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+	limit: Double,
+) -> anyhow::Result<AbsOrRelCoord<Double>> {
 	let (rel, tok) = parse_coord_part_inner(toks).context("Failed to parse inner angle")?;
-	if let Token::Decimal(val) = &tok.0 {
-		let val = *val as f32;
-		let out = if rel {
-			Angle::new_relative(val)
-		} else {
-			Angle::new_absolute(val)
-		};
-		Ok(out)
+	let val = match &tok.0 {
+		Token::Num(val) => *val as Double,
+		Token::Decimal(val) => *val,
+		_ => bail!("Unexpected token {:?} {}", tok.0, tok.1),
+	};
+	if !val.is_finite() {
+		bail!("Angle {val} is not a finite number {}", tok.1);
+	}
+	if rel {
+		Ok(AbsOrRelCoord::Rel(val))
 	} else {
-		bail!("Unexpected token {:?} {}", tok.0, tok.1);
+		if val.abs() > limit {
+			bail!(
+				"Angle {val} is outside of the range -{limit} to {limit} {}",
+				tok.1
+			);
+		}
+		Ok(AbsOrRelCoord::Abs(val))
 	}
 }
 
@@ -1908,6 +3182,49 @@ fn parse_item_data<'t>(
 	})
 }
 
+fn parse_particle<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<ParticleData> {
+	let first = consume!(toks, { bail!("Missing particle") });
+	let particle = match &first.0 {
+		Token::Str(particle) => ParticleData::Simple(particle.clone().into()),
+		Token::Ident(ty) => match ty.as_str() {
+			"dust" => {
+				let color = parse_decimals(toks).context("Failed to parse color")?;
+				let size = consume_extract!(toks, Decimal, { bail!("Missing size") });
+				ParticleData::Dust { color, size: *size }
+			}
+			"dust_color_transition" => {
+				let from = parse_decimals(toks).context("Failed to parse start color")?;
+				let to = parse_decimals(toks).context("Failed to parse end color")?;
+				let size = consume_extract!(toks, Decimal, { bail!("Missing size") });
+				ParticleData::DustColorTransition {
+					from,
+					to,
+					size: *size,
+				}
+			}
+			"block" => ParticleData::Block(parse_block_data(toks)?),
+			"block_marker" => ParticleData::BlockMarker(parse_block_data(toks)?),
+			"falling_dust" => ParticleData::FallingDust(parse_block_data(toks)?),
+			"item" => ParticleData::Item(parse_item_data(toks)?),
+			other => bail!("Unknown particle type {other}"),
+		},
+		other => bail!("Unexpected token {other:?}"),
+	};
+	Ok(particle)
+}
+
+/// Parses three decimals in a row, like a color or the spread of particles
+fn parse_decimals<'t>(
+	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
+) -> anyhow::Result<[f64; 3]> {
+	let x = consume_extract!(toks, Decimal, { bail!("Missing first number") });
+	let y = consume_extract!(toks, Decimal, { bail!("Missing second number") });
+	let z = consume_extract!(toks, Decimal, { bail!("Missing third number") });
+	Ok([*x, *y, *z])
+}
+
 fn parse_block_data<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
 ) -> anyhow::Result<BlockData> {
@@ -1982,57 +3299,100 @@ fn parse_bool<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::R
 }
 
 fn parse_time<'t>(toks: &mut impl Iterator<Item = &'t TokenAndPos>) -> anyhow::Result<Time> {
-	let num = consume_extract!(toks, Decimal, { bail!("Missing time token") });
-	let num = *num as f32;
-	let suffix = consume_extract!(toks, Ident, { bail!("Missing time suffix token") });
-	let unit = match suffix.as_str() {
-		"t" => TimeUnit::Ticks,
-		"s" => TimeUnit::Seconds,
-		"d" => TimeUnit::Days,
-		other => bail!("Unknown time unit suffix {other}"),
+	let (tok, pos) = consume!(toks, { bail!("Missing time token") });
+	let num = match tok {
+		Token::Num(num) => *num as f32,
+		Token::Decimal(num) => *num as f32,
+		other => bail!("Unexpected token {other:?} {pos}"),
 	};
+	let suffix = consume_extract!(toks, Ident, { bail!("Missing time suffix token") });
+	let unit =
+		TimeUnit::parse(suffix).with_context(|| format!("Unknown time unit suffix {suffix}"))?;
 
 	Ok(Time::new(num, unit))
 }
 
+/// Parses a store location, along with the token after it if it had to be consumed
 fn parse_storage_location<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<StoreModLocation> {
+) -> anyhow::Result<(StoreModLocation, Option<&'t TokenAndPos>)> {
 	let ty = consume_extract!(toks, Ident, {
 		bail!("Missing storage location type token")
 	});
 	match ty.as_str() {
 		"reg" => {
 			let reg = consume_extract!(toks, Ident, { bail!("Missing storage register token") });
-			consume_expect!(toks, Token::Comma, { bail!("Missing comma") });
-			let scale = consume_extract!(toks, Decimal, { bail!("Missing scale token") });
-			Ok(StoreModLocation::Local(
-				Local::Reg(reg.clone().into()),
-				*scale,
-			))
+			let (ty, scale, next) = parse_storage_ty_and_scale(toks)?;
+			let loc = StoreModLocation::Local(Local::Reg(reg.clone().into()), ty, scale);
+			Ok((loc, Some(next)))
 		}
 		"data" => {
-			let loc =
-				parse_full_data_location(toks).context("Failed to parse full data location")?;
-			consume_expect!(toks, Token::Comma, { bail!("Missing comma") });
-			let ty = parse_storage_ty(toks).context("Failed to parse storage type")?;
-			consume_expect!(toks, Token::Comma, { bail!("Missing comma") });
-			let scale = consume_extract!(toks, Decimal, { bail!("Missing scale token") });
-			Ok(StoreModLocation::Data(loc, ty, *scale))
+			let (tok, pos) = consume!(toks, { bail!("Missing data location token") });
+			match tok {
+				// The type of a register is already known from its declaration
+				Token::Percent => {
+					let reg =
+						consume_extract!(toks, Ident, { bail!("Missing storage register token") });
+					let (ty, scale, next) = parse_storage_ty_and_scale(toks)?;
+					let loc = StoreModLocation::Local(Local::Reg(reg.clone().into()), ty, scale);
+					Ok((loc, Some(next)))
+				}
+				Token::Ident(loc) => {
+					let loc = impl_parse_full_data_location(loc, toks)
+						.context("Failed to parse full data location")?;
+					let (ty, scale, next) = parse_storage_ty_and_scale(toks)?;
+					let ty = ty.with_context(|| {
+						format!("Missing storage type, which can only be inferred for registers {pos}")
+					})?;
+					Ok((StoreModLocation::Data(loc, ty, scale), Some(next)))
+				}
+				other => bail!("Unexpected token {other:?} {pos}"),
+			}
 		}
 		"sco" => {
 			let score = parse_score(toks).context("Failed to parse score")?;
-			Ok(StoreModLocation::Score(score))
+			Ok((StoreModLocation::Score(score), None))
+		}
+		"bb" => {
+			let bar = consume_extract!(toks, Str, { bail!("Missing bossbar") });
+			let mode = consume_extract!(toks, Ident, { bail!("Missing bossbar store mode") });
+			let mode = match mode.as_str() {
+				"value" => StoreBossbarMode::Value,
+				"max" => StoreBossbarMode::Max,
+				other => bail!("Unknown bossbar store mode {other}"),
+			};
+			Ok((StoreModLocation::Bossbar(bar.clone().into(), mode), None))
 		}
 		other => bail!("Unknown storage location type {other}"),
 	}
 }
 
-fn parse_storage_ty<'t>(
+/// Parses the optional storage type and scale after a store location, which default to
+/// the type of the location and 1.0. The token after them is consumed and returned
+fn parse_storage_ty_and_scale<'t>(
 	toks: &mut impl Iterator<Item = &'t TokenAndPos>,
-) -> anyhow::Result<StoreDataType> {
-	let ty = consume_extract!(toks, Ident, { bail!("Missing storage type token") });
-	match ty.as_str() {
+) -> anyhow::Result<(Option<StoreDataType>, Double, &'t TokenAndPos)> {
+	let mut ty = None;
+	let mut scale = None;
+	loop {
+		let next = consume!(toks, { bail!("Missing modifier body") });
+		if next.0 != Token::Comma {
+			return Ok((ty, scale.unwrap_or(1.0), next));
+		}
+		let (tok, pos) = consume!(toks, { bail!("Missing storage type or scale") });
+		match tok {
+			Token::Ident(name) if ty.is_none() && scale.is_none() => {
+				ty = Some(parse_storage_ty(name)?);
+			}
+			Token::Decimal(num) if scale.is_none() => scale = Some(*num),
+			Token::Num(num) if scale.is_none() => scale = Some(*num as Double),
+			other => bail!("Unexpected token {other:?} {pos}"),
+		}
+	}
+}
+
+fn parse_storage_ty(ty: &str) -> anyhow::Result<StoreDataType> {
+	match ty {
 		"byte" => Ok(StoreDataType::Byte),
 		"short" => Ok(StoreDataType::Short),
 		"int" => Ok(StoreDataType::Int),
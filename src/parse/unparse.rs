@@ -0,0 +1,1655 @@
+//! Writing of IR and MIR back to the textual format that the parser reads, so that
+//! the stages of the compiler can be dumped and diffed. MIR is written as the IR
+//! that would lower to it, and LIR has no textual form
+
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::condition::Condition;
+use crate::common::function::{
+	FunctionAnnotations, FunctionInterface, InvocationFrequency, ReturnType,
+};
+use crate::common::mc::block::{BlockData, BlockStateValue, CloneMaskMode, FillMode};
+use crate::common::mc::bossbar::BossbarOption;
+use crate::common::mc::entity::{EffectDuration, SelectorParameter};
+use crate::common::mc::instr::MinecraftInstr;
+use crate::common::mc::item::{ItemData, ItemModifyLocation};
+use crate::common::mc::particle::ParticleData;
+use crate::common::mc::modifier::{MIRModifier, StoreDataType, StoreModLocation};
+use crate::common::mc::pos::{AbsOrRelCoord, Angle, Coordinates, DoubleCoordinates2D};
+use crate::common::mc::scoreboard_and_teams::{
+	CollisionRule, Criterion, NumberFormat, SingleCriterion, TeamOption, TeamVisibility,
+};
+use crate::common::mc::text::{HoverEvent, TextComponent, TextComponentContents, TextStyle};
+use crate::common::mc::time::Time;
+use crate::common::mc::{DataLocation, DataPath, EntityTarget, FullDataLocation, Location, Score};
+use crate::common::range::IntRange;
+use crate::common::reg::Local;
+use crate::common::storage::{StorageDeclarations, StorageLifetime};
+use crate::common::ty::{
+	DataType, DataTypeContents, Double, NBTArrayType, NBTArrayTypeContents,
+	NBTCompoundTypeContents, NBTType, NBTTypeContents, ScoreType, ScoreTypeContents,
+};
+use crate::common::val::{MutableNBTValue, MutableScoreValue, MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier, ResourceLocation};
+use crate::ir::{InstrKind, Instruction, LoopCheck, IR};
+use crate::mir::{MIRBlock, MIRInstrKind, MIRInstruction, MIR};
+
+use super::lex::is_ident;
+
+/// Writes IR as text that can be parsed back into the same IR
+pub fn unparse_ir(ir: &IR) -> anyhow::Result<String> {
+	let mut out = unparse_storages(&ir.storages);
+	for (id, func) in ir.functions.iter().sorted_by(|l, r| l.0.cmp(r.0)) {
+		let body = write_block(&func.block.contents, 0)
+			.with_context(|| format!("Failed to write function {id}"))?;
+		out.push_str(&write_interface(&func.interface)?);
+		out.push_str(&body);
+		out.push_str("\n\n");
+	}
+
+	Ok(out)
+}
+
+/// Writes MIR as the textual IR that would lower to it. The objectives and constants
+/// of the MIR are not written, since lowering creates them again
+pub fn unparse_mir(mir: &MIR) -> anyhow::Result<String> {
+	let mut out = unparse_storages(&mir.storages);
+	for (id, func) in mir.functions.iter().sorted_by(|l, r| l.0.cmp(r.0)) {
+		let mut regs = FxHashMap::default();
+		let body = write_mir_block(&func.block, 0, &mut regs)
+			.with_context(|| format!("Failed to write function {id}"))?;
+		out.push_str(&write_interface(&func.interface)?);
+		out.push_str(&body);
+		out.push_str("\n\n");
+	}
+
+	Ok(out)
+}
+
+/// Writes a list of instructions that are not inside of a function, in the
+/// format that parse_instrs reads
+pub fn unparse_instrs(instrs: &[Instruction]) -> anyhow::Result<String> {
+	let mut out = String::new();
+	for instr in instrs {
+		out.push_str(&write_instr(instr, 0)?);
+		out.push_str(";\n");
+	}
+
+	Ok(out)
+}
+
+fn unparse_storages(storages: &StorageDeclarations) -> String {
+	let mut out = String::new();
+	for (storage, lifetime) in storages.iter().sorted_by(|l, r| l.0.cmp(r.0)) {
+		if let StorageLifetime::Transient = lifetime {
+			out.push_str("@transient ");
+		}
+		out.push_str(&format!("storage {};\n", string(storage)));
+	}
+	if !out.is_empty() {
+		out.push('\n');
+	}
+
+	out
+}
+
+fn write_interface(interface: &FunctionInterface) -> anyhow::Result<String> {
+	let mut out = write_annotations(&interface.annotations);
+	out.push_str(&string(&interface.id));
+	for param in &interface.sig.params {
+		out.push_str(&format!(" {}", write_simple_ty(param)?));
+	}
+	if let ReturnType::Standard(rets) = &interface.sig.ret {
+		out.push_str(" :");
+		for ret in rets {
+			out.push_str(&format!(" {}", write_simple_ty(ret)?));
+		}
+	}
+	out.push(' ');
+
+	Ok(out)
+}
+
+fn write_annotations(annotations: &FunctionAnnotations) -> String {
+	let flags = [
+		("preserve", annotations.preserve),
+		("no_inline", annotations.no_inline),
+		("no_strip", annotations.no_strip),
+		("unused_result", annotations.unused_result),
+		("test", annotations.test),
+		("public", annotations.public),
+		("tick", annotations.tick),
+		("load", annotations.load),
+	];
+	let mut out = String::new();
+	for (name, _) in flags.iter().filter(|x| x.1) {
+		out.push_str(&format!("@{name}\n"));
+	}
+	if let Some(freq) = &annotations.freq {
+		let freq = match freq {
+			InvocationFrequency::Tick => "tick".into(),
+			InvocationFrequency::Rare => "rare".into(),
+			InvocationFrequency::Interval(ticks) => format!("{ticks}t"),
+		};
+		out.push_str(&format!("@freq {freq}\n"));
+	}
+	if let Some(cost) = annotations.cost {
+		out.push_str(&format!("@cost {cost}\n"));
+	}
+	if let Some(order) = annotations.order {
+		out.push_str(&format!("@order {order}\n"));
+	}
+	for lint in &annotations.allow {
+		out.push_str(&format!("@allow {}\n", lint.code()));
+	}
+	if let Some(export) = &annotations.export {
+		out.push_str(&format!("@export {}\n", string(export)));
+	}
+
+	out
+}
+
+fn write_block(instrs: &[Instruction], indent: usize) -> anyhow::Result<String> {
+	let mut out = String::from("{\n");
+	for instr in instrs {
+		out.push_str(&"\t".repeat(indent + 1));
+		out.push_str(&write_instr(instr, indent + 1)?);
+		out.push_str(";\n");
+	}
+	out.push_str(&"\t".repeat(indent));
+	out.push('}');
+
+	Ok(out)
+}
+
+fn write_instr(instr: &Instruction, indent: usize) -> anyhow::Result<String> {
+	let mut out = String::new();
+	for lint in &instr.allowed_lints {
+		out.push_str(&format!("@allow {} ", lint.code()));
+	}
+	let kind = match &instr.kind {
+		InstrKind::Declare { left, ty, right } => write_declare(left, ty, right)?,
+		InstrKind::DeclareCriteria {
+			left,
+			criterion,
+			target,
+		} => format!(
+			"let {}: score = criteria {} of {}",
+			reg_name(left)?,
+			write_criterion(criterion),
+			write_target(target)?
+		),
+		InstrKind::Assign { left, right } => write_op("set", left, right)?,
+		InstrKind::Cast {
+			left,
+			ty,
+			right,
+			scale,
+		} => format!(
+			"cast {}, {}",
+			write_mut_val(left)?,
+			write_cast(ty, right, *scale)?
+		),
+		InstrKind::Add { left, right } => write_op("add", left, right)?,
+		InstrKind::Sub { left, right } => write_op("sub", left, right)?,
+		InstrKind::Mul { left, right } => write_op("mul", left, right)?,
+		InstrKind::Div { left, right } => write_op("div", left, right)?,
+		InstrKind::Mod { left, right } => write_op("mod", left, right)?,
+		InstrKind::Min { left, right } => write_op("min", left, right)?,
+		InstrKind::Max { left, right } => write_op("max", left, right)?,
+		InstrKind::BitAnd { left, right } => write_op("band", left, right)?,
+		InstrKind::BitOr { left, right } => write_op("bor", left, right)?,
+		InstrKind::BitXor { left, right } => write_op("bxor", left, right)?,
+		InstrKind::Shl { left, right } => write_op("shl", left, right)?,
+		InstrKind::Shr { left, right } => write_op("shr", left, right)?,
+		InstrKind::UUIDToString { left, right } => write_op("uuidstr", left, right)?,
+		InstrKind::StringToUUID { left, right } => write_op("struuid", left, right)?,
+		InstrKind::GetFlag { left, flags, bit } => format!(
+			"fget {}, {}, {bit}",
+			write_mut_val(left)?,
+			write_mut_val(flags)?
+		),
+		InstrKind::SetFlag { flags, bit, right } => format!(
+			"fset {}, {bit}, {}",
+			write_mut_val(flags)?,
+			write_val(right)?
+		),
+		InstrKind::ToggleFlag { flags, bit } => format!("ftgl {}, {bit}", write_mut_val(flags)?),
+		InstrKind::And { left, right } => write_op("and", left, right)?,
+		InstrKind::Or { left, right } => write_op("or", left, right)?,
+		InstrKind::Xor { left, right } => write_op("xor", left, right)?,
+		InstrKind::Merge { left, right } => write_op("mrg", left, right)?,
+		InstrKind::Push { left, right } => write_op("psh", left, right)?,
+		InstrKind::PushFront { left, right } => write_op("pshf", left, right)?,
+		InstrKind::Insert { left, right, index } => {
+			format!("{}, {index}", write_op("ins", left, right)?)
+		}
+		InstrKind::Swap { left, right } => {
+			format!("swap {}, {}", write_mut_val(left)?, write_mut_val(right)?)
+		}
+		InstrKind::Remove { val } => format!("rm {}", write_mut_val(val)?),
+		InstrKind::CopyData { from, to, paths } => write_copy_data(from, to, paths)?,
+		InstrKind::Abs { val } => format!("abs {}", write_mut_val(val)?),
+		InstrKind::Not { value } => format!("not {}", write_mut_val(value)?),
+		InstrKind::Use { val } => format!("use {}", write_mut_val(val)?),
+		InstrKind::Pow { base, exp } => format!("pow {}, {exp}", write_mut_val(base)?),
+		InstrKind::Rand { left, min, max } => {
+			format!("rand {}, {min}, {max}", write_mut_val(left)?)
+		}
+		InstrKind::Get { value, scale } => {
+			format!("get {}, {}", write_mut_val(value)?, double(*scale)?)
+		}
+		InstrKind::If { condition, body } => format!(
+			"if {} {}",
+			write_condition(condition)?,
+			write_block(&body.contents, indent)?
+		),
+		InstrKind::IfElse {
+			condition,
+			first,
+			second,
+		} => format!(
+			"ife {} {} {}",
+			write_condition(condition)?,
+			write_block(&first.contents, indent)?,
+			write_block(&second.contents, indent)?
+		),
+		InstrKind::Loop {
+			condition,
+			body,
+			check,
+		} => {
+			let keyword = match check {
+				LoopCheck::Before => "while",
+				LoopCheck::After => "dow",
+			};
+			format!(
+				"{keyword} {} {}",
+				write_condition(condition)?,
+				write_block(&body.contents, indent)?
+			)
+		}
+		InstrKind::Call { call } => write_call(&call.function, &call.args, &call.ret)?,
+		InstrKind::CallExtern { func } => format!("callx {}", string(func)),
+		InstrKind::CallWith { func, args } => {
+			format!("callw {}, {}", string(func), write_val(args)?)
+		}
+		InstrKind::MC(instr) => write_mc_instr(instr)?,
+		InstrKind::ReturnValue { index, value } => format!("retv {index}, {}", write_val(value)?),
+		InstrKind::Return { value } => format!("ret {}", write_val(value)?),
+		InstrKind::ReturnRun { body } => format!("retr {}", write_block(&body.contents, indent)?),
+		InstrKind::Command { command } => format!("cmd {}", string(command)),
+		InstrKind::InterpolatedCommand { command } => format!("icmd {}", string(command)),
+		InstrKind::MacroCommand { command } => format!("mcmd {}", string(command)),
+		InstrKind::Comment { comment } => format!("cmt {}", string(comment)),
+		InstrKind::Modify { modifier, body } => format!(
+			"mdf {} {}",
+			write_modifier(modifier)?,
+			write_block(&body.contents, indent)?
+		),
+		InstrKind::Assert { condition, message } => {
+			format!(
+				"assert {}, {}",
+				write_condition(condition)?,
+				string(message)
+			)
+		}
+		InstrKind::Atomic { body } => format!("atomic {}", write_block(&body.contents, indent)?),
+		InstrKind::SummonAs {
+			entity,
+			nbt,
+			uuid,
+			body,
+		} => {
+			let mut out = format!("smna {}, {}", string(entity), write_compound_lit(nbt)?);
+			if let Some(uuid) = uuid {
+				out.push_str(&format!(", {}", write_mut_val(uuid)?));
+			}
+			format!("{out} {}", write_block(&body.contents, indent)?)
+		}
+		InstrKind::Label { name } => format!("label {}", string(name)),
+		InstrKind::Goto { label } => format!("goto {}", string(label)),
+		InstrKind::SpreadOverTicks {
+			per_tick,
+			item,
+			ty,
+			list,
+			on_complete,
+			body,
+		} => {
+			let mut out = format!(
+				"spread {per_tick}, {}: {}, {}",
+				reg_name(item)?,
+				write_ty(ty)?,
+				write_val(list)?
+			);
+			if let Some(func) = on_complete {
+				out.push_str(&format!(", {}", string(func)));
+			}
+			format!("{out} {}", write_block(&body.contents, indent)?)
+		}
+	};
+	out.push_str(&kind);
+
+	Ok(out)
+}
+
+fn write_mir_block(
+	block: &MIRBlock,
+	indent: usize,
+	regs: &mut FxHashMap<Identifier, DataType>,
+) -> anyhow::Result<String> {
+	let mut out = String::from("{\n");
+	let mut instrs = block.contents.iter().peekable();
+	while let Some(instr) = instrs.next() {
+		let mut line = String::new();
+		// Declarations are written together with the assignment after them,
+		// which is how they are lowered from IR
+		let text = match (&instr.kind, instrs.peek().map(|x| &x.kind)) {
+			(
+				MIRInstrKind::Declare { left, ty },
+				Some(MIRInstrKind::Assign {
+					left: MutableValue::Reg(reg),
+					right,
+				}),
+			) if left == reg => {
+				regs.insert(left.clone(), ty.clone());
+				let next = instrs.next().expect("Instruction was peeked");
+				write_mir_lints(instr, &mut line);
+				write_mir_lints(next, &mut line);
+				write_declare(left, ty, right)?
+			}
+			(MIRInstrKind::NoOp, ..) => continue,
+			_ => {
+				write_mir_lints(instr, &mut line);
+				write_mir_instr(&instr.kind, indent + 1, regs)?
+			}
+		};
+		out.push_str(&"\t".repeat(indent + 1));
+		out.push_str(&line);
+		out.push_str(&text);
+		out.push_str(";\n");
+	}
+	out.push_str(&"\t".repeat(indent));
+	out.push('}');
+
+	Ok(out)
+}
+
+fn write_mir_lints(instr: &MIRInstruction, out: &mut String) {
+	for lint in &instr.allowed_lints {
+		out.push_str(&format!("@allow {} ", lint.code()));
+	}
+}
+
+fn write_mir_instr(
+	kind: &MIRInstrKind,
+	indent: usize,
+	regs: &mut FxHashMap<Identifier, DataType>,
+) -> anyhow::Result<String> {
+	let out = match kind {
+		MIRInstrKind::Declare { left, ty } => {
+			regs.insert(left.clone(), ty.clone());
+			write_declare(left, ty, &DeclareBinding::Null)?
+		}
+		MIRInstrKind::Assign {
+			left,
+			right: DeclareBinding::Value(right),
+		} => write_op("set", left, right)?,
+		MIRInstrKind::Assign {
+			left,
+			right: DeclareBinding::Cast(ty, right, scale),
+		} if !matches!(left, MutableValue::Reg(..)) => format!(
+			"cast {}, {}",
+			write_mut_val(left)?,
+			write_cast(ty, right, *scale)?
+		),
+		// Other bindings can only be written as a new declaration of the register
+		MIRInstrKind::Assign { left, right } => {
+			let MutableValue::Reg(reg) = left else {
+				bail!("Assignment to {left:?} has no textual form");
+			};
+			let Some(ty) = regs.get(reg) else {
+				bail!("Register {reg} is assigned before it is declared");
+			};
+			write_declare(reg, ty, right)?
+		}
+		MIRInstrKind::Add { left, right } => write_op("add", left, right)?,
+		MIRInstrKind::Sub { left, right } => write_op("sub", left, right)?,
+		MIRInstrKind::Mul { left, right } => write_op("mul", left, right)?,
+		MIRInstrKind::Div { left, right } => write_op("div", left, right)?,
+		MIRInstrKind::Mod { left, right } => write_op("mod", left, right)?,
+		MIRInstrKind::Min { left, right } => write_op("min", left, right)?,
+		MIRInstrKind::Max { left, right } => write_op("max", left, right)?,
+		MIRInstrKind::BitAnd { left, right } => write_op("band", left, right)?,
+		MIRInstrKind::BitOr { left, right } => write_op("bor", left, right)?,
+		MIRInstrKind::BitXor { left, right } => write_op("bxor", left, right)?,
+		MIRInstrKind::Shl { left, right } => write_op("shl", left, right)?,
+		MIRInstrKind::Shr { left, right } => write_op("shr", left, right)?,
+		MIRInstrKind::UUIDToString { left, right } => write_op("uuidstr", left, right)?,
+		MIRInstrKind::StringToUUID { left, right } => write_op("struuid", left, right)?,
+		MIRInstrKind::And { left, right } => write_op("and", left, right)?,
+		MIRInstrKind::Or { left, right } => write_op("or", left, right)?,
+		MIRInstrKind::Xor { left, right } => write_op("xor", left, right)?,
+		MIRInstrKind::Merge { left, right } => write_op("mrg", left, right)?,
+		MIRInstrKind::Push { left, right } => write_op("psh", left, right)?,
+		MIRInstrKind::PushFront { left, right } => write_op("pshf", left, right)?,
+		MIRInstrKind::Insert { left, right, index } => {
+			format!("{}, {index}", write_op("ins", left, right)?)
+		}
+		MIRInstrKind::Swap { left, right } => {
+			format!("swap {}, {}", write_mut_val(left)?, write_mut_val(right)?)
+		}
+		MIRInstrKind::Remove { val } => format!("rm {}", write_mut_val(val)?),
+		MIRInstrKind::CopyData { from, to, paths } => write_copy_data(from, to, paths)?,
+		MIRInstrKind::Abs { val } => format!("abs {}", write_mut_val(val)?),
+		MIRInstrKind::Not { value } => format!("not {}", write_mut_val(value)?),
+		MIRInstrKind::Use { val } => format!("use {}", write_mut_val(val)?),
+		MIRInstrKind::Pow { base, exp } => format!("pow {}, {exp}", write_mut_val(base)?),
+		MIRInstrKind::Rand { left, min, max } => {
+			format!("rand {}, {min}, {max}", write_mut_val(left)?)
+		}
+		MIRInstrKind::Get { value, scale } => {
+			format!("get {}, {}", write_mut_val(value)?, double(*scale)?)
+		}
+		MIRInstrKind::If { condition, body } => format!(
+			"if {} {}",
+			write_condition(condition)?,
+			write_mir_block(body, indent, regs)?
+		),
+		MIRInstrKind::IfElse {
+			condition,
+			first,
+			second,
+		} => format!(
+			"ife {} {} {}",
+			write_condition(condition)?,
+			write_mir_block(first, indent, regs)?,
+			write_mir_block(second, indent, regs)?
+		),
+		MIRInstrKind::Call { call } => write_call(&call.function, &call.args, &call.ret)?,
+		MIRInstrKind::CallExtern { func } => format!("callx {}", string(func)),
+		MIRInstrKind::CallWith { func, args } => {
+			format!("callw {}, {}", string(func), write_val(args)?)
+		}
+		MIRInstrKind::MC(instr) => write_mc_instr(instr)?,
+		MIRInstrKind::ReturnValue { index, value } => {
+			format!("retv {index}, {}", write_val(value)?)
+		}
+		MIRInstrKind::Return { value } => format!("ret {}", write_val(value)?),
+		MIRInstrKind::ReturnRun { body } => {
+			format!("retr {}", write_mir_block(body, indent, regs)?)
+		}
+		MIRInstrKind::Command { command } => format!("cmd {}", string(command)),
+		MIRInstrKind::MacroCommand { command } => format!("mcmd {}", string(command)),
+		MIRInstrKind::Comment { comment } => format!("cmt {}", string(comment)),
+		MIRInstrKind::Modify { modifier, body } => format!(
+			"mdf {} {}",
+			write_modifier(modifier)?,
+			write_mir_block(body, indent, regs)?
+		),
+		MIRInstrKind::GetConst { .. } | MIRInstrKind::ReturnFail | MIRInstrKind::NoOp => {
+			bail!("Instruction {kind:?} has no textual form")
+		}
+	};
+
+	Ok(out)
+}
+
+fn write_cast(ty: &DataType, val: &Value, scale: Double) -> anyhow::Result<String> {
+	let mut out = format!("{} {}", write_ty(ty)?, write_val(val)?);
+	if scale != 1.0 {
+		out.push_str(&format!(", {}", double(scale)?));
+	}
+	Ok(out)
+}
+
+fn write_declare(
+	left: &Identifier,
+	ty: &DataType,
+	right: &DeclareBinding,
+) -> anyhow::Result<String> {
+	let binding = match right {
+		DeclareBinding::Null => "null".into(),
+		DeclareBinding::Value(val) => format!("val {}", write_val(val)?),
+		DeclareBinding::Cast(ty, val, scale) => format!("cast {}", write_cast(ty, val, *scale)?),
+		DeclareBinding::Condition(cond) => format!("cond {}", write_condition(cond)?),
+	};
+	Ok(format!(
+		"let {}: {} = {binding}",
+		reg_name(left)?,
+		write_ty(ty)?
+	))
+}
+
+fn write_op(name: &str, left: &MutableValue, right: &Value) -> anyhow::Result<String> {
+	Ok(format!(
+		"{name} {}, {}",
+		write_mut_val(left)?,
+		write_val(right)?
+	))
+}
+
+fn write_call(func: &str, args: &[Value], rets: &[MutableValue]) -> anyhow::Result<String> {
+	let mut out = String::from("call ");
+	if !rets.is_empty() {
+		let rets: Vec<_> = rets
+			.iter()
+			.map(write_mut_val)
+			.collect::<anyhow::Result<_>>()?;
+		out.push_str(&format!("{} = ", rets.join(", ")));
+	}
+	out.push_str(&string(func));
+	if !args.is_empty() {
+		let args: Vec<_> = args.iter().map(write_val).collect::<anyhow::Result<_>>()?;
+		out.push_str(&format!(" {}", args.join(", ")));
+	}
+
+	Ok(out)
+}
+
+fn write_condition(condition: &Condition) -> anyhow::Result<String> {
+	let out = match condition {
+		Condition::Not(cond) => format!("not {}", write_condition(cond)?),
+		Condition::And(l, r) => format!("and {}, {}", write_condition(l)?, write_condition(r)?),
+		Condition::Or(l, r) => format!("or {}, {}", write_condition(l)?, write_condition(r)?),
+		Condition::Xor(l, r) => format!("xor {}, {}", write_condition(l)?, write_condition(r)?),
+		Condition::Equal(l, r) => format!("eq {}, {}", write_val(l)?, write_val(r)?),
+		Condition::GreaterThan(l, r) => format!("gt {}, {}", write_val(l)?, write_val(r)?),
+		Condition::GreaterThanOrEqual(l, r) => {
+			format!("gte {}, {}", write_val(l)?, write_val(r)?)
+		}
+		Condition::LessThan(l, r) => format!("lt {}, {}", write_val(l)?, write_val(r)?),
+		Condition::LessThanOrEqual(l, r) => format!("lte {}, {}", write_val(l)?, write_val(r)?),
+		Condition::Exists(val) => format!("exi {}", write_val(val)?),
+		Condition::Bool(val) => format!("bool {}", write_val(val)?),
+		Condition::NotBool(val) => format!("not bool {}", write_val(val)?),
+		Condition::Entity(target) => format!("ent {}", write_target(target)?),
+		Condition::Predicate(pred) => format!("pred {}", string(pred)),
+		Condition::Dimension(dim) => format!("dim {}", string(dim)),
+		Condition::Biome(pos, biome) => {
+			format!("bio {}, {}", write_int_coords(pos)?, string(biome))
+		}
+		Condition::Loaded(pos) => format!("load {}", write_int_coords(pos)?),
+		Condition::Gamemode(target, gamemode) => {
+			format!("gm {}, {gamemode}", write_target(target)?)
+		}
+		Condition::Difficulty(difficulty) => format!("diff {difficulty}"),
+		Condition::DataCount(val, range) => {
+			format!("dcnt {}, {}", write_val(val)?, string(&range.to_string()))
+		}
+		Condition::Items(loc, slot, item) => {
+			let loc = match loc {
+				ItemModifyLocation::Block(pos) => format!("blk {}", write_int_coords(pos)?),
+				ItemModifyLocation::Entity(target) => format!("ent {}", write_target(target)?),
+			};
+			format!("itm {loc}, {}, {}", string(slot), string(item))
+		}
+		Condition::Function(..) => bail!("Condition {condition:?} has no textual form"),
+	};
+
+	Ok(out)
+}
+
+fn write_modifier(modifier: &MIRModifier) -> anyhow::Result<String> {
+	let out = match modifier {
+		MIRModifier::As(target) => format!("as {}", write_target(target)?),
+		MIRModifier::At(target) => format!("at {}", write_target(target)?),
+		MIRModifier::StoreResult(loc) => format!("str {}", write_store_location(loc)?),
+		MIRModifier::StoreSuccess(loc) => format!("sts {}", write_store_location(loc)?),
+		MIRModifier::Positioned(pos) => format!("pos {}", write_double_coords(pos)?),
+		MIRModifier::In(dim) => format!("in {}", string(dim)),
+		MIRModifier::Rotated(rot) => format!("rot {}", write_double_coords_2d(rot)?),
+		MIRModifier::RotatedAs(target) => format!("rote {}", write_target(target)?),
+		other => bail!("Modifier {other:?} has no textual form"),
+	};
+
+	Ok(out)
+}
+
+fn write_store_location(loc: &StoreModLocation) -> anyhow::Result<String> {
+	let (mut out, ty, scale) = match loc {
+		StoreModLocation::Local(Local::Reg(reg), ty, scale) => {
+			(format!("reg {}", reg_name(reg)?), ty.as_ref(), *scale)
+		}
+		StoreModLocation::Data(loc, ty, scale) => (
+			format!("data {}", write_data_location(loc)?),
+			Some(ty),
+			*scale,
+		),
+		StoreModLocation::Score(score) => return Ok(format!("sco {}", write_score(score)?)),
+		StoreModLocation::Bossbar(bar, mode) => {
+			return Ok(format!("bb {} {mode:?}", string(bar)));
+		}
+		other => bail!("Store location {other:?} has no textual form"),
+	};
+	if let Some(ty) = ty {
+		let ty = match ty {
+			StoreDataType::Byte => "byte",
+			StoreDataType::Short => "short",
+			StoreDataType::Int => "int",
+			StoreDataType::Long => "long",
+			StoreDataType::Float => "float",
+			StoreDataType::Double => "double",
+		};
+		out.push_str(&format!(", {ty}"));
+	}
+	if scale != 1.0 {
+		out.push_str(&format!(", {}", double(scale)?));
+	}
+
+	Ok(out)
+}
+
+fn write_val(val: &Value) -> anyhow::Result<String> {
+	match val {
+		Value::Mutable(val) => write_mut_val(val),
+		Value::Constant(lit) => write_lit(lit),
+	}
+}
+
+fn write_mut_val(val: &MutableValue) -> anyhow::Result<String> {
+	let out = match val {
+		MutableValue::Reg(reg) => format!("%{}", reg_name(reg)?),
+		MutableValue::Arg(index) => format!("&{index}"),
+		MutableValue::Score(score) => format!("sco {}", write_score(score)?),
+		MutableValue::Data(loc) => write_data_location(loc)?,
+		MutableValue::Property(val, prop) => {
+			format!("prop {} {}", string(prop), write_mut_val(val)?)
+		}
+		MutableValue::Index(val, index) => format!("idx {index} {}", write_mut_val(val)?),
+		MutableValue::CallArg(..)
+		| MutableValue::ReturnValue(..)
+		| MutableValue::CallReturnValue(..) => bail!("Value {val:?} has no textual form"),
+	};
+
+	Ok(out)
+}
+
+fn write_score(score: &Score) -> anyhow::Result<String> {
+	Ok(format!(
+		"{} {}",
+		write_target(&score.holder)?,
+		string(&score.objective)
+	))
+}
+
+fn write_data_location(loc: &FullDataLocation) -> anyhow::Result<String> {
+	Ok(format!(
+		"{} {}",
+		write_location(&loc.loc)?,
+		write_quoted_data_path(&loc.path)?
+	))
+}
+
+fn write_location(loc: &DataLocation) -> anyhow::Result<String> {
+	let out = match loc {
+		DataLocation::Entity(target) => format!("ent {}", write_target(target)?),
+		DataLocation::Block(pos) => format!("blk {}", write_int_coords(pos)?),
+		DataLocation::Storage(storage) => format!("stg {}", string(storage)),
+	};
+
+	Ok(out)
+}
+
+fn write_quoted_data_path(path: &DataPath) -> anyhow::Result<String> {
+	let out = match path {
+		DataPath::This => "this".into(),
+		path => string(&write_data_path(path)?),
+	};
+
+	Ok(out)
+}
+
+fn write_copy_data(
+	from: &DataLocation,
+	to: &DataLocation,
+	paths: &[DataPath],
+) -> anyhow::Result<String> {
+	let mut out = format!("copy {}, {}", write_location(to)?, write_location(from)?);
+	for path in paths {
+		out.push_str(&format!(", {}", write_quoted_data_path(path)?));
+	}
+
+	Ok(out)
+}
+
+/// Flattens a data path into the string form that the parser reads
+fn write_data_path(path: &DataPath) -> anyhow::Result<String> {
+	let out = match path {
+		DataPath::This => bail!("Accesses of the root of a data location have no textual form"),
+		DataPath::String(string) => string.clone(),
+		DataPath::Access(path, prop) => format!("{}.{prop}", write_data_path(path)?),
+		DataPath::Index(path, index) => format!("{}[{index}]", write_data_path(path)?),
+	};
+
+	Ok(out)
+}
+
+fn write_target(target: &EntityTarget) -> anyhow::Result<String> {
+	let sel = match target {
+		EntityTarget::Player(player) => return Ok(string(player)),
+		EntityTarget::Selector(sel) => sel,
+	};
+
+	let invert_char = |invert: &bool| if *invert { "!" } else { "" };
+	let params: Vec<_> = sel
+		.params
+		.iter()
+		.map(|param| {
+			let out = match param {
+				SelectorParameter::Type { ty, invert } => {
+					format!("type={}{}", invert_char(invert), string(ty))
+				}
+				SelectorParameter::Tag { tag, invert } => {
+					format!("tag={}{}", invert_char(invert), string(tag))
+				}
+				SelectorParameter::NoTags => "no_tags=".into(),
+				SelectorParameter::Predicate { predicate, invert } => {
+					format!("pred={}{}", invert_char(invert), string(predicate))
+				}
+				SelectorParameter::Name { name, invert } => {
+					format!("name={}{}", invert_char(invert), string(name))
+				}
+				SelectorParameter::NBT { nbt, invert } => {
+					format!("nbt={}{}", invert_char(invert), write_compound_lit(nbt)?)
+				}
+				SelectorParameter::Gamemode { gamemode, invert } => {
+					format!("gamemode={}{gamemode}", invert_char(invert))
+				}
+				SelectorParameter::Team { team, invert } => {
+					format!("team={}{}", invert_char(invert), string(team))
+				}
+				SelectorParameter::Distance { range } => {
+					format!("distance={}", string(&range.to_string()))
+				}
+				SelectorParameter::Level { range } => {
+					format!("level={}", string(&range.to_string()))
+				}
+				SelectorParameter::Origin { value, .. }
+				| SelectorParameter::Volume { value, .. } => {
+					format!("{}={value:?}", param.get_key())
+				}
+				SelectorParameter::Scores { scores } => {
+					let scores: Vec<_> = scores
+						.iter()
+						.map(|(obj, range)| {
+							format!("{}={}", string(obj), string(&range.to_string()))
+						})
+						.collect();
+					format!("scores={{{}}}", scores.join(", "))
+				}
+				SelectorParameter::Advancements { advancements } => {
+					let advancements: Vec<_> = advancements
+						.iter()
+						.map(|(adv, val)| format!("{}={val}", string(adv)))
+						.collect();
+					format!("advancements={{{}}}", advancements.join(", "))
+				}
+				SelectorParameter::Limit(limit) => format!("limit={limit}"),
+				SelectorParameter::Sort(sort) => format!("sort={sort}"),
+			};
+			Ok(out)
+		})
+		.collect::<anyhow::Result<_>>()?;
+
+	Ok(format!(
+		"{}[{}]",
+		sel.selector.codegen_str(),
+		params.join(", ")
+	))
+}
+
+fn write_ty(ty: &DataType) -> anyhow::Result<String> {
+	let out = match ty {
+		DataType::NBT(NBTType::List(inner)) => {
+			format!("[{}]", write_simple_ty(&DataType::NBT(*inner.clone()))?)
+		}
+		DataType::NBT(NBTType::Arr(arr)) => {
+			let (inner, len) = match arr {
+				NBTArrayType::Byte(len) => ("nbyte", len),
+				NBTArrayType::Int(len) => ("nint", len),
+				NBTArrayType::Long(len) => ("nlong", len),
+			};
+			format!("[{inner}, {len}]")
+		}
+		DataType::NBT(NBTType::Compound(comp)) => {
+			let items: Vec<_> = comp
+				.iter()
+				.sorted_by(|l, r| l.0.cmp(r.0))
+				.map(|(key, ty)| {
+					Ok(format!(
+						"{}: {}",
+						string(key),
+						write_ty(&DataType::NBT(ty.clone()))?
+					))
+				})
+				.collect::<anyhow::Result<_>>()?;
+			format!("{{{}}}", items.join(", "))
+		}
+		DataType::Score(ScoreType::Flags(bits)) => format!("flags<{bits}>"),
+		DataType::Score(ScoreType::Enum(count)) => format!("enum<{count}>"),
+		other => write_simple_ty(other)?,
+	};
+
+	Ok(out)
+}
+
+/// Writes a type that is a single identifier, which is the only kind of type
+/// that can be used in function signatures and list types
+fn write_simple_ty(ty: &DataType) -> anyhow::Result<String> {
+	let out = match ty {
+		DataType::Score(ScoreType::Score) => "score",
+		DataType::Score(ScoreType::Bool) => "bool",
+		DataType::NBT(NBTType::Any) => "nany",
+		DataType::NBT(NBTType::Byte) => "nbyte",
+		DataType::NBT(NBTType::Bool) => "nbool",
+		DataType::NBT(NBTType::Short) => "nshort",
+		DataType::NBT(NBTType::Int) => "nint",
+		DataType::NBT(NBTType::Long) => "nlong",
+		DataType::NBT(NBTType::Float) => "nfloat",
+		DataType::NBT(NBTType::Double) => "ndouble",
+		DataType::NBT(NBTType::String) => "nstr",
+		DataType::NBT(NBTType::Arr(NBTArrayType::Int(4))) => "nuuid",
+		other => bail!("Type {other:?} cannot be written as a single identifier"),
+	};
+
+	Ok(out.into())
+}
+
+fn write_lit(lit: &DataTypeContents) -> anyhow::Result<String> {
+	let out = match lit {
+		DataTypeContents::Score(ScoreTypeContents::Score(num)) => format!("{num}s"),
+		DataTypeContents::Score(ScoreTypeContents::Bool(val)) => val.to_string(),
+		DataTypeContents::NBT(nbt) => write_nbt_lit(nbt)?,
+		DataTypeContents::Macro(..) => bail!("Literal {lit:?} has no textual form"),
+	};
+
+	Ok(out)
+}
+
+fn write_nbt_lit(lit: &NBTTypeContents) -> anyhow::Result<String> {
+	let out = match lit {
+		NBTTypeContents::Byte(num) => format!("{num}nb"),
+		NBTTypeContents::Bool(val) => format!("{val}n"),
+		NBTTypeContents::Short(num) => format!("{num}ns"),
+		NBTTypeContents::Int(num) => format!("{num}ni"),
+		NBTTypeContents::Long(num) => format!("{num}nl"),
+		NBTTypeContents::Float(num) => format!("{}nf", float(*num)?),
+		NBTTypeContents::Double(num) => format!("{}nd", double(*num)?),
+		NBTTypeContents::String(string) => self::string(string),
+		NBTTypeContents::Arr(arr) => match arr {
+			NBTArrayTypeContents::Byte(items, ..) => {
+				format!("b[{}]", items.iter().map(|x| format!("{x}nb")).join(", "))
+			}
+			NBTArrayTypeContents::Int(items, ..) => {
+				format!("i[{}]", items.iter().map(|x| format!("{x}ni")).join(", "))
+			}
+			NBTArrayTypeContents::Long(items, ..) => {
+				format!("l[{}]", items.iter().map(|x| format!("{x}nl")).join(", "))
+			}
+		},
+		NBTTypeContents::List(ty, items) => {
+			let items: Vec<_> = items
+				.iter()
+				.map(write_nbt_lit)
+				.collect::<anyhow::Result<_>>()?;
+			format!(
+				"[{}][{}]",
+				write_ty(&DataType::NBT(ty.clone()))?,
+				items.join(", ")
+			)
+		}
+		NBTTypeContents::Compound(_, contents) => write_compound_lit(contents)?,
+	};
+
+	Ok(out)
+}
+
+fn write_compound_lit(contents: &NBTCompoundTypeContents) -> anyhow::Result<String> {
+	let items: Vec<_> = contents
+		.0
+		.iter()
+		.sorted_by(|l, r| l.0.cmp(r.0))
+		.map(|(key, val)| Ok(format!("{}: {}", string(key), write_nbt_lit(val)?)))
+		.collect::<anyhow::Result<_>>()?;
+
+	Ok(format!("{{{}}}", items.join(", ")))
+}
+
+fn write_criterion(criterion: &Criterion) -> String {
+	match criterion {
+		Criterion::Compound(name) => string(name),
+		Criterion::Single(single) => match single {
+			SingleCriterion::Air => "air",
+			SingleCriterion::Armor => "armor",
+			SingleCriterion::DeathCount => "death_count",
+			SingleCriterion::Dummy => "dummy",
+			SingleCriterion::Food => "food",
+			SingleCriterion::Health => "health",
+			SingleCriterion::Level => "level",
+			SingleCriterion::PlayerKillCount => "player_kill_count",
+			SingleCriterion::TotalKillCount => "total_kill_count",
+			SingleCriterion::Trigger => "trigger",
+			SingleCriterion::XP => "xp",
+		}
+		.into(),
+	}
+}
+
+fn write_mc_instr(instr: &MinecraftInstr) -> anyhow::Result<String> {
+	let out = match instr {
+		MinecraftInstr::Say { message } => format!("say {}", string(message)),
+		MinecraftInstr::Tell { target, message } => {
+			format!("tell {}, {}", write_target(target)?, string(message))
+		}
+		MinecraftInstr::TellRaw { target, message } => format!(
+			"tellraw {}, {}",
+			write_target(target)?,
+			write_text_component(message)?
+		),
+		MinecraftInstr::Title { target, title } => format!(
+			"title {}, {}",
+			write_target(target)?,
+			write_text_component(title)?
+		),
+		MinecraftInstr::Subtitle { target, subtitle } => format!(
+			"subtitle {}, {}",
+			write_target(target)?,
+			write_text_component(subtitle)?
+		),
+		MinecraftInstr::ActionBar { target, text } => format!(
+			"actionbar {}, {}",
+			write_target(target)?,
+			write_text_component(text)?
+		),
+		MinecraftInstr::Me { message } => format!("me {}", string(message)),
+		MinecraftInstr::TeamMessage { message } => format!("tm {}", string(message)),
+		MinecraftInstr::Banlist => "banl".into(),
+		MinecraftInstr::BanIP { target, reason } => match reason {
+			Some(reason) => format!("bani {}, {}", string(target), string(reason)),
+			None => format!("bani {}", string(target)),
+		},
+		MinecraftInstr::PardonIP { target } => format!("pari {}", string(target)),
+		MinecraftInstr::WhitelistOn => "wlon".into(),
+		MinecraftInstr::WhitelistOff => "wloff".into(),
+		MinecraftInstr::WhitelistReload => "wlrl".into(),
+		MinecraftInstr::WhitelistList => "wll".into(),
+		MinecraftInstr::ListPlayers => "lsp".into(),
+		MinecraftInstr::Reload => "rl".into(),
+		MinecraftInstr::Seed => "seed".into(),
+		MinecraftInstr::StopServer => "stop".into(),
+		MinecraftInstr::StopSound => "stops".into(),
+		MinecraftInstr::GetDifficulty => "diffg".into(),
+		MinecraftInstr::SetDifficulty { difficulty } => format!("diffs {difficulty}"),
+		MinecraftInstr::SpectateStop => "specs".into(),
+		MinecraftInstr::Kill { target } => format!("kill {}", write_target(target)?),
+		MinecraftInstr::Enchant {
+			target,
+			enchantment,
+			level,
+		} => format!(
+			"ench {}, {}, {level}",
+			write_target(target)?,
+			string(enchantment)
+		),
+		MinecraftInstr::SetXP {
+			target,
+			amount,
+			value,
+		} => format!("xps {}, {amount}, {value}", write_target(target)?),
+		MinecraftInstr::AddXP {
+			target,
+			amount,
+			value,
+		} => format!("xpa {}, {amount}, {value}", write_target(target)?),
+		MinecraftInstr::GetXP { target, value } => {
+			format!("xpg {}, {value}", write_target(target)?)
+		}
+		MinecraftInstr::AddTag { target, tag } => {
+			format!("taga {}, {}", write_target(target)?, string(tag))
+		}
+		MinecraftInstr::RemoveTag { target, tag } => {
+			format!("tagr {}, {}", write_target(target)?, string(tag))
+		}
+		MinecraftInstr::ListTags { target } => format!("tagl {}", write_target(target)?),
+		MinecraftInstr::RideMount { target, vehicle } => {
+			format!("mnt {}, {}", write_target(target)?, write_target(vehicle)?)
+		}
+		MinecraftInstr::RideDismount { target } => format!("dmnt {}", write_target(target)?),
+		MinecraftInstr::Spectate { target, spectator } => format!(
+			"spec {}, {}",
+			write_target(target)?,
+			write_target(spectator)?
+		),
+		MinecraftInstr::RemoveScoreboardObjective { objective } => {
+			format!("sbor {}", string(objective))
+		}
+		MinecraftInstr::ListScoreboardObjectives => "sbol".into(),
+		MinecraftInstr::AddTeam { team, display_name } => match display_name {
+			Some(name) => format!("teama {}, {}", string(team), write_text_component(name)?),
+			None => format!("teama {}", string(team)),
+		},
+		MinecraftInstr::RemoveTeam { team } => format!("teamr {}", string(team)),
+		MinecraftInstr::EmptyTeam { team } => format!("teame {}", string(team)),
+		MinecraftInstr::JoinTeam { team, target } => {
+			format!("teamj {}, {}", string(team), write_target(target)?)
+		}
+		MinecraftInstr::LeaveTeam { target } => format!("teaml {}", write_target(target)?),
+		MinecraftInstr::ListTeams { team } => match team {
+			Some(team) => format!("teamls {}", string(team)),
+			None => "teamls".into(),
+		},
+		MinecraftInstr::ModifyTeam { team, option } => {
+			let option = match option {
+				TeamOption::DisplayName(name) => {
+					format!("display_name {}", write_text_component(name)?)
+				}
+				TeamOption::Color(color) => format!("color {color}"),
+				TeamOption::FriendlyFire(value) => format!("friendly_fire {value}"),
+				TeamOption::SeeFriendlyInvisibles(value) => {
+					format!("see_friendly_invisibles {value}")
+				}
+				TeamOption::NametagVisibility(value) => {
+					format!("nametag_visibility {}", write_team_visibility(value))
+				}
+				TeamOption::DeathMessageVisibility(value) => {
+					format!("death_message_visibility {}", write_team_visibility(value))
+				}
+				TeamOption::CollisionRule(rule) => format!(
+					"collision_rule {}",
+					match rule {
+						CollisionRule::Always => "always",
+						CollisionRule::Never => "never",
+						CollisionRule::PushOtherTeams => "push_other_teams",
+						CollisionRule::PushOwnTeam => "push_own_team",
+					}
+				),
+				TeamOption::Prefix(prefix) => format!("prefix {}", write_text_component(prefix)?),
+				TeamOption::Suffix(suffix) => format!("suffix {}", write_text_component(suffix)?),
+			};
+			format!("teamm {}, {option}", string(team))
+		}
+		MinecraftInstr::AddBossbar { id, name } => {
+			format!("bba {}, {}", string(id), write_text_component(name)?)
+		}
+		MinecraftInstr::RemoveBossbar { id } => format!("bbr {}", string(id)),
+		MinecraftInstr::SetBossbar { id, option } => {
+			let option = match option {
+				BossbarOption::Value(value) => format!("value {value}"),
+				BossbarOption::Max(max) => format!("max {max}"),
+				BossbarOption::Name(name) => format!("name {}", write_text_component(name)?),
+				BossbarOption::Players(Some(players)) => {
+					format!("players {}", write_target(players)?)
+				}
+				BossbarOption::Players(None) => "players".into(),
+			};
+			format!("bbs {}, {option}", string(id))
+		}
+		MinecraftInstr::GetBossbar { id, query } => format!("bbg {}, {query:?}", string(id)),
+		MinecraftInstr::SetObjectiveDisplayName { objective, name } => format!(
+			"sbodn {}, {}",
+			string(objective),
+			write_text_component(name)?
+		),
+		MinecraftInstr::SetObjectiveNumberFormat { objective, format } => {
+			let format = match format {
+				None => "reset".into(),
+				Some(NumberFormat::Blank) => "blank".into(),
+				Some(NumberFormat::Fixed(component)) => {
+					format!("fixed {}", write_text_component(component)?)
+				}
+				Some(NumberFormat::Styled(style)) => format!("styled {}", write_text_style(style)?),
+			};
+			format!("sbonf {}, {format}", string(objective))
+		}
+		MinecraftInstr::WorldBorderGet => "wbg".into(),
+		MinecraftInstr::RandomValue { range, sequence } => write_random("rndv", range, sequence),
+		MinecraftInstr::RandomRoll { range, sequence } => write_random("rndr", range, sequence),
+		MinecraftInstr::TriggerAdd { objective, amount } => {
+			format!("trga {}, {amount}", string(objective))
+		}
+		MinecraftInstr::TriggerSet { objective, amount } => {
+			format!("trgs {}, {amount}", string(objective))
+		}
+		MinecraftInstr::DisableDatapack { pack } => format!("dpd {}", string(pack)),
+		MinecraftInstr::EnableDatapack { pack } => format!("dpe {}", string(pack)),
+		MinecraftInstr::ListPlayerUUIDs => "lspu".into(),
+		MinecraftInstr::AddScoreboardObjective {
+			objective,
+			criterion,
+			display_name,
+		} => {
+			let mut out = format!("sboa {}, {}", string(objective), write_criterion(criterion));
+			if let Some(display_name) = display_name {
+				out.push_str(&format!(", {}", string(display_name)));
+			}
+			out
+		}
+		MinecraftInstr::SetWorldSpawn { pos, angle } => {
+			format!("sws {}, {}", write_int_coords(pos)?, write_angle(angle)?)
+		}
+		MinecraftInstr::SetSpawnpoint {
+			targets,
+			pos,
+			angle,
+		} if targets.len() == 1 => format!(
+			"ssp {}, {}, {}",
+			write_target(&targets[0])?,
+			write_int_coords(pos)?,
+			write_angle(angle)?
+		),
+		MinecraftInstr::SummonEntity { entity, pos, nbt } => format!(
+			"smn {}, {}, {}",
+			string(entity),
+			write_double_coords(pos)?,
+			write_compound_lit(nbt)?
+		),
+		MinecraftInstr::GiveItem {
+			target,
+			item,
+			amount,
+		} => format!(
+			"itmg {}, {}, {amount}",
+			write_target(target)?,
+			write_item_data(item)?
+		),
+		MinecraftInstr::ClearItems {
+			targets,
+			item: None,
+			max_count: None,
+		} if targets.len() == 1 => format!("itmc {}", write_target(&targets[0])?),
+		MinecraftInstr::ClearEffect { target, effect } => match effect {
+			Some(effect) => format!("effc {}, {}", write_target(target)?, string(effect)),
+			None => format!("effc {}", write_target(target)?),
+		},
+		MinecraftInstr::GiveEffect {
+			target,
+			effect,
+			duration,
+			amplifier,
+			hide_particles,
+		} => {
+			let duration = match duration {
+				EffectDuration::Seconds(seconds) => seconds.to_string(),
+				EffectDuration::Infinite => "infinite".into(),
+			};
+			format!(
+				"effg {}, {}, {duration}, {amplifier}, {hide_particles}",
+				write_target(target)?,
+				string(effect)
+			)
+		}
+		MinecraftInstr::AddTime { time } => format!("tima {}", write_time(time)?),
+		MinecraftInstr::SetTime { time } => format!("tims {}", write_time(time)?),
+		MinecraftInstr::ScheduleFunction {
+			function,
+			time,
+			mode,
+		} => format!("sch {}, {}, {mode:?}", string(function), write_time(time)?),
+		MinecraftInstr::ScheduleClear { function } => format!("schc {}", string(function)),
+		MinecraftInstr::TeleportToEntity { source, dest } => {
+			format!("tpe {}, {}", write_target(source)?, write_target(dest)?)
+		}
+		MinecraftInstr::TeleportToLocation { source, dest } => format!(
+			"tpl {}, {}",
+			write_target(source)?,
+			write_double_coords(dest)?
+		),
+		MinecraftInstr::TeleportWithRotation {
+			source,
+			dest,
+			rotation,
+		} => format!(
+			"tpr {}, {}, {}",
+			write_target(source)?,
+			write_double_coords(dest)?,
+			write_double_coords_2d(rotation)?
+		),
+		MinecraftInstr::TeleportFacingLocation {
+			source,
+			dest,
+			facing,
+		} => format!(
+			"tpfl {}, {}, {}",
+			write_target(source)?,
+			write_double_coords(dest)?,
+			write_double_coords(facing)?
+		),
+		MinecraftInstr::TeleportFacingEntity {
+			source,
+			dest,
+			facing,
+		} => format!(
+			"tpfe {}, {}, {}",
+			write_target(source)?,
+			write_double_coords(dest)?,
+			write_target(facing)?
+		),
+		MinecraftInstr::SetGameruleBool { rule, value } => {
+			format!("grsb {}, {value}", string(rule))
+		}
+		MinecraftInstr::SetGameruleInt { rule, value } => {
+			format!("grsi {}, {value}", string(rule))
+		}
+		MinecraftInstr::GetGamerule { rule } => format!("grg {}", string(rule)),
+		MinecraftInstr::WorldBorderAdd { dist, time } => {
+			format!("wba {}, {time}", double(*dist)?)
+		}
+		MinecraftInstr::WorldBorderSet { dist, time } => {
+			format!("wbs {}, {time}", double(*dist)?)
+		}
+		MinecraftInstr::WorldBorderCenter { pos } => format!("wbc {}", write_int_coords(pos)?),
+		MinecraftInstr::WorldBorderDamage { damage } => format!("wbd {}", double(*damage)?),
+		MinecraftInstr::WorldBorderBuffer { buffer } => format!("wbb {}", double(*buffer)?),
+		MinecraftInstr::WorldBorderWarningDistance { dist } => {
+			format!("wbwd {}", double(*dist)?)
+		}
+		MinecraftInstr::WorldBorderWarningTime { time } => format!("wbwt {time}"),
+		MinecraftInstr::Locate {
+			location_type,
+			location,
+		} => {
+			let ty = match location_type {
+				Location::Structure => "struct",
+				Location::Biome => "bio",
+				Location::POI => "poi",
+			};
+			format!("loc {ty}, {}", string(location))
+		}
+		MinecraftInstr::PlaySound {
+			sound,
+			source,
+			target,
+			pos,
+			volume,
+			pitch,
+			min_volume,
+		} => format!(
+			"ply {}, {source:?}, {}, {}, {}, {}, {}",
+			string(sound),
+			write_target(target)?,
+			write_double_coords(pos)?,
+			float(*volume)?,
+			float(*pitch)?,
+			float(*min_volume)?
+		),
+		MinecraftInstr::Particle {
+			particle,
+			pos,
+			delta,
+			speed,
+			count,
+			mode,
+			viewers,
+		} => {
+			let mut out = format!(
+				"ptc {}, {}, {}, {}, {count}, {mode:?}",
+				write_particle(particle)?,
+				write_double_coords(pos)?,
+				write_decimals(delta)?,
+				double(*speed)?
+			);
+			if let Some(viewers) = viewers {
+				out.push_str(&format!(", {}", write_target(viewers)?));
+			}
+			out
+		}
+		MinecraftInstr::SetBlock { data } => format!(
+			"sb {}, {}, {:?}",
+			write_int_coords(&data.pos)?,
+			write_block_data(&data.block)?,
+			data.mode
+		),
+		MinecraftInstr::Fill { data } => {
+			if let FillMode::Replace(Some(..)) = data.mode {
+				bail!("Fill filters have no textual form");
+			}
+			format!(
+				"fill {}, {}, {}, {:?}",
+				write_int_coords(&data.start)?,
+				write_int_coords(&data.end)?,
+				write_block_data(&data.block)?,
+				data.mode
+			)
+		}
+		MinecraftInstr::Clone { data } => {
+			if data.source_dimension.is_some() || data.target_dimension.is_some() {
+				bail!("Clone dimensions have no textual form");
+			}
+			if let CloneMaskMode::Filtered(..) = data.mask_mode {
+				bail!("Clone filters have no textual form");
+			}
+			format!(
+				"cln {}, {}, {}, {:?}, {:?}",
+				write_int_coords(&data.start)?,
+				write_int_coords(&data.end)?,
+				write_int_coords(&data.destination)?,
+				data.mask_mode,
+				data.mode
+			)
+		}
+		other => bail!("Instruction {other:?} has no textual form"),
+	};
+
+	Ok(out)
+}
+
+fn write_text_component(component: &TextComponent) -> anyhow::Result<String> {
+	let contents = match &component.contents {
+		TextComponentContents::Text(text) => string(text),
+		TextComponentContents::Score(MutableScoreValue::Local(Local::Reg(reg)))
+		| TextComponentContents::Nbt(MutableNBTValue::Local(Local::Reg(reg))) => {
+			format!("%{}", reg_name(reg)?)
+		}
+		TextComponentContents::Score(MutableScoreValue::Score(score)) => {
+			format!("sco {}", write_score(score)?)
+		}
+		TextComponentContents::Nbt(MutableNBTValue::Data(loc)) => {
+			format!("nbt {}", write_data_location(loc)?)
+		}
+		other => bail!("Text component contents {other:?} have no textual form"),
+	};
+	let mut out = if component.style.is_empty() {
+		contents
+	} else {
+		format!("sty {} {contents}", write_text_style(&component.style)?)
+	};
+	if !component.extra.is_empty() {
+		let extra: Vec<_> = component
+			.extra
+			.iter()
+			.map(write_text_component)
+			.collect::<anyhow::Result<_>>()?;
+		out = format!("[{out}, {}]", extra.join(", "));
+	}
+
+	Ok(out)
+}
+
+fn write_team_visibility(visibility: &TeamVisibility) -> &'static str {
+	match visibility {
+		TeamVisibility::Always => "always",
+		TeamVisibility::Never => "never",
+		TeamVisibility::HideForOtherTeams => "hide_for_other_teams",
+		TeamVisibility::HideForOwnTeam => "hide_for_own_team",
+	}
+}
+
+fn write_text_style(style: &TextStyle) -> anyhow::Result<String> {
+	let mut params = Vec::new();
+	if let Some(color) = &style.color {
+		params.push(format!("color={}", string(color)));
+	}
+	if let Some(font) = &style.font {
+		params.push(format!("font={}", string(font)));
+	}
+	let flags = [
+		("bold", style.bold),
+		("italic", style.italic),
+		("underlined", style.underlined),
+		("strikethrough", style.strikethrough),
+		("obfuscated", style.obfuscated),
+	];
+	for (name, val) in flags {
+		if let Some(val) = val {
+			params.push(format!("{name}={val}"));
+		}
+	}
+	if let Some(click) = &style.click_event {
+		params.push(format!("click={:?} {}", click.action, string(&click.value)));
+	}
+	if let Some(HoverEvent::ShowText(text)) = &style.hover_event {
+		params.push(format!("hover={}", write_text_component(text)?));
+	}
+
+	Ok(format!("[{}]", params.join(", ")))
+}
+
+fn write_item_data(item: &ItemData) -> anyhow::Result<String> {
+	Ok(format!(
+		"{} {}",
+		string(&item.item),
+		write_compound_lit(&item.nbt)?
+	))
+}
+
+fn write_particle(particle: &ParticleData) -> anyhow::Result<String> {
+	let out = match particle {
+		ParticleData::Simple(particle) => string(particle),
+		ParticleData::Dust { color, size } => {
+			format!("dust {} {}", write_decimals(color)?, double(*size)?)
+		}
+		ParticleData::DustColorTransition { from, to, size } => format!(
+			"dust_color_transition {} {} {}",
+			write_decimals(from)?,
+			write_decimals(to)?,
+			double(*size)?
+		),
+		ParticleData::Block(block) => format!("block {}", write_block_data(block)?),
+		ParticleData::BlockMarker(block) => format!("block_marker {}", write_block_data(block)?),
+		ParticleData::FallingDust(block) => format!("falling_dust {}", write_block_data(block)?),
+		ParticleData::Item(item) => format!("item {}", write_item_data(item)?),
+	};
+	Ok(out)
+}
+
+fn write_decimals(nums: &[f64; 3]) -> anyhow::Result<String> {
+	Ok(format!(
+		"{} {} {}",
+		double(nums[0])?,
+		double(nums[1])?,
+		double(nums[2])?
+	))
+}
+
+fn write_block_data(block: &BlockData) -> anyhow::Result<String> {
+	let states = block
+		.props
+		.states
+		.get()
+		.iter()
+		.sorted_by(|l, r| l.0.cmp(r.0))
+		.map(|(key, val)| {
+			let BlockStateValue::String(val) = val;
+			format!("{key}={}", string(val))
+		})
+		.join(", ");
+
+	Ok(format!(
+		"{} {} [{states}]",
+		string(&block.block),
+		write_compound_lit(&block.props.data)?
+	))
+}
+
+fn write_time(time: &Time) -> anyhow::Result<String> {
+	Ok(format!("{}{:?}", float(time.amount)?, time.unit))
+}
+
+fn write_random(name: &str, range: &IntRange, sequence: &Option<ResourceLocation>) -> String {
+	match sequence {
+		Some(sequence) => format!("{name} {}, {}", string(&range.to_string()), string(sequence)),
+		None => format!("{name} {}", string(&range.to_string())),
+	}
+}
+
+fn write_int_coords(coords: &Coordinates<i64>) -> anyhow::Result<String> {
+	let Coordinates::XYZ(x, y, z) = coords else {
+		bail!("Local coordinates have no textual form");
+	};
+	let part = |part: &AbsOrRelCoord<i64>| match part {
+		AbsOrRelCoord::Abs(val) => val.to_string(),
+		AbsOrRelCoord::Rel(val) => format!("~{val}"),
+	};
+
+	Ok(format!("{} {} {}", part(x), part(y), part(z)))
+}
+
+fn write_double_coords(coords: &Coordinates<f64>) -> anyhow::Result<String> {
+	let Coordinates::XYZ(x, y, z) = coords else {
+		bail!("Local coordinates have no textual form");
+	};
+
+	Ok(format!(
+		"{} {} {}",
+		write_double_coord(x)?,
+		write_double_coord(y)?,
+		write_double_coord(z)?
+	))
+}
+
+fn write_double_coords_2d(coords: &DoubleCoordinates2D) -> anyhow::Result<String> {
+	Ok(format!(
+		"{} {}",
+		write_double_coord(&coords.0)?,
+		write_double_coord(&coords.1)?
+	))
+}
+
+fn write_double_coord(part: &AbsOrRelCoord<f64>) -> anyhow::Result<String> {
+	match part {
+		AbsOrRelCoord::Abs(val) => double(*val),
+		AbsOrRelCoord::Rel(val) => Ok(format!("~{}", double(*val)?)),
+	}
+}
+
+fn write_angle(angle: &Angle) -> anyhow::Result<String> {
+	let val = float(angle.value)?;
+	if angle.relative {
+		Ok(format!("~{val}"))
+	} else {
+		Ok(val)
+	}
+}
+
+/// Writes a decimal number, which always needs a point to be lexed as one
+fn double(num: f64) -> anyhow::Result<String> {
+	if !num.is_finite() {
+		bail!("Number {num} has no textual form");
+	}
+	Ok(with_point(num.to_string()))
+}
+
+fn float(num: f32) -> anyhow::Result<String> {
+	if !num.is_finite() {
+		bail!("Number {num} has no textual form");
+	}
+	Ok(with_point(num.to_string()))
+}
+
+fn with_point(num: String) -> String {
+	if num.contains('.') {
+		num
+	} else {
+		num + ".0"
+	}
+}
+
+/// Writes a quoted string, escaping the characters that would end it
+fn string(string: &str) -> String {
+	let mut out = String::with_capacity(string.len() + 2);
+	out.push('"');
+	for c in string.chars() {
+		if c == '"' || c == '\\' {
+			out.push('\\');
+		}
+		out.push(c);
+	}
+	out.push('"');
+	out
+}
+
+/// Checks that a register name can be read back as an identifier
+fn reg_name(name: &str) -> anyhow::Result<&str> {
+	let mut chars = name.chars();
+	let valid =
+		chars.next().is_some_and(|x| is_ident(x, true)) && chars.all(|x| is_ident(x, false));
+	if !valid {
+		bail!("Register name {name} is not a valid identifier");
+	}
+	Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse::Parser;
+
+	use super::*;
+
+	fn parse(text: &str) -> IR {
+		let mut parser = Parser::new();
+		parser.parse(text).expect("Failed to parse");
+		parser.finish()
+	}
+
+	#[test]
+	fn test_round_trip() {
+		let text = r#"
+			@transient storage "test:tmp";
+			@preserve
+			@freq 20t
+			@allow broad_selector
+			@export("test:api/main")
+			"test:main" score nint : bool {
+				let x: score = val 5s;
+				let y: {"a": [nint], "b": [nbyte, 2]} = val {"a": [nint][1ni, -2ni], "b": b[1nb, 2nb]};
+				let z: bool = cond and gt %x, 3s, not exi stg "test:tmp" "a.b[0]";
+				let w: nfloat = cast nfloat sco @e[type="zombie", tag="a", limit=1] "obj";
+				add %x, &0;
+				if eq %x, 6s {
+					say "hello \"there\"";
+					mdf str reg x, int, 2.5 {
+						cmd "time query daytime";
+					};
+				};
+				ife bool %z: tell "Player", "a" {};
+				if and gm @a[], creative, not diff peaceful {
+					say "creative";
+				};
+				if or dcnt stg "test:tmp" "a[]", "2..", itm ent @s[], "weapon.*", "minecraft:stick" {
+					say "items";
+				};
+				while lt %x, 10s {
+					@allow broad_selector kill @e[];
+					kill @e[type=!"zombie", team="red", gamemode=!creative, level="1..5", distance=2.5, x=1.0, y=2.0, z=3.0, dx=4.0, scores={"foo"="1..", "bar"=3}, advancements={"story/root"=true}];
+					tpr @s[], 1.5 ~ 2.0 ~-3.0, 90.0 0.0;
+					mdf rot 45 ~-10.5 {
+						say "rotated";
+					};
+				};
+				call %x = "test:other" 1s, "str";
+				callw "test:other", %y;
+				cast stg "test:tmp" "pos", nshort %x, 0.5;
+				mcmd "say $(a)";
+				icmd "say $(x)";
+				sch "test:other", 2.5s, append;
+				teama "red", sty [color="red"] "Red";
+				teamm "red", nametag_visibility hide_for_other_teams;
+				tellraw @a[], [sty [color="gold", click=run_command "/say hi", hover="tip"] "Score: ", %x, sco @s[] "obj", nbt stg "test:tmp" "a"];
+				bbs "test:bar", players @a[];
+				mdf str bb "test:bar" value {
+					cmd "time query daytime";
+				};
+				effg @s[], "speed", 30, 1, false;
+				sb 0 ~1 0, "stone" {} [facing="north"], replace;
+				ptc dust 1.0 0.0 0.5 1.5, ~0.0 ~1.0 ~0.0, 0.5 0.5 0.5, 0.1, 10, force, @a[];
+				ptc item "minecraft:stick" {}, 0.0 64.0 0.0, 0.0 0.0 0.0, 0.0, 1, normal;
+				spread 2, item: nint, %y, "test:done" {
+					say "item";
+				};
+				retv 0, true;
+			}
+		"#;
+		let ir = parse(text);
+		let first = unparse_ir(&ir).expect("Failed to unparse IR");
+		let second = unparse_ir(&parse(&first)).expect("Failed to unparse parsed IR");
+		assert_eq!(first, second);
+		let first = &ir.functions[&Identifier::from("test:main")];
+		let second = parse(&second);
+		let second = &second.functions[&Identifier::from("test:main")];
+		assert_eq!(first.block, second.block);
+	}
+}
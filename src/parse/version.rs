@@ -0,0 +1,185 @@
+use anyhow::{bail, Context};
+
+use super::lex::{lex, Token, TokenAndPos};
+
+/// The current version of the text format
+pub const CURRENT_VERSION: u32 = 1;
+
+/// The identifier that starts the version header
+pub const VERSION_HEADER: &str = "dpc_version";
+
+/// Changes to the syntax between one version and the next
+pub struct Migration {
+	/// The version that this migration upgrades to
+	pub to: u32,
+	/// Instruction mnemonics that were renamed, from old to new
+	pub renames: &'static [(&'static str, &'static str)],
+}
+
+/// Every migration, in order of version
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// The version header of a file, if it has one
+pub struct VersionHeader {
+	pub version: u32,
+	/// The number of tokens that the header takes up, including ignored ones
+	pub len: usize,
+}
+
+/// Reads the version header from the start of a list of tokens
+pub fn read_header(tokens: &[TokenAndPos]) -> anyhow::Result<Option<VersionHeader>> {
+	let mut toks = tokens
+		.iter()
+		.enumerate()
+		.filter(|(_, (tok, _))| !tok.is_ignored());
+
+	match toks.next() {
+		Some((_, (Token::Ident(ident), _))) if ident == VERSION_HEADER => {}
+		_ => return Ok(None),
+	}
+	let version = match toks.next() {
+		Some((_, (Token::Num(num), pos))) => u32::try_from(*num)
+			.ok()
+			.filter(|x| *x > 0)
+			.with_context(|| format!("Invalid version {num} {pos}"))?,
+		Some((_, (tok, pos))) => bail!("Expected version number, found {tok:?} {pos}"),
+		None => bail!("Expected version number"),
+	};
+	let len = match toks.next() {
+		Some((i, (Token::Semicolon, _))) => i + 1,
+		Some((_, (tok, pos))) => bail!("Expected ; after version header, found {tok:?} {pos}"),
+		None => bail!("Expected ; after version header"),
+	};
+
+	Ok(Some(VersionHeader { version, len }))
+}
+
+/// Checks that a version can be read by this compiler
+pub fn check_version(version: u32) -> anyhow::Result<()> {
+	check_version_impl(version, CURRENT_VERSION)
+}
+
+fn check_version_impl(version: u32, current: u32) -> anyhow::Result<()> {
+	if version > current {
+		bail!(
+			"File uses version {version} of the text format, but this compiler only supports up to version {current}"
+		);
+	}
+
+	Ok(())
+}
+
+/// Gets the renames needed to bring a file from a version up to the current one
+fn get_renames(
+	version: u32,
+	migrations: &[Migration],
+) -> impl Iterator<Item = &(&'static str, &'static str)> {
+	migrations
+		.iter()
+		.filter(move |x| x.to > version)
+		.flat_map(|x| x.renames)
+}
+
+/// Applies migrations to lexed tokens from an older version
+pub fn migrate_tokens(tokens: &mut [TokenAndPos], version: u32, migrations: &[Migration]) {
+	for (old, new) in get_renames(version, migrations) {
+		for (tok, _) in tokens.iter_mut() {
+			if let Token::Ident(ident) = tok {
+				if ident == old {
+					*ident = new.to_string();
+				}
+			}
+		}
+	}
+}
+
+/// Rewrites a file to the current version, applying renames and
+/// adding or updating the version header
+pub fn migrate_text(text: &str) -> anyhow::Result<String> {
+	migrate_text_impl(text, MIGRATIONS, CURRENT_VERSION)
+}
+
+fn migrate_text_impl(
+	text: &str,
+	migrations: &[Migration],
+	current: u32,
+) -> anyhow::Result<String> {
+	let tokens = lex(text).context("Failed to lex text")?;
+	let header = read_header(&tokens)?;
+	let version = header.as_ref().map(|x| x.version).unwrap_or(current);
+	check_version_impl(version, current)?;
+
+	// Replacements of character ranges with new text
+	let mut replacements = Vec::new();
+	let new_header = format!("{VERSION_HEADER} {current};");
+	let body_start = if let Some(header) = &header {
+		let start = tokens
+			.iter()
+			.find(|(tok, _)| !tok.is_ignored())
+			.map(|(_, pos)| pos.start)
+			.unwrap_or_default();
+		let end = tokens[header.len - 1].1.end;
+		replacements.push((start, end, new_header));
+		header.len
+	} else {
+		// Put the header after any leading comments
+		let start = tokens
+			.iter()
+			.find(|(tok, _)| !tok.is_ignored())
+			.map(|(_, pos)| pos.start)
+			.unwrap_or(text.chars().count());
+		replacements.push((start, start, new_header + "\n\n"));
+		0
+	};
+
+	for (old, new) in get_renames(version, migrations) {
+		for (tok, pos) in &tokens[body_start..] {
+			if let Token::Ident(ident) = tok {
+				if ident == old {
+					let start = pos.start;
+					replacements.push((start, start + old.chars().count(), new.to_string()));
+				}
+			}
+		}
+	}
+	replacements.sort_by_key(|x| x.0);
+
+	let chars: Vec<_> = text.chars().collect();
+	let mut out = String::with_capacity(text.len());
+	let mut i = 0;
+	for (start, end, replacement) in replacements {
+		out.extend(&chars[i..start]);
+		out.push_str(&replacement);
+		i = end;
+	}
+	out.extend(&chars[i..]);
+
+	Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEST_MIGRATIONS: &[Migration] = &[Migration {
+		to: 2,
+		renames: &[("old", "new")],
+	}];
+
+	#[test]
+	fn test_migrate_text() {
+		let text = "# comment\n\"foo:bar\" {\n\told %a;\n}\n";
+		let migrated = migrate_text_impl(text, TEST_MIGRATIONS, 2).unwrap();
+		assert_eq!(
+			migrated,
+			"# comment\ndpc_version 2;\n\n\"foo:bar\" {\n\told %a;\n}\n"
+		);
+
+		let text = "dpc_version 1;\n\"foo:bar\" {\n\told %a;\n}\n";
+		let migrated = migrate_text_impl(text, TEST_MIGRATIONS, 2).unwrap();
+		assert_eq!(migrated, "dpc_version 2;\n\"foo:bar\" {\n\tnew %a;\n}\n");
+
+		let text = "dpc_version 5;";
+		assert!(migrate_text_impl(text, TEST_MIGRATIONS, 2).is_err());
+	}
+}
@@ -0,0 +1,102 @@
+use anyhow::bail;
+use rustc_hash::FxHashSet;
+
+use crate::common::function::CallInterface;
+use crate::common::mc::instr::MinecraftInstr;
+use crate::common::ResourceLocation;
+use crate::ir::{Block, InstrKind, IR};
+
+/// Checks that nothing inside of an atomic scope, including the functions that
+/// it calls, could make part of it run on a later tick
+pub fn check_atomic_scopes(ir: &IR) -> anyhow::Result<()> {
+	for (func_id, func) in &ir.functions {
+		check_block(&func.block, func_id, ir)?;
+	}
+
+	Ok(())
+}
+
+fn check_block(block: &Block, func_id: &ResourceLocation, ir: &IR) -> anyhow::Result<()> {
+	for instr in &block.contents {
+		match &instr.kind {
+			InstrKind::Atomic { body } => {
+				if let Some(reason) = find_violation(body, ir, &mut FxHashSet::default()) {
+					bail!("Atomic scope in function {func_id} {reason}");
+				}
+			}
+			other => {
+				for body in other.get_bodies() {
+					check_block(body, func_id, ir)?;
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Finds the first thing in a block that would break atomicity, with a description of it
+fn find_violation(
+	block: &Block,
+	ir: &IR,
+	visited: &mut FxHashSet<ResourceLocation>,
+) -> Option<String> {
+	for instr in &block.contents {
+		let violation = match &instr.kind {
+			InstrKind::Command { command } | InstrKind::InterpolatedCommand { command }
+				if command.trim_start().starts_with("schedule ") =>
+			{
+				Some(format!("schedules a function with the command '{command}'"))
+			}
+			InstrKind::MC(MinecraftInstr::ScheduleFunction { function, .. }) => {
+				Some(format!("schedules function {function}"))
+			}
+			InstrKind::SpreadOverTicks { .. } => Some("spreads work over multiple ticks".into()),
+			InstrKind::Call {
+				call: CallInterface { function, .. },
+			}
+			| InstrKind::CallExtern { func: function }
+			| InstrKind::CallWith { func: function, .. } => {
+				if !visited.insert(function.clone()) {
+					continue;
+				}
+				// Functions that aren't in the IR can't be checked
+				ir.functions.get(function).and_then(|callee| {
+					find_violation(&callee.block, ir, visited)
+						.map(|reason| format!("calls function {function}, which {reason}"))
+				})
+			}
+			other => other
+				.get_bodies()
+				.into_iter()
+				.find_map(|body| find_violation(body, ir, visited)),
+		};
+		if violation.is_some() {
+			return violation;
+		}
+	}
+
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse::Parser;
+
+	use super::*;
+
+	fn check(body: &str) -> anyhow::Result<()> {
+		let mut parser = Parser::new();
+		let text = format!(r#""test:main" {{ atomic {{ {body} }}; }} "test:other" {{}}"#);
+		parser.parse(&text).expect("Failed to parse");
+		check_atomic_scopes(&parser.finish())
+	}
+
+	#[test]
+	fn test_atomic_schedule() {
+		assert!(check(r#"say "hi";"#).is_ok());
+		assert!(check(r#"cmd "schedule function test:other 1t";"#).is_err());
+		assert!(check(r#"sch "test:other", 1t, replace;"#).is_err());
+		assert!(check(r#"if eq %x, 1s { sch "test:other", 1t, append; };"#).is_err());
+	}
+}
@@ -0,0 +1,158 @@
+use crate::common::diag::Diagnostic;
+use crate::common::lint::{report, Lint, LintLevel};
+use crate::common::mc::entity::{SelectorParameter, SelectorType};
+use crate::common::mc::instr::meta::TargetUse;
+use crate::common::mc::{DataLocation, EntityTarget};
+use crate::common::val::MutableValue;
+use crate::common::ResourceLocation;
+use crate::ir::{Block, InstrKind, IR};
+use crate::passes::{IRPass, Pass};
+use crate::project::ProjectSettings;
+
+/// Warns about destructive instructions that target every entity in the world,
+/// like `kill @e`, which are usually missing a constraint on what they select
+pub struct BroadSelectorsPass {
+	level: LintLevel,
+}
+
+impl BroadSelectorsPass {
+	pub fn new(proj: &ProjectSettings) -> Self {
+		Self {
+			level: proj.get_lint_level(Lint::BroadSelector),
+		}
+	}
+}
+
+impl Pass for BroadSelectorsPass {
+	fn get_name(&self) -> &'static str {
+		"broad_selectors"
+	}
+
+	fn should_run(&self, _: &ProjectSettings) -> bool {
+		self.level != LintLevel::Allow
+	}
+}
+
+impl IRPass for BroadSelectorsPass {
+	fn run_pass(&mut self, ir: &mut IR) -> anyhow::Result<()> {
+		for (func_id, func) in &ir.functions {
+			let allowed = &func.interface.annotations.allow;
+			self.check_block(&func.block, func_id, allowed, &mut ir.diagnostics)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl BroadSelectorsPass {
+	fn check_block(
+		&self,
+		block: &Block,
+		func_id: &ResourceLocation,
+		func_allowed: &[Lint],
+		diagnostics: &mut Vec<Diagnostic>,
+	) -> anyhow::Result<()> {
+		for instr in &block.contents {
+			if let Some(target) = get_destroyed_target(&instr.kind) {
+				if is_broad(target) {
+					report(
+						diagnostics,
+						Lint::BroadSelector,
+						self.level,
+						instr.allowed_lints.iter().chain(func_allowed),
+						format!("Instruction {:?} in function {func_id} affects every entity. Consider limiting the selector by type, tag, or distance", instr.kind),
+						instr.span,
+					)?;
+				}
+			}
+			for body in instr.kind.get_bodies() {
+				self.check_block(body, func_id, func_allowed, diagnostics)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Gets the target of an instruction that kills, moves, or changes the data of entities
+fn get_destroyed_target(kind: &InstrKind) -> Option<&EntityTarget> {
+	match kind {
+		InstrKind::MC(instr) if instr.get_meta().targets == TargetUse::Moved => {
+			instr.get_targets().into_iter().next()
+		}
+		InstrKind::Assign { left, .. }
+		| InstrKind::Merge { left, .. }
+		| InstrKind::Push { left, .. }
+		| InstrKind::PushFront { left, .. }
+		| InstrKind::Insert { left, .. }
+		| InstrKind::Rand { left, .. }
+		| InstrKind::Remove { val: left } => get_entity_data_target(left),
+		_ => None,
+	}
+}
+
+/// Gets the entity whose data a value is stored in
+fn get_entity_data_target(val: &MutableValue) -> Option<&EntityTarget> {
+	match val {
+		MutableValue::Data(loc) => match &loc.loc {
+			DataLocation::Entity(target) => Some(target),
+			_ => None,
+		},
+		MutableValue::Property(val, ..) | MutableValue::Index(val, ..) => {
+			get_entity_data_target(val)
+		}
+		_ => None,
+	}
+}
+
+/// Checks if a target selects all entities without anything that narrows it down
+fn is_broad(target: &EntityTarget) -> bool {
+	let EntityTarget::Selector(sel) = target else {
+		return false;
+	};
+	matches!(sel.selector, SelectorType::AllEntities)
+		&& !sel.params.iter().any(|x| {
+			matches!(
+				x,
+				SelectorParameter::Type { invert: false, .. }
+					| SelectorParameter::Tag { invert: false, .. }
+					| SelectorParameter::Name { invert: false, .. }
+					| SelectorParameter::Distance { .. }
+					| SelectorParameter::Limit(..)
+			)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::common::mc::entity::TargetSelector;
+
+	use super::*;
+
+	#[test]
+	fn test_broad_selectors() {
+		let all = TargetSelector::new(SelectorType::AllEntities);
+		assert!(is_broad(&EntityTarget::Selector(all)));
+
+		let typed = TargetSelector::with_params(
+			SelectorType::AllEntities,
+			vec![SelectorParameter::Type {
+				ty: "zombie".into(),
+				invert: false,
+			}],
+		);
+		assert!(!is_broad(&EntityTarget::Selector(typed)));
+
+		let inverted = TargetSelector::with_params(
+			SelectorType::AllEntities,
+			vec![SelectorParameter::Type {
+				ty: "player".into(),
+				invert: true,
+			}],
+		);
+		assert!(is_broad(&EntityTarget::Selector(inverted)));
+
+		let players = TargetSelector::new(SelectorType::AllPlayers);
+		assert!(!is_broad(&EntityTarget::Selector(players)));
+	}
+}
@@ -0,0 +1,276 @@
+use std::fmt::Display;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::common::cost::GetCost;
+use crate::common::function::InvocationFrequency;
+use crate::common::ResourceLocation;
+use crate::lir::{LIRInstrKind, LIRInstruction, LIR};
+
+/// Estimated performance impact of a function that has an invocation frequency
+#[derive(Debug, Clone)]
+pub struct TickBudgetEntry {
+	pub function: ResourceLocation,
+	pub freq: InvocationFrequency,
+	/// Estimated number of commands run each time the function is invoked,
+	/// including the commands of the functions that it calls
+	pub commands: f32,
+	/// Estimated cost each time the function is invoked from the cost model
+	pub cost: f32,
+}
+
+impl TickBudgetEntry {
+	pub fn get_commands_per_tick(&self) -> f32 {
+		self.commands * self.freq.get_invocations_per_tick()
+	}
+
+	pub fn get_cost_per_tick(&self) -> f32 {
+		self.cost * self.freq.get_invocations_per_tick()
+	}
+}
+
+/// Report of the estimated commands per tick of the functions in a pack,
+/// sorted so that the biggest offenders come first
+#[derive(Debug, Clone)]
+pub struct TickBudgetReport {
+	pub entries: Vec<TickBudgetEntry>,
+}
+
+impl TickBudgetReport {
+	/// Gets the total estimated commands run every tick
+	pub fn get_total_commands_per_tick(&self) -> f32 {
+		self.entries.iter().map(|x| x.get_commands_per_tick()).sum()
+	}
+
+	/// Gets the functions with the highest commands per tick
+	pub fn top(&self, count: usize) -> &[TickBudgetEntry] {
+		&self.entries[..count.min(self.entries.len())]
+	}
+}
+
+impl Display for TickBudgetReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(
+			f,
+			"Estimated commands per tick: {:.3}",
+			self.get_total_commands_per_tick()
+		)?;
+		for entry in self.top(10) {
+			writeln!(
+				f,
+				"  {} ({:?}): {:.3} commands/tick, {:.3} cost/tick",
+				entry.function,
+				entry.freq,
+				entry.get_commands_per_tick(),
+				entry.get_cost_per_tick()
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Estimates the commands per tick of every function annotated with a frequency.
+/// Conditional commands are assumed to always run, so this is an upper bound
+pub fn estimate_tick_budget(lir: &LIR) -> TickBudgetReport {
+	let mut cache = FxHashMap::default();
+	let mut entries = Vec::new();
+	for (func_id, func) in &lir.functions {
+		let Some(freq) = func.interface.annotations.freq else {
+			continue;
+		};
+		let (commands, cost) =
+			estimate_function(func_id, lir, &mut cache, &mut FxHashSet::default());
+		entries.push(TickBudgetEntry {
+			function: func_id.clone(),
+			freq,
+			commands,
+			cost,
+		});
+	}
+
+	entries.sort_by(|l, r| {
+		r.get_commands_per_tick()
+			.total_cmp(&l.get_commands_per_tick())
+			.then_with(|| l.function.cmp(&r.function))
+	});
+
+	TickBudgetReport { entries }
+}
+
+/// Estimates the commands and cost of a single invocation of a function
+fn estimate_function(
+	func_id: &ResourceLocation,
+	lir: &LIR,
+	cache: &mut FxHashMap<ResourceLocation, (f32, f32)>,
+	stack: &mut FxHashSet<ResourceLocation>,
+) -> (f32, f32) {
+	if let Some(estimate) = cache.get(func_id) {
+		return *estimate;
+	}
+	// Extern functions and recursive calls can't be estimated
+	let Some(func) = lir.functions.get(func_id) else {
+		return (0.0, 0.0);
+	};
+	if !stack.insert(func_id.clone()) {
+		return (0.0, 0.0);
+	}
+
+	let estimate = if let Some(cost) = func.interface.annotations.cost {
+		(cost as f32, cost as f32)
+	} else {
+		func.block
+			.contents
+			.iter()
+			.map(|instr| estimate_instr(instr, lir, cache, stack))
+			.fold((0.0, 0.0), |acc, x| (acc.0 + x.0, acc.1 + x.1))
+	};
+
+	stack.remove(func_id);
+	cache.insert(func_id.clone(), estimate);
+	estimate
+}
+
+fn estimate_instr(
+	instr: &LIRInstruction,
+	lir: &LIR,
+	cache: &mut FxHashMap<ResourceLocation, (f32, f32)>,
+	stack: &mut FxHashSet<ResourceLocation>,
+) -> (f32, f32) {
+	let mut commands = 1.0;
+	let mut cost = 1.0 + instr.modifiers.iter().map(|x| x.get_cost()).sum::<f32>();
+	match &instr.kind {
+		LIRInstrKind::Call(func, ..) | LIRInstrKind::CallWith(func, ..) => {
+			let (call_commands, call_cost) = estimate_function(func, lir, cache, stack);
+			commands += call_commands;
+			cost += call_cost;
+		}
+		LIRInstrKind::ReturnRun(body) => {
+			let (body_commands, body_cost) = estimate_instr(body, lir, cache, stack);
+			// The return itself is on the same line
+			commands += body_commands - 1.0;
+			cost += body_cost;
+		}
+		_ => {}
+	}
+
+	(commands, cost)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::lower::{ir_to_mir::lower_ir, mir_to_lir::lower_mir};
+	use crate::parse::Parser;
+
+	use super::*;
+
+	fn estimate(source: &str) -> TickBudgetReport {
+		let mut parser = Parser::new();
+		parser.parse(source).expect("Failed to parse");
+		let mir = lower_ir(parser.finish()).expect("Failed to lower IR");
+		let lir = lower_mir(mir).expect("Failed to lower MIR");
+		estimate_tick_budget(&lir)
+	}
+
+	fn get_entry<'r>(report: &'r TickBudgetReport, func: &str) -> &'r TickBudgetEntry {
+		report
+			.entries
+			.iter()
+			.find(|x| x.function == ResourceLocation::from(func))
+			.expect("Function is not in the report")
+	}
+
+	#[test]
+	fn test_function_commands() {
+		let report = estimate(
+			r#"
+			@freq tick
+			"test:main" {
+				say "a";
+				say "b";
+				call run "test:other";
+			}
+
+			"test:other" {
+				say "c";
+			}
+			"#,
+		);
+		// Functions without a frequency are only counted through their callers
+		assert_eq!(report.entries.len(), 1);
+		let entry = get_entry(&report, "test:main");
+		assert_eq!(entry.commands, 4.0);
+		assert_eq!(entry.get_commands_per_tick(), 4.0);
+		assert_eq!(report.get_total_commands_per_tick(), 4.0);
+	}
+
+	#[test]
+	fn test_frequency_and_cost() {
+		let report = estimate(
+			r#"
+			@freq 20t
+			@cost 10
+			"test:interval" {
+				say "a";
+			}
+
+			@freq rare
+			"test:rare" {
+				call run "test:manual";
+			}
+
+			@freq tick
+			"test:tick" {
+				say "a";
+			}
+
+			@cost 1200
+			"test:manual" {
+				say "a";
+			}
+			"#,
+		);
+
+		// The manual cost replaces the estimate from the commands
+		let interval = get_entry(&report, "test:interval");
+		assert_eq!(interval.commands, 10.0);
+		assert_eq!(interval.get_commands_per_tick(), 0.5);
+		let rare = get_entry(&report, "test:rare");
+		assert_eq!(rare.commands, 1201.0);
+		assert!((rare.get_commands_per_tick() - 1201.0 / 1200.0).abs() < 0.0001);
+
+		// The biggest offenders come first
+		let order: Vec<_> = report
+			.entries
+			.iter()
+			.map(|x| x.function.to_string())
+			.collect();
+		assert_eq!(order, ["test:rare", "test:tick", "test:interval"]);
+	}
+
+	#[test]
+	fn test_recursion() {
+		let report = estimate(
+			r#"
+			@freq tick
+			"test:recurse" {
+				say "a";
+				call run "test:recurse";
+			}
+
+			@freq tick
+			"test:loop" {
+				let x: score = val 0s;
+				while lt %x, 10s {
+					add %x, 1s;
+				};
+			}
+			"#,
+		);
+		// Recursive calls are only counted once instead of forever
+		assert_eq!(get_entry(&report, "test:recurse").commands, 2.0);
+		let looped = get_entry(&report, "test:loop");
+		assert!(looped.commands.is_finite());
+		assert!(looped.commands > 1.0);
+	}
+}
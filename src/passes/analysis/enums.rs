@@ -0,0 +1,242 @@
+use std::fmt::Debug;
+
+use crate::common::condition::Condition;
+use crate::common::function::FunctionSignature;
+use crate::common::ty::{DataType, ScoreType};
+use crate::common::val::{MutableValue, Value};
+use crate::common::RegisterList;
+use crate::ir::{Block, InstrKind};
+
+/// A chain of if / else instructions that compare the same enum value to constants,
+/// like the ones that switches are lowered to
+pub struct EnumChain<'a> {
+	instr: &'a InstrKind,
+	val: &'a MutableValue,
+	count: u32,
+}
+
+impl<'a> EnumChain<'a> {
+	/// Gets the chain that starts at an instruction, if it compares an enum
+	pub fn new(instr: &'a InstrKind, regs: &RegisterList, sig: &FunctionSignature) -> Option<Self> {
+		let (InstrKind::If { condition, .. } | InstrKind::IfElse { condition, .. }) = instr else {
+			return None;
+		};
+		let val = get_compared_value(condition)?;
+		let Ok(DataType::Score(ScoreType::Enum(count))) = val.get_ty(regs, sig) else {
+			return None;
+		};
+
+		Some(Self { instr, val, count })
+	}
+
+	/// Gets the enum value that the chain compares
+	pub fn get_value(&self) -> &'a MutableValue {
+		self.val
+	}
+
+	/// Gets all of the variants that the value can have
+	pub fn get_variants(&self) -> ValueSet {
+		ValueSet::enum_variants(self.count)
+	}
+
+	/// Gets the variants that none of the bodies in the chain are run for
+	pub fn get_unhandled(&self) -> ValueSet {
+		get_unhandled(self.instr, self.val, self.get_variants())
+	}
+
+	/// Gets the bodies in the chain that don't continue it
+	pub fn get_leaves(&self) -> Vec<&'a Block> {
+		let mut out = Vec::new();
+		get_leaves(self.instr, self.val, &mut out);
+		out
+	}
+}
+
+fn get_unhandled(instr: &InstrKind, val: &MutableValue, values: ValueSet) -> ValueSet {
+	match instr {
+		InstrKind::If { condition, body } => match split_values(condition, val, &values) {
+			Some((t, f)) => get_unhandled_block(body, val, t).union(&f),
+			None => ValueSet::default(),
+		},
+		InstrKind::IfElse {
+			condition,
+			first,
+			second,
+		} => match split_values(condition, val, &values) {
+			Some((t, f)) => {
+				get_unhandled_block(first, val, t).union(&get_unhandled_block(second, val, f))
+			}
+			None => ValueSet::default(),
+		},
+		_ => ValueSet::default(),
+	}
+}
+
+fn get_unhandled_block(block: &Block, val: &MutableValue, values: ValueSet) -> ValueSet {
+	match block.contents.as_slice() {
+		[] => values,
+		[instr] if continues_chain(&instr.kind, val) => get_unhandled(&instr.kind, val, values),
+		_ => ValueSet::default(),
+	}
+}
+
+fn get_leaves<'a>(instr: &'a InstrKind, val: &MutableValue, out: &mut Vec<&'a Block>) {
+	let bodies = match instr {
+		InstrKind::If { body, .. } => vec![body.as_ref()],
+		InstrKind::IfElse { first, second, .. } => vec![first.as_ref(), second.as_ref()],
+		_ => Vec::new(),
+	};
+	for body in bodies {
+		match body.contents.as_slice() {
+			[instr] if continues_chain(&instr.kind, val) => get_leaves(&instr.kind, val, out),
+			_ => out.push(body),
+		}
+	}
+}
+
+/// Checks if an instruction is another condition in a chain over a value
+pub fn continues_chain(instr: &InstrKind, val: &MutableValue) -> bool {
+	let (InstrKind::If { condition, .. } | InstrKind::IfElse { condition, .. }) = instr else {
+		return false;
+	};
+	matches!(get_compared_value(condition), Some(other) if other.is_same_val(val))
+}
+
+/// Gets the value that a condition compares to a constant
+fn get_compared_value(condition: &Condition) -> Option<&MutableValue> {
+	match condition {
+		Condition::Not(condition) => get_compared_value(condition),
+		Condition::Equal(Value::Mutable(val), Value::Constant(..))
+		| Condition::Equal(Value::Constant(..), Value::Mutable(val))
+		| Condition::LessThan(Value::Mutable(val), Value::Constant(..))
+		| Condition::LessThanOrEqual(Value::Mutable(val), Value::Constant(..))
+		| Condition::GreaterThan(Value::Mutable(val), Value::Constant(..))
+		| Condition::GreaterThanOrEqual(Value::Mutable(val), Value::Constant(..)) => Some(val),
+		_ => None,
+	}
+}
+
+/// Splits a set of values into the ones that a condition over a value is true
+/// and false for, if the condition compares that value to a constant
+pub fn split_values(
+	condition: &Condition,
+	val: &MutableValue,
+	values: &ValueSet,
+) -> Option<(ValueSet, ValueSet)> {
+	let (l, r) = match condition {
+		Condition::Not(condition) => {
+			let (t, f) = split_values(condition, val, values)?;
+			return Some((f, t));
+		}
+		Condition::Equal(Value::Constant(r), Value::Mutable(l)) => (l, r),
+		Condition::Equal(Value::Mutable(l), Value::Constant(r))
+		| Condition::LessThan(Value::Mutable(l), Value::Constant(r))
+		| Condition::LessThanOrEqual(Value::Mutable(l), Value::Constant(r))
+		| Condition::GreaterThan(Value::Mutable(l), Value::Constant(r))
+		| Condition::GreaterThanOrEqual(Value::Mutable(l), Value::Constant(r)) => (l, r),
+		_ => return None,
+	};
+	if !l.is_same_val(val) {
+		return None;
+	}
+	let r = r.try_get_i32()? as i64;
+
+	let (start, end) = match condition {
+		Condition::Equal(..) => (r, r),
+		Condition::LessThan(..) => (i64::MIN, r - 1),
+		Condition::LessThanOrEqual(..) => (i64::MIN, r),
+		Condition::GreaterThan(..) => (r + 1, i64::MAX),
+		Condition::GreaterThanOrEqual(..) => (r, i64::MAX),
+		_ => return None,
+	};
+	let t = values.intersect(start, end);
+	let f = values
+		.intersect(i64::MIN, start.saturating_sub(1))
+		.union(&values.intersect(end.saturating_add(1), i64::MAX));
+
+	Some((t, f))
+}
+
+/// A set of integers, stored as sorted inclusive ranges that don't touch
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct ValueSet(Vec<(i64, i64)>);
+
+impl ValueSet {
+	/// Creates the set of all of the variants of an enum
+	pub fn enum_variants(count: u32) -> Self {
+		Self(vec![(0, count as i64 - 1)])
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Gets the values in this set that are in a range
+	pub fn intersect(&self, start: i64, end: i64) -> Self {
+		let ranges = self.0.iter().filter_map(|(l, r)| {
+			let (l, r) = ((*l).max(start), (*r).min(end));
+			(l <= r).then_some((l, r))
+		});
+		Self(ranges.collect())
+	}
+
+	pub fn union(&self, other: &Self) -> Self {
+		let mut ranges: Vec<_> = self.0.iter().chain(&other.0).copied().collect();
+		ranges.sort();
+		let mut out: Vec<(i64, i64)> = Vec::with_capacity(ranges.len());
+		for (l, r) in ranges {
+			match out.last_mut() {
+				Some(last) if l <= last.1.saturating_add(1) => last.1 = last.1.max(r),
+				_ => out.push((l, r)),
+			}
+		}
+		Self(out)
+	}
+}
+
+impl Debug for ValueSet {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (i, (l, r)) in self.0.iter().enumerate() {
+			if i != 0 {
+				write!(f, ", ")?;
+			}
+			if l == r {
+				write!(f, "{l}")?;
+			} else {
+				write!(f, "{l}..{r}")?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::ty::{DataTypeContents, ScoreTypeContents};
+
+	#[test]
+	fn test_split_values() {
+		let val = MutableValue::Reg("x".into());
+		let constant = |x| Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(x)));
+		let values = ValueSet::enum_variants(5);
+
+		let condition = Condition::Equal(Value::Mutable(val.clone()), constant(2));
+		let (t, f) = split_values(&condition, &val, &values).expect("Should split");
+		assert_eq!(format!("{t:?}"), "2");
+		assert_eq!(format!("{f:?}"), "0..1, 3..4");
+
+		let condition = Condition::Not(Box::new(Condition::LessThanOrEqual(
+			Value::Mutable(val.clone()),
+			constant(0),
+		)));
+		let (t, f) = split_values(&condition, &val, &f).expect("Should split");
+		assert_eq!(format!("{t:?}"), "1, 3..4");
+		assert_eq!(format!("{f:?}"), "0");
+		assert_eq!(t.union(&f), ValueSet(vec![(0, 1), (3, 4)]));
+
+		let other = MutableValue::Reg("y".into());
+		assert!(split_values(&condition, &other, &values).is_none());
+	}
+}
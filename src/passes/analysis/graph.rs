@@ -0,0 +1,172 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::reg::GetUsedRegs;
+use crate::common::val::MutableValue;
+use crate::common::{Identifier, ResourceLocation};
+use crate::mir::{MIRBlock, MIRInstrKind, MIR};
+
+/// Exports the call graph and the register def-use chains of every function
+/// as a Graphviz graph. Each function is a cluster containing its instructions,
+/// with edges between the instructions that define and use each register
+pub fn export_dot(mir: &MIR) -> String {
+	let mut out = String::new();
+	let _ = writeln!(out, "digraph dpc {{");
+	let _ = writeln!(out, "\tcompound=true;");
+	let _ = writeln!(out, "\tnode [shape=box, fontname=monospace];");
+
+	let func_ids: FxHashMap<_, _> = mir
+		.functions
+		.keys()
+		.sorted()
+		.enumerate()
+		.map(|(i, x)| (x.clone(), i))
+		.collect();
+
+	let mut calls = Vec::new();
+	for (func_id, func) in mir.functions.iter().sorted_by_key(|x| x.0) {
+		let index = func_ids[func_id];
+		// Roots of DCE get a double border
+		let peripheries = if func.interface.annotations.is_root() {
+			2
+		} else {
+			1
+		};
+
+		let _ = writeln!(out, "\tsubgraph cluster_{index} {{");
+		let _ = writeln!(out, "\t\tlabel=\"{}\";", escape(func_id));
+		let _ = writeln!(
+			out,
+			"\t\tf{index} [label=\"{}\", shape=ellipse, peripheries={peripheries}];",
+			escape(func_id)
+		);
+
+		let mut gcx = GraphCx {
+			func_index: index,
+			instr_count: 0,
+			defs: FxHashMap::default(),
+			out: &mut out,
+			calls: &mut calls,
+		};
+		gcx.graph_block(&func.block);
+
+		let _ = writeln!(out, "\t}}");
+	}
+
+	for (from, callee) in calls {
+		match func_ids.get(&callee) {
+			Some(to) => {
+				let _ = writeln!(out, "\t{from} -> f{to} [color=blue, lhead=cluster_{to}];");
+			}
+			None => {
+				let _ = writeln!(
+					out,
+					"\t\"{}\" [shape=ellipse, style=dashed];",
+					escape(&callee)
+				);
+				let _ = writeln!(
+					out,
+					"\t{from} -> \"{}\" [color=blue, style=dashed];",
+					escape(&callee)
+				);
+			}
+		}
+	}
+
+	let _ = writeln!(out, "}}");
+	out
+}
+
+struct GraphCx<'out> {
+	func_index: usize,
+	instr_count: usize,
+	/// The node that last defined each register
+	defs: FxHashMap<Identifier, String>,
+	out: &'out mut String,
+	/// Calls from an instruction node to a function
+	calls: &'out mut Vec<(String, ResourceLocation)>,
+}
+
+impl<'out> GraphCx<'out> {
+	fn graph_block(&mut self, block: &MIRBlock) {
+		for instr in &block.contents {
+			let node = format!("f{}_{}", self.func_index, self.instr_count);
+			self.instr_count += 1;
+
+			let label = match &instr.kind {
+				MIRInstrKind::If { condition, .. } | MIRInstrKind::IfElse { condition, .. } => {
+					format!("if {condition:?}")
+				}
+				MIRInstrKind::Modify { modifier, .. } => format!("mdf {modifier:?}"),
+				MIRInstrKind::ReturnRun { .. } => "retr".into(),
+				other => format!("{other:?}"),
+			};
+			let _ = writeln!(self.out, "\t\t{node} [label=\"{}\"];", escape(&label));
+
+			let defs = match &instr.kind {
+				MIRInstrKind::Declare { left, .. } => vec![left],
+				MIRInstrKind::Call { call } => call
+					.ret
+					.iter()
+					.filter_map(|x| match x {
+						MutableValue::Reg(reg) => Some(reg),
+						_ => None,
+					})
+					.collect(),
+				other => match other.get_op_lhs() {
+					Some(MutableValue::Reg(reg)) => vec![reg],
+					_ => Vec::new(),
+				},
+			};
+			// Instructions with bodies only use the registers in their condition here,
+			// since the instructions in the body get their own nodes
+			let uses = match &instr.kind {
+				MIRInstrKind::If { condition, .. } | MIRInstrKind::IfElse { condition, .. } => {
+					condition.get_used_regs()
+				}
+				MIRInstrKind::Modify { .. } | MIRInstrKind::ReturnRun { .. } => Vec::new(),
+				// The left side of an assignment is only defined, not used
+				MIRInstrKind::Assign { right, .. } => right.get_used_regs(),
+				MIRInstrKind::Call { call } => {
+					call.args.iter().flat_map(|x| x.get_used_regs()).collect()
+				}
+				other => other.get_used_regs(),
+			};
+			for reg in uses.into_iter().unique() {
+				if let Some(def_node) = self.defs.get(reg) {
+					let _ = writeln!(
+						self.out,
+						"\t\t{def_node} -> {node} [label=\"{}\"];",
+						escape(reg)
+					);
+				}
+			}
+			for def in defs {
+				self.defs.insert(def.clone(), node.clone());
+			}
+
+			match &instr.kind {
+				MIRInstrKind::Call { call } => {
+					self.calls.push((node.clone(), call.function.clone()));
+				}
+				MIRInstrKind::CallExtern { func } | MIRInstrKind::CallWith { func, .. } => {
+					self.calls.push((node.clone(), func.clone()))
+				}
+				MIRInstrKind::If { body, .. }
+				| MIRInstrKind::Modify { body, .. }
+				| MIRInstrKind::ReturnRun { body } => self.graph_block(body),
+				MIRInstrKind::IfElse { first, second, .. } => {
+					self.graph_block(first);
+					self.graph_block(second);
+				}
+				_ => {}
+			}
+		}
+	}
+}
+
+fn escape(string: &str) -> String {
+	string.replace('\\', "\\\\").replace('"', "\\\"")
+}
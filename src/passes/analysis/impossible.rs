@@ -0,0 +1,273 @@
+use rustc_hash::FxHashMap;
+
+use crate::common::condition::Condition;
+use crate::common::diag::Diagnostic;
+use crate::common::lint::{report, Lint, LintLevel};
+use crate::common::mc::modifier::MIRModifier;
+use crate::common::ty::DataTypeContents;
+use crate::common::val::{MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier, ResourceLocation};
+use crate::ir::{Block, InstrKind, IR};
+use crate::passes::{IRPass, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Warns about conditions and selectors that can never be true, and removes
+/// the code that they guard at higher optimization levels
+pub struct ImpossibleConditionsPass {
+	remove: bool,
+	level: LintLevel,
+}
+
+impl ImpossibleConditionsPass {
+	pub fn new(proj: &ProjectSettings) -> Self {
+		Self {
+			remove: proj.op_level >= OptimizationLevel::More,
+			level: proj.get_lint_level(Lint::ImpossibleCondition),
+		}
+	}
+}
+
+impl Pass for ImpossibleConditionsPass {
+	fn get_name(&self) -> &'static str {
+		"impossible_conditions"
+	}
+}
+
+impl IRPass for ImpossibleConditionsPass {
+	fn run_pass(&mut self, ir: &mut IR) -> anyhow::Result<()> {
+		for (func_id, func) in &mut ir.functions {
+			let allowed = &func.interface.annotations.allow;
+			let consts = &mut FxHashMap::default();
+			self.check_block(
+				&mut func.block,
+				func_id,
+				allowed,
+				consts,
+				&mut ir.diagnostics,
+			)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl ImpossibleConditionsPass {
+	/// Checks a block, keeping track of registers with known constant score values
+	fn check_block(
+		&self,
+		block: &mut Block,
+		func_id: &ResourceLocation,
+		func_allowed: &[Lint],
+		consts: &mut FxHashMap<Identifier, i32>,
+		diagnostics: &mut Vec<Diagnostic>,
+	) -> anyhow::Result<()> {
+		let mut to_remove = Vec::new();
+		for (i, instr) in block.contents.iter_mut().enumerate() {
+			let allowed = instr.allowed_lints.iter().chain(func_allowed);
+			let span = instr.span;
+			let impossible = match &mut instr.kind {
+				InstrKind::Declare {
+					left,
+					right: DeclareBinding::Value(Value::Constant(DataTypeContents::Score(val))),
+					..
+				}
+				| InstrKind::Assign {
+					left: MutableValue::Reg(left),
+					right: Value::Constant(DataTypeContents::Score(val)),
+				} => {
+					consts.insert(left.clone(), val.get_i32());
+					false
+				}
+				InstrKind::Comment { .. } | InstrKind::MC(..) | InstrKind::Use { .. } => false,
+				InstrKind::If { condition, body } => {
+					let impossible = eval_condition(condition, consts) == Some(false);
+					if impossible {
+						report(
+							diagnostics,
+							Lint::ImpossibleCondition,
+							self.level,
+							allowed,
+							format!("Condition {condition:?} in function {func_id} can never be true"),
+							span,
+						)?;
+					} else {
+						self.check_block(
+							body,
+							func_id,
+							func_allowed,
+							&mut consts.clone(),
+							diagnostics,
+						)?;
+					}
+					consts.clear();
+					impossible
+				}
+				InstrKind::IfElse {
+					condition,
+					first,
+					second,
+				} => {
+					// We don't remove the whole instruction here since the else branch still runs
+					if eval_condition(condition, consts) == Some(false) {
+						report(
+							diagnostics,
+							Lint::ImpossibleCondition,
+							self.level,
+							allowed,
+							format!("Condition {condition:?} in function {func_id} can never be true"),
+							span,
+						)?;
+					}
+					self.check_block(
+						first,
+						func_id,
+						func_allowed,
+						&mut consts.clone(),
+						diagnostics,
+					)?;
+					self.check_block(
+						second,
+						func_id,
+						func_allowed,
+						&mut consts.clone(),
+						diagnostics,
+					)?;
+					consts.clear();
+					false
+				}
+				InstrKind::Modify { modifier, body } => {
+					let impossible = match modifier {
+						MIRModifier::As(tgt) | MIRModifier::At(tgt) => tgt.is_impossible(),
+						_ => false,
+					};
+					if impossible {
+						report(
+							diagnostics,
+							Lint::ImpossibleCondition,
+							self.level,
+							allowed,
+							format!("Modifier {modifier:?} in function {func_id} will never run its body"),
+							span,
+						)?;
+					} else {
+						// Registers changed by one run of the body are different in the next
+						let mut body_consts = if can_repeat(modifier) {
+							FxHashMap::default()
+						} else {
+							consts.clone()
+						};
+						self.check_block(
+							body,
+							func_id,
+							func_allowed,
+							&mut body_consts,
+							diagnostics,
+						)?;
+					}
+					consts.clear();
+					impossible
+				}
+				InstrKind::Loop { body, .. } => {
+					// The body runs many times, so values from before the loop aren't known in it
+					self.check_block(
+						body,
+						func_id,
+						func_allowed,
+						&mut FxHashMap::default(),
+						diagnostics,
+					)?;
+					consts.clear();
+					false
+				}
+				InstrKind::ReturnRun { body }
+				| InstrKind::Atomic { body }
+				| InstrKind::SummonAs { body, .. } => {
+					self.check_block(
+						body,
+						func_id,
+						func_allowed,
+						&mut consts.clone(),
+						diagnostics,
+					)?;
+					consts.clear();
+					false
+				}
+				_ => {
+					// We don't know what this instruction modifies
+					consts.clear();
+					false
+				}
+			};
+			if impossible && self.remove {
+				to_remove.push(i);
+			}
+		}
+
+		for i in to_remove.into_iter().rev() {
+			block.contents.remove(i);
+		}
+
+		Ok(())
+	}
+}
+
+/// Tries to evaluate a condition at compile time
+fn eval_condition(condition: &Condition, consts: &FxHashMap<Identifier, i32>) -> Option<bool> {
+	let get = |val: &Value| match val {
+		Value::Constant(DataTypeContents::Score(val)) => Some(val.get_i32()),
+		Value::Mutable(MutableValue::Reg(reg)) => consts.get(reg).copied(),
+		_ => None,
+	};
+
+	match condition {
+		Condition::Equal(l, r) => Some(get(l)? == get(r)?),
+		Condition::GreaterThan(l, r) => Some(get(l)? > get(r)?),
+		Condition::GreaterThanOrEqual(l, r) => Some(get(l)? >= get(r)?),
+		Condition::LessThan(l, r) => Some(get(l)? < get(r)?),
+		Condition::LessThanOrEqual(l, r) => Some(get(l)? <= get(r)?),
+		Condition::Bool(val) => Some(get(val)? != 0),
+		Condition::NotBool(val) => Some(get(val)? == 0),
+		Condition::Entity(tgt) if tgt.is_impossible() => Some(false),
+		Condition::Not(condition) => eval_condition(condition, consts).map(|x| !x),
+		// The other side of the condition still has to run if it has side effects
+		Condition::And(l, r) | Condition::Or(l, r)
+			if has_side_effects(l) || has_side_effects(r) =>
+		{
+			None
+		}
+		Condition::And(l, r) => match (eval_condition(l, consts), eval_condition(r, consts)) {
+			(Some(false), _) | (_, Some(false)) => Some(false),
+			(Some(true), Some(true)) => Some(true),
+			_ => None,
+		},
+		Condition::Or(l, r) => match (eval_condition(l, consts), eval_condition(r, consts)) {
+			(Some(true), _) | (_, Some(true)) => Some(true),
+			(Some(false), Some(false)) => Some(false),
+			_ => None,
+		},
+		Condition::Xor(l, r) => Some(eval_condition(l, consts)? != eval_condition(r, consts)?),
+		_ => None,
+	}
+}
+
+/// Checks if a modifier can run its body more than once
+fn can_repeat(modifier: &MIRModifier) -> bool {
+	matches!(
+		modifier,
+		MIRModifier::As(..)
+			| MIRModifier::At(..)
+			| MIRModifier::PositionedAs(..)
+			| MIRModifier::RotatedAs(..)
+			| MIRModifier::FacingEntity(..)
+	)
+}
+
+fn has_side_effects(condition: &Condition) -> bool {
+	match condition {
+		Condition::Not(condition) => has_side_effects(condition),
+		Condition::And(l, r) | Condition::Or(l, r) | Condition::Xor(l, r) => {
+			has_side_effects(l) || has_side_effects(r)
+		}
+		other => other.has_side_effects(),
+	}
+}
@@ -1,6 +1,6 @@
 use crate::common::ResourceLocation;
 use crate::mir::{MIRInstrKind, MIR};
-use crate::passes::opt::get_instr_calls;
+use crate::passes::opt::{get_instr_calls, instr_has_macro_line};
 use crate::passes::{MIRPass, MIRPassData, Pass};
 
 use anyhow::anyhow;
@@ -30,11 +30,15 @@ impl MIRPass for InlineCandidatesPass {
 				&mut call_stack,
 				&mut checked,
 			)?;
+			// Functions with macro lines can't be inlined since the caller
+			// doesn't have their macro arguments
 			if func.block.contents.iter().any(|x| {
 				matches!(
 					x.kind,
-					MIRInstrKind::Return { .. } | MIRInstrKind::ReturnRun { .. }
-				)
+					MIRInstrKind::Return { .. }
+						| MIRInstrKind::ReturnRun { .. }
+						| MIRInstrKind::ReturnFail
+				) || instr_has_macro_line(&x.kind)
 			}) {
 				data.inline_candidates.remove(func_id);
 			}
@@ -1,12 +1,18 @@
 use anyhow::{bail, Context};
 
+use crate::common::diag::WithSpan;
+use crate::common::function::{CallInterface, FunctionSignature, ReturnType};
+use crate::common::mc::instr::MinecraftInstr;
 use crate::common::mc::modifier::{MIRModifier, StoreModLocation};
 use crate::common::reg::Local;
-use crate::common::ty::{get_op_tys, DataType};
-use crate::common::{Register, RegisterList};
+use crate::common::val::{MutableValue, Value};
+use crate::common::ty::{get_op_tys, DataType, NBTArrayType, NBTType, ScoreType};
+use crate::common::{DeclareBinding, Register, RegisterList};
 use crate::ir::{Block, IRFunction, InstrKind, IR};
 use crate::passes::{IRPass, Pass};
 
+use super::enums::EnumChain;
+
 pub struct ValidatePass;
 
 impl Pass for ValidatePass {
@@ -18,17 +24,22 @@ impl Pass for ValidatePass {
 impl IRPass for ValidatePass {
 	fn run_pass(&mut self, ir: &mut IR) -> anyhow::Result<()> {
 		for func in ir.functions.values() {
-			validate_block(&func.block, &mut RegisterList::default(), func)?;
+			validate_block(&func.block, &mut RegisterList::default(), func, ir)?;
 		}
 
 		Ok(())
 	}
 }
 
-fn validate_block(block: &Block, regs: &mut RegisterList, func: &IRFunction) -> anyhow::Result<()> {
+fn validate_block(
+	block: &Block,
+	regs: &mut RegisterList,
+	func: &IRFunction,
+	ir: &IR,
+) -> anyhow::Result<()> {
 	let mut regs = regs.clone();
 	for (i, instr) in block.contents.iter().enumerate() {
-		validate_instr_kind(&instr.kind, &mut regs, func, &i)?;
+		validate_instr_kind(&instr.kind, &mut regs, func, ir, &i).with_span(instr.span)?;
 	}
 
 	Ok(())
@@ -38,16 +49,45 @@ fn validate_instr_kind(
 	instr: &InstrKind,
 	regs: &mut RegisterList,
 	func: &IRFunction,
+	ir: &IR,
 	i: &usize,
 ) -> anyhow::Result<()> {
+	// Enums can only be given their variants, so that they always stay in range
+	for val in get_modified_scores(instr) {
+		if let Ok(DataType::Score(ScoreType::Enum(..))) = val.get_ty(regs, &func.interface.sig) {
+			bail!("Enum at {i} can only be assigned one of its variants");
+		}
+	}
+
+	if let Some(chain) = EnumChain::new(instr, regs, &func.interface.sig) {
+		// Single conditions are fine, it is only chains that have to handle everything
+		if matches!(instr, InstrKind::IfElse { .. }) {
+			let unhandled = chain.get_unhandled();
+			if !unhandled.is_empty() {
+				bail!("Conditions over enum at {i} do not handle variants {unhandled:?}");
+			}
+		}
+		for body in chain.get_leaves() {
+			validate_block(body, regs, func, ir)?;
+		}
+		return Ok(());
+	}
+
 	match instr {
 		InstrKind::Declare { left, ty, right } => {
 			if regs.contains_key(left) {
 				bail!("Redefinition of register {left} at {i}");
 			}
+			let is_variant = match right {
+				DeclareBinding::Value(right) => is_enum_variant(ty, right, i)?,
+				DeclareBinding::Cast(..) if matches!(ty, DataType::Score(ScoreType::Enum(..))) => {
+					bail!("Values cannot be cast to an enum at {i}")
+				}
+				_ => false,
+			};
 			let right_ty = right.get_ty(regs, &func.interface.sig)?;
 			if let Some(right_ty) = right_ty {
-				if !right_ty.is_trivially_castable(ty) {
+				if !is_variant && !right_ty.is_trivially_castable(ty) {
 					bail!("Register type does not match value type at {i}");
 				}
 			}
@@ -57,8 +97,23 @@ fn validate_instr_kind(
 			};
 			regs.insert(left.clone(), reg);
 		}
-		InstrKind::Assign { left, right }
-		| InstrKind::Add { left, right }
+		InstrKind::DeclareCriteria { left, .. } => {
+			if regs.contains_key(left) {
+				bail!("Redefinition of register {left} at {i}");
+			}
+			let reg = Register {
+				id: left.clone(),
+				ty: DataType::Score(ScoreType::Score),
+			};
+			regs.insert(left.clone(), reg);
+		}
+		InstrKind::Assign { left, right } => {
+			let (left_ty, right_ty) = get_op_tys(left, right, regs, &func.interface.sig)?;
+			if !is_enum_variant(&left_ty, right, i)? && !right_ty.is_trivially_castable(&left_ty) {
+				bail!("Incompatible types in instruction at {i}");
+			}
+		}
+		InstrKind::Add { left, right }
 		| InstrKind::Sub { left, right }
 		| InstrKind::Mul { left, right }
 		| InstrKind::Div { left, right }
@@ -70,6 +125,53 @@ fn validate_instr_kind(
 				bail!("Incompatible types in instruction at {i}");
 			}
 		}
+		InstrKind::BitAnd { left, right }
+		| InstrKind::BitOr { left, right }
+		| InstrKind::BitXor { left, right }
+		| InstrKind::Shl { left, right }
+		| InstrKind::Shr { left, right } => {
+			let tys = get_op_tys(left, right, regs, &func.interface.sig)?;
+			if !matches!(tys, (DataType::Score(..), DataType::Score(..))) {
+				bail!("Bitwise operations at {i} can only be done on scores");
+			}
+		}
+		InstrKind::UUIDToString { left, right } => {
+			let tys = get_op_tys(left, right, regs, &func.interface.sig)?;
+			if !matches!(
+				tys,
+				(
+					DataType::NBT(NBTType::String),
+					DataType::NBT(NBTType::Arr(NBTArrayType::Int(..)))
+				)
+			) {
+				bail!("UUID at {i} must be an int array converted to a string");
+			}
+		}
+		InstrKind::StringToUUID { left, right } => {
+			let tys = get_op_tys(left, right, regs, &func.interface.sig)?;
+			if !matches!(
+				tys,
+				(
+					DataType::NBT(NBTType::Arr(NBTArrayType::Int(..))),
+					DataType::NBT(NBTType::String)
+				)
+			) {
+				bail!("UUID at {i} must be a string converted to an int array");
+			}
+		}
+		InstrKind::GetFlag { left, flags, bit } => {
+			validate_flags(flags, *bit, regs, func, i)?;
+			if !matches!(left.get_ty(regs, &func.interface.sig)?, DataType::Score(..)) {
+				bail!("Flag at {i} can only be stored in a score");
+			}
+		}
+		InstrKind::SetFlag { flags, bit, right } => {
+			validate_flags(flags, *bit, regs, func, i)?;
+			if !matches!(right.get_ty(regs, &func.interface.sig)?, DataType::Score(..)) {
+				bail!("Flag at {i} can only be set to a score");
+			}
+		}
+		InstrKind::ToggleFlag { flags, bit } => validate_flags(flags, *bit, regs, func, i)?,
 		InstrKind::Push { left, right }
 		| InstrKind::PushFront { left, right }
 		| InstrKind::Insert { left, right, .. } => {
@@ -82,10 +184,9 @@ fn validate_instr_kind(
 			}
 		}
 		InstrKind::Swap { left, right } => {
-			if !right
-				.get_ty(regs, &func.interface.sig)?
-				.is_trivially_castable(&left.get_ty(regs, &func.interface.sig)?)
-			{
+			let left = left.get_ty(regs, &func.interface.sig)?;
+			let right = right.get_ty(regs, &func.interface.sig)?;
+			if !right.is_trivially_castable(&left) || !left.is_trivially_castable(&right) {
 				bail!("Incompatible types in instruction at {i}");
 			}
 		}
@@ -101,26 +202,187 @@ fn validate_instr_kind(
 			modifier: MIRModifier::StoreResult(location),
 			body,
 		} => {
-			validate_block(body, regs, func)?;
-			if let StoreModLocation::Local(Local::Reg(reg), scale) = location {
-				if let DataType::Score(..) = regs.get(reg).context("Register does not exist")?.ty {
-					if *scale != 1.0 {
-						bail!("Scale that is not 1.0 cannot be used for storing to a value of score type");
+			validate_block(body, regs, func, ir)?;
+			if let StoreModLocation::Local(Local::Reg(reg), _, scale) = location {
+				match regs.get(reg).context("Register does not exist")?.ty {
+					DataType::Score(ScoreType::Enum(..)) => {
+						bail!("Results cannot be stored in an enum at {i}");
 					}
+					DataType::Score(..) => {
+						if *scale != 1.0 {
+							bail!("Scale that is not 1.0 cannot be used for storing to a value of score type");
+						}
+					}
+					_ => {}
 				}
 			}
 		}
+		InstrKind::SummonAs { uuid, body, .. } => {
+			if let Some(uuid) = uuid {
+				if let DataType::Score(..) = uuid.get_ty(regs, &func.interface.sig)? {
+					bail!("UUID cannot be stored in a value of score type at {i}");
+				}
+			}
+			validate_block(body, regs, func, ir)?;
+		}
 		InstrKind::If { body, .. }
 		| InstrKind::Modify { body, .. }
-		| InstrKind::ReturnRun { body, .. } => {
-			validate_block(body, regs, func)?;
+		| InstrKind::ReturnRun { body, .. }
+		| InstrKind::Atomic { body }
+		| InstrKind::Loop { body, .. } => {
+			validate_block(body, regs, func, ir)?;
+		}
+		InstrKind::Call { call } => {
+			// Calls to functions that aren't defined can't be checked
+			if let Some(callee) = ir.functions.get(&call.function) {
+				validate_call(call, &callee.interface.sig, regs, func, i)?;
+			}
 		}
 		InstrKind::IfElse { first, second, .. } => {
-			validate_block(first, regs, func)?;
-			validate_block(second, regs, func)?;
+			validate_block(first, regs, func, ir)?;
+			validate_block(second, regs, func, ir)?;
+		}
+		InstrKind::MC(MinecraftInstr::ScheduleFunction { function, time, .. }) => {
+			let Some(callee) = ir.functions.get(function) else {
+				bail!("Scheduled function {function} at {i} does not exist");
+			};
+			if !callee.interface.sig.params.is_empty() {
+				bail!("Scheduled function {function} at {i} cannot take arguments");
+			}
+			if time.round_to_ticks().get_whole_ticks()? == 0 {
+				bail!("Function {function} at {i} cannot be scheduled for the current tick");
+			}
+		}
+		InstrKind::ReturnValue { index, value } => {
+			let ReturnType::Standard(ret_tys) = &func.interface.sig.ret else {
+				bail!("Function returns void but sets return value {index} at {i}");
+			};
+			let Some(ret_ty) = ret_tys.get(*index) else {
+				bail!(
+					"Function returns {} values but sets return value {index} at {i}",
+					ret_tys.len()
+				);
+			};
+			if !value
+				.get_ty(regs, &func.interface.sig)?
+				.is_trivially_castable(ret_ty)
+			{
+				bail!("Incompatible type for return value {index} at {i}");
+			}
+		}
+		InstrKind::CallWith { args, .. } => {
+			let ty = args.get_ty(regs, &func.interface.sig)?;
+			if !matches!(ty, DataType::NBT(NBTType::Compound(..) | NBTType::Any)) {
+				bail!("Macro arguments at {i} must be a compound");
+			}
 		}
 		_ => {}
 	}
 
 	Ok(())
 }
+
+fn validate_call(
+	call: &CallInterface,
+	callee_sig: &FunctionSignature,
+	regs: &RegisterList,
+	func: &IRFunction,
+	i: &usize,
+) -> anyhow::Result<()> {
+	let name = &call.function;
+	if call.args.len() != callee_sig.params.len() {
+		bail!(
+			"Function {name} takes {} arguments but {} were given at {i}",
+			callee_sig.params.len(),
+			call.args.len()
+		);
+	}
+	for (arg, param) in call.args.iter().zip(&callee_sig.params) {
+		if !arg.get_ty(regs, &func.interface.sig)?.is_trivially_castable(param) {
+			bail!("Incompatible argument type for call to {name} at {i}");
+		}
+	}
+
+	// Not using the return values at all is allowed
+	if call.ret.is_empty() {
+		return Ok(());
+	}
+	let ret_tys = match &callee_sig.ret {
+		ReturnType::Void => &[][..],
+		ReturnType::Standard(tys) => tys,
+	};
+	if call.ret.len() != ret_tys.len() {
+		bail!(
+			"Function {name} returns {} values but {} were destructured at {i}",
+			ret_tys.len(),
+			call.ret.len()
+		);
+	}
+	for (ret, ty) in call.ret.iter().zip(ret_tys) {
+		if !ty.is_trivially_castable(&ret.get_ty(regs, &func.interface.sig)?) {
+			bail!("Incompatible return value type for call to {name} at {i}");
+		}
+	}
+
+	Ok(())
+}
+
+fn validate_flags(
+	flags: &MutableValue,
+	bit: u8,
+	regs: &RegisterList,
+	func: &IRFunction,
+	i: &usize,
+) -> anyhow::Result<()> {
+	match flags.get_ty(regs, &func.interface.sig)? {
+		DataType::Score(ScoreType::Flags(bits)) if bit >= bits => {
+			bail!("Bit {bit} at {i} is out of range for flags with {bits} bits")
+		}
+		DataType::Score(ScoreType::Enum(..)) => bail!("Flags at {i} cannot be an enum"),
+		DataType::Score(..) => Ok(()),
+		_ => bail!("Flags at {i} must be a score"),
+	}
+}
+
+/// Checks if a value is a constant variant of an enum type. Constants that are out of
+/// the range of the enum are an error
+fn is_enum_variant(ty: &DataType, val: &Value, i: &usize) -> anyhow::Result<bool> {
+	let (DataType::Score(ScoreType::Enum(count)), Value::Constant(val)) = (ty, val) else {
+		return Ok(false);
+	};
+	let Some(val) = val.try_get_i32() else {
+		return Ok(false);
+	};
+	if val < 0 || val as i64 >= *count as i64 {
+		bail!("Value {val} at {i} is not a variant of an enum with {count} variants");
+	}
+
+	Ok(true)
+}
+
+/// Gets the values that an instruction changes the score of with arithmetic
+fn get_modified_scores(instr: &InstrKind) -> Vec<&MutableValue> {
+	match instr {
+		InstrKind::Add { left, .. }
+		| InstrKind::Sub { left, .. }
+		| InstrKind::Mul { left, .. }
+		| InstrKind::Div { left, .. }
+		| InstrKind::Mod { left, .. }
+		| InstrKind::Min { left, .. }
+		| InstrKind::Max { left, .. }
+		| InstrKind::BitAnd { left, .. }
+		| InstrKind::BitOr { left, .. }
+		| InstrKind::BitXor { left, .. }
+		| InstrKind::Shl { left, .. }
+		| InstrKind::Shr { left, .. }
+		| InstrKind::GetFlag { left, .. }
+		| InstrKind::Rand { left, .. }
+		| InstrKind::And { left, .. }
+		| InstrKind::Or { left, .. }
+		| InstrKind::Xor { left, .. } => vec![left],
+		InstrKind::Abs { val } => vec![val],
+		InstrKind::Pow { base, .. } => vec![base],
+		InstrKind::Not { value } => vec![value],
+		_ => Vec::new(),
+	}
+}
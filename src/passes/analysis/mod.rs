@@ -1,3 +1,10 @@
+pub mod atomic;
+pub mod broad_selectors;
+pub mod budget;
+pub mod enums;
+pub mod graph;
+pub mod impossible;
 pub mod inline_candidates;
 pub mod ir;
+pub mod ranges;
 pub mod util;
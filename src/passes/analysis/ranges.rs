@@ -0,0 +1,199 @@
+use anyhow::bail;
+
+use crate::common::diag::{Diagnostic, WithSpan};
+use crate::common::lint::{report, Lint, LintLevel};
+use crate::common::mc::instr::{InstrOperand, MinecraftInstr};
+use crate::common::mc::time::Time;
+use crate::common::ResourceLocation;
+use crate::ir::{Block, InstrKind, IR};
+use crate::passes::{IRPass, Pass};
+use crate::project::ProjectSettings;
+
+/// The furthest that the world border can be from the center of the world
+const MAX_WORLD_BORDER: f64 = 59_999_968.0;
+/// The most numbers that a random range can contain
+const MAX_RANDOM_SPAN: i64 = i32::MAX as i64;
+
+/// Checks that numeric arguments are in the ranges that the game accepts, so that
+/// they error at compile time instead of producing commands that fail to load.
+/// Also warns about times that have to be rounded to a whole number of ticks
+pub struct ValueRangesPass {
+	lossy_time: LintLevel,
+}
+
+impl ValueRangesPass {
+	pub fn new(proj: &ProjectSettings) -> Self {
+		Self {
+			lossy_time: proj.get_lint_level(Lint::LossyTime),
+		}
+	}
+}
+
+impl Pass for ValueRangesPass {
+	fn get_name(&self) -> &'static str {
+		"value_ranges"
+	}
+}
+
+impl IRPass for ValueRangesPass {
+	fn run_pass(&mut self, ir: &mut IR) -> anyhow::Result<()> {
+		for (func_id, func) in &ir.functions {
+			let allowed = &func.interface.annotations.allow;
+			self.check_block(&func.block, func_id, allowed, &mut ir.diagnostics)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl ValueRangesPass {
+	fn check_block(
+		&self,
+		block: &Block,
+		func_id: &ResourceLocation,
+		func_allowed: &[Lint],
+		diagnostics: &mut Vec<Diagnostic>,
+	) -> anyhow::Result<()> {
+		for instr in &block.contents {
+			if let InstrKind::Rand { min, max, .. } = &instr.kind {
+				check_random_range(*min as i64, *max as i64).with_span(instr.span)?;
+			}
+			if let InstrKind::MC(mc_instr) = &instr.kind {
+				check_ranges(mc_instr).with_span(instr.span)?;
+				for time in get_times(mc_instr) {
+					if !time.is_whole_ticks() {
+						report(
+							diagnostics,
+							Lint::LossyTime,
+							self.lossy_time,
+							instr.allowed_lints.iter().chain(func_allowed),
+							format!(
+								"Time {time:?} in function {func_id} is {} ticks, which will be rounded to {:?}",
+								time.to_ticks(),
+								time.round_to_ticks()
+							),
+							instr.span,
+						)?;
+					}
+				}
+			}
+			for body in instr.kind.get_bodies() {
+				self.check_block(body, func_id, func_allowed, diagnostics)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Checks the numeric arguments of an instruction
+fn check_ranges(instr: &MinecraftInstr) -> anyhow::Result<()> {
+	match instr {
+		MinecraftInstr::WorldBorderSet { dist, time } => {
+			if !(1.0..=MAX_WORLD_BORDER).contains(dist) {
+				bail!("World border distance {dist} must be between 1 and {MAX_WORLD_BORDER}");
+			}
+			check_non_negative("World border time", *time as f64)?;
+		}
+		MinecraftInstr::WorldBorderAdd { dist, time } => {
+			if !(-MAX_WORLD_BORDER..=MAX_WORLD_BORDER).contains(dist) {
+				bail!("World border distance {dist} must be between -{MAX_WORLD_BORDER} and {MAX_WORLD_BORDER}");
+			}
+			check_non_negative("World border time", *time as f64)?;
+		}
+		MinecraftInstr::WorldBorderDamage { damage } => {
+			check_non_negative("World border damage", *damage)?
+		}
+		MinecraftInstr::WorldBorderBuffer { buffer } => {
+			check_non_negative("World border buffer", *buffer)?
+		}
+		MinecraftInstr::WorldBorderWarningDistance { dist } => {
+			check_non_negative("World border warning distance", *dist)?;
+			if dist.fract() != 0.0 {
+				bail!("World border warning distance {dist} must be a whole number");
+			}
+		}
+		MinecraftInstr::WorldBorderWarningTime { time } => {
+			check_non_negative("World border warning time", *time as f64)?
+		}
+		MinecraftInstr::AddTime { time } | MinecraftInstr::SetTime { time } => {
+			check_non_negative("Time", time.to_ticks() as f64)?;
+		}
+		MinecraftInstr::SetWeather {
+			duration: Some(duration),
+			..
+		} if duration.round_to_ticks().to_ticks() < 1.0 => {
+			bail!("Weather duration {duration:?} must be at least one tick");
+		}
+		MinecraftInstr::GiveEffect { duration, .. } => duration.validate()?,
+		MinecraftInstr::SetXP { amount, .. } => {
+			check_non_negative("XP amount", *amount as f64)?;
+		}
+		MinecraftInstr::RandomValue { range, .. } | MinecraftInstr::RandomRoll { range, .. } => {
+			let (min, max) = range.get_bounds();
+			check_random_range(min as i64, max as i64)?;
+		}
+		_ => {}
+	}
+
+	Ok(())
+}
+
+fn check_non_negative(name: &str, value: f64) -> anyhow::Result<()> {
+	if value < 0.0 {
+		bail!("{name} {value} cannot be negative");
+	}
+
+	Ok(())
+}
+
+/// Checks that a range of random numbers is one that the game can generate
+pub fn check_random_range(min: i64, max: i64) -> anyhow::Result<()> {
+	let span = max - min + 1;
+	if span < 2 {
+		bail!("Random range {min}..{max} must contain at least two numbers");
+	}
+	if span > MAX_RANDOM_SPAN {
+		bail!("Random range {min}..{max} cannot contain more than {MAX_RANDOM_SPAN} numbers");
+	}
+
+	Ok(())
+}
+
+/// Gets the times that an instruction converts to ticks
+fn get_times(instr: &MinecraftInstr) -> Vec<&Time> {
+	instr
+		.get_operands()
+		.into_iter()
+		.filter_map(|x| match x {
+			InstrOperand::Time(time) => Some(time),
+			_ => None,
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::common::mc::time::TimeUnit;
+
+	use super::*;
+
+	#[test]
+	fn test_value_ranges() {
+		let set = |dist| MinecraftInstr::WorldBorderSet { dist, time: 0 };
+		assert!(check_ranges(&set(10.0)).is_ok());
+		assert!(check_ranges(&set(0.5)).is_err());
+		assert!(check_ranges(&set(6.0e7)).is_err());
+		assert!(check_ranges(&MinecraftInstr::WorldBorderWarningDistance { dist: 2.5 }).is_err());
+		assert!(check_ranges(&MinecraftInstr::AddTime {
+			time: Time::new_ticks(-5.0)
+		})
+		.is_err());
+
+		let time = MinecraftInstr::SetTime {
+			time: Time::new(0.01, TimeUnit::Seconds),
+		};
+		assert_eq!(get_times(&time).len(), 1);
+		assert!(!get_times(&time)[0].is_whole_ticks());
+	}
+}
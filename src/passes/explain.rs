@@ -0,0 +1,207 @@
+use std::fmt::{Debug, Write};
+use std::hash::{Hash, Hasher};
+
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHasher};
+
+use crate::common::block::Block;
+use crate::common::{FunctionTrait, IRType, ResourceLocation};
+
+/// A record of which passes changed each function, used to explain
+/// why the output looks the way that it does
+#[derive(Debug, Clone, Default)]
+pub struct OptimizationLog {
+	functions: FxHashMap<ResourceLocation, Vec<FunctionChange>>,
+}
+
+/// A change that a single run of a pass made to a function
+#[derive(Debug, Clone)]
+pub enum FunctionChange {
+	Changed {
+		pass: &'static str,
+		before: usize,
+		after: usize,
+	},
+	Created {
+		pass: &'static str,
+	},
+	Removed {
+		pass: &'static str,
+	},
+}
+
+/// The state of every function before a pass runs
+pub struct Snapshot {
+	functions: FxHashMap<ResourceLocation, (usize, u64)>,
+}
+
+impl Snapshot {
+	pub fn new<T: IRType>(ir: &T) -> Self
+	where
+		<T::FunctionType as FunctionTrait>::BlockType: Debug,
+	{
+		let functions = ir
+			.iter_fns()
+			.map(|(id, func)| {
+				let block = func.block();
+				let mut hasher = FxHasher::default();
+				format!("{block:?}").hash(&mut hasher);
+				(id.clone(), (block.instr_count(), hasher.finish()))
+			})
+			.collect();
+
+		Self { functions }
+	}
+}
+
+impl OptimizationLog {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Records the differences between the functions before and after a pass
+	pub fn record(&mut self, pass: &'static str, before: &Snapshot, after: &Snapshot) {
+		for (id, (count, hash)) in &before.functions {
+			let change = match after.functions.get(id) {
+				Some((_, new_hash)) if new_hash == hash => continue,
+				Some((new_count, _)) => FunctionChange::Changed {
+					pass,
+					before: *count,
+					after: *new_count,
+				},
+				None => FunctionChange::Removed { pass },
+			};
+			self.functions.entry(id.clone()).or_default().push(change);
+		}
+		for id in after.functions.keys() {
+			if !before.functions.contains_key(id) {
+				let change = FunctionChange::Created { pass };
+				self.functions.entry(id.clone()).or_default().push(change);
+			}
+		}
+	}
+
+	/// Gets the changes made to a function, in the order that they happened
+	pub fn get_changes(&self, func: &ResourceLocation) -> &[FunctionChange] {
+		self.functions
+			.get(func)
+			.map(Vec::as_slice)
+			.unwrap_or_default()
+	}
+
+	/// Creates a report with a line for each function summarizing what was done to it
+	pub fn report(&self) -> String {
+		if self.functions.is_empty() {
+			return "No optimizations changed any functions\n".into();
+		}
+
+		let mut out = String::new();
+		for (id, changes) in self.functions.iter().sorted_by_key(|x| x.0) {
+			// Runs of the same pass are combined into one part of the summary
+			let mut parts: Vec<(&'static str, isize)> = Vec::new();
+			let mut status = None;
+			for change in changes {
+				let (pass, delta) = match change {
+					FunctionChange::Changed {
+						pass,
+						before,
+						after,
+					} => (*pass, *after as isize - *before as isize),
+					FunctionChange::Created { pass } => {
+						status = Some(format!("created by {pass}"));
+						continue;
+					}
+					FunctionChange::Removed { pass } => {
+						status = Some(format!("removed by {pass}"));
+						continue;
+					}
+				};
+				if let Some(part) = parts.iter_mut().find(|x| x.0 == pass) {
+					part.1 += delta;
+				} else {
+					parts.push((pass, delta));
+				}
+			}
+
+			let mut parts: Vec<_> = parts
+				.into_iter()
+				.map(|(pass, delta)| format!("{} ({delta:+} instrs)", describe_pass(pass)))
+				.collect();
+			parts.extend(status);
+			let _ = writeln!(out, "{id}: {}", parts.join(", "));
+		}
+
+		out
+	}
+}
+
+/// Gets a human-readable description of what a pass does to a function
+fn describe_pass(pass: &'static str) -> &'static str {
+	match pass {
+		"simple_inline" => "inlined calls",
+		"const_fold" => "folded constants",
+		"const_prop" => "propagated constants",
+		"const_combo" => "combined constant operations",
+		"const_args" => "stored constant arguments",
+		"shared_constants" => "shared large constants",
+		"dead_code_elimination" => "removed dead code",
+		"dead_store_elimination" | "lir_dse" => "removed dead stores",
+		"merge_modifiers" => "merged modifiers",
+		"null_modifiers" => "removed modifiers that do nothing",
+		"simplify_modifiers" => "simplified modifiers",
+		"direct_targets" => "targeted entities directly",
+		"multifold_assign" | "multifold_combine" | "multifold_logic" => {
+			"combined repeated operations"
+		}
+		"copy_propagation" => "propagated copies",
+		"copy_elision" => "elided copies",
+		"result_dataflow" | "get_dataflow" => "forwarded values between commands",
+		"unused_args" => "removed unused arguments",
+		"cleanup" | "cleanup_return" => "cleaned up",
+		"simplify_mir" | "simplify_lir" => "simplified instructions",
+		"type_based_optimization" => "optimized using types",
+		"reorder_conditions" => "reordered conditions",
+		"control_flow" => "folded branches and merged blocks",
+		other => other,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::lower::ir_to_mir::lower_ir;
+	use crate::parse::Parser;
+	use crate::passes::run_mir_passes_with_log;
+	use crate::project::{OptimizationLevel, ProjectSettingsBuilder};
+
+	use super::*;
+
+	#[test]
+	fn test_optimization_report() {
+		let mut parser = Parser::new();
+		parser
+			.parse(
+				r#"
+				"test:helper" {
+					say "Hello";
+				}
+				@preserve
+				"test:main" {
+					call "test:helper";
+				}
+				"#,
+			)
+			.expect("Failed to parse");
+		let mut mir = lower_ir(parser.finish()).expect("Failed to lower");
+
+		let mut log = OptimizationLog::new();
+		let proj = ProjectSettingsBuilder::new("dpc")
+			.op_level(OptimizationLevel::Full)
+			.build();
+		run_mir_passes_with_log(&mut mir, &proj, false, Some(&mut log))
+			.expect("Passes failed");
+
+		let report = log.report();
+		assert!(report.contains("test:main: inlined calls"), "{report}");
+		assert!(report.contains("test:helper: removed by"), "{report}");
+	}
+}
@@ -4,30 +4,47 @@ use crate::common::ResourceLocation;
 use crate::project::ProjectSettings;
 use crate::{ir::IR, lir::LIR, mir::MIR};
 
+use self::analysis::broad_selectors::BroadSelectorsPass;
+use self::analysis::impossible::ImpossibleConditionsPass;
 use self::analysis::inline_candidates::InlineCandidatesPass;
 use self::analysis::ir::ValidatePass;
+use self::analysis::ranges::ValueRangesPass;
+use self::explain::{OptimizationLog, Snapshot};
+use self::opt::assert::AssertElisionPass;
+use self::opt::constant::dedup::SharedConstantsPass;
 use self::opt::constant::{fold::ConstFoldPass, prop::ConstPropPass, ConstComboPass};
+use self::opt::control_flow::ControlFlowPass;
+use self::opt::data_copies::MergeDataCopiesPass;
 use self::opt::dataflow::copy_elide::CopyElisionPass;
 use self::opt::dataflow::copy_prop::CopyPropPass;
 use self::opt::dataflow::get::DataflowGetPass;
 use self::opt::dataflow::result::DataflowResultPass;
 use self::opt::dce::DCEPass;
 use self::opt::dse::{DSEPass, LIRDSEPass};
+use self::opt::enums::EnumDispatchPass;
 use self::opt::func::cleanup_return::CleanupReturnPass;
+use self::opt::func::const_args::ConstArgsPass;
 use self::opt::func::inline::SimpleInlinePass;
 use self::opt::func::unused_args::UnusedArgsPass;
+use self::opt::modifiers::direct::DirectTargetsPass;
+use self::opt::modifiers::hoist::HoistModifiersPass;
 use self::opt::modifiers::merge::MergeModifiersPass;
+use self::opt::modifiers::narrow::NarrowSelectorsPass;
 use self::opt::modifiers::null::NullModifiersPass;
 use self::opt::modifiers::simplify::SimplifyModifiersPass;
+use self::opt::modifiers::variants::ContextVariantsPass;
 use self::opt::multifold::assign::MultifoldAssignPass;
 use self::opt::multifold::combine::MultifoldCombinePass;
 use self::opt::multifold::logic::MultifoldLogicPass;
 use self::opt::order::conditions::ReorderConditionsPass;
+use self::opt::reset::ResetBatchingPass;
 use self::opt::simplify::cleanup::CleanupPass;
 use self::opt::simplify::{lir::LIRSimplifyPass, mir::MIRSimplifyPass};
+use self::opt::ty::ranges::ScoreRangesPass;
 use self::opt::ty::TypeBasedOptimizationPass;
 
 pub mod analysis;
+pub mod explain;
 pub mod opt;
 pub mod util;
 
@@ -38,7 +55,13 @@ pub trait IRPass: Pass {
 pub fn run_ir_passes(ir: &mut IR, proj: &ProjectSettings, debug: bool) -> anyhow::Result<()> {
 	let passes = [
 		Box::new(NullPass) as Box<dyn IRPass>,
+		// Validation runs on the IR as written, before passes remove conditions and asserts
 		Box::new(ValidatePass),
+		Box::new(AssertElisionPass),
+		Box::new(ImpossibleConditionsPass::new(proj)),
+		Box::new(BroadSelectorsPass::new(proj)),
+		Box::new(ValueRangesPass::new(proj)),
+		Box::new(EnumDispatchPass),
 	];
 
 	for mut pass in passes {
@@ -65,6 +88,16 @@ pub struct MIRPassData<'mir, 'proj> {
 }
 
 pub fn run_mir_passes(mir: &mut MIR, proj: &ProjectSettings, debug: bool) -> anyhow::Result<()> {
+	run_mir_passes_with_log(mir, proj, debug, None)
+}
+
+/// Runs the MIR passes, recording the functions that each one changes in the log
+pub fn run_mir_passes_with_log(
+	mir: &mut MIR,
+	proj: &ProjectSettings,
+	debug: bool,
+	mut log: Option<&mut OptimizationLog>,
+) -> anyhow::Result<()> {
 	let passes = [
 		Box::new(NullPass) as Box<dyn MIRPass>,
 		Box::new(CleanupPass),
@@ -74,6 +107,7 @@ pub fn run_mir_passes(mir: &mut MIR, proj: &ProjectSettings, debug: bool) -> any
 		Box::new(SimpleInlinePass),
 		Box::new(DCEPass),
 		Box::new(TypeBasedOptimizationPass),
+		Box::new(ScoreRangesPass),
 		Box::new(UnusedArgsPass),
 		Box::new(MIRSimplifyPass),
 		Box::new(ConstComboPass),
@@ -85,6 +119,8 @@ pub fn run_mir_passes(mir: &mut MIR, proj: &ProjectSettings, debug: bool) -> any
 		Box::new(ConstPropPass::new()),
 		Box::new(MIRSimplifyPass),
 		Box::new(ConstFoldPass::new()),
+		Box::new(ScoreRangesPass),
+		Box::new(ControlFlowPass),
 		Box::new(ConstComboPass),
 		Box::new(CleanupReturnPass),
 		Box::new(InlineCandidatesPass),
@@ -97,6 +133,7 @@ pub fn run_mir_passes(mir: &mut MIR, proj: &ProjectSettings, debug: bool) -> any
 		Box::new(MIRSimplifyPass),
 		Box::new(DCEPass),
 		Box::new(ReorderConditionsPass),
+		Box::new(ConstArgsPass),
 		Box::new(UnusedArgsPass),
 	];
 
@@ -113,7 +150,11 @@ pub fn run_mir_passes(mir: &mut MIR, proj: &ProjectSettings, debug: bool) -> any
 		if debug {
 			println!("Running pass {}", pass.get_name());
 		}
+		let before = log.is_some().then(|| Snapshot::new(data.mir));
 		pass.run_pass(&mut data)?;
+		if let (Some(log), Some(before)) = (&mut log, before) {
+			log.record(pass.get_name(), &before, &Snapshot::new(data.mir));
+		}
 	}
 
 	Ok(())
@@ -129,21 +170,38 @@ pub struct LIRPassData<'lir, 'proj> {
 }
 
 pub fn run_lir_passes(lir: &mut LIR, proj: &ProjectSettings, debug: bool) -> anyhow::Result<()> {
+	run_lir_passes_with_log(lir, proj, debug, None)
+}
+
+/// Runs the LIR passes, recording the functions that each one changes in the log
+pub fn run_lir_passes_with_log(
+	lir: &mut LIR,
+	proj: &ProjectSettings,
+	debug: bool,
+	mut log: Option<&mut OptimizationLog>,
+) -> anyhow::Result<()> {
 	let passes = [
 		Box::new(NullPass) as Box<dyn LIRPass>,
 		Box::new(LIRSimplifyPass),
+		Box::new(MergeDataCopiesPass),
 		Box::new(CopyPropPass),
 		Box::new(CopyElisionPass),
 		Box::new(LIRDSEPass),
 		Box::new(DataflowResultPass),
 		Box::new(MergeModifiersPass),
+		Box::new(DirectTargetsPass),
+		Box::new(NarrowSelectorsPass),
+		Box::new(ContextVariantsPass),
 		Box::new(NullModifiersPass),
 		Box::new(SimplifyModifiersPass),
 		Box::new(MergeModifiersPass),
+		Box::new(HoistModifiersPass),
 		Box::new(DataflowGetPass),
 		Box::new(CopyPropPass),
 		Box::new(LIRDSEPass),
 		Box::new(LIRSimplifyPass),
+		Box::new(SharedConstantsPass),
+		Box::new(ResetBatchingPass),
 	];
 
 	let mut data = LIRPassData { lir, proj };
@@ -155,7 +213,11 @@ pub fn run_lir_passes(lir: &mut LIR, proj: &ProjectSettings, debug: bool) -> any
 		if debug {
 			println!("Running pass {}", pass.get_name());
 		}
+		let before = log.is_some().then(|| Snapshot::new(data.lir));
 		pass.run_pass(&mut data)?;
+		if let (Some(log), Some(before)) = (&mut log, before) {
+			log.record(pass.get_name(), &before, &Snapshot::new(data.lir));
+		}
 	}
 
 	Ok(())
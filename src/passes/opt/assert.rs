@@ -0,0 +1,89 @@
+use crate::ir::{Block, InstrKind, IR};
+use crate::passes::{IRPass, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Removes runtime assertions at release optimization levels
+pub struct AssertElisionPass;
+
+impl Pass for AssertElisionPass {
+	fn get_name(&self) -> &'static str {
+		"assert_elision"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl IRPass for AssertElisionPass {
+	fn run_pass(&mut self, ir: &mut IR) -> anyhow::Result<()> {
+		for func in ir.functions.values_mut() {
+			// Tests rely on their assertions to report failures
+			if func.interface.annotations.test {
+				continue;
+			}
+			elide_block(&mut func.block);
+		}
+
+		Ok(())
+	}
+}
+
+fn elide_block(block: &mut Block) {
+	block
+		.contents
+		.retain(|x| !matches!(x.kind, InstrKind::Assert { .. }));
+
+	for instr in &mut block.contents {
+		for body in instr.kind.get_bodies_mut() {
+			elide_block(body);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::common::ResourceLocation;
+	use crate::parse::Parser;
+
+	use super::*;
+
+	fn contains_assert(block: &Block) -> bool {
+		block.contents.iter().any(|x| {
+			matches!(x.kind, InstrKind::Assert { .. })
+				|| x.kind.get_bodies().into_iter().any(contains_assert)
+		})
+	}
+
+	#[test]
+	fn test_assert_elision() {
+		let mut parser = Parser::new();
+		parser
+			.parse(
+				r#"
+				"test:main" {
+					let x: score = val 0s;
+					let y: [nint] = val [nint][1ni, 2ni];
+					assert eq %x, 0s, "top";
+					while lt %x, 10s {
+						assert lt %x, 10s, "loop";
+						add %x, 1s;
+					};
+					spread 2, item: nint, %y {
+						assert eq %x, 10s, "spread";
+					};
+					atomic {
+						if eq %x, 10s {
+							assert eq %x, 10s, "nested";
+						};
+					};
+				}
+				"#,
+			)
+			.expect("Failed to parse");
+		let mut ir = parser.finish();
+		AssertElisionPass.run_pass(&mut ir).unwrap();
+		let func = &ir.functions[&ResourceLocation::from("test:main")];
+		assert!(!contains_assert(&func.block));
+	}
+}
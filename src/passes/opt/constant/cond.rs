@@ -168,7 +168,7 @@ fn const_eval_result(condition: &mut Condition) -> Option<bool> {
 	}
 }
 
-fn const_eval_condition(condition: &Condition) -> Option<bool> {
+pub(crate) fn const_eval_condition(condition: &Condition) -> Option<bool> {
 	match condition {
 		Condition::Equal(
 			Value::Constant(DataTypeContents::Score(l)),
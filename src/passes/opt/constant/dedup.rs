@@ -0,0 +1,106 @@
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::common::ty::NBTTypeContents;
+use crate::common::val::{MutableNBTValue, NBTValue};
+use crate::lir::LIRInstrKind;
+use crate::passes::opt::func::const_args::ConstPool;
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Stores large constant compounds and lists that are used in more than one
+/// function once when the pack loads, and copies them from storage instead of
+/// repeating the literal everywhere
+pub struct SharedConstantsPass;
+
+impl Pass for SharedConstantsPass {
+	fn get_name(&self) -> &'static str {
+		"shared_constants"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl LIRPass for SharedConstantsPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		let threshold = data.proj.const_arg_threshold;
+
+		// Find the functions that each literal is used in. Subblocks count as
+		// part of their parent function
+		let mut users: FxHashMap<String, FxHashSet<_>> = FxHashMap::default();
+		for (func_id, func) in &data.lir.functions {
+			let owner = func.parent.as_ref().unwrap_or(func_id);
+			for instr in &func.block.contents {
+				if let Some(val) = get_const_operand(&instr.kind, threshold) {
+					users
+						.entry(val.get_literal_str())
+						.or_default()
+						.insert(owner.clone());
+				}
+			}
+		}
+
+		let mut pool = ConstPool {
+			consts: &mut data.lir.storage_consts,
+		};
+		let func_ids: Vec<_> = data.lir.functions.keys().sorted().cloned().collect();
+		for func_id in func_ids {
+			let func = data
+				.lir
+				.functions
+				.get_mut(&func_id)
+				.expect("Function should exist");
+			for instr in &mut func.block.contents {
+				let Some(operand) = get_operand_mut(&mut instr.kind) else {
+					continue;
+				};
+				let NBTValue::Constant(val) = operand else {
+					continue;
+				};
+				if !is_large_const(val, threshold) || users[&val.get_literal_str()].len() < 2 {
+					continue;
+				}
+				let loc = pool.get_loc(val.clone());
+				*operand = NBTValue::Mutable(MutableNBTValue::Data(loc));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Gets the constant that an instruction writes, if it is large enough to be shared
+fn get_const_operand(kind: &LIRInstrKind, threshold: usize) -> Option<&NBTTypeContents> {
+	match kind {
+		LIRInstrKind::SetData(_, NBTValue::Constant(val))
+		| LIRInstrKind::MergeData(_, NBTValue::Constant(val))
+		| LIRInstrKind::PushData(_, NBTValue::Constant(val))
+		| LIRInstrKind::PushFrontData(_, NBTValue::Constant(val))
+		| LIRInstrKind::InsertData(_, NBTValue::Constant(val), ..)
+			if is_large_const(val, threshold) =>
+		{
+			Some(val)
+		}
+		_ => None,
+	}
+}
+
+fn get_operand_mut(kind: &mut LIRInstrKind) -> Option<&mut NBTValue> {
+	match kind {
+		LIRInstrKind::SetData(_, val)
+		| LIRInstrKind::MergeData(_, val)
+		| LIRInstrKind::PushData(_, val)
+		| LIRInstrKind::PushFrontData(_, val)
+		| LIRInstrKind::InsertData(_, val, ..) => Some(val),
+		_ => None,
+	}
+}
+
+fn is_large_const(val: &NBTTypeContents, threshold: usize) -> bool {
+	matches!(
+		val,
+		NBTTypeContents::Compound(..) | NBTTypeContents::List(..)
+	) && val.get_literal_str().len() > threshold
+}
@@ -1,4 +1,5 @@
 pub mod cond;
+pub mod dedup;
 pub mod fold;
 pub mod prop;
 
@@ -108,7 +109,12 @@ impl ConstAnalyzer {
 			| MIRInstrKind::Merge { left, .. }
 			| MIRInstrKind::Push { left, .. }
 			| MIRInstrKind::PushFront { left, .. }
-			| MIRInstrKind::Insert { left, .. } => {
+			| MIRInstrKind::Insert { left, .. }
+			| MIRInstrKind::BitAnd { left, .. }
+			| MIRInstrKind::BitOr { left, .. }
+			| MIRInstrKind::BitXor { left, .. }
+			| MIRInstrKind::Shl { left, .. }
+			| MIRInstrKind::Shr { left, .. } => {
 				if let MutableValue::Reg(reg) = left {
 					ConstAnalyzerResult::Remove(vec![reg])
 				} else {
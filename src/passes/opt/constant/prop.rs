@@ -95,7 +95,12 @@ fn const_prop_instr(instr: &mut MIRInstrKind, an: &mut StoringConstAnalyzer, run
 		| MIRInstrKind::PushFront { right, .. }
 		| MIRInstrKind::Insert { right, .. }
 		| MIRInstrKind::And { right, .. }
-		| MIRInstrKind::Or { right, .. } => {
+		| MIRInstrKind::Or { right, .. }
+		| MIRInstrKind::BitAnd { right, .. }
+		| MIRInstrKind::BitOr { right, .. }
+		| MIRInstrKind::BitXor { right, .. }
+		| MIRInstrKind::Shl { right, .. }
+		| MIRInstrKind::Shr { right, .. } => {
 			if let Value::Mutable(MutableValue::Reg(reg)) = right {
 				if let Some(val) = an.vals.get(reg) {
 					if let ConstAnalyzerValue::Value(val) = val {
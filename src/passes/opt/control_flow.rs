@@ -0,0 +1,61 @@
+use crate::mir::cfg::{ControlFlowGraph, Terminator};
+use crate::passes::{MIRPass, MIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+use super::constant::cond::const_eval_condition;
+
+/// Folds branches with constant conditions and merges the blocks
+/// that are left over, using the control flow graph of each function
+pub struct ControlFlowPass;
+
+impl Pass for ControlFlowPass {
+	fn get_name(&self) -> &'static str {
+		"control_flow"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::Basic
+	}
+}
+
+impl MIRPass for ControlFlowPass {
+	fn run_pass(&mut self, data: &mut MIRPassData) -> anyhow::Result<()> {
+		for func in data.mir.functions.values_mut() {
+			let mut cfg = ControlFlowGraph::from_block(func.block.clone());
+			let mut changed = fold_branches(&mut cfg);
+			changed |= cfg.merge_blocks();
+			// Functions are only rebuilt when they change so that the
+			// shape of their nested blocks stays the same otherwise
+			if changed {
+				func.block = cfg.into_block()?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Replaces branches that always go the same way with jumps
+fn fold_branches(cfg: &mut ControlFlowGraph) -> bool {
+	let mut changed = false;
+	for block in &mut cfg.blocks {
+		if let Terminator::Branch {
+			condition,
+			then,
+			otherwise,
+			..
+		} = &block.terminator
+		{
+			if condition.has_side_effects() {
+				continue;
+			}
+			if let Some(result) = const_eval_condition(condition) {
+				let target = if result { *then } else { *otherwise };
+				block.terminator = Terminator::Jump(target);
+				changed = true;
+			}
+		}
+	}
+
+	changed
+}
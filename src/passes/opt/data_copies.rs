@@ -0,0 +1,122 @@
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation};
+use crate::common::storage::{can_paths_overlap, strip_parent_path};
+use crate::common::val::{MutableNBTValue, NBTValue};
+use crate::lir::{LIRInstrKind, LIRInstruction};
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Removes copies between two storages that are already done by another copy of a
+/// parent path in the same run of copies, such as the ones that copy instructions
+/// in a row lower into
+pub struct MergeDataCopiesPass;
+
+impl Pass for MergeDataCopiesPass {
+	fn get_name(&self) -> &'static str {
+		"merge_data_copies"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::Basic
+	}
+}
+
+impl LIRPass for MergeDataCopiesPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		for func in data.lir.functions.values_mut() {
+			let contents = &mut func.block.contents;
+			let mut removed = vec![false; contents.len()];
+			let mut start = 0;
+			while start < contents.len() {
+				let Some(end) = get_run_end(contents, start) else {
+					start += 1;
+					continue;
+				};
+				remove_covered_copies(&contents[start..end], &mut removed[start..end]);
+				start = end;
+			}
+
+			let mut removed = removed.into_iter();
+			contents.retain(|_| !removed.next().expect("Lengths should be equal"));
+		}
+
+		Ok(())
+	}
+}
+
+/// A copy from a path in one storage to a path in another
+struct DataCopy<'a> {
+	from: &'a DataPath,
+	to: &'a DataPath,
+}
+
+/// Gets the end of the run of copies between the same storages that starts at an
+/// instruction, if it is a copy. Since the source and destination are different, none of the copies
+/// in the run can change what the others read
+fn get_run_end(contents: &[LIRInstruction], start: usize) -> Option<usize> {
+	let (from, to, ..) = get_copy(&contents[start])?;
+	let len = contents[start..]
+		.iter()
+		.take_while(|instr| get_copy(instr).is_some_and(|x| x.0 == from && x.1 == to))
+		.count();
+	Some(start + len)
+}
+
+/// Marks the copies in a run that are already done by another copy. A later copy of
+/// a parent path overwrites the value, and an earlier one already set it as long as
+/// nothing in between wrote to the same place
+fn remove_covered_copies(run: &[LIRInstruction], removed: &mut [bool]) {
+	let copies: Vec<_> = run
+		.iter()
+		.map(|instr| {
+			let (.., from, to) = get_copy(instr).expect("Run should only contain copies");
+			DataCopy { from, to }
+		})
+		.collect();
+
+	for (i, copy) in copies.iter().enumerate() {
+		let covered = copies.iter().enumerate().any(|(j, other)| {
+			if j == i || removed[j] || !covers(other, copy) {
+				return false;
+			}
+			j > i
+				|| copies[j + 1..i]
+					.iter()
+					.all(|x| !can_paths_overlap(x.to, copy.to))
+		});
+		removed[i] = covered;
+	}
+}
+
+/// Checks if a copy also copies everything that another copy does
+fn covers(parent: &DataCopy, child: &DataCopy) -> bool {
+	let to_rest = strip_parent_path(child.to, parent.to);
+	let from_rest = strip_parent_path(child.from, parent.from);
+	to_rest.is_some() && to_rest == from_rest
+}
+
+/// Gets the storages and paths of an unconditional copy between two different storages
+fn get_copy(
+	instr: &LIRInstruction,
+) -> Option<(&DataLocation, &DataLocation, &DataPath, &DataPath)> {
+	if !instr.modifiers.is_empty() {
+		return None;
+	}
+	let LIRInstrKind::SetData(
+		MutableNBTValue::Data(FullDataLocation {
+			loc: to_loc @ DataLocation::Storage(to_storage),
+			path: to,
+		}),
+		NBTValue::Mutable(MutableNBTValue::Data(FullDataLocation {
+			loc: from_loc @ DataLocation::Storage(from_storage),
+			path: from,
+		})),
+	) = &instr.kind
+	else {
+		return None;
+	};
+	if from_storage == to_storage {
+		return None;
+	}
+
+	Some((from_loc, to_loc, from, to))
+}
@@ -4,7 +4,7 @@ use intset::GrowSet;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::common::block::Block;
-use crate::common::reg::Local;
+use crate::common::reg::{GetUsedRegs, Local};
 use crate::common::val::ScoreValue;
 use crate::common::{val::MutableScoreValue, Identifier};
 use crate::lir::{LIRBlock, LIRInstrKind};
@@ -76,6 +76,15 @@ fn run_iter(
 ) -> bool {
 	let mut run_again = false;
 
+	// An arg that is read more than once can't become the storage of a register,
+	// since modifying the register would change what the other reads see
+	let mut arg_read_counts = FxHashMap::default();
+	for instr in &block.contents {
+		if let Some(arg) = instr.kind.get_read_score_arg() {
+			*arg_read_counts.entry(*arg).or_insert(0) += 1;
+		}
+	}
+
 	// Run the forward propagating elisions
 	for instr in &mut block.contents.iter_mut() {
 		match &instr.kind {
@@ -84,7 +93,7 @@ fn run_iter(
 				ScoreValue::Mutable(MutableScoreValue::Local(Local::Arg(r))),
 			) => {
 				if instr.modifiers.is_empty() {
-					if !used_args.contains(r) {
+					if !used_args.contains(r) && arg_read_counts.get(r) == Some(&1) {
 						arg_mapping.insert(l.clone(), MutableScoreValue::Local(Local::Arg(*r)));
 						// We don't want to create weird assign arg to self instructions, continue
 						continue;
@@ -140,7 +149,9 @@ fn run_iter(
 		run_again = run_again_2.take();
 	}
 
-	// Run the backward propagating elisions
+	// Run the backward propagating elisions. Registers that are read after the
+	// copy, including by another copy, can't be replaced since they would lose their value
+	let mut used_later = FxHashSet::default();
 	for (i, instr) in &mut block.contents.iter_mut().enumerate().rev() {
 		if instrs_to_remove.contains(i) {
 			continue;
@@ -150,32 +161,26 @@ fn run_iter(
 			LIRInstrKind::SetScore(
 				l @ MutableScoreValue::Local(Local::CallArg(..)),
 				ScoreValue::Mutable(MutableScoreValue::Local(Local::Reg(r))),
-			) => {
-				if instr.modifiers.is_empty() {
-					call_arg_mapping.insert(r.clone(), l.clone());
-					// We have to remove this assignment since it is now assigning
-					// to an invalid register
-					instrs_to_remove.add(i);
-					// We don't want to create weird assign arg to self instructions, continue
-					continue;
-				} else {
-					call_arg_mapping.remove(r);
-				}
+			) if instr.modifiers.is_empty() && !used_later.contains(r) => {
+				call_arg_mapping.insert(r.clone(), l.clone());
+				used_later.insert(r.clone());
+				// We have to remove this assignment since it is now assigning
+				// to an invalid register
+				instrs_to_remove.add(i);
+				// We don't want to create weird assign arg to self instructions, continue
+				continue;
 			}
 			LIRInstrKind::SetScore(
 				l @ MutableScoreValue::Local(Local::ReturnValue(..)),
 				ScoreValue::Mutable(MutableScoreValue::Local(Local::Reg(r))),
-			) => {
-				if instr.modifiers.is_empty() {
-					ret_mapping.insert(r.clone(), l.clone());
-					// We have to remove this assignment since it is now assigning
-					// to an invalid register
-					instrs_to_remove.add(i);
-					// We don't want to create weird assign arg to self instructions, continue
-					continue;
-				} else {
-					ret_mapping.remove(r);
-				}
+			) if instr.modifiers.is_empty() && !used_later.contains(r) => {
+				ret_mapping.insert(r.clone(), l.clone());
+				used_later.insert(r.clone());
+				// We have to remove this assignment since it is now assigning
+				// to an invalid register
+				instrs_to_remove.add(i);
+				// We don't want to create weird assign arg to self instructions, continue
+				continue;
 			}
 			_ => {}
 		}
@@ -198,6 +203,16 @@ fn run_iter(
 			}
 		});
 		run_again = run_again_2.take();
+
+		// A register that is overwritten here doesn't need its earlier value
+		if let LIRInstrKind::SetScore(MutableScoreValue::Local(Local::Reg(l)), right) = &instr.kind {
+			if instr.modifiers.is_empty() {
+				used_later.remove(l);
+				used_later.extend(right.get_used_regs().into_iter().cloned());
+				continue;
+			}
+		}
+		used_later.extend(instr.get_used_regs().into_iter().cloned());
 	}
 
 	run_again
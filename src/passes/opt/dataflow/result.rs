@@ -144,7 +144,7 @@ fn run_iter(
 				point
 					.store_locs
 					.into_iter()
-					.map(|x| Modifier::StoreResult(StoreModLocation::Local(x, 1.0))),
+					.map(|x| Modifier::StoreResult(StoreModLocation::Local(x, None, 1.0))),
 			);
 		}
 	}
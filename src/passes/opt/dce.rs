@@ -3,7 +3,7 @@ use rustc_hash::FxHashSet;
 use crate::passes::{MIRPass, MIRPassData, Pass};
 use crate::project::{OptimizationLevel, ProjectSettings};
 
-use super::get_instr_calls;
+use super::{get_instr_calls, get_instr_indirect_calls};
 
 pub struct DCEPass;
 
@@ -19,31 +19,47 @@ impl Pass for DCEPass {
 
 impl MIRPass for DCEPass {
 	fn run_pass(&mut self, data: &mut MIRPassData) -> anyhow::Result<()> {
-		// Find used functions
-		let mut used = FxHashSet::default();
-		for func in data.mir.functions.values() {
-			let block = &func.block;
-
-			for instr in &block.contents {
-				let calls = get_instr_calls(&instr.kind);
-				for call in calls {
-					used.insert(call.function.clone());
-				}
-			}
-		}
-
-		// Remove unused functions
-		let mut unused = FxHashSet::default();
-		for (func_id, func) in &data.mir.functions {
-			if func.interface.annotations.preserve {
+		// Find the functions that are reachable from the roots. Public functions can be
+		// called from outside the pack, and tests and tag handlers are called by generated
+		// functions and tags. Cycles of functions that only call each other are not kept alive
+		let mut reachable = FxHashSet::default();
+		let mut stack: Vec<_> = data
+			.mir
+			.functions
+			.iter()
+			.filter(|(_, func)| func.interface.annotations.is_root())
+			.map(|(func_id, _)| func_id)
+			.collect();
+		while let Some(func_id) = stack.pop() {
+			if !reachable.insert(func_id) {
 				continue;
 			}
-			if !used.contains(func_id) {
-				unused.insert(func_id.clone());
+			let Some(func) = data.mir.functions.get(func_id) else {
+				continue;
+			};
+
+			for instr in &func.block.contents {
+				let calls = get_instr_calls(&instr.kind)
+					.into_iter()
+					.map(|x| &x.function);
+				for callee in calls.chain(get_instr_indirect_calls(&instr.kind)) {
+					if let Some((callee, _)) = data.mir.functions.get_key_value(callee) {
+						stack.push(callee);
+					}
+				}
 			}
 		}
-		for unused in unused {
-			data.mir.functions.remove(&unused);
+
+		// Remove unreachable functions
+		let unreachable: Vec<_> = data
+			.mir
+			.functions
+			.keys()
+			.filter(|x| !reachable.contains(x))
+			.cloned()
+			.collect();
+		for func_id in unreachable {
+			data.mir.functions.remove(&func_id);
 		}
 
 		Ok(())
@@ -71,8 +71,16 @@ fn run_mir_iter(block: &mut MIRBlock, instrs_to_remove: &mut HashSetEmptyTracker
 			}
 
 			if let MIRInstrKind::Call { call } = &instr.kind {
+				// The arguments are read before the return values are written
+				let mut arg_regs = Vec::new();
+				for arg in &call.args {
+					arg.append_used_regs(&mut arg_regs);
+				}
 				for val in &call.ret {
 					if let MutableValue::Reg(reg) = val {
+						if arg_regs.contains(&reg) {
+							continue;
+						}
 						// If the candidate already exists, then that is a dead store that can be removed
 						if let Some(candidate) = elim_candidates.get(reg) {
 							dead_stores.push(*candidate);
@@ -0,0 +1,123 @@
+use crate::common::function::FunctionSignature;
+use crate::common::val::MutableValue;
+use crate::common::{Register, RegisterList};
+use crate::ir::{Block, InstrKind, Instruction, IR};
+use crate::passes::analysis::enums::{continues_chain, split_values, EnumChain, ValueSet};
+use crate::passes::{IRPass, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Uses the range of values that an enum can have to remove conditions in chains
+/// over it that are always true or false, like the check for the last case of a
+/// switch that handles every variant
+pub struct EnumDispatchPass;
+
+impl Pass for EnumDispatchPass {
+	fn get_name(&self) -> &'static str {
+		"enum_dispatch"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl IRPass for EnumDispatchPass {
+	fn run_pass(&mut self, ir: &mut IR) -> anyhow::Result<()> {
+		for func in ir.functions.values_mut() {
+			run_block(
+				&mut func.block,
+				&RegisterList::default(),
+				&func.interface.sig,
+			);
+		}
+
+		Ok(())
+	}
+}
+
+fn run_block(block: &mut Block, regs: &RegisterList, sig: &FunctionSignature) {
+	let mut regs = regs.clone();
+	let mut contents = Vec::with_capacity(block.contents.len());
+	for mut instr in std::mem::take(&mut block.contents) {
+		if let InstrKind::Declare { left, ty, .. } = &instr.kind {
+			let reg = Register {
+				id: left.clone(),
+				ty: ty.clone(),
+			};
+			regs.insert(left.clone(), reg);
+		}
+		for body in instr.kind.get_bodies_mut() {
+			run_block(body, &regs, sig);
+		}
+
+		let chain = EnumChain::new(&instr.kind, &regs, sig);
+		let Some((val, values)) = chain.map(|x| (x.get_value().clone(), x.get_variants())) else {
+			contents.push(instr);
+			continue;
+		};
+		contents.extend(narrow_chain(instr, &val, &values));
+	}
+	block.contents = contents;
+}
+
+/// Removes the conditions in a chain that are always true or false for the values
+/// that reach them, returning the instructions to replace the chain with
+fn narrow_chain(mut instr: Instruction, val: &MutableValue, values: &ValueSet) -> Vec<Instruction> {
+	match &mut instr.kind {
+		InstrKind::If { condition, body } => {
+			let Some((t, f)) = split_values(condition, val, values) else {
+				return vec![instr];
+			};
+			if t.is_empty() {
+				return Vec::new();
+			}
+			narrow_body(body, val, &t);
+			if f.is_empty() && can_flatten(body) {
+				return std::mem::take(&mut body.contents);
+			}
+		}
+		InstrKind::IfElse {
+			condition,
+			first,
+			second,
+		} => {
+			let Some((t, f)) = split_values(condition, val, values) else {
+				return vec![instr];
+			};
+			narrow_body(first, val, &t);
+			narrow_body(second, val, &f);
+			if t.is_empty() && can_flatten(second) {
+				return std::mem::take(&mut second.contents);
+			}
+			if f.is_empty() && can_flatten(first) {
+				return std::mem::take(&mut first.contents);
+			}
+		}
+		_ => {}
+	}
+
+	vec![instr]
+}
+
+/// Narrows the chain that a body continues with, if it does
+fn narrow_body(body: &mut Block, val: &MutableValue, values: &ValueSet) {
+	// The value is only known at the start of the body, since the body could change it
+	if let [instr] = body.contents.as_slice() {
+		if continues_chain(&instr.kind, val) {
+			let instr = body
+				.contents
+				.pop()
+				.expect("Body should have an instruction");
+			body.contents = narrow_chain(instr, val, values);
+		}
+	}
+}
+
+/// Checks if a body can be moved out into the block around it without its registers
+/// becoming visible to the rest of that block
+fn can_flatten(body: &Block) -> bool {
+	!body
+		.contents
+		.iter()
+		.any(|x| matches!(x.kind, InstrKind::Declare { .. }))
+}
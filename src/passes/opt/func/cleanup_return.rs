@@ -24,7 +24,9 @@ impl MIRPass for CleanupReturnPass {
 			let mut rem_pos = None;
 			for (i, instr) in block.contents.iter().enumerate() {
 				match &instr.kind {
-					MIRInstrKind::Return { .. } | MIRInstrKind::ReturnRun { .. } => {
+					MIRInstrKind::Return { .. }
+					| MIRInstrKind::ReturnRun { .. }
+					| MIRInstrKind::ReturnFail => {
 						rem_pos = Some(i);
 						break;
 					}
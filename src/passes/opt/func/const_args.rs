@@ -0,0 +1,237 @@
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::mc::{DataLocation, DataPath, FullDataLocation};
+use crate::common::ty::{DataTypeContents, NBTTypeContents};
+use crate::common::val::{ArgRetIndex, MutableValue, Value};
+use crate::common::ResourceLocation;
+use crate::mir::{MIRBlock, MIRInstrKind};
+use crate::output::text::{format_const_storage_entry, REG_STORAGE_LOCATION};
+use crate::passes::opt::{get_instr_calls, get_instr_calls_mut};
+use crate::passes::{MIRPass, MIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+use crate::util::remove_indices;
+
+/// Stores large constant compound arguments once when the pack loads instead
+/// of copying the literal at every call. When every call to a function passes the
+/// same constant and the function never modifies it, the argument is removed
+/// and the function reads the stored constant directly
+pub struct ConstArgsPass;
+
+impl Pass for ConstArgsPass {
+	fn get_name(&self) -> &'static str {
+		"const_args"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl MIRPass for ConstArgsPass {
+	fn run_pass(&mut self, data: &mut MIRPassData) -> anyhow::Result<()> {
+		let threshold = data.proj.const_arg_threshold;
+		let func_ids: Vec<_> = data.mir.functions.keys().sorted().cloned().collect();
+
+		// Find the constant that every call to a function passes for each argument
+		let mut call_args: FxHashMap<(ResourceLocation, usize), Option<NBTTypeContents>> =
+			FxHashMap::default();
+		for func_id in &func_ids {
+			let func = &data.mir.functions[func_id];
+			for instr in &func.block.contents {
+				for call in get_instr_calls(&instr.kind) {
+					for (i, arg) in call.args.iter().enumerate() {
+						let val = get_large_const(arg, threshold).cloned();
+						call_args
+							.entry((call.function.clone(), i))
+							.and_modify(|x| {
+								if *x != val {
+									*x = None;
+								}
+							})
+							.or_insert(val);
+					}
+				}
+			}
+		}
+
+		let mut pool = ConstPool {
+			consts: &mut data.mir.storage_consts,
+		};
+
+		// Read the arguments that are always the same straight from storage
+		let mut removed = FxHashMap::default();
+		for func_id in &func_ids {
+			let func = data
+				.mir
+				.functions
+				.get_mut(func_id)
+				.expect("Function should exist");
+			// Public functions can be called from outside of the pack
+			if func.interface.annotations.public {
+				continue;
+			}
+
+			let mut removed_args = Vec::new();
+			let mut new_mapping = FxHashMap::default();
+			for i in 0..func.interface.sig.params.len() {
+				let val = call_args.get(&(func_id.clone(), i)).cloned().flatten();
+				match val {
+					Some(val) if !is_arg_modified(&func.block, i as ArgRetIndex) => {
+						new_mapping.insert(
+							i as ArgRetIndex,
+							MutableValue::Data(pool.get_loc(val)),
+						);
+						removed_args.push(i);
+					}
+					_ => {
+						new_mapping.insert(
+							i as ArgRetIndex,
+							MutableValue::Arg((i - removed_args.len()) as ArgRetIndex),
+						);
+					}
+				}
+			}
+			if removed_args.is_empty() {
+				continue;
+			}
+
+			// Use hints only apply to locals, so they are dropped for stored arguments
+			remove_arg_uses(&mut func.block, &removed_args);
+			let remap = |val: &mut MutableValue| {
+				let root = get_root_mut(val);
+				if let MutableValue::Arg(arg) = root {
+					*root = new_mapping
+						.get(arg)
+						.expect("New argument mapping should exist")
+						.clone();
+				}
+			};
+			for instr in &mut func.block.contents {
+				instr.kind.replace_mut_vals(&remap);
+				for call in get_instr_calls_mut(&mut instr.kind) {
+					for ret in &mut call.ret {
+						remap(ret);
+					}
+				}
+			}
+			remove_indices(&mut func.interface.sig.params, &removed_args);
+			removed.insert(func_id.clone(), removed_args);
+		}
+
+		// Remove the arguments from calls and make the other calls copy from storage
+		for func_id in &func_ids {
+			let func = data
+				.mir
+				.functions
+				.get_mut(func_id)
+				.expect("Function should exist");
+			for instr in &mut func.block.contents {
+				for call in get_instr_calls_mut(&mut instr.kind) {
+					if let Some(removed) = removed.get(&call.function) {
+						remove_indices(&mut call.args, removed);
+					}
+					for arg in &mut call.args {
+						if let Some(val) = get_large_const(arg, threshold) {
+							*arg = Value::Mutable(MutableValue::Data(pool.get_loc(val.clone())));
+						}
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Constants that have been put in storage
+pub(crate) struct ConstPool<'mir> {
+	pub consts: &'mir mut FxHashMap<String, NBTTypeContents>,
+}
+
+impl<'mir> ConstPool<'mir> {
+	/// Gets the location of a constant in storage, adding it if it isn't there yet
+	pub fn get_loc(&mut self, val: NBTTypeContents) -> FullDataLocation {
+		let existing = self
+			.consts
+			.iter()
+			.find(|(_, x)| **x == val)
+			.map(|(entry, _)| entry.clone());
+		let entry = existing.unwrap_or_else(|| {
+			let entry = format_const_storage_entry(self.consts.len() as u32);
+			self.consts.insert(entry.clone(), val);
+			entry
+		});
+
+		FullDataLocation {
+			loc: DataLocation::Storage(REG_STORAGE_LOCATION.into()),
+			path: DataPath::String(entry),
+		}
+	}
+}
+
+/// Gets a compound constant that is large enough to be worth storing
+fn get_large_const(val: &Value, threshold: usize) -> Option<&NBTTypeContents> {
+	match val {
+		Value::Constant(DataTypeContents::NBT(val @ NBTTypeContents::Compound(..)))
+			if val.get_literal_str().len() > threshold =>
+		{
+			Some(val)
+		}
+		_ => None,
+	}
+}
+
+/// Checks if an argument or any part of it is modified in a block
+fn is_arg_modified(block: &MIRBlock, arg: ArgRetIndex) -> bool {
+	let is_arg = |val: &MutableValue| matches!(get_root(val), MutableValue::Arg(a) if *a == arg);
+
+	block.contents.iter().any(|instr| {
+		let modified = match &instr.kind {
+			MIRInstrKind::Swap { left, right } => is_arg(left) || is_arg(right),
+			MIRInstrKind::Call { call } => call.ret.iter().any(is_arg),
+			MIRInstrKind::ReturnRun { body } => is_arg_modified(body, arg),
+			other => other.get_op_lhs().is_some_and(is_arg),
+		};
+		modified
+			|| instr
+				.kind
+				.get_bodies()
+				.into_iter()
+				.any(|body| is_arg_modified(body, arg))
+	})
+}
+
+fn remove_arg_uses(block: &mut MIRBlock, args: &[usize]) {
+	for instr in &mut block.contents {
+		match &mut instr.kind {
+			MIRInstrKind::Use { val } => {
+				if let MutableValue::Arg(arg) = get_root(val) {
+					if args.contains(arg) {
+						instr.kind = MIRInstrKind::NoOp;
+					}
+				}
+			}
+			MIRInstrKind::ReturnRun { body } => remove_arg_uses(body, args),
+			other => {
+				for body in other.get_bodies_mut() {
+					remove_arg_uses(body, args);
+				}
+			}
+		}
+	}
+}
+
+fn get_root(val: &MutableValue) -> &MutableValue {
+	match val {
+		MutableValue::Property(val, ..) | MutableValue::Index(val, ..) => get_root(val),
+		other => other,
+	}
+}
+
+fn get_root_mut(val: &mut MutableValue) -> &mut MutableValue {
+	match val {
+		MutableValue::Property(val, ..) | MutableValue::Index(val, ..) => get_root_mut(val),
+		other => other,
+	}
+}
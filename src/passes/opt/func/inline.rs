@@ -2,11 +2,13 @@ use anyhow::{anyhow, Context};
 use intset::GrowSet;
 use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::common::cost::GetCost;
 use crate::common::function::{CallInterface, FunctionArgs, FunctionInterface, FunctionSignature};
 use crate::common::val::{MutableValue, Value};
 use crate::common::{DeclareBinding, Identifier, Register, RegisterList, ResourceLocation};
 use crate::lower::{cleanup_fn_id, fmt_lowered_arg};
 use crate::mir::{MIRBlock, MIRFunction, MIRInstrKind, MIRInstruction};
+use crate::passes::opt::{get_instr_calls, get_instr_indirect_calls};
 use crate::passes::util::RunAgain;
 use crate::passes::{MIRPass, MIRPassData, Pass};
 use crate::project::{OptimizationLevel, ProjectSettings};
@@ -27,39 +29,43 @@ impl Pass for SimpleInlinePass {
 impl MIRPass for SimpleInlinePass {
 	fn run_pass(&mut self, data: &mut MIRPassData) -> anyhow::Result<()> {
 		let cloned_funcs = data.mir.functions.clone();
+		let uses = count_uses(&cloned_funcs);
 		for func in data.mir.functions.values_mut() {
-			run_block(
-				&func.interface,
-				&data.inline_candidates,
-				&mut func.block,
-				&cloned_funcs,
-				true,
-			)?;
+			let cx = InlineCx {
+				interface: &func.interface,
+				inline_candidates: &data.inline_candidates,
+				cloned_funcs: &cloned_funcs,
+				uses: &uses,
+				threshold: (data.proj.inline_thresholds).get(func.interface.annotations.freq),
+			};
+			run_block(&cx, &mut func.block, true)?;
 		}
 
 		Ok(())
 	}
 }
 
-fn run_block(
-	interface: &FunctionInterface,
-	inline_candidates: &FxHashSet<ResourceLocation>,
-	block: &mut MIRBlock,
-	cloned_funcs: &FxHashMap<ResourceLocation, MIRFunction>,
-	is_root: bool,
-) -> anyhow::Result<RunAgain> {
+struct InlineCx<'a> {
+	interface: &'a FunctionInterface,
+	inline_candidates: &'a FxHashSet<ResourceLocation>,
+	cloned_funcs: &'a FxHashMap<ResourceLocation, MIRFunction>,
+	/// The number of copies of each function that there would be if every call was inlined
+	uses: &'a FxHashMap<ResourceLocation, u32>,
+	/// The largest cost of a function with more than one use that will be inlined
+	threshold: f32,
+}
+
+fn run_block(cx: &InlineCx, block: &mut MIRBlock, is_root: bool) -> anyhow::Result<RunAgain> {
 	let mut out = RunAgain::new();
 
 	loop {
 		let mut instrs_to_remove_set = GrowSet::with_capacity(block.contents.len());
 		let mut instrs_to_remove = Vec::new();
 		let run_again = run_iter(
-			interface,
+			cx,
 			block,
 			&mut instrs_to_remove,
 			&mut instrs_to_remove_set,
-			inline_candidates,
-			&cloned_funcs,
 			is_root,
 		)?;
 		out.merge(run_again);
@@ -75,12 +81,10 @@ fn run_block(
 }
 
 fn run_iter(
-	interface: &FunctionInterface,
+	cx: &InlineCx,
 	block: &mut MIRBlock,
 	instrs_to_remove: &mut Vec<(usize, Vec<MIRInstruction>)>,
 	instrs_to_remove_set: &mut GrowSet,
-	inline_candidates: &FxHashSet<ResourceLocation>,
-	cloned_funcs: &FxHashMap<ResourceLocation, MIRFunction>,
 	is_root: bool,
 ) -> anyhow::Result<RunAgain> {
 	let mut run_again = RunAgain::new();
@@ -99,14 +103,14 @@ fn run_iter(
 		}
 		// Inline simple blocks into modifying instruction bodies
 		if let MIRInstrKind::Call { call } = &instr.kind {
-			let block = get_call_block(call, inline_candidates, interface, cloned_funcs)?;
+			let block = get_call_block(call, cx)?;
 			if let Some(block) = block {
 				// If we aren't at the root, then inlining blocks that are more than 1 long
 				// will just create a bunch of identical copies of functions because the blocks will be inlined
 				// and just lowered to functions again
 				// We may want to relax this for special cases in the future that allow certain folds to be run
 				if !(!is_root && block.contents.len() != 1) {
-					let block = get_inlined_block(call, interface, block, &regs)?;
+					let block = get_inlined_block(call, cx.interface, block, &regs)?;
 					instrs_to_remove.push((i, block));
 					instrs_to_remove_set.add(i);
 					run_again.yes();
@@ -114,13 +118,37 @@ fn run_iter(
 			}
 		}
 		for body in instr.kind.get_bodies_mut() {
-			run_block(interface, inline_candidates, body, cloned_funcs, false)?;
+			run_block(cx, body, false)?;
 		}
 	}
 
 	Ok(run_again)
 }
 
+/// Counts how many copies of each function there would be if every call to it was inlined.
+/// Functions that are kept around for other reasons, like being called from outside
+/// of the pack, count as being used once more
+fn count_uses(
+	funcs: &FxHashMap<ResourceLocation, MIRFunction>,
+) -> FxHashMap<ResourceLocation, u32> {
+	let mut out = FxHashMap::default();
+	for (func_id, func) in funcs {
+		if func.interface.annotations.is_root() {
+			*out.entry(func_id.clone()).or_default() += 1;
+		}
+		for instr in &func.block.contents {
+			for call in get_instr_calls(&instr.kind) {
+				*out.entry(call.function.clone()).or_default() += 1;
+			}
+			for callee in get_instr_indirect_calls(&instr.kind) {
+				*out.entry(callee.clone()).or_default() += 1;
+			}
+		}
+	}
+
+	out
+}
+
 fn get_inlined_block(
 	call: &CallInterface,
 	interface: &FunctionInterface,
@@ -144,23 +172,31 @@ fn get_inlined_block(
 	Ok(inlined_contents)
 }
 
-/// Get the block from the function we are calling, to possibly be inlined
+/// Get the block from the function we are calling, if it should be inlined
 fn get_call_block<'f>(
 	call: &CallInterface,
-	inline_candidates: &FxHashSet<ResourceLocation>,
-	interface: &FunctionInterface,
-	cloned_funcs: &'f FxHashMap<ResourceLocation, MIRFunction>,
+	cx: &InlineCx<'f>,
 ) -> anyhow::Result<Option<&'f MIRBlock>> {
 	// Don't inline this function call if it is recursive
-	if call.function == interface.id {
+	if call.function == cx.interface.id {
 		return Ok(None);
 	}
-	if !inline_candidates.contains(&call.function) {
+	if !cx.inline_candidates.contains(&call.function) {
 		return Ok(None);
 	}
-	let func = cloned_funcs
+	let func = cx
+		.cloned_funcs
 		.get(&call.function)
 		.ok_or(anyhow!("Called function does not exist"))?;
+	// Functions that are used in more than one place are copied by inlining, so only
+	// small ones are, with larger ones allowed when the caller runs often. Calls with
+	// only constant arguments are still inlined since they will likely be folded away
+	let uses = cx.uses.get(&call.function).copied().unwrap_or_default();
+	let const_args =
+		!call.args.is_empty() && call.args.iter().all(|x| matches!(x, Value::Constant(..)));
+	if uses > 1 && !const_args && func.block.get_cost() > cx.threshold {
+		return Ok(None);
+	}
 	Ok(Some(&func.block))
 }
 
@@ -1,3 +1,4 @@
 pub mod cleanup_return;
+pub mod const_args;
 pub mod inline;
 pub mod unused_args;
@@ -1,16 +1,22 @@
 use crate::common::function::CallInterface;
+use crate::common::mc::instr::MinecraftInstr;
 use crate::common::val::ArgRetIndex;
-use crate::common::Identifier;
+use crate::common::{Identifier, ResourceLocation};
 use crate::mir::{MIRBlock, MIRInstrKind};
 
+pub mod assert;
 pub mod constant;
+pub mod control_flow;
+pub mod data_copies;
 pub mod dataflow;
 pub mod dce;
 pub mod dse;
+pub mod enums;
 pub mod func;
 pub mod modifiers;
 pub mod multifold;
 pub mod order;
+pub mod reset;
 pub mod simplify;
 pub mod ty;
 
@@ -23,6 +29,11 @@ pub enum OptimizableValue {
 pub fn get_instr_calls(instr: &MIRInstrKind) -> Vec<&CallInterface> {
 	match instr {
 		MIRInstrKind::Call { call } => vec![call],
+		MIRInstrKind::ReturnRun { body } => body
+			.contents
+			.iter()
+			.flat_map(|x| get_instr_calls(&x.kind))
+			.collect(),
 		other => {
 			let bodies = other.get_bodies();
 			let mut out = Vec::new();
@@ -41,6 +52,11 @@ pub fn get_instr_calls(instr: &MIRInstrKind) -> Vec<&CallInterface> {
 pub fn get_instr_calls_mut(instr: &mut MIRInstrKind) -> Vec<&mut CallInterface> {
 	match instr {
 		MIRInstrKind::Call { call } => vec![call],
+		MIRInstrKind::ReturnRun { body } => body
+			.contents
+			.iter_mut()
+			.flat_map(|x| get_instr_calls_mut(&mut x.kind))
+			.collect(),
 		other => {
 			let bodies = other.get_bodies_mut();
 			let mut out = Vec::new();
@@ -56,6 +72,41 @@ pub fn get_instr_calls_mut(instr: &mut MIRInstrKind) -> Vec<&mut CallInterface>
 	}
 }
 
+/// Gets the functions that an instruction runs without a normal call, such as
+/// ones called by name, called with macro arguments, or scheduled to run later
+pub fn get_instr_indirect_calls(instr: &MIRInstrKind) -> Vec<&ResourceLocation> {
+	match instr {
+		MIRInstrKind::CallExtern { func }
+		| MIRInstrKind::CallWith { func, .. }
+		| MIRInstrKind::MC(MinecraftInstr::ScheduleFunction { function: func, .. }) => vec![func],
+		MIRInstrKind::ReturnRun { body } => body
+			.contents
+			.iter()
+			.flat_map(|x| get_instr_indirect_calls(&x.kind))
+			.collect(),
+		other => other
+			.get_bodies()
+			.into_iter()
+			.flat_map(|body| body.contents.iter())
+			.flat_map(|x| get_instr_indirect_calls(&x.kind))
+			.collect(),
+	}
+}
+
+/// Checks if an instruction is or contains a macro line
+pub fn instr_has_macro_line(instr: &MIRInstrKind) -> bool {
+	match instr {
+		MIRInstrKind::MacroCommand { .. } => true,
+		MIRInstrKind::ReturnRun { body } => {
+			body.contents.iter().any(|x| instr_has_macro_line(&x.kind))
+		}
+		other => other
+			.get_bodies()
+			.into_iter()
+			.any(|body| body.contents.iter().any(|x| instr_has_macro_line(&x.kind))),
+	}
+}
+
 pub fn are_blocks_equivalent(block1: &MIRBlock, block2: &MIRBlock) -> bool {
 	block1 == block2
 }
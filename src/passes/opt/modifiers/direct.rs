@@ -0,0 +1,186 @@
+use crate::common::mc::entity::{SelectorParameter, SelectorType};
+use crate::common::mc::modifier::{IfModCondition, IfScoreCondition, Modifier, StoreModLocation};
+use crate::common::mc::{DataLocation, EntityTarget, FullDataLocation, Score};
+use crate::common::val::{MutableNBTValue, MutableScoreValue, NBTValue, ScoreValue};
+use crate::common::Identifier;
+use crate::lir::LIRInstrKind;
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Replaces selectors that can only ever be one named player with that
+/// name, and `@s` under an `as` of a player name with the name directly,
+/// so that the executor switch can be removed by the null modifiers pass
+pub struct DirectTargetsPass;
+
+impl Pass for DirectTargetsPass {
+	fn get_name(&self) -> &'static str {
+		"direct_targets"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl LIRPass for DirectTargetsPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		for func in data.lir.functions.values_mut() {
+			for instr in &mut func.block.contents {
+				// The player that is known to be executing the rest of the command
+				let mut executor: Option<Identifier> = None;
+				for modifier in &mut instr.modifiers {
+					match modifier {
+						Modifier::As(tgt) => {
+							make_direct(tgt);
+							replace_this(tgt, &executor);
+							executor = match tgt {
+								EntityTarget::Player(name) => Some(name.clone()),
+								_ => None,
+							};
+						}
+						Modifier::On(..) | Modifier::Summon(..) => executor = None,
+						Modifier::At(tgt)
+						| Modifier::PositionedAs(tgt)
+						| Modifier::RotatedAs(tgt)
+						| Modifier::FacingEntity(tgt, ..) => replace_this(tgt, &executor),
+						Modifier::If { condition, .. } => match condition.as_mut() {
+							IfModCondition::Entity(tgt) => replace_this(tgt, &executor),
+							IfModCondition::Score(IfScoreCondition::Single { left, right }) => {
+								replace_this_score_val(left, &executor);
+								replace_this_score_val(right, &executor);
+							}
+							IfModCondition::Score(IfScoreCondition::Range { score, .. }) => {
+								replace_this_score_val(score, &executor);
+							}
+							IfModCondition::DataExists(val)
+							| IfModCondition::DataEquals(val, ..) => {
+								replace_this_nbt(val, &executor);
+							}
+							_ => {}
+						},
+						Modifier::StoreResult(loc) | Modifier::StoreSuccess(loc) => match loc {
+							StoreModLocation::Score(score) => {
+								replace_this(&mut score.holder, &executor)
+							}
+							StoreModLocation::Data(loc, ..) => replace_this_loc(loc, &executor),
+							_ => {}
+						},
+						_ => {}
+					}
+				}
+
+				replace_in_instr(&mut instr.kind, &executor);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+fn replace_in_instr(kind: &mut LIRInstrKind, executor: &Option<Identifier>) {
+	match kind {
+		LIRInstrKind::SetScore(left, right)
+		| LIRInstrKind::AddScore(left, right)
+		| LIRInstrKind::SubScore(left, right)
+		| LIRInstrKind::MulScore(left, right)
+		| LIRInstrKind::DivScore(left, right)
+		| LIRInstrKind::ModScore(left, right)
+		| LIRInstrKind::MinScore(left, right)
+		| LIRInstrKind::MaxScore(left, right) => {
+			replace_this_score(left, executor);
+			replace_this_score_val(right, executor);
+		}
+		LIRInstrKind::SwapScore(left, right) => {
+			replace_this_score(left, executor);
+			replace_this_score(right, executor);
+		}
+		LIRInstrKind::ResetScore(val) | LIRInstrKind::GetScore(val) => {
+			replace_this_score(val, executor);
+		}
+		LIRInstrKind::SetData(left, right)
+		| LIRInstrKind::MergeData(left, right)
+		| LIRInstrKind::PushData(left, right)
+		| LIRInstrKind::PushFrontData(left, right)
+		| LIRInstrKind::InsertData(left, right, ..) => {
+			replace_this_nbt(left, executor);
+			if let NBTValue::Mutable(right) = right {
+				replace_this_nbt(right, executor);
+			}
+		}
+		LIRInstrKind::RemoveData(val) | LIRInstrKind::GetData(val, ..) => {
+			replace_this_nbt(val, executor);
+		}
+		LIRInstrKind::MC(instr) if instr.get_meta().targets.is_loaded() => {
+			for tgt in instr.get_targets_mut() {
+				make_direct(tgt);
+				replace_this(tgt, executor);
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Replaces a selector that can only select one player by name with that name
+fn make_direct(tgt: &mut EntityTarget) {
+	let EntityTarget::Selector(sel) = tgt else {
+		return;
+	};
+	if !matches!(
+		sel.selector,
+		SelectorType::AllPlayers | SelectorType::NearestPlayer | SelectorType::RandomPlayer
+	) {
+		return;
+	}
+
+	let mut name = None;
+	for param in &sel.params {
+		match param {
+			SelectorParameter::Name {
+				name: param_name,
+				invert: false,
+			} if name.is_none() => name = Some(param_name),
+			SelectorParameter::Limit(1) => {}
+			_ => return,
+		}
+	}
+	// Quoted names can have characters that aren't allowed in a plain name
+	if let Some(name) = name.filter(|x| !x.is_empty() && !x.contains(['"', ' '])) {
+		*tgt = EntityTarget::Player(name.as_str().into());
+	}
+}
+
+fn replace_this(tgt: &mut EntityTarget, executor: &Option<Identifier>) {
+	if let Some(executor) = executor {
+		if tgt.is_blank_this() {
+			*tgt = EntityTarget::Player(executor.clone());
+		}
+	}
+}
+
+fn replace_this_score(val: &mut MutableScoreValue, executor: &Option<Identifier>) {
+	if let MutableScoreValue::Score(Score { holder, .. }) = val {
+		replace_this(holder, executor);
+	}
+}
+
+fn replace_this_score_val(val: &mut ScoreValue, executor: &Option<Identifier>) {
+	if let ScoreValue::Mutable(val) = val {
+		replace_this_score(val, executor);
+	}
+}
+
+fn replace_this_nbt(val: &mut MutableNBTValue, executor: &Option<Identifier>) {
+	match val {
+		MutableNBTValue::Data(loc) => replace_this_loc(loc, executor),
+		MutableNBTValue::Index(val, ..) | MutableNBTValue::Property(val, ..) => {
+			replace_this_nbt(val, executor)
+		}
+		MutableNBTValue::Local(..) => {}
+	}
+}
+
+fn replace_this_loc(loc: &mut FullDataLocation, executor: &Option<Identifier>) {
+	if let DataLocation::Entity(tgt) = &mut loc.loc {
+		replace_this(tgt, executor);
+	}
+}
@@ -0,0 +1,255 @@
+use anyhow::bail;
+use itertools::Itertools;
+
+use crate::common::cost::GetCost;
+use crate::common::function::FunctionInterface;
+use crate::common::mc::entity::SelectorType;
+use crate::common::mc::instr::meta::InstrWrites;
+use crate::common::mc::modifier::{Modifier, StoreModLocation};
+use crate::common::mc::{DataLocation, EntityTarget};
+use crate::common::reg::GetUsedLocals;
+use crate::common::val::MutableNBTValue;
+use crate::common::ResourceLocation;
+use crate::lir::{LIRBlock, LIRFunction, LIRInstrKind, LIRInstruction};
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// The cost of moving instructions into a function and calling it
+const CALL_COST: f32 = 100.0;
+
+/// Moves runs of instructions that start with the same modifiers into a function
+/// that is called once with those modifiers, so that they are only evaluated once
+pub struct HoistModifiersPass;
+
+impl Pass for HoistModifiersPass {
+	fn get_name(&self) -> &'static str {
+		"hoist_modifiers"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl LIRPass for HoistModifiersPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		let mut new_funcs = Vec::new();
+		for (func_id, func) in data.lir.functions.iter_mut().sorted_by(|x, y| x.0.cmp(y.0)) {
+			let runs = get_runs(&func.block.contents);
+			if runs.is_empty() {
+				continue;
+			}
+
+			let mut contents = Vec::with_capacity(func.block.contents.len());
+			let mut old_contents = std::mem::take(&mut func.block.contents).into_iter();
+			let mut pos = 0;
+			for (i, (start, end, prefix_len)) in runs.into_iter().enumerate() {
+				contents.extend(old_contents.by_ref().take(start - pos));
+				let mut run: Vec<_> = old_contents.by_ref().take(end - start).collect();
+				pos = end;
+
+				let modifiers: Vec<_> = run[0].modifiers.drain(..prefix_len).collect();
+				for instr in &mut run[1..] {
+					instr.modifiers.drain(..prefix_len);
+				}
+
+				let mut block = LIRBlock::new(func.block.regs.clone());
+				block.contents = run;
+				let locs = block.get_used_locals().into_iter().cloned().collect();
+				let id: ResourceLocation = format!("{func_id}_hoist_{i}").into();
+				let mut interface = FunctionInterface::new(id.clone());
+				interface.annotations.generated = true;
+				new_funcs.push(LIRFunction {
+					interface,
+					block,
+					// The function uses the same registers as the original
+					parent: Some(func.parent.clone().unwrap_or(func_id.clone())),
+				});
+				contents.push(LIRInstruction::with_modifiers(
+					LIRInstrKind::Call(id, locs),
+					modifiers,
+				));
+			}
+			contents.extend(old_contents);
+			func.block.contents = contents;
+		}
+
+		for func in new_funcs {
+			if data.lir.functions.contains_key(&func.interface.id) {
+				bail!(
+					"Function {} generated for hoisted modifiers conflicts with an existing function",
+					func.interface.id
+				);
+			}
+			data.lir.functions.insert(func.interface.id.clone(), func);
+		}
+
+		Ok(())
+	}
+}
+
+/// Finds the runs of instructions that are worth hoisting, as the start and end
+/// of the run and the number of modifiers that they share
+fn get_runs(contents: &[LIRInstruction]) -> Vec<(usize, usize, usize)> {
+	let mut out = Vec::new();
+	let mut start = 0;
+	while start < contents.len() {
+		let first = &contents[start];
+		let mut prefix_len = if can_move_instr(first) {
+			first
+				.modifiers
+				.iter()
+				.take_while(|x| is_hoistable(x))
+				.count()
+		} else {
+			0
+		};
+
+		let mut end = start + 1;
+		if prefix_len > 0 {
+			for (prev, instr) in contents[start..].iter().tuple_windows() {
+				if !can_move_instr(instr) {
+					break;
+				}
+				let mut common = first.modifiers[..prefix_len]
+					.iter()
+					.zip(&instr.modifiers)
+					.take_while(|(l, r)| are_modifiers_eq(l, r))
+					.count();
+				// Modifiers that depend on the world would be evaluated before the previous
+				// instruction runs instead of after it
+				if can_modify_world(prev) {
+					common = first.modifiers[..common]
+						.iter()
+						.take_while(|x| !relies_on_world(x))
+						.count();
+				}
+				if common == 0 {
+					break;
+				}
+				prefix_len = common;
+				end += 1;
+			}
+		}
+
+		let cost: f32 = first.modifiers[..prefix_len]
+			.iter()
+			.map(|x| x.get_cost())
+			.sum();
+		if prefix_len > 0 && cost * (end - start - 1) as f32 > CALL_COST {
+			out.push((start, end, prefix_len));
+		}
+		start = end;
+	}
+
+	out
+}
+
+/// Checks if a modifier can be hoisted. Instructions that are run separately have
+/// the modifier evaluated in between them, so it can only be hoisted if it runs the
+/// rest of the command at most once and gives the same context each time
+fn is_hoistable(modifier: &Modifier) -> bool {
+	match modifier {
+		Modifier::Anchored(..)
+		| Modifier::Align(..)
+		| Modifier::In(..)
+		| Modifier::Positioned(..)
+		| Modifier::Rotated(..)
+		| Modifier::FacingPosition(..) => true,
+		Modifier::As(tgt)
+		| Modifier::At(tgt)
+		| Modifier::PositionedAs(tgt)
+		| Modifier::RotatedAs(tgt)
+		| Modifier::FacingEntity(tgt, ..) => is_fixed_target(tgt),
+		_ => false,
+	}
+}
+
+/// Checks if a target always selects the same single entity
+fn is_fixed_target(tgt: &EntityTarget) -> bool {
+	match tgt {
+		EntityTarget::Player(..) => true,
+		EntityTarget::Selector(sel) => sel.selector == SelectorType::This && sel.params.is_empty(),
+	}
+}
+
+/// Checks if the context a modifier gives depends on where an entity is
+fn relies_on_world(modifier: &Modifier) -> bool {
+	matches!(
+		modifier,
+		Modifier::At(..)
+			| Modifier::PositionedAs(..)
+			| Modifier::RotatedAs(..)
+			| Modifier::FacingEntity(..)
+	)
+}
+
+fn are_modifiers_eq(l: &Modifier, r: &Modifier) -> bool {
+	match (l, r) {
+		(Modifier::Anchored(l), Modifier::Anchored(r)) => l == r,
+		(Modifier::Align(l), Modifier::Align(r)) => l == r,
+		(Modifier::In(l), Modifier::In(r)) => l == r,
+		(Modifier::Positioned(l), Modifier::Positioned(r))
+		| (Modifier::FacingPosition(l), Modifier::FacingPosition(r)) => l == r,
+		(Modifier::Rotated(l), Modifier::Rotated(r)) => l == r,
+		(Modifier::As(l), Modifier::As(r))
+		| (Modifier::At(l), Modifier::At(r))
+		| (Modifier::PositionedAs(l), Modifier::PositionedAs(r))
+		| (Modifier::RotatedAs(l), Modifier::RotatedAs(r)) => l.is_value_eq(r),
+		(Modifier::FacingEntity(l, l_anchor), Modifier::FacingEntity(r, r_anchor)) => {
+			l.is_value_eq(r) && l_anchor == r_anchor
+		}
+		_ => false,
+	}
+}
+
+/// Checks if an instruction can be moved into another function. Returns would only
+/// exit that function, and macro lines need the arguments of this one
+fn can_move_instr(instr: &LIRInstruction) -> bool {
+	!matches!(
+		instr.kind,
+		LIRInstrKind::ReturnValue(..)
+			| LIRInstrKind::ReturnFail
+			| LIRInstrKind::ReturnRun(..)
+			| LIRInstrKind::MacroCommand(..)
+	)
+}
+
+/// Checks if an instruction could move entities around
+fn can_modify_world(instr: &LIRInstruction) -> bool {
+	let stores_to_world = instr.modifiers.iter().any(|x| {
+		matches!(
+			x,
+			Modifier::StoreResult(StoreModLocation::Data(loc, ..))
+				| Modifier::StoreSuccess(StoreModLocation::Data(loc, ..))
+				if !matches!(loc.loc, DataLocation::Storage(..))
+		) || matches!(x, Modifier::Summon(..))
+	});
+	if stores_to_world {
+		return true;
+	}
+
+	match &instr.kind {
+		LIRInstrKind::SetData(val, ..)
+		| LIRInstrKind::MergeData(val, ..)
+		| LIRInstrKind::PushData(val, ..)
+		| LIRInstrKind::PushFrontData(val, ..)
+		| LIRInstrKind::InsertData(val, ..)
+		| LIRInstrKind::RemoveData(val) => is_world_data(val),
+		LIRInstrKind::MC(instr) => instr.get_meta().writes == InstrWrites::World,
+		LIRInstrKind::Call(..)
+		| LIRInstrKind::CallWith(..)
+		| LIRInstrKind::Command(..)
+		| LIRInstrKind::MacroCommand(..) => true,
+		_ => false,
+	}
+}
+
+/// Checks if a data value is in an entity or block instead of a storage
+fn is_world_data(val: &MutableNBTValue) -> bool {
+	match val {
+		MutableNBTValue::Data(loc) => !matches!(loc.loc, DataLocation::Storage(..)),
+		MutableNBTValue::Index(val, ..) | MutableNBTValue::Property(val, ..) => is_world_data(val),
+		MutableNBTValue::Local(..) => false,
+	}
+}
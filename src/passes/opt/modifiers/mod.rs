@@ -1,18 +1,24 @@
 use num_traits::Num;
 use rustc_hash::FxHashSet;
 
-use crate::common::mc::instr::MinecraftInstr;
+use crate::common::mc::instr::{InstrOperand, MinecraftInstr};
 use crate::common::mc::item::{ItemModifyLocation, LootSource};
 use crate::common::mc::modifier::Modifier;
 use crate::common::mc::pos::{Coordinates, Coordinates2D};
+use crate::common::mc::text::TextComponentContents;
 use crate::common::mc::{DataLocation, EntityTarget};
+use crate::common::ResourceLocation;
 use crate::common::val::{MutableNBTValue, MutableScoreValue, NBTValue, ScoreValue};
 use crate::lir::LIRInstrKind;
 use crate::util::GetSetOwned;
 
+pub mod direct;
+pub mod hoist;
 pub mod merge;
+pub mod narrow;
 pub mod null;
 pub mod simplify;
+pub mod variants;
 
 /// The aspects of the game that a modifier can modify. Can be used
 /// to model modifier and command dependencies and relationships
@@ -103,7 +109,12 @@ impl GetSetOwned<Modified> for Modifier {
 					}
 				}
 			}
-			Modifier::At(..) | Modifier::Align(..) | Modifier::Anchored(..) | Modifier::On(..) => {
+			Modifier::At(..) => {
+				set.insert(Modified(ModifierContext::Position));
+				set.insert(Modified(ModifierContext::Rotation));
+				set.insert(Modified(ModifierContext::Dimension));
+			}
+			Modifier::Align(..) | Modifier::Anchored(..) | Modifier::On(..) => {
 				set.insert(Modified(ModifierContext::Position));
 				set.insert(Modified(ModifierContext::Rotation));
 			}
@@ -130,6 +141,30 @@ impl GetSetOwned<Modified> for Modifier {
 	}
 }
 
+/// Gets the contexts that a modifier completely replaces, so that the
+/// values they had before the modifier can't be observed after it
+pub fn get_overwritten_contexts(modifier: &Modifier) -> &'static [ModifierContext] {
+	match modifier {
+		Modifier::At(..) => &[
+			ModifierContext::Position,
+			ModifierContext::Rotation,
+			ModifierContext::Dimension,
+		],
+		Modifier::Positioned(Coordinates::XYZ(x, y, z))
+			if !x.is_rel() && !y.is_rel() && !z.is_rel() =>
+		{
+			&[ModifierContext::Position]
+		}
+		Modifier::In(..) => &[ModifierContext::Dimension],
+		_ => &[],
+	}
+}
+
+/// Checks if a dimension is the overworld
+pub fn is_overworld(dim: &ResourceLocation) -> bool {
+	matches!(dim.as_ref(), "minecraft:overworld" | "overworld")
+}
+
 impl GetSetOwned<Dependency> for LIRInstrKind {
 	fn append_set(&self, set: &mut FxHashSet<Dependency>) {
 		let mut depend_repetition = true;
@@ -188,7 +223,9 @@ impl GetSetOwned<Dependency> for LIRInstrKind {
 				r.append_set(set);
 			}
 			LIRInstrKind::Call(..)
+			| LIRInstrKind::CallWith(..)
 			| LIRInstrKind::Command(..)
+			| LIRInstrKind::MacroCommand(..)
 			| LIRInstrKind::ReturnFail
 			| LIRInstrKind::ReturnRun(..) => {
 				set.insert(Dependency(ModifierContext::Everything));
@@ -209,215 +246,49 @@ impl GetSetOwned<Dependency> for LIRInstrKind {
 
 impl GetSetOwned<Dependency> for MinecraftInstr {
 	fn append_set(&self, set: &mut FxHashSet<Dependency>) {
-		let mut depend_repetition = true;
-		match self {
-			MinecraftInstr::WhitelistReload
-			| MinecraftInstr::Say { .. }
-			| MinecraftInstr::GetTime { .. }
-			| MinecraftInstr::GetGamerule { .. }
-			| MinecraftInstr::WorldBorderAdd { .. }
-			| MinecraftInstr::Reload
-			| MinecraftInstr::AddScoreboardObjective { .. }
-			| MinecraftInstr::BanIP { .. }
-			| MinecraftInstr::PardonIP { .. }
-			| MinecraftInstr::RemoveScoreboardObjective { .. }
-			| MinecraftInstr::SetDatapackOrder { .. }
-			| MinecraftInstr::SetDatapackPriority { .. }
-			| MinecraftInstr::AddTime { .. }
-			| MinecraftInstr::StopServer
-			| MinecraftInstr::StopSound
-			| MinecraftInstr::ListDatapacks { .. }
-			| MinecraftInstr::WorldBorderSet { .. } => {}
-			MinecraftInstr::Banlist
-			| MinecraftInstr::GetDifficulty
-			| MinecraftInstr::WhitelistList
-			| MinecraftInstr::WhitelistOff
-			| MinecraftInstr::WhitelistOn
-			| MinecraftInstr::Seed
-			| MinecraftInstr::WorldBorderGet
-			| MinecraftInstr::DisableDatapack { .. }
-			| MinecraftInstr::EnableDatapack { .. }
-			| MinecraftInstr::WorldBorderBuffer { .. }
-			| MinecraftInstr::WorldBorderDamage { .. }
-			| MinecraftInstr::WorldBorderWarningDistance { .. }
-			| MinecraftInstr::WorldBorderWarningTime { .. }
-			| MinecraftInstr::DefaultGamemode { .. }
-			| MinecraftInstr::ListPlayerUUIDs
-			| MinecraftInstr::ListPlayers
-			| MinecraftInstr::ListScoreboardObjectives
-			| MinecraftInstr::SetGameruleBool { .. }
-			| MinecraftInstr::SetGameruleInt { .. }
-			| MinecraftInstr::SetTime { .. }
-			| MinecraftInstr::SetTimePreset { .. }
-			| MinecraftInstr::SetDifficulty { .. } => {
-				depend_repetition = false;
-			}
-			MinecraftInstr::AddTag { target, .. }
-			| MinecraftInstr::Kill { target }
-			| MinecraftInstr::AddXP { target, .. }
-			| MinecraftInstr::SetXP { target, .. }
-			| MinecraftInstr::GetXP { target, .. }
-			| MinecraftInstr::ListTags { target }
-			| MinecraftInstr::Tell { target, .. }
-			| MinecraftInstr::RemoveTag { target, .. }
-			| MinecraftInstr::GiveEffect { target, .. }
-			| MinecraftInstr::AddAttributeModifier { target, .. }
-			| MinecraftInstr::GetAttribute { target, .. }
-			| MinecraftInstr::GetAttributeBase { target, .. }
-			| MinecraftInstr::GetAttributeModifier { target, .. }
-			| MinecraftInstr::SetAttributeBase { target, .. }
-			| MinecraftInstr::RemoveAttributeModifier { target, .. }
-			| MinecraftInstr::ClearEffect { target, .. }
-			| MinecraftInstr::Enchant { target, .. }
-			| MinecraftInstr::RideDismount { target }
-			| MinecraftInstr::SetGamemode { target, .. }
-			| MinecraftInstr::GiveItem { target, .. } => {
-				target.append_set(set);
-			}
-			MinecraftInstr::BanPlayers { targets, .. }
-			| MinecraftInstr::PardonPlayers { targets }
-			| MinecraftInstr::ClearItems { targets, .. }
-			| MinecraftInstr::Op { targets }
-			| MinecraftInstr::Deop { targets }
-			| MinecraftInstr::Kick { targets, .. }
-			| MinecraftInstr::WhitelistAdd { targets }
-			| MinecraftInstr::WhitelistRemove { targets } => {
-				for tgt in targets {
-					tgt.append_set(set);
-				}
-			}
-			MinecraftInstr::SetSpawnpoint { targets, pos, .. } => {
-				for tgt in targets {
-					tgt.append_set(set);
+		let meta = self.get_meta();
+		for context in meta.reads {
+			set.insert(Dependency(context.clone()));
+		}
+		for target in self.get_targets() {
+			target.append_set(set);
+		}
+		for operand in self.get_operands() {
+			operand.append_set(set);
+		}
+
+		// Scores and NBT in text components are resolved in the context of the command
+		for text in self.get_text_components() {
+			for contents in text.get_all_contents() {
+				match contents {
+					TextComponentContents::Score(val) => val.append_set(set),
+					TextComponentContents::Nbt(val) => val.append_set(set),
+					TextComponentContents::Text(..) => {}
 				}
-				pos.append_set(set);
-			}
-			MinecraftInstr::SetWorldSpawn { pos, .. }
-			| MinecraftInstr::WorldBorderCenter { pos } => {
-				pos.append_set(set);
-			}
-			MinecraftInstr::SummonEntity { pos, .. } => {
-				pos.append_set(set);
-			}
-			MinecraftInstr::TeleportToLocation { source, dest } => {
-				source.append_set(set);
-				dest.append_set(set);
-			}
-			MinecraftInstr::TeleportWithRotation {
-				source,
-				dest,
-				rotation,
-			} => {
-				source.append_set(set);
-				dest.append_set(set);
-				rotation.append_set(set);
-			}
-			MinecraftInstr::TeleportFacingEntity {
-				source,
-				dest,
-				facing,
-			} => {
-				source.append_set(set);
-				dest.append_set(set);
-				facing.append_set(set);
-			}
-			MinecraftInstr::TeleportFacingLocation {
-				source,
-				dest,
-				facing,
-			} => {
-				source.append_set(set);
-				dest.append_set(set);
-				facing.append_set(set);
-			}
-			MinecraftInstr::Locate { .. } => {
-				set.insert(Dependency(ModifierContext::Position));
-			}
-			MinecraftInstr::Fill { data } => {
-				data.start.append_set(set);
-				data.end.append_set(set);
-			}
-			MinecraftInstr::FillBiome { data } => {
-				data.start.append_set(set);
-				data.end.append_set(set);
-			}
-			MinecraftInstr::ItemModify { location, .. }
-			| MinecraftInstr::ItemReplaceWith { location, .. } => {
-				location.append_set(set);
-			}
-			MinecraftInstr::ItemReplaceFrom { dest, source, .. } => {
-				dest.append_set(set);
-				source.append_set(set);
-			}
-			MinecraftInstr::Clone { data } => {
-				data.start.append_set(set);
-				data.end.append_set(set);
-				data.destination.append_set(set);
-				set.insert(Dependency(ModifierContext::Position));
-			}
-			MinecraftInstr::SpreadPlayers { center, target, .. } => {
-				center.append_set(set);
-				target.append_set(set);
-			}
-			MinecraftInstr::TeamMessage { .. }
-			| MinecraftInstr::Me { .. }
-			| MinecraftInstr::SpectateStop
-			| MinecraftInstr::TriggerAdd { .. }
-			| MinecraftInstr::TriggerSet { .. } => {
-				set.insert(Dependency(ModifierContext::Executor));
-			}
-			MinecraftInstr::LootGive {
-				player: target,
-				source,
-			}
-			| MinecraftInstr::LootReplaceEntity { target, source, .. } => {
-				target.append_set(set);
-				source.append_set(set);
-			}
-			MinecraftInstr::LootInsert { pos, source }
-			| MinecraftInstr::LootReplaceBlock { pos, source, .. } => {
-				pos.append_set(set);
-				source.append_set(set);
-			}
-			MinecraftInstr::LootSpawn { pos, source } => {
-				pos.append_set(set);
-				source.append_set(set);
-			}
-			MinecraftInstr::PlaceFeature { pos, .. }
-			| MinecraftInstr::PlaceJigsaw { pos, .. }
-			| MinecraftInstr::PlaceStructure { pos, .. } => {
-				pos.append_set(set);
-			}
-			MinecraftInstr::PlaySound { target, pos, .. } => {
-				target.append_set(set);
-				pos.append_set(set);
-			}
-			MinecraftInstr::RideMount { target, vehicle }
-			| MinecraftInstr::Spectate {
-				target,
-				spectator: vehicle,
-			}
-			| MinecraftInstr::TeleportToEntity {
-				source: target,
-				dest: vehicle,
-			} => {
-				target.append_set(set);
-				vehicle.append_set(set);
-			}
-			MinecraftInstr::SetBlock { data } => {
-				data.pos.append_set(set);
-			}
-			MinecraftInstr::SetWeather { .. } => {
-				set.insert(Dependency(ModifierContext::Dimension));
 			}
 		}
 
-		if depend_repetition {
+		// Running an instruction that is idempotent again doesn't change anything
+		if !meta.idempotent {
 			set.insert(Dependency(ModifierContext::Repetition));
 		}
 	}
 }
 
+impl GetSetOwned<Dependency> for InstrOperand<'_> {
+	fn append_set(&self, set: &mut FxHashSet<Dependency>) {
+		match self {
+			Self::Entity(tgt) => tgt.append_set(set),
+			Self::Pos(pos) => pos.append_set(set),
+			Self::BlockPos(pos) => pos.append_set(set),
+			Self::Pos2D(pos) => pos.append_set(set),
+			Self::Item(loc) => loc.append_set(set),
+			Self::Loot(source) => source.append_set(set),
+			Self::Time(..) => {}
+		}
+	}
+}
+
 impl GetSetOwned<Dependency> for ScoreValue {
 	fn append_set(&self, set: &mut FxHashSet<Dependency>) {
 		match self {
@@ -0,0 +1,140 @@
+use crate::common::mc::entity::{SelectorParameter, SelectorType, TargetSelector};
+use crate::common::mc::modifier::{IfModCondition, Modifier, StoreModLocation};
+use crate::common::mc::EntityTarget;
+use crate::lir::{LIRInstrKind, LIRInstruction};
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// Uses what is known about the executor under an `as` modifier to make later
+/// selectors in the same command cheaper. Parameters of `@s` that the `as` selector
+/// already guarantees are removed, and `if entity` checks that the executor is
+/// guaranteed to pass are changed to check `@s`
+pub struct NarrowSelectorsPass;
+
+impl Pass for NarrowSelectorsPass {
+	fn get_name(&self) -> &'static str {
+		"narrow_selectors"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl LIRPass for NarrowSelectorsPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		for func in data.lir.functions.values_mut() {
+			for instr in &mut func.block.contents {
+				narrow_instr(instr, None);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Narrows the selectors in an instruction, given the selector that the executor
+/// is known to match when it starts. Returns true if anything was changed
+pub(super) fn narrow_instr(
+	instr: &mut LIRInstruction,
+	mut executor: Option<TargetSelector>,
+) -> bool {
+	let mut changed = false;
+	for modifier in &mut instr.modifiers {
+		match modifier {
+			Modifier::As(tgt)
+			| Modifier::At(tgt)
+			| Modifier::PositionedAs(tgt)
+			| Modifier::RotatedAs(tgt)
+			| Modifier::FacingEntity(tgt, ..) => changed |= narrow_this(tgt, &executor),
+			Modifier::If { condition, .. } => {
+				if let IfModCondition::Entity(tgt) = condition.as_mut() {
+					changed |= narrow_this(tgt, &executor);
+					changed |= narrow_to_this(tgt, &executor);
+				}
+			}
+			Modifier::StoreResult(StoreModLocation::Score(score))
+			| Modifier::StoreSuccess(StoreModLocation::Score(score)) => {
+				changed |= narrow_this(&mut score.holder, &executor)
+			}
+			_ => {}
+		}
+		track_executor(modifier, &mut executor);
+	}
+
+	if let LIRInstrKind::MC(mc_instr) = &mut instr.kind {
+		for tgt in mc_instr.get_targets_mut() {
+			changed |= narrow_this(tgt, &executor);
+		}
+	}
+
+	changed
+}
+
+/// Updates the selector that the current executor was selected with after a modifier
+pub(super) fn track_executor(modifier: &Modifier, executor: &mut Option<TargetSelector>) {
+	match modifier {
+		// Filtering the executor keeps what we know about it
+		Modifier::As(EntityTarget::Selector(sel)) if sel.selector == SelectorType::This => {}
+		Modifier::As(EntityTarget::Selector(sel)) => *executor = Some(sel.clone()),
+		Modifier::As(EntityTarget::Player(..)) | Modifier::On(..) | Modifier::Summon(..) => {
+			*executor = None
+		}
+		// Functions can change the executor's tags and scores
+		Modifier::If { condition, .. }
+			if matches!(condition.as_ref(), IfModCondition::Function(..)) =>
+		{
+			*executor = None
+		}
+		_ => {}
+	}
+}
+
+/// Removes the parameters of `@s` that the executor is known to match
+fn narrow_this(tgt: &mut EntityTarget, executor: &Option<TargetSelector>) -> bool {
+	let (EntityTarget::Selector(sel), Some(executor)) = (tgt, executor) else {
+		return false;
+	};
+	if sel.selector != SelectorType::This {
+		return false;
+	}
+	let len = sel.params.len();
+	sel.params.retain(|param| !is_implied(param, executor));
+	sel.params.len() != len
+}
+
+/// Replaces a selector that the executor is known to be selected by with `@s`
+fn narrow_to_this(tgt: &mut EntityTarget, executor: &Option<TargetSelector>) -> bool {
+	let (EntityTarget::Selector(sel), Some(executor)) = (&tgt, executor) else {
+		return false;
+	};
+	// @e doesn't select dead players, so it might not include an executor from @a
+	let contains_executor = match sel.selector {
+		SelectorType::AllEntities => executor.selector == SelectorType::AllEntities,
+		SelectorType::AllPlayers => executor.is_player_type(),
+		_ => false,
+	};
+	if contains_executor && sel.params.iter().all(|x| is_implied(x, executor)) {
+		*tgt = EntityTarget::Selector(TargetSelector::new(SelectorType::This));
+		true
+	} else {
+		false
+	}
+}
+
+/// Checks if every entity that a selector finds is guaranteed to match a parameter
+fn is_implied(param: &SelectorParameter, sel: &TargetSelector) -> bool {
+	match param {
+		SelectorParameter::Type { ty, invert: false } if ty == "player" && sel.is_player_type() => {
+			true
+		}
+		// These can change depending on where or when they are checked
+		SelectorParameter::Distance { .. }
+		| SelectorParameter::Origin { .. }
+		| SelectorParameter::Volume { .. }
+		| SelectorParameter::Predicate { .. }
+		| SelectorParameter::Limit(..)
+		| SelectorParameter::Sort(..) => false,
+		_ => sel.params.iter().any(|x| x.is_value_eq(param)),
+	}
+}
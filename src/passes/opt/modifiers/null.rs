@@ -1,13 +1,11 @@
 use intset::GrowSet;
-use rustc_hash::FxHashSet;
-
 use crate::common::mc::modifier::Modifier;
 use crate::lir::LIRInstrKind;
 use crate::passes::{LIRPass, LIRPassData, Pass};
 use crate::project::{OptimizationLevel, ProjectSettings};
 use crate::util::{remove_indices, GetSetOwned};
 
-use super::{Dependency, Modified, ModifierContext};
+use super::{get_overwritten_contexts, Dependency, Modified, ModifierContext};
 
 pub struct NullModifiersPass;
 
@@ -35,7 +33,9 @@ impl LIRPass for NullModifiersPass {
 				}
 				let mut mods_to_remove = GrowSet::with_capacity(modifier_count);
 
-				let mut mod_deps = FxHashSet::default();
+				// The contexts that are needed by the instruction or the modifiers
+				// after the current one
+				let mut live_deps = instr_deps;
 				'outer: for (i, modifier) in instr.modifiers.iter_mut().enumerate().rev() {
 					// If neither the instruction nor any of the modifiers after this one
 					// needs the context of this modifier, then it can be removed
@@ -47,10 +47,10 @@ impl LIRPass for NullModifiersPass {
 					if !modifier.has_extra_side_efects() {
 						// Can't do if either since that can break the chain
 						if !matches!(modifier, Modifier::If { .. }) {
-							if !this_mod_ctx.iter().any(|x| {
-								let dep = x.clone().to_dep();
-								instr_deps.contains(&dep) | mod_deps.contains(&dep)
-							}) {
+							if !this_mod_ctx
+								.iter()
+								.any(|x| live_deps.contains(&x.clone().to_dep()))
+							{
 								mods_to_remove.add(i);
 								dont_push_deps = true;
 							}
@@ -58,9 +58,26 @@ impl LIRPass for NullModifiersPass {
 					}
 
 					if !dont_push_deps {
-						<Modifier as GetSetOwned<Dependency>>::append_set(modifier, &mut mod_deps);
+						// The dimension change scales the position, so it only depends on
+						// the previous position and dimension if the position is still used
+						let position_live =
+							live_deps.contains(&Dependency(ModifierContext::Position));
+						for ctx in get_overwritten_contexts(modifier) {
+							live_deps.remove(&Dependency(ctx.clone()));
+						}
+						if let Modifier::In(..) = modifier {
+							if position_live {
+								live_deps.insert(Dependency(ModifierContext::Position));
+								live_deps.insert(Dependency(ModifierContext::Dimension));
+							}
+						} else {
+							<Modifier as GetSetOwned<Dependency>>::append_set(
+								modifier,
+								&mut live_deps,
+							);
+						}
 					}
-					if mod_deps.contains(&Dependency(ModifierContext::Everything)) {
+					if live_deps.contains(&Dependency(ModifierContext::Everything)) {
 						break 'outer;
 					}
 				}
@@ -1,6 +1,7 @@
 use crate::common::mc::modifier::{IfModCondition, IfScoreCondition, IfScoreRangeEnd, Modifier};
 use crate::common::val::ScoreValue;
 use crate::lir::LIRInstrKind;
+use crate::passes::opt::modifiers::is_overworld;
 use crate::passes::{LIRPass, LIRPassData, Pass};
 use crate::project::{OptimizationLevel, ProjectSettings};
 use crate::util::{remove_indices, HashSetEmptyTracker};
@@ -41,6 +42,10 @@ impl LIRPass for SimplifyModifiersPass {
 						Modifier::As(target) if target.is_blank_this() => {
 							mods_to_remove.insert(i);
 						}
+						// Switching to the overworld does nothing if we are always there
+						Modifier::In(dim) if data.proj.single_dimension && is_overworld(dim) => {
+							mods_to_remove.insert(i);
+						}
 						Modifier::If { condition, negate } => {
 							let result = optimize_condition(condition);
 							match result {
@@ -0,0 +1,190 @@
+use anyhow::bail;
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::function::FunctionInterface;
+use crate::common::mc::entity::{SelectorParameter, SelectorType, TargetSelector};
+use crate::common::mc::instr::MinecraftInstr;
+use crate::common::mc::modifier::{IfModCondition, Modifier};
+use crate::common::ResourceLocation;
+use crate::lir::{LIRFunction, LIRInstrKind, LIRInstruction, LIR};
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+use super::narrow::{narrow_instr, track_executor};
+
+/// The most instructions that a function can have to be copied into variants
+const MAX_VARIANT_SIZE: usize = 32;
+/// The most variants that are made of a single function
+const MAX_VARIANTS: usize = 4;
+
+/// Specializes functions for the executors that they are called with. A function that
+/// is only called with one kind of executor has its selectors narrowed in place, and
+/// small functions called with different kinds of executors get a narrowed copy
+/// for each one
+pub struct ContextVariantsPass;
+
+impl Pass for ContextVariantsPass {
+	fn get_name(&self) -> &'static str {
+		"context_variants"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::Full
+	}
+}
+
+impl LIRPass for ContextVariantsPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		let contexts = get_call_contexts(data.lir);
+
+		let mut new_funcs = Vec::new();
+		let mut variants = Vec::new();
+		for (func_id, contexts) in contexts.into_iter().sorted_by(|x, y| x.0.cmp(&y.0)) {
+			// Calls to functions outside of the pack can't be specialized
+			let Some(func) = data.lir.functions.get_mut(&func_id) else {
+				continue;
+			};
+
+			let mut distinct: Vec<TargetSelector> = Vec::new();
+			for context in contexts.iter().flatten() {
+				if !distinct.iter().any(|x| x.is_value_eq(context)) {
+					distinct.push(context.clone());
+				}
+			}
+
+			// Functions that are always called in the same context don't need to be copied
+			if !func.interface.annotations.is_root()
+				&& distinct.len() == 1
+				&& contexts.iter().all(Option::is_some)
+			{
+				for instr in &mut func.block.contents {
+					narrow_instr(instr, Some(distinct[0].clone()));
+				}
+				continue;
+			}
+
+			if func.block.contents.len() > MAX_VARIANT_SIZE {
+				continue;
+			}
+			for (i, context) in distinct.into_iter().take(MAX_VARIANTS).enumerate() {
+				let mut block = func.block.clone();
+				let mut changed = false;
+				for instr in &mut block.contents {
+					changed |= narrow_instr(instr, Some(context.clone()));
+				}
+				// Don't make copies that don't improve anything
+				if !changed {
+					continue;
+				}
+
+				let id: ResourceLocation = format!("{func_id}_ctx_{i}").into();
+				let mut interface =
+					FunctionInterface::with_signature(id.clone(), func.interface.sig.clone());
+				interface.annotations.generated = true;
+				new_funcs.push(LIRFunction {
+					interface,
+					block,
+					// The copy uses the same registers as the original
+					parent: Some(func.parent.clone().unwrap_or(func_id.clone())),
+				});
+				variants.push((func_id.clone(), context, id));
+			}
+		}
+
+		if variants.is_empty() {
+			return Ok(());
+		}
+		for func in new_funcs {
+			if data.lir.functions.contains_key(&func.interface.id) {
+				bail!(
+					"Function {} generated for a context variant conflicts with an existing function",
+					func.interface.id
+				);
+			}
+			data.lir.functions.insert(func.interface.id.clone(), func);
+		}
+
+		// Point the calls at their variants
+		for func in data.lir.functions.values_mut() {
+			for instr in &mut func.block.contents {
+				let context = get_instr_context(instr);
+				let LIRInstrKind::Call(callee, ..) = &mut instr.kind else {
+					continue;
+				};
+				let Some(context) = context else {
+					continue;
+				};
+				let variant = variants
+					.iter()
+					.find(|(id, other, _)| id == callee && other.is_value_eq(&context));
+				if let Some((.., variant)) = variant {
+					*callee = variant.clone();
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Gets the context of every call to each function. Calls without a known context
+/// are stored as None
+fn get_call_contexts(lir: &LIR) -> FxHashMap<ResourceLocation, Vec<Option<TargetSelector>>> {
+	let mut out: FxHashMap<_, Vec<_>> = FxHashMap::default();
+	for func in lir.functions.values() {
+		for instr in &func.block.contents {
+			for modifier in &instr.modifiers {
+				if let Modifier::If { condition, .. } = modifier {
+					if let IfModCondition::Function(fun, ..) = condition.as_ref() {
+						out.entry(fun.clone()).or_default().push(None);
+					}
+				}
+			}
+			match &instr.kind {
+				LIRInstrKind::Call(fun, ..) => {
+					out.entry(fun.clone())
+						.or_default()
+						.push(get_instr_context(instr));
+				}
+				LIRInstrKind::CallWith(fun, ..)
+				| LIRInstrKind::MC(MinecraftInstr::ScheduleFunction { function: fun, .. }) => {
+					out.entry(fun.clone()).or_default().push(None);
+				}
+				_ => {}
+			}
+		}
+	}
+
+	out
+}
+
+/// Gets what is known about the executor that an instruction is run as, that will
+/// stay true for the whole function that it calls
+fn get_instr_context(instr: &LIRInstruction) -> Option<TargetSelector> {
+	let mut executor = None;
+	for modifier in &instr.modifiers {
+		track_executor(modifier, &mut executor);
+	}
+	let executor = executor?;
+
+	// The type of an entity is the only thing that can't change while the function runs
+	let mut params: Vec<_> = executor
+		.params
+		.iter()
+		.filter(|x| matches!(x, SelectorParameter::Type { .. }))
+		.cloned()
+		.collect();
+	let player = SelectorParameter::Type {
+		ty: "player".into(),
+		invert: false,
+	};
+	if executor.is_player_type() && !params.contains(&player) {
+		params.push(player);
+	}
+	if params.is_empty() {
+		return None;
+	}
+
+	Some(TargetSelector::with_params(SelectorType::This, params))
+}
@@ -0,0 +1,139 @@
+use anyhow::bail;
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::common::function::FunctionInterface;
+use crate::common::val::MutableScoreValue;
+use crate::common::{RegisterList, ResourceLocation};
+use crate::lir::{LIRBlock, LIRFunction, LIRInstrKind, LIRInstruction};
+use crate::passes::{LIRPass, LIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+
+/// The fewest resets at the end of a function that are moved into a shared cleanup function
+const MIN_SHARED_RESETS: usize = 3;
+
+/// Tidies up the resets at the ends of functions. Repeated resets are removed, and
+/// when the same resets end multiple functions they are moved into one cleanup function
+/// that each of them calls
+pub struct ResetBatchingPass;
+
+impl Pass for ResetBatchingPass {
+	fn get_name(&self) -> &'static str {
+		"reset_batching"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl LIRPass for ResetBatchingPass {
+	fn run_pass(&mut self, data: &mut LIRPassData) -> anyhow::Result<()> {
+		for func in data.lir.functions.values_mut() {
+			dedup_trailing_resets(&mut func.block);
+		}
+
+		if data.proj.op_level < OptimizationLevel::Full {
+			return Ok(());
+		}
+
+		// Group the functions by the resets that they end with. Registers are different in
+		// every function, so only resets of global scores can be shared
+		let mut groups: FxHashMap<String, Vec<ResourceLocation>> = FxHashMap::default();
+		for (func_id, func) in &data.lir.functions {
+			let start = get_trailing_resets_start(&func.block);
+			let resets = &func.block.contents[start..];
+			if start == 0
+				|| resets.len() < MIN_SHARED_RESETS
+				|| resets.iter().any(|x| !is_global_reset(x))
+			{
+				continue;
+			}
+			let key = format!("{resets:?}");
+			groups.entry(key).or_default().push(func_id.clone());
+		}
+
+		let groups = groups
+			.into_values()
+			.filter(|x| x.len() > 1)
+			.map(|x| x.into_iter().sorted().collect::<Vec<_>>())
+			.sorted();
+		for func_ids in groups {
+			let id: ResourceLocation = format!("{}_resets", func_ids[0]).into();
+			if data.lir.functions.contains_key(&id) {
+				bail!(
+					"Function {id} generated for shared resets conflicts with an existing function"
+				);
+			}
+
+			let mut block = None;
+			for func_id in &func_ids {
+				let func = data
+					.lir
+					.functions
+					.get_mut(func_id)
+					.expect("Function should exist");
+				let start = get_trailing_resets_start(&func.block);
+				let resets = func.block.contents.split_off(start);
+				block.get_or_insert_with(|| {
+					let mut block = LIRBlock::new(RegisterList::default());
+					block.contents = resets;
+					block
+				});
+				func.block
+					.contents
+					.push(LIRInstruction::new(LIRInstrKind::Call(
+						id.clone(),
+						Vec::new(),
+					)));
+			}
+
+			let mut interface = FunctionInterface::new(id.clone());
+			interface.annotations.generated = true;
+			let func = LIRFunction {
+				interface,
+				block: block.expect("Group should not be empty"),
+				parent: None,
+			};
+			data.lir.functions.insert(id, func);
+		}
+
+		Ok(())
+	}
+}
+
+/// Removes resets at the end of a block that reset something that was already reset
+fn dedup_trailing_resets(block: &mut LIRBlock) {
+	let start = get_trailing_resets_start(block);
+	let mut seen: Vec<MutableScoreValue> = Vec::new();
+	let mut i = start;
+	while i < block.contents.len() {
+		let LIRInstrKind::ResetScore(val) = &block.contents[i].kind else {
+			unreachable!("Trailing instructions should be resets");
+		};
+		if seen.iter().any(|x| x.is_value_eq(val)) {
+			block.contents.remove(i);
+		} else {
+			seen.push(val.clone());
+			i += 1;
+		}
+	}
+}
+
+/// Gets the index of the first of the unconditional resets that end a block
+fn get_trailing_resets_start(block: &LIRBlock) -> usize {
+	let count = block
+		.contents
+		.iter()
+		.rev()
+		.take_while(|x| matches!(x.kind, LIRInstrKind::ResetScore(..)) && x.modifiers.is_empty())
+		.count();
+	block.contents.len() - count
+}
+
+fn is_global_reset(instr: &LIRInstruction) -> bool {
+	matches!(
+		instr.kind,
+		LIRInstrKind::ResetScore(MutableScoreValue::Score(..))
+	)
+}
@@ -102,11 +102,12 @@ fn run_iter(block: &mut LIRBlock, instrs_to_remove: &mut HashSetEmptyTracker<usi
 	}
 
 	let repl_mutated = block.contents.iter_mut().fold(false, |out, instr| {
-		// Instructions to replace
+		// Instructions to replace. The lowest score is skipped in the ones that make
+		// a constant positive, since it has no positive counterpart
 		let kind_repl = match &instr.kind {
 			// Add by negative is sub by positive
 			LIRInstrKind::AddScore(left, ScoreValue::Constant(score))
-				if score.get_i32().is_negative() =>
+				if score.get_i32().is_negative() && score.get_i32() != i32::MIN =>
 			{
 				Some(LIRInstrKind::SubScore(
 					left.clone(),
@@ -115,7 +116,7 @@ fn run_iter(block: &mut LIRBlock, instrs_to_remove: &mut HashSetEmptyTracker<usi
 			}
 			// Sub by negative is add by positive
 			LIRInstrKind::SubScore(left, ScoreValue::Constant(score))
-				if score.get_i32().is_negative() =>
+				if score.get_i32().is_negative() && score.get_i32() != i32::MIN =>
 			{
 				Some(LIRInstrKind::AddScore(
 					left.clone(),
@@ -124,7 +125,7 @@ fn run_iter(block: &mut LIRBlock, instrs_to_remove: &mut HashSetEmptyTracker<usi
 			}
 			// Mod by negative is same as mod by positive
 			LIRInstrKind::ModScore(left, ScoreValue::Constant(score))
-				if score.get_i32().is_negative() =>
+				if score.get_i32().is_negative() && score.get_i32() != i32::MIN =>
 			{
 				Some(LIRInstrKind::ModScore(
 					left.clone(),
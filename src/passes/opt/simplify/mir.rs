@@ -47,6 +47,29 @@ fn simplify_block(block: &mut MIRBlock, is_root: bool) -> RunAgain {
 		}
 	}
 	remove_indices(&mut block.contents, &instrs_to_remove);
+	out.merge(combine_time_adds(block));
+	out
+}
+
+/// Combines runs of constant time additions into a single addition
+fn combine_time_adds(block: &mut MIRBlock) -> RunAgain {
+	let mut out = RunAgain::new();
+	let mut i = 1;
+	while i < block.contents.len() {
+		let (prev, next) = block.contents.split_at_mut(i);
+		if let (
+			MIRInstrKind::MC(MinecraftInstr::AddTime { time: first }),
+			MIRInstrKind::MC(MinecraftInstr::AddTime { time: second }),
+		) = (&mut prev[i - 1].kind, &next[0].kind)
+		{
+			*first = first.clone() + second.clone();
+			block.contents.remove(i);
+			out.yes();
+		} else {
+			i += 1;
+		}
+	}
+
 	out
 }
 
@@ -133,17 +156,8 @@ fn run_iter(
 			} if comp.is_empty() => true,
 			// Get instructions without their results stored don't do anything, as long as we are
 			// at the root
-			MIRInstrKind::GetConst { .. }
-			| MIRInstrKind::Get { .. }
-			| MIRInstrKind::MC(
-				MinecraftInstr::GetAttribute { .. }
-				| MinecraftInstr::GetAttributeBase { .. }
-				| MinecraftInstr::GetAttributeModifier { .. }
-				| MinecraftInstr::GetDifficulty
-				| MinecraftInstr::GetGamerule { .. }
-				| MinecraftInstr::GetTime { .. }
-				| MinecraftInstr::GetXP { .. },
-			) => is_root,
+			MIRInstrKind::GetConst { .. } | MIRInstrKind::Get { .. } => is_root,
+			MIRInstrKind::MC(instr) if instr.get_meta().is_pure() => is_root,
 			// Empty block inside of an if can be removed
 			MIRInstrKind::If { body, .. } => body.contents.is_empty(),
 			MIRInstrKind::NoOp => true,
@@ -404,7 +418,7 @@ fn run_iter(
 			},
 			MIRInstrKind::Modify {
 				modifier:
-					MIRModifier::StoreResult(StoreModLocation::Local(Local::Reg(left), left_scale)),
+					MIRModifier::StoreResult(StoreModLocation::Local(Local::Reg(left), _, left_scale)),
 				body,
 			} => {
 				match body.contents.only().map(|x| &x.kind) {
@@ -427,7 +441,7 @@ fn run_iter(
 			}
 			MIRInstrKind::Modify {
 				modifier:
-					MIRModifier::StoreSuccess(StoreModLocation::Local(Local::Reg(left), left_scale)),
+					MIRModifier::StoreSuccess(StoreModLocation::Local(Local::Reg(left), _, left_scale)),
 				body,
 			} if left_scale == &1.0 => {
 				match body.contents.only().map(|x| &x.kind) {
@@ -11,6 +11,8 @@ use crate::util::remove_indices;
 
 use intset::GrowSet;
 
+pub mod ranges;
+
 /// Does optimizations on values based on type information
 pub struct TypeBasedOptimizationPass;
 
@@ -0,0 +1,455 @@
+use rustc_hash::FxHashMap;
+
+use crate::common::block::Block;
+use crate::common::condition::Condition;
+use crate::common::reg::GetUsedRegs;
+use crate::common::ty::{DataType, DataTypeContents, ScoreType, ScoreTypeContents};
+use crate::common::val::{MutableValue, Value};
+use crate::common::{DeclareBinding, Identifier, Register, RegisterList};
+use crate::mir::{MIRBlock, MIRInstrKind};
+use crate::passes::{MIRPass, MIRPassData, Pass};
+use crate::project::{OptimizationLevel, ProjectSettings};
+use crate::util::remove_indices;
+
+/// Tracks the ranges that score registers are known to be in, from their types,
+/// the constants assigned to them, and the operations on them. Uses the ranges to turn
+/// comparisons of values that are 0 or 1 into bool conditions, to remove operations that
+/// can't change a value, and to replace logic operations with cheaper ones
+pub struct ScoreRangesPass;
+
+impl Pass for ScoreRangesPass {
+	fn get_name(&self) -> &'static str {
+		"score_ranges"
+	}
+
+	fn should_run(&self, proj: &ProjectSettings) -> bool {
+		proj.op_level >= OptimizationLevel::More
+	}
+}
+
+impl MIRPass for ScoreRangesPass {
+	fn run_pass(&mut self, data: &mut MIRPassData) -> anyhow::Result<()> {
+		for func in data.mir.functions.values_mut() {
+			let mut regs = RegisterList::default();
+			collect_regs(&func.block, &mut regs);
+			run_block(&mut func.block, &mut Ranges::default(), &regs);
+		}
+
+		Ok(())
+	}
+}
+
+/// The ranges of registers that are different from the ones their types allow
+type Ranges = FxHashMap<Identifier, ScoreRange>;
+
+/// An inclusive range of score values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScoreRange {
+	min: i64,
+	max: i64,
+}
+
+impl ScoreRange {
+	const FULL: Self = Self {
+		min: i32::MIN as i64,
+		max: i32::MAX as i64,
+	};
+	const BOOL: Self = Self { min: 0, max: 1 };
+
+	/// Creates a range, which is the full range if the values could overflow a score
+	fn new(min: i64, max: i64) -> Self {
+		if min < Self::FULL.min || max > Self::FULL.max {
+			Self::FULL
+		} else {
+			Self { min, max }
+		}
+	}
+
+	fn single(val: i32) -> Self {
+		Self::new(val.into(), val.into())
+	}
+
+	/// Gets the range of values that a type can hold
+	fn for_type(ty: &DataType) -> Self {
+		match ty {
+			DataType::Score(ScoreType::Bool) => Self::BOOL,
+			DataType::Score(ScoreType::Flags(bits)) => Self::new(0, (1i64 << bits) - 1),
+			DataType::Score(ScoreType::Enum(count)) => Self::new(0, *count as i64 - 1),
+			_ => Self::FULL,
+		}
+	}
+
+	fn is_bool(&self) -> bool {
+		self.min >= 0 && self.max <= 1
+	}
+
+	fn contains(&self, val: i64) -> bool {
+		(self.min..=self.max).contains(&val)
+	}
+
+	fn is_single(&self, val: i64) -> bool {
+		self.min == val && self.max == val
+	}
+}
+
+fn collect_regs(block: &MIRBlock, regs: &mut RegisterList) {
+	for instr in &block.contents {
+		if let MIRInstrKind::Declare { left, ty } = &instr.kind {
+			regs.insert(
+				left.clone(),
+				Register {
+					id: left.clone(),
+					ty: ty.clone(),
+				},
+			);
+		}
+		for body in instr.kind.get_bodies() {
+			collect_regs(body, regs);
+		}
+	}
+}
+
+fn run_block(block: &mut MIRBlock, ranges: &mut Ranges, regs: &RegisterList) {
+	let mut instrs_to_remove = block.get_index_set();
+	for (i, instr) in block.contents.iter_mut().enumerate() {
+		if let Some(condition) = instr.kind.get_condition_mut() {
+			simplify_condition(condition, ranges, regs);
+		}
+
+		match simplify_instr(&instr.kind, ranges, regs) {
+			Simplified::Remove => {
+				instrs_to_remove.add(i);
+				continue;
+			}
+			Simplified::Replace(kind) => instr.kind = *kind,
+			Simplified::Keep => {}
+		}
+
+		if !instr.kind.get_bodies().is_empty() {
+			// This includes the registers written by the instruction itself, like
+			// the target of a store modifier
+			let changed_regs: Vec<_> = instr.kind.get_used_regs().into_iter().cloned().collect();
+			// Modifiers like as can run the body more than once, so the ranges
+			// at the start of the body aren't known for the registers it changes
+			let repeats = matches!(instr.kind, MIRInstrKind::Modify { .. });
+			for body in instr.kind.get_bodies_mut() {
+				let mut body_ranges = ranges.clone();
+				if repeats {
+					for reg in &changed_regs {
+						body_ranges.remove(reg);
+					}
+				}
+				run_block(body, &mut body_ranges, regs);
+			}
+			for reg in &changed_regs {
+				ranges.remove(reg);
+			}
+			continue;
+		}
+
+		update_ranges(&instr.kind, ranges, regs);
+	}
+	remove_indices(&mut block.contents, &instrs_to_remove);
+}
+
+enum Simplified {
+	Keep,
+	Remove,
+	Replace(Box<MIRInstrKind>),
+}
+
+fn simplify_instr(kind: &MIRInstrKind, ranges: &Ranges, regs: &RegisterList) -> Simplified {
+	match kind {
+		MIRInstrKind::Abs {
+			val: MutableValue::Reg(reg),
+		} if get_reg_range(reg, ranges, regs).min >= 0 => Simplified::Remove,
+		MIRInstrKind::Min {
+			left: MutableValue::Reg(reg),
+			right,
+		} if get_reg_range(reg, ranges, regs).max <= get_range(right, ranges, regs).min => {
+			Simplified::Remove
+		}
+		MIRInstrKind::Max {
+			left: MutableValue::Reg(reg),
+			right,
+		} if get_reg_range(reg, ranges, regs).min >= get_range(right, ranges, regs).max => {
+			Simplified::Remove
+		}
+		// Or sets the left side to 1 if the right side is 1
+		MIRInstrKind::Or {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let left = get_reg_range(reg, ranges, regs);
+			if left.is_single(1) || !get_range(right, ranges, regs).contains(1) {
+				Simplified::Remove
+			} else {
+				Simplified::Keep
+			}
+		}
+		// Xor is a subtraction and a multiplication, which is only the same as the
+		// cheaper operations when both sides are 0 or 1
+		MIRInstrKind::Xor {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let left = get_reg_range(reg, ranges, regs);
+			let right_range = get_range(right, ranges, regs);
+			if !left.is_bool() || !right_range.is_bool() {
+				Simplified::Keep
+			} else if right_range.is_single(0) {
+				Simplified::Remove
+			} else if right_range.is_single(1) {
+				Simplified::Replace(Box::new(MIRInstrKind::Not {
+					value: MutableValue::Reg(reg.clone()),
+				}))
+			} else if left.is_single(0) {
+				Simplified::Replace(Box::new(MIRInstrKind::Assign {
+					left: MutableValue::Reg(reg.clone()),
+					right: DeclareBinding::Value(right.clone()),
+				}))
+			} else {
+				Simplified::Keep
+			}
+		}
+		// Not checks if the value is 0 with an execute command, but setting a
+		// constant is cheaper when it can't be 0
+		MIRInstrKind::Not {
+			value: MutableValue::Reg(reg),
+		} if !get_reg_range(reg, ranges, regs).contains(0) => {
+			Simplified::Replace(Box::new(MIRInstrKind::Assign {
+				left: MutableValue::Reg(reg.clone()),
+				right: DeclareBinding::Value(score_constant(0)),
+			}))
+		}
+		_ => Simplified::Keep,
+	}
+}
+
+/// Turns comparisons of values that are 0 or 1 with constants into bool conditions.
+/// Both kinds of conditions fail for scores that aren't set, so this doesn't need to
+/// know if the register is set
+fn simplify_condition(condition: &mut Condition, ranges: &Ranges, regs: &RegisterList) {
+	match condition {
+		Condition::Not(condition) => return simplify_condition(condition, ranges, regs),
+		Condition::And(l, r) | Condition::Or(l, r) | Condition::Xor(l, r) => {
+			simplify_condition(l, ranges, regs);
+			simplify_condition(r, ranges, regs);
+			return;
+		}
+		_ => {}
+	}
+
+	let Some((reg, true_values)) = get_true_values(condition) else {
+		return;
+	};
+	if !get_reg_range(reg, ranges, regs).is_bool() {
+		return;
+	}
+	let check = match (true_values.contains(0), true_values.contains(1)) {
+		(false, true) => true,
+		(true, false) => false,
+		_ => return,
+	};
+
+	let val = Value::Mutable(MutableValue::Reg(reg.clone()));
+	*condition = if check {
+		Condition::Bool(val)
+	} else {
+		Condition::NotBool(val)
+	};
+}
+
+/// Gets the register that a condition compares to a constant, and the values
+/// of the register that the condition is true for
+fn get_true_values(condition: &Condition) -> Option<(&Identifier, ScoreRange)> {
+	let (l, r) = match condition {
+		Condition::Equal(l, r)
+		| Condition::GreaterThan(l, r)
+		| Condition::GreaterThanOrEqual(l, r)
+		| Condition::LessThan(l, r)
+		| Condition::LessThanOrEqual(l, r) => (l, r),
+		_ => return None,
+	};
+	let (reg, val, flipped) = match (l, r) {
+		(Value::Mutable(MutableValue::Reg(reg)), Value::Constant(val)) => (reg, val, false),
+		(Value::Constant(val), Value::Mutable(MutableValue::Reg(reg))) => (reg, val, true),
+		_ => return None,
+	};
+	let val = val.try_get_i32()? as i64;
+
+	let (min, max) = match (condition, flipped) {
+		(Condition::Equal(..), _) => (val, val),
+		(Condition::GreaterThan(..), false) | (Condition::LessThan(..), true) => {
+			(val + 1, i64::MAX)
+		}
+		(Condition::GreaterThanOrEqual(..), false) | (Condition::LessThanOrEqual(..), true) => {
+			(val, i64::MAX)
+		}
+		(Condition::LessThan(..), false) | (Condition::GreaterThan(..), true) => {
+			(i64::MIN, val - 1)
+		}
+		_ => (i64::MIN, val),
+	};
+
+	Some((reg, ScoreRange { min, max }))
+}
+
+/// Updates the ranges with the values that an instruction writes
+fn update_ranges(kind: &MIRInstrKind, ranges: &mut Ranges, regs: &RegisterList) {
+	let operands = |reg, right| {
+		(
+			get_reg_range(reg, ranges, regs),
+			get_range(right, ranges, regs),
+		)
+	};
+	let (reg, range) = match kind {
+		MIRInstrKind::Assign {
+			left: MutableValue::Reg(reg),
+			right: DeclareBinding::Value(right),
+		} => (reg, get_range(right, ranges, regs)),
+		MIRInstrKind::Assign {
+			left: MutableValue::Reg(reg),
+			right: DeclareBinding::Condition(..),
+		}
+		| MIRInstrKind::Not {
+			value: MutableValue::Reg(reg),
+		} => (reg, ScoreRange::BOOL),
+		MIRInstrKind::Add {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let (l, r) = operands(reg, right);
+			(reg, ScoreRange::new(l.min + r.min, l.max + r.max))
+		}
+		MIRInstrKind::Sub {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let (l, r) = operands(reg, right);
+			(reg, ScoreRange::new(l.min - r.max, l.max - r.min))
+		}
+		MIRInstrKind::Mul {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let (l, r) = operands(reg, right);
+			let products = [l.min * r.min, l.min * r.max, l.max * r.min, l.max * r.max];
+			let min = products.iter().min().copied().unwrap_or_default();
+			let max = products.iter().max().copied().unwrap_or_default();
+			(reg, ScoreRange::new(min, max))
+		}
+		MIRInstrKind::Mod {
+			left: MutableValue::Reg(reg),
+			right: Value::Constant(DataTypeContents::Score(r)),
+		} if r.get_i32() > 0 => (reg, ScoreRange::new(0, r.get_i32() as i64 - 1)),
+		MIRInstrKind::Min {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let (l, r) = operands(reg, right);
+			(reg, ScoreRange::new(l.min.min(r.min), l.max.min(r.max)))
+		}
+		MIRInstrKind::Max {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let (l, r) = operands(reg, right);
+			(reg, ScoreRange::new(l.min.max(r.min), l.max.max(r.max)))
+		}
+		MIRInstrKind::Abs {
+			val: MutableValue::Reg(reg),
+		} => {
+			let l = get_reg_range(reg, ranges, regs);
+			let range = if l.min >= 0 {
+				l
+			} else if l.min == ScoreRange::FULL.min {
+				// The smallest score stays negative
+				ScoreRange::FULL
+			} else if l.max <= 0 {
+				ScoreRange::new(-l.max, -l.min)
+			} else {
+				ScoreRange::new(0, l.max.max(-l.min))
+			};
+			(reg, range)
+		}
+		MIRInstrKind::And {
+			left: MutableValue::Reg(reg),
+			right,
+		}
+		| MIRInstrKind::Or {
+			left: MutableValue::Reg(reg),
+			right,
+		}
+		| MIRInstrKind::Xor {
+			left: MutableValue::Reg(reg),
+			right,
+		} => {
+			let (l, r) = operands(reg, right);
+			let range = if l.is_bool() && r.is_bool() {
+				ScoreRange::BOOL
+			} else {
+				ScoreRange::FULL
+			};
+			(reg, range)
+		}
+		MIRInstrKind::Rand {
+			left: MutableValue::Reg(reg),
+			min,
+			max,
+		} => (reg, ScoreRange::new((*min).into(), (*max).into())),
+		MIRInstrKind::Swap {
+			left: MutableValue::Reg(l),
+			right: MutableValue::Reg(r),
+		} => {
+			let (l_range, r_range) = (
+				get_reg_range(l, ranges, regs),
+				get_reg_range(r, ranges, regs),
+			);
+			set_range(l, r_range, ranges, regs);
+			set_range(r, l_range, ranges, regs);
+			return;
+		}
+		other => {
+			for reg in other.get_used_regs() {
+				ranges.remove(reg);
+			}
+			return;
+		}
+	};
+
+	set_range(reg, range, ranges, regs);
+}
+
+/// Sets the range of a register, which only needs to be stored if it isn't the
+/// range of its type
+fn set_range(reg: &Identifier, range: ScoreRange, ranges: &mut Ranges, regs: &RegisterList) {
+	let ty_range = regs.get(reg).map(|x| ScoreRange::for_type(&x.ty));
+	if range == ty_range.unwrap_or(ScoreRange::FULL) {
+		ranges.remove(reg);
+	} else {
+		ranges.insert(reg.clone(), range);
+	}
+}
+
+fn get_reg_range(reg: &Identifier, ranges: &Ranges, regs: &RegisterList) -> ScoreRange {
+	if let Some(range) = ranges.get(reg) {
+		*range
+	} else if let Some(reg) = regs.get(reg) {
+		ScoreRange::for_type(&reg.ty)
+	} else {
+		ScoreRange::FULL
+	}
+}
+
+fn get_range(val: &Value, ranges: &Ranges, regs: &RegisterList) -> ScoreRange {
+	match val {
+		Value::Constant(DataTypeContents::Score(score)) => ScoreRange::single(score.get_i32()),
+		Value::Mutable(MutableValue::Reg(reg)) => get_reg_range(reg, ranges, regs),
+		_ => ScoreRange::FULL,
+	}
+}
+
+fn score_constant(val: i32) -> Value {
+	Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(val)))
+}
@@ -1,4 +1,15 @@
+use std::path::PathBuf;
+
+use rustc_hash::FxHashMap;
+
+use crate::common::function::InvocationFrequency;
+use crate::common::lint::{Lint, LintLevel};
+use crate::common::mc::version::MinecraftVersion;
+use crate::common::snippet::Snippet;
+use crate::common::ResourceLocation;
+use crate::output::datapack::{AuxiliaryFile, DirectoryLayout, PackFilter, TextFormat};
 use crate::output::strip::StripMode;
+use crate::output::text::LIT_OBJECTIVE;
 
 /// Settings for a generated project
 #[derive(Clone)]
@@ -6,6 +17,54 @@ pub struct ProjectSettings {
 	pub(crate) name: String,
 	pub(crate) strip_mode: StripMode,
 	pub(crate) op_level: OptimizationLevel,
+	pub(crate) target_version: MinecraftVersion,
+	pub(crate) directory_layout: Option<DirectoryLayout>,
+	/// Assume that all of the code in the pack runs in the overworld
+	pub(crate) single_dimension: bool,
+	/// The literal length above which constant compound arguments, and constants
+	/// shared between functions, are stored once instead of being copied everywhere
+	pub(crate) const_arg_threshold: usize,
+	/// The sizes of functions that are inlined into the functions that call them
+	pub(crate) inline_thresholds: InlineThresholds,
+	/// A guard or prefix that is put in front of every generated command
+	pub(crate) command_prefix: Option<CommandPrefix>,
+	/// Feature flags that the pack enables in its pack.mcmeta
+	pub(crate) features: Vec<String>,
+	/// Patterns for files from lower packs that this pack filters out
+	pub(crate) filter: Vec<PackFilter>,
+	/// Split fill and clone commands over regions that are too large for
+	/// one command into multiple commands instead of erroring
+	pub(crate) chunk_large_regions: bool,
+	/// Levels for lints that override their default of warning
+	pub(crate) lint_levels: FxHashMap<Lint, LintLevel>,
+	/// Start the fake players for registers and literals with a # so that
+	/// they are hidden from the sidebar, instead of a %
+	pub(crate) hidden_fake_players: bool,
+	/// Reset the fake players left over from older versions of the pack when it loads
+	pub(crate) migrate_fake_players: bool,
+	/// The description in the pack.mcmeta, which is the name of the project if it isn't set
+	pub(crate) description: Option<String>,
+	/// A function in another pack that creates the score literals, which is
+	/// called when this pack loads instead of creating them in this pack
+	pub(crate) literal_pool: Option<ResourceLocation>,
+	/// The objective that score literals are put in, instead of the default one
+	pub(crate) literal_objective: Option<String>,
+	/// Handwritten functions that are copied into the pack
+	pub(crate) snippets: Vec<Snippet>,
+	/// Functions that keep their names when stripping, since other packs call them
+	pub(crate) stable_names: Vec<ResourceLocation>,
+	/// Files like the pack icon and license that are copied into the root of the
+	/// pack, by their path in the pack
+	pub(crate) auxiliary_files: Vec<(String, AuxiliaryFile)>,
+	/// A namespace that the functions generated by the compiler are put in, so that
+	/// they don't clutter the namespaces of the project
+	pub(crate) internal_namespace: Option<String>,
+	/// Create the objectives that scores in the pack use in the init function
+	pub(crate) create_objectives: bool,
+	/// Add the init function to the minecraft:load tag
+	pub(crate) load_init: bool,
+	/// The line endings and encoding of the text files in the output pack
+	pub(crate) text_format: TextFormat,
 }
 
 impl ProjectSettings {
@@ -14,8 +73,52 @@ impl ProjectSettings {
 			name,
 			strip_mode: StripMode::None,
 			op_level: OptimizationLevel::Basic,
+			target_version: MinecraftVersion::LATEST,
+			directory_layout: None,
+			single_dimension: false,
+			const_arg_threshold: 64,
+			inline_thresholds: InlineThresholds::default(),
+			command_prefix: None,
+			features: Vec::new(),
+			filter: Vec::new(),
+			chunk_large_regions: false,
+			lint_levels: FxHashMap::default(),
+			hidden_fake_players: true,
+			migrate_fake_players: false,
+			description: None,
+			literal_pool: None,
+			literal_objective: None,
+			snippets: Vec::new(),
+			stable_names: Vec::new(),
+			auxiliary_files: Vec::new(),
+			internal_namespace: None,
+			create_objectives: false,
+			load_init: true,
+			text_format: TextFormat::default(),
 		}
 	}
+
+	/// Gets the directory layout of the output pack, which is picked from the
+	/// target version unless it was overridden
+	pub fn get_directory_layout(&self) -> DirectoryLayout {
+		self.directory_layout
+			.unwrap_or_else(|| DirectoryLayout::for_version(self.target_version))
+	}
+
+	/// Gets the description of the output pack
+	pub fn get_description(&self) -> &str {
+		self.description.as_deref().unwrap_or(&self.name)
+	}
+
+	/// Gets the objective that score literals are put in
+	pub fn get_literal_objective(&self) -> &str {
+		self.literal_objective.as_deref().unwrap_or(LIT_OBJECTIVE)
+	}
+
+	/// Gets how severe a lint is in this project
+	pub fn get_lint_level(&self, lint: Lint) -> LintLevel {
+		self.lint_levels.get(&lint).copied().unwrap_or_default()
+	}
 }
 
 pub struct ProjectSettingsBuilder {
@@ -29,6 +132,11 @@ impl ProjectSettingsBuilder {
 		}
 	}
 
+	/// Starts from existing settings so that some of them can be changed
+	pub fn from_settings(settings: ProjectSettings) -> Self {
+		Self { settings }
+	}
+
 	pub fn build(self) -> ProjectSettings {
 		self.settings
 	}
@@ -42,6 +150,228 @@ impl ProjectSettingsBuilder {
 		self.settings.op_level = level;
 		self
 	}
+
+	pub fn target_version(mut self, version: MinecraftVersion) -> Self {
+		self.settings.target_version = version;
+		self
+	}
+
+	/// Overrides the directory layout that would be picked from the target version
+	pub fn directory_layout(mut self, layout: DirectoryLayout) -> Self {
+		self.settings.directory_layout = Some(layout);
+		self
+	}
+
+	/// Assume that all of the code in the pack runs in the overworld, which
+	/// allows modifiers that switch to the overworld to be removed
+	pub fn single_dimension(mut self, single_dimension: bool) -> Self {
+		self.settings.single_dimension = single_dimension;
+		self
+	}
+
+	/// Sets the literal length above which constant compound arguments, and
+	/// constants shared between functions, are stored once when the pack loads
+	/// instead of being copied everywhere
+	pub fn const_arg_threshold(mut self, threshold: usize) -> Self {
+		self.settings.const_arg_threshold = threshold;
+		self
+	}
+
+	/// Sets how large functions that are called from more than one place can be
+	/// while still being inlined, depending on how often their callers run
+	pub fn inline_thresholds(mut self, thresholds: InlineThresholds) -> Self {
+		self.settings.inline_thresholds = thresholds;
+		self
+	}
+
+	/// Puts a guard or prefix in front of every generated command, such
+	/// as a score check that acts as a kill switch for the whole pack
+	pub fn command_prefix(mut self, prefix: CommandPrefix) -> Self {
+		self.settings.command_prefix = Some(prefix);
+		self
+	}
+
+	/// Enables a feature flag like `update_1_21` in the pack.mcmeta, which
+	/// allows the pack to use content that is gated behind it
+	pub fn feature(mut self, feature: &str) -> Self {
+		self.settings.features.push(feature.to_string());
+		self
+	}
+
+	/// Adds a block to the filter section of the pack.mcmeta, which hides
+	/// matching files from packs that are loaded below this one
+	pub fn filter(mut self, filter: PackFilter) -> Self {
+		self.settings.filter.push(filter);
+		self
+	}
+
+	/// Splits fill and clone commands with constant regions that are larger than
+	/// Minecraft allows into multiple commands. When this is off, those commands
+	/// are an error instead
+	pub fn chunk_large_regions(mut self, chunk: bool) -> Self {
+		self.settings.chunk_large_regions = chunk;
+		self
+	}
+
+	/// Sets how severe a lint is. Lints can still be allowed on
+	/// individual functions and instructions
+	pub fn lint_level(mut self, lint: Lint, level: LintLevel) -> Self {
+		self.settings.lint_levels.insert(lint, level);
+		self
+	}
+
+	/// Sets whether the fake players for registers and literals start with a #,
+	/// which hides them from the sidebar. When this is off, they start with a %
+	/// like in older versions of the compiler
+	pub fn hidden_fake_players(mut self, hidden: bool) -> Self {
+		self.settings.hidden_fake_players = hidden;
+		self
+	}
+
+	/// Resets all of the scores in the internal objectives when the pack loads,
+	/// which clears out fake players from older versions of the pack that used
+	/// a different naming scheme
+	pub fn migrate_fake_players(mut self, migrate: bool) -> Self {
+		self.settings.migrate_fake_players = migrate;
+		self
+	}
+
+	/// Sets the description that is shown for the pack in the game
+	pub fn description(mut self, description: &str) -> Self {
+		self.settings.description = Some(description.to_string());
+		self
+	}
+
+	/// Calls a function from another pack, such as the shared library pack of a
+	/// workspace, to create the score literals instead of creating them in this pack
+	pub fn literal_pool(mut self, function: &str) -> Self {
+		self.settings.literal_pool = Some(function.into());
+		self
+	}
+
+	/// Sets the objective that score literals are put in. Packs that share a literal
+	/// pool need to use the same objective as it
+	pub fn literal_objective(mut self, objective: &str) -> Self {
+		self.settings.literal_objective = Some(objective.to_string());
+		self
+	}
+
+	/// Adds a handwritten function to the pack
+	pub fn snippet(mut self, snippet: Snippet) -> Self {
+		self.settings.snippets.push(snippet);
+		self
+	}
+
+	/// Keeps the name of a function when stripping, so that other packs can call it
+	pub fn stable_name(mut self, function: &str) -> Self {
+		self.settings.stable_names.push(function.into());
+		self
+	}
+
+	/// Copies an image into the pack as the pack.png that is shown in the game
+	pub fn pack_icon(self, path: impl Into<PathBuf>) -> Self {
+		self.auxiliary_file("pack.png", AuxiliaryFile::Path(path.into()))
+	}
+
+	/// Copies a license file into the pack, keeping its file name
+	pub fn license(self, path: impl Into<PathBuf>) -> Self {
+		self.copied_file(path.into(), "LICENSE")
+	}
+
+	/// Copies a readme file into the pack, keeping its file name
+	pub fn readme(self, path: impl Into<PathBuf>) -> Self {
+		self.copied_file(path.into(), "README.md")
+	}
+
+	/// Adds a file to a path relative to the root of the pack
+	pub fn auxiliary_file(mut self, path: &str, file: AuxiliaryFile) -> Self {
+		self.settings.auxiliary_files.push((path.to_string(), file));
+		self
+	}
+
+	/// Puts the functions that the compiler generates, like the bodies of ifs and
+	/// loops, in a separate namespace
+	pub fn internal_namespace(mut self, namespace: &str) -> Self {
+		self.settings.internal_namespace = Some(namespace.to_string());
+		self
+	}
+
+	/// Creates the objectives that scores in the pack are in when the pack loads,
+	/// unless the pack creates them itself. Objectives are created as dummies
+	pub fn create_objectives(mut self, create: bool) -> Self {
+		self.settings.create_objectives = create;
+		self
+	}
+
+	/// Sets whether the init function, which creates the objectives and constants
+	/// that the pack uses, is run from the minecraft:load tag. When this is off, the
+	/// init function has to be called by something else before the pack is used
+	pub fn load_init(mut self, load: bool) -> Self {
+		self.settings.load_init = load;
+		self
+	}
+
+	/// Sets the line endings, trailing newline, and byte order mark of the text
+	/// files in the output pack
+	pub fn text_format(mut self, format: TextFormat) -> Self {
+		self.settings.text_format = format;
+		self
+	}
+
+	fn copied_file(self, path: PathBuf, default_name: &str) -> Self {
+		let name = path.file_name().map(|x| x.to_string_lossy().to_string());
+		let name = name.unwrap_or_else(|| default_name.to_string());
+		self.auxiliary_file(&name, AuxiliaryFile::Path(path))
+	}
+}
+
+/// The largest cost of a function that is inlined into each of its callers, based on
+/// how often the caller runs. A function with one caller is always inlined, since
+/// the original is removed afterwards
+#[derive(Clone, Copy, Debug)]
+pub struct InlineThresholds {
+	/// For callers that run rarely
+	pub cold: f32,
+	/// For callers without a known frequency
+	pub normal: f32,
+	/// For callers that run every tick or on an interval
+	pub hot: f32,
+}
+
+impl InlineThresholds {
+	/// Gets the threshold for a caller that runs at a frequency
+	pub fn get(&self, freq: Option<InvocationFrequency>) -> f32 {
+		match freq {
+			Some(InvocationFrequency::Rare) => self.cold,
+			None => self.normal,
+			Some(InvocationFrequency::Tick | InvocationFrequency::Interval(..)) => self.hot,
+		}
+	}
+}
+
+impl Default for InlineThresholds {
+	fn default() -> Self {
+		Self {
+			cold: 4.0,
+			normal: 16.0,
+			hot: 64.0,
+		}
+	}
+}
+
+/// Something that is put in front of every generated command
+#[derive(Clone, Debug)]
+pub enum CommandPrefix {
+	/// Only runs commands when a score matches a value, using
+	/// `execute if score <holder> <objective> matches <value>`
+	ScoreGuard {
+		holder: String,
+		objective: String,
+		value: i32,
+	},
+	/// Text that is put in front of every command as-is, which
+	/// should end with `run ` if it is an execute command
+	Custom(String),
 }
 
 /// Different optimization levels that can be used
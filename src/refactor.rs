@@ -0,0 +1,321 @@
+use std::cell::Cell;
+
+use anyhow::{bail, Context};
+
+use crate::common::condition::Condition;
+use crate::common::mc::instr::MinecraftInstr;
+use crate::common::{DeclareBinding, Identifier, ResourceLocation};
+use crate::ir::{Block, InstrKind, IR};
+
+impl IR {
+	/// Renames a function, updating every call, scheduled call, and function
+	/// condition that refers to it, as well as commands that name it
+	pub fn rename_function(
+		&mut self,
+		old: &ResourceLocation,
+		new: ResourceLocation,
+	) -> anyhow::Result<()> {
+		if self.functions.contains_key(&new) {
+			bail!("Function {new} already exists");
+		}
+		let mut func = self
+			.functions
+			.remove(old)
+			.with_context(|| format!("Function {old} does not exist"))?;
+		func.interface.id = new.clone();
+		self.functions.insert(new.clone(), func);
+
+		let replace = |id: &mut ResourceLocation| {
+			if id == old {
+				*id = new.clone();
+			}
+		};
+		for func in self.functions.values_mut() {
+			for_each_instr_mut(&mut func.block, &mut |kind| match kind {
+				InstrKind::Call { call } => replace(&mut call.function),
+				InstrKind::CallExtern { func } | InstrKind::CallWith { func, .. } => replace(func),
+				InstrKind::MC(MinecraftInstr::ScheduleFunction { function, .. })
+				| InstrKind::MC(MinecraftInstr::ScheduleClear { function }) => replace(function),
+				InstrKind::SpreadOverTicks {
+					on_complete: Some(func),
+					..
+				} => replace(func),
+				InstrKind::Command { command }
+				| InstrKind::InterpolatedCommand { command }
+				| InstrKind::MacroCommand { command } => {
+					*command = replace_words(command, old, &new);
+				}
+				other => {
+					if let Some(condition) = get_condition_mut(other) {
+						replace_function_conditions(condition, &replace);
+					}
+				}
+			});
+		}
+
+		Ok(())
+	}
+
+	/// Renames a register in a function, including where commands interpolate it
+	pub fn rename_register(
+		&mut self,
+		func: &ResourceLocation,
+		old: &Identifier,
+		new: Identifier,
+	) -> anyhow::Result<()> {
+		let func = self
+			.functions
+			.get_mut(func)
+			.with_context(|| format!("Function {func} does not exist"))?;
+
+		let conflicts = Cell::new(false);
+		for_each_instr_mut(&mut func.block, &mut |kind| {
+			replace_regs(kind, &|reg| conflicts.set(conflicts.get() || *reg == new));
+		});
+		if conflicts.get() {
+			bail!("Register {new} already exists");
+		}
+
+		let old_interp = format!("$({old})");
+		let new_interp = format!("$({new})");
+		for_each_instr_mut(&mut func.block, &mut |kind| {
+			replace_regs(kind, &|reg| {
+				if reg == old {
+					*reg = new.clone();
+				}
+			});
+			if let InstrKind::InterpolatedCommand { command } = kind {
+				*command = command.replace(&old_interp, &new_interp);
+			}
+		});
+
+		Ok(())
+	}
+}
+
+/// Runs a function on every instruction in a block and the bodies inside of it
+fn for_each_instr_mut(block: &mut Block, f: &mut impl FnMut(&mut InstrKind)) {
+	for instr in &mut block.contents {
+		f(&mut instr.kind);
+		for body in instr.kind.get_bodies_mut() {
+			for_each_instr_mut(body, f);
+		}
+	}
+}
+
+fn get_condition_mut(kind: &mut InstrKind) -> Option<&mut Condition> {
+	match kind {
+		InstrKind::If { condition, .. }
+		| InstrKind::IfElse { condition, .. }
+		| InstrKind::Loop { condition, .. }
+		| InstrKind::Assert { condition, .. } => Some(condition),
+		InstrKind::Declare {
+			right: DeclareBinding::Condition(condition),
+			..
+		} => Some(condition),
+		_ => None,
+	}
+}
+
+fn replace_function_conditions(condition: &mut Condition, f: &impl Fn(&mut ResourceLocation)) {
+	match condition {
+		Condition::Function(func) => f(func),
+		Condition::Not(condition) => replace_function_conditions(condition, f),
+		Condition::And(l, r) | Condition::Or(l, r) | Condition::Xor(l, r) => {
+			replace_function_conditions(l, f);
+			replace_function_conditions(r, f);
+		}
+		_ => {}
+	}
+}
+
+/// Replaces the words in a command that are exactly the old ID
+fn replace_words(command: &str, old: &str, new: &str) -> String {
+	command
+		.split(' ')
+		.map(|word| if word == old { new } else { word })
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+/// Runs a function on the registers used directly by an instruction, not counting its bodies
+fn replace_regs<F: Fn(&mut Identifier)>(kind: &mut InstrKind, f: &F) {
+	let regs = match kind {
+		InstrKind::Declare { left, right, .. } => {
+			f(left);
+			match right {
+				DeclareBinding::Null => Vec::new(),
+				DeclareBinding::Value(val) | DeclareBinding::Cast(_, val, _) => {
+					val.get_used_regs_mut()
+				}
+				DeclareBinding::Condition(condition) => condition.iter_used_regs_mut().collect(),
+			}
+		}
+		InstrKind::DeclareCriteria { left, .. } => vec![left],
+		InstrKind::Assign { left, right }
+		| InstrKind::Cast { left, right, .. }
+		| InstrKind::Add { left, right }
+		| InstrKind::Sub { left, right }
+		| InstrKind::Mul { left, right }
+		| InstrKind::Div { left, right }
+		| InstrKind::Mod { left, right }
+		| InstrKind::Min { left, right }
+		| InstrKind::Max { left, right }
+		| InstrKind::BitAnd { left, right }
+		| InstrKind::BitOr { left, right }
+		| InstrKind::BitXor { left, right }
+		| InstrKind::Shl { left, right }
+		| InstrKind::Shr { left, right }
+		| InstrKind::UUIDToString { left, right }
+		| InstrKind::StringToUUID { left, right }
+		| InstrKind::Merge { left, right }
+		| InstrKind::Push { left, right }
+		| InstrKind::PushFront { left, right }
+		| InstrKind::Insert { left, right, .. }
+		| InstrKind::And { left, right }
+		| InstrKind::Or { left, right }
+		| InstrKind::Xor { left, right }
+		| InstrKind::SetFlag {
+			flags: left, right, ..
+		} => {
+			let mut regs = left.get_used_regs_mut();
+			regs.extend(right.get_used_regs_mut());
+			regs
+		}
+		InstrKind::GetFlag { left, flags, .. } | InstrKind::Swap { left, right: flags } => {
+			let mut regs = left.get_used_regs_mut();
+			regs.extend(flags.get_used_regs_mut());
+			regs
+		}
+		InstrKind::ToggleFlag { flags: val, .. }
+		| InstrKind::Remove { val }
+		| InstrKind::Abs { val }
+		| InstrKind::Pow { base: val, .. }
+		| InstrKind::Rand { left: val, .. }
+		| InstrKind::Get { value: val, .. }
+		| InstrKind::Not { value: val }
+		| InstrKind::Use { val }
+		| InstrKind::SummonAs {
+			uuid: Some(val), ..
+		} => val.get_used_regs_mut(),
+		InstrKind::If { condition, .. }
+		| InstrKind::IfElse { condition, .. }
+		| InstrKind::Loop { condition, .. }
+		| InstrKind::Assert { condition, .. } => condition.iter_used_regs_mut().collect(),
+		InstrKind::Call { call } => call.iter_used_regs_mut().collect(),
+		InstrKind::CallWith { args: value, .. }
+		| InstrKind::ReturnValue { value, .. }
+		| InstrKind::Return { value } => value.get_used_regs_mut(),
+		InstrKind::MC(instr) => instr.get_used_regs_mut(),
+		InstrKind::Modify { modifier, .. } => {
+			modifier.replace_regs(f);
+			Vec::new()
+		}
+		InstrKind::SpreadOverTicks { item, list, .. } => {
+			f(item);
+			list.get_used_regs_mut()
+		}
+		InstrKind::SummonAs { uuid: None, .. }
+		| InstrKind::CopyData { .. }
+		| InstrKind::CallExtern { .. }
+		| InstrKind::ReturnRun { .. }
+		| InstrKind::Command { .. }
+		| InstrKind::InterpolatedCommand { .. }
+		| InstrKind::MacroCommand { .. }
+		| InstrKind::Comment { .. }
+		| InstrKind::Atomic { .. }
+		| InstrKind::Label { .. }
+		| InstrKind::Goto { .. } => Vec::new(),
+	};
+	for reg in regs {
+		f(reg);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::parse::unparse::unparse_ir;
+	use crate::parse::Parser;
+
+	use super::*;
+
+	fn parse(text: &str) -> IR {
+		let mut parser = Parser::new();
+		parser.parse(text).expect("Failed to parse");
+		parser.finish()
+	}
+
+	#[test]
+	fn test_rename_function() {
+		let mut ir = parse(
+			r#"
+			"test:main" {
+				call "test:old";
+				if eq %x, 1s {
+					sch "test:old", 5t, replace;
+				};
+				cmd "function test:old";
+			}
+			"test:old" {
+				say "old";
+			}
+			"#,
+		);
+		let expected = parse(
+			r#"
+			"test:main" {
+				call "test:new";
+				if eq %x, 1s {
+					sch "test:new", 5t, replace;
+				};
+				cmd "function test:new";
+			}
+			"test:new" {
+				say "old";
+			}
+			"#,
+		);
+		ir.rename_function(&"test:old".into(), "test:new".into())
+			.expect("Failed to rename");
+		assert_eq!(unparse_ir(&ir).unwrap(), unparse_ir(&expected).unwrap());
+
+		assert!(ir
+			.rename_function(&"test:old".into(), "test:x".into())
+			.is_err());
+		assert!(ir
+			.rename_function(&"test:new".into(), "test:main".into())
+			.is_err());
+	}
+
+	#[test]
+	fn test_rename_register() {
+		let mut ir = parse(
+			r#"
+			"test:main" {
+				let x: score = val 5s;
+				if gt %x, 2s {
+					add %x, 1s;
+				};
+				icmd "say $(x)";
+			}
+			"#,
+		);
+		let expected = parse(
+			r#"
+			"test:main" {
+				let y: score = val 5s;
+				if gt %y, 2s {
+					add %y, 1s;
+				};
+				icmd "say $(y)";
+			}
+			"#,
+		);
+		let func: ResourceLocation = "test:main".into();
+		ir.rename_register(&func, &"x".into(), "y".into())
+			.expect("Failed to rename");
+		assert_eq!(unparse_ir(&ir).unwrap(), unparse_ir(&expected).unwrap());
+
+		assert!(ir.rename_register(&func, &"y".into(), "y".into()).is_err());
+	}
+}
@@ -0,0 +1,125 @@
+use anyhow::Context;
+
+use crate::common::ty::DataType;
+use crate::common::val::MutableValue;
+use crate::common::{DeclareBinding, Identifier};
+use crate::compile_instructions;
+use crate::ir::InstrKind;
+use crate::parse::parse_instrs;
+use crate::project::ProjectSettings;
+
+/// State for an interactive session that compiles instructions one line at a time
+pub struct Repl {
+	project: ProjectSettings,
+	/// Registers that have been declared so far, which are kept between lines
+	regs: Vec<(Identifier, DataType)>,
+}
+
+impl Repl {
+	pub fn new(project: ProjectSettings) -> Self {
+		Self {
+			project,
+			regs: Vec::new(),
+		}
+	}
+
+	/// Compiles a line of instructions, returning the generated commands
+	pub fn eval(&mut self, line: &str) -> anyhow::Result<Vec<String>> {
+		let instrs = parse_instrs(line).context("Failed to parse line")?;
+		let instrs: Vec<_> = instrs.into_iter().map(|x| x.kind).collect();
+
+		// Redeclare the existing registers without values so that
+		// they can be used without generating any commands. Registers get their
+		// slots in the order they are first used, so every register is used
+		// right after it is declared to keep its slot the same between lines
+		let mut body = Vec::new();
+		for (id, ty) in &self.regs {
+			body.push(InstrKind::Declare {
+				left: id.clone(),
+				ty: ty.clone(),
+				right: DeclareBinding::Null,
+			});
+			body.push(use_reg(id));
+		}
+		for instr in &instrs {
+			body.push(instr.clone());
+			if let InstrKind::Declare { left, .. } = instr {
+				body.push(use_reg(left));
+			}
+		}
+		let out = compile_instructions(body, &self.project).context("Failed to compile line")?;
+
+		// Only keep new registers once the line compiled successfully
+		for instr in instrs {
+			if let InstrKind::Declare { left, ty, .. } = instr {
+				if let Some(existing) = self.regs.iter_mut().find(|x| x.0 == left) {
+					existing.1 = ty;
+				} else {
+					self.regs.push((left, ty));
+				}
+			}
+		}
+
+		Ok(out)
+	}
+
+	/// Gets the registers that have been declared so far
+	pub fn get_regs(&self) -> &[(Identifier, DataType)] {
+		&self.regs
+	}
+}
+
+fn use_reg(id: &Identifier) -> InstrKind {
+	InstrKind::Use {
+		val: MutableValue::Reg(id.clone()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn get_reg_names(repl: &Repl) -> Vec<&str> {
+		repl.get_regs().iter().map(|x| x.0.as_ref()).collect()
+	}
+
+	#[test]
+	fn test_repl_persists_regs() {
+		let mut repl = Repl::new(ProjectSettings::new("test".into()));
+		let out = repl.eval("let x: score = val 5s;").unwrap();
+		assert_eq!(out, ["scoreboard players set #rdpc_single.0 _r 5"]);
+		let out = repl.eval("add %x, 2s;").unwrap();
+		assert_eq!(out, ["scoreboard players add #rdpc_single.0 _r 2"]);
+		let out = repl.eval("let y: score = val %x; say \"hi\";").unwrap();
+		assert_eq!(
+			out,
+			[
+				"scoreboard players operation #rdpc_single.1 _r = #rdpc_single.0 _r",
+				"say hi"
+			]
+		);
+		// Redeclaring a register keeps its slot
+		let out = repl.eval("let x: score = val 3s; add %y, %x;").unwrap();
+		assert_eq!(
+			out,
+			[
+				"scoreboard players set #rdpc_single.0 _r 3",
+				"scoreboard players operation #rdpc_single.1 _r += #rdpc_single.0 _r"
+			]
+		);
+		assert_eq!(get_reg_names(&repl), ["x", "y"]);
+	}
+
+	#[test]
+	fn test_repl_error() {
+		let mut repl = Repl::new(ProjectSettings::new("test".into()));
+		repl.eval("let x: score = val 5s;").unwrap();
+		assert!(repl.eval("let y: score = val 1s; add %z, 1s;").is_err());
+		assert!(repl.eval("add %x,").is_err());
+		// The failed lines don't declare anything and the session keeps working
+		assert_eq!(get_reg_names(&repl), ["x"]);
+		let out = repl.eval("sub %x, 1s;").unwrap();
+		assert_eq!(out, ["scoreboard players remove #rdpc_single.0 _r 1"]);
+		assert!(repl.eval("add %y, 1s;").is_err());
+	}
+}
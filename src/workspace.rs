@@ -0,0 +1,385 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+
+use crate::ir::IR;
+use crate::output::datapack::{Datapack, Function};
+use crate::output::gen_literals;
+use crate::package::{resolve_package, Lockfile, PackageDependency, LOCKFILE_NAME};
+use crate::parse::Parser;
+use crate::project::ProjectSettingsBuilder;
+use crate::{codegen_ir, CodegenIRSettings};
+
+/// The name of the pack that shared libraries are compiled into
+pub const COMMON_PACK_NAME: &str = "common";
+/// The name of the function in the common pack that creates the score literals
+/// for every pack in the workspace
+pub const LITERAL_POOL_NAME: &str = "dpc_literals";
+
+/// Config file for a workspace
+#[derive(Deserialize, Debug)]
+pub struct WorkspaceConfig {
+	#[serde(default)]
+	pub projects: Vec<MemberConfig>,
+	#[serde(default)]
+	pub libraries: Vec<MemberConfig>,
+	/// Library packages from outside of the workspace, which are
+	/// built into the common pack like the other libraries
+	#[serde(default)]
+	pub packages: Vec<PackageDependency>,
+}
+
+/// Config for a single project or library in a workspace
+#[derive(Deserialize, Debug)]
+pub struct MemberConfig {
+	pub name: String,
+	/// Source files, relative to the workspace config
+	pub sources: Vec<PathBuf>,
+	/// Names of the other members this one is allowed to call into
+	#[serde(default)]
+	pub dependencies: Vec<String>,
+}
+
+/// A set of projects and shared libraries that are built together
+pub struct Workspace {
+	members: FxHashMap<String, WorkspaceMember>,
+}
+
+struct WorkspaceMember {
+	ir: IR,
+	dependencies: Vec<String>,
+	is_library: bool,
+}
+
+impl Workspace {
+	pub fn new() -> Self {
+		Self {
+			members: FxHashMap::default(),
+		}
+	}
+
+	/// Loads a workspace from a config file, parsing all of the sources.
+	/// Packages are checked against the lockfile, which is created if it does not exist
+	pub fn load(path: &Path) -> anyhow::Result<Self> {
+		Self::load_with_lock(path, false)
+	}
+
+	/// Loads a workspace from a config file, optionally rewriting the lockfile
+	/// with the packages that were resolved
+	pub fn load_with_lock(path: &Path, update_lock: bool) -> anyhow::Result<Self> {
+		let contents = std::fs::read_to_string(path).context("Failed to read workspace config")?;
+		let config: WorkspaceConfig =
+			serde_json::from_str(&contents).context("Failed to parse workspace config")?;
+		let root = path.parent().unwrap_or(Path::new("."));
+
+		let mut out = Self::new();
+		for (member, is_library) in config
+			.projects
+			.into_iter()
+			.map(|x| (x, false))
+			.chain(config.libraries.into_iter().map(|x| (x, true)))
+		{
+			let mut parser = Parser::new();
+			for source in &member.sources {
+				let text = std::fs::read_to_string(root.join(source))
+					.with_context(|| format!("Failed to read source file {source:?}"))?;
+				parser
+					.parse(&text)
+					.with_context(|| format!("Failed to parse source file {source:?}"))?;
+			}
+			out.add_member(member.name, parser.finish(), member.dependencies, is_library)?;
+		}
+
+		let mut packages = Vec::new();
+		for dep in &config.packages {
+			let package = resolve_package(dep, root)
+				.with_context(|| format!("Failed to resolve package {}", dep.name))?;
+			packages.push(package);
+		}
+		let lock_path = root.join(LOCKFILE_NAME);
+		let lock = Lockfile::from_packages(packages.iter());
+		match Lockfile::load(&lock_path)? {
+			Some(existing) if !update_lock => existing.check(&lock)?,
+			_ => lock.save(&lock_path)?,
+		}
+		for package in packages {
+			out.add_library(package.name, package.ir, Vec::new())?;
+		}
+
+		Ok(out)
+	}
+
+	/// Adds a project, which is compiled into its own pack
+	pub fn add_project(
+		&mut self,
+		name: String,
+		ir: IR,
+		dependencies: Vec<String>,
+	) -> anyhow::Result<()> {
+		self.add_member(name, ir, dependencies, false)
+	}
+
+	/// Adds a shared library, which is compiled into the common pack
+	pub fn add_library(
+		&mut self,
+		name: String,
+		ir: IR,
+		dependencies: Vec<String>,
+	) -> anyhow::Result<()> {
+		self.add_member(name, ir, dependencies, true)
+	}
+
+	fn add_member(
+		&mut self,
+		name: String,
+		ir: IR,
+		dependencies: Vec<String>,
+		is_library: bool,
+	) -> anyhow::Result<()> {
+		if name == COMMON_PACK_NAME {
+			bail!("The name {COMMON_PACK_NAME} is reserved for the shared library pack");
+		}
+		if self.members.contains_key(&name) {
+			bail!("Workspace member {name} is defined more than once");
+		}
+		self.members.insert(
+			name,
+			WorkspaceMember {
+				ir,
+				dependencies,
+				is_library,
+			},
+		);
+		Ok(())
+	}
+
+	/// Checks the dependencies between members and the extern calls that cross between them
+	pub fn validate(&self) -> anyhow::Result<()> {
+		for (name, member) in &self.members {
+			for dep in &member.dependencies {
+				if !self.members.contains_key(dep) {
+					bail!("Workspace member {name} depends on unknown member {dep}");
+				}
+			}
+		}
+		self.check_cycles()?;
+
+		// Map every function to the member that defines it and whether it is public
+		let mut owners = FxHashMap::default();
+		for (name, member) in &self.members {
+			for (func_id, func) in &member.ir.functions {
+				let public = func.interface.annotations.public;
+				if let Some((other, ..)) = owners.insert(func_id.clone(), (name, public)) {
+					bail!("Function {func_id} is defined by both {other} and {name}");
+				}
+			}
+		}
+
+		for (name, member) in &self.members {
+			let funcs = member.ir.functions.values();
+			for call in funcs.flat_map(|x| x.block.get_callees()) {
+				// Calls to functions outside the workspace are left alone
+				let Some((owner, public)) = owners.get(call) else {
+					continue;
+				};
+				if *owner == name {
+					continue;
+				}
+				if !member.dependencies.contains(owner) {
+					bail!("Workspace member {name} calls {call} from {owner} without depending on it");
+				}
+				if !public {
+					bail!("Workspace member {name} calls private function {call} from {owner}");
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn check_cycles(&self) -> anyhow::Result<()> {
+		fn visit<'ws>(
+			name: &'ws str,
+			ws: &'ws Workspace,
+			stack: &mut Vec<&'ws str>,
+			done: &mut FxHashSet<&'ws str>,
+		) -> anyhow::Result<()> {
+			if done.contains(name) {
+				return Ok(());
+			}
+			if let Some(pos) = stack.iter().position(|x| *x == name) {
+				let cycle = stack[pos..].iter().chain(std::iter::once(&name)).join(" -> ");
+				bail!("Dependency cycle between workspace members: {cycle}");
+			}
+			stack.push(name);
+			for dep in &ws.members[name].dependencies {
+				visit(dep, ws, stack, done)?;
+			}
+			stack.pop();
+			done.insert(name);
+			Ok(())
+		}
+
+		let mut done = FxHashSet::default();
+		for name in self.members.keys().sorted() {
+			visit(name, self, &mut Vec::new(), &mut done)?;
+		}
+
+		Ok(())
+	}
+
+	/// Validates and builds the workspace. Each project gets its own pack, and
+	/// all of the libraries are compiled into a single common pack. The score literals
+	/// of every pack are created once by a function in the common pack
+	pub fn build(
+		self,
+		settings: CodegenIRSettings,
+	) -> anyhow::Result<FxHashMap<String, Datapack>> {
+		self.validate()?;
+
+		let pool_fn = format!("{COMMON_PACK_NAME}:{LITERAL_POOL_NAME}");
+		let mut literals = FxHashSet::default();
+		let mut out = FxHashMap::default();
+		let mut common = IR::new();
+		for (name, member) in self.members {
+			if member.is_library {
+				common.functions.extend(member.ir.functions);
+				common.storages.extend(member.ir.storages);
+			} else {
+				let project = ProjectSettingsBuilder::new(&name)
+					.literal_pool(&pool_fn)
+					.build();
+				let pack = codegen_ir(member.ir, &project, settings.clone())
+					.with_context(|| format!("Failed to build project {name}"))?;
+				literals.extend(pack.score_literals.iter().copied());
+				out.insert(name, pack);
+			}
+		}
+
+		if !common.functions.is_empty() || !literals.is_empty() {
+			let project = ProjectSettingsBuilder::new(COMMON_PACK_NAME)
+				.literal_pool(&pool_fn)
+				.build();
+			let mut pack = codegen_ir(common, &project, settings)
+				.context("Failed to build shared library pack")?;
+			literals.extend(pack.score_literals.iter().copied());
+			if !literals.is_empty() {
+				let mut pool = Function::new();
+				pool.contents = gen_literals(&project, literals.into_iter());
+				pack.functions.insert(pool_fn.into(), pool);
+			}
+			out.insert(COMMON_PACK_NAME.into(), pack);
+		}
+
+		Ok(out)
+	}
+}
+
+impl Default for Workspace {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use crate::common::ResourceLocation;
+
+	fn parse(text: &str) -> IR {
+		let mut parser = Parser::new();
+		parser.parse(text).expect("Failed to parse");
+		parser.finish()
+	}
+
+	#[test]
+	fn test_workspace_cycle() {
+		let mut ws = Workspace::new();
+		ws.add_project("a".into(), IR::new(), vec!["b".into()])
+			.unwrap();
+		ws.add_library("b".into(), IR::new(), vec!["a".into()])
+			.unwrap();
+		assert!(ws.validate().is_err());
+	}
+
+	#[test]
+	fn test_workspace_callx() {
+		let lib = parse(r#"@public "lib:util" { say "util"; } "lib:private" { say "private"; }"#);
+		let project = parse(r#""game:main" { callx "lib:util"; }"#);
+
+		let mut ws = Workspace::new();
+		ws.add_library("lib".into(), lib.clone(), Vec::new())
+			.unwrap();
+		ws.add_project("game".into(), project.clone(), Vec::new())
+			.unwrap();
+		assert!(ws.validate().is_err());
+
+		let private = parse(r#""game:other" { callx "lib:private"; }"#);
+		let mut ws = Workspace::new();
+		ws.add_library("lib".into(), lib.clone(), Vec::new())
+			.unwrap();
+		ws.add_project("game".into(), private, vec!["lib".into()])
+			.unwrap();
+		assert!(ws.validate().is_err());
+
+		let mut ws = Workspace::new();
+		ws.add_library("lib".into(), lib, Vec::new()).unwrap();
+		ws.add_project("game".into(), project, vec!["lib".into()])
+			.unwrap();
+		let packs = ws.build(CodegenIRSettings::new()).unwrap();
+		assert!(packs.contains_key("game"));
+		assert!(packs[COMMON_PACK_NAME]
+			.functions
+			.contains_key(&ResourceLocation::from("lib:util")));
+	}
+
+	#[test]
+	fn test_workspace_nested_calls() {
+		let lib = parse(r#""lib:private" { say "private"; }"#);
+		let callers = [
+			r#""game:main" { atomic { callx "lib:private"; }; }"#,
+			r#""game:main" { while eq %x, 1s { callx "lib:private"; }; }"#,
+			r#""game:main" { callw "lib:private", {}; }"#,
+			r#""game:main" { sch "lib:private", 5t, replace; }"#,
+		];
+		for caller in callers {
+			let mut ws = Workspace::new();
+			ws.add_library("lib".into(), lib.clone(), Vec::new())
+				.unwrap();
+			ws.add_project("game".into(), parse(caller), vec!["lib".into()])
+				.unwrap();
+			assert!(ws.validate().is_err(), "{caller}");
+		}
+	}
+
+	#[test]
+	fn test_workspace_literal_pool() {
+		let a = parse(r#""a:main" { let x: score = val 1s; mul %x, 7s; }"#);
+		let b = parse(r#""b:main" { let x: score = val 1s; mul %x, 7s; mul %x, 9s; }"#);
+
+		let mut ws = Workspace::new();
+		ws.add_project("a".into(), a, Vec::new()).unwrap();
+		ws.add_project("b".into(), b, Vec::new()).unwrap();
+		let packs = ws.build(CodegenIRSettings::new()).unwrap();
+
+		let pool_fn = format!("{COMMON_PACK_NAME}:{LITERAL_POOL_NAME}");
+		let pool = &packs[COMMON_PACK_NAME].functions[&ResourceLocation::from(pool_fn.as_str())];
+		assert_eq!(
+			pool.contents,
+			vec![
+				"scoreboard objectives add _l dummy",
+				"scoreboard players set #l7 _l 7",
+				"scoreboard players set #l9 _l 9",
+			]
+		);
+		for name in ["a", "b"] {
+			let init = &packs[name].functions[&ResourceLocation::from(format!("{name}:dpc_init"))];
+			assert!(init.contents.contains(&format!("function {pool_fn}")));
+			assert!(!init.contents.iter().any(|x| x.contains("players set")));
+		}
+	}
+}
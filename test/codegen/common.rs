@@ -1,16 +1,18 @@
 use std::collections::HashMap;
 use std::fmt::Write;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use dpc::common::diag::Diagnostic;
+use dpc::common::mc::version::MinecraftVersion;
 use dpc::ir::IR;
 use dpc::output::datapack::Datapack;
 use dpc::output::strip::StripMode;
 use dpc::parse::lex::{lex, Token};
-use dpc::project::{OptimizationLevel, ProjectSettings, ProjectSettingsBuilder};
+use dpc::parse::Parser;
+use dpc::project::{CommandPrefix, OptimizationLevel, ProjectSettings, ProjectSettingsBuilder};
 use dpc::{codegen_ir, CodegenIRSettings};
 use itertools::Itertools;
 
-#[allow(dead_code)]
 pub static TEST_ENTRYPOINT: &str = "test:main";
 
 pub fn get_control_comment(
@@ -22,6 +24,9 @@ pub fn get_control_comment(
 		ir_passes: false,
 		mir_passes: false,
 		lir_passes: false,
+		tick_budget: false,
+		explain: None,
+		function_hooks: Vec::new(),
 	};
 	let mut project = ProjectSettingsBuilder::new("dpc");
 	// We start at full since we want all the optimizations to interact,
@@ -62,13 +67,92 @@ pub fn get_control_comment(
 		ir_passes,
 		mir_passes,
 		lir_passes,
+		tick_budget: false,
+		explain: None,
+		function_hooks: Vec::new(),
 	};
 
-	let project = project.strip_mode(strip_mode);
+	let mut project = project
+		.strip_mode(strip_mode)
+		.single_dimension(comment.contains("single_dimension"))
+		.chunk_large_regions(comment.contains("chunk_regions"))
+		.hidden_fake_players(!comment.contains("visible_players"))
+		.migrate_fake_players(comment.contains("migrate_players"))
+		.create_objectives(comment.contains("create_objectives"))
+		.load_init(!comment.contains("no_load_init"));
+	let target = comment
+		.split_whitespace()
+		.find_map(|x| x.strip_prefix("target_"));
+	if let Some(target) = target {
+		let version = MinecraftVersion::parse(target).context("Invalid target version")?;
+		project = project.target_version(version);
+	}
+	if comment.contains("literal_objective") {
+		project = project.literal_objective("dpc.lit");
+	}
+	if comment.contains("internal_namespace") {
+		project = project.internal_namespace("dpc_internal");
+	}
+	if comment.contains("command_guard") {
+		project = project.command_prefix(CommandPrefix::ScoreGuard {
+			holder: "#enabled".into(),
+			objective: "dpc.flags".into(),
+			value: 1,
+		});
+	}
 
 	Ok((settings, project.build(), split))
 }
 
+/// Checks if a test is expected to fail to compile, which is marked with `expect_error`
+/// in its control comment. These tests check the diagnostic instead of the output
+pub fn is_error_test(contents: &str) -> bool {
+	let first = contents.lines().next().unwrap_or_default();
+	first.starts_with('#') && first.contains("expect_error")
+}
+
+/// Gets the extension of the file with the expected output of a test
+pub fn get_output_extension(contents: &str) -> &'static str {
+	if is_error_test(contents) {
+		"err"
+	} else {
+		"mcfunction"
+	}
+}
+
+/// Compiles the input of a test, getting the datapacks it generates
+pub fn compile_input(contents: &str) -> anyhow::Result<HashMap<String, Datapack>> {
+	let mut parse = Parser::new();
+	parse.parse(contents)?;
+	let mut ir = parse.finish();
+
+	// Make sure the test function is marked as preserve
+	let Some(mut actual) = ir.functions.remove_entry(TEST_ENTRYPOINT) else {
+		bail!("Test function does not exist")
+	};
+	actual.1.interface.annotations.preserve = true;
+	ir.functions.insert(actual.0, actual.1);
+
+	let (settings, project, split) =
+		get_control_comment(contents).context("Failed to get control comment")?;
+	generate_datapacks(ir, project, settings, split)
+}
+
+/// Gets the output of a test to compare with the expected one. For tests that
+/// should fail, this is the diagnostic that the compiler reports
+pub fn get_test_output(test_name: &str, contents: &str) -> anyhow::Result<String> {
+	if is_error_test(contents) {
+		let Err(e) = compile_input(contents) else {
+			bail!("Test was expected to fail to compile, but it succeeded");
+		};
+		let file_name = format!("{test_name}.dpc");
+		Ok(Diagnostic::from_error(&e).render(&file_name, contents))
+	} else {
+		let datapacks = compile_input(contents).context("Failed to codegen input")?;
+		create_output(datapacks)
+	}
+}
+
 pub fn generate_datapacks(
 	ir: IR,
 	project: ProjectSettings,
@@ -76,13 +160,12 @@ pub fn generate_datapacks(
 	split: bool,
 ) -> anyhow::Result<HashMap<String, Datapack>> {
 	let mut out = HashMap::new();
-	let datapack =
-		codegen_ir(ir.clone(), &project, settings.clone()).context("Failed to codegen input")?;
+	let datapack = codegen_ir(ir.clone(), &project, settings.clone())?;
 	out.insert("main".into(), datapack);
 	if split {
 		settings.mir_passes = true;
 		settings.lir_passes = true;
-		let datapack = codegen_ir(ir, &project, settings).context("Failed to codegen input")?;
+		let datapack = codegen_ir(ir, &project, settings)?;
 		out.insert("opt".into(), datapack);
 	}
 	Ok(out)
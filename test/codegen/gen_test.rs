@@ -2,8 +2,7 @@ mod common;
 
 use std::{fs::File, io::Write, path::PathBuf};
 
-use common::{create_output, generate_datapacks, get_control_comment};
-use dpc::parse::Parser;
+use common::{get_output_extension, get_test_output};
 
 fn main() {
 	let test_dir = PathBuf::from("./test/codegen/tests");
@@ -11,19 +10,10 @@ fn main() {
 	let input = std::fs::read_to_string(test_dir.join(format!("{test_name}.dpc")))
 		.expect("Failed to open test file");
 
-	// Parse the input
-	let mut parse = Parser::new();
-	parse.parse(&input).expect("Failed to parse test input");
-	let ir = parse.finish();
+	let output = get_test_output(&test_name, &input).expect("Failed to run test");
 
-	// Run the codegen
-	let (settings, project, split) =
-		get_control_comment(&input).expect("Failed to get control comment");
-	let datapacks =
-		generate_datapacks(ir, project, settings, split).expect("Failed to codegen input");
-
-	let mut out_file = File::create(test_dir.join(format!("{test_name}.mcfunction")))
+	let extension = get_output_extension(&input);
+	let mut out_file = File::create(test_dir.join(format!("{test_name}.{extension}")))
 		.expect("Failed to create output file");
-	let output = create_output(datapacks).expect("Failed to output generated test");
 	write!(&mut out_file, "{output}").expect("Failed to write");
 }
@@ -2,13 +2,11 @@ mod common;
 
 use std::{fmt::Debug, io::Write, panic::catch_unwind, path::PathBuf, time::Instant};
 
-use anyhow::{bail, Context};
+use anyhow::Context;
 use color_print::cprintln;
-use common::generate_datapacks;
-use dpc::parse::Parser;
 use include_dir::{include_dir, Dir, DirEntry, File};
 
-use crate::common::{create_output, get_control_comment, TEST_ENTRYPOINT};
+use crate::common::{get_output_extension, get_test_output};
 
 static TESTS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/test/codegen/tests");
 
@@ -90,43 +88,24 @@ fn run_test(test_name: &str, generate: bool) -> anyhow::Result<()> {
 		.expect("Input file does not exist")
 		.contents_utf8()
 		.context("Input file is not UTF-8")?;
-	let output_contents = TESTS
-		.get_file(format!("{test_name}.mcfunction"))
-		.expect("Output file does not exist")
-		.contents_utf8()
-		.context("Output file is not UTF-8")?;
-
-	// Parse the input
-	let mut parse = Parser::new();
-	parse
-		.parse(input_contents)
-		.context("Failed to parse test input")?;
-	let mut ir = parse.finish();
-
-	// Make sure the test function is marked as preserve
-	let Some(mut actual) = ir.functions.remove_entry(TEST_ENTRYPOINT) else {
-		bail!("Test function does not exist")
-	};
-	actual.1.interface.annotations.preserve = true;
-	ir.functions.insert(actual.0, actual.1);
+	let output_name = format!("{test_name}.{}", get_output_extension(input_contents));
 
-	// Run the codegen
-	let (settings, project, split) =
-		get_control_comment(input_contents).expect("Failed to get control comment");
-	let datapacks =
-		generate_datapacks(ir, project, settings, split).context("Failed to codegen input")?;
+	let actual = get_test_output(test_name, input_contents)?;
 
 	// Generate if we need to
 	if generate {
 		let test_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
 			.join("test/codegen/tests")
-			.join(format!("{test_name}.mcfunction"));
+			.join(output_name);
 		println!("Regenerating...");
 		let mut out_file = std::fs::File::create(test_path).expect("Failed to create output file");
-		let output = create_output(datapacks).expect("Failed to output generated test");
-		write!(&mut out_file, "{output}").expect("Failed to write");
+		write!(&mut out_file, "{actual}").expect("Failed to write");
 	} else {
-		let actual = create_output(datapacks).expect("Failed to create actual test output");
+		let output_contents = TESTS
+			.get_file(&output_name)
+			.expect("Output file does not exist")
+			.contents_utf8()
+			.context("Output file is not UTF-8")?;
 		assert_eq!(
 			output_contents.lines().count(),
 			actual.lines().count(),
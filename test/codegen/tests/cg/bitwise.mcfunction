@@ -0,0 +1,227 @@
+# === dpc:bit/and === #
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r = #adpc_bit_and.0 _r
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r = #adpc_bit_and.1 _r
+scoreboard players set #Rdpc_bit_and.0 _r 0
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 1
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 2
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 4
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 8
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 16
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 32
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 64
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 128
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 256
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 512
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 1024
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 2048
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 4096
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 8192
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 16384
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 32768
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 65536
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 131072
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 262144
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 524288
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 1048576
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 2097152
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 4194304
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 8388608
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 16777216
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 33554432
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 67108864
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 134217728
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 268435456
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 536870912
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players add #Rdpc_bit_and.0 _r 1073741824
+execute store result score #r.1 _r run scoreboard players operation #r.0 _r /= #l2 _l
+execute store result score #r.3 _r run scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.3 _r %= #l2 _l
+execute if score #r.1 _r matches 1 if score #r.3 _r matches 1 run scoreboard players operation #Rdpc_bit_and.0 _r += #l-2147483648 _l
+
+# === dpc:bit/shr === #
+scoreboard players operation #rdpc_bit_shr.0 _r = #adpc_bit_shr.0 _r
+scoreboard players operation #adpc_bit_shr.1 _r %= #l32 _l
+scoreboard players set #r.0 _r 1
+execute if score #adpc_bit_shr.1 _r matches 1 run scoreboard players set #r.0 _r 2
+execute if score #adpc_bit_shr.1 _r matches 2 run scoreboard players set #r.0 _r 4
+execute if score #adpc_bit_shr.1 _r matches 3 run scoreboard players set #r.0 _r 8
+execute if score #adpc_bit_shr.1 _r matches 4 run scoreboard players set #r.0 _r 16
+execute if score #adpc_bit_shr.1 _r matches 5 run scoreboard players set #r.0 _r 32
+execute if score #adpc_bit_shr.1 _r matches 6 run scoreboard players set #r.0 _r 64
+execute if score #adpc_bit_shr.1 _r matches 7 run scoreboard players set #r.0 _r 128
+execute if score #adpc_bit_shr.1 _r matches 8 run scoreboard players set #r.0 _r 256
+execute if score #adpc_bit_shr.1 _r matches 9 run scoreboard players set #r.0 _r 512
+execute if score #adpc_bit_shr.1 _r matches 10 run scoreboard players set #r.0 _r 1024
+execute if score #adpc_bit_shr.1 _r matches 11 run scoreboard players set #r.0 _r 2048
+execute if score #adpc_bit_shr.1 _r matches 12 run scoreboard players set #r.0 _r 4096
+execute if score #adpc_bit_shr.1 _r matches 13 run scoreboard players set #r.0 _r 8192
+execute if score #adpc_bit_shr.1 _r matches 14 run scoreboard players set #r.0 _r 16384
+execute if score #adpc_bit_shr.1 _r matches 15 run scoreboard players set #r.0 _r 32768
+execute if score #adpc_bit_shr.1 _r matches 16 run scoreboard players set #r.0 _r 65536
+execute if score #adpc_bit_shr.1 _r matches 17 run scoreboard players set #r.0 _r 131072
+execute if score #adpc_bit_shr.1 _r matches 18 run scoreboard players set #r.0 _r 262144
+execute if score #adpc_bit_shr.1 _r matches 19 run scoreboard players set #r.0 _r 524288
+execute if score #adpc_bit_shr.1 _r matches 20 run scoreboard players set #r.0 _r 1048576
+execute if score #adpc_bit_shr.1 _r matches 21 run scoreboard players set #r.0 _r 2097152
+execute if score #adpc_bit_shr.1 _r matches 22 run scoreboard players set #r.0 _r 4194304
+execute if score #adpc_bit_shr.1 _r matches 23 run scoreboard players set #r.0 _r 8388608
+execute if score #adpc_bit_shr.1 _r matches 24 run scoreboard players set #r.0 _r 16777216
+execute if score #adpc_bit_shr.1 _r matches 25 run scoreboard players set #r.0 _r 33554432
+execute if score #adpc_bit_shr.1 _r matches 26 run scoreboard players set #r.0 _r 67108864
+execute if score #adpc_bit_shr.1 _r matches 27 run scoreboard players set #r.0 _r 134217728
+execute if score #adpc_bit_shr.1 _r matches 28 run scoreboard players set #r.0 _r 268435456
+execute if score #adpc_bit_shr.1 _r matches 29 run scoreboard players set #r.0 _r 536870912
+execute if score #adpc_bit_shr.1 _r matches 30 run scoreboard players set #r.0 _r 1073741824
+execute store result score #Rdpc_bit_shr.0 _r run scoreboard players operation #adpc_bit_shr.0 _r /= #r.0 _r
+execute if score #adpc_bit_shr.1 _r matches 31 run function dpc:bit/shr_body_0
+
+# === dpc:bit/shr_body_0 === #
+scoreboard players operation #rdpc_bit_shr.0 _r /= #l1073741824 _l
+scoreboard players operation #rdpc_bit_shr.0 _r /= #l2 _l
+
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l-2147483648 _l -2147483648
+scoreboard players set #l-1 _l -1
+scoreboard players set #l2 _l 2
+scoreboard players set #l16 _l 16
+scoreboard players set #l32 _l 32
+scoreboard players set #l1073741824 _l 1073741824
+
+# === test:main === #
+scoreboard players set #adpc_bit_shr.0 _r 29
+execute store result score #adpc_bit_shr.1 _r run scoreboard players set #adpc_bit_and.1 _r 3
+scoreboard players operation #adpc_bit_shr.0 _r %= #l16 _l
+scoreboard players operation #adpc_bit_shr.0 _r *= #l-1 _l
+scoreboard players remove #adpc_bit_shr.0 _r 1
+scoreboard players operation #adpc_bit_shr.0 _r *= #l16 _l
+scoreboard players operation #adpc_bit_shr.0 _r /= #l1073741824 _l
+scoreboard players operation #adpc_bit_shr.0 _r /= #l2 _l
+function dpc:bit/shr
+scoreboard players operation #adpc_bit_and.0 _r = #Rdpc_bit_shr.0 _r
+function dpc:bit/and
+scoreboard players operation #r.0 _r = #Rdpc_bit_and.0 _r
@@ -0,0 +1,16 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #r.0 _r 7
+bossbar add test:bar {"color":"gold","text":"Progress"}
+bossbar set test:bar max 20
+bossbar set test:bar value 3
+bossbar set test:bar name "Loading"
+bossbar set test:bar players @a
+bossbar set test:bar players
+execute store result bossbar test:bar value run scoreboard players get #r.0 _r
+execute store result bossbar test:bar max run time query day
+bossbar get test:bar value
+bossbar get test:bar visible
+bossbar remove test:bar
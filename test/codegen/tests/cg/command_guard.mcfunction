@@ -0,0 +1,9 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+execute if score #enabled dpc.flags matches 1 run scoreboard players set #r.0 _r 5
+#Comments are not guarded
+execute if score #enabled dpc.flags matches 1 as @e[tag=foo] run say Hello
+execute if score #enabled dpc.flags matches 1 if score #r.0 _r matches 5 run say Five
+execute if score #enabled dpc.flags matches 1 run setblock ~ ~ ~ stone
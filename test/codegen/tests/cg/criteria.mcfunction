@@ -0,0 +1,9 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _c_health health
+scoreboard objectives add _c_minecraft.custom-.minecraft.jump minecraft.custom:minecraft.jump
+
+# === test:main === #
+execute if score @s _c_health matches ..4 run say low health
+scoreboard players operation #r.0 _r = @s _c_minecraft.custom-.minecraft.jump
+scoreboard players add #r.0 _r 1
@@ -0,0 +1,7 @@
+# === test:main === #
+scoreboard objectives modify foo displayname "Kills"
+scoreboard objectives modify foo displayname {"bold":true,"color":"gold","text":"Kills"}
+scoreboard objectives modify foo numberformat blank
+scoreboard objectives modify foo numberformat fixed {"italic":false,"text":"???"}
+scoreboard objectives modify foo numberformat styled {"color":"red"}
+scoreboard objectives modify foo numberformat
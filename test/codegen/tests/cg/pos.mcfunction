@@ -2,5 +2,5 @@
 tp ~ ~ ~
 tp ~ ~ ~
 tp @e ~ ~ ~
-tp @s ~ ~ ~ 7 ~2 
+tp @s ~ ~ ~ 7 ~2
 tp ~-.4 .8 ~-3
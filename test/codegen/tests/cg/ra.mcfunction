@@ -1,14 +1,25 @@
 # === dpc:init === #
 scoreboard objectives add _r dummy
 
+# === test:helper === #
+scoreboard players set #r.0 _r 6
+scoreboard players add #r.0 _r 3
+
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 0
-scoreboard players set %rtest_main.0 _r 2
-execute if predicate foo:bar run scoreboard players add %rtest_main.0 _r 1
-scoreboard players set %rtest_main.0 _r 0
-scoreboard players set %rtest_main.1 _r 8
+scoreboard players set #r.0 _r 0
+scoreboard players set #r.0 _r 2
+execute if predicate foo:bar run scoreboard players add #r.0 _r 1
+scoreboard players set #rtest_main.0 _r 0
+scoreboard players set #rtest_main.1 _r 8
 execute if predicate foo:bar run function test:main_body_0
 
 # === test:main_body_0 === #
-scoreboard players operation %rtest_main.1 _r %= %rtest_main.0 _r
-scoreboard players set %rtest_main.2 _r 10
+scoreboard players operation #rtest_main.1 _r %= #rtest_main.0 _r
+scoreboard players set #rtest_main.2 _r 10
+
+# === test:other === #
+scoreboard players set #rtest_other.0 _r 4
+function test:helper
+scoreboard players add #rtest_other.0 _r 1
+scoreboard players set #r.0 _r 5
+scoreboard players add #r.0 _r 2
@@ -0,0 +1,8 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+execute store result score #r.0 _r run random value 1..6
+execute store result storage dpc:r r_0 int 1 run random value -10..10
+random value 1..100 foo:loot
+random roll 1..20
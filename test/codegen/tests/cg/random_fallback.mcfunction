@@ -0,0 +1,16 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add dpc_rng dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l6 _l 6
+scoreboard players set #l65536 _l 65536
+scoreboard players set #l1103515245 _l 1103515245
+
+# === test:main === #
+scoreboard players operation #rng dpc_rng *= #l1103515245 _l
+scoreboard players add #rng dpc_rng 12345
+scoreboard players operation #rng_out dpc_rng = #rng dpc_rng
+scoreboard players operation #rng_out dpc_rng /= #l65536 _l
+scoreboard players operation #rng_out dpc_rng %= #l6 _l
+scoreboard players add #rng_out dpc_rng 1
+scoreboard players operation #r.0 _r = #rng_out dpc_rng
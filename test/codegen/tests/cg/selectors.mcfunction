@@ -0,0 +1,4 @@
+# === test:main === #
+kill @e[type=!zombie,team=red,gamemode=!creative,level=1..5,distance=2.5]
+kill @e[x=1,y=-2.5,z=0,dx=4,scores={foo=1..,bar=3},advancements={story/root=true}]
+kill @a[name="Bob",team=!]
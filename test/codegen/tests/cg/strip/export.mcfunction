@@ -0,0 +1,13 @@
+# === dpc:s/ === #
+say I can be stripped
+
+# === test:api/stable === #
+say I am exported, so I get a stable name
+
+# === test:api/uncalled === #
+say I am only called by other packs
+
+# === test:main === #
+function test:api/stable
+function dpc:s/
+function dpc:s/
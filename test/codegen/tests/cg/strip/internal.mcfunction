@@ -0,0 +1,11 @@
+# === dpc_internal:dpc/init === #
+scoreboard objectives add _r dummy
+
+# === dpc_internal:test/main_body_0 === #
+say True
+say Still true
+
+# === test:main === #
+execute store success score #rtest_main.0 _r if predicate foo:bar
+execute if score #rtest_main.0 _r matches 1 run function dpc_internal:test/main_body_0
+execute if score #rtest_main.0 _r matches 0 run say False
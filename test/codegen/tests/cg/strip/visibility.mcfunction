@@ -0,0 +1,9 @@
+# === dpc:s/ === #
+say I am private, so I can be stripped
+
+# === test:exported_function === #
+say I am public, so I keep my name
+
+# === test:main === #
+function test:exported_function
+function dpc:s/
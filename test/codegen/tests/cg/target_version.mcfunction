@@ -0,0 +1,4 @@
+# === test:main === #
+locate minecraft:village
+locatebiome minecraft:taiga
+locate minecraft:igloo
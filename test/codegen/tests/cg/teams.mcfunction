@@ -0,0 +1,20 @@
+# === test:main === #
+team add red
+team add blue {"color":"blue","text":"Blue Team"}
+team join red
+team join red @a[tag=red]
+team join blue Player
+team leave @a
+team modify red displayName "Red Team"
+team modify red color red
+team modify red friendlyFire false
+team modify red seeFriendlyInvisibles true
+team modify red nametagVisibility hideForOtherTeams
+team modify red deathMessageVisibility never
+team modify red collisionRule pushOwnTeam
+team modify red prefix {"color":"red","text":"[R] "}
+team modify red suffix "!"
+team list
+team list red
+team empty red
+team remove blue
@@ -0,0 +1,12 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #r.0 _r 7
+data modify storage dpc:r r_0 set value 5
+tellraw @a {"color":"gold","extra":[{"score":{"name":"#r.0","objective":"_r"}}," and ",{"nbt":"r_0","storage":"dpc:r"}],"text":"Score: "}
+tellraw @s {"score":{"name":"@s","objective":"kills"}}
+tellraw Player {"nbt":"path.to.value","storage":"test:data"}
+title @a title {"bold":true,"text":"Hello"}
+title @a subtitle {"clickEvent":{"action":"run_command","value":"/say hi"},"hoverEvent":{"action":"show_text","contents":{"extra":[{"score":{"name":"#r.0","objective":"_r"}}],"text":"Value is "}},"text":"Click me"}
+title @a actionbar {"extra":["/",{"score":{"name":"Player","objective":"max"}}],"score":{"name":"#r.0","objective":"_r"}}
@@ -5,3 +5,5 @@ time set 170
 time set 234875
 time set .5d
 time set 1d
+time set day
+time add 430
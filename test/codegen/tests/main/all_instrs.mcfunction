@@ -1,35 +1,35 @@
 # === dpc:init === #
 scoreboard objectives add _r dummy
 scoreboard objectives add _l dummy
-scoreboard players set %l-1 _l -1
-scoreboard players set %l1 _l 1
+scoreboard players set #l-1 _l -1
+scoreboard players set #l1 _l 1
 
 # === test:foo === #
 return 7
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 7
-scoreboard players operation %rtest_main.0 _r = %rtest_main.0 _r
-scoreboard players add %rtest_main.0 _r 1
-scoreboard players remove %rtest_main.0 _r 1
-scoreboard players operation %rtest_main.0 _r *= %l1 _l
-scoreboard players operation %rtest_main.0 _r /= %l1 _l
-scoreboard players operation %rtest_main.0 _r %= %l1 _l
-scoreboard players operation %rtest_main.0 _r < %l1 _l
-scoreboard players operation %rtest_main.0 _r > %l1 _l
-scoreboard players set %rtest_main.1 _r 3
-scoreboard players operation %rtest_main.0 _r >< %rtest_main.1 _r
-scoreboard players reset %rtest_main.0
-execute if score %rtest_main.0 _r matches ..-1 run scoreboard players operation %rtest_main.0 _r *= %l-1 _l
-execute store success score %rtest_main.0 _r if score %rtest_main.0 _r matches 0
-scoreboard players operation %rtest_main.0 _r *= %rtest_main.1 _r
-execute if score %rtest_main.1 _r matches 1 run scoreboard players set %rtest_main.0 _r 1
-scoreboard players operation %rtest_main.0 _r -= %rtest_main.1 _r
-scoreboard players operation %rtest_main.0 _r *= %rtest_main.0 _r
-scoreboard players operation %rtest_main.1 _r = %rtest_main.0 _r
-scoreboard players operation %rtest_main.0 _r *= %rtest_main.1 _r
-scoreboard players operation %rtest_main.0 _r *= %rtest_main.1 _r
-scoreboard players get %rtest_main.0 _r
+scoreboard players set #r.0 _r 7
+scoreboard players operation #r.0 _r = #r.0 _r
+scoreboard players add #r.0 _r 1
+scoreboard players remove #r.0 _r 1
+scoreboard players operation #r.0 _r *= #l1 _l
+scoreboard players operation #r.0 _r /= #l1 _l
+scoreboard players operation #r.0 _r %= #l1 _l
+scoreboard players operation #r.0 _r < #l1 _l
+scoreboard players operation #r.0 _r > #l1 _l
+scoreboard players set #r.1 _r 3
+scoreboard players operation #r.0 _r >< #r.1 _r
+scoreboard players reset #r.0
+execute if score #r.0 _r matches ..-1 run scoreboard players operation #r.0 _r *= #l-1 _l
+execute store success score #r.0 _r if score #r.0 _r matches 0
+scoreboard players operation #r.0 _r *= #r.1 _r
+execute if score #r.1 _r matches 1 run scoreboard players set #r.0 _r 1
+scoreboard players operation #r.0 _r -= #r.1 _r
+scoreboard players operation #r.0 _r *= #r.0 _r
+scoreboard players operation #r.1 _r = #r.0 _r
+scoreboard players operation #r.0 _r *= #r.1 _r
+scoreboard players operation #r.0 _r *= #r.1 _r
+scoreboard players get #r.0 _r
 say foo
 me foo
 tm foo
@@ -74,7 +74,7 @@ scoreboard objectives add bar foo.bar
 setworldspawn 0 ~6 8 ~3
 summon minecraft:zombie ~ ~ ~ {CustomName:"Foo"}
 summon minecraft:zombie
-scoreboard players set %atest_foo.0 _r 7
+scoreboard players set #atest_foo.0 _r 7
 data modify storage dpc:r atest_foo_1 set value 82
 function test:foo
 function #minecraft:tick
@@ -88,6 +88,7 @@ effect give @s speed 4
 effect give @s speed
 effect give @s speed infinite
 effect give @s speed infinite 1 true
+effect give @s speed 10
 time set 85
 time add 4d
 # This does something
@@ -97,7 +98,7 @@ spawnpoint @r ~ ~ ~5
 tp @a @s
 tp @e
 tp 0 0 ~
-tp @s 0 0 0 .4 ~4 
+tp @s 0 0 0 .4 ~4
 tp @s 0 0 0 facing .4 ~4 7.4
 tp @s 0 0 0 facing @p
 gamerule doFireTick true
@@ -114,4 +115,9 @@ worldborder warning distance .8
 worldborder warning time 3
 playsound entity.ender_dragon.roar master @s ~ ~ ~ 1 2
 setblock ~ ~ ~ stone
+particle flame
+particle dust 1 0 .5 1.5 ~ ~1 ~ .5 .5 .5 .1 10
+particle dust_color_transition 1 0 0 2 0 0 1 0 64 0 0 0 0 0 0 force
+particle block stone ~ ~ ~ .25 0 .25 .5 20 force @a
+particle item diamond ~ ~ ~ 0 0 0 0 1 normal @s
 return run say hello
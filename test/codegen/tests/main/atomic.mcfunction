@@ -0,0 +1,11 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:helper === #
+say hi
+
+# === test:main === #
+scoreboard players set #rtest_main.0 _r 1
+scoreboard players add #rtest_main.0 _r 2
+function test:helper
+execute if score #rtest_main.0 _r matches 1 run say hello
@@ -0,0 +1,12 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+execute store result score #r.0 _r if data storage test:main players[]
+execute if score #r.0 _r matches 2.. run say at least two players
+execute store result score #r.0 _r if data entity @s Inventory[{id:"minecraft:stone"}]
+execute if score #r.0 _r matches 3 run say three stacks of stone
+execute store result score #r.0 _r if data storage test:main queue[]
+execute unless score #r.0 _r matches ..4 run say queue is long
+execute if items entity @s weapon.mainhand minecraft:diamond_sword run say holding a sword
+execute if items block ~ ~-1 ~ container.* *[minecraft:custom_data~{key:1b}] run say chest has a key
@@ -0,0 +1,14 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l0 _l 0
+
+# === test:main === #
+execute if entity @s[gamemode=creative] run say creative
+execute if entity @a[name="Player",gamemode=spectator] run say spectator
+execute if entity @a[gamemode=adventure] run say adventure
+execute if score #l0 _l matches 1 run say never
+execute store result score #r.0 _r run difficulty
+execute if score #r.0 _r matches 3 run say hard
+execute store result score #r.0 _r run difficulty
+execute unless score #r.0 _r matches 0 run say not peaceful
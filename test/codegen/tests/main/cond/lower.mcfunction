@@ -8,23 +8,23 @@ execute if score @s foo matches 0 run say goodbye
 # === test:main === #
 
 # === test:not_and === #
-execute store success score %rtest_not_and.0 _r unless predicate bar:foo
-execute unless predicate foo:bar run scoreboard players add %rtest_not_and.0 _r 1
-execute if score %rtest_not_and.0 _r matches 1.. run say Hello
+execute store success score #r.0 _r unless predicate bar:foo
+execute unless predicate foo:bar run scoreboard players add #r.0 _r 1
+execute if score #r.0 _r matches 1.. run say Hello
 
 # === test:or === #
-execute store success score %rtest_or.0 _r if predicate bar:foo
-execute if predicate foo:bar run scoreboard players add %rtest_or.0 _r 1
-execute if score %rtest_or.0 _r matches 1.. run say Hello
-execute store success score %rtest_or.0 _r if function test:or_body_0
-execute if predicate foo:bar4 run scoreboard players add %rtest_or.0 _r 1
-execute if score %rtest_or.0 _r matches 1.. run say Hello2
+execute store success score #r.0 _r if predicate bar:foo
+execute if predicate foo:bar run scoreboard players add #r.0 _r 1
+execute if score #r.0 _r matches 1.. run say Hello
+execute store success score #rtest_or.0 _r if function test:or_body_0
+execute if predicate foo:bar4 run scoreboard players add #rtest_or.0 _r 1
+execute if score #rtest_or.0 _r matches 1.. run say Hello2
 execute if data storage foo:bar foo run function test:or_body_1
-execute store success score %rtest_or.0 _r if score @s foo matches 1
-scoreboard players operation %rtest_or.0 _r += @s bar
-scoreboard players operation %rtest_or.1 _r = %rtest_or.0 _r
-scoreboard players operation %rtest_or.1 _r += @s foo
-execute if score %rtest_or.1 _r matches 1.. run say Hello5
+execute store success score #r.0 _r if score @s foo matches 1
+scoreboard players operation #r.0 _r += @s bar
+scoreboard players operation #r.1 _r = #r.0 _r
+scoreboard players operation #r.1 _r += @s foo
+execute if score #r.1 _r matches 1.. run say Hello5
 
 # === test:or_body_0 === #
 execute if predicate foo:bar run return 1
@@ -35,6 +35,6 @@ say Hello3
 say Hello4
 
 # === test:xor === #
-execute store success score %rtest_xor.0 _r if predicate foo:bar
-execute if predicate bar:foo run scoreboard players remove %rtest_xor.0 _r 1
-execute unless score %rtest_xor.0 _r matches 0 run say Hello
+execute store success score #r.0 _r if predicate foo:bar
+execute if predicate bar:foo run scoreboard players remove #r.0 _r 1
+execute unless score #r.0 _r matches 0 run say Hello
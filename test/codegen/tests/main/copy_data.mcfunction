@@ -0,0 +1,5 @@
+# === test:main === #
+data modify storage test:to a set from storage test:from a
+data modify storage test:to b.c set from storage test:from b.c
+data modify storage test:to d[0] set from storage test:from d[0]
+data modify entity @s Items set from block ~ ~ ~ Items
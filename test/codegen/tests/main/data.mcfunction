@@ -1,5 +1,5 @@
 # === test:main === #
-data modify storage dpc:r rtest_main_0 set value 7
-data modify storage dpc:r rtest_main_0 set from storage test:test foo
-data modify storage dpc:r rtest_main_0 set from entity @s foo
-data modify storage dpc:r rtest_main_0 set from block ~ ~ ~ foo
+data modify storage dpc:r r_0 set value 7
+data modify storage dpc:r r_0 set from storage test:test foo
+data modify storage dpc:r r_0 set from entity @s foo
+data modify storage dpc:r r_0 set from block ~ ~ ~ foo
@@ -0,0 +1,57 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === dpc:run_tests === #
+function dpc:test/test/check
+function dpc:test/test/check_loop
+
+# === dpc:test/test/check === #
+scoreboard players reset * _r
+scoreboard players set #test _r 1
+execute store success score #test _r run function test:check
+execute if score #test _r matches 1 run tellraw @a {"color":"green","text":"[PASS] test:check"}
+execute unless score #test _r matches 1 run tellraw @a {"color":"red","text":"[FAIL] test:check"}
+
+# === dpc:test/test/check_loop === #
+scoreboard players reset * _r
+scoreboard players set #test _r 1
+execute store success score #test _r run function test:check_loop
+execute if score #test _r matches 1 run tellraw @a {"color":"green","text":"[PASS] test:check_loop"}
+execute unless score #test _r matches 1 run tellraw @a {"color":"red","text":"[FAIL] test:check_loop"}
+
+# === test:check === #
+scoreboard players set #rtest_check.0 _r 0
+scoreboard players operation #rtest_check.1 _r = @s foo
+execute unless score #rtest_check.1 _r matches 5 run tellraw @a {"color":"red","text":"Score should be 5"}
+execute unless score #rtest_check.1 _r matches 5 run return fail
+execute if score @s bar matches ..2147483647 run function test:check_body_0
+execute if score #rtest_check.0 _r matches 1 run return fail
+
+# === test:check_body_0 === #
+execute if score #rtest_check.1 _r matches 1 run tellraw @a {"color":"red","text":"Nested assertions are kept too"}
+execute if score #rtest_check.1 _r matches 1 run scoreboard players set #rtest_check.0 _r 1
+execute if score #rtest_check.1 _r matches 1 run return fail
+
+# === test:check_loop === #
+scoreboard players set #atest_check_loop_loop_0.0 _r 0
+scoreboard players set #atest_check_loop_loop_0.1 _r 0
+function test:check_loop_loop_0
+execute if score #Rtest_check_loop_loop_0.0 _r matches 1 run return fail
+say Done
+
+# === test:check_loop_loop_0 === #
+execute store result score #Rtest_check_loop_loop_0.0 _r run scoreboard players operation #rtest_check_loop_loop_0.0 _r = #atest_check_loop_loop_0.0 _r
+scoreboard players operation #rtest_check_loop_loop_0.1 _r = #atest_check_loop_loop_0.1 _r
+execute if score #atest_check_loop_loop_0.1 _r matches ..9 run function test:check_loop_loop_0_body_0
+scoreboard players operation #Rtest_check_loop_loop_0.1 _r = #atest_check_loop_loop_0.1 _r
+
+# === test:check_loop_loop_0_body_0 === #
+execute unless score #atest_check_loop_loop_0.1 _r matches ..4 run tellraw @a {"color":"red","text":"Loops fail the test too"}
+execute unless score #atest_check_loop_loop_0.1 _r matches ..4 run scoreboard players set #atest_check_loop_loop_0.0 _r 1
+execute unless score #atest_check_loop_loop_0.1 _r matches ..4 run scoreboard players operation #Rtest_check_loop_loop_0.0 _r = #atest_check_loop_loop_0.0 _r
+execute unless score #atest_check_loop_loop_0.1 _r matches ..4 run return fail
+scoreboard players add #atest_check_loop_loop_0.1 _r 1
+function test:check_loop_loop_0
+
+# === test:main === #
+say Hello
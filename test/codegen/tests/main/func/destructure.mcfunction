@@ -0,0 +1,16 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #atest_swap.0 _r 17
+scoreboard players set #atest_swap.1 _r 5
+function test:swap
+scoreboard players operation #r.0 _r = #Rtest_swap.0 _r
+scoreboard players operation #r.1 _r = #Rtest_swap.1 _r
+scoreboard players set #atest_swap.0 _r 3
+scoreboard players set #atest_swap.1 _r 2
+function test:swap
+
+# === test:swap === #
+scoreboard players operation #Rtest_swap.0 _r = #atest_swap.1 _r
+scoreboard players operation #Rtest_swap.1 _r = #atest_swap.0 _r
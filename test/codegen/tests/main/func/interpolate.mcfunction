@@ -0,0 +1,46 @@
+######## main ########
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #r.0 _r 5
+data modify storage dpc:r rtest_main_0 set from storage test:players first
+data modify storage dpc:r rtest_main_1 set value {}
+execute store result storage dpc:r r_0 int 1 run scoreboard players get #r.0 _r
+data modify storage dpc:r rtest_main_1.count set from storage dpc:r r_0
+data modify storage dpc:r rtest_main_1.name set from storage dpc:r rtest_main_0
+function test:main_cmd_0 with storage dpc:r rtest_main_1
+data modify storage dpc:r rtest_main_1 set value {}
+data modify storage dpc:r rtest_main_1.name set from storage dpc:r rtest_main_0
+function test:main_cmd_1 with storage dpc:r rtest_main_1
+say No interpolation
+say Plain commands keep $(name) as written
+
+# === test:main_cmd_0 === #
+$say $(name) has $(count) points
+
+# === test:main_cmd_1 === #
+$say Hello $(name)
+
+######## opt ########
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+data modify storage dpc:r rtest_main_0 set from storage test:players first
+data modify storage dpc:r rtest_main_1 set value {}
+execute store result storage dpc:r r_0 int 1 run scoreboard players set #r.0 _r 5
+data modify storage dpc:r rtest_main_1.count set from storage dpc:r r_0
+data modify storage dpc:r rtest_main_1.name set from storage dpc:r rtest_main_0
+function test:main_cmd_0 with storage dpc:r rtest_main_1
+data modify storage dpc:r rtest_main_1 set value {}
+data modify storage dpc:r rtest_main_1.name set from storage dpc:r rtest_main_0
+function test:main_cmd_1 with storage dpc:r rtest_main_1
+say No interpolation
+say Plain commands keep $(name) as written
+
+# === test:main_cmd_0 === #
+$say $(name) has $(count) points
+
+# === test:main_cmd_1 === #
+$say Hello $(name)
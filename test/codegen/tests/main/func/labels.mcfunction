@@ -0,0 +1,35 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #rtest_main.0 _r 0
+return run function test:main_body_0
+
+# === test:main_body_0 === #
+scoreboard players operation #atest_main_label_loop.0 _r = #rtest_main.0 _r
+function test:main_label_loop
+
+# === test:main_label_end === #
+scoreboard players operation #r.0 _r = #atest_main_label_end.0 _r
+say Done
+
+# === test:main_label_loop === #
+scoreboard players operation #rtest_main_label_loop.0 _r = #atest_main_label_loop.0 _r
+scoreboard players add #rtest_main_label_loop.0 _r 1
+say Looping
+execute if score #rtest_main_label_loop.0 _r matches ..9 run return run function test:main_label_loop_body_0
+return run function test:main_label_loop_body_1
+
+# === test:main_label_loop_body_0 === #
+scoreboard players operation #atest_main_label_loop.0 _r = #rtest_main_label_loop.0 _r
+function test:main_label_loop
+
+# === test:main_label_loop_body_1 === #
+scoreboard players operation #atest_main_label_end.0 _r = #rtest_main_label_loop.0 _r
+function test:main_label_end
+
+# === test:skip === #
+return run function test:skip_label_after
+
+# === test:skip_label_after === #
+say After
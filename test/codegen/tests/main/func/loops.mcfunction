@@ -0,0 +1,42 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #atest_main_loop_0.0 _r 0
+scoreboard players set #atest_main_loop_0.1 _r 0
+function test:main_loop_0
+scoreboard players operation #r.0 _r = #Rtest_main_loop_0.1 _r
+scoreboard players operation #atest_main_loop_1.0 _r = #Rtest_main_loop_0.0 _r
+function test:main_loop_1
+execute store result score #atest_main_loop_2.0 _r run scoreboard players operation #rtest_main.0 _r = #Rtest_main_loop_1.0 _r
+function test:main_loop_2
+scoreboard players operation #rtest_main.0 _r = #Rtest_main_loop_2.0 _r
+
+# === test:main_loop_0 === #
+scoreboard players operation #rtest_main_loop_0.0 _r = #atest_main_loop_0.0 _r
+execute store result score #Rtest_main_loop_0.1 _r run scoreboard players operation #rtest_main_loop_0.1 _r = #atest_main_loop_0.1 _r
+execute if score #atest_main_loop_0.0 _r matches ..9 run function test:main_loop_0_body_0
+scoreboard players operation #Rtest_main_loop_0.0 _r = #atest_main_loop_0.0 _r
+
+# === test:main_loop_0_body_0 === #
+scoreboard players add #atest_main_loop_0.0 _r 1
+scoreboard players operation #atest_main_loop_0.1 _r += #atest_main_loop_0.0 _r
+function test:main_loop_0
+
+# === test:main_loop_1 === #
+scoreboard players operation #rtest_main_loop_1.0 _r = #atest_main_loop_1.0 _r
+execute unless score #atest_main_loop_1.0 _r matches 20.. run function test:main_loop_1_body_0
+scoreboard players operation #Rtest_main_loop_1.0 _r = #atest_main_loop_1.0 _r
+
+# === test:main_loop_1_body_0 === #
+scoreboard players add #atest_main_loop_1.0 _r 2
+function test:main_loop_1
+
+# === test:main_loop_2 === #
+scoreboard players operation #rtest_main_loop_2.0 _r = #atest_main_loop_2.0 _r
+scoreboard players remove #atest_main_loop_2.0 _r 3
+execute if score #atest_main_loop_2.0 _r matches 1.. run function test:main_loop_2_body_0
+scoreboard players operation #Rtest_main_loop_2.0 _r = #atest_main_loop_2.0 _r
+
+# === test:main_loop_2_body_0 === #
+function test:main_loop_2
@@ -0,0 +1,28 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #atest_main_loop_0.0 _r 0
+function test:main_loop_0
+scoreboard players set #atest_main_loop_1.0 _r 20
+function test:main_loop_1
+scoreboard players operation #rtest_main.0 _r = #Rtest_main_loop_1.0 _r
+
+# === test:main_loop_0 === #
+scoreboard players operation #rtest_main_loop_0.0 _r = #atest_main_loop_0.0 _r
+execute if score #atest_main_loop_0.0 _r matches ..9 run function test:main_loop_0_body_0
+scoreboard players operation #Rtest_main_loop_0.0 _r = #atest_main_loop_0.0 _r
+
+# === test:main_loop_0_body_0 === #
+say iter
+scoreboard players add #atest_main_loop_0.0 _r 1
+function test:main_loop_0
+
+# === test:main_loop_1 === #
+scoreboard players operation #rtest_main_loop_1.0 _r = #atest_main_loop_1.0 _r
+scoreboard players remove #atest_main_loop_1.0 _r 3
+execute if score #atest_main_loop_1.0 _r matches 1.. run function test:main_loop_1_body_0
+scoreboard players operation #Rtest_main_loop_1.0 _r = #atest_main_loop_1.0 _r
+
+# === test:main_loop_1_body_0 === #
+function test:main_loop_1
@@ -0,0 +1,21 @@
+######## main ########
+# === test:greet === #
+$say Hello $(name)
+say Greeted
+
+# === test:main === #
+data modify storage dpc:r rtest_main_0 set value {name:"Bob"}
+function test:greet with storage dpc:r rtest_main_0
+function test:greet {name:"Alice"}
+function test:greet with storage test:players first
+
+######## opt ########
+# === test:greet === #
+$say Hello $(name)
+say Greeted
+
+# === test:main === #
+data modify storage dpc:r rtest_main_0 set value {name:"Bob"}
+function test:greet with storage dpc:r rtest_main_0
+function test:greet {name:"Alice"}
+function test:greet with storage test:players first
@@ -0,0 +1,15 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #atest_stats.0 _r 4
+function test:stats
+scoreboard players operation #r.0 _r = #Rtest_stats.0 _r
+data modify storage dpc:r r_0 set from storage dpc:r Rtest_stats_1
+data modify storage dpc:r r_1 set from storage dpc:r Rtest_stats_2
+
+# === test:stats === #
+execute store result storage dpc:r r_0 int 1 run scoreboard players get #atest_stats.0 _r
+scoreboard players operation #Rtest_stats.0 _r = #atest_stats.0 _r
+data modify storage dpc:r Rtest_stats_1 set from storage dpc:r r_0
+data modify storage dpc:r Rtest_stats_2 set value {count:3,name:"foo"}
@@ -0,0 +1,37 @@
+# === dpc:tick === #
+function test:late_spread_0
+function test:early_spread_0
+function test:main_spread_0
+
+# === test:early === #
+data modify storage dpc:spread test_early_0 set from storage test:data b
+
+# === test:early_spread_0 === #
+execute if data storage dpc:spread test_early_0[0] run function test:early_spread_0_step
+
+# === test:early_spread_0_step === #
+data modify storage dpc:r r_0 set from storage dpc:spread test_early_0[0]
+data remove storage dpc:spread test_early_0[0]
+say Early
+
+# === test:late === #
+data modify storage dpc:spread test_late_0 set from storage test:data a
+
+# === test:late_spread_0 === #
+execute if data storage dpc:spread test_late_0[0] run function test:late_spread_0_step
+
+# === test:late_spread_0_step === #
+data modify storage dpc:r r_0 set from storage dpc:spread test_late_0[0]
+data remove storage dpc:spread test_late_0[0]
+say Late
+
+# === test:main === #
+data modify storage dpc:spread test_main_0 set from storage test:data c
+
+# === test:main_spread_0 === #
+execute if data storage dpc:spread test_main_0[0] run function test:main_spread_0_step
+
+# === test:main_spread_0_step === #
+data modify storage dpc:r r_0 set from storage dpc:spread test_main_0[0]
+data remove storage dpc:spread test_main_0[0]
+say Main
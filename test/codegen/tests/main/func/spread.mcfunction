@@ -0,0 +1,36 @@
+# === dpc:tick === #
+function test:main_spread_0
+function test:other_spread_0
+
+# === test:done === #
+say Done
+
+# === test:main === #
+data modify storage dpc:spread test_main_0 set from storage test:data entries
+execute unless data storage dpc:spread test_main_0[0] run function test:done
+
+# === test:main_spread_0 === #
+execute if data storage dpc:spread test_main_0[0] run function test:main_spread_0_body_0
+
+# === test:main_spread_0_body_0 === #
+function test:main_spread_0_step
+execute if data storage dpc:spread test_main_0[0] run function test:main_spread_0_step
+execute if data storage dpc:spread test_main_0[0] run function test:main_spread_0_step
+execute unless data storage dpc:spread test_main_0[0] run function test:done
+
+# === test:main_spread_0_step === #
+data modify storage dpc:r r_0 set from storage dpc:spread test_main_0[0]
+data remove storage dpc:spread test_main_0[0]
+data modify storage test:data last set from storage dpc:r r_0
+say Processed
+
+# === test:other === #
+data modify storage dpc:spread test_other_0 set from storage test:data ids
+
+# === test:other_spread_0 === #
+execute if data storage dpc:spread test_other_0[0] run function test:other_spread_0_step
+
+# === test:other_spread_0_step === #
+data modify storage dpc:r r_0 set from storage dpc:spread test_other_0[0]
+data remove storage dpc:spread test_other_0[0]
+say Single
@@ -0,0 +1,21 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === dpc:load === #
+function dpc:init
+function test:main
+function test:setup
+function test:late_setup
+
+# === test:late_setup === #
+say Loaded
+
+# === test:loop === #
+say Tick
+
+# === test:main === #
+say Main
+
+# === test:setup === #
+scoreboard players set #r.0 _r 5
+scoreboard players operation @s setup.count = #r.0 _r
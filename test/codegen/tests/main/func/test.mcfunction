@@ -0,0 +1,33 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l3 _l 3
+
+# === dpc:run_tests === #
+function dpc:test/test/check_score
+function dpc:test/test/other/check_data
+
+# === dpc:test/test/check_score === #
+scoreboard players reset * _r
+scoreboard players set #test _r 1
+execute store success score #test _r run function test:check_score
+execute if score #test _r matches 1 run tellraw @a {"color":"green","text":"[PASS] test:check_score"}
+execute unless score #test _r matches 1 run tellraw @a {"color":"red","text":"[FAIL] test:check_score"}
+
+# === dpc:test/test/other/check_data === #
+scoreboard players reset * _r
+scoreboard players set #test _r 1
+execute store success score #test _r run function test:other/check_data
+execute if score #test _r matches 1 run tellraw @a {"color":"green","text":"[PASS] test:other/check_data"}
+execute unless score #test _r matches 1 run tellraw @a {"color":"red","text":"[FAIL] test:other/check_data"}
+
+# === test:check_score === #
+scoreboard players set #r.0 _r 7
+scoreboard players operation #r.0 _r *= #l3 _l
+scoreboard players operation @s foo = #r.0 _r
+
+# === test:main === #
+say Hello
+
+# === test:other/check_data === #
+data modify entity @s Tags set value "foo"
@@ -2,4 +2,4 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-execute as @s at @a[tag=foo] if score %rtest_main.0 _r = @s bar if score foo bar < @r[gamemode="Bar"] risk store result score %rtest_main.1 _r run data get entity @s name
+execute as @s at @a[tag=foo] if score #r.0 _r = @s bar if score foo bar < @r[name="Bar"] risk store result score #r.1 _r run data get entity @s name
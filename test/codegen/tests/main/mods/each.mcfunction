@@ -0,0 +1,6 @@
+# === test:main === #
+execute as @e[type=minecraft:zombie] run say Arbitrary
+execute as @e[tag=foo,limit=3,sort=nearest] run say Nearest
+execute as @a[sort=furthest] run say Furthest
+execute as @e[sort=random] run say Shuffled
+execute as @e[sort=arbitrary] run say Sorted
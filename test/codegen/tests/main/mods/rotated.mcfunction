@@ -0,0 +1,6 @@
+# === test:main === #
+execute rotated 90 ~-10.5 run say rotated
+execute rotated ~ -90 run say looking up
+execute rotated as @e[type=zombie,limit=1] run say rotated as
+tp @s ~ ~ ~ -180 ~45
+setworldspawn 0 64 0 ~30
@@ -3,5 +3,5 @@ scoreboard objectives add _r dummy
 
 # === test:main === #
 execute store result entity @s foo int .8 run say hello
-scoreboard players set %rtest_main.0 _r 7
-execute store success score %rtest_main.0 _r run data get entity baz foo 4.2
+scoreboard players set #r.0 _r 7
+execute store success score #r.0 _r run data get entity baz foo 4.2
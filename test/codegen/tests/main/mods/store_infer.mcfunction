@@ -0,0 +1,9 @@
+# === test:main === #
+data modify storage dpc:r rtest_main_0 set value 0
+execute store result storage dpc:r rtest_main_0 int 1 run time query daytime
+data modify storage dpc:r rtest_main_1 set value 0
+execute store result storage dpc:r rtest_main_1 double .5 run time query gametime
+execute store result storage test:data count long 1 run time query day
+execute store result storage dpc:r rtest_main_1 double 1 run data get storage dpc:r rtest_main_0 2
+data modify storage test:data x set from storage dpc:r rtest_main_0
+data modify storage test:data y set from storage dpc:r rtest_main_1
@@ -0,0 +1,9 @@
+# === test:main === #
+execute summon minecraft:zombie run say hello
+execute summon minecraft:pig run function test:main_body_0
+
+# === test:main_body_0 === #
+data merge entity @s {CustomName:"\"Foo\""}
+data modify storage dpc:r rtest_main_0 set from entity @s UUID
+say hello
+kill
@@ -0,0 +1,15 @@
+# === test:main === #
+fill 0 0 0 15 15 15 stone
+fill 0 0 0 99 2 99 air destroy
+fill 0 3 0 99 5 99 air destroy
+fill 0 6 0 99 8 99 air destroy
+fill 0 9 0 99 9 99 air destroy
+fill ~ ~ ~ ~40 ~18 ~40 glass keep
+fill ~ ~19 ~ ~40 ~37 ~40 glass keep
+fill ~ ~38 ~ ~40 ~40 ~40 glass keep
+clone 0 0 0 9 9 9 100 0 0
+clone 0 0 0 199 0 99 ~ ~10 ~ masked force
+clone 0 1 0 199 1 99 ~ ~11 ~ masked force
+clone 0 2 0 199 2 99 ~ ~12 ~ masked force
+clone 0 3 0 199 3 99 ~ ~13 ~ masked force
+clone 0 4 0 199 4 99 ~ ~14 ~ masked force
@@ -0,0 +1,8 @@
+# === test:later === #
+say later
+
+# === test:main === #
+schedule function test:later 5s
+schedule function test:later 30 append
+schedule function test:later 2d
+schedule clear test:later
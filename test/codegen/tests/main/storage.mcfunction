@@ -0,0 +1,17 @@
+# === dpc:init === #
+data remove storage test:tmp atest_other_0
+data remove storage test:tmp queue
+data remove storage test:tmp r_0
+data remove storage test:tmp state
+
+# === test:main === #
+data modify storage test:tmp r_0 set from storage test:save count
+data modify storage test:tmp state.current set from storage test:tmp r_0
+data modify storage test:tmp queue[0] set value 3
+data modify storage test:save count set value 4
+data modify storage test:tmp r_0 set value 2
+data modify storage test:tmp atest_other_0 set from storage test:tmp r_0
+function test:other
+
+# === test:other === #
+data modify storage test:save last set from storage test:tmp atest_other_0
@@ -0,0 +1,44 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #rtest_main.0 _r 7
+execute store success score #rtest_main.1 _r if score #rtest_main.0 _r matches 7
+execute if score #rtest_main.1 _r matches 1 run say seven
+execute if score #rtest_main.1 _r matches 0 run function test:main_body_0
+execute store success score #rtest_main.1 _r if score #rtest_main.0 _r matches 1
+execute if score #rtest_main.1 _r matches 1 run say one
+execute if score #rtest_main.1 _r matches 0 run function test:main_body_1
+execute store success score #rtest_main.1 _r if score #rtest_main.0 _r matches ..4
+execute if score #rtest_main.1 _r matches 1 run function test:main_body_3
+execute if score #rtest_main.1 _r matches 0 run function test:main_body_5
+
+# === test:main_body_0 === #
+execute store success score #rtest_main.2 _r if score #rtest_main.0 _r matches 8
+execute if score #rtest_main.2 _r matches 1 run say eight
+execute if score #rtest_main.2 _r matches 0 run say other
+
+# === test:main_body_1 === #
+execute store success score #rtest_main.2 _r if score #rtest_main.0 _r matches 2
+execute if score #rtest_main.2 _r matches 1 run say two
+execute if score #rtest_main.2 _r matches 0 run say default
+
+# === test:main_body_2 === #
+execute store success score #rtest_main.3 _r if score #rtest_main.0 _r matches 2
+execute if score #rtest_main.3 _r matches 1 run say two
+execute if score #rtest_main.0 _r matches 4 if score #rtest_main.3 _r matches 0 run say four
+
+# === test:main_body_3 === #
+execute store success score #rtest_main.2 _r if score #rtest_main.0 _r matches 1
+execute if score #rtest_main.2 _r matches 1 run say one
+execute if score #rtest_main.2 _r matches 0 run function test:main_body_2
+
+# === test:main_body_4 === #
+execute store success score #rtest_main.2 _r if score #rtest_main.0 _r matches 9
+execute if score #rtest_main.2 _r matches 1 run say nine
+execute if score #rtest_main.0 _r matches 12 if score #rtest_main.2 _r matches 0 run say twelve
+
+# === test:main_body_5 === #
+execute store success score #rtest_main.3 _r if score #rtest_main.0 _r matches 6
+execute if score #rtest_main.3 _r matches 1 run say six
+execute if score #rtest_main.3 _r matches 0 run function test:main_body_4
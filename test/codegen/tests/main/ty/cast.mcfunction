@@ -2,7 +2,7 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-execute store result storage dpc:r rtest_main_0 int 1 run scoreboard players get @s name
-scoreboard players set %rtest_main.0 _r 7
-scoreboard players operation %rtest_main.1 _r = %rtest_main.0 _r
-scoreboard players operation %rtest_main.0 _r = @s bar
+execute store result storage dpc:r r_0 int 1 run scoreboard players get @s name
+scoreboard players set #r.0 _r 7
+scoreboard players operation #r.1 _r = #r.0 _r
+scoreboard players operation #r.0 _r = @s bar
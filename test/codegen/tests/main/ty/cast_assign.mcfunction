@@ -0,0 +1,11 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #r.0 _r 150
+execute store result storage test:pos x double .001 run scoreboard players get #r.0 _r
+execute store result storage test:pos y short 1 run scoreboard players get #r.0 _r
+execute store result entity @s Motion[1] float .5 run data get storage test:pos x
+execute store result score #r.0 _r run data get storage test:pos x 1000
+data modify storage dpc:r r_0 set value 0l
+execute store result storage dpc:r r_0 long 1 run data get storage test:pos x 100
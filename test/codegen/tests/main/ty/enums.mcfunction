@@ -0,0 +1,18 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+execute store result score #r.0 _r run data get storage test:main started
+scoreboard players set #rtest_main.0 _r 0
+execute if score #r.0 _r matches 1 run scoreboard players set #rtest_main.0 _r 1
+execute store success score #rtest_main.1 _r if score #rtest_main.0 _r matches 0
+execute if score #rtest_main.1 _r matches 1 run say idle
+execute if score #rtest_main.1 _r matches 0 run function test:main_body_0
+execute store success score #r.0 _r if score #rtest_main.0 _r matches 2
+execute if score #r.0 _r matches 1 run say finished
+execute if score #r.0 _r matches 0 run scoreboard players set #rtest_main.0 _r 2
+
+# === test:main_body_0 === #
+execute store success score #rtest_main.2 _r if score #rtest_main.0 _r matches 1
+execute if score #rtest_main.2 _r matches 1 run say active
+execute if score #rtest_main.2 _r matches 0 run say done
@@ -0,0 +1,30 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l-32 _l -32
+scoreboard players set #l2 _l 2
+scoreboard players set #l16 _l 16
+
+# === test:main === #
+scoreboard players set #r.0 _r 0
+scoreboard players operation #r.1 _r = #r.0 _r
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players remove #r.1 _r 1
+scoreboard players operation #r.0 _r -= #r.1 _r
+scoreboard players operation #r.1 _r = #r.0 _r
+scoreboard players operation #r.1 _r /= #l16 _l
+scoreboard players operation #r.1 _r %= #l2 _l
+scoreboard players operation #r.1 _r *= #l-32 _l
+scoreboard players add #r.1 _r 16
+scoreboard players operation #r.0 _r += #r.1 _r
+scoreboard players set #r.1 _r 0
+scoreboard players operation #r.2 _r = #r.0 _r
+scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.2 _r %= #l2 _l
+scoreboard players operation #r.1 _r = #r.2 _r
+scoreboard players operation #r.2 _r = #r.0 _r
+scoreboard players operation #r.2 _r /= #l2 _l
+scoreboard players operation #r.2 _r %= #l2 _l
+scoreboard players operation #r.2 _r -= #r.1 _r
+scoreboard players operation #r.2 _r *= #l2 _l
+scoreboard players operation #r.0 _r -= #r.2 _r
@@ -0,0 +1,10 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+scoreboard players set #r.0 _r 150
+execute store result storage dpc:r r_0 double .05 run scoreboard players get #r.0 _r
+execute store result score #r.0 _r run data get storage dpc:r r_0 20
+execute store result storage dpc:r r_1 int 1 run data get storage dpc:r r_0 10
+data modify storage dpc:r r_1 set value 2.5
+scoreboard players set #r.0 _r 7
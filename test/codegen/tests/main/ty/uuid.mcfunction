@@ -0,0 +1,15 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:main === #
+data modify storage dpc:r r_0 set value [I;-132296786,2112623056,-1486552928,-920753162]
+data modify storage dpc:r r_1 set from entity @s UUID
+data modify storage dpc:r r_2 set from storage dpc:r r_0
+execute store success score #r.0 _r run data modify storage dpc:r r_2 set from storage dpc:r r_1
+execute if score #r.0 _r matches 0 run say same
+data modify storage dpc:r r_2 set from storage dpc:r r_1
+execute store success score #r.0 _r run data modify storage dpc:r r_2 set value [I;0,0,0,1]
+execute unless score #r.0 _r matches 0 run say different
+
+# === test:target === #
+kill f81d4fae-7dec-11d0-a765-00a0c91e6bf6
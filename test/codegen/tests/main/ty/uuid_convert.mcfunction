@@ -0,0 +1,437 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l16 _l 16
+
+# === dpc:uuid/from_string === #
+data modify storage dpc:r __uuid_in set from storage dpc:r adpc_uuid_from_string_0
+data modify storage dpc:r __uuid_values set value {"0":0,"1":1,"2":2,"3":3,"4":4,"5":5,"6":6,"7":7,"8":8,"9":9,"a":10,"b":11,"c":12,"d":13,"e":14,"f":15,"A":10,"B":11,"C":12,"D":13,"E":14,"F":15}
+data modify storage dpc:r __uuid_nibbles set value {}
+data modify storage dpc:r __uuid_chars.c0 set string storage dpc:r __uuid_in 0 1
+data modify storage dpc:r __uuid_chars.c1 set string storage dpc:r __uuid_in 1 2
+data modify storage dpc:r __uuid_chars.c2 set string storage dpc:r __uuid_in 2 3
+data modify storage dpc:r __uuid_chars.c3 set string storage dpc:r __uuid_in 3 4
+data modify storage dpc:r __uuid_chars.c4 set string storage dpc:r __uuid_in 4 5
+data modify storage dpc:r __uuid_chars.c5 set string storage dpc:r __uuid_in 5 6
+data modify storage dpc:r __uuid_chars.c6 set string storage dpc:r __uuid_in 6 7
+data modify storage dpc:r __uuid_chars.c7 set string storage dpc:r __uuid_in 7 8
+data modify storage dpc:r __uuid_chars.c8 set string storage dpc:r __uuid_in 9 10
+data modify storage dpc:r __uuid_chars.c9 set string storage dpc:r __uuid_in 10 11
+data modify storage dpc:r __uuid_chars.c10 set string storage dpc:r __uuid_in 11 12
+data modify storage dpc:r __uuid_chars.c11 set string storage dpc:r __uuid_in 12 13
+data modify storage dpc:r __uuid_chars.c12 set string storage dpc:r __uuid_in 14 15
+data modify storage dpc:r __uuid_chars.c13 set string storage dpc:r __uuid_in 15 16
+data modify storage dpc:r __uuid_chars.c14 set string storage dpc:r __uuid_in 16 17
+data modify storage dpc:r __uuid_chars.c15 set string storage dpc:r __uuid_in 17 18
+data modify storage dpc:r __uuid_chars.c16 set string storage dpc:r __uuid_in 19 20
+data modify storage dpc:r __uuid_chars.c17 set string storage dpc:r __uuid_in 20 21
+data modify storage dpc:r __uuid_chars.c18 set string storage dpc:r __uuid_in 21 22
+data modify storage dpc:r __uuid_chars.c19 set string storage dpc:r __uuid_in 22 23
+data modify storage dpc:r __uuid_chars.c20 set string storage dpc:r __uuid_in 24 25
+data modify storage dpc:r __uuid_chars.c21 set string storage dpc:r __uuid_in 25 26
+data modify storage dpc:r __uuid_chars.c22 set string storage dpc:r __uuid_in 26 27
+data modify storage dpc:r __uuid_chars.c23 set string storage dpc:r __uuid_in 27 28
+data modify storage dpc:r __uuid_chars.c24 set string storage dpc:r __uuid_in 28 29
+data modify storage dpc:r __uuid_chars.c25 set string storage dpc:r __uuid_in 29 30
+data modify storage dpc:r __uuid_chars.c26 set string storage dpc:r __uuid_in 30 31
+data modify storage dpc:r __uuid_chars.c27 set string storage dpc:r __uuid_in 31 32
+data modify storage dpc:r __uuid_chars.c28 set string storage dpc:r __uuid_in 32 33
+data modify storage dpc:r __uuid_chars.c29 set string storage dpc:r __uuid_in 33 34
+data modify storage dpc:r __uuid_chars.c30 set string storage dpc:r __uuid_in 34 35
+data modify storage dpc:r __uuid_chars.c31 set string storage dpc:r __uuid_in 35 36
+function dpc:uuid/lookup with storage dpc:r __uuid_chars
+data modify storage dpc:r r_0 set value [I;0,0,0,0]
+scoreboard players set #r.0 _r 0
+scoreboard players set #r.1 _r 0
+data modify storage dpc:r r_1 set value 0
+scoreboard players set #r.0 _r 0
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c0
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c1
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c2
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c3
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c4
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c5
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c6
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c7
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result storage dpc:r r_1 int 1 run scoreboard players get #r.0 _r
+data modify storage dpc:r r_0[0] set from storage dpc:r r_1
+scoreboard players set #r.0 _r 0
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c8
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c9
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c10
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c11
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c12
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c13
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c14
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c15
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result storage dpc:r r_1 int 1 run scoreboard players get #r.0 _r
+data modify storage dpc:r r_0[1] set from storage dpc:r r_1
+scoreboard players set #r.0 _r 0
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c16
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c17
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c18
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c19
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c20
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c21
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c22
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c23
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result storage dpc:r r_1 int 1 run scoreboard players get #r.0 _r
+data modify storage dpc:r r_0[2] set from storage dpc:r r_1
+scoreboard players set #r.0 _r 0
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c24
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c25
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c26
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c27
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c28
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c29
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c30
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result score #r.1 _r run data get storage dpc:r __uuid_nibbles.c31
+scoreboard players operation #r.0 _r *= #l16 _l
+scoreboard players operation #r.0 _r += #r.1 _r
+execute store result storage dpc:r r_1 int 1 run scoreboard players get #r.0 _r
+data modify storage dpc:r r_0[3] set from storage dpc:r r_1
+data modify storage dpc:r Rdpc_uuid_from_string_0 set from storage dpc:r r_0
+
+# === dpc:uuid/hex === #
+$data modify storage dpc:r __uuid_hex.h0 set from storage dpc:r __uuid_digits[$(h0)]
+$data modify storage dpc:r __uuid_hex.h1 set from storage dpc:r __uuid_digits[$(h1)]
+$data modify storage dpc:r __uuid_hex.h2 set from storage dpc:r __uuid_digits[$(h2)]
+$data modify storage dpc:r __uuid_hex.h3 set from storage dpc:r __uuid_digits[$(h3)]
+$data modify storage dpc:r __uuid_hex.h4 set from storage dpc:r __uuid_digits[$(h4)]
+$data modify storage dpc:r __uuid_hex.h5 set from storage dpc:r __uuid_digits[$(h5)]
+$data modify storage dpc:r __uuid_hex.h6 set from storage dpc:r __uuid_digits[$(h6)]
+$data modify storage dpc:r __uuid_hex.h7 set from storage dpc:r __uuid_digits[$(h7)]
+$data modify storage dpc:r __uuid_hex.h8 set from storage dpc:r __uuid_digits[$(h8)]
+$data modify storage dpc:r __uuid_hex.h9 set from storage dpc:r __uuid_digits[$(h9)]
+$data modify storage dpc:r __uuid_hex.h10 set from storage dpc:r __uuid_digits[$(h10)]
+$data modify storage dpc:r __uuid_hex.h11 set from storage dpc:r __uuid_digits[$(h11)]
+$data modify storage dpc:r __uuid_hex.h12 set from storage dpc:r __uuid_digits[$(h12)]
+$data modify storage dpc:r __uuid_hex.h13 set from storage dpc:r __uuid_digits[$(h13)]
+$data modify storage dpc:r __uuid_hex.h14 set from storage dpc:r __uuid_digits[$(h14)]
+$data modify storage dpc:r __uuid_hex.h15 set from storage dpc:r __uuid_digits[$(h15)]
+$data modify storage dpc:r __uuid_hex.h16 set from storage dpc:r __uuid_digits[$(h16)]
+$data modify storage dpc:r __uuid_hex.h17 set from storage dpc:r __uuid_digits[$(h17)]
+$data modify storage dpc:r __uuid_hex.h18 set from storage dpc:r __uuid_digits[$(h18)]
+$data modify storage dpc:r __uuid_hex.h19 set from storage dpc:r __uuid_digits[$(h19)]
+$data modify storage dpc:r __uuid_hex.h20 set from storage dpc:r __uuid_digits[$(h20)]
+$data modify storage dpc:r __uuid_hex.h21 set from storage dpc:r __uuid_digits[$(h21)]
+$data modify storage dpc:r __uuid_hex.h22 set from storage dpc:r __uuid_digits[$(h22)]
+$data modify storage dpc:r __uuid_hex.h23 set from storage dpc:r __uuid_digits[$(h23)]
+$data modify storage dpc:r __uuid_hex.h24 set from storage dpc:r __uuid_digits[$(h24)]
+$data modify storage dpc:r __uuid_hex.h25 set from storage dpc:r __uuid_digits[$(h25)]
+$data modify storage dpc:r __uuid_hex.h26 set from storage dpc:r __uuid_digits[$(h26)]
+$data modify storage dpc:r __uuid_hex.h27 set from storage dpc:r __uuid_digits[$(h27)]
+$data modify storage dpc:r __uuid_hex.h28 set from storage dpc:r __uuid_digits[$(h28)]
+$data modify storage dpc:r __uuid_hex.h29 set from storage dpc:r __uuid_digits[$(h29)]
+$data modify storage dpc:r __uuid_hex.h30 set from storage dpc:r __uuid_digits[$(h30)]
+$data modify storage dpc:r __uuid_hex.h31 set from storage dpc:r __uuid_digits[$(h31)]
+
+# === dpc:uuid/join === #
+$data modify storage dpc:r __uuid_out set value "$(h0)$(h1)$(h2)$(h3)$(h4)$(h5)$(h6)$(h7)-$(h8)$(h9)$(h10)$(h11)-$(h12)$(h13)$(h14)$(h15)-$(h16)$(h17)$(h18)$(h19)-$(h20)$(h21)$(h22)$(h23)$(h24)$(h25)$(h26)$(h27)$(h28)$(h29)$(h30)$(h31)"
+
+# === dpc:uuid/lookup === #
+$data modify storage dpc:r __uuid_nibbles.c0 set from storage dpc:r __uuid_values."$(c0)"
+$data modify storage dpc:r __uuid_nibbles.c1 set from storage dpc:r __uuid_values."$(c1)"
+$data modify storage dpc:r __uuid_nibbles.c2 set from storage dpc:r __uuid_values."$(c2)"
+$data modify storage dpc:r __uuid_nibbles.c3 set from storage dpc:r __uuid_values."$(c3)"
+$data modify storage dpc:r __uuid_nibbles.c4 set from storage dpc:r __uuid_values."$(c4)"
+$data modify storage dpc:r __uuid_nibbles.c5 set from storage dpc:r __uuid_values."$(c5)"
+$data modify storage dpc:r __uuid_nibbles.c6 set from storage dpc:r __uuid_values."$(c6)"
+$data modify storage dpc:r __uuid_nibbles.c7 set from storage dpc:r __uuid_values."$(c7)"
+$data modify storage dpc:r __uuid_nibbles.c8 set from storage dpc:r __uuid_values."$(c8)"
+$data modify storage dpc:r __uuid_nibbles.c9 set from storage dpc:r __uuid_values."$(c9)"
+$data modify storage dpc:r __uuid_nibbles.c10 set from storage dpc:r __uuid_values."$(c10)"
+$data modify storage dpc:r __uuid_nibbles.c11 set from storage dpc:r __uuid_values."$(c11)"
+$data modify storage dpc:r __uuid_nibbles.c12 set from storage dpc:r __uuid_values."$(c12)"
+$data modify storage dpc:r __uuid_nibbles.c13 set from storage dpc:r __uuid_values."$(c13)"
+$data modify storage dpc:r __uuid_nibbles.c14 set from storage dpc:r __uuid_values."$(c14)"
+$data modify storage dpc:r __uuid_nibbles.c15 set from storage dpc:r __uuid_values."$(c15)"
+$data modify storage dpc:r __uuid_nibbles.c16 set from storage dpc:r __uuid_values."$(c16)"
+$data modify storage dpc:r __uuid_nibbles.c17 set from storage dpc:r __uuid_values."$(c17)"
+$data modify storage dpc:r __uuid_nibbles.c18 set from storage dpc:r __uuid_values."$(c18)"
+$data modify storage dpc:r __uuid_nibbles.c19 set from storage dpc:r __uuid_values."$(c19)"
+$data modify storage dpc:r __uuid_nibbles.c20 set from storage dpc:r __uuid_values."$(c20)"
+$data modify storage dpc:r __uuid_nibbles.c21 set from storage dpc:r __uuid_values."$(c21)"
+$data modify storage dpc:r __uuid_nibbles.c22 set from storage dpc:r __uuid_values."$(c22)"
+$data modify storage dpc:r __uuid_nibbles.c23 set from storage dpc:r __uuid_values."$(c23)"
+$data modify storage dpc:r __uuid_nibbles.c24 set from storage dpc:r __uuid_values."$(c24)"
+$data modify storage dpc:r __uuid_nibbles.c25 set from storage dpc:r __uuid_values."$(c25)"
+$data modify storage dpc:r __uuid_nibbles.c26 set from storage dpc:r __uuid_values."$(c26)"
+$data modify storage dpc:r __uuid_nibbles.c27 set from storage dpc:r __uuid_values."$(c27)"
+$data modify storage dpc:r __uuid_nibbles.c28 set from storage dpc:r __uuid_values."$(c28)"
+$data modify storage dpc:r __uuid_nibbles.c29 set from storage dpc:r __uuid_values."$(c29)"
+$data modify storage dpc:r __uuid_nibbles.c30 set from storage dpc:r __uuid_values."$(c30)"
+$data modify storage dpc:r __uuid_nibbles.c31 set from storage dpc:r __uuid_values."$(c31)"
+
+# === dpc:uuid/to_string === #
+data modify storage dpc:r rdpc_uuid_to_string_0 set from storage dpc:r adpc_uuid_to_string_0
+data modify storage dpc:r rdpc_uuid_to_string_1 set value {}
+scoreboard players set #rdpc_uuid_to_string.0 _r 0
+scoreboard players set #rdpc_uuid_to_string.1 _r 0
+scoreboard players set #rdpc_uuid_to_string.2 _r 0
+data modify storage dpc:r rdpc_uuid_to_string_2 set value 0
+execute store result score #rdpc_uuid_to_string.0 _r run data get storage dpc:r rdpc_uuid_to_string_0[0]
+scoreboard players set #rdpc_uuid_to_string.1 _r 0
+execute if score #rdpc_uuid_to_string.0 _r matches ..-1 run function dpc:uuid/to_string_body_0
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h7 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h6 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h5 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h4 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h3 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h2 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h1 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.0 _r += #rdpc_uuid_to_string.1 _r
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.0 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h0 set from storage dpc:r rdpc_uuid_to_string_2
+execute store result score #rdpc_uuid_to_string.0 _r run data get storage dpc:r rdpc_uuid_to_string_0[1]
+scoreboard players set #rdpc_uuid_to_string.1 _r 0
+execute if score #rdpc_uuid_to_string.0 _r matches ..-1 run function dpc:uuid/to_string_body_1
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h15 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h14 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h13 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h12 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h11 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h10 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h9 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.0 _r += #rdpc_uuid_to_string.1 _r
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.0 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h8 set from storage dpc:r rdpc_uuid_to_string_2
+execute store result score #rdpc_uuid_to_string.0 _r run data get storage dpc:r rdpc_uuid_to_string_0[2]
+scoreboard players set #rdpc_uuid_to_string.1 _r 0
+execute if score #rdpc_uuid_to_string.0 _r matches ..-1 run function dpc:uuid/to_string_body_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h23 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h22 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h21 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h20 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h19 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h18 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h17 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.0 _r += #rdpc_uuid_to_string.1 _r
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.0 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h16 set from storage dpc:r rdpc_uuid_to_string_2
+execute store result score #rdpc_uuid_to_string.0 _r run data get storage dpc:r rdpc_uuid_to_string_0[3]
+scoreboard players set #rdpc_uuid_to_string.1 _r 0
+execute if score #rdpc_uuid_to_string.0 _r matches ..-1 run function dpc:uuid/to_string_body_3
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h31 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h30 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h29 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h28 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h27 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h26 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.2 _r = #rdpc_uuid_to_string.0 _r
+scoreboard players operation #rdpc_uuid_to_string.2 _r %= #l16 _l
+scoreboard players operation #rdpc_uuid_to_string.0 _r /= #l16 _l
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.2 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h25 set from storage dpc:r rdpc_uuid_to_string_2
+scoreboard players operation #rdpc_uuid_to_string.0 _r += #rdpc_uuid_to_string.1 _r
+execute store result storage dpc:r rdpc_uuid_to_string_2 int 1 run scoreboard players get #rdpc_uuid_to_string.0 _r
+data modify storage dpc:r rdpc_uuid_to_string_1.h24 set from storage dpc:r rdpc_uuid_to_string_2
+data modify storage dpc:r __uuid_digits set value ["0","1","2","3","4","5","6","7","8","9","a","b","c","d","e","f"]
+function dpc:uuid/hex with storage dpc:r rdpc_uuid_to_string_1
+function dpc:uuid/join with storage dpc:r __uuid_hex
+data modify storage dpc:r Rdpc_uuid_to_string_0 set from storage dpc:r __uuid_out
+
+# === dpc:uuid/to_string_body_0 === #
+scoreboard players add #rdpc_uuid_to_string.0 _r 2147483647
+scoreboard players add #rdpc_uuid_to_string.0 _r 1
+scoreboard players set #rdpc_uuid_to_string.1 _r 8
+
+# === dpc:uuid/to_string_body_1 === #
+scoreboard players add #rdpc_uuid_to_string.0 _r 2147483647
+scoreboard players add #rdpc_uuid_to_string.0 _r 1
+scoreboard players set #rdpc_uuid_to_string.1 _r 8
+
+# === dpc:uuid/to_string_body_2 === #
+scoreboard players add #rdpc_uuid_to_string.0 _r 2147483647
+scoreboard players add #rdpc_uuid_to_string.0 _r 1
+scoreboard players set #rdpc_uuid_to_string.1 _r 8
+
+# === dpc:uuid/to_string_body_3 === #
+scoreboard players add #rdpc_uuid_to_string.0 _r 2147483647
+scoreboard players add #rdpc_uuid_to_string.0 _r 1
+scoreboard players set #rdpc_uuid_to_string.1 _r 8
+
+# === test:main === #
+data modify storage dpc:r rtest_main_0 set from entity @s UUID
+data modify storage dpc:r rtest_main_1 set value ""
+data modify storage dpc:r adpc_uuid_to_string_0 set from storage dpc:r rtest_main_0
+function dpc:uuid/to_string
+data modify storage dpc:r rtest_main_1 set from storage dpc:r Rdpc_uuid_to_string_0
+tellraw @a {"nbt":"rtest_main_1","storage":"dpc:r"}
+data modify storage dpc:r rtest_main_2 set value [I;0,0,0,1]
+data modify storage dpc:r adpc_uuid_from_string_0 set from storage dpc:r rtest_main_1
+function dpc:uuid/from_string
+data modify storage dpc:r rtest_main_2 set from storage dpc:r Rdpc_uuid_from_string_0
+data modify storage dpc:r r_0 set from storage dpc:r rtest_main_0
+execute store success score #r.0 _r run data modify storage dpc:r r_0 set from storage dpc:r rtest_main_2
+execute if score #r.0 _r matches 0 run say same
@@ -2,7 +2,7 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 120
-scoreboard players set %rtest_main.1 _r 991
-execute store success score %rtest_main.2 _r if score %rtest_main.0 _r = %rtest_main.1 _r
-execute store success score %rtest_main.2 _r unless score %rtest_main.0 _r = %rtest_main.1 _r
+scoreboard players set #r.0 _r 120
+scoreboard players set #r.1 _r 991
+execute store success score #r.2 _r if score #r.0 _r = #r.1 _r
+execute store success score #r.2 _r unless score #r.0 _r = #r.1 _r
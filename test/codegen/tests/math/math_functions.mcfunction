@@ -2,4 +2,4 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 117
+scoreboard players set #r.0 _r 117
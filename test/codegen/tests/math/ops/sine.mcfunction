@@ -2,56 +2,56 @@
 # === dpc:init === #
 scoreboard objectives add _r dummy
 scoreboard objectives add _l dummy
-scoreboard players set %l1800 _l 1800
-scoreboard players set %l3600 _l 3600
+scoreboard players set #l1800 _l 1800
+scoreboard players set #l3600 _l 3600
 
 # === test:main === #
-scoreboard players set %atest_sine.0 _r 104
+scoreboard players set #atest_sine.0 _r 104
 function test:sine
-scoreboard players operation %rtest_main.0 _r = %Rtest_sine.0 _r
-scoreboard players set %atest_sine.0 _r 104
+scoreboard players operation #r.0 _r = #Rtest_sine.0 _r
+scoreboard players set #atest_sine.0 _r 104
 function test:sine
-scoreboard players operation %rtest_main.0 _r = %Rtest_sine.0 _r
-scoreboard players set %atest_sine.0 _r 1660
+scoreboard players operation #rtest_main.0 _r = #Rtest_sine.0 _r
+scoreboard players set #atest_sine.0 _r 1660
 function test:sine
-scoreboard players operation %rtest_main.0 _r = %Rtest_sine.0 _r
+scoreboard players operation #rtest_main.0 _r = #Rtest_sine.0 _r
 
 # === test:sine === #
-scoreboard players operation %rtest_sine.0 _r = %atest_sine.0 _r
-scoreboard players set %rtest_sine.1 _r -400
-scoreboard players operation %rtest_sine.0 _r %= %l3600 _l
-execute if score %rtest_sine.0 _r matches 1800.. run scoreboard players set %rtest_sine.1 _r 400
-scoreboard players operation %rtest_sine.0 _r %= %l1800 _l
-scoreboard players operation %rtest_sine.2 _r = %rtest_sine.0 _r
-scoreboard players remove %rtest_sine.2 _r 1800
-scoreboard players operation %rtest_sine.2 _r *= %rtest_sine.0 _r
-scoreboard players operation %rtest_sine.0 _r = %rtest_sine.2 _r
-scoreboard players operation %rtest_sine.0 _r *= %rtest_sine.1 _r
-scoreboard players add %rtest_sine.2 _r 4050000
-scoreboard players operation %rtest_sine.0 _r /= %rtest_sine.2 _r
-execute if score %rtest_sine.1 _r matches 400 run scoreboard players add %rtest_sine.0 _r 1
-scoreboard players operation %Rtest_sine.0 _r = %rtest_sine.0 _r
+scoreboard players operation #r.0 _r = #atest_sine.0 _r
+scoreboard players set #r.1 _r -400
+scoreboard players operation #r.0 _r %= #l3600 _l
+execute if score #r.0 _r matches 1800.. run scoreboard players set #r.1 _r 400
+scoreboard players operation #r.0 _r %= #l1800 _l
+scoreboard players operation #r.2 _r = #r.0 _r
+scoreboard players remove #r.2 _r 1800
+scoreboard players operation #r.2 _r *= #r.0 _r
+scoreboard players operation #r.0 _r = #r.2 _r
+scoreboard players operation #r.0 _r *= #r.1 _r
+scoreboard players add #r.2 _r 4050000
+scoreboard players operation #r.0 _r /= #r.2 _r
+execute if score #r.1 _r matches 400 run scoreboard players add #r.0 _r 1
+scoreboard players operation #Rtest_sine.0 _r = #r.0 _r
 
 ######## opt ########
 # === dpc:init === #
 scoreboard objectives add _r dummy
 scoreboard objectives add _l dummy
-scoreboard players set %l1800 _l 1800
-scoreboard players set %l3600 _l 3600
+scoreboard players set #l1800 _l 1800
+scoreboard players set #l3600 _l 3600
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 18
-scoreboard players set %rtest_main.0 _r 18
-scoreboard players set %rtest_main.0 _r 24
+scoreboard players set #r.0 _r 18
+scoreboard players set #r.0 _r 18
+scoreboard players set #r.0 _r 24
 
 # === test:sine === #
-scoreboard players set %rtest_sine.0 _r -400
-scoreboard players operation %atest_sine.0 _r %= %l3600 _l
-execute if score %atest_sine.0 _r matches 1800.. run scoreboard players set %rtest_sine.0 _r 400
-execute store result score %rtest_sine.1 _r run scoreboard players operation %atest_sine.0 _r %= %l1800 _l
-scoreboard players remove %rtest_sine.1 _r 1800
-execute store result score %Rtest_sine.0 _r run scoreboard players operation %rtest_sine.1 _r *= %atest_sine.0 _r
-scoreboard players operation %Rtest_sine.0 _r *= %rtest_sine.0 _r
-scoreboard players add %rtest_sine.1 _r 4050000
-scoreboard players operation %Rtest_sine.0 _r /= %rtest_sine.1 _r
-execute if score %rtest_sine.0 _r matches 400 run scoreboard players add %Rtest_sine.0 _r 1
+scoreboard players set #r.0 _r -400
+scoreboard players operation #atest_sine.0 _r %= #l3600 _l
+execute if score #atest_sine.0 _r matches 1800.. run scoreboard players set #r.0 _r 400
+execute store result score #r.1 _r run scoreboard players operation #atest_sine.0 _r %= #l1800 _l
+scoreboard players remove #r.1 _r 1800
+execute store result score #Rtest_sine.0 _r run scoreboard players operation #r.1 _r *= #atest_sine.0 _r
+scoreboard players operation #Rtest_sine.0 _r *= #r.0 _r
+scoreboard players add #r.1 _r 4050000
+scoreboard players operation #Rtest_sine.0 _r /= #r.1 _r
+execute if score #r.0 _r matches 400 run scoreboard players add #Rtest_sine.0 _r 1
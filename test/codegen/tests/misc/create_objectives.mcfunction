@@ -0,0 +1,11 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add kills dummy
+scoreboard objectives add points dummy
+
+# === test:main === #
+scoreboard players operation #r.0 _r = Player kills
+scoreboard players operation Player points = #r.0 _r
+scoreboard players add @s points 1
+scoreboard objectives add timer dummy
+scoreboard players set Player timer 5
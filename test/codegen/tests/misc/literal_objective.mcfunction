@@ -0,0 +1,15 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add dpc.lit dummy
+scoreboard players set #l3 dpc.lit 3
+scoreboard players set #l8 dpc.lit 8
+
+# === test:main === #
+scoreboard players set #r.0 _r 7
+scoreboard players operation #r.0 _r *= #l8 dpc.lit
+scoreboard players operation #r.0 _r /= #l3 dpc.lit
+function test:other
+
+# === test:other === #
+scoreboard players set #r.0 _r 2
+scoreboard players operation #r.0 _r *= #l8 dpc.lit
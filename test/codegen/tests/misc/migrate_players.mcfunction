@@ -0,0 +1,12 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard players reset * _r
+scoreboard objectives add _l dummy
+scoreboard players reset * _l
+scoreboard players set #l7 _l 7
+
+# === test:main === #
+scoreboard players set #r.0 _r 1
+scoreboard players add #r.0 _r 2
+execute if score #r.0 _r matches 101.. run say big
+scoreboard players operation #r.0 _r *= #l7 _l
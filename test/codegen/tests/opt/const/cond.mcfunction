@@ -8,10 +8,10 @@ execute if predicate foo:bar run say hello
 
 # === test:prop === #
 execute if score foo bar matches 5.. run say hello
-scoreboard players set %rtest_prop.0 _r 10
-scoreboard players operation %rtest_prop.1 _r = @s foo
-execute if score %rtest_prop.1 _r matches 15 run scoreboard players add %rtest_prop.0 _r 15
-scoreboard players operation %rtest_prop.1 _r = @s bar
-scoreboard players operation %rtest_prop.0 _r += %rtest_prop.1 _r
-scoreboard players set %rtest_prop.1 _r 1
-scoreboard players set %rtest_prop.1 _r 0
+scoreboard players set #r.0 _r 10
+scoreboard players operation #r.1 _r = @s foo
+execute if score #r.1 _r matches 15 run scoreboard players add #r.0 _r 15
+scoreboard players operation #r.1 _r = @s bar
+scoreboard players operation #r.0 _r += #r.1 _r
+scoreboard players set #r.1 _r 1
+scoreboard players set #r.1 _r 0
@@ -1,3 +1,3 @@
 # === test:main === #
-data modify storage dpc:r rtest_main_0 set value [7,3]
-data modify storage dpc:r rtest_main_0 append value 10
+data modify storage dpc:r r_0 set value [7,3]
+data modify storage dpc:r r_0 append value 10
@@ -2,6 +2,6 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 30
-scoreboard players set %rtest_main.1 _r 40
-scoreboard players set %rtest_main.1 _r 117
+scoreboard players set #r.0 _r 30
+scoreboard players set #r.1 _r 40
+scoreboard players set #r.1 _r 117
@@ -0,0 +1,12 @@
+# === dpc:init === #
+data modify storage dpc:r c0 set value {count:64,name:"A very long item name indeed",tags:["foo","bar","baz"]}
+
+# === test:main === #
+data modify storage test:data config set from storage dpc:r c0
+data modify storage test:data backup set from storage dpc:r c0
+data modify storage test:data single set value {count:32,name:"Only used in one function",tags:["foo","bar"]}
+function test:other
+
+# === test:other === #
+data modify storage test:data config merge from storage dpc:r c0
+data modify storage test:data small set value {a:1}
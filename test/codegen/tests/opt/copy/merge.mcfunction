@@ -0,0 +1,6 @@
+# === test:main === #
+data modify storage test:to b set from storage test:from b
+data modify storage test:to a set from storage test:from a
+data modify storage test:to c set from storage test:from c
+data modify storage test:other c.z set from storage test:from c.z
+data modify storage test:to c.w set from storage test:from c.w
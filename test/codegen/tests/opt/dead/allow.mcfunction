@@ -0,0 +1,13 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _c_health health
+
+# === test:main === #
+scoreboard players set #rtest_main.0 _r 0
+execute if score #rtest_main.0 _r matches 0 run function test:main_body_0
+scoreboard players set @s _c_health 20
+function test:other
+
+# === test:main_body_0 === #
+
+# === test:other === #
@@ -1,6 +1,10 @@
+# === test:extern === #
+say hello
+
 # === test:keep === #
 function test:keep2
 
 # === test:keep2 === #
 
 # === test:main === #
+function test:extern
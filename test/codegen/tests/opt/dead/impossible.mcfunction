@@ -0,0 +1,35 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:as_body === #
+scoreboard players set #rtest_as_body.0 _r 0
+execute as @e run function test:as_body_body_0
+
+# === test:as_body_body_0 === #
+execute if score #rtest_as_body.0 _r matches 1 run say second
+scoreboard players add #rtest_as_body.0 _r 1
+
+# === test:loop_body === #
+scoreboard players set #rtest_loop_body.0 _r 0
+scoreboard players operation #atest_loop_body_loop_0.0 _r = #rtest_loop_body.0 _r
+function test:loop_body_loop_0
+scoreboard players operation #rtest_loop_body.0 _r = #Rtest_loop_body_loop_0.0 _r
+
+# === test:loop_body_loop_0 === #
+scoreboard players operation #rtest_loop_body_loop_0.0 _r = #atest_loop_body_loop_0.0 _r
+execute if score #rtest_loop_body_loop_0.0 _r matches ..9 run function test:loop_body_loop_0_body_0
+scoreboard players operation #Rtest_loop_body_loop_0.0 _r = #rtest_loop_body_loop_0.0 _r
+
+# === test:loop_body_loop_0_body_0 === #
+execute if score #rtest_loop_body_loop_0.0 _r matches 5 run say five
+scoreboard players add #rtest_loop_body_loop_0.0 _r 1
+scoreboard players operation #atest_loop_body_loop_0.0 _r = #rtest_loop_body_loop_0.0 _r
+function test:loop_body_loop_0
+scoreboard players operation #rtest_loop_body_loop_0.0 _r = #Rtest_loop_body_loop_0.0 _r
+
+# === test:main === #
+scoreboard players set #r.0 _r 0
+execute if score #r.0 _r matches 0 run say always
+execute as @e[nbt={},tag=foo] run say maybe
+scoreboard players add #r.0 _r 1
+execute if score #r.0 _r matches 1 run say unknown
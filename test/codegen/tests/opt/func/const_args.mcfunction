@@ -0,0 +1,16 @@
+# === dpc:init === #
+data modify storage dpc:r c0 set value {count:64,name:"A very long item name indeed",tags:["foo","bar","baz"]}
+data modify storage dpc:r c1 set value {count:32,name:"Another long item name",tags:["foo","bar","baz"]}
+
+# === test:main === #
+function test:read
+function test:read
+data modify storage dpc:r atest_modify_0 set from storage dpc:r c0
+function test:modify
+data modify storage dpc:r atest_modify_0 set from storage dpc:r c1
+function test:modify
+
+# === test:modify === #
+data modify storage dpc:r atest_modify_0.modified set value 1b
+
+# === test:read === #
@@ -0,0 +1,67 @@
+# === test:cold === #
+say 0
+say 1
+say 2
+say 3
+say 4
+say 5
+function test:medium
+
+# === test:hot === #
+say 0
+say 1
+say 2
+say 3
+say 4
+say 5
+say 0
+say 1
+say 2
+say 3
+say 4
+say 5
+say 6
+say 7
+say 8
+say 9
+say 10
+say 11
+say 12
+say 13
+say 14
+say 15
+say 16
+say 17
+say 18
+say 19
+
+# === test:main === #
+say 0
+say 1
+say 2
+say 3
+say 4
+say 5
+function test:medium
+
+# === test:medium === #
+say 0
+say 1
+say 2
+say 3
+say 4
+say 5
+say 6
+say 7
+say 8
+say 9
+say 10
+say 11
+say 12
+say 13
+say 14
+say 15
+say 16
+say 17
+say 18
+say 19
@@ -0,0 +1,5 @@
+# === test:main === #
+execute in minecraft:the_nether positioned 1 2 3 run setblock ~ ~ ~ stone
+execute positioned ~1 ~ ~ in minecraft:the_nether run setblock ~ ~ ~ stone
+execute at @s positioned 1 2 3 run setblock ~ ~ ~ stone
+setblock ~ ~ ~ stone
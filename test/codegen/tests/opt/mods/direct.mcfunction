@@ -0,0 +1,11 @@
+# === test:main === #
+tag Admin add admin
+scoreboard players set Admin foo 5
+kill Admin
+tag Admin remove admin
+execute as Admin run custom command
+execute as @a[tag=foo] run kill
+execute as Admin run say Messages are sent with the executor's name
+team join red @a[name="Admin"]
+execute as Admin run team join red
+w Admin Players that aren't loaded can't be told anything
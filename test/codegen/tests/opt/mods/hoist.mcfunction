@@ -0,0 +1,32 @@
+# === test:main === #
+execute as Admin run function test:main_hoist_0
+say -
+execute as Admin run say 1
+execute as Admin run say 2
+say -
+execute as Admin run function test:main_hoist_1
+say -
+execute at @s run tag @p add 6
+execute at @s run tag @p add 7
+execute at @s run tp @s ~ ~1 ~
+execute at @s run function test:main_hoist_2
+say -
+execute as @e run say 12
+execute as @e run say 13
+execute as @e run say 14
+
+# === test:main_hoist_0 === #
+kill @s
+say hi
+say bye
+
+# === test:main_hoist_1 === #
+execute positioned 1 2 3 run say 3
+say 4
+say 5
+
+# === test:main_hoist_2 === #
+tag @p add 8
+tag @p add 9
+tag @p add 10
+tag @p add 11
@@ -0,0 +1,8 @@
+# === test:main === #
+execute as @e[type=zombie,tag=foo] run kill
+execute as @a[tag=foo] if entity @s[tag=bar] run say hi
+execute as @a[tag=foo] if entity @s run say hi
+execute as @e[type=player] if entity @s run say hi
+execute as @a if entity @e run say hi
+execute as @e[distance=5] if entity @e[distance=5] run say hi
+execute as @e[tag=foo] as @e run kill @s[tag=foo]
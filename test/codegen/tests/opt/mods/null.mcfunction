@@ -2,7 +2,7 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 0
-scoreboard players add %rtest_main.0 _r 2
+scoreboard players set #r.0 _r 0
+scoreboard players add #r.0 _r 2
 execute as @e run say hello
 execute as @e[type=zombie] as @e[type=wolf] run custom command
@@ -2,9 +2,9 @@
 scoreboard objectives add _r dummy
 
 # === test:main === #
-scoreboard players set %rtest_main.0 _r 0
-execute if score %rtest_main.0 _r matches ..2147483647 run say hello
-execute if score %rtest_main.0 _r matches 1 run say hello
+scoreboard players set #r.0 _r 0
+execute if score #r.0 _r matches ..2147483647 run say hello
+execute if score #r.0 _r matches 1 run say hello
 say guaranteed 1
 say guaranteed 2
 say guaranteed 3
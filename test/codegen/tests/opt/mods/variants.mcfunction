@@ -0,0 +1,20 @@
+# === test:main === #
+execute as @e[type=zombie,tag=foo] run function test:zombie
+execute as @e[type=zombie] run function test:shared_ctx_0
+execute as @a run function test:shared_ctx_1
+function test:shared
+
+# === test:shared === #
+kill @s[type=zombie]
+execute if entity @s[type=player] run say hi
+
+# === test:shared_ctx_0 === #
+kill
+execute if entity @s[type=player] run say hi
+
+# === test:shared_ctx_1 === #
+kill @s[type=zombie]
+execute if entity @s run say hi
+
+# === test:zombie === #
+kill @s[tag=foo]
@@ -0,0 +1,21 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+
+# === test:first === #
+say first
+function test:first_resets
+
+# === test:first_resets === #
+scoreboard players reset @s[type=player] foo
+scoreboard players reset #bar foo
+scoreboard players reset #baz foo
+
+# === test:main === #
+scoreboard players set #rtest_main.0 _r 7
+function test:first
+function test:second
+scoreboard players reset #rtest_main.0
+
+# === test:second === #
+say second
+function test:first_resets
@@ -0,0 +1,32 @@
+# === dpc:init === #
+scoreboard objectives add _r dummy
+scoreboard objectives add _l dummy
+scoreboard players set #l5 _l 5
+scoreboard players set #l8 _l 8
+
+# === test:bool_conditions === #
+execute store result score #r.0 _r run random value 0..1
+execute if score #r.0 _r matches 1 run say Positive
+execute if score #r.0 _r matches ..0 run say Zero
+execute if score #r.0 _r matches 1.. run say One
+
+# === test:clamps === #
+execute store result score #r.0 _r run random value 0..10
+scoreboard players operation #r.0 _r > #l5 _l
+scoreboard players operation #r.0 _r < #l8 _l
+scoreboard players operation foo bar = #r.0 _r
+
+# === test:logic === #
+execute store success score #r.0 _r if predicate foo:bar
+execute store success score #r.1 _r unless predicate foo:baz
+execute store result score #r.2 _r run random value 2..3
+scoreboard players operation foo x = #r.0 _r
+scoreboard players operation foo y = #r.1 _r
+scoreboard players set foo z 0
+
+# === test:main === #
+
+# === test:store_result === #
+execute store result score #rtest_store_result.0 _r run random value 0..1
+execute store result score #rtest_store_result.0 _r run time query daytime
+execute if score #rtest_store_result.0 _r matches 1.. run say Positive
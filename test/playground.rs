@@ -155,7 +155,8 @@ fn known() {
 			ty: DataType::Score(ScoreType::Score),
 			right: DeclareBinding::Cast(
 				DataType::Score(ScoreType::Score),
-				MutableValue::Reg(reg4_id.clone()),
+				Value::Mutable(MutableValue::Reg(reg4_id.clone())),
+				1.0,
 			),
 		};
 		InstrKind::Mul {
@@ -403,6 +404,74 @@ fn fuzz() {
 	}
 }
 
+/// Measures how fast commands are generated from LIR, which is
+/// dominated by building the strings for scores
+#[allow(dead_code)]
+fn bench_codegen() {
+	let fn_count = 5000;
+	let instr_count = 50;
+	let iterations = 5;
+
+	let mut ir = IR::new();
+	for fn_i in 0..fn_count {
+		let mut block = Block::new();
+		for reg in 0..4 {
+			block.contents.push(Instruction::new(InstrKind::Declare {
+				left: Identifier::from(format!("reg{reg}")),
+				ty: DataType::Score(ScoreType::Score),
+				right: DeclareBinding::Value(Value::Constant(DataTypeContents::Score(
+					ScoreTypeContents::Score(reg),
+				))),
+			}));
+		}
+		for i in 0..instr_count {
+			let left = MutableValue::Reg(Identifier::from(format!("reg{}", i % 4)));
+			let right = if i % 2 == 0 {
+				Value::Mutable(MutableValue::Reg(Identifier::from(format!(
+					"reg{}",
+					(i + 1) % 4
+				))))
+			} else {
+				Value::Constant(DataTypeContents::Score(ScoreTypeContents::Score(i % 16)))
+			};
+			block
+				.contents
+				.push(Instruction::new(InstrKind::Mul { left, right }));
+		}
+
+		let id = Identifier::from(format!("test:fn{fn_i}"));
+		let mut annotations = FunctionAnnotations::new();
+		annotations.preserve = true;
+		ir.functions.insert(
+			id.clone(),
+			IRFunction {
+				interface: FunctionInterface::with_all(id, FunctionSignature::new(), annotations),
+				block,
+			},
+		);
+	}
+
+	let proj = ProjectSettings::new("dpc".into());
+	let mir = lower_ir(ir).expect("Failed to lower IR");
+	let lir = lower_mir(mir).expect("Failed to lower MIR");
+
+	let start = std::time::Instant::now();
+	let mut commands = 0;
+	for _ in 0..iterations {
+		let datapack = link(lir.clone(), &proj).expect("Failed to link datapack");
+		commands += datapack
+			.functions
+			.values()
+			.map(|x| x.contents.len())
+			.sum::<usize>();
+	}
+	let elapsed = start.elapsed();
+	println!(
+		"Generated {commands} commands in {elapsed:?} ({:.0} commands/s)",
+		commands as f64 / elapsed.as_secs_f64()
+	);
+}
+
 fn run(mut ir: IR, debug: bool) -> anyhow::Result<()> {
 	let proj = ProjectSettings::new("dpc".into());
 	if debug {